@@ -0,0 +1,152 @@
+// Gönderim yolunun ölçümleri: örnek dönüşümü, 20 ms'lik çerçeve, WAV'dan kabloya paket ve
+// çekişme altında oturum tablosu araması; alım yolunda tek tek ve toplu paket okuma (toplu okuma
+// `--features recvmmsg` ile ölçülür, aksi halde iki ölçüm aynıdır). Sunucu gerektirmez;
+// `cargo bench` ile çalışır.
+use std::hint::black_box;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use media::announcement::PromptLibrary;
+use media::codec::{self, pcm16_to_g711_alaw, pcm16_to_g711_ulaw, Pcmu};
+use media::config::{PromptConfig, Settings};
+use media::rtp::{RtpPacket, MAX_PACKET_LEN};
+use media::session::{ActiveSessions, RtpSession};
+use media::transport::Transport;
+
+const FRAME: usize = 160;
+
+/// Tam ölçekli bir testere dalgası; her genlik segmenti temsil edilir.
+fn frame() -> Vec<i16> {
+    (0..FRAME).map(|i| (i as i32 * 409 - 32768) as i16).collect()
+}
+
+fn conversion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sample");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("ulaw", |b| b.iter(|| pcm16_to_g711_ulaw(black_box(-12345))));
+    group.bench_function("alaw", |b| b.iter(|| pcm16_to_g711_alaw(black_box(-12345))));
+    group.finish();
+
+    let samples = frame();
+    let mut group = c.benchmark_group("frame_160");
+    group.throughput(Throughput::Elements(FRAME as u64));
+    for codec in codec::registry().iter() {
+        let mut payload = Vec::with_capacity(FRAME);
+        group.bench_with_input(BenchmarkId::from_parameter(codec.name()), &samples, |b, samples| {
+            b.iter(|| {
+                payload.clear();
+                codec.encode(samples, &mut payload);
+                black_box(&payload);
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Anonsun çalma döngüsündeki iş: çerçeveyi kodla, başlığı ekle.
+fn packetization(c: &mut Criterion) {
+    let mut settings = Settings::builtin();
+    settings.announcement.prompts.insert("welcome".to_string(), PromptConfig {
+        path: "audio/processed/standard/welcome.wav".to_string(),
+        gain_db: 0.0,
+        looped: false,
+        language: None,
+        preload: true,
+        languages: Default::default(),
+    });
+    let prompts = PromptLibrary::load(&settings.announcement).expect("welcome prompt");
+    let samples = prompts.get("welcome").unwrap().samples().unwrap();
+
+    let mut group = c.benchmark_group("wav_to_wire");
+    group.throughput(Throughput::Elements(samples.len() as u64));
+    for codec in codec::registry().iter() {
+        group.bench_function(BenchmarkId::from_parameter(codec.name()), |b| {
+            b.iter(|| {
+                let mut wire = [0u8; MAX_PACKET_LEN];
+                let mut payload = Vec::with_capacity(FRAME);
+                for (i, chunk) in samples.chunks(FRAME).enumerate() {
+                    payload.clear();
+                    codec.encode(chunk, &mut payload);
+                    let packet = RtpPacket::new(codec.payload_type(), i as u16, (i * FRAME) as u32, 1, &payload);
+                    let len = packet.write(&mut wire).unwrap();
+                    black_box(&wire[..len]);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Her gelen istek oturum tablosunu kilitler; iş parçacığı sayısı arttıkça aramanın maliyeti.
+fn registry_lookup(c: &mut Criterion) {
+    const SESSIONS: u16 = 256;
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let sessions = ActiveSessions::default();
+    runtime.block_on(async {
+        for port in 0..SESSIONS {
+            let sock = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let session = RtpSession::new(port, &Pcmu, sock, "bench");
+            sessions.lock().unwrap().insert(Arc::new(session));
+        }
+    });
+
+    let mut group = c.benchmark_group("registry_lookup");
+    for threads in [1, 4, 16] {
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, &threads| {
+            b.iter_custom(|iters| {
+                let per_thread = iters.div_ceil(threads);
+                let start = Instant::now();
+                thread::scope(|scope| {
+                    for t in 0..threads {
+                        let sessions = &sessions;
+                        scope.spawn(move || {
+                            for i in 0..per_thread {
+                                let port = ((i + t) % SESSIONS as u64) as u16;
+                                black_box(sessions.lock().unwrap().get(&port).cloned());
+                            }
+                        });
+                    }
+                });
+                // Her iş parçacığı `per_thread` arama yaptı; arama başına gecikme için çarpılır.
+                start.elapsed() * threads as u32
+            })
+        });
+    }
+    group.finish();
+}
+
+fn recv_batch(c: &mut Criterion) {
+    // Soketin alım tamponuna sığacak kadar paket; her ölçüm turu hepsini gönderip okur.
+    const PACKETS: usize = 64;
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let transport = runtime.block_on(async { Transport::from(tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap()) });
+    let addr = transport.local_addr().unwrap();
+    let peer = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let payload = [0u8; 172];
+
+    let mut group = c.benchmark_group("recv_batch");
+    group.throughput(Throughput::Elements(PACKETS as u64));
+    for size in [1, 16] {
+        let mut batch = transport.batch(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                for _ in 0..PACKETS {
+                    peer.send_to(&payload, addr).unwrap();
+                }
+                runtime.block_on(async {
+                    let mut received = 0;
+                    while received < PACKETS {
+                        received += transport.recv_batch(&mut batch).await.unwrap();
+                    }
+                });
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, conversion, packetization, registry_lookup, recv_batch);
+criterion_main!(benches);
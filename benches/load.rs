@@ -0,0 +1,139 @@
+// Yük ölçümü: çok sayıda yalnızca gönderen oturum aynı anda döngülü bir anons çalar; alıcı taraf
+// saniyede gönderilen ve alınan paket sayısını, kaybı ve akış başına paketler arası sürenin ptime'dan sapmasının
+// p50/p99/en büyük değerlerini raporlar. Criterion kullanmaz; `cargo bench --bench load` ile
+// çalışır. Oturum sayısı `LOAD_SESSIONS` (varsayılan 2000), ölçüm süresi `LOAD_SECONDS`
+// (varsayılan 10) ile değiştirilir.
+use std::collections::HashMap;
+use std::net::{SocketAddr, UdpSocket};
+use std::os::fd::AsRawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use media::announcement::PromptLibrary;
+use media::codec::Pcmu;
+use media::config::{QualityConfig, Settings, TimersConfig};
+use media::playback::Playback;
+use media::scheduler::SendScheduler;
+use media::session::{rtp_session_handler, ActiveSessions, RtpSession};
+use media::source::SampleSource;
+
+const RECEIVERS: usize = 4;
+const PTIME: Duration = Duration::from_millis(20);
+const WARMUP: Duration = Duration::from_secs(2);
+
+fn env(name: &str, default: u64) -> u64 {
+    std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+/// Alıcı soket; ölçüm açıkken her akışın paketler arası sapmasını mikrosaniye olarak biriktirir.
+fn receive(sock: UdpSocket, measuring: Arc<AtomicBool>, done: Arc<AtomicBool>) -> (u64, Vec<u32>) {
+    // Sel anında çekirdeğin düşürmemesi için alım tamponu büyütülür (rmem_max sınırına kadar).
+    let size: libc::c_int = 8 << 20;
+    // SAFETY: geçerli bir sokete, boyutu doğru verilmiş bir tamsayı seçeneği yazılır.
+    unsafe {
+        libc::setsockopt(sock.as_raw_fd(), libc::SOL_SOCKET, libc::SO_RCVBUF, (&size as *const libc::c_int).cast(), std::mem::size_of::<libc::c_int>() as libc::socklen_t);
+    }
+    sock.set_read_timeout(Some(Duration::from_millis(100))).unwrap();
+    let mut last: HashMap<u32, Instant> = HashMap::new();
+    let (mut packets, mut deviations) = (0u64, Vec::new());
+    let mut buf = [0u8; 2048];
+    while !done.load(Ordering::Relaxed) {
+        let Ok(len) = sock.recv(&mut buf) else { continue };
+        let now = Instant::now();
+        if len < 12 {
+            continue;
+        }
+        let ssrc = u32::from_be_bytes([buf[8], buf[9], buf[10], buf[11]]);
+        let previous = last.insert(ssrc, now);
+        if !measuring.load(Ordering::Relaxed) {
+            continue;
+        }
+        packets += 1;
+        if let Some(previous) = previous {
+            let gap = now - previous;
+            deviations.push(gap.abs_diff(PTIME).as_micros() as u32);
+        }
+    }
+    (packets, deviations)
+}
+
+fn cpu_time() -> Duration {
+    // SAFETY: getrusage yalnızca verilen yapıyı doldurur.
+    let usage = unsafe {
+        let mut usage = std::mem::zeroed::<libc::rusage>();
+        libc::getrusage(libc::RUSAGE_SELF, &mut usage);
+        usage
+    };
+    let time = |t: libc::timeval| Duration::new(t.tv_sec as u64, t.tv_usec as u32 * 1000);
+    time(usage.ru_utime) + time(usage.ru_stime)
+}
+
+fn percentile(sorted: &[u32], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    sorted[((sorted.len() - 1) as f64 * p).round() as usize] as f64 / 1000.0
+}
+
+fn main() {
+    let sessions = env("LOAD_SESSIONS", 2000) as usize;
+    let seconds = env("LOAD_SECONDS", 10);
+    let (measuring, done) = (Arc::new(AtomicBool::new(false)), Arc::new(AtomicBool::new(false)));
+    let mut receivers = Vec::new();
+    let mut targets: Vec<SocketAddr> = Vec::new();
+    for _ in 0..RECEIVERS {
+        let sock = UdpSocket::bind("127.0.0.1:0").unwrap();
+        targets.push(sock.local_addr().unwrap());
+        let (measuring, done) = (measuring.clone(), done.clone());
+        receivers.push(thread::spawn(move || receive(sock, measuring, done)));
+    }
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let scheduler = Arc::new(SendScheduler::start(Settings::builtin().rtp.send_schedulers(), PTIME));
+    let prompts = Arc::new(PromptLibrary::load(&Settings::builtin().announcement).unwrap());
+    let active_sessions = ActiveSessions::default();
+    // Bir saniyelik 440 Hz sinüs, döngüde çalınır.
+    let tone: Arc<Vec<i16>> = Arc::new((0..8000).map(|i| ((i as f64 * 440.0 * std::f64::consts::TAU / 8000.0).sin() * 8000.0) as i16).collect());
+    runtime.block_on(async {
+        for i in 0..sessions {
+            let sock = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let session = Arc::new(RtpSession::new(i as u16, &Pcmu, sock, "load").without_welcome().with_send_only(targets[i % RECEIVERS], false));
+            active_sessions.lock().unwrap().insert(session.clone());
+            tokio::spawn(rtp_session_handler(session.clone(), prompts.clone(), TimersConfig::default(), QualityConfig::default(), active_sessions.clone(), scheduler.clone()));
+            session.play(Playback::new("load", Box::new(SampleSource::new(tone.clone(), true))));
+        }
+    });
+
+    thread::sleep(WARMUP);
+    let sent = || media::metrics::get().rtp_packets_sent.get();
+    let (cpu_before, sent_before, started) = (cpu_time(), sent(), Instant::now());
+    measuring.store(true, Ordering::Relaxed);
+    thread::sleep(Duration::from_secs(seconds));
+    measuring.store(false, Ordering::Relaxed);
+    let (cpu, sent, elapsed) = (cpu_time() - cpu_before, sent() - sent_before, started.elapsed());
+    done.store(true, Ordering::Relaxed);
+
+    let (mut packets, mut deviations) = (0, Vec::new());
+    for receiver in receivers {
+        let (received, mut gaps) = receiver.join().unwrap();
+        packets += received;
+        deviations.append(&mut gaps);
+    }
+    deviations.sort_unstable();
+    let expected = sessions as f64 * elapsed.as_secs_f64() / PTIME.as_secs_f64();
+    println!("sessions={} seconds={:.1}", sessions, elapsed.as_secs_f64());
+    let rate = |count: f64| count / elapsed.as_secs_f64();
+    println!(
+        "expected/s={:.0} sent/s={:.0} received/s={:.0} loss={:.2}%",
+        rate(expected), rate(sent as f64), rate(packets as f64), (1.0 - packets as f64 / expected).max(0.0) * 100.0,
+    );
+    println!(
+        "jitter_ms p50={:.3} p99={:.3} max={:.3}",
+        percentile(&deviations, 0.5), percentile(&deviations, 0.99), deviations.last().map_or(0.0, |&d| d as f64 / 1000.0),
+    );
+    println!("cpu={:.1}%", cpu.as_secs_f64() / elapsed.as_secs_f64() * 100.0);
+    std::process::exit(0);
+}
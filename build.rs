@@ -1,5 +1,77 @@
 // C:\centric\media\build.rs
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PROTO: &str = "proto/media.proto";
+// Yalnızca asr feature'ıyla derlenen tanıma servisi istemcisi.
+const ASR_PROTO: &str = "proto/asr.proto";
+// Proto dosyasında şema sürümünü taşıyan satırın öneki.
+const SCHEMA_MARKER: &str = "// schema_version:";
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_build::compile_protos("proto/media.proto")?;
+    tonic_build::compile_protos(PROTO)?;
+    if std::env::var_os("CARGO_FEATURE_ASR").is_some() {
+        tonic_build::compile_protos(ASR_PROTO)?;
+    }
+    build_info()?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// GetVersion, başlangıç logu ve media_build_info için derleme bilgisini OUT_DIR'e yazar.
+fn build_info() -> Result<(), Box<dyn std::error::Error>> {
+    let schema_version: u32 = std::fs::read_to_string(PROTO)?
+        .lines()
+        .find_map(|line| line.strip_prefix(SCHEMA_MARKER))
+        .ok_or_else(|| format!("{} lacks a '{}' line", PROTO, SCHEMA_MARKER))?
+        .trim()
+        .parse()?;
+    let commit = git(&["rev-parse", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    // İzlenmeyen dosyalar sayılmaz; yalnızca commit'ten farklı izlenen dosyalar "dirty" yapar.
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|status| !status.is_empty());
+    // Tekrarlanabilir derlemeler için SOURCE_DATE_EPOCH önceliklidir.
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH").ok().and_then(|epoch| epoch.parse().ok())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()));
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(name, _)| name.strip_prefix("CARGO_FEATURE_").map(|feature| feature.to_lowercase().replace('_', "-")))
+        .filter(|feature| feature != "default")
+        .collect();
+    features.sort();
+
+    let out = Path::new(&std::env::var("OUT_DIR")?).join("build_info.rs");
+    std::fs::write(out, format!(
+        "pub const GIT_COMMIT: &str = {:?};\npub const GIT_DIRTY: bool = {};\npub const BUILD_TIMESTAMP: &str = {:?};\n\
+         pub const FEATURES: &[&str] = &{:?};\npub const SCHEMA_VERSION: u32 = {};\n",
+        commit, dirty, rfc3339(timestamp), features, schema_version,
+    ))?;
+
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=src");
+    for path in [".git/HEAD", ".git/index"] {
+        // Var olmayan yol her derlemede yeniden çalıştırır; git dışı kaynak paketinde eklenmez.
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+    Ok(())
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok().filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Unix saniyesini `YYYY-MM-DDTHH:MM:SSZ` olarak yazar (Howard Hinnant'ın civil_from_days'i).
+fn rfc3339(secs: u64) -> String {
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
@@ -0,0 +1,18 @@
+// RFC 6464 ses seviyesi uzantısı: rastgele RTP paketlerinde panik veya sınır dışı okuma olmamalı.
+// Bulunan seviye kendi kimliğiyle yeniden yazılıp okunduğunda aynı çıkmalı.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use media::audio_level::{AudioLevel, IDS};
+use media::rtp::RtpPacketRef;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(packet) = RtpPacketRef::parse(data) else { return };
+    // Tahsis yalnızca bu kimlikleri kabul eder.
+    for id in IDS {
+        let Some(level) = AudioLevel::parse(packet.extension(), id) else { continue };
+        assert!(level.level <= 127);
+        let mut buf = [0u8; 4];
+        assert_eq!(AudioLevel::parse(Some(level.extension(id, &mut buf)), id), Some(level));
+    }
+});
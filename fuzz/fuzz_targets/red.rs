@@ -0,0 +1,28 @@
+// RFC 2198 RED ayrıştırıcısı: rastgele baytlarda panik veya sınır dışı okuma olmamalı. İlk dört
+// bayt RTP zaman damgası, gerisi yüktür. Başarılı ayrıştırmada başlıklar ve bloklar yükün tamamını
+// kaplamalı, son blok birincil yük olmalı.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use media::red::{parse, primary};
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 4 {
+        return;
+    }
+    let (timestamp, payload) = (u32::from_be_bytes(data[..4].try_into().unwrap()), &data[4..]);
+    let mut blocks = Vec::new();
+    if parse(payload, timestamp, &mut blocks).is_err() {
+        assert_eq!(primary(payload, timestamp), None);
+        return;
+    }
+    let last = *blocks.last().expect("primary block");
+    assert_eq!(last.timestamp, timestamp);
+    assert_eq!(primary(payload, timestamp), Some(last));
+    // Yedek blok başına dört, birincil için bir baytlık başlık.
+    let headers = 4 * (blocks.len() - 1) + 1;
+    assert_eq!(headers + blocks.iter().map(|block| block.data.len()).sum::<usize>(), payload.len());
+    for block in &blocks[..blocks.len() - 1] {
+        assert!(timestamp.wrapping_sub(block.timestamp) <= 0x3FFF && block.data.len() <= 0x3FF);
+    }
+});
@@ -0,0 +1,27 @@
+// Gelen SR/RR ayrıştırıcısı: rastgele baytlarda panik olmamalı; okunan raporlar yazılıp tekrar
+// okunduğunda aynı çıkmalı. Bloklardan RTT hesabı da her değerde panik olmadan dönmeli.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use media::rtcp::{parse_reports, round_trip, CompoundWriter};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(reports) = parse_reports(data) else { return };
+    let mut wire = [0u8; 1024];
+    let mut writer = CompoundWriter::new(&mut wire);
+    for report in &reports {
+        for block in &report.blocks {
+            let _ = round_trip(block, block.last_sr.wrapping_add(block.delay_since_last_sr));
+        }
+        // Sığmayan ya da 31'den fazla blok taşıyan rapor yazılamaz; bu girdide karşılaştırma yok.
+        let written = match report.sender {
+            Some(sender) => writer.sender_report(report.ssrc, &sender, &report.blocks).map(drop),
+            None => writer.receiver_report(report.ssrc, &report.blocks).map(drop),
+        };
+        if written.is_err() {
+            return;
+        }
+    }
+    let len = writer.finish();
+    assert_eq!(parse_reports(&wire[..len]).unwrap(), reports);
+});
@@ -0,0 +1,19 @@
+// Gelen RTCP XR ayrıştırıcısı: rastgele baytlarda panik olmamalı; okunan VoIP metrikleri bloğu
+// yazılıp tekrar okunduğunda değerler sabitlenmeli (aralık dışı MOS gibi alanlar ilk yazımda kırpılır).
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use media::rtcp::{parse_voip_metrics, write_xr};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(reports) = parse_voip_metrics(data) else { return };
+    for report in reports {
+        let mut first = [0u8; 64];
+        let len = write_xr(1, &report, &mut first).unwrap();
+        let normalized = parse_voip_metrics(&first[..len]).unwrap();
+        assert_eq!(normalized.len(), 1);
+        let mut second = [0u8; 64];
+        assert_eq!(write_xr(1, &normalized[0], &mut second).unwrap(), len);
+        assert_eq!(&first[..len], &second[..len]);
+    }
+});
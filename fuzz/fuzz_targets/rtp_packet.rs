@@ -0,0 +1,30 @@
+// Gelen RTP ayrıştırıcısı: rastgele baytlarda panik veya sınır dışı okuma olmamalı; başarılı
+// ayrıştırmada paket builder ile yeniden kurulunca aynı baytlar çıkmalı (dolgu içeriği hariç).
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use media::rtp::{RtpPacket, RtpPacketRef, MAX_PACKET_LEN};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(parsed) = RtpPacketRef::parse(data) else { return };
+    let csrcs: Vec<u32> = parsed.csrcs().collect();
+    let padding = if parsed.padding() { data[data.len() - 1] } else { 0 };
+    let packet = RtpPacket {
+        marker: parsed.marker(),
+        payload_type: parsed.payload_type(),
+        sequence: parsed.sequence(),
+        timestamp: parsed.timestamp(),
+        ssrc: parsed.ssrc(),
+        csrcs: &csrcs,
+        extension: parsed.extension(),
+        padding,
+        payload: parsed.payload(),
+    };
+    assert_eq!(packet.encoded_len(), data.len());
+    if data.len() <= MAX_PACKET_LEN {
+        let mut wire = [0u8; MAX_PACKET_LEN];
+        let len = packet.write(&mut wire).unwrap();
+        let body = len - padding as usize;
+        assert_eq!(&wire[..body], &data[..body]);
+    }
+});
@@ -0,0 +1,272 @@
+// Yük altında oynatma kabulü. CPU'su doymuş bir node'da başlayan her yeni anons süren bütün
+// çağrıların sesini bozar; bu yüzden `run` her `admission.interval_ms`'de sürecin CPU kullanımını
+// ve gönderim döngüsü gecikmesinin o aralıktaki 95. yüzdeliğini örnekler, biri eşiği aşınca node
+// bozulmuş (degraded) sayılır. Bozulmuşken `admit` yeni PlayAnnouncement, SayDigits ve SayNumber
+// isteklerini reddeder, karşılama anonsu `skip_welcome` ile atlanır; süren ve kuyruktaki
+// oynatmalara dokunulmaz. Eşiklerin altında `recovery_samples` ardışık örnekten sonra normale
+// dönülür. Denetleyici çalışmıyorsa ([admission] kapalı) durum hep normaldir.
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::time::Duration;
+
+use tokio::time::{interval, Instant, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::audit;
+use crate::config::AdmissionConfig;
+use crate::error::SessionError;
+use crate::metrics;
+
+/// Gecikme eşiğiyle karşılaştırılan yüzdelik.
+const SEND_LAG_QUANTILE: f64 = 0.95;
+/// /proc/self/stat'taki sürelerin birimi (sysconf(_SC_CLK_TCK)); libc olmadan sorulamadığı için
+/// Linux'un bütün yaygın mimarilerde kullandığı değer.
+const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+/// Node'un bozulmuş sayılmasına yol açan eşik.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cause {
+    Cpu = 1,
+    SendLag = 2,
+}
+
+impl Cause {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Cause::Cpu => "cpu",
+            Cause::SendLag => "send_lag",
+        }
+    }
+}
+
+/// Bir örnekleme aralığının ölçümleri.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Load {
+    /// Bütün çekirdeklerin yüzdesi olarak; ölçülemiyorsa yok.
+    pub cpu_percent: Option<f64>,
+    /// Aralıktaki gönderim gecikmesinin 95. yüzdeliği, kova üst sınırı olarak; gönderim yoksa sıfır.
+    pub send_lag_p95: Duration,
+}
+
+// Süreç genelindeki durum; gRPC, /readyz ve karşılama anonsu buradan okur.
+static CAUSE: AtomicU8 = AtomicU8::new(0);
+static SKIP_WELCOME: AtomicBool = AtomicBool::new(false);
+static CPU_PERCENT_BITS: AtomicU64 = AtomicU64::new(f64::NAN.to_bits());
+static SEND_LAG_MICROS: AtomicU64 = AtomicU64::new(0);
+
+/// Node bozulmuşsa aşılan eşik.
+pub fn cause() -> Option<Cause> {
+    match CAUSE.load(Ordering::Relaxed) {
+        1 => Some(Cause::Cpu),
+        2 => Some(Cause::SendLag),
+        _ => None,
+    }
+}
+
+/// GetServerStatus ve heartbeat'te görünen durum adı.
+pub fn state() -> &'static str {
+    if cause().is_some() { "degraded" } else { "normal" }
+}
+
+/// Son örneğin ölçümleri; denetleyici çalışmıyorsa boş.
+pub fn last_load() -> Load {
+    let cpu_percent = f64::from_bits(CPU_PERCENT_BITS.load(Ordering::Relaxed));
+    Load {
+        cpu_percent: Some(cpu_percent).filter(|percent| !percent.is_nan()),
+        send_lag_p95: Duration::from_micros(SEND_LAG_MICROS.load(Ordering::Relaxed)),
+    }
+}
+
+/// Yeni bir oynatma isteğinden önce çağrılır; node bozulmuşsa istek reddedilir ve sayılır.
+pub fn admit() -> Result<(), SessionError> {
+    match cause() {
+        Some(cause) => {
+            metrics::get().playbacks_rejected.inc();
+            Err(SessionError::Overloaded { cause: cause.as_str() })
+        }
+        None => Ok(()),
+    }
+}
+
+/// Karşılama anonsu atlanacaksa sebebi: node bozulmuş ve `admission.skip_welcome` açık.
+pub fn skip_welcome() -> Option<Cause> {
+    cause().filter(|_| SKIP_WELCOME.load(Ordering::Relaxed))
+}
+
+/// Eşiklerle örnekleri karşılaştırıp durumu tutar.
+#[derive(Debug)]
+struct Detector {
+    max_cpu_percent: f64,
+    max_send_lag: Duration,
+    recovery_samples: u32,
+    cause: Option<Cause>,
+    // Bozulmuşken eşiklerin altında kalan ardışık örnekler.
+    normal_samples: u32,
+}
+
+impl Detector {
+    fn new(config: &AdmissionConfig) -> Self {
+        Detector {
+            max_cpu_percent: config.max_cpu_percent, max_send_lag: Duration::from_millis(config.max_send_lag_ms),
+            recovery_samples: config.recovery_samples, cause: None, normal_samples: 0,
+        }
+    }
+
+    fn exceeded(&self, load: &Load) -> Option<Cause> {
+        if self.max_cpu_percent > 0.0 && load.cpu_percent.is_some_and(|percent| percent > self.max_cpu_percent) {
+            return Some(Cause::Cpu);
+        }
+        if !self.max_send_lag.is_zero() && load.send_lag_p95 > self.max_send_lag {
+            return Some(Cause::SendLag);
+        }
+        None
+    }
+
+    /// Örneği işler; durum normal ile bozulmuş arasında değiştiyse true.
+    fn observe(&mut self, load: &Load) -> bool {
+        match (self.exceeded(load), self.cause) {
+            (Some(cause), None) => {
+                self.cause = Some(cause);
+                self.normal_samples = 0;
+                true
+            }
+            (Some(_), Some(_)) => {
+                self.normal_samples = 0;
+                false
+            }
+            (None, Some(_)) => {
+                self.normal_samples += 1;
+                if self.normal_samples < self.recovery_samples {
+                    return false;
+                }
+                self.cause = None;
+                true
+            }
+            (None, None) => false,
+        }
+    }
+}
+
+/// Kümülatif sayaçlardan aralık ölçümü çıkarır.
+struct Sampler {
+    at: Instant,
+    cpu_time: Option<Duration>,
+    lag: ([u64; 10], u64),
+    cores: f64,
+}
+
+impl Sampler {
+    fn start() -> Self {
+        let cores = std::thread::available_parallelism().map_or(1, |cores| cores.get()) as f64;
+        Sampler { at: Instant::now(), cpu_time: process_cpu_time(), lag: metrics::get().send_loop_lag.counts(), cores }
+    }
+
+    fn sample(&mut self) -> Load {
+        let now = Instant::now();
+        let cpu_time = process_cpu_time();
+        let wall = now.duration_since(self.at).as_secs_f64() * self.cores;
+        let cpu_percent = match (cpu_time, self.cpu_time) {
+            (Some(current), Some(previous)) if wall > 0.0 => Some((current.saturating_sub(previous).as_secs_f64() / wall * 100.0).min(100.0)),
+            _ => None,
+        };
+        let histogram = &metrics::get().send_loop_lag;
+        let lag = histogram.counts();
+        let send_lag_p95 = interval_quantile(histogram.bounds(), &self.lag, &lag, SEND_LAG_QUANTILE);
+        (self.at, self.cpu_time, self.lag) = (now, cpu_time, lag);
+        Load { cpu_percent, send_lag_p95 }
+    }
+}
+
+/// İki histogram görüntüsü arasındaki gözlemlerin `quantile`'ı, düştüğü kovanın üst sınırı olarak;
+/// son sınırı aşanlar son sınır sayılır. Aralıkta gözlem yoksa sıfır.
+fn interval_quantile<const N: usize>(bounds: &[f64; N], previous: &([u64; N], u64), current: &([u64; N], u64), quantile: f64) -> Duration {
+    let total = current.1.saturating_sub(previous.1);
+    if total == 0 {
+        return Duration::ZERO;
+    }
+    let rank = (total as f64 * quantile).ceil() as u64;
+    let mut seen = 0;
+    for (i, bound) in bounds.iter().enumerate() {
+        seen += current.0[i].saturating_sub(previous.0[i]);
+        if seen >= rank {
+            return Duration::from_secs_f64(*bound);
+        }
+    }
+    Duration::from_secs_f64(bounds[N - 1])
+}
+
+/// Sürecin kullanıcı ve çekirdek kipindeki toplam CPU süresi; Linux dışında ya da okunamazsa yok.
+fn process_cpu_time() -> Option<Duration> {
+    parse_cpu_time(&std::fs::read_to_string("/proc/self/stat").ok()?)
+}
+
+fn parse_cpu_time(stat: &str) -> Option<Duration> {
+    // İkinci alan (komut adı) boşluk ve parantez içerebilir; alanlar son ')'ten sonra sayılır, ilki
+    // stat'ın 3. alanıdır. utime ve stime 14. ve 15. alanlar.
+    let mut fields = stat.get(stat.rfind(')')? + 1..)?.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(Duration::from_millis((utime + stime) * 1000 / CLOCK_TICKS_PER_SEC))
+}
+
+/// Yükü her `config.interval()`'da örnekler ve durumu günceller; süreç bitene kadar çalışır.
+pub async fn run(config: AdmissionConfig) {
+    SKIP_WELCOME.store(config.skip_welcome, Ordering::Relaxed);
+    let mut detector = Detector::new(&config);
+    let mut sampler = Sampler::start();
+    let mut ticker = interval(config.interval());
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // İlk tick hemen döner; ilk örnek bir aralık sonra alınır.
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let load = sampler.sample();
+        CPU_PERCENT_BITS.store(load.cpu_percent.unwrap_or(f64::NAN).to_bits(), Ordering::Relaxed);
+        SEND_LAG_MICROS.store(load.send_lag_p95.as_micros() as u64, Ordering::Relaxed);
+        if !detector.observe(&load) {
+            continue;
+        }
+        CAUSE.store(detector.cause.map_or(0, |cause| cause as u8), Ordering::Relaxed);
+        metrics::get().admission_degraded.set(detector.cause.is_some() as i64);
+        let send_lag_p95_ms = load.send_lag_p95.as_secs_f64() * 1000.0;
+        match detector.cause {
+            Some(cause) => warn!(
+                target: audit::TARGET, event = audit::ADMISSION_CHANGED, state = state(), cause = cause.as_str(), cpu_percent = load.cpu_percent, send_lag_p95_ms,
+            ),
+            None => info!(target: audit::TARGET, event = audit::ADMISSION_CHANGED, state = state(), cpu_percent = load.cpu_percent, send_lag_p95_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_is_sampled_from_interval_deltas_and_recovery_needs_consecutive_normal_samples() {
+        let stat = "4242 (media (rtp) x) S 1 4242 4242 0 -1 4194560 1200 0 0 0 250 130 0 0 20 0 9 0 100 0 0";
+        assert_eq!(parse_cpu_time(stat), Some(Duration::from_millis(3800)));
+        assert_eq!(parse_cpu_time("4242 (media) S 1"), None);
+
+        let bounds = [0.001, 0.01, 0.1];
+        let previous = ([5, 5, 0], 10);
+        assert_eq!(interval_quantile(&bounds, &previous, &previous, SEND_LAG_QUANTILE), Duration::ZERO);
+        assert_eq!(interval_quantile(&bounds, &previous, &([100, 5, 0], 105), SEND_LAG_QUANTILE), Duration::from_millis(1));
+        assert_eq!(interval_quantile(&bounds, &previous, &([95, 10, 5], 110), SEND_LAG_QUANTILE), Duration::from_millis(10));
+        // Son kovayı aşan gözlemler yalnızca toplamda sayılır.
+        assert_eq!(interval_quantile(&bounds, &previous, &([90, 5, 0], 110), SEND_LAG_QUANTILE), Duration::from_millis(100));
+
+        let config = AdmissionConfig { enabled: true, max_cpu_percent: 80.0, max_send_lag_ms: 20, recovery_samples: 2, ..AdmissionConfig::default() };
+        let mut detector = Detector::new(&config);
+        let load = |cpu_percent: Option<f64>, lag_ms: u64| Load { cpu_percent, send_lag_p95: Duration::from_millis(lag_ms) };
+        assert!(!detector.observe(&load(None, 20)));
+        assert!(detector.observe(&load(Some(85.0), 0)));
+        assert_eq!(detector.cause, Some(Cause::Cpu));
+        assert!(!detector.observe(&load(Some(10.0), 0)));
+        assert!(!detector.observe(&load(Some(10.0), 50)));
+        assert!(!detector.observe(&load(Some(10.0), 5)));
+        assert!(detector.observe(&load(None, 5)));
+        assert_eq!(detector.cause, None);
+        assert!(detector.observe(&load(None, 50)));
+        assert_eq!(detector.cause, Some(Cause::SendLag));
+    }
+}
@@ -0,0 +1,159 @@
+// Telesekreter algılama (AMD): giden aramalarda cevaplayanın insan mı makine mi olduğuna hızlı bir
+// tahmin. İnsan kısa bir "alo" der ve susar; telesekreter uzun, kesintisiz bir karşılama çalar.
+// Gelen sesin her çerçevesi seviyesine göre konuşma ya da sessizlik sayılır (bkz. vad.rs);
+// `min_word_ms` süren konuşma bir kelime, `between_words_silence_ms` süren sessizlik kelime sonudur.
+// İlk kelimeden önceki sessizlik, ilk kelimeden son kelimenin sonuna kadar süren karşılama, kelime
+// sayısı ve karşılamadan sonraki sessizlik ölçülür; eşiklerden ilk aşılan sonucu belirler, hiçbiri
+// `window_ms` içinde aşılmazsa sonuç not_sure olur. Süreler çözülen sesle ölçülür; hiç paket
+// gelmeyen aralıklar sayılmaz. Sonuç bir kez verilir, sonra analiz biter.
+use crate::audit::{AmdReason, AmdVerdict};
+use crate::config::AmdConfig;
+use crate::vad;
+
+/// Analizin sonucu ve eşikleri ayarlamak için ölçülen özellikler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AmdResult {
+    pub verdict: AmdVerdict,
+    pub reason: AmdReason,
+    /// İlk kelimeye kadar (kelime yoksa analiz boyunca) süren sessizlik.
+    pub initial_silence_ms: u64,
+    /// İlk kelimenin başından son kelimenin sonuna; süren kelime dahil.
+    pub greeting_ms: u64,
+    pub words: u32,
+    /// Son kelimeden beri süren sessizlik; kelime yoksa ya da konuşma sürüyorsa 0.
+    pub silence_after_greeting_ms: u64,
+    /// Analiz edilen ses.
+    pub analysis_ms: u64,
+}
+
+#[derive(Debug)]
+pub struct AmdDetector {
+    config: AmdConfig,
+    sample_rate: u32,
+    // Analiz edilen örnekler; süreler bundan hesaplanır.
+    samples: u64,
+    voice_ms: u64,
+    silence_ms: u64,
+    in_word: bool,
+    words: u32,
+    greeting_start: Option<u64>,
+    greeting_end: u64,
+}
+
+impl AmdDetector {
+    pub fn new(config: &AmdConfig, sample_rate: u32) -> Self {
+        AmdDetector {
+            config: *config, sample_rate, samples: 0, voice_ms: 0, silence_ms: 0, in_word: false, words: 0,
+            greeting_start: None, greeting_end: 0,
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.samples * 1000 / self.sample_rate as u64
+    }
+
+    /// Gelen çerçeveyi işler; karar verildiyse sonucu döner. Sonuçtan sonra çağrılmamalıdır.
+    pub fn push(&mut self, pcm: &[i16]) -> Option<AmdResult> {
+        let before = self.elapsed_ms();
+        self.samples += pcm.len() as u64;
+        let (elapsed, frame_ms) = (self.elapsed_ms(), self.elapsed_ms() - before);
+        let config = self.config;
+
+        if vad::level_dbov(pcm) >= config.threshold_dbov as f64 {
+            self.voice_ms += frame_ms;
+            self.silence_ms = 0;
+            if !self.in_word && self.voice_ms >= config.min_word_ms {
+                self.in_word = true;
+                self.words += 1;
+                self.greeting_start.get_or_insert(elapsed - self.voice_ms);
+                if self.words >= config.max_words {
+                    return Some(self.result(AmdVerdict::Machine, AmdReason::MaxWords));
+                }
+            }
+        } else {
+            // Kelime olamayacak kadar kısa ses (tık, nefes) sayılmaz.
+            self.voice_ms = 0;
+            self.silence_ms += frame_ms;
+            if self.in_word && self.silence_ms >= config.between_words_silence_ms {
+                self.in_word = false;
+                self.greeting_end = elapsed - self.silence_ms;
+            }
+        }
+
+        match self.greeting_start {
+            None if elapsed >= config.initial_silence_ms => Some(self.result(AmdVerdict::Machine, AmdReason::InitialSilence)),
+            Some(start) if self.in_word && elapsed - start >= config.greeting_ms => Some(self.result(AmdVerdict::Machine, AmdReason::LongGreeting)),
+            Some(_) if !self.in_word && self.silence_ms >= config.after_greeting_silence_ms => {
+                Some(self.result(AmdVerdict::Human, AmdReason::SilenceAfterGreeting))
+            }
+            _ if elapsed >= config.window_ms => Some(self.result(AmdVerdict::NotSure, AmdReason::WindowElapsed)),
+            _ => None,
+        }
+    }
+
+    /// Oturum karar verilmeden bitti; o ana kadarki ölçümlerle not_sure.
+    pub fn finish(&self) -> AmdResult {
+        self.result(AmdVerdict::NotSure, AmdReason::SessionEnded)
+    }
+
+    fn result(&self, verdict: AmdVerdict, reason: AmdReason) -> AmdResult {
+        let elapsed = self.elapsed_ms();
+        let (initial_silence_ms, greeting_ms) = match self.greeting_start {
+            Some(start) => (start, if self.in_word { elapsed } else { self.greeting_end } - start),
+            None => (elapsed, 0),
+        };
+        let after = if self.words > 0 && !self.in_word { self.silence_ms } else { 0 };
+        AmdResult { verdict, reason, initial_silence_ms, greeting_ms, words: self.words, silence_after_greeting_ms: after, analysis_ms: elapsed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 8000;
+
+    /// Sırayla (konuşma mı, ms) dilimlerinden oluşan sesi 20 ms'lik çerçevelerle verir; ilk sonucu döner.
+    fn analyse(config: &AmdConfig, parts: &[(bool, u64)]) -> Option<AmdResult> {
+        let mut detector = AmdDetector::new(config, RATE);
+        let audio: Vec<i16> = parts.iter()
+            .flat_map(|&(speech, ms)| (0..RATE as u64 * ms / 1000).map(move |i| if speech { if i % 2 == 0 { 6000 } else { -6000 } } else { 20 }))
+            .collect();
+        audio.chunks(160).find_map(|frame| detector.push(frame))
+    }
+
+    #[test]
+    fn short_hello_then_silence_is_human_and_long_greeting_is_machine() {
+        let config = AmdConfig::default();
+        let human = analyse(&config, &[(false, 400), (true, 600), (false, 2000)]).unwrap();
+        assert_eq!(human, AmdResult {
+            verdict: AmdVerdict::Human, reason: AmdReason::SilenceAfterGreeting, initial_silence_ms: 400, greeting_ms: 600,
+            words: 1, silence_after_greeting_ms: config.after_greeting_silence_ms, analysis_ms: 1000 + config.after_greeting_silence_ms,
+        });
+
+        let machine = analyse(&config, &[(false, 300), (true, 3000)]).unwrap();
+        assert_eq!((machine.verdict, machine.reason, machine.greeting_ms), (AmdVerdict::Machine, AmdReason::LongGreeting, config.greeting_ms));
+
+        // Kısa aralarla üç kelime: "Merhaba, şu an ulaşılamıyor..."
+        let words = analyse(&config, &[(true, 300), (false, 200), (true, 300), (false, 200), (true, 300)]).unwrap();
+        assert_eq!((words.verdict, words.reason, words.words), (AmdVerdict::Machine, AmdReason::MaxWords, 3));
+
+        // Kelime sayılmayacak kadar kısa sesler sessizlikten ayrılmaz.
+        let silent = analyse(&config, &[(true, 40), (false, 3000)]).unwrap();
+        assert_eq!((silent.verdict, silent.reason, silent.words), (AmdVerdict::Machine, AmdReason::InitialSilence, 0));
+        assert_eq!(silent.initial_silence_ms, config.initial_silence_ms);
+    }
+
+    #[test]
+    fn undecided_analysis_ends_with_the_window_or_the_session() {
+        let config = AmdConfig { after_greeting_silence_ms: 2000, window_ms: 2000, ..AmdConfig::default() };
+        let result = analyse(&config, &[(true, 500), (false, 1600)]).unwrap();
+        assert_eq!((result.verdict, result.reason, result.analysis_ms), (AmdVerdict::NotSure, AmdReason::WindowElapsed, 2000));
+        assert_eq!(result.silence_after_greeting_ms, 1500);
+
+        let mut detector = AmdDetector::new(&config, RATE);
+        assert_eq!(detector.push(&[6000; 160]), None);
+        let ended = detector.finish();
+        assert_eq!((ended.verdict, ended.reason, ended.words, ended.analysis_ms), (AmdVerdict::NotSure, AmdReason::SessionEnded, 0, 20));
+    }
+}
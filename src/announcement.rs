@@ -0,0 +1,974 @@
+// Adlandırılmış anons kütüphanesi: config'deki isim -> dosya eşlemesini tutar,
+// başlangıçta bütün girdileri doğrular, karşılama ve istenen anonsları önceden belleğe alır;
+// diğerleri ilk çalındıklarında `prompt_cache` bütçesine göre belleğe alınır. `upload_dir`
+// tanımlıysa UploadAnnouncement ile yüklenen dosyalar da adlarıyla burada tutulur: yükleme önce
+// geçici bir dosyaya yazılır, başlangıçtaki doğrulamadan geçerse yerine taşınır ve aynı adlı
+// anonsun önbelleği yenisiyle değişir. Silme, anons karşılama ya da süre sonu anonsu olarak
+// kullanılıyorsa veya o an çalıyorsa reddedilir. Yolu `http://` olan anonslar başlangıçta
+// doğrulanmaz; ilk çalmada `url_cache` üzerinden indirilir. Bir anonsun `languages` altında
+// başka dillerde dosyaları olabilir; çalarken istenen dildeki seçilir, yoksa anonsun kendi
+// dosyasına (varsayılan dil) düşülür. Birleşik anonsların (SayDigits, SayNumber) parçaları
+// `segment_prefix` önekli adlı sıradan anonslardır; parçalarda bu geri düşüş yapılmaz.
+// ReloadAnnouncements ve config'in yeniden okunması diskte değişen dosyaları yeniden doğrular:
+// anons yenisiyle değiştirilir, önbellekteki eski sesi düşer; çalmakta olan oynatmalar başladıkları
+// sesi bitirir.
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::audit;
+use crate::config::{AnnouncementConfig, PromptConfig};
+use crate::error::{PlaybackError, PromptStoreError};
+use crate::prompt_cache::{CacheKey, CacheStats, PromptCache};
+use crate::source::{AudioSource, SampleSource, WavSource};
+use crate::url_cache::{self, UrlCache, UrlSource};
+
+#[derive(Debug)]
+pub struct Prompt {
+    pub name: String,
+    pub config: PromptConfig,
+    // Yerel dosyalı anonsların çözülmüş örnekleri; URL'li anonslarda yok.
+    cache: Option<Arc<PromptCache>>,
+    // Ada bağlıdır; yükleme dosyayı değiştirse de sayaçlar sürer.
+    usage: Arc<Usage>,
+    // Yolu URL ise kopyanın indirildiği önbellek.
+    url_cache: Option<Arc<UrlCache>>,
+    // Dil -> aynı anonsun o dildeki dosyası; kullanım sayaçları ortaktır.
+    variants: BTreeMap<String, Arc<Prompt>>,
+    // Çözülmüş örneklerin özeti; yeniden doğrulama değişikliği bununla anlar. URL'li ya da
+    // doğrulanamamış dosyada yok.
+    digest: Option<[u8; 32]>,
+}
+
+#[derive(Debug, Default)]
+struct Usage {
+    plays: AtomicU64,
+    active: AtomicUsize,
+}
+
+/// Çalan bir oynatmanın anonsu tuttuğunu gösterir; oynatma bitince düşer ve anons silinebilir.
+#[derive(Debug)]
+pub struct PromptClaim(Arc<Usage>);
+
+impl Drop for PromptClaim {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Prompt {
+    #[cfg(test)]
+    pub fn from_samples(name: &str, samples: Vec<i16>) -> Self {
+        let config = PromptConfig { path: String::new(), gain_db: 0.0, looped: false, language: None, preload: true, languages: BTreeMap::new() };
+        let cache = Arc::new(PromptCache::new(u64::MAX, u64::MAX));
+        cache.pin(CacheKey::new(&config.path, config.gain_db), Arc::new(samples));
+        Self { name: name.to_string(), config, cache: Some(cache), usage: Arc::default(), url_cache: None, variants: BTreeMap::new(), digest: None }
+    }
+
+    /// Anonsun `language` dilindeki hali. O dilde dosyası yoksa kendisi (varsayılan dil) döner ve
+    /// geri düşüş denetim kaydına yazılır; dil verilmezse kendisi.
+    pub fn in_language(self: &Arc<Self>, language: Option<&str>) -> Arc<Prompt> {
+        let Some(language) = language.filter(|language| !language.is_empty()) else { return self.clone() };
+        if self.config.language.as_deref() == Some(language) {
+            return self.clone();
+        }
+        if let Some(variant) = self.variants.get(language) {
+            return variant.clone();
+        }
+        // Dili bilinmeyen tek dosyalı anonsta (ör. PlayAnnouncement.url) düşülecek bir seçim yok.
+        if self.config.language.is_some() || !self.variants.is_empty() {
+            info!(
+                target: audit::TARGET, event = audit::PROMPT_LANGUAGE_FALLBACK,
+                prompt = %self.name, requested = %language, language = self.config.language.as_deref().unwrap_or("-"),
+            );
+        }
+        self.clone()
+    }
+
+    /// Anonsun `language` dilinde dosyası var mı; dili bilinmeyen tek dosyalı anons her dili
+    /// karşılar.
+    fn speaks(&self, language: Option<&str>) -> bool {
+        match language {
+            Some(language) => self.config.language.as_deref() == Some(language) || self.variants.contains_key(language)
+                || (self.config.language.is_none() && self.variants.is_empty()),
+            None => true,
+        }
+    }
+
+    /// Anonsun dosyası olan diller, alfabetik.
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages: Vec<String> = self.config.language.iter().chain(self.variants.keys()).cloned().collect();
+        languages.sort();
+        languages
+    }
+
+    /// Anonsu çalmaya başlayan oynatma için; çalınma sayısını artırır.
+    pub fn claim(&self) -> PromptClaim {
+        self.usage.plays.fetch_add(1, Ordering::Relaxed);
+        self.usage.active.fetch_add(1, Ordering::Relaxed);
+        PromptClaim(self.usage.clone())
+    }
+
+    /// Dosyanın bilgileri ve kullanım sayaçları; dosyayı okur ve özetler, bloklar. URL'li
+    /// anonslarda önbellekteki kopyanın bilgileri.
+    fn info(&self) -> PromptInfo {
+        let file = match (&self.url_cache, self.config.url()) {
+            (Some(cache), Some(url)) => cache.cached(url)
+                .ok_or_else(|| "not downloaded yet".to_string())
+                .and_then(|path| file_info(&path.display().to_string())),
+            _ => file_info(&self.config.path),
+        };
+        PromptInfo {
+            name: self.name.clone(),
+            path: self.config.path.clone(),
+            file,
+            cached: self.cache.as_ref().is_some_and(|cache| cache.contains(&self.cache_key())),
+            languages: self.languages(),
+            plays: self.usage.plays.load(Ordering::Relaxed),
+            active_playbacks: self.usage.active.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Kazanç uygulanmış PCM örnekleri; önbellekte yoksa dosyadan okunur. URL'li anonslarda
+    /// yalnızca indirilmiş kopya okunur.
+    pub fn samples(&self) -> Result<Arc<Vec<i16>>, PlaybackError> {
+        if let (Some(cache), Some(url)) = (&self.url_cache, self.config.url()) {
+            let path = cache.cached(url).ok_or_else(|| PlaybackError::Fetch { url: url.to_string(), reason: "not downloaded yet".to_string() })?;
+            return load_samples(&PromptConfig { path: path.display().to_string(), ..self.config.clone() }).map(Arc::new);
+        }
+        match self.cached_samples()? {
+            Some(samples) => Ok(samples),
+            None => load_samples(&self.config).map(Arc::new),
+        }
+    }
+
+    /// Oynatma için kaynak: önbellekteyse ya da önbelleğe sığıyorsa bellekten, değilse dosyadan
+    /// akıtılarak okunur. URL'li
+    /// anonsun kaynağı indirmeyi başlatır ve kopya hazır olunca çalar; tokio içinde çağrılmalı.
+    pub fn source(&self) -> Result<Box<dyn AudioSource>, PlaybackError> {
+        self.open_source(&self.config)
+    }
+
+    /// Fon müziği olarak çalınacak kaynak; anonsun döngü ayarından bağımsız olarak başa sarar.
+    pub fn bed_source(&self) -> Result<Box<dyn AudioSource>, PlaybackError> {
+        self.open_source(&PromptConfig { looped: true, ..self.config.clone() })
+    }
+
+    fn open_source(&self, config: &PromptConfig) -> Result<Box<dyn AudioSource>, PlaybackError> {
+        if let Some(cache) = &self.url_cache {
+            return Ok(Box::new(UrlSource::new(cache.clone(), config)));
+        }
+        match self.cached_samples()? {
+            Some(samples) => Ok(Box::new(SampleSource::new(samples, config.looped))),
+            None => Ok(Box::new(WavSource::for_prompt(config)?)),
+        }
+    }
+
+    fn cache_key(&self) -> CacheKey {
+        CacheKey::new(&self.config.path, self.config.gain_db)
+    }
+
+    /// Önbellekteki örnekler; yoksa ve dosya girdi sınırını aşmıyorsa dosya bütünüyle okunup
+    /// önbelleğe alınır (bloklar). Sınırı aşan ya da okunamayan dosyada `None`, çağıran diskten
+    /// akıtır ve hatayı kendisi raporlar.
+    fn cached_samples(&self) -> Result<Option<Arc<Vec<i16>>>, PlaybackError> {
+        let Some(cache) = &self.cache else { return Ok(None) };
+        let key = self.cache_key();
+        if let Some(samples) = cache.get(&key) {
+            return Ok(Some(samples));
+        }
+        // 16 bit mono WAV'da dosya boyutu çözülmüş örneklerin boyutuna yakındır.
+        if !fs::metadata(&self.config.path).is_ok_and(|meta| cache.admits(meta.len())) {
+            return Ok(None);
+        }
+        let samples = load_samples(&self.config)?;
+        Ok(Some(cache.insert(key, Arc::new(samples))))
+    }
+}
+
+// Anons adının üst sınırı; adlar dosya adı olarak da kullanılır.
+const MAX_NAME_LEN: usize = 64;
+// WavSource yalnızca 8 kHz kabul eder.
+const SAMPLE_RATE: u64 = 8000;
+
+/// Kaydedilen bir yüklemenin özeti; çağıran bütünlüğü özetle doğrular.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uploaded {
+    pub path: String,
+    pub size_bytes: u64,
+    pub duration: Duration,
+    /// Dosya içeriğinin SHA-256 özeti (küçük harf hex).
+    pub sha256: String,
+}
+
+/// ListAnnouncements'ın bir satırı.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptInfo {
+    pub name: String,
+    pub path: String,
+    /// Dosya okunamadıysa sebebi.
+    pub file: Result<FileInfo, String>,
+    pub cached: bool,
+    /// Dosyası olan diller; ilk dosyanın dili bilinmiyorsa listede yoktur.
+    pub languages: Vec<String>,
+    /// Başlangıçtan beri çalınma sayısı.
+    pub plays: u64,
+    pub active_playbacks: usize,
+}
+
+/// Bir anonsun yeniden doğrulanmasının sonucu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReloadOutcome {
+    Unchanged,
+    /// Sesi ya da config'deki yolu değişti; yeni çalmalar yenisini çalar.
+    Changed,
+    /// Dosya yok; önbellekteki sesi düştü, çalmalar hata verir.
+    Missing { path: String },
+    /// Dosya çözülemedi; önbellekteki sesi düştü, çalmalar hata verir.
+    Invalid { reason: String },
+}
+
+impl ReloadOutcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReloadOutcome::Unchanged => "unchanged",
+            ReloadOutcome::Changed => "changed",
+            ReloadOutcome::Missing { .. } => "missing",
+            ReloadOutcome::Invalid { .. } => "invalid",
+        }
+    }
+
+    /// Dosya yoksa yolu, çözülemediyse sebebi.
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            ReloadOutcome::Missing { path } => Some(path),
+            ReloadOutcome::Invalid { reason } => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+/// Yeniden doğrulamayı başlatan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadTrigger {
+    Rpc,
+    Config,
+}
+
+impl ReloadTrigger {
+    fn label(self) -> &'static str {
+        match self {
+            ReloadTrigger::Rpc => "rpc",
+            ReloadTrigger::Config => "config",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileInfo {
+    pub size_bytes: u64,
+    pub duration: Duration,
+    pub sample_rate: u32,
+    pub bits_per_sample: u16,
+    pub channels: u16,
+    /// Dosya içeriğinin SHA-256 özeti (küçük harf hex).
+    pub sha256: String,
+}
+
+#[derive(Debug)]
+pub struct PromptLibrary {
+    // Yüklemeler çalışırken değiştirir; oturumlar her çalmada okur.
+    prompts: RwLock<HashMap<String, Arc<Prompt>>>,
+    welcome: Option<String>,
+    replay_welcome: bool,
+    max_duration: Option<String>,
+    // Karşılamanın altında çalınacak fon müziği ve kısması.
+    welcome_bed: Option<(String, f32)>,
+    default_language: Option<String>,
+    segment_prefix: String,
+    max_queued: usize,
+    upload_dir: Option<PathBuf>,
+    max_upload_bytes: u64,
+    url_cache: Option<Arc<UrlCache>>,
+    cache: Arc<PromptCache>,
+    // Config'deki girdiler; yeniden okunan config bunlarla karşılaştırılır.
+    declared: Mutex<HashMap<String, PromptConfig>>,
+}
+
+impl PromptLibrary {
+    /// Tüm girdileri doğrular; hatalar ilk hatada kesilmeden birlikte raporlanır. `upload_dir`'deki
+    /// `<ad>.wav` dosyaları da yüklenir; aynı adlı config girdisinin dosyasının yerine geçer.
+    pub fn load(config: &AnnouncementConfig) -> Result<Self, PlaybackError> {
+        let mut prompts = HashMap::new();
+        let mut errors = Vec::new();
+
+        let mut declared = config.prompts.clone();
+        let upload_dir = config.upload_dir().map(PathBuf::from);
+        if let Some(dir) = &upload_dir {
+            match uploaded_files(dir) {
+                Ok(files) => {
+                    for (name, path) in files {
+                        declared.entry(name).or_insert_with(uploaded_config).path = path;
+                    }
+                }
+                Err(e) => errors.push(format!("announcement.upload_dir: '{}' okunamadı: {}", dir.display(), e)),
+            }
+        }
+
+        let url_cache = match config.url_cache_dir() {
+            Some(dir) => match UrlCache::new(Path::new(dir), config.url_cache_max_bytes(), config.url_revalidate()) {
+                Ok(cache) => Some(Arc::new(cache)),
+                Err(e) => {
+                    errors.push(format!("announcement.url_cache_dir: '{}' oluşturulamadı: {}", dir, e));
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let cache = Arc::new(PromptCache::new(config.cache_max_bytes(), config.cache_max_entry_bytes()));
+        let default_language = config.default_language().map(str::to_string);
+        for (name, prompt_config) in &declared {
+            let pinned = prompt_config.preload || config.welcome_name() == Some(name.as_str());
+            let usage = Arc::<Usage>::default();
+            let mut variants = BTreeMap::new();
+            for (language, path) in &prompt_config.languages {
+                let variant = PromptConfig { path: path.clone(), language: Some(language.clone()), languages: BTreeMap::new(), ..prompt_config.clone() };
+                match open(name, variant, &usage, url_cache.as_ref(), &cache, pinned) {
+                    Ok(variant) => { variants.insert(language.clone(), Arc::new(variant)); }
+                    Err(e) => errors.push(format!("announcement.prompts.{}.languages.{}: {}", name, language, e)),
+                }
+            }
+            let base = PromptConfig { language: prompt_config.language.clone().or_else(|| default_language.clone()), ..prompt_config.clone() };
+            match open(name, base, &usage, url_cache.as_ref(), &cache, pinned) {
+                Ok(prompt) => { prompts.insert(name.clone(), Arc::new(Prompt { variants, ..prompt })); }
+                Err(e) => errors.push(format!("announcement.prompts.{}: {}", name, e)),
+            }
+        }
+
+        if !errors.is_empty() {
+            errors.sort();
+            return Err(PlaybackError::Library { failures: errors });
+        }
+        Ok(Self {
+            prompts: RwLock::new(prompts),
+            welcome: config.welcome_name().map(str::to_string),
+            replay_welcome: config.replay_welcome,
+            max_duration: config.max_duration.clone().filter(|name| !name.is_empty()),
+            welcome_bed: config.welcome_bed().map(|name| (name.to_string(), config.bed_gain_db())),
+            default_language,
+            segment_prefix: config.segment_prefix().to_string(),
+            max_queued: config.max_queued(),
+            upload_dir,
+            max_upload_bytes: config.max_upload_bytes(),
+            url_cache,
+            cache,
+            declared: Mutex::new(config.prompts.clone()),
+        })
+    }
+
+    pub fn welcome(&self) -> Option<Arc<Prompt>> {
+        self.welcome.as_ref().and_then(|name| self.prompts.read().unwrap().get(name).cloned())
+    }
+
+    /// Karşılamanın altına karışacak fon müziği anonsu ve dB cinsinden kısması.
+    pub fn welcome_bed(&self) -> Option<(Arc<Prompt>, f32)> {
+        let (name, gain_db) = self.welcome_bed.as_ref()?;
+        self.prompts.read().unwrap().get(name).map(|prompt| (prompt.clone(), *gain_db))
+    }
+
+    /// Dili yazılmayan anons dosyalarının dili.
+    pub fn default_language(&self) -> Option<&str> {
+        self.default_language.as_deref()
+    }
+
+    /// Oturum başına kuyrukta bekleyebilecek en fazla oynatma.
+    pub fn max_queued(&self) -> usize {
+        self.max_queued
+    }
+
+    /// Birleşik anons parçalarının (`compose` anahtarları) `language` dilindeki anonsları,
+    /// sırayla. Tanımlı olmayan ya da o dilde dosyası bulunmayan parçalar birlikte raporlanır;
+    /// eksik varsa hiçbiri dönmez, yarım cümle çalınmaz.
+    pub fn segments(&self, keys: &[String], language: Option<&str>) -> Result<Vec<Arc<Prompt>>, PlaybackError> {
+        let prompts = self.prompts.read().unwrap();
+        let mut segments = Vec::with_capacity(keys.len());
+        let mut missing = Vec::new();
+        for key in keys {
+            let name = format!("{}{}", self.segment_prefix, key);
+            let absent = match prompts.get(&name) {
+                Some(prompt) if prompt.speaks(language) => {
+                    segments.push(prompt.in_language(language));
+                    continue;
+                }
+                Some(_) => format!("{} ({})", name, language.unwrap_or_default()),
+                None => name,
+            };
+            if !missing.contains(&absent) {
+                missing.push(absent);
+            }
+        }
+        if !missing.is_empty() {
+            return Err(PlaybackError::MissingSegments { missing });
+        }
+        Ok(segments)
+    }
+
+    /// URL'li anonsların önbelleği; `url_cache_dir` tanımlı değilse yok.
+    pub fn url_cache(&self) -> Option<&UrlCache> {
+        self.url_cache.as_deref()
+    }
+
+    /// Anons önbelleğinin anlık durumu.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// En uzun oturum süresi dolunca kapanıştan önce çalınacak anons.
+    pub fn max_duration(&self) -> Option<Arc<Prompt>> {
+        self.max_duration.as_ref().and_then(|name| self.prompts.read().unwrap().get(name).cloned())
+    }
+
+    /// Yeni bir gelen akış kilitlendiğinde karşılama anonsu yeniden çalınacak mı.
+    pub fn replay_welcome(&self) -> bool {
+        self.replay_welcome
+    }
+
+    /// Bulunamazsa hata, isme en yakın tanımlı anonsları öneri olarak taşır.
+    pub fn get(&self, name: &str) -> Result<Arc<Prompt>, PlaybackError> {
+        self.prompts.read().unwrap().get(name).cloned()
+            .ok_or_else(|| PlaybackError::UnknownPrompt { name: name.to_string(), suggestions: self.suggestions(name) })
+    }
+
+    /// Config'de tanımlı olmayan bir adresten tek seferlik anons; `name` olaylarda görünür, boşsa
+    /// adresin kendisi kullanılır. Kullanım sayaçları kütüphanede tutulmaz.
+    pub fn remote(&self, url: &str, name: &str) -> Result<Arc<Prompt>, PlaybackError> {
+        let cache = self.url_cache.as_ref().ok_or(PlaybackError::UrlCacheDisabled)?;
+        url_cache::check_url(url).map_err(|reason| PlaybackError::InvalidUrl { url: url.to_string(), reason })?;
+        let config = PromptConfig { path: url.to_string(), ..uploaded_config() };
+        let name = if name.is_empty() { url } else { name };
+        Ok(Arc::new(Prompt { name: name.to_string(), config, cache: None, usage: Arc::default(), url_cache: Some(cache.clone()), variants: BTreeMap::new(), digest: None }))
+    }
+
+    /// Bir yüklemenin geçebileceği en büyük boyut; akış bu sınırı aşınca kesilir.
+    pub fn max_upload_bytes(&self) -> u64 {
+        self.max_upload_bytes
+    }
+
+    /// `data`'yı `name` adıyla kaydeder. Dosya geçici adla yazılıp diske işlenir, başlangıçtaki
+    /// doğrulamadan geçerse `<ad>.wav` olarak yerine taşınır; geçmezse önceki dosya yerinde kalır.
+    /// Aynı adlı anonsun kazanç, döngü, önceden yükleme ayarları ve dil varyantları korunur,
+    /// önbellekteki eski dosyası düşer; karşılama ve önceden yüklenen anonsun yenisi sabitlenir. Çalmakta olan oturumlar önceki dosyayı bitirir. Dosya işlemleri bloklar.
+    pub fn upload(&self, name: &str, data: &[u8]) -> Result<Uploaded, PromptStoreError> {
+        static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+        let dir = self.upload_dir.as_ref().ok_or(PromptStoreError::Disabled)?;
+        if !valid_name(name) {
+            return Err(PromptStoreError::InvalidName { name: name.to_string() });
+        }
+        if data.len() as u64 > self.max_upload_bytes {
+            return Err(PromptStoreError::TooLarge { limit: self.max_upload_bytes });
+        }
+
+        let temp = dir.join(format!(".{}.{}.tmp", name, NEXT_TEMP.fetch_add(1, Ordering::Relaxed)));
+        write_synced(&temp, data).map_err(|source| io_error(&temp, source))?;
+        let previous = self.prompts.read().unwrap().get(name).map(|prompt| (prompt.config.clone(), prompt.usage.clone(), prompt.variants.clone()));
+        let (mut config, usage, variants) = previous.unwrap_or_else(|| {
+            (PromptConfig { language: self.default_language.clone(), ..uploaded_config() }, Arc::default(), BTreeMap::new())
+        });
+        let previous_key = CacheKey::new(&config.path, config.gain_db);
+        let checked = load_samples(&PromptConfig { path: temp.display().to_string(), ..config.clone() });
+        let path = dir.join(format!("{}.wav", name));
+        let samples = checked.map_err(PromptStoreError::Invalid)
+            .and_then(|samples| fs::rename(&temp, &path).map(|()| samples).map_err(|source| io_error(&path, source)))
+            .inspect_err(|_| { let _ = fs::remove_file(&temp); })?;
+
+        config.path = path.display().to_string();
+        let uploaded = Uploaded {
+            path: config.path.clone(),
+            size_bytes: data.len() as u64,
+            duration: Duration::from_millis(samples.len() as u64 * 1000 / SAMPLE_RATE),
+            sha256: format!("{:x}", Sha256::digest(data)),
+        };
+        let digest = Some(digest(&samples));
+        self.cache.remove(&previous_key);
+        if config.preload || self.welcome.as_deref() == Some(name) {
+            self.cache.pin(CacheKey::new(&config.path, config.gain_db), Arc::new(samples));
+        }
+        info!(prompt = %name, file = %config.path, preload = config.preload, "Anons yüklendi");
+        let prompt = Arc::new(Prompt { name: name.to_string(), config, cache: Some(self.cache.clone()), usage, url_cache: None, variants, digest });
+        self.prompts.write().unwrap().insert(name.to_string(), prompt);
+        Ok(uploaded)
+    }
+
+    /// Bütün anonslar (config'de tanımlı ve yüklenmiş), ada göre sıralı. Dosyaları okur, bloklar.
+    pub fn list(&self) -> Vec<PromptInfo> {
+        let mut prompts: Vec<Arc<Prompt>> = self.prompts.read().unwrap().values().cloned().collect();
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        prompts.iter().map(|prompt| prompt.info()).collect()
+    }
+
+    /// Anonsu ve dosyasını siler; silinen dosyanın yolunu döner. Karşılama, karşılama fonu ya da
+    /// süre sonu anonsu olarak kullanılan ya da o an çalan anons silinmez. Config'de tanımlı bir anonsun dosyası da
+    /// silinir; girdisi config'den kaldırılmazsa sonraki başlangıç doğrulaması başarısız olur.
+    /// Yüklemeyle değiştirilmiş bir config anonsunda yalnızca yüklenen dosya silinir, config'deki
+    /// dosya sonraki başlangıçta geri gelir. URL'li anonsta önbellekteki kopya silinir. Dil
+    /// varyantlarının dosyalarına dokunulmaz.
+    pub fn delete(&self, name: &str) -> Result<String, PromptStoreError> {
+        let in_use = |by: String| PromptStoreError::InUse { name: name.to_string(), by };
+        let mut prompts = self.prompts.write().unwrap();
+        let prompt = prompts.get(name).ok_or_else(|| PromptStoreError::NotFound { name: name.to_string() })?;
+        if self.welcome.as_deref() == Some(name) {
+            return Err(in_use("announcement.welcome".to_string()));
+        }
+        if self.max_duration.as_deref() == Some(name) {
+            return Err(in_use("announcement.max_duration".to_string()));
+        }
+        if self.welcome_bed.as_ref().is_some_and(|(bed, _)| bed == name) {
+            return Err(in_use("announcement.welcome_bed".to_string()));
+        }
+        match prompt.usage.active.load(Ordering::Relaxed) {
+            0 => {}
+            active => return Err(in_use(format!("{} active playback(s)", active))),
+        }
+        let path = prompt.config.path.clone();
+        let removed = match (&prompt.url_cache, prompt.config.url()) {
+            (Some(cache), Some(url)) => cache.remove(url),
+            _ => fs::remove_file(&path),
+        };
+        match removed {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(source) => return Err(PromptStoreError::Io { path, source }),
+        }
+        self.cache.remove(&prompt.cache_key());
+        prompts.remove(name);
+        info!(prompt = %name, file = %path, "Anons silindi");
+        Ok(path)
+    }
+
+    /// `names` anonslarının (boşsa hepsinin) dosyalarını yeniden doğrular; sonuçlar ada göre sıralı.
+    /// Bilinmeyen bir ad varsa hiçbiri yenilenmez. Dosyaları okur, bloklar.
+    pub fn reload(&self, names: &[String]) -> Result<Vec<(String, ReloadOutcome)>, PromptStoreError> {
+        let mut targets: Vec<Arc<Prompt>> = {
+            let prompts = self.prompts.read().unwrap();
+            if names.is_empty() {
+                prompts.values().cloned().collect()
+            } else {
+                names.iter()
+                    .map(|name| prompts.get(name).cloned().ok_or_else(|| PromptStoreError::NotFound { name: name.clone() }))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        targets.sort_by(|a, b| a.name.cmp(&b.name));
+        targets.dedup_by(|a, b| a.name == b.name);
+        Ok(targets.iter().map(|prompt| (prompt.name.clone(), self.refresh(prompt, prompt.config.clone(), ReloadTrigger::Rpc))).collect())
+    }
+
+    /// Config yeniden okununca yolu ya da dil dosyaları değişen anonsları yeni dosyalarıyla yeniden
+    /// doğrular; sonuçlar ada göre sıralı. Yüklemeyle değiştirilmiş anonsta yalnızca config girdisi
+    /// güncellenir. Eklenen ya da kaldırılan anonslar ve diğer ayarlar yeniden başlatmayla geçerli
+    /// olur. Dosyaları okur, bloklar.
+    pub fn reconfigure(&self, config: &AnnouncementConfig) -> Vec<(String, ReloadOutcome)> {
+        let mut moved = Vec::new();
+        {
+            let mut declared = self.declared.lock().unwrap();
+            for (name, new) in &config.prompts {
+                let Some(old) = declared.get_mut(name) else { continue };
+                if old.path != new.path || old.languages != new.languages {
+                    let previous = std::mem::replace(old, new.clone());
+                    moved.push((name.clone(), previous.path, new.clone()));
+                }
+            }
+        }
+        moved.sort_by(|a, b| a.0.cmp(&b.0));
+        moved.into_iter()
+            .filter_map(|(name, previous_path, new)| {
+                let current = self.prompts.read().unwrap().get(&name).cloned()?;
+                if current.config.path != previous_path {
+                    return None;
+                }
+                let config = PromptConfig { path: new.path, languages: new.languages, ..current.config.clone() };
+                Some((name, self.refresh(&current, config, ReloadTrigger::Config)))
+            })
+            .collect()
+    }
+
+    /// Anonsu `config`'in dosyalarıyla yeniden açıp kütüphanedekinin yerine koyar. Eski dosyaların
+    /// önbellekteki sesi düşer; sabitlenen anonsun yenisi sabitlenir, diğerleri ilk çalmada önbelleğe
+    /// alınır. Dosyası bozulan anons kütüphanede kalır, çalınınca hatası oynatmaya döner.
+    fn refresh(&self, current: &Prompt, config: PromptConfig, trigger: ReloadTrigger) -> ReloadOutcome {
+        let name = current.name.as_str();
+        let pinned = config.preload || self.welcome.as_deref() == Some(name);
+        for file in std::iter::once(current).chain(current.variants.values().map(Arc::as_ref)) {
+            self.cache.remove(&file.cache_key());
+        }
+        let reopen = |config: PromptConfig| {
+            if config.url().is_none() && !Path::new(&config.path).exists() {
+                return Err(ReloadOutcome::Missing { path: config.path });
+            }
+            open(name, config, &current.usage, self.url_cache.as_ref(), &self.cache, pinned).map_err(|reason| ReloadOutcome::Invalid { reason })
+        };
+
+        let mut changed = config.path != current.config.path || config.languages != current.config.languages;
+        let mut failure = None;
+        let mut variants = BTreeMap::new();
+        for (language, path) in &config.languages {
+            let variant = PromptConfig { path: path.clone(), language: Some(language.clone()), languages: BTreeMap::new(), ..config.clone() };
+            match reopen(variant) {
+                Ok(variant) => {
+                    changed |= current.variants.get(language).map(|previous| previous.digest) != Some(variant.digest);
+                    variants.insert(language.clone(), Arc::new(variant));
+                }
+                Err(outcome) => { failure.get_or_insert(outcome); }
+            }
+        }
+        let prompt = match reopen(config.clone()) {
+            Ok(prompt) => {
+                changed |= prompt.digest != current.digest;
+                prompt
+            }
+            Err(outcome) => {
+                failure = Some(outcome);
+                let url_cache = config.url().and(self.url_cache.clone());
+                Prompt { name: name.to_string(), config, cache: Some(self.cache.clone()), usage: current.usage.clone(), url_cache, variants: BTreeMap::new(), digest: None }
+            }
+        };
+        let outcome = match failure {
+            Some(failure) => failure,
+            None if changed => ReloadOutcome::Changed,
+            None => ReloadOutcome::Unchanged,
+        };
+
+        let mut prompts = self.prompts.write().unwrap();
+        // Bu arada silinen ya da yüklemeyle değişen anonsun yerine geçilmez.
+        if prompts.get(name).is_some_and(|existing| std::ptr::eq(existing.as_ref(), current)) {
+            prompts.insert(name.to_string(), Arc::new(Prompt { variants, ..prompt }));
+        }
+        if outcome != ReloadOutcome::Unchanged {
+            info!(
+                target: audit::TARGET, event = audit::PROMPT_RELOADED,
+                prompt = %name, outcome = outcome.label(), reason = outcome.reason(), trigger = trigger.label(),
+            );
+        }
+        outcome
+    }
+
+    fn suggestions(&self, name: &str) -> Vec<String> {
+        let max_distance = (name.chars().count() / 3).max(2);
+        let prompts = self.prompts.read().unwrap();
+        let mut candidates: Vec<(usize, &String)> = prompts.keys()
+            .map(|candidate| (levenshtein(name, candidate), candidate))
+            .filter(|(distance, candidate)| *distance <= max_distance || candidate.starts_with(name))
+            .collect();
+        candidates.sort();
+        candidates.into_iter().take(3).map(|(_, candidate)| candidate.clone()).collect()
+    }
+}
+
+/// Bir anons dosyasını doğrular, `pinned` ise önbelleğe sabitler; URL'li dosya ilk çalmada indirilir.
+fn open(name: &str, config: PromptConfig, usage: &Arc<Usage>, url_cache: Option<&Arc<UrlCache>>, cache: &Arc<PromptCache>, pinned: bool) -> Result<Prompt, String> {
+    let prompt = |config, cache, url_cache, digest| Prompt { name: name.to_string(), config, cache, usage: usage.clone(), url_cache, variants: BTreeMap::new(), digest };
+    if let Some(url) = config.url() {
+        url_cache::check_url(url).map_err(|reason| PlaybackError::InvalidUrl { url: url.to_string(), reason }.to_string())?;
+        let cache = url_cache.ok_or_else(|| PlaybackError::UrlCacheDisabled.to_string())?;
+        info!(prompt = %name, url = %url, language = config.language.as_deref(), "Anons ilk çalmada URL'den indirilecek");
+        return Ok(prompt(config, None, Some(cache.clone()), None));
+    }
+    let samples = load_samples(&config).map_err(|e| e.to_string())?;
+    info!(prompt = %name, file = %config.path, preload = pinned, language = config.language.as_deref(), "Anons doğrulandı");
+    let digest = Some(digest(&samples));
+    if pinned {
+        cache.pin(CacheKey::new(&config.path, config.gain_db), Arc::new(samples));
+    }
+    Ok(prompt(config, Some(cache.clone()), None, digest))
+}
+
+fn load_samples(config: &PromptConfig) -> Result<Vec<i16>, PlaybackError> {
+    WavSource::open(&config.path, config.gain_db)?.read_to_end()
+}
+
+fn digest(samples: &[i16]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for sample in samples {
+        hasher.update(sample.to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Anons adları dosya adı olarak kullanıldığından yalnızca harf, rakam, '-' ve '_' içerebilir.
+fn valid_name(name: &str) -> bool {
+    (1..=MAX_NAME_LEN).contains(&name.len()) && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Config'de tanımlı olmayan yüklenmiş anonsun ayarları; yol çağıran tarafından yazılır.
+fn uploaded_config() -> PromptConfig {
+    PromptConfig { path: String::new(), gain_db: 0.0, looped: false, language: None, preload: false, languages: BTreeMap::new() }
+}
+
+/// Yükleme dizinindeki `<ad>.wav` dosyaları; dizin yoksa oluşturulur. Yarım kalmış geçici
+/// dosyalar (`.` ile başlar) geçerli bir ad taşımadığından atlanır.
+fn uploaded_files(dir: &Path) -> io::Result<Vec<(String, String)>> {
+    fs::create_dir_all(dir)?;
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_stem().and_then(|stem| stem.to_str()).filter(|stem| valid_name(stem));
+        if let (Some(name), Some("wav")) = (name, path.extension().and_then(|ext| ext.to_str())) {
+            files.push((name.to_string(), path.display().to_string()));
+        }
+    }
+    Ok(files)
+}
+
+fn file_info(path: &str) -> Result<FileInfo, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let reader = hound::WavReader::new(io::Cursor::new(&data)).map_err(|e| e.to_string())?;
+    let spec = reader.spec();
+    Ok(FileInfo {
+        size_bytes: data.len() as u64,
+        duration: Duration::from_millis(reader.duration() as u64 * 1000 / spec.sample_rate.max(1) as u64),
+        sample_rate: spec.sample_rate,
+        bits_per_sample: spec.bits_per_sample,
+        channels: spec.channels,
+        sha256: format!("{:x}", Sha256::digest(&data)),
+    })
+}
+
+fn io_error(path: &Path, source: io::Error) -> PromptStoreError {
+    PromptStoreError::Io { path: path.display().to_string(), source }
+}
+
+pub(crate) fn write_synced(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(data)?;
+    file.sync_all()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(dir: &Path) -> PromptLibrary {
+        let mut config = AnnouncementConfig { upload_dir: Some(dir.display().to_string()), ..AnnouncementConfig::default() };
+        config.prompts.insert("welcome".to_string(), PromptConfig {
+            path: "audio/processed/standard/welcome.wav".to_string(), gain_db: 0.0, looped: false, language: None, preload: true, languages: BTreeMap::new(),
+        });
+        PromptLibrary::load(&config).unwrap()
+    }
+
+    /// 8 kHz 16 bit mono, sabit değerli bir WAV dosyası.
+    fn wav(value: i16, samples: usize) -> Vec<u8> {
+        let spec = hound::WavSpec { channels: 1, sample_rate: 8000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut cursor = io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for _ in 0..samples {
+            writer.write_sample(value).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn upload_replaces_cached_prompt_and_survives_reload() {
+        let dir = std::env::temp_dir().join(format!("media-upload-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let prompts = library(&dir);
+        let original = prompts.get("welcome").unwrap().samples().unwrap();
+
+        let data = wav(1000, 4000);
+        let uploaded = prompts.upload("welcome", &data).unwrap();
+        assert_eq!((uploaded.size_bytes, uploaded.duration), (data.len() as u64, Duration::from_millis(500)));
+        assert_eq!(uploaded.sha256, format!("{:x}", Sha256::digest(&data)));
+        assert_eq!(uploaded.sha256.len(), 64);
+        // Önceden yüklenen anonsun önbelleği yeni dosyayla değişti.
+        let replaced = prompts.get("welcome").unwrap();
+        assert!(replaced.config.preload);
+        assert_eq!(*replaced.samples().unwrap(), vec![1000; 4000]);
+        assert_ne!(replaced.samples().unwrap(), original);
+
+        // Geçersiz dosya, büyük dosya ve dizin dışına çıkan ad reddedilir; önceki dosya yerinde kalır.
+        assert!(matches!(prompts.upload("welcome", b"RIFF not a wav"), Err(PromptStoreError::Invalid(_))));
+        assert!(matches!(prompts.upload("big", &vec![0; prompts.max_upload_bytes() as usize + 1]), Err(PromptStoreError::TooLarge { .. })));
+        assert!(matches!(prompts.upload("../welcome", &data), Err(PromptStoreError::InvalidName { .. })));
+        assert_eq!(prompts.get("welcome").unwrap().samples().unwrap().len(), 4000);
+        prompts.upload("hold", &wav(-5, 800)).unwrap();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2, "no temporary files left behind");
+
+        // Yeniden başlatmada yüklenen dosyalar config girdilerinin yerine geçer.
+        let reloaded = library(&dir);
+        assert_eq!(*reloaded.get("welcome").unwrap().samples().unwrap(), vec![1000; 4000]);
+        assert_eq!(reloaded.get("hold").unwrap().samples().unwrap().len(), 800);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let disabled = PromptLibrary::load(&AnnouncementConfig::default()).unwrap();
+        assert!(matches!(disabled.upload("welcome", &data), Err(PromptStoreError::Disabled)));
+    }
+
+    #[test]
+    fn language_variants_resolve_with_fallback_to_the_default_language() {
+        let dir = std::env::temp_dir().join(format!("media-languages-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("welcome.en.wav"), wav(300, 800)).unwrap();
+        let mut config = AnnouncementConfig { default_language: Some("tr".to_string()), ..AnnouncementConfig::default() };
+        config.prompts.insert("welcome".to_string(), PromptConfig {
+            path: "audio/processed/standard/welcome.wav".to_string(), gain_db: 0.0, looped: false, language: None, preload: true,
+            languages: BTreeMap::from([("en".to_string(), dir.join("welcome.en.wav").display().to_string())]),
+        });
+        let prompts = PromptLibrary::load(&config).unwrap();
+        let welcome = prompts.get("welcome").unwrap();
+        assert_eq!(welcome.languages(), ["en", "tr"]);
+
+        let english = welcome.in_language(Some("en"));
+        assert_eq!((english.config.language.as_deref(), english.samples().unwrap().len()), (Some("en"), 800));
+        for requested in [Some("tr"), Some("de"), None] {
+            let chosen = welcome.in_language(requested);
+            assert!(Arc::ptr_eq(&chosen, &welcome), "{requested:?}");
+        }
+        // Varyantın çalınması da anonsun kullanımı sayılır.
+        let _claim = english.claim();
+        assert_eq!((prompts.list()[0].plays, prompts.list()[0].active_playbacks), (1, 1));
+
+        // Her varyant başlangıçta doğrulanır.
+        config.prompts.get_mut("welcome").unwrap().languages.insert("fr".to_string(), dir.join("missing.wav").display().to_string());
+        let failures = match PromptLibrary::load(&config) {
+            Err(PlaybackError::Library { failures }) => failures,
+            other => panic!("{other:?}"),
+        };
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("announcement.prompts.welcome.languages.fr:"), "{}", failures[0]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cache_evicts_over_budget_streams_large_files_and_keeps_pinned_prompts() {
+        let dir = std::env::temp_dir().join(format!("media-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = AnnouncementConfig {
+            upload_dir: Some(dir.display().to_string()), welcome: Some("hello".to_string()),
+            cache_max_bytes: Some(4000), cache_max_entry_bytes: Some(3000), ..AnnouncementConfig::default()
+        };
+        let prompts = PromptLibrary::load(&config).unwrap();
+        prompts.upload("hello", &wav(5, 400)).unwrap();
+        for (name, value) in [("a", 1), ("b", 2), ("c", 3)] {
+            prompts.upload(name, &wav(value, 800)).unwrap();
+        }
+        prompts.upload("big", &wav(9, 2000)).unwrap();
+        // Karşılama anonsu yüklenirken sabitlendi; diğerleri ilk çalmayı bekler.
+        assert_eq!((prompts.cache_stats().entries, prompts.cache_stats().pinned_entries), (1, 1));
+
+        let play = |name: &str| prompts.get(name).unwrap().samples().unwrap();
+        play("a");
+        play("b");
+        play("a");
+        // Bütçe aşıldı: en uzun süredir çalınmayan b atıldı, karşılama kaldı.
+        assert_eq!(*play("c"), vec![3; 800]);
+        let cached: Vec<(String, bool)> = prompts.list().into_iter().map(|info| (info.name, info.cached)).collect();
+        assert_eq!(cached, [("a", true), ("b", false), ("big", false), ("c", true), ("hello", true)].map(|(name, cached)| (name.to_string(), cached)));
+
+        // Girdi sınırını aşan dosya çalınır ama önbelleğe alınmaz.
+        assert_eq!(play("big").len(), 2000);
+        assert!(!prompts.list().iter().any(|info| info.name == "big" && info.cached));
+        assert_eq!(prompts.cache_stats(), CacheStats {
+            bytes: 4000, max_bytes: 4000, entries: 3, pinned_entries: 1, hits: 1, misses: 4, evictions: 1,
+        });
+
+        // Silinen anonsun girdisi düşer.
+        prompts.delete("c").unwrap();
+        assert_eq!(prompts.cache_stats().bytes, 2400);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reload_picks_up_changed_files_and_leaves_playing_audio_alone() {
+        let dir = std::env::temp_dir().join(format!("media-reload-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut config = AnnouncementConfig::default();
+        for (name, preload) in [("hold", true), ("note", false), ("gone", false), ("bad", false)] {
+            fs::write(dir.join(format!("{name}.wav")), wav(1, 800)).unwrap();
+            config.prompts.insert(name.to_string(), PromptConfig {
+                path: dir.join(format!("{name}.wav")).display().to_string(), gain_db: 0.0, looped: false, language: None, preload, languages: BTreeMap::new(),
+            });
+        }
+        let prompts = PromptLibrary::load(&config).unwrap();
+        // Çalmakta olan oynatma başladığı sesi tutar.
+        let playing = prompts.get("hold").unwrap().samples().unwrap();
+
+        fs::write(dir.join("hold.wav"), wav(2, 400)).unwrap();
+        fs::remove_file(dir.join("gone.wav")).unwrap();
+        fs::write(dir.join("bad.wav"), b"RIFF not a wav").unwrap();
+        let reloaded = prompts.reload(&[]).unwrap();
+        assert_eq!(reloaded.iter().map(|(name, outcome)| (name.as_str(), outcome.label())).collect::<Vec<_>>(), [
+            ("bad", "invalid"), ("gone", "missing"), ("hold", "changed"), ("note", "unchanged"),
+        ]);
+        assert_eq!(reloaded[1].1.reason(), Some(dir.join("gone.wav").display().to_string().as_str()));
+        assert_eq!(*prompts.get("hold").unwrap().samples().unwrap(), vec![2; 400]);
+        assert_eq!(*playing, vec![1; 800]);
+        assert!(prompts.get("gone").unwrap().source().is_err());
+        // Sabitlenen anonsun yenisi önbellekte; eskisi düştü.
+        assert_eq!((prompts.cache_stats().pinned_entries, prompts.cache_stats().bytes), (1, 800));
+        assert!(matches!(prompts.reload(&["hold".to_string(), "nope".to_string()]), Err(PromptStoreError::NotFound { name }) if name == "nope"));
+
+        // Config'de yolu değişen anons yeni dosyasıyla açılır; değişmeyenlere dokunulmaz.
+        fs::write(dir.join("note2.wav"), wav(3, 160)).unwrap();
+        config.prompts.get_mut("note").unwrap().path = dir.join("note2.wav").display().to_string();
+        assert_eq!(prompts.reconfigure(&config), [("note".to_string(), ReloadOutcome::Changed)]);
+        assert_eq!(*prompts.get("note").unwrap().samples().unwrap(), vec![3; 160]);
+        assert!(prompts.reconfigure(&config).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn delete_refuses_referenced_and_playing_prompts_and_list_reports_usage() {
+        let dir = std::env::temp_dir().join(format!("media-delete-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut config = AnnouncementConfig { upload_dir: Some(dir.display().to_string()), welcome: Some("welcome".to_string()), ..AnnouncementConfig::default() };
+        config.prompts.insert("welcome".to_string(), PromptConfig {
+            path: "audio/processed/standard/welcome.wav".to_string(), gain_db: 0.0, looped: false, language: None, preload: true, languages: BTreeMap::new(),
+        });
+        let prompts = PromptLibrary::load(&config).unwrap();
+        let data = wav(7, 1600);
+        prompts.upload("hold", &data).unwrap();
+
+        let claim = prompts.get("hold").unwrap().claim();
+        let list = prompts.list();
+        assert_eq!(list.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["hold", "welcome"]);
+        assert_eq!((list[0].plays, list[0].active_playbacks, list[0].cached), (1, 1, false));
+        assert_eq!(list[0].file, Ok(FileInfo {
+            size_bytes: data.len() as u64, duration: Duration::from_millis(200), sample_rate: 8000, bits_per_sample: 16, channels: 1,
+            sha256: format!("{:x}", Sha256::digest(&data)),
+        }));
+        assert!(list[1].cached);
+
+        assert!(matches!(prompts.delete("hold"), Err(PromptStoreError::InUse { .. })));
+        assert!(matches!(prompts.delete("welcome"), Err(PromptStoreError::InUse { by, .. }) if by == "announcement.welcome"));
+        assert!(matches!(prompts.delete("missing"), Err(PromptStoreError::NotFound { .. })));
+        drop(claim);
+        assert_eq!(prompts.delete("hold").unwrap(), dir.join("hold.wav").display().to_string());
+        assert!(!dir.join("hold.wav").exists());
+        assert!(prompts.get("hold").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
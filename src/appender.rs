@@ -0,0 +1,203 @@
+// Satır satır eklenen kayıt dosyaları (CDR, port günlüğü) için ortak yazıcı. Kayıtlar sınırlı bir
+// kuyruğa `try_send` ile konur; dosyaya ayrı bir iş parçacığı yazar ve birikmiş kayıtları tek bir
+// fsync ile diske zorlar. Çağıran diski hiç beklemez: kuyruk doluysa kayıt düşer. Kaydın nasıl
+// satıra çevrileceği, dosyanın ne zaman ve hangi ada döndürüleceği ve sonucun nasıl sayılıp
+// loglanacağı kullanan modüldedir (bkz. `Sink`).
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+
+/// Satırların eklendiği tek bir dosya; dosya ilk yazmada açılır.
+pub struct AppendFile {
+    path: PathBuf,
+    // Boş dosyaya ilk satır olarak yazılır (CSV başlığı).
+    header: Option<String>,
+    file: Option<File>,
+    size: u64,
+    unsynced: bool,
+}
+
+impl AppendFile {
+    pub fn new(path: PathBuf, header: Option<String>) -> Self {
+        AppendFile { path, header, file: None, size: 0, unsynced: false }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Dosya kapalıysa açar ve `true` döner; açıksa hiçbir şey yapmaz.
+    pub fn open(&mut self) -> io::Result<bool> {
+        if self.file.is_some() {
+            return Ok(false);
+        }
+        self.file = Some(self.open_file()?);
+        Ok(true)
+    }
+
+    fn open_file(&mut self) -> io::Result<File> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(&self.path)?;
+        self.size = file.metadata()?.len();
+        // Çöken süreçten kalan yarım satır yeni kayda karışmaz.
+        if self.size > 0 {
+            let mut last = [0u8];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                file.write_all(b"\n")?;
+                self.size += 1;
+            }
+        }
+        if let Some(header) = self.header.as_ref().filter(|_| self.size == 0) {
+            file.write_all(header.as_bytes())?;
+            self.size = header.len() as u64;
+        }
+        Ok(file)
+    }
+
+    /// Başlık dışında en az bir satır var mı.
+    pub fn has_records(&self) -> bool {
+        self.size > self.header.as_ref().map_or(0, |header| header.len() as u64)
+    }
+
+    /// `len` baytlık satır dosyayı `max_bytes`'ın üstüne taşır mı; boş dosya hiç taşmaz.
+    pub fn exceeds(&self, len: usize, max_bytes: u64) -> bool {
+        self.has_records() && self.size + len as u64 > max_bytes
+    }
+
+    /// `line` sonundaki satır sonuyla birlikte eklenir; dosya kapalıysa önce açılır.
+    pub fn append(&mut self, line: &str) -> io::Result<()> {
+        let mut file = match self.file.take() {
+            Some(file) => file,
+            None => self.open_file()?,
+        };
+        // Hata olursa yarım kalan satırdan sonra boyut bilinmiyor; dosya kapalı kalır ve bir
+        // sonraki kayıtta yeniden açılır.
+        file.write_all(line.as_bytes())?;
+        self.file = Some(file);
+        self.size += line.len() as u64;
+        self.unsynced = true;
+        Ok(())
+    }
+
+    /// Yazılan ama diske zorlanmamış satırları zorlar.
+    pub fn sync(&mut self) -> io::Result<()> {
+        if !self.unsynced {
+            return Ok(());
+        }
+        if let Some(file) = &self.file {
+            file.sync_data()?;
+        }
+        self.unsynced = false;
+        Ok(())
+    }
+
+    /// Dosyayı kapatıp `target` adıyla kenara alır; bir sonraki satır yeni dosyaya yazılır.
+    /// Diske zorlamak çağıranın işidir.
+    pub fn rotate_to(&mut self, target: &Path) -> io::Result<()> {
+        self.file = None;
+        self.size = 0;
+        self.unsynced = false;
+        fs::rename(&self.path, target)
+    }
+}
+
+/// Yazıcı iş parçacığının kayıtları teslim ettiği taraf; sonuçları kendisi sayar ve loglar.
+pub trait Sink: Send + 'static {
+    type Record: Send + 'static;
+
+    /// Kaydı dosyaya ekler.
+    fn deliver(&mut self, record: Self::Record);
+
+    /// Kuyrukta bekleyen kayıtlar bitince çağrılır; biriken satırlar burada diske zorlanır.
+    fn end_batch(&mut self);
+
+    /// Kuyruk dolu ya da yazıcı kapalı olduğu için yazılmayan kayıt.
+    fn dropped(record: Self::Record);
+}
+
+/// Kayıtları `Sink`'e sınırlı bir kuyruk ve tek bir yazıcı iş parçacığı üzerinden teslim eder:
+/// `submit` beklemez, kuyruk doluysa kaydı düşürür; iş parçacığı birikmiş kayıtları sırayla yazıp
+/// her grubun sonunda `Sink::end_batch` ile diske zorlar. Dosyanın kendisi `Sink`'in `AppendFile`'ındadır.
+pub struct Appender<S: Sink> {
+    // Kapanışta alınır; yazıcı kuyruk boşalınca çıkar.
+    queue: Mutex<Option<SyncSender<S::Record>>>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl<S: Sink> Appender<S> {
+    /// `name` adlı yazıcı iş parçacığını `queue_size` kayıtlık kuyrukla başlatır.
+    pub fn start(name: &str, queue_size: usize, sink: S) -> io::Result<Self> {
+        let (queue, records) = mpsc::sync_channel(queue_size.max(1));
+        let worker = std::thread::Builder::new().name(name.to_string()).spawn(move || run(sink, records))?;
+        Ok(Appender { queue: Mutex::new(Some(queue)), worker: Mutex::new(Some(worker)) })
+    }
+
+    /// Kaydı kuyruğa koyar; kuyruk doluysa ya da yazıcı kapandıysa düşürür ve `false` döner.
+    pub fn submit(&self, record: S::Record) -> bool {
+        let queue = self.queue.lock().unwrap();
+        let Some(queue) = queue.as_ref() else { return false };
+        match queue.try_send(record) {
+            Ok(()) => true,
+            Err(TrySendError::Full(record) | TrySendError::Disconnected(record)) => {
+                S::dropped(record);
+                false
+            }
+        }
+    }
+
+    /// Yeni kayıt almayı bırakır ve bekleyenler yazılıp diske zorlanana kadar bekler.
+    pub fn flush(&self) {
+        self.queue.lock().unwrap().take();
+        if let Some(worker) = self.worker.lock().unwrap().take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn run<S: Sink>(mut sink: S, records: Receiver<S::Record>) {
+    while let Ok(record) = records.recv() {
+        sink.deliver(record);
+        // Birikmiş kayıtlar tek bir fsync ile diske zorlanır.
+        while let Ok(record) = records.try_recv() {
+            sink.deliver(record);
+        }
+        sink.end_batch();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn torn_lines_are_closed_and_the_header_is_written_once() {
+        let dir = std::env::temp_dir().join(format!("media-appender-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("records.csv");
+        let mut file = AppendFile::new(path.clone(), Some("a,b\n".to_string()));
+        assert!(file.open().unwrap());
+        assert!(!file.has_records());
+        file.append("1,2\n").unwrap();
+        assert!(file.exceeds(5, 10) && !file.exceeds(2, 10));
+        drop(file);
+        // Çöken süreçten kalan yarım satır.
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "3,").unwrap();
+
+        let mut file = AppendFile::new(path.clone(), Some("a,b\n".to_string()));
+        file.append("5,6\n").unwrap();
+        file.sync().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a,b\n1,2\n3,\n5,6\n");
+        file.rotate_to(&dir.join("records.csv.1")).unwrap();
+        assert!(!file.has_records());
+        file.append("7,8\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a,b\n7,8\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
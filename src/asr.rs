@@ -0,0 +1,289 @@
+// Canlı transkripsiyon: AllocatePort(asr) açılan oturumun çözülmüş gelen sesi dış bir tanıma
+// servisine (proto/asr.proto) çift yönlü gRPC akışıyla gönderilir; servisin ara ve kesin
+// sonuçları transcript olayı olarak yazılır. Medya yolu sesi yalnızca sınırlı bir tampona ekler:
+// tampon `asr.buffer_ms`'i aşınca en eski çerçeve düşer, böylece yavaş ya da erişilemeyen servis
+// ne bellek tüketir ne de medyayı bekletir. Kopan bağlantı `retry_initial_ms`'den `retry_max_ms`'e
+// katlanan aralıklarla yeniden kurulur; yeni akış yine config mesajıyla başlar ve tamponda kalan
+// sesten devam eder. Oturum bitince tampondaki ses gönderilir, akış kapatılır ve kesin sonuçlar
+// FINAL_WAIT kadar beklenir; o sırada bağlantı kurulamazsa kalan ses atılır.
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, Notify};
+use tokio::time::{sleep, sleep_until, Instant};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::MetadataValue;
+use tonic::transport::Endpoint;
+use tonic::Request;
+use tracing::{debug, info, warn, Instrument, Span};
+
+use crate::audit;
+use crate::config::AsrConfig;
+use crate::metrics;
+
+pub mod proto {
+    tonic::include_proto!("asr");
+}
+
+use proto::recognizer_client::RecognizerClient;
+use proto::{AudioChunk, StreamConfig, TranscriptResult};
+
+// Oturum bittikten sonra kesin sonuçların en fazla bekleneceği süre.
+const FINAL_WAIT: Duration = Duration::from_secs(2);
+// gRPC akışına verilmeyi bekleyen mesajlar; asıl tampon drop-oldest kuyruktur.
+const CHANNEL_CAPACITY: usize = 4;
+
+/// Oturumun tanıma akışı; düşünce ya da `close` ile kapanır.
+#[derive(Debug)]
+pub struct AsrStream {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    buffer: Mutex<Buffer>,
+    ready: Notify,
+}
+
+#[derive(Debug)]
+struct Buffer {
+    frames: VecDeque<Vec<i16>>,
+    samples: usize,
+    max_samples: usize,
+    closed: bool,
+}
+
+impl AsrStream {
+    /// Akış görevini oturumun span'inde başlatır; bağlantı arka planda kurulur.
+    pub fn start(config: &AsrConfig, stream: StreamConfig, span: Span) -> Self {
+        let shared = Arc::new(Shared::new(config.buffer_samples(stream.sample_rate)));
+        tokio::spawn(run(config.clone(), stream, shared.clone()).instrument(span));
+        AsrStream { shared }
+    }
+
+    /// Çözülmüş gelen çerçeveyi tampona ekler; tampon doluysa en eski ses düşer. Beklemez.
+    pub fn push(&self, pcm: &[i16]) {
+        self.shared.push(pcm);
+    }
+
+    /// Tampondaki ses gönderildikten sonra akış kapanır.
+    pub fn close(&self) {
+        self.shared.buffer.lock().unwrap().closed = true;
+        self.shared.ready.notify_one();
+    }
+}
+
+impl Drop for AsrStream {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl Shared {
+    fn new(max_samples: usize) -> Self {
+        Shared { buffer: Mutex::new(Buffer { frames: VecDeque::new(), samples: 0, max_samples, closed: false }), ready: Notify::new() }
+    }
+
+    fn push(&self, pcm: &[i16]) {
+        let mut buffer = self.buffer.lock().unwrap();
+        buffer.samples += pcm.len();
+        buffer.frames.push_back(pcm.to_vec());
+        while buffer.samples > buffer.max_samples {
+            let Some(dropped) = buffer.frames.pop_front() else { break };
+            buffer.samples -= dropped.len();
+            metrics::get().asr_frames_dropped.inc();
+        }
+        drop(buffer);
+        self.ready.notify_one();
+    }
+
+    /// Sıradaki çerçeve; akış kapandıysa ve tampon boşaldıysa yok.
+    async fn next(&self) -> Option<Vec<i16>> {
+        loop {
+            {
+                let mut buffer = self.buffer.lock().unwrap();
+                if let Some(frame) = buffer.frames.pop_front() {
+                    buffer.samples -= frame.len();
+                    return Some(frame);
+                }
+                if buffer.closed {
+                    return None;
+                }
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.buffer.lock().unwrap().closed
+    }
+}
+
+/// Akış kapanana kadar bağlanır, sesi gönderir ve sonuçları yazar; koparsa bekleyip yeniden dener.
+async fn run(config: AsrConfig, stream: StreamConfig, shared: Arc<Shared>) {
+    let mut backoff = config.retry_initial();
+    loop {
+        let Err(e) = forward(&config, &stream, &shared, &mut backoff).await else { return };
+        if shared.is_closed() {
+            warn!(endpoint = %config.endpoint, error = %e, "ASR akışı oturum sonunda tamamlanamadı, kalan ses atıldı");
+            return;
+        }
+        metrics::get().asr_reconnects.inc();
+        warn!(endpoint = %config.endpoint, error = %e, retry_ms = backoff.as_millis() as u64, "ASR bağlantısı koptu, yeniden denenecek");
+        sleep(backoff).await;
+        backoff = (backoff * 2).min(config.retry_max());
+    }
+}
+
+/// Tek bir akış; oturum bitip son sonuçlar alınınca ya da beklenince Ok.
+async fn forward(config: &AsrConfig, stream: &StreamConfig, shared: &Shared, backoff: &mut Duration) -> Result<(), String> {
+    let channel = Endpoint::from_shared(config.endpoint.clone())
+        .map_err(|e| e.to_string())?
+        .connect_timeout(config.connect_timeout())
+        .connect()
+        .await
+        .map_err(|e| e.to_string())?;
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    tx.try_send(AudioChunk { config: Some(stream.clone()), pcm: Vec::new() }).map_err(|e| e.to_string())?;
+    let mut request = Request::new(ReceiverStream::new(rx));
+    if !config.auth_token.is_empty() {
+        let token = MetadataValue::try_from(format!("Bearer {}", config.auth_token)).map_err(|e| e.to_string())?;
+        request.metadata_mut().insert("authorization", token);
+    }
+    let mut results = RecognizerClient::new(channel).recognize(request).await.map_err(|status| status.message().to_string())?.into_inner();
+    *backoff = config.retry_initial();
+    debug!(endpoint = %config.endpoint, "ASR akışı açıldı");
+
+    // Oturum bitince gönderen düşer (akış kapanır) ve son sonuçlar bu ana kadar beklenir.
+    let (mut sender, mut deadline) = (Some(tx), None);
+    loop {
+        tokio::select! {
+            result = results.message() => match result.map_err(|status| status.message().to_string())? {
+                Some(result) => transcript(&result),
+                None if sender.is_none() => return Ok(()),
+                None => return Err("recognizer ended the stream".to_string()),
+            },
+            frame = shared.next(), if sender.is_some() => match (frame, &sender) {
+                (Some(pcm), Some(tx)) => {
+                    let chunk = AudioChunk { config: None, pcm: pcm.iter().flat_map(|sample| sample.to_le_bytes()).collect() };
+                    tx.send(chunk).await.map_err(|_| "recognizer stream closed".to_string())?;
+                }
+                _ => {
+                    sender = None;
+                    deadline = Some(Instant::now() + FINAL_WAIT);
+                }
+            },
+            _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                debug!("ASR kesin sonuçları beklenirken süre doldu");
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn transcript(result: &TranscriptResult) {
+    metrics::get().asr_transcripts.inc();
+    info!(
+        target: audit::TARGET, event = audit::TRANSCRIPT,
+        text = %result.text, is_final = result.is_final, confidence = result.confidence, start_ms = result.start_ms, end_ms = result.end_ms,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tokio_stream::StreamExt;
+    use tonic::transport::Server;
+    use tonic::{Response, Status, Streaming};
+
+    use super::proto::recognizer_server::{Recognizer, RecognizerServer};
+    use super::*;
+
+    // Akış başına alınan config ve ses örneği sayısı.
+    type Received = Vec<(Option<StreamConfig>, usize)>;
+
+    /// Aldığı config'i ve örnek sayısını saklayan, her akışa bir kesin sonuç dönen servis.
+    /// `fail_first` ise ilk akışı ilk ses çerçevesinde hatayla keser.
+    #[derive(Default, Clone)]
+    struct FakeRecognizer {
+        received: Arc<Mutex<Received>>,
+        fail_first: bool,
+    }
+
+    #[tonic::async_trait]
+    impl Recognizer for FakeRecognizer {
+        type RecognizeStream = ReceiverStream<Result<TranscriptResult, Status>>;
+
+        async fn recognize(&self, request: Request<Streaming<AudioChunk>>) -> Result<Response<Self::RecognizeStream>, Status> {
+            assert_eq!(request.metadata().get("authorization").unwrap(), "Bearer s3cret");
+            let mut chunks = request.into_inner();
+            let (tx, rx) = mpsc::channel(4);
+            let (received, fail) = (self.received.clone(), self.fail_first && self.received.lock().unwrap().is_empty());
+            tokio::spawn(async move {
+                let first = chunks.next().await.unwrap().unwrap();
+                let index = {
+                    let mut received = received.lock().unwrap();
+                    received.push((first.config, 0));
+                    received.len() - 1
+                };
+                while let Some(Ok(chunk)) = chunks.next().await {
+                    if fail {
+                        let _ = tx.send(Err(Status::unavailable("restarting"))).await;
+                        return;
+                    }
+                    received.lock().unwrap()[index].1 += chunk.pcm.len() / 2;
+                }
+                let _ = tx.send(Ok(TranscriptResult { text: "merhaba".into(), is_final: true, confidence: 0.9, start_ms: 0, end_ms: 100 })).await;
+            });
+            Ok(Response::new(ReceiverStream::new(rx)))
+        }
+    }
+
+    async fn serve(recognizer: FakeRecognizer) -> AsrConfig {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(Server::builder().add_service(RecognizerServer::new(recognizer)).serve_with_incoming(TcpListenerStream::new(listener)));
+        AsrConfig { enabled: true, endpoint, auth_token: "s3cret".into(), retry_initial_ms: 10, ..AsrConfig::default() }
+    }
+
+    fn stream_config() -> StreamConfig {
+        StreamConfig { session_id: "s1".into(), call_id: "c1".into(), sample_rate: 8000, language: "tr-TR".into(), interim_results: true }
+    }
+
+    #[tokio::test]
+    async fn audio_is_forwarded_and_the_stream_resumes_after_a_failure() {
+        let recognizer = FakeRecognizer { fail_first: true, ..FakeRecognizer::default() };
+        let config = serve(recognizer.clone()).await;
+        let shared = Arc::new(Shared::new(config.buffer_samples(8000)));
+        let task = tokio::spawn(run(config, stream_config(), shared.clone()));
+        shared.push(&[1; 160]);
+        // İlk akış ilk çerçevede kesilir; yeniden kurulan akış yine config ile başlar.
+        while recognizer.received.lock().unwrap().len() < 2 {
+            sleep(Duration::from_millis(5)).await;
+        }
+        for _ in 0..5 {
+            shared.push(&[2; 160]);
+        }
+        shared.buffer.lock().unwrap().closed = true;
+        shared.ready.notify_one();
+        tokio::time::timeout(Duration::from_secs(5), task).await.expect("stream finishes").unwrap();
+
+        let received = recognizer.received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(received.iter().all(|(config, _)| config.as_ref() == Some(&stream_config())));
+        assert_eq!(received[1].1, 5 * 160);
+    }
+
+    #[test]
+    fn a_full_buffer_drops_the_oldest_audio() {
+        let shared = Shared::new(400);
+        for sample in 0..4 {
+            shared.push(&[sample; 160]);
+        }
+        let buffer = shared.buffer.lock().unwrap();
+        assert_eq!(buffer.frames.iter().map(|frame| frame[0]).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(buffer.samples, 320);
+    }
+}
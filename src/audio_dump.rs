@@ -0,0 +1,118 @@
+// Hata ayıklama dökümü: oturumun gelen paketlerden çözdüğü PCM ile kodlanmadan önceki giden PCM
+// iki ayrı WAV dosyasına yazılır; "ses robot gibiydi" şikâyetlerinde sayaçlar yerine tam olarak
+// çözülen ses incelenir. Dosya başına boyut sınırı vardır ve aynı anda en fazla
+// `audio_dump.max_sessions` oturum döker; üretimde açık kalsa bile diski doldurmaz.
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use tokio::sync::mpsc;
+use tracing::{warn, Span};
+
+use crate::config::AudioDumpConfig;
+use crate::error::SessionError;
+
+// Yaklaşık 5 saniyelik 20 ms çerçeve; yazıcı yetişemezse çerçeve düşer.
+const CHANNEL_CAPACITY: usize = 256;
+const WAV_HEADER_LEN: u64 = 44;
+
+// Şu anda döken oturum sayısı.
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+struct Track {
+    path: String,
+    tx: mpsc::Sender<Vec<i16>>,
+}
+
+impl Track {
+    fn start(path: PathBuf, sample_rate: u32, max_bytes: u64) -> Result<Self, SessionError> {
+        let error = |source: io::Error| SessionError::AudioDump { path: path.display().to_string(), source };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(error)?;
+        }
+        let spec = WavSpec { channels: 1, sample_rate, bits_per_sample: 16, sample_format: SampleFormat::Int };
+        let writer = WavWriter::new(BufWriter::new(File::create_new(&path).map_err(error)?), spec).map_err(|e| error(io::Error::other(e)))?;
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let task_path = path.clone();
+        let span = Span::current();
+        tokio::task::spawn_blocking(move || span.in_scope(|| run_writer(writer, rx, &task_path, max_bytes)));
+        Ok(Track { path: path.display().to_string(), tx })
+    }
+}
+
+/// Süren bir döküm. Düşürülünce dosyalar arka planda kapanır ve yer açılır.
+pub struct AudioDump {
+    inbound: Track,
+    outbound: Track,
+}
+
+impl std::fmt::Debug for AudioDump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AudioDump").field("inbound", &self.inbound.path).field("outbound", &self.outbound.path).finish()
+    }
+}
+
+impl AudioDump {
+    /// Yer varsa iki dosyayı açar: `<session_id>_<port>_<unix saniye>_in.wav` ve `_out.wav`.
+    pub fn start(config: &AudioDumpConfig, session_id: &str, port: u16, sample_rate: u32) -> Result<Self, SessionError> {
+        if config.max_sessions == 0 {
+            return Err(SessionError::AudioDumpDisabled);
+        }
+        ACTIVE.fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| (active < config.max_sessions).then_some(active + 1))
+            .map_err(|_| SessionError::AudioDumpLimit { max_sessions: config.max_sessions })?;
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let path = |direction: &str| Path::new(&config.directory).join(format!("{}_{}_{}_{}.wav", session_id, port, started, direction));
+        let tracks = Track::start(path("in"), sample_rate, config.max_file_bytes)
+            .and_then(|inbound| Track::start(path("out"), sample_rate, config.max_file_bytes).map(|outbound| (inbound, outbound)));
+        match tracks {
+            Ok((inbound, outbound)) => Ok(AudioDump { inbound, outbound }),
+            Err(e) => {
+                ACTIVE.fetch_sub(1, Ordering::AcqRel);
+                Err(e)
+            }
+        }
+    }
+
+    /// Gelen paketten çözülen bir çerçeve.
+    pub fn inbound(&self, pcm: &[i16]) {
+        let _ = self.inbound.tx.try_send(pcm.to_vec());
+    }
+
+    /// Kodlanmak üzere olan giden bir çerçeve.
+    pub fn outbound(&self, pcm: &[i16]) {
+        let _ = self.outbound.tx.try_send(pcm.to_vec());
+    }
+
+    /// Gelen ve giden dosyanın yolları, bu sırayla.
+    pub fn paths(&self) -> [String; 2] {
+        [self.inbound.path.clone(), self.outbound.path.clone()]
+    }
+}
+
+impl Drop for AudioDump {
+    fn drop(&mut self) {
+        ACTIVE.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn run_writer(mut writer: WavWriter<BufWriter<File>>, mut rx: mpsc::Receiver<Vec<i16>>, path: &Path, max_bytes: u64) {
+    let mut written = WAV_HEADER_LEN;
+    while let Some(frame) = rx.blocking_recv() {
+        let size = frame.len() as u64 * 2;
+        if written + size > max_bytes {
+            warn!(file = %path.display(), max_bytes, "Ses dökümü boyut sınırına ulaştı, döküm durduruldu");
+            break;
+        }
+        if let Err(e) = frame.iter().try_for_each(|&sample| writer.write_sample(sample)) {
+            warn!(file = %path.display(), error = %e, "Ses dökümüne yazılamadı, döküm durduruldu");
+            break;
+        }
+        written += size;
+    }
+    if let Err(e) = writer.finalize() {
+        warn!(file = %path.display(), error = %e, "Ses dökümü kapatılamadı");
+    }
+}
@@ -0,0 +1,100 @@
+// RFC 6464 istemciden karıştırıcıya ses seviyesi: RTP başlık uzantısında (RFC 8285 tek baytlık
+// biçim) paketin ses seviyesi (-dBov, 0-127) ve ses etkinliği biti taşınır. Uzantı kimliği SDP'de
+// (a=extmap) anlaşılır ve tahsiste verilir. Gelen paketlerde seviye okunup istatistiklere yazılır;
+// giden ses paketlerine kodlanan PCM'den ölçülen seviye eklenir. Uzantıyı anlamayan uçlar onu yok
+// sayar (RFC 3550 5.3.1), bu yüzden başlık her durumda kurala uygun kurulmalıdır.
+use crate::rtp::RtpExtension;
+use crate::vad;
+
+/// RFC 8285 4.2 tek baytlık uzantı biçiminin profil kimliği.
+pub const ONE_BYTE_PROFILE: u16 = 0xBEDE;
+/// Tek baytlık biçimde kullanılabilecek kimlikler; 0 dolgu, 15 ayrılmıştır.
+pub const IDS: std::ops::RangeInclusive<u8> = 1..=14;
+// Bu seviyenin üstündeki çerçeveler ses sayılır; karışımdaki konuşan eşiğiyle (mixer::ACTIVE_RMS)
+// aynıdır.
+const VOICE_DBOV: f64 = -50.0;
+
+/// Bir paketin ses seviyesi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioLevel {
+    /// -dBov; 0 en yüksek, 127 sessizlik.
+    pub level: u8,
+    pub voice: bool,
+}
+
+impl AudioLevel {
+    /// Giden çerçevenin seviyesi.
+    pub fn measure(pcm: &[i16]) -> Self {
+        let dbov = vad::level_dbov(pcm);
+        AudioLevel { level: (-dbov).round() as u8, voice: dbov > VOICE_DBOV }
+    }
+
+    /// Paketin uzantısında `id`'li öğeyi arar. Uzantı yoksa, tek baytlık biçimde değilse ya da
+    /// öğe bulunmazsa `None`.
+    pub fn parse(extension: Option<RtpExtension>, id: u8) -> Option<Self> {
+        let extension = extension.filter(|e| e.profile == ONE_BYTE_PROFILE)?;
+        let mut data = extension.data;
+        while let Some((&header, rest)) = data.split_first() {
+            // Öğeler arasındaki dolgu baytları.
+            if header == 0 {
+                data = rest;
+                continue;
+            }
+            let (element, len) = (header >> 4, (header & 0x0F) as usize + 1);
+            // 15 kimliği uzantının geri kalanının işlenmemesi gerektiğini bildirir.
+            if element == 15 || rest.len() < len {
+                return None;
+            }
+            if element == id {
+                return Some(AudioLevel { level: rest[0] & 0x7F, voice: rest[0] & 0x80 != 0 });
+            }
+            data = &rest[len..];
+        }
+        None
+    }
+
+    /// Seviyeyi `id`'li tek öğe olarak `buf`'a yazar: öğe başlığı, seviye baytı ve uzantıyı 32
+    /// bite tamamlayan iki dolgu baytı.
+    pub fn extension<'a>(&self, id: u8, buf: &'a mut [u8; 4]) -> RtpExtension<'a> {
+        *buf = [id << 4, (self.voice as u8) << 7 | self.level.min(127), 0, 0];
+        RtpExtension { profile: ONE_BYTE_PROFILE, data: buf }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtp::{RtpPacket, RtpPacketRef, MAX_PACKET_LEN};
+
+    #[test]
+    fn level_round_trips_through_a_spec_correct_header() {
+        let level = AudioLevel { level: 42, voice: true };
+        let mut ext = [0u8; 4];
+        let packet = RtpPacket { extension: Some(level.extension(3, &mut ext)), ..RtpPacket::new(0, 1, 160, 0xAB, &[0xFF; 4]) };
+        let mut wire = [0u8; MAX_PACKET_LEN];
+        let len = packet.write(&mut wire).unwrap();
+        // X biti, 0xBEDE profili, 1 kelimelik uzunluk; kimlik 3, L=0, V=1 ve 42.
+        assert_eq!(wire[0], 0x90);
+        assert_eq!(wire[12..20], [0xBE, 0xDE, 0x00, 0x01, 0x30, 0x80 | 42, 0x00, 0x00]);
+        let parsed = RtpPacketRef::parse(&wire[..len]).unwrap();
+        assert_eq!(parsed.payload(), [0xFF; 4]);
+        assert_eq!(AudioLevel::parse(parsed.extension(), 3), Some(level));
+        assert_eq!(AudioLevel::parse(parsed.extension(), 4), None);
+        assert_eq!(AudioLevel::parse(None, 3), None);
+    }
+
+    #[test]
+    fn parse_skips_other_elements_and_padding_and_rejects_other_formats() {
+        let ext = |profile, data| Some(RtpExtension { profile, data });
+        // Kimlik 1'de iki baytlık başka bir öğe, dolgu, sonra kimlik 5'te V=0 seviye 90.
+        let data = [0x11, 0xAA, 0xBB, 0x00, 0x50, 90, 0x00, 0x00];
+        assert_eq!(AudioLevel::parse(ext(ONE_BYTE_PROFILE, &data), 5), Some(AudioLevel { level: 90, voice: false }));
+        // İki baytlık biçim (RFC 8285 4.3), 15 ile kesilen ve taşan uzantılar.
+        assert_eq!(AudioLevel::parse(ext(0x1000, &[0x05, 0x01, 90, 0x00]), 5), None);
+        assert_eq!(AudioLevel::parse(ext(ONE_BYTE_PROFILE, &[0xF0, 0x50, 90, 0x00]), 5), None);
+        assert_eq!(AudioLevel::parse(ext(ONE_BYTE_PROFILE, &[0x13, 0xAA, 0xBB, 0xCC]), 5), None);
+
+        assert_eq!(AudioLevel::measure(&[0; 160]), AudioLevel { level: 127, voice: false });
+        assert_eq!(AudioLevel::measure(&[3000; 160]), AudioLevel { level: 21, voice: true });
+    }
+}
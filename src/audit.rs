@@ -0,0 +1,500 @@
+// Oturum yaşam döngüsü denetim olayları.
+//
+// Olaylar `media::audit` hedefiyle, `event` alanında aşağıdaki isimlerle yazılır. İsimler ve
+// alan setleri SIEM tarafındaki ayrıştırıcılar için sözleşmedir: mevcut alanları yeniden
+// adlandırmayın veya silmeyin, yalnızca yeni alan ekleyin.
+
+/// Bütün denetim olaylarının `tracing` hedefi.
+pub const TARGET: &str = "media::audit";
+
+/// Port tahsis edildi. Alanlar: session_id, call_id, request_id, rtp_port, codec, red_payload_type,
+/// dtmf_payload_type (anlaşılmadıysa yok), silence_suppression, welcome, max_duration_s (sınırsızsa yok),
+/// ssrc (giden akışın), transport (udp, tcp), audio_level_id (anlaşılmadıysa yok), overflow (port
+/// RTP aralığının dışından alındıysa true), tenant (kiracısızsa yok), language (anons dili; verilmediyse yok),
+/// send_only ve symmetric_rtp (yalnızca gönderen oturumda tahsiste verilen hedef; değilse yok), labels
+/// (tahsisteki etiketler, anahtara göre sıralı `anahtar=değer` çiftleri virgülle; etiket yoksa yok),
+/// fax_detection (gelen seste faks tonu aranıyorsa true), amd (telesekreter algılaması istendiyse true),
+/// interface (portun bağlandığı `[[interfaces]]` arayüzü; rtp.host ise yok), asr (gelen ses tanıma
+/// servisine akıtılıyorsa true), dtmf_hook (tuşlar tahsiste verilen adrese bildiriliyorsa true)
+pub const SESSION_ALLOCATED: &str = "session_allocated";
+/// İlk RTP paketi geldi ve uzak adres kilitlendi. Alanlar: remote, wait_ms
+pub const FIRST_PACKET: &str = "first_packet";
+/// Aynı portta gelen akış değişti. Alanlar: previous_remote, remote, previous_ssrc, ssrc (RTP
+/// değilse yok), trigger (new_source | silence_gap | address_changed), gap_ms, welcome_replayed
+pub const STREAM_CHANGED: &str = "stream_changed";
+/// Uzak ucun saat sapması `quality.skew_warning_ppm` eşiğini aştı; tahmin eşiğin altına inip
+/// yeniden aşarsa tekrar yazılır. Alanlar: skew_ppm, threshold_ppm
+pub const CLOCK_SKEW: &str = "clock_skew";
+/// İki yön de akmışken biri kesildi: direction `inbound` ise paket gönderirken
+/// `quality.one_way_inbound_s` boyunca hiç paket gelmedi, `outbound` ise paket gelirken
+/// `quality.one_way_outbound_s` boyunca her gönderim başarısız oldu. Bekletme ve yalnızca gönderen
+/// oturumlarda aranmaz. Alanlar: direction, silent_ms (kesilen yönün son başarılı paketinden beri),
+/// packets_sent, packets_received, send_error (son gönderim hatasının sınıfı; yoksa yok)
+pub const ONE_WAY_AUDIO_SUSPECTED: &str = "one_way_audio_suspected";
+/// Kesilen yön yeniden aktı ya da tek yönlü ses beklenir oldu (ör. bekletme). Alanlar: direction,
+/// duration_ms (yönün kesildiği andan beri)
+pub const ONE_WAY_AUDIO_CLEARED: &str = "one_way_audio_cleared";
+/// Gelen paketler `rate_limit.inbound_packets_per_s` sınırını aştı ve atılmaya başlandı; sınırlama
+/// bir saniye durulmadan yeniden yazılmaz. Alanlar: remote, limit_pps, burst
+pub const INBOUND_FLOOD: &str = "inbound_flood";
+/// Sel `rate_limit.flood_trip_s` boyunca sürdü ve `flood_action` uygulandı. Alanlar: remote,
+/// action (teardown, block), flood_ms
+pub const INBOUND_FLOOD_TRIPPED: &str = "inbound_flood_tripped";
+/// Kendi giden paketlerimiz (aynı SSRC, sıra numarası ve yük) geri geldi; yanlış yapılandırılmış
+/// bir SBC ya da NAT yansıtıyor. Paketler atılır; oturum başına bir kez yazılır. Alanlar: remote
+/// (yansıtan adres), ssrc
+pub const RTP_REFLECTED: &str = "rtp_reflected";
+/// İki oturum köprülendi; her bacağın gelen akışı diğerine aktarılır. Alanlar: rtp_port, peer_port
+pub const SESSIONS_BRIDGED: &str = "sessions_bridged";
+/// Köprü kaldırıldı. Alanlar: rtp_port, peer_port, reason (request | session_ended | rebridged);
+/// rebridged ise rtp_port'un yeni karşı bacağı new_peer_port
+pub const SESSIONS_UNBRIDGED: &str = "sessions_unbridged";
+/// Bir oturuma dinleme bacağı eklendi; bacağa oturumun iki yönünün karışımı gider (bkz. tap.rs).
+/// Alanlar: rtp_port (dinlenen), tap_port, taps (oturumun dinleme bacağı sayısı)
+pub const SESSION_TAPPED: &str = "session_tapped";
+/// Dinleme bitti. Alanlar: rtp_port, tap_port, reason (request | tap_ended | session_ended);
+/// session_ended ise dinleme bacağı da kapatılır
+pub const SESSION_UNTAPPED: &str = "session_untapped";
+/// Oturum ExportSessions ile başka bir node'a taşınmak üzere dışa aktarıldı; oturum bu node'da
+/// sürer. Alanlar: remote (bilinmiyorsa yok), ssrc, next_sequence, next_timestamp,
+/// stopped_playback_id (durdurulan oynatma; yoksa yok), flushed_playbacks, recordings (bitirilen
+/// kaydın dosyaları, virgülle; kayıt yoksa yok)
+pub const SESSION_EXPORTED: &str = "session_exported";
+/// Başka bir node'un dışa aktardığı oturum ImportSessions ile yeni bir portta kuruldu; oturumun
+/// kendi olayları ayrıca yazılır. Alanlar: rtp_port, session_id, previous_port, previous_session_id,
+/// call_id, codec, remote (bilinmiyorsa yok), ssrc, overflow
+pub const SESSION_IMPORTED: &str = "session_imported";
+/// UploadAnnouncement ile bir anons kaydedildi. Alanlar: prompt, file, size_bytes, duration_ms,
+/// sha256
+pub const PROMPT_UPLOADED: &str = "prompt_uploaded";
+/// DeleteAnnouncement ile bir anons silindi. Alanlar: prompt, file
+pub const PROMPT_DELETED: &str = "prompt_deleted";
+/// ReloadAnnouncements ya da yeniden okunan config bir anonsun dosyalarını yeniden doğruladı ve
+/// sesi değişti ya da dosya kullanılamaz oldu. Alanlar: prompt, outcome (changed | missing |
+/// invalid), reason (eksik dosyanın yolu ya da çözülememe sebebi; changed'de yok), trigger (rpc |
+/// config)
+pub const PROMPT_RELOADED: &str = "prompt_reloaded";
+/// Node'un oynatma kabul durumu değişti (bkz. admission.rs); oturuma bağlı değildir. Alanlar: state
+/// (normal | degraded), cause (degraded ise aşılan eşik: cpu | send_lag), cpu_percent (ölçülemiyorsa
+/// yok), send_lag_p95_ms
+pub const ADMISSION_CHANGED: &str = "admission_changed";
+/// Kayıt başladı. Alanlar: file, format (pcm16-wav | ulaw-wav | alaw-wav | raw-ulaw)
+pub const RECORDING_STARTED: &str = "recording_started";
+/// Kayıt bitti. Alanlar: files (virgülle ayrılmış, sırayla), duration_ms, reason (stopped |
+/// disk_quota | write_error), key_id (şifreli kayıtta anahtarın kimliği; değilse yok)
+pub const RECORDING_STOPPED: &str = "recording_stopped";
+/// Biten kayıt nesne deposuna yüklendi. Alanlar: file, bucket, key, size_bytes, attempts, spooled
+/// (spool dizininden yeniden denenerek yüklendiyse true)
+pub const RECORDING_UPLOADED: &str = "recording_uploaded";
+/// Kaydın yüklemesi denemeler tükenince başarısız oldu. Alanlar: file, bucket, key, attempts,
+/// error, status (spooled | failed)
+pub const RECORDING_UPLOAD_FAILED: &str = "recording_upload_failed";
+/// İstenen dilde anons dosyası yok, varsayılan dildeki çalınıyor. Alanlar: prompt, requested,
+/// language (çalınan dosyanın dili; bilinmiyorsa "-")
+pub const PROMPT_LANGUAGE_FALLBACK: &str = "prompt_language_fallback";
+/// Oynatma, çalan bitince başlamak üzere oturumun kuyruğuna alındı. Alanlar: playback_id, prompt,
+/// position (kuyruktaki sırası, 1 ilk sıradır)
+pub const PLAYBACK_QUEUED: &str = "playback_queued";
+/// Anons çalmaya başladı. Alanlar: prompt, file, codec, language, samples (rate uygulanmış), rate,
+/// bed ve bed_gain_db (altına fon müziği karışıyorsa; yoksa yok), playback_id
+pub const PLAYBACK_STARTED: &str = "playback_started";
+/// Anons durdu. Alanlar: prompt, packets, played_ms (kaynaktan okunan ses, rate uygulanmış), reason (completed |
+/// load_error | decode_error | send_error | replaced | session_ended | bridged | resumed | stopped |
+/// flushed | fax_tone), failure (başarısızsa: unknown_prompt | file_missing | bad_format | read_error |
+/// send_error | fetch_error), send_error (failure = send_error ise hatanın sınıfı: unreachable | denied |
+/// no_buffers | other; bkz. `SendFailure`), error (başarısızsa), playback_id (kaynak açılamadan biten
+/// oynatmada yok). Kuyrukta beklerken atılan oynatma yalnızca bu olayı packets = 0 ile yazar.
+pub const PLAYBACK_STOPPED: &str = "playback_stopped";
+/// Node aşırı yüklü olduğu için karşılama anonsu çalınmadı (`admission.skip_welcome`). Alanlar:
+/// prompt, cause (cpu | send_lag)
+pub const WELCOME_SKIPPED: &str = "welcome_skipped";
+/// AllocatePort(fax_detection) açılan oturumun gelen sesinde faks tonu algılandı; her ton oturum
+/// başına bir kez yazılır. Alanlar: tone (cng | ced | ced_am), offset_ms (tahsisten beri),
+/// duration_ms (tonun algılanana kadar süren kısmı), playback_stopped (`fax_detection.stop_playback`
+/// ile çalan anons durdurulduysa true)
+pub const FAX_TONE_DETECTED: &str = "fax_tone_detected";
+/// AllocatePort(amd) açılan oturumun telesekreter algılaması bitti; oturum başına bir kez yazılır.
+/// Alanlar: result (human | machine | not_sure), reason (initial_silence | long_greeting | max_words |
+/// silence_after_greeting | window_elapsed | session_ended), initial_silence_ms, greeting_ms, words,
+/// silence_after_greeting_ms, analysis_ms (bkz. amd.rs; eşikler `[amd]`'den ayarlanır)
+pub const AMD_RESULT: &str = "amd_result";
+/// Oturumun giden yol bozulması AllocatePort(impairment) ya da SetImpairment ile değişti (bkz.
+/// impairment.rs). Alanlar: loss_pct, jitter_ms, reorder_pct, duplicate_pct (hepsi 0 ise kapatıldı)
+pub const IMPAIRMENT_CHANGED: &str = "impairment_changed";
+/// Oturumun susturması SetMute ile ya da köprü kaldırılınca değişti. Alanlar: inbound, outbound
+/// (yeni durum), reason (request | unbridged)
+pub const MUTE_CHANGED: &str = "mute_changed";
+/// Oturumun medya yönü SetDirection ile değişti (ör. re-INVITE ile bekletme). Alanlar: direction,
+/// previous (sendrecv | sendonly | recvonly | inactive)
+pub const DIRECTION_CHANGED: &str = "direction_changed";
+/// Tanıma servisinden bir sonuç geldi (bkz. asr.rs). Alanlar: text, is_final (false ise aynı parça için
+/// sonra yenisi gelecek ara sonuç), confidence, start_ms, end_ms (akışın başından itibaren)
+pub const TRANSCRIPT: &str = "transcript";
+/// Oturum en uzun süresine yaklaşıyor; sinyalleşme kapanıştan önce davranabilir. Alanlar:
+/// remaining_s, max_duration_s
+pub const SESSION_EXPIRING: &str = "session_expiring";
+/// Oturumun son satırı, her oturum için tam olarak bir kez yazılır. Alanlar: request_id, duration_ms,
+/// first_packet_ms (paket hiç gelmediyse yok), packets_sent, bytes_sent, packets_received,
+/// bytes_received, packets_malformed, packets_lost, packets_duplicated, sequence_gaps, jitter_ms,
+/// clock_skew_ppm (tahmin yoksa yok), r_factor, mos (paket gelmediyse yok), red_recovered,
+/// packets_flood_dropped, packets_reflected, announcements_played, announcements_failed, playback_failure (son başarısız anonsun sebebi;
+/// yoksa yok), dtmf_digits (gelen RFC 4733 tuşları), send_bitrate_avg_bps, send_bitrate_peak_bps,
+/// receive_bitrate_avg_bps, receive_bitrate_peak_bps (IP/UDP başlıkları dahil hat hızı; ortalama
+/// oturum süresine göre, tepe 5 saniyelik kayan ortalamanın en yükseği), recordings (virgülle ayrılmış dosyalar),
+/// audio_dumps (StartAudioDump'ın gelen ve giden dosyaları; açılmadıysa boş), codecs,
+/// teardown_reason, tenant (kiracısızsa yok), recording_key_id (kayıt şifrelendiyse anahtarın kimliği),
+/// labels (session_allocated'daki gibi; etiket yoksa boş), fax_tone (ilk algılanan faks tonu; yoksa yok),
+/// amd_result (telesekreter algılaması istendiyse sonucu), send_error (son soket gönderim hatasının sınıfı;
+/// hata olmadıysa yok), one_way_audio (ilk şüphelenilen tek yönlü sesin yönü; olmadıysa yok),
+/// one_way_audio_ms (şüphenin toplam süresi)
+pub const SESSION_SUMMARY: &str = "session_summary";
+
+/// `session_summary.teardown_reason` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TeardownReason {
+    FirstPacketTimeout,
+    MediaTimeout,
+    Shutdown,
+    /// Oturum en uzun süresine ulaştı (`timers.max_session_duration_s` ya da tahsisteki değer).
+    MaxDuration,
+    /// Soket kalıcı bir hata döndü (ör. kapatılmış tanımlayıcı); oturum medya alamaz.
+    SocketError,
+    /// Gelen paket seli `rate_limit.flood_trip_s` boyunca sürdü (`flood_action = "teardown"`).
+    InboundFlood,
+    /// RFC 4571 TCP bağlantısını uzak uç kapattı ya da bağlantı koptu.
+    PeerDisconnected,
+    /// Gönderim uzak ucun ulaşılamaz olduğunu bildirdi (ICMP port/host unreachable, bağlantı sıfırlandı).
+    RemoteUnreachable,
+    /// Dinleme bacağının dinlediği oturum kapandı.
+    TappedSessionEnded,
+    /// Çağrı kontrolü portu ReleasePort ile bıraktı.
+    Released,
+}
+
+impl TeardownReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TeardownReason::FirstPacketTimeout => "first_packet_timeout",
+            TeardownReason::MediaTimeout => "media_timeout",
+            TeardownReason::Shutdown => "shutdown",
+            TeardownReason::MaxDuration => "max_duration",
+            TeardownReason::SocketError => "socket_error",
+            TeardownReason::InboundFlood => "inbound_flood",
+            TeardownReason::PeerDisconnected => "peer_disconnected",
+            TeardownReason::RemoteUnreachable => "remote_unreachable",
+            TeardownReason::TappedSessionEnded => "tapped_session_ended",
+            TeardownReason::Released => "released",
+        }
+    }
+}
+
+/// `playback_stopped.reason` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackStopReason {
+    Completed,
+    LoadError,
+    /// Kaynak çalarken okunamadı (ör. kesik ya da bozuk WAV); hataya kadarki ses çalındı.
+    DecodeError,
+    SendError,
+    /// Aynı oturumda başka bir kaynak kuruldu.
+    Replaced,
+    /// Oturum çalma bitmeden kapandı.
+    SessionEnded,
+    /// Köprüden ayrılan bacağın dolgusu; bacak yeniden köprülendi.
+    Bridged,
+    /// Bekletilen (sendonly) oturumun bekleme sesi; oturumun yönü değişti.
+    Resumed,
+    /// StopPlayback ile durduruldu.
+    Stopped,
+    /// Başlamadan kuyruktan atıldı (StopPlayback flush).
+    Flushed,
+    /// Gelen seste faks tonu algılandı (`fax_detection.stop_playback`); kuyruk da boşaltılır.
+    FaxTone,
+}
+
+impl PlaybackStopReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlaybackStopReason::Completed => "completed",
+            PlaybackStopReason::LoadError => "load_error",
+            PlaybackStopReason::DecodeError => "decode_error",
+            PlaybackStopReason::SendError => "send_error",
+            PlaybackStopReason::Replaced => "replaced",
+            PlaybackStopReason::SessionEnded => "session_ended",
+            PlaybackStopReason::Bridged => "bridged",
+            PlaybackStopReason::Resumed => "resumed",
+            PlaybackStopReason::Stopped => "stopped",
+            PlaybackStopReason::Flushed => "flushed",
+            PlaybackStopReason::FaxTone => "fax_tone",
+        }
+    }
+}
+
+/// `fax_tone_detected.tone` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaxTone {
+    /// Arayan faksın 1100 Hz çağrı tonu.
+    Cng,
+    /// Cevaplayan faksın 2100 Hz cevap tonu.
+    Ced,
+    /// Yankı gidericileri kapatan, 15 Hz ile genlik modülasyonlu CED (V.8 ANSam).
+    CedAm,
+}
+
+impl FaxTone {
+    pub const ALL: [FaxTone; 3] = [FaxTone::Cng, FaxTone::Ced, FaxTone::CedAm];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FaxTone::Cng => "cng",
+            FaxTone::Ced => "ced",
+            FaxTone::CedAm => "ced_am",
+        }
+    }
+}
+
+/// `one_way_audio_suspected.direction` değerleri: kesilen yön.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OneWayDirection {
+    /// Uzak uçtan ses gelmiyor.
+    Inbound,
+    /// Uzak uca ses gitmiyor.
+    Outbound,
+}
+
+impl OneWayDirection {
+    pub const ALL: [OneWayDirection; 2] = [OneWayDirection::Inbound, OneWayDirection::Outbound];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OneWayDirection::Inbound => "inbound",
+            OneWayDirection::Outbound => "outbound",
+        }
+    }
+}
+
+/// `amd_result.result` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmdVerdict {
+    Human,
+    /// Telesekreter ya da sesli mesaj.
+    Machine,
+    NotSure,
+}
+
+impl AmdVerdict {
+    pub const ALL: [AmdVerdict; 3] = [AmdVerdict::Human, AmdVerdict::Machine, AmdVerdict::NotSure];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AmdVerdict::Human => "human",
+            AmdVerdict::Machine => "machine",
+            AmdVerdict::NotSure => "not_sure",
+        }
+    }
+}
+
+/// `amd_result.reason` değerleri: sonucu belirleyen eşik.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmdReason {
+    /// İlk kelimeden önceki sessizlik `amd.initial_silence_ms`'i aştı.
+    InitialSilence,
+    /// Karşılama `amd.greeting_ms`'ten uzun sürdü.
+    LongGreeting,
+    /// Kelime sayısı `amd.max_words`'e ulaştı.
+    MaxWords,
+    /// Karşılamadan sonra `amd.after_greeting_silence_ms` sessizlik.
+    SilenceAfterGreeting,
+    /// `amd.window_ms` içinde hiçbir eşik aşılmadı.
+    WindowElapsed,
+    /// Oturum analiz bitmeden kapandı.
+    SessionEnded,
+}
+
+impl AmdReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AmdReason::InitialSilence => "initial_silence",
+            AmdReason::LongGreeting => "long_greeting",
+            AmdReason::MaxWords => "max_words",
+            AmdReason::SilenceAfterGreeting => "silence_after_greeting",
+            AmdReason::WindowElapsed => "window_elapsed",
+            AmdReason::SessionEnded => "session_ended",
+        }
+    }
+}
+
+/// `recording_stopped.reason` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingStopReason {
+    /// StopRecording ya da oturumun kapanması.
+    Stopped,
+    /// Kayıt dizini `recording.max_total_bytes` sınırına ulaştı.
+    DiskQuota,
+    WriteError,
+}
+
+impl RecordingStopReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RecordingStopReason::Stopped => "stopped",
+            RecordingStopReason::DiskQuota => "disk_quota",
+            RecordingStopReason::WriteError => "write_error",
+        }
+    }
+}
+
+/// Kayıt yüklemesinin sonucu; `recording_upload_failed.status` ve kanca bildirimindeki
+/// `uploads[].status` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingUploadStatus {
+    Uploaded,
+    /// Denemeler tükendi; kayıt spool dizinine taşındı ve orada yeniden denenecek.
+    Spooled,
+    /// Denemeler tükendi ve spool dizini yok ya da kayıt oraya taşınamadı.
+    Failed,
+}
+
+impl RecordingUploadStatus {
+    pub const ALL: [RecordingUploadStatus; 3] = [RecordingUploadStatus::Uploaded, RecordingUploadStatus::Spooled, RecordingUploadStatus::Failed];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RecordingUploadStatus::Uploaded => "uploaded",
+            RecordingUploadStatus::Spooled => "spooled",
+            RecordingUploadStatus::Failed => "failed",
+        }
+    }
+}
+
+/// `sessions_unbridged.reason` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnbridgeReason {
+    Request,
+    /// Bacaklardan biri kapandı.
+    SessionEnded,
+    /// Bacaklardan biri başka bir oturumla köprülendi.
+    Rebridged,
+}
+
+impl UnbridgeReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnbridgeReason::Request => "request",
+            UnbridgeReason::SessionEnded => "session_ended",
+            UnbridgeReason::Rebridged => "rebridged",
+        }
+    }
+}
+
+/// `session_untapped.reason` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapEndReason {
+    Request,
+    /// Dinleme bacağı kapandı.
+    TapEnded,
+    /// Dinlenen oturum kapandı.
+    SessionEnded,
+}
+
+impl TapEndReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TapEndReason::Request => "request",
+            TapEndReason::TapEnded => "tap_ended",
+            TapEndReason::SessionEnded => "session_ended",
+        }
+    }
+}
+
+/// `mute_changed.reason` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MuteReason {
+    Request,
+    /// Köprü kaldırıldı ya da bacak başka bir oturumla köprülendi.
+    Unbridged,
+}
+
+impl MuteReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MuteReason::Request => "request",
+            MuteReason::Unbridged => "unbridged",
+        }
+    }
+}
+
+/// `playback_stopped.failure` ve oturum özetindeki `playback_failure` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackFailure {
+    UnknownPrompt,
+    /// Anons dosyası yok (silinmiş ya da yol yanlış).
+    FileMissing,
+    /// WAV açılamadı ya da 16 bit 8000 Hz mono değil.
+    BadFormat,
+    ReadError,
+    SendError,
+    /// URL'li anons indirilemedi ve önbellekte kopyası yok.
+    FetchError,
+}
+
+impl PlaybackFailure {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlaybackFailure::UnknownPrompt => "unknown_prompt",
+            PlaybackFailure::FileMissing => "file_missing",
+            PlaybackFailure::BadFormat => "bad_format",
+            PlaybackFailure::ReadError => "read_error",
+            PlaybackFailure::SendError => "send_error",
+            PlaybackFailure::FetchError => "fetch_error",
+        }
+    }
+}
+
+/// Soket gönderim hatasının sınıfı: `playback_stopped.send_error`, oturum özetindeki `send_error` ve
+/// `media_send_errors_total`'un `class` etiketi. Sınıf hatanın nasıl ele alınacağını belirler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendFailure {
+    /// Uzak uç yok (ICMP unreachable, ECONNREFUSED, EHOSTUNREACH, kopan TCP); oturum kapatılır.
+    Unreachable,
+    /// Yerel güvenlik duvarı reddetti (EPERM, EACCES); çalma durur, operatör uyarılır.
+    Denied,
+    /// Gönderim tamponu dolu (ENOBUFS, EAGAIN); kısa aralıklarla yeniden denenir, olmazsa paket düşer.
+    NoBuffers,
+    /// Diğerleri; çalma durur.
+    Other,
+}
+
+impl SendFailure {
+    pub const ALL: [SendFailure; 4] = [SendFailure::Unreachable, SendFailure::Denied, SendFailure::NoBuffers, SendFailure::Other];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SendFailure::Unreachable => "unreachable",
+            SendFailure::Denied => "denied",
+            SendFailure::NoBuffers => "no_buffers",
+            SendFailure::Other => "other",
+        }
+    }
+}
+
+/// `stream_changed.trigger` değerleri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamChangeTrigger {
+    /// Başka bir kaynaktan (adres veya SSRC) ardışık paketler geldi.
+    NewSource,
+    /// Yapılandırılan sessizlik süresinden sonra paket geldi.
+    SilenceGap,
+    /// Aynı SSRC yeni bir adresten geldi (NAT yeniden bağlama); akış aynı sayılır.
+    AddressChanged,
+}
+
+impl StreamChangeTrigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StreamChangeTrigger::NewSource => "new_source",
+            StreamChangeTrigger::SilenceGap => "silence_gap",
+            StreamChangeTrigger::AddressChanged => "address_changed",
+        }
+    }
+}
@@ -0,0 +1,519 @@
+// İki oturumun köprülenmesi: bir bacağın kilitli akışından gelen RTP, karşı bacağın giden
+// akışına (SSRC, sıra numarası, zaman damgası) çevrilerek gönderilir. Codec'ler farklıysa ses
+// çevrilir. RFC 4733 telephone-event paketleri karşı bacağın anlaştığı yük tipine taşınır ya da
+// orada yeniden üretilir (`bridge.dtmf`). Aktarım kaynak bacağın dinleyici görevinde yapılır.
+use std::net::SocketAddr;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::audit::{self, MuteReason, UnbridgeReason};
+use crate::config::DtmfMode;
+use crate::error::SessionError;
+use crate::metrics;
+use crate::rtp::{RtpPacket, RtpPacketRef, COMFORT_NOISE_PT, MAX_PACKET_LEN};
+use crate::session::RtpSession;
+use crate::tap::{self, Direction};
+use crate::vad::Frame;
+
+/// RFC 4733 olay yükünün uzunluğu.
+pub const TELEPHONE_EVENT_LEN: usize = 4;
+/// Olay bitişi kayba karşı bu kadar kez gönderilir (RFC 4733 2.5.1.4).
+pub const END_REPEATS: usize = 3;
+/// Bu kadar süre güncellenmeyen olayın bitiş paketleri kaybolmuş sayılır; karşı bacakta bitirilir.
+const EVENT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// RFC 4733 telephone-event yükü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TelephoneEvent {
+    pub event: u8,
+    pub end: bool,
+    pub volume: u8,
+    /// Olayın başından beri geçen süre, örnek cinsinden.
+    pub duration: u16,
+}
+
+impl TelephoneEvent {
+    /// Yükün ilk dört baytını çözer; daha kısa yük olay değildir.
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        let &[event, flags, hi, lo] = payload.get(..TELEPHONE_EVENT_LEN)? else { return None };
+        Some(TelephoneEvent { event, end: flags & 0x80 != 0, volume: flags & 0x3F, duration: u16::from_be_bytes([hi, lo]) })
+    }
+
+    /// 0-15 olaylarının tuşu (RFC 4733 3.2); flash ve tonlar tuş değildir.
+    pub fn digit(self) -> Option<char> {
+        b"0123456789*#ABCD".get(self.event as usize).map(|&digit| digit as char)
+    }
+
+    pub fn to_bytes(self) -> [u8; TELEPHONE_EVENT_LEN] {
+        let [hi, lo] = self.duration.to_be_bytes();
+        [self.event, (self.end as u8) << 7 | (self.volume & 0x3F), hi, lo]
+    }
+}
+
+// Karşı bacağa aktarılmakta olan olay.
+#[derive(Debug)]
+struct Forwarded {
+    // Gelen olayın zaman damgası; aynı olayın bütün paketlerinde aynıdır.
+    source_timestamp: u32,
+    // Olayın karşı akıştaki zaman damgası.
+    timestamp: u32,
+    started: Instant,
+    last_update: Instant,
+    // Karşı bacağa son gönderilen yük; bitiş gönderildiyse `end` taşır.
+    sent: TelephoneEvent,
+}
+
+/// Köprünün bir yönü: bu bacaktan gelenleri `peer`'a aktarır. Kaynak oturumda tutulur.
+#[derive(Debug)]
+pub struct Relay {
+    peer: Weak<RtpSession>,
+    mode: DtmfMode,
+    bridged_at: Instant,
+    event: Option<Forwarded>,
+    // Köprüden önce başlamış olayın zaman damgası; bitene kadar aktarılmaz.
+    ignored: Option<u32>,
+    pcm: Vec<i16>,
+    payload: Vec<u8>,
+    wire: [u8; MAX_PACKET_LEN],
+}
+
+impl Relay {
+    fn new(peer: &Arc<RtpSession>, mode: DtmfMode, now: Instant) -> Self {
+        Relay {
+            peer: Arc::downgrade(peer), mode, bridged_at: now, event: None, ignored: None,
+            pcm: Vec::new(), payload: Vec::new(), wire: [0; MAX_PACKET_LEN],
+        }
+    }
+
+    pub fn peer(&self) -> Option<Arc<RtpSession>> {
+        self.peer.upgrade()
+    }
+
+    fn audio(&mut self, source: &RtpSession, peer: &RtpSession, target: SocketAddr, payload: &[u8], now: Instant) {
+        self.pcm.clear();
+        source.codec().decode(payload, &mut self.pcm);
+        // Kaynağın gelen ya da karşının giden yönü susturulduysa karşıya sessizlik gider.
+        let muted = source.inbound_muted() || peer.outbound_muted();
+        if muted {
+            self.pcm.fill(0);
+        }
+        tap::feed(peer, Direction::Outbound, &self.pcm);
+        let marker = match peer.suppress(&self.pcm, false, now) {
+            Frame::Send { marker } => marker,
+            Frame::Suppress => return,
+            Frame::ComfortNoise { level } => {
+                let (sequence, timestamp) = peer.stream.next(now, 0);
+                let level = [level];
+                let packet = RtpPacket::new(COMFORT_NOISE_PT, sequence, timestamp, peer.stream.ssrc, &level);
+                return send(peer, target, &mut self.wire, &packet);
+            }
+        };
+        let payload = if source.codec() == peer.codec() && !muted {
+            payload
+        } else {
+            self.payload.clear();
+            peer.codec().encode(&self.pcm, &mut self.payload);
+            &self.payload
+        };
+        let increment = peer.codec().timestamp_increment(source.codec().frame_duration(self.pcm.len()));
+        let (sequence, timestamp) = peer.stream.next(now, increment);
+        let packet = RtpPacket { marker, ..RtpPacket::new(peer.codec().payload_type(), sequence, timestamp, peer.stream.ssrc, payload) };
+        send(peer, target, &mut self.wire, &packet);
+    }
+
+    fn event(&mut self, peer: &RtpSession, target: SocketAddr, packet: &RtpPacketRef, now: Instant) {
+        let Some(event) = TelephoneEvent::parse(packet.payload()) else { return };
+        let Some(payload_type) = peer.dtmf_payload_type() else {
+            debug!(parent: &peer.span, event = event.event, "Karşı bacakta telephone-event anlaşılmadı, tuş aktarılmadı");
+            return;
+        };
+        let source_timestamp = packet.timestamp();
+        if self.ignored == Some(source_timestamp) {
+            return;
+        }
+
+        let Some(forwarded) = self.event.as_mut().filter(|f| f.source_timestamp == source_timestamp) else {
+            // Yeni olay; önceki bitmediyse önce o bitirilir.
+            self.end_event(peer, target);
+            // Köprüden önce başlayan olay karşıda başsız ve belki hiç bitmeyen bir olay olurdu.
+            let age = Duration::from_secs(event.duration as u64) / peer.codec().clock_rate();
+            if !packet.marker() && now.saturating_duration_since(self.bridged_at) < age {
+                self.ignored = Some(source_timestamp);
+                return;
+            }
+            let (sequence, timestamp) = peer.stream.next(now, 0);
+            let sent = match self.mode {
+                DtmfMode::Relay => event,
+                DtmfMode::Regenerate => TelephoneEvent { duration: 0, ..event },
+            };
+            self.event = Some(Forwarded { source_timestamp, timestamp, started: now, last_update: now, sent });
+            metrics::get().dtmf_events_relayed.inc();
+            debug!(parent: &peer.span, event = event.event, "Tuş karşı bacağa aktarılıyor");
+            let payload = sent.to_bytes();
+            let packet = RtpPacket { marker: true, ..RtpPacket::new(payload_type, sequence, timestamp, peer.stream.ssrc, &payload) };
+            send(peer, target, &mut self.wire, &packet);
+            if self.mode == DtmfMode::Regenerate && event.end {
+                self.end_event(peer, target);
+            }
+            return;
+        };
+
+        forwarded.last_update = now;
+        match self.mode {
+            // Bitiş tekrarları da dahil, gelen her güncelleme karşıya gider.
+            DtmfMode::Relay => forwarded.sent = event,
+            // Bitiş bir kez, kendi tekrarlarımızla gönderilir; gelen tekrarlar yok sayılır.
+            DtmfMode::Regenerate if forwarded.sent.end => return,
+            DtmfMode::Regenerate if event.end => return self.end_event(peer, target),
+            DtmfMode::Regenerate => forwarded.sent.duration = elapsed_samples(forwarded.started, now, peer),
+        }
+        let payload = forwarded.sent.to_bytes();
+        let packet = RtpPacket::new(payload_type, peer.stream.next_sequence(), forwarded.timestamp, peer.stream.ssrc, &payload);
+        send(peer, target, &mut self.wire, &packet);
+    }
+
+    /// Karşıda süren olayı bitiş paketleriyle kapatır.
+    fn end_event(&mut self, peer: &RtpSession, target: SocketAddr) {
+        let (Some(forwarded), Some(payload_type)) = (self.event.as_mut(), peer.dtmf_payload_type()) else { return };
+        if forwarded.sent.end {
+            return;
+        }
+        if self.mode == DtmfMode::Regenerate {
+            forwarded.sent.duration = elapsed_samples(forwarded.started, Instant::now(), peer);
+        }
+        forwarded.sent.end = true;
+        let payload = forwarded.sent.to_bytes();
+        for _ in 0..END_REPEATS {
+            let packet = RtpPacket::new(payload_type, peer.stream.next_sequence(), forwarded.timestamp, peer.stream.ssrc, &payload);
+            send(peer, target, &mut self.wire, &packet);
+        }
+    }
+
+    /// Bitiş paketleri kaybolan olayı karşı bacakta bitirir.
+    fn expire(&mut self, peer: &RtpSession, target: SocketAddr, now: Instant) {
+        if self.event.as_ref().is_some_and(|f| !f.sent.end && now - f.last_update >= EVENT_TIMEOUT) {
+            self.end_event(peer, target);
+        }
+    }
+}
+
+fn elapsed_samples(started: Instant, now: Instant, peer: &RtpSession) -> u16 {
+    let samples = now.saturating_duration_since(started).as_micros() * peer.codec().clock_rate() as u128 / 1_000_000;
+    samples.min(u16::MAX as u128) as u16
+}
+
+// Dinleyici görevi karşı bacağın soketini beklemesin diye bloklamadan gönderilir; tampon doluysa
+// paket düşer.
+fn send(peer: &RtpSession, target: SocketAddr, wire: &mut [u8; MAX_PACKET_LEN], packet: &RtpPacket) {
+    let Ok(len) = packet.write(wire) else { return };
+    match peer.try_send_to(&wire[..len], target) {
+        Ok(_) => {
+            peer.mark_sent(target, &wire[..len]);
+            peer.capture_sent(target, &wire[..len]);
+            metrics::get().bridge_packets_relayed.inc();
+        }
+        Err(e) => debug!(parent: &peer.span, target = %target, error = %e, "Köprü paketi gönderilemedi"),
+    }
+}
+
+/// İki oturumu köprüler. Aynı bacağın aynı anda iki köprüye girmemesi için çağıran
+/// `ActiveSessions` kilidini tutar. Bacaklardan biri başka bir oturumla köprülüyse o köprü aynı
+/// adımda kaldırılır; bacağın kilitli adresi, SSRC'si ve sayaçları değişmez. Köprüsü kopan eski
+/// karşı bacaklar döner.
+pub fn bridge(a: &Arc<RtpSession>, b: &Arc<RtpSession>, mode: DtmfMode) -> Result<Vec<Arc<RtpSession>>, SessionError> {
+    if a.port == b.port {
+        return Err(SessionError::SelfBridge { port: a.port });
+    }
+    if let Some(leg) = [a, b].into_iter().find(|leg| leg.tap_source().is_some()) {
+        return Err(SessionError::TapLeg { port: leg.port });
+    }
+    if let Some(peer) = a.bridged_peer().filter(|peer| peer.port == b.port) {
+        return Err(SessionError::AlreadyBridged { port: a.port, peer: peer.port });
+    }
+    let mut detached = Vec::new();
+    for (leg, new_peer) in [(a, b), (b, a)] {
+        if let Some(old) = detach(leg) {
+            info!(
+                target: audit::TARGET, event = audit::SESSIONS_UNBRIDGED, rtp_port = leg.port, peer_port = old.port,
+                reason = UnbridgeReason::Rebridged.as_str(), new_peer_port = new_peer.port,
+            );
+            detached.push(old);
+        }
+    }
+    let now = Instant::now();
+    *a.bridge.lock().unwrap() = Some(Relay::new(b, mode, now));
+    *b.bridge.lock().unwrap() = Some(Relay::new(a, mode, now));
+    info!(target: audit::TARGET, event = audit::SESSIONS_BRIDGED, rtp_port = a.port, peer_port = b.port);
+    Ok(detached)
+}
+
+/// Köprüyü iki yönde de kaldırır; iki bacakta da süren olaylar bitirilir. Karşı bacağın portunu
+/// döner; oturum köprülü değilse `None`.
+pub fn unbridge(session: &RtpSession, reason: UnbridgeReason) -> Option<u16> {
+    let peer = detach(session)?;
+    info!(target: audit::TARGET, event = audit::SESSIONS_UNBRIDGED, rtp_port = session.port, peer_port = peer.port, reason = reason.as_str());
+    Some(peer.port)
+}
+
+// Köprüyü kaydetmeden kaldırır, iki bacağın susturmasını kaldırır ve hâlâ açık olan karşı bacağı
+// döner.
+fn detach(session: &RtpSession) -> Option<Arc<RtpSession>> {
+    let mut forward = session.bridge.lock().unwrap().take()?;
+    session.set_mute(false, false, MuteReason::Unbridged);
+    let peer = forward.peer()?;
+    close(&mut forward, &peer);
+    let backward = peer.bridge.lock().unwrap().take();
+    if let Some(mut backward) = backward {
+        close(&mut backward, session);
+    }
+    peer.set_mute(false, false, MuteReason::Unbridged);
+    Some(peer)
+}
+
+fn close(relay: &mut Relay, peer: &RtpSession) {
+    if let Some(target) = *peer.remote_addr.lock().unwrap() {
+        relay.end_event(peer, target);
+    }
+}
+
+/// Kilitli akıştan gelen paketi köprünün karşı bacağına aktarır; köprü yoksa bir şey yapmaz.
+pub fn forward(source: &RtpSession, packet: &RtpPacketRef, now: Instant) {
+    let mut slot = source.bridge.lock().unwrap();
+    let Some(relay) = slot.as_mut() else { return };
+    let Some(peer) = relay.peer() else {
+        *slot = None;
+        return;
+    };
+    // Gelen sesi beklemeyen bacaktan ya da göndermeyen karşı bacağa (SetDirection) ses ve tuş gitmez.
+    if !source.direction().receives() || !peer.direction().sends() {
+        return;
+    }
+    // Karşı bacak henüz paket göndermediyse adresi bilinmiyor.
+    let Some(target) = *peer.remote_addr.lock().unwrap() else { return };
+    relay.expire(&peer, target, now);
+
+    let payload_type = packet.payload_type();
+    if source.dtmf_payload_type() == Some(payload_type) {
+        relay.event(&peer, target, packet, now);
+    } else if let Some(audio) = source.audio_payload(packet) {
+        relay.audio(source, &peer, target, audio, now);
+    }
+    // Konfor gürültüsü ve anlaşılmamış yük tipleri aktarılmaz.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::UdpSocket;
+
+    use crate::codec::{Codec, Pcma, Pcmu};
+
+    const CALLER_DTMF: u8 = 101;
+    const CALLEE_DTMF: u8 = 96;
+
+    /// Köprülü iki bacak; `callee`'nin uzak ucu kilitlidir ve aktarılanları `far` alır.
+    async fn legs(callee_codec: &'static dyn Codec, mode: DtmfMode) -> (Arc<RtpSession>, Arc<RtpSession>, UdpSocket) {
+        let leg = |codec: &'static dyn Codec, dtmf: u8| async move {
+            let sock = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let port = sock.local_addr().unwrap().port();
+            Arc::new(RtpSession::new(port, codec, sock, "test-call").with_dtmf(dtmf))
+        };
+        let caller = leg(&Pcmu, CALLER_DTMF).await;
+        let callee = leg(callee_codec, CALLEE_DTMF).await;
+        let far = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        *callee.remote_addr.lock().unwrap() = Some(far.local_addr().unwrap());
+        bridge(&caller, &callee, mode).unwrap();
+        // Reaktör soketlerin yazılabilir olduğunu görsün; aktarım bloklamadan gönderir.
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        (caller, callee, far)
+    }
+
+    fn wire(payload_type: u8, marker: bool, sequence: u16, timestamp: u32, payload: &[u8]) -> Vec<u8> {
+        let mut buf = [0u8; MAX_PACKET_LEN];
+        let packet = RtpPacket { marker, ..RtpPacket::new(payload_type, sequence, timestamp, 0xCAFE, payload) };
+        let len = packet.write(&mut buf).unwrap();
+        buf[..len].to_vec()
+    }
+
+    fn digit(event: u8, end: bool, duration: u16) -> [u8; 4] {
+        TelephoneEvent { event, end, volume: 10, duration }.to_bytes()
+    }
+
+    /// `far`'a ulaşmış paketler: (yük tipi, işaret, sıra, zaman damgası, yük).
+    async fn drain(far: &UdpSocket) -> Vec<(u8, bool, u16, u32, Vec<u8>)> {
+        let mut buf = [0u8; MAX_PACKET_LEN];
+        let mut packets = Vec::new();
+        while let Ok(Ok((len, _))) = tokio::time::timeout(Duration::from_millis(100), far.recv_from(&mut buf)).await {
+            let p = RtpPacketRef::parse(&buf[..len]).unwrap();
+            packets.push((p.payload_type(), p.marker(), p.sequence(), p.timestamp(), p.payload().to_vec()));
+        }
+        packets
+    }
+
+    async fn relay(caller: &RtpSession, packets: &[Vec<u8>]) {
+        for bytes in packets {
+            forward(caller, &RtpPacketRef::parse(bytes).unwrap(), Instant::now());
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    #[test]
+    fn telephone_event_round_trips() {
+        let event = TelephoneEvent { event: 11, end: true, volume: 63, duration: 0x1234 };
+        assert_eq!(event.to_bytes(), [11, 0xBF, 0x12, 0x34]);
+        assert_eq!(TelephoneEvent::parse(&event.to_bytes()), Some(event));
+        assert_eq!(TelephoneEvent::parse(&[1, 2, 3]), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn digit_is_relayed_with_the_peer_payload_type_between_audio() {
+        let (caller, callee, far) = legs(&Pcmu, DtmfMode::Relay).await;
+        let audio = [0x55; 160];
+        relay(&caller, &[
+            wire(0, false, 10, 1600, &audio),
+            wire(CALLER_DTMF, true, 11, 1760, &digit(5, false, 160)),
+            wire(0, false, 12, 1760, &audio),
+            wire(CALLER_DTMF, false, 13, 1760, &digit(5, false, 480)),
+            wire(CALLER_DTMF, false, 14, 1760, &digit(5, true, 640)),
+            wire(CALLER_DTMF, false, 15, 1760, &digit(5, true, 640)),
+            wire(0, false, 16, 2080, &audio),
+        ]).await;
+
+        let packets = drain(&far).await;
+        assert_eq!(packets.len(), 7);
+        for pair in packets.windows(2) {
+            assert_eq!(pair[1].2, pair[0].2.wrapping_add(1), "one sequence space on the far leg");
+        }
+        let events: Vec<_> = packets.iter().filter(|p| p.0 == CALLEE_DTMF).collect();
+        assert_eq!(events.len(), 4);
+        assert!(events[0].1, "first event packet carries the marker");
+        assert!(events.iter().all(|p| p.3 == events[0].3), "event packets share the start timestamp");
+        assert_eq!(events[3].4, digit(5, true, 640));
+        let audio: Vec<_> = packets.iter().filter(|p| p.0 == 0).collect();
+        assert_eq!(audio.len(), 3);
+        for pair in audio.windows(2) {
+            assert!(pair[1].3.wrapping_sub(pair[0].3) as i32 > 0, "audio keeps advancing during the digit");
+        }
+        assert_eq!(audio.iter().map(|p| p.4.clone()).collect::<Vec<_>>(), vec![audio[0].4.clone(); 3]);
+        assert_eq!(callee.stats.packets_sent.load(std::sync::atomic::Ordering::Relaxed), 7);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn audio_is_transcoded_to_the_peer_codec() {
+        let (caller, _callee, far) = legs(&Pcma, DtmfMode::Relay).await;
+        let mut pcmu = Vec::new();
+        Pcmu.encode(&[1000; 160], &mut pcmu);
+        relay(&caller, &[wire(0, false, 1, 160, &pcmu)]).await;
+
+        let mut expected = Vec::new();
+        let mut pcm = Vec::new();
+        Pcmu.decode(&pcmu, &mut pcm);
+        Pcma.encode(&pcm, &mut expected);
+        let packets = drain(&far).await;
+        assert_eq!((packets[0].0, &packets[0].4), (8, &expected));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn muted_audio_reaches_the_peer_as_silence_until_unbridged() {
+        let (caller, callee, far) = legs(&Pcmu, DtmfMode::Relay).await;
+        let mut pcmu = Vec::new();
+        Pcmu.encode(&[1000; 160], &mut pcmu);
+        caller.set_mute(true, false, MuteReason::Request);
+        relay(&caller, &[wire(0, false, 1, 160, &pcmu)]).await;
+        caller.set_mute(false, false, MuteReason::Request);
+        callee.set_mute(false, true, MuteReason::Request);
+        relay(&caller, &[wire(0, false, 2, 320, &pcmu)]).await;
+
+        let packets = drain(&far).await;
+        assert_eq!(packets.iter().map(|p| p.4.clone()).collect::<Vec<_>>(), vec![vec![0xFF; 160]; 2]);
+        assert_eq!(packets[1].2, packets[0].2.wrapping_add(1));
+        assert_eq!(unbridge(&caller, UnbridgeReason::Request), Some(callee.port));
+        assert!(!callee.outbound_muted());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn digit_started_before_bridging_is_not_relayed() {
+        let (caller, _callee, far) = legs(&Pcmu, DtmfMode::Relay).await;
+        // Olay köprüden önce başlamış: işaretsiz ve süresi köprünün yaşından uzun.
+        relay(&caller, &[
+            wire(CALLER_DTMF, false, 20, 800, &digit(1, false, 4000)),
+            wire(CALLER_DTMF, false, 21, 800, &digit(1, true, 4160)),
+            wire(CALLER_DTMF, true, 22, 5000, &digit(2, false, 160)),
+        ]).await;
+
+        let packets = drain(&far).await;
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].4, digit(2, false, 160));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unfinished_digits_are_ended_on_the_far_leg() {
+        let (caller, callee, far) = legs(&Pcmu, DtmfMode::Relay).await;
+        // Bitiş paketleri kayboldu: sonraki ses paketi olayı zaman aşımıyla bitirir.
+        relay(&caller, &[wire(CALLER_DTMF, true, 1, 160, &digit(3, false, 160))]).await;
+        tokio::time::sleep(EVENT_TIMEOUT).await;
+        relay(&caller, &[wire(0, false, 2, 4160, &[0xFF; 160])]).await;
+        let packets = drain(&far).await;
+        let ends: Vec<_> = packets.iter().filter(|p| p.0 == CALLEE_DTMF && p.4[1] & 0x80 != 0).collect();
+        assert_eq!(ends.len(), END_REPEATS);
+        assert_eq!(packets.last().unwrap().0, 0);
+
+        // Köprü olay sürerken kaldırılırsa da karşıda olay kapanır.
+        relay(&caller, &[wire(CALLER_DTMF, true, 3, 4320, &digit(4, false, 160))]).await;
+        assert_eq!(unbridge(&caller, UnbridgeReason::Request), Some(callee.port));
+        let packets = drain(&far).await;
+        assert_eq!(packets.len(), 1 + END_REPEATS);
+        assert!(packets[1..].iter().all(|p| p.4[..2] == digit(4, true, 160)[..2]));
+        assert!(caller.bridged_peer().is_none() && callee.bridged_peer().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn leg_moves_to_a_new_peer_keeping_its_stream() {
+        let (caller, callee, callee_far) = legs(&Pcmu, DtmfMode::Relay).await;
+        relay(&caller, &[wire(0, false, 1, 160, &[0x55; 160])]).await;
+        let sent = callee.stats.packets_sent.load(std::sync::atomic::Ordering::Relaxed);
+        assert_eq!(bridge(&caller, &callee, DtmfMode::Relay).unwrap_err().to_string(),
+            SessionError::AlreadyBridged { port: caller.port, peer: callee.port }.to_string());
+
+        let sock = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = sock.local_addr().unwrap().port();
+        let third = Arc::new(RtpSession::new(port, &Pcmu, sock, "test-call").with_dtmf(CALLEE_DTMF));
+        let third_far = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        *third.remote_addr.lock().unwrap() = Some(third_far.local_addr().unwrap());
+        let (ssrc, remote) = (caller.stream.ssrc, *caller.remote_addr.lock().unwrap());
+        let detached = bridge(&caller, &third, DtmfMode::Relay).unwrap();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        // Eski karşı bacak çözülür ve döner; taşınan bacağın akışı olduğu gibi kalır.
+        assert_eq!(detached.iter().map(|s| s.port).collect::<Vec<_>>(), [callee.port]);
+        assert!(callee.bridged_peer().is_none());
+        assert_eq!(third.bridged_peer().map(|s| s.port), Some(caller.port));
+        assert_eq!((caller.stream.ssrc, *caller.remote_addr.lock().unwrap()), (ssrc, remote));
+        relay(&caller, &[wire(0, false, 2, 320, &[0x55; 160])]).await;
+        assert_eq!(drain(&callee_far).await.len(), 1, "only the packet relayed before the move");
+        assert_eq!(callee.stats.packets_sent.load(std::sync::atomic::Ordering::Relaxed), sent);
+        assert_eq!(drain(&third_far).await.len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn regenerated_digit_has_own_duration_and_three_ends() {
+        let (caller, _callee, far) = legs(&Pcmu, DtmfMode::Regenerate).await;
+        relay(&caller, &[
+            wire(CALLER_DTMF, true, 1, 160, &digit(9, false, 160)),
+            wire(CALLER_DTMF, false, 2, 160, &digit(9, false, 320)),
+            wire(CALLER_DTMF, false, 3, 160, &digit(9, true, 480)),
+            wire(CALLER_DTMF, false, 4, 160, &digit(9, true, 480)),
+            wire(CALLER_DTMF, false, 5, 160, &digit(9, true, 480)),
+            wire(CALLER_DTMF, false, 6, 160, &digit(9, true, 480)),
+        ]).await;
+
+        let events: Vec<_> = drain(&far).await.into_iter().map(|p| TelephoneEvent::parse(&p.4).unwrap()).collect();
+        // Başlangıç, bir güncelleme ve tam üç bitiş; gelen fazladan bitiş tekrarı aktarılmaz.
+        assert_eq!(events.len(), 2 + END_REPEATS);
+        assert_eq!((events[0].duration, events[1].duration), (0, 160));
+        assert!(events[2..].iter().all(|e| e.end && e.duration == 320 && e.event == 9));
+    }
+}
@@ -0,0 +1,6 @@
+// Derleme bilgisi: build.rs'in derleme anında ürettiği sabitler. GetVersion, başlangıç logu ve
+// media_build_info metriği aynı değerleri kullanır.
+include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+
+/// Crate sürümü (Cargo.toml).
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
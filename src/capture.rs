@@ -0,0 +1,221 @@
+// Oturum başına pcap yakalama. Gönderilen ve alınan UDP yükleri, gerçek adres/portlarla
+// uydurulmuş Ethernet/IP/UDP başlıklarına sarılıp standart pcap dosyasına yazılır.
+// Tamamen best-effort: medya yolu yalnızca kanala `try_send` yapar, kanal doluysa kayıt düşer.
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::mpsc;
+use tracing::warn;
+
+const CHANNEL_CAPACITY: usize = 1024;
+const PCAP_GLOBAL_HEADER_LEN: u64 = 24;
+const PCAP_RECORD_HEADER_LEN: u64 = 16;
+const LINKTYPE_ETHERNET: u32 = 1;
+
+struct Record {
+    at: SystemTime,
+    src: SocketAddr,
+    dst: SocketAddr,
+    payload: Vec<u8>,
+}
+
+/// Çalışan bir yakalamaya paket göndermek için tutamak.
+#[derive(Debug)]
+pub struct Capture {
+    path: PathBuf,
+    tx: mpsc::Sender<Record>,
+}
+
+impl Capture {
+    /// Dosyayı oluşturur ve yazıcı görevini başlatır. `max_bytes` dolunca sonraki paketler yazılmaz.
+    pub fn start(path: PathBuf, max_bytes: u64) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut writer = BufWriter::new(File::create(&path)?);
+        write_global_header(&mut writer)?;
+
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let task_path = path.clone();
+        tokio::task::spawn_blocking(move || run_writer(writer, rx, &task_path, max_bytes));
+        Ok(Self { path, tx })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&self, src: SocketAddr, dst: SocketAddr, payload: &[u8]) {
+        let record = Record { at: SystemTime::now(), src, dst, payload: payload.to_vec() };
+        let _ = self.tx.try_send(record);
+    }
+}
+
+/// Paketleri tek seferde, yakalama dosyasıyla aynı biçimde yazar; testlerin altın kayıtları için.
+pub fn write_packets<'a>(w: &mut impl Write, packets: impl IntoIterator<Item = (SystemTime, SocketAddr, SocketAddr, &'a [u8])>) -> std::io::Result<()> {
+    write_global_header(w)?;
+    for (at, src, dst, payload) in packets {
+        write_record(w, at, &build_frame(src, dst, payload))?;
+    }
+    Ok(())
+}
+
+fn run_writer(mut writer: BufWriter<File>, mut rx: mpsc::Receiver<Record>, path: &Path, max_bytes: u64) {
+    let mut written = PCAP_GLOBAL_HEADER_LEN;
+    while let Some(record) = rx.blocking_recv() {
+        let frame = build_frame(record.src, record.dst, &record.payload);
+        let size = PCAP_RECORD_HEADER_LEN + frame.len() as u64;
+        if written + size > max_bytes {
+            warn!(file = %path.display(), max_bytes, "Yakalama dosyası boyut sınırına ulaştı, yakalama durduruldu");
+            break;
+        }
+        if let Err(e) = write_record(&mut writer, record.at, &frame) {
+            warn!(file = %path.display(), error = %e, "Yakalama dosyasına yazılamadı, yakalama durduruldu");
+            return;
+        }
+        written += size;
+    }
+    if let Err(e) = writer.flush() {
+        warn!(file = %path.display(), error = %e, "Yakalama dosyası kapatılamadı");
+    }
+}
+
+fn write_global_header(w: &mut impl Write) -> std::io::Result<()> {
+    w.write_all(&0xa1b2c3d4u32.to_le_bytes())?;
+    w.write_all(&2u16.to_le_bytes())?;
+    w.write_all(&4u16.to_le_bytes())?;
+    w.write_all(&0i32.to_le_bytes())?; // thiszone
+    w.write_all(&0u32.to_le_bytes())?; // sigfigs
+    w.write_all(&65535u32.to_le_bytes())?; // snaplen
+    w.write_all(&LINKTYPE_ETHERNET.to_le_bytes())
+}
+
+fn write_record(w: &mut impl Write, at: SystemTime, frame: &[u8]) -> std::io::Result<()> {
+    let since_epoch = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+    w.write_all(&(since_epoch.as_secs() as u32).to_le_bytes())?;
+    w.write_all(&since_epoch.subsec_micros().to_le_bytes())?;
+    w.write_all(&(frame.len() as u32).to_le_bytes())?;
+    w.write_all(&(frame.len() as u32).to_le_bytes())?;
+    w.write_all(frame)
+}
+
+/// Ethernet + IPv4/IPv6 + UDP çerçevesi. Adres aileleri farklıysa ikisi de IPv6'ya eşlenir.
+fn build_frame(src: SocketAddr, dst: SocketAddr, payload: &[u8]) -> Vec<u8> {
+    let udp_len = (8 + payload.len()) as u16;
+    let mut frame = Vec::with_capacity(14 + 40 + udp_len as usize);
+    frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x02]); // hedef MAC
+    frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x01]); // kaynak MAC
+
+    let checksum;
+    match (src.ip(), dst.ip()) {
+        (IpAddr::V4(s), IpAddr::V4(d)) => {
+            frame.extend_from_slice(&0x0800u16.to_be_bytes());
+            let mut ip = [0u8; 20];
+            ip[0] = 0x45;
+            ip[2..4].copy_from_slice(&(20 + udp_len).to_be_bytes());
+            ip[6] = 0x40; // DF
+            ip[8] = 64;
+            ip[9] = 17;
+            ip[12..16].copy_from_slice(&s.octets());
+            ip[16..20].copy_from_slice(&d.octets());
+            let ip_checksum = internet_checksum(0, &ip);
+            ip[10..12].copy_from_slice(&ip_checksum.to_be_bytes());
+            frame.extend_from_slice(&ip);
+
+            let mut pseudo = Vec::with_capacity(12);
+            pseudo.extend_from_slice(&s.octets());
+            pseudo.extend_from_slice(&d.octets());
+            pseudo.extend_from_slice(&[0, 17]);
+            pseudo.extend_from_slice(&udp_len.to_be_bytes());
+            checksum = udp_checksum(&pseudo, src.port(), dst.port(), udp_len, payload);
+        }
+        (s, d) => {
+            let (s, d) = (to_v6(s), to_v6(d));
+            frame.extend_from_slice(&0x86DDu16.to_be_bytes());
+            frame.extend_from_slice(&[0x60, 0, 0, 0]);
+            frame.extend_from_slice(&udp_len.to_be_bytes());
+            frame.extend_from_slice(&[17, 64]);
+            frame.extend_from_slice(&s.octets());
+            frame.extend_from_slice(&d.octets());
+
+            let mut pseudo = Vec::with_capacity(40);
+            pseudo.extend_from_slice(&s.octets());
+            pseudo.extend_from_slice(&d.octets());
+            pseudo.extend_from_slice(&(udp_len as u32).to_be_bytes());
+            pseudo.extend_from_slice(&[0, 0, 0, 17]);
+            checksum = udp_checksum(&pseudo, src.port(), dst.port(), udp_len, payload);
+        }
+    }
+
+    frame.extend_from_slice(&src.port().to_be_bytes());
+    frame.extend_from_slice(&dst.port().to_be_bytes());
+    frame.extend_from_slice(&udp_len.to_be_bytes());
+    frame.extend_from_slice(&checksum.to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+fn to_v6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(v4) => v4.to_ipv6_mapped(),
+        IpAddr::V6(v6) => v6,
+    }
+}
+
+fn udp_checksum(pseudo: &[u8], src_port: u16, dst_port: u16, udp_len: u16, payload: &[u8]) -> u16 {
+    let mut header = [0u8; 8];
+    header[0..2].copy_from_slice(&src_port.to_be_bytes());
+    header[2..4].copy_from_slice(&dst_port.to_be_bytes());
+    header[4..6].copy_from_slice(&udp_len.to_be_bytes());
+    let sum = checksum_add(checksum_add(checksum_add(0, pseudo), &header), payload);
+    match fold(sum) {
+        // UDP'de 0 "checksum yok" demektir; hesaplanan 0 değeri 0xFFFF olarak yazılır.
+        0 => 0xFFFF,
+        c => c,
+    }
+}
+
+fn internet_checksum(initial: u32, data: &[u8]) -> u16 {
+    fold(checksum_add(initial, data))
+}
+
+fn checksum_add(mut sum: u32, data: &[u8]) -> u32 {
+    let mut chunks = data.chunks_exact(2);
+    for pair in &mut chunks {
+        sum += u16::from_be_bytes([pair[0], pair[1]]) as u32;
+    }
+    if let [last] = chunks.remainder() {
+        sum += (*last as u32) << 8;
+    }
+    sum
+}
+
+fn fold(mut sum: u32) -> u16 {
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipv4_frame_has_valid_header_checksum_and_lengths() {
+        let src: SocketAddr = "10.0.0.1:10000".parse().unwrap();
+        let dst: SocketAddr = "10.0.0.2:40000".parse().unwrap();
+        let payload = [0x80u8, 0, 0, 1, 0, 0, 0, 160, 0, 0, 0, 7, 0xFF, 0xFF, 0xFF];
+        let frame = build_frame(src, dst, &payload);
+
+        assert_eq!(frame.len(), 14 + 20 + 8 + payload.len());
+        assert_eq!(&frame[12..14], &[0x08, 0x00]);
+        // Doğru başlıkta checksum dahil toplam 0 olmalı.
+        assert_eq!(internet_checksum(0, &frame[14..34]), 0);
+        assert_eq!(u16::from_be_bytes([frame[38], frame[39]]), 8 + payload.len() as u16);
+        assert_eq!(&frame[42..], &payload);
+    }
+}
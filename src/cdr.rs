@@ -0,0 +1,345 @@
+// Oturum sonu kayıtları (CDR): faturalama için her oturumun tek satırlık özeti yapılandırılan
+// dosyaya NDJSON ya da CSV olarak eklenir. Kapanış yalnızca sınırlı kuyruğa `try_send` yapar;
+// dosyaya ayrı bir iş parçacığı yazar, fsync politikası ve döndürme orada uygulanır. Kuyruk dolarsa
+// ya da yazma başarısız olursa kayıt düşer, sayılır ve ERROR loglanır; oturum kapanışı diski beklemez.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tracing::error;
+
+use crate::appender::{AppendFile, Appender, Sink};
+use crate::config::{CdrConfig, CdrFormat, CdrFsync, CdrRotation};
+use crate::error::CdrError;
+use crate::labels::Labels;
+use crate::metrics::{self, RecordOutcome};
+
+/// CSV başlığı; `CdrRecord` alanlarıyla aynı sırada.
+pub const FIELDS: [&str; 16] = [
+    "session_id", "call_id", "allocated_at_ms", "first_packet_at_ms", "ended_at_ms", "teardown_reason", "codecs",
+    "packets_sent", "bytes_sent", "packets_received", "bytes_received", "loss_percent", "recordings", "dtmf_digits",
+    "tenant", "labels",
+];
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Tek bir oturumun kaydı; zamanlar Unix milisaniyesi.
+#[derive(Debug, Clone, Serialize)]
+pub struct CdrRecord {
+    pub session_id: String,
+    pub call_id: String,
+    pub allocated_at_ms: u64,
+    // Hiç paket gelmediyse yok (CSV'de boş).
+    pub first_packet_at_ms: Option<u64>,
+    pub ended_at_ms: u64,
+    pub teardown_reason: &'static str,
+    pub codecs: &'static str,
+    pub packets_sent: u64,
+    pub bytes_sent: u64,
+    pub packets_received: u64,
+    pub bytes_received: u64,
+    // Gelen akışta beklenen paketlerin kaybolan yüzdesi.
+    pub loss_percent: f64,
+    // CSV'de `;` ile birleştirilir.
+    pub recordings: Vec<String>,
+    pub dtmf_digits: u64,
+    // `[[tenants]]` kiracısı; kiracısızsa boş.
+    pub tenant: &'static str,
+    // AllocatePort etiketleri; CSV'de `anahtar=değer` çiftleri `;` ile birleştirilir.
+    pub labels: Labels,
+}
+
+impl CdrRecord {
+    fn csv_row(&self) -> String {
+        let optional = |value: Option<u64>| value.map_or(String::new(), |v| v.to_string());
+        let row = [
+            csv_field(&self.session_id), csv_field(&self.call_id), self.allocated_at_ms.to_string(), optional(self.first_packet_at_ms),
+            self.ended_at_ms.to_string(), csv_field(self.teardown_reason), csv_field(self.codecs),
+            self.packets_sent.to_string(), self.bytes_sent.to_string(), self.packets_received.to_string(), self.bytes_received.to_string(),
+            self.loss_percent.to_string(), csv_field(&self.recordings.join(";")), self.dtmf_digits.to_string(),
+            csv_field(self.tenant),
+            csv_field(&self.labels.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join(";")),
+        ];
+        row.join(",")
+    }
+}
+
+/// Virgül, tırnak ya da satır sonu içeren alan RFC 4180'deki gibi tırnaklanır.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Duvar saatinden Unix milisaniyesi; saat 1970'ten gerideyse 0.
+pub fn unix_millis(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+fn utc_day(at: SystemTime) -> u64 {
+    unix_millis(at) / 1000 / SECS_PER_DAY
+}
+
+/// `CdrRecord`'ları yapılandırılan biçimde (NDJSON ya da CSV) satır satır yazar; fsync politikasını ve
+/// günlük ya da boyutla döndürmeyi uygular.
+pub struct CdrWriter {
+    format: CdrFormat,
+    file: AppendFile,
+    fsync: CdrFsync,
+    rotate: CdrRotation,
+    max_file_bytes: u64,
+    // Açık dosyanın ait olduğu UTC gün.
+    day: u64,
+}
+
+impl CdrWriter {
+    pub fn new(config: &CdrConfig) -> Self {
+        let header = (config.format == CdrFormat::Csv).then(|| FIELDS.join(",") + "\n");
+        CdrWriter {
+            format: config.format, file: AppendFile::new(PathBuf::from(&config.path), header), fsync: config.fsync, rotate: config.rotate,
+            max_file_bytes: config.max_file_bytes, day: 0,
+        }
+    }
+
+    /// Kaydı `now` anında ekler; gerekiyorsa önce dosyayı döndürür. `fsync = "always"` ise kayıt
+    /// diske zorlanmadan dönmez.
+    pub fn write(&mut self, record: &CdrRecord, now: SystemTime) -> Result<(), CdrError> {
+        let line = match self.format {
+            CdrFormat::Csv => record.csv_row(),
+            _ => serde_json::to_string(record).map_err(|e| self.write_error(io::Error::other(e)))?,
+        } + "\n";
+        self.open(now).map_err(|e| self.write_error(e))?;
+        if self.rotation_due(now, line.len()) {
+            self.rotate_file(now).map_err(|source| CdrError::Rotate { path: self.file.path().display().to_string(), source })?;
+            self.open(now).map_err(|e| self.write_error(e))?;
+        }
+        self.file.append(&line).map_err(|e| self.write_error(e))?;
+        if self.fsync == CdrFsync::Always {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Yazılan ama diske zorlanmamış kayıtları zorlar; `fsync = "never"` ise hiçbir şey yapmaz.
+    pub fn sync(&mut self) -> Result<(), CdrError> {
+        self.sync_file().map_err(|e| self.write_error(e))
+    }
+
+    fn sync_file(&mut self) -> io::Result<()> {
+        if self.fsync == CdrFsync::Never {
+            return Ok(());
+        }
+        self.file.sync()
+    }
+
+    fn write_error(&self, source: io::Error) -> CdrError {
+        CdrError::Write { path: self.file.path().display().to_string(), source }
+    }
+
+    fn open(&mut self, now: SystemTime) -> io::Result<()> {
+        if self.file.open()? {
+            // Önceki bir süreçten kalan dosya son yazıldığı güne aittir.
+            let modified = || fs::metadata(self.file.path()).and_then(|meta| meta.modified());
+            self.day = if self.file.has_records() { modified().map_or(utc_day(now), utc_day) } else { utc_day(now) };
+        }
+        Ok(())
+    }
+
+    fn rotation_due(&self, now: SystemTime, len: usize) -> bool {
+        self.file.has_records() && match self.rotate {
+            CdrRotation::None => false,
+            CdrRotation::Daily => utc_day(now) != self.day,
+            CdrRotation::Size => self.file.exceeds(len, self.max_file_bytes),
+        }
+    }
+
+    /// Açık dosyayı kapatıp `{path}.{unix_saniye}` adıyla kenara alır; ad alınmışsa `-2`, `-3`... eklenir.
+    fn rotate_file(&mut self, now: SystemTime) -> io::Result<()> {
+        self.sync_file()?;
+        let base = format!("{}.{}", self.file.path().display(), unix_millis(now) / 1000);
+        let mut target = PathBuf::from(&base);
+        let mut n = 2;
+        while target.exists() {
+            target = PathBuf::from(format!("{}-{}", base, n));
+            n += 1;
+        }
+        self.file.rotate_to(&target)
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
+
+impl Sink for CdrWriter {
+    type Record = CdrRecord;
+
+    fn deliver(&mut self, record: CdrRecord) {
+        match self.write(&record, SystemTime::now()) {
+            Ok(()) => metrics::get().cdr_record(RecordOutcome::Written),
+            Err(e) => {
+                metrics::get().cdr_record(RecordOutcome::Failed);
+                error!(session_id = %record.session_id, error = %e, "CDR kaydı yazılamadı");
+            }
+        }
+    }
+
+    fn end_batch(&mut self) {
+        if let Err(e) = self.sync() {
+            error!(error = %e, "CDR dosyası diske zorlanamadı");
+        }
+    }
+
+    fn dropped(record: CdrRecord) {
+        metrics::get().cdr_record(RecordOutcome::Dropped);
+        error!(session_id = %record.session_id, "CDR kuyruğu dolu, kayıt düşürüldü");
+    }
+}
+
+pub type Cdr = Appender<CdrWriter>;
+
+/// Yazıcı iş parçacığını başlatır; `format = "none"` ise CDR yazılmaz.
+pub fn start(config: &CdrConfig) -> Option<Cdr> {
+    if config.format == CdrFormat::None {
+        return None;
+    }
+    match Appender::start("cdr", config.queue_size, CdrWriter::new(config)) {
+        Ok(cdr) => Some(cdr),
+        Err(e) => { error!(error = %e, "CDR yazıcısı başlatılamadı"); None }
+    }
+}
+
+static CDR: OnceLock<Cdr> = OnceLock::new();
+
+/// Config'deki CDR yazıcısını süreç geneli için kurar; yalnızca ilk çağrı etkilidir.
+pub fn install(config: &CdrConfig) {
+    if let Some(cdr) = start(config) {
+        let _ = CDR.set(cdr);
+    }
+}
+
+/// Oturum kapanışında çağrılır; CDR kapalıysa hiçbir şey yapmaz, asla beklemez.
+pub fn session_ended(record: CdrRecord) {
+    if let Some(cdr) = CDR.get() {
+        cdr.submit(record);
+    }
+}
+
+/// Süreç kapanırken bekleyen kayıtları yazar (bkz. `Appender::flush`).
+pub fn flush() {
+    if let Some(cdr) = CDR.get() {
+        cdr.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::time::Duration;
+
+    use super::*;
+
+    fn record(session_id: &str, recordings: &[&str]) -> CdrRecord {
+        CdrRecord {
+            session_id: session_id.to_string(), call_id: "call,\"7\"".to_string(),
+            allocated_at_ms: 1_760_000_000_000, first_packet_at_ms: None, ended_at_ms: 1_760_000_030_000,
+            teardown_reason: "released", codecs: "pcmu",
+            packets_sent: 1500, bytes_sent: 258_000, packets_received: 1490, bytes_received: 256_280,
+            loss_percent: 0.67, recordings: recordings.iter().map(|r| r.to_string()).collect(), dtmf_digits: 3,
+            tenant: "acme", labels: Labels::from([("queue".to_string(), "sales".to_string()), ("campaign".to_string(), "q3".to_string())]),
+        }
+    }
+
+    fn config(name: &str, format: CdrFormat, rotate: CdrRotation) -> CdrConfig {
+        let dir = std::env::temp_dir().join(format!("media-cdr-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        CdrConfig { format, path: dir.join("media.cdr").display().to_string(), fsync: CdrFsync::Always, rotate, max_file_bytes: 4096, queue_size: 8 }
+    }
+
+    // RFC 4180: tırnaklı alanlarda virgül ve çift tırnak.
+    fn parse_csv_line(line: &str) -> Vec<String> {
+        let (mut fields, mut field, mut quoted, mut chars) = (Vec::new(), String::new(), false, line.chars().peekable());
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => { field.push('"'); chars.next(); }
+                '"' => quoted = !quoted,
+                ',' if !quoted => fields.push(std::mem::take(&mut field)),
+                c => field.push(c),
+            }
+        }
+        fields.push(field);
+        fields
+    }
+
+    #[test]
+    fn records_parse_back_with_the_same_schema_in_both_formats() {
+        let now = SystemTime::now();
+        let json = config("json", CdrFormat::Json, CdrRotation::None);
+        let mut writer = CdrWriter::new(&json);
+        writer.write(&record("a", &["a.wav"]), now).unwrap();
+        writer.write(&CdrRecord { first_packet_at_ms: Some(1_760_000_000_120), ..record("b", &[]) }, now).unwrap();
+        let lines: Vec<serde_json::Value> = fs::read_to_string(writer.path()).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            let keys: BTreeSet<&str> = line.as_object().unwrap().keys().map(String::as_str).collect();
+            assert_eq!(keys, FIELDS.iter().copied().collect::<BTreeSet<_>>());
+        }
+        assert_eq!(lines[0]["first_packet_at_ms"], serde_json::Value::Null);
+        assert_eq!(lines[1]["first_packet_at_ms"], 1_760_000_000_120u64);
+        assert_eq!(lines[0]["recordings"], serde_json::json!(["a.wav"]));
+        assert_eq!(lines[0]["dtmf_digits"], 3);
+        assert_eq!(lines[0]["labels"], serde_json::json!({"campaign": "q3", "queue": "sales"}));
+
+        let csv = config("csv", CdrFormat::Csv, CdrRotation::None);
+        let mut writer = CdrWriter::new(&csv);
+        writer.write(&record("a", &["a.wav", "a_1.wav"]), now).unwrap();
+        // Yeniden açılan dosyaya başlık tekrar yazılmaz.
+        let mut writer = CdrWriter::new(&csv);
+        writer.write(&record("b", &[]), now).unwrap();
+        let text = fs::read_to_string(writer.path()).unwrap();
+        let rows: Vec<Vec<String>> = text.lines().map(parse_csv_line).collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], FIELDS);
+        let row = |i: usize, field: &str| rows[i][FIELDS.iter().position(|f| *f == field).unwrap()].clone();
+        assert!(rows[1..].iter().all(|r| r.len() == FIELDS.len()));
+        assert_eq!(row(1, "call_id"), "call,\"7\"");
+        assert_eq!(row(1, "recordings"), "a.wav;a_1.wav");
+        assert_eq!(row(1, "first_packet_at_ms"), "");
+        assert_eq!(row(1, "labels"), "campaign=q3;queue=sales");
+        assert_eq!(row(2, "session_id"), "b");
+        assert_eq!(row(2, "loss_percent").parse::<f64>().unwrap(), 0.67);
+    }
+
+    #[test]
+    fn file_is_rotated_by_size_and_by_utc_day() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_760_000_000);
+        let size = config("size", CdrFormat::Json, CdrRotation::Size);
+        let mut writer = CdrWriter::new(&size);
+        let long = record("a", &["x".repeat(1500).as_str()]);
+        for _ in 0..3 {
+            writer.write(&long, now).unwrap();
+        }
+        let dir = writer.path().parent().unwrap().to_path_buf();
+        let mut names: Vec<String> = fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name().into_string().unwrap()).collect();
+        names.sort();
+        assert_eq!(names, ["media.cdr", "media.cdr.1760000000"]);
+        assert_eq!(fs::read_to_string(writer.path()).unwrap().lines().count(), 1);
+
+        let daily = config("daily", CdrFormat::Csv, CdrRotation::Daily);
+        let mut writer = CdrWriter::new(&daily);
+        writer.write(&record("a", &[]), now).unwrap();
+        writer.write(&record("b", &[]), now + Duration::from_secs(60)).unwrap();
+        writer.write(&record("c", &[]), now + Duration::from_secs(SECS_PER_DAY)).unwrap();
+        let rotated = fs::read_to_string(format!("{}.{}", daily.path, 1_760_000_000 + SECS_PER_DAY)).unwrap();
+        assert_eq!(rotated.lines().count(), 3);
+        let current = fs::read_to_string(writer.path()).unwrap();
+        assert_eq!(current.lines().collect::<Vec<_>>(), [FIELDS.join(","), record("c", &[]).csv_row()]);
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(writer.path().parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,303 @@
+// Desteklenen ses codec'leri ve G.711 kodlayıcıları.
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Bir RTP ses codec'i. Oynatma ve alma yolları yalnızca bu arayüzü kullanır; yeni codec
+/// eklemek bir implementasyon ve `CodecRegistry::builtin`'e bir satırdır.
+pub trait Codec: Send + Sync + fmt::Debug {
+    /// Config ve gRPC'de kullanılan küçük harfli isim.
+    fn name(&self) -> &'static str;
+
+    /// RTP payload type (RFC 3551 statik ya da dinamik).
+    fn payload_type(&self) -> u8;
+
+    /// RTP zaman damgası saati (RFC 3551 6); örnekleme hızından farklı olabilir: G.722 16 kHz
+    /// örnekler ama 8000 duyurur, Opus çerçeve boyundan bağımsız olarak 48000 kullanır.
+    fn clock_rate(&self) -> u32;
+
+    /// Kodlayıcıya verilen ve çözücüden çıkan PCM'in örnekleme hızı.
+    fn sample_rate(&self) -> u32 {
+        self.clock_rate()
+    }
+
+    /// `ptime` süresindeki PCM örnek sayısı.
+    fn samples_per_frame(&self, ptime: Duration) -> usize {
+        (self.sample_rate() as u64 * ptime.as_micros() as u64 / 1_000_000) as usize
+    }
+
+    /// `samples` PCM örneğinin süresi.
+    fn frame_duration(&self, samples: usize) -> Duration {
+        Duration::from_micros(samples as u64 * 1_000_000 / self.sample_rate() as u64)
+    }
+
+    /// `frame` süresindeki bir paketin zaman damgasını ne kadar ilerlettiği.
+    fn timestamp_increment(&self, frame: Duration) -> u32 {
+        (self.clock_rate() as u128 * frame.as_micros() / 1_000_000) as u32
+    }
+
+    /// Bir çerçevelik PCM'i kodlayıp `payload`'ın sonuna ekler.
+    fn encode(&self, pcm: &[i16], payload: &mut Vec<u8>);
+
+    /// Bir paketin yükünü çözüp `pcm`'in sonuna ekler.
+    fn decode(&self, payload: &[u8], pcm: &mut Vec<i16>);
+}
+
+impl fmt::Display for dyn Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Payload type bir oturum içinde codec'i tekil olarak belirler.
+impl PartialEq for dyn Codec {
+    fn eq(&self, other: &Self) -> bool {
+        self.payload_type() == other.payload_type()
+    }
+}
+
+/// G.711 µ-law, RFC 3551 PT 0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pcmu;
+
+impl Codec for Pcmu {
+    fn name(&self) -> &'static str { "pcmu" }
+    fn payload_type(&self) -> u8 { 0 }
+    fn clock_rate(&self) -> u32 { 8000 }
+
+    fn encode(&self, pcm: &[i16], payload: &mut Vec<u8>) {
+        payload.extend(pcm.iter().map(|&s| pcm16_to_g711_ulaw(s)));
+    }
+
+    fn decode(&self, payload: &[u8], pcm: &mut Vec<i16>) {
+        pcm.extend(payload.iter().map(|&b| g711_ulaw_to_pcm16(b)));
+    }
+}
+
+/// G.711 A-law, RFC 3551 PT 8.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pcma;
+
+impl Codec for Pcma {
+    fn name(&self) -> &'static str { "pcma" }
+    fn payload_type(&self) -> u8 { 8 }
+    fn clock_rate(&self) -> u32 { 8000 }
+
+    fn encode(&self, pcm: &[i16], payload: &mut Vec<u8>) {
+        payload.extend(pcm.iter().map(|&s| pcm16_to_g711_alaw(s)));
+    }
+
+    fn decode(&self, payload: &[u8], pcm: &mut Vec<i16>) {
+        pcm.extend(payload.iter().map(|&b| g711_alaw_to_pcm16(b)));
+    }
+}
+
+/// Derlenmiş codec'lerin listesi. `ListCodecs`, config doğrulaması ve port tahsisi codec'leri
+/// buradan bulur.
+#[derive(Debug)]
+pub struct CodecRegistry {
+    codecs: Vec<Box<dyn Codec>>,
+}
+
+impl CodecRegistry {
+    pub fn builtin() -> Self {
+        CodecRegistry { codecs: vec![Box::new(Pcmu), Box::new(Pcma)] }
+    }
+
+    /// İsimden codec'i bulur (büyük/küçük harf duyarsız).
+    pub fn by_name(&self, name: &str) -> Option<&dyn Codec> {
+        self.iter().find(|c| c.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn by_payload_type(&self, payload_type: u8) -> Option<&dyn Codec> {
+        self.iter().find(|c| c.payload_type() == payload_type)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Codec> {
+        self.codecs.iter().map(|c| c.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.iter().map(|c| c.name()).collect()
+    }
+}
+
+/// Süreç genelindeki registry.
+pub fn registry() -> &'static CodecRegistry {
+    static REGISTRY: OnceLock<CodecRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(CodecRegistry::builtin)
+}
+
+pub fn pcm16_to_g711_ulaw(sample: i16) -> u8 {
+    const BIAS: i16 = 0x84;
+    const CLIP: i16 = 32635;
+    // Aritmetik kaydırma: negatif örneklerde üst bitler 1 dolar, işaret biti 0x80 olur.
+    let sign = (sample >> 8) & 0x80;
+    // i16::MIN'in mutlak değeri i16'ya sığmaz; doymalı alınır, zaten CLIP'e kırpılacak.
+    let mut val = sample.saturating_abs();
+    if val > CLIP { val = CLIP; }
+    val += BIAS;
+    let exponent = match val {
+        0..=0x00FF => 0, 0x0100..=0x01FF => 1, 0x0200..=0x03FF => 2,
+        0x0400..=0x07FF => 3, 0x0800..=0x0FFF => 4, 0x1000..=0x1FFF => 5,
+        0x2000..=0x3FFF => 6, _ => 7,
+    };
+    let mantissa = (val >> (exponent + 3)) & 0x0F;
+    let ulaw = !(sign | (exponent << 4) | mantissa);
+    ulaw as u8
+}
+
+pub fn pcm16_to_g711_alaw(sample: i16) -> u8 {
+    // 13-bit lineer değer üzerinde segment araması (ITU-T G.711 A-law)
+    const SEG_END: [i16; 8] = [0x1F, 0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF];
+    let mut pcm = sample >> 3;
+    let mask: u8 = if pcm >= 0 { 0xD5 } else { pcm = -pcm - 1; 0x55 };
+    let segment = match SEG_END.iter().position(|&end| pcm <= end) {
+        Some(s) => s,
+        None => return 0x7F ^ mask,
+    };
+    let shift = if segment < 2 { 1 } else { segment };
+    let aval = ((segment as u8) << 4) | ((pcm >> shift) & 0x0F) as u8;
+    aval ^ mask
+}
+
+/// ITU-T G.711 µ-law çözücü; sonuç 16 bit ölçeğindedir.
+pub fn g711_ulaw_to_pcm16(ulaw: u8) -> i16 {
+    let u = !ulaw;
+    let exponent = (u & 0x70) >> 4;
+    let magnitude = ((((u & 0x0F) as i16) << 3) + 0x84) << exponent;
+    if u & 0x80 != 0 { 0x84 - magnitude } else { magnitude - 0x84 }
+}
+
+/// ITU-T G.711 A-law çözücü; sonuç 16 bit ölçeğindedir.
+pub fn g711_alaw_to_pcm16(alaw: u8) -> i16 {
+    let a = alaw ^ 0x55;
+    let segment = (a & 0x70) >> 4;
+    let mut magnitude = ((a & 0x0F) as i16) << 4;
+    magnitude += match segment {
+        0 => 8,
+        _ => 0x108,
+    };
+    if segment > 1 {
+        magnitude <<= segment - 1;
+    }
+    if a & 0x80 != 0 { magnitude } else { -magnitude }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ITU-T G.711 µ-law referansı (CCITT/Sun g711.c `linear2ulaw`, 16-bit giriş): genişletilmiş
+    /// tamsayıda segment tablosu araması, tablonun üstü doyma.
+    fn reference_ulaw(sample: i16) -> u8 {
+        const BIAS: i32 = 0x84;
+        const SEG_END: [i32; 8] = [0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF, 0x1FFF, 0x3FFF, 0x7FFF];
+        let pcm = sample as i32;
+        let (val, mask) = if pcm < 0 { (BIAS - pcm, 0x7F) } else { (BIAS + pcm, 0xFF) };
+        match SEG_END.iter().position(|&end| val <= end) {
+            Some(seg) => (((seg as i32) << 4 | ((val >> (seg + 3)) & 0x0F)) ^ mask) as u8,
+            None => (0x7F ^ mask) as u8,
+        }
+    }
+
+    /// Örnekleme hızı RTP saatinden farklı codec'ler için yalnızca saat bilgisi taşıyan vekil.
+    #[derive(Debug)]
+    struct Clocks { name: &'static str, clock_rate: u32, sample_rate: u32 }
+
+    impl Codec for Clocks {
+        fn name(&self) -> &'static str { self.name }
+        fn payload_type(&self) -> u8 { 96 }
+        fn clock_rate(&self) -> u32 { self.clock_rate }
+        fn sample_rate(&self) -> u32 { self.sample_rate }
+        fn encode(&self, _pcm: &[i16], _payload: &mut Vec<u8>) {}
+        fn decode(&self, _payload: &[u8], _pcm: &mut Vec<i16>) {}
+    }
+
+    #[test]
+    fn timestamp_increment_follows_the_rtp_clock_not_the_sample_rate() {
+        let ptime = Duration::from_millis(20);
+        let g722 = Clocks { name: "g722", clock_rate: 8000, sample_rate: 16000 };
+        let opus = Clocks { name: "opus", clock_rate: 48000, sample_rate: 48000 };
+        let cases: [(&dyn Codec, u32, usize); 4] = [(&Pcmu, 160, 160), (&Pcma, 160, 160), (&g722, 160, 320), (&opus, 960, 960)];
+        for (codec, increment, samples) in cases {
+            assert_eq!(codec.timestamp_increment(ptime), increment, "{codec}");
+            assert_eq!(codec.samples_per_frame(ptime), samples, "{codec}");
+            assert_eq!(codec.timestamp_increment(codec.frame_duration(samples)), increment, "{codec}");
+        }
+    }
+
+    #[test]
+    fn ulaw_matches_reference_for_every_sample() {
+        for sample in i16::MIN..=i16::MAX {
+            assert_eq!(pcm16_to_g711_ulaw(sample), reference_ulaw(sample), "sample {sample}");
+        }
+    }
+
+    #[test]
+    fn ulaw_edge_samples() {
+        assert_eq!(pcm16_to_g711_ulaw(i16::MIN), 0x00);
+        assert_eq!(pcm16_to_g711_ulaw(-1), 0x7F);
+        assert_eq!(pcm16_to_g711_ulaw(0), 0xFF);
+        assert_eq!(pcm16_to_g711_ulaw(i16::MAX), 0x80);
+        // İşaret biti negatif örneklerde temizlenir (ters çevrilmiş kodda 0), pozitiflerde set kalır.
+        assert_eq!(pcm16_to_g711_ulaw(-1000) & 0x80, 0);
+        assert_eq!(pcm16_to_g711_ulaw(1000) & 0x80, 0x80);
+    }
+
+    /// Bir konuşma bandı sinüsü: ~1 kHz, tam ölçeğin yarısı.
+    fn tone(len: usize) -> Vec<i16> {
+        (0..len).map(|i| ((i as f64 * 0.785).sin() * 16000.0) as i16).collect()
+    }
+
+    #[test]
+    fn every_registered_codec_sizes_frames_by_ptime() {
+        for codec in registry().iter() {
+            for ms in [10, 20, 30, 40, 60] {
+                let samples = codec.samples_per_frame(Duration::from_millis(ms));
+                assert_eq!(samples as u64, codec.clock_rate() as u64 * ms / 1000, "{codec} {ms} ms");
+
+                let mut payload = Vec::new();
+                codec.encode(&tone(samples), &mut payload);
+                let mut pcm = Vec::new();
+                codec.decode(&payload, &mut pcm);
+                assert_eq!(pcm.len(), samples, "{codec} {ms} ms frame decodes to a full frame");
+            }
+        }
+    }
+
+    #[test]
+    fn every_registered_codec_round_trips_with_bounded_error() {
+        for codec in registry().iter() {
+            let original = tone(codec.samples_per_frame(Duration::from_millis(20)));
+            let mut payload = Vec::new();
+            codec.encode(&original, &mut payload);
+            let mut decoded = Vec::new();
+            codec.decode(&payload, &mut decoded);
+
+            let signal: f64 = original.iter().map(|&s| (s as f64).powi(2)).sum();
+            let noise: f64 = original.iter().zip(&decoded).map(|(&a, &b)| (a as f64 - b as f64).powi(2)).sum();
+            let snr_db = 10.0 * (signal / noise).log10();
+            assert!(snr_db > 30.0, "{codec} SNR {snr_db:.1} dB");
+
+            // Çözülmüş sinyali yeniden kodlamak aynı yükü verir.
+            let mut again = Vec::new();
+            codec.encode(&decoded, &mut again);
+            assert_eq!(again, payload, "{codec} re-encode");
+        }
+    }
+
+    #[test]
+    fn g711_decoders_match_reference_endpoints() {
+        assert_eq!([0xFF, 0x7F, 0x80, 0x00].map(g711_ulaw_to_pcm16), [0, 0, 32124, -32124]);
+        assert_eq!([0xD5, 0x55, 0xAA, 0x2A].map(g711_alaw_to_pcm16), [8, -8, 32256, -32256]);
+    }
+
+    #[test]
+    fn registry_finds_codecs_by_name_and_payload_type() {
+        assert_eq!(registry().by_name("PCMU").map(|c| c.payload_type()), Some(0));
+        assert_eq!(registry().by_payload_type(8).map(|c| c.name()), Some("pcma"));
+        assert!(registry().by_name("opus").is_none());
+        assert_eq!(registry().names(), ["pcmu", "pcma"]);
+    }
+}
@@ -0,0 +1,131 @@
+// Birleşik anonslar: rakam dizileri ve sayılar küçük parça anonslarından okunur. Burada yalnızca
+// parça anahtarlarının sırası çıkarılır ("0".."9", "10".."90", "11".."19", "hundred",
+// "thousand", "million", "billion", sıra sayısında "ordinal_<anahtar>"); anahtarları
+// `announcement.segment_prefix` ile anons adına çeviren ve eksikleri bulan kütüphanedir. Parçalar
+// tek bir oynatmada art arda, aralarında boşluk olmadan çalınır.
+use crate::error::PlaybackError;
+
+pub const MAX_DIGITS: usize = 64;
+pub const MAX_NUMBER: u64 = 999_999_999_999;
+
+// Büyükten küçüğe ölçek kelimeleri.
+const SCALES: [(u64, &str); 3] = [(1_000_000_000, "billion"), (1_000_000, "million"), (1_000, "thousand")];
+
+/// Sayı okuma kuralı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grammar {
+    /// 11-19 kendi parçalarıyla; "one hundred", "one thousand".
+    English,
+    /// 11-19 "on" + rakam; "yüz" ve "bin" önünde "bir" okunmaz, "bir milyon" okunur.
+    Turkish,
+}
+
+impl Grammar {
+    /// "tr" ve "tr-*" dilleri Türkçe, diğerleri (dil verilmemişse de) İngilizce kuralıyla okunur.
+    pub fn for_language(language: Option<&str>) -> Self {
+        match language {
+            Some(language) if language == "tr" || language.starts_with("tr-") => Grammar::Turkish,
+            _ => Grammar::English,
+        }
+    }
+}
+
+/// Her rakam kendi parçasıyla: "4271" -> 4, 2, 7, 1.
+pub fn digits(digits: &str) -> Result<Vec<String>, PlaybackError> {
+    if digits.is_empty() || digits.len() > MAX_DIGITS || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(PlaybackError::InvalidDigits { digits: digits.to_string() });
+    }
+    Ok(digits.chars().map(String::from).collect())
+}
+
+/// Sayının parçaları; sıra sayısında son parça "ordinal_" önekli haliyle değişir
+/// (21. -> "20", "ordinal_1").
+pub fn number(number: u64, ordinal: bool, grammar: Grammar) -> Result<Vec<String>, PlaybackError> {
+    if number > MAX_NUMBER {
+        return Err(PlaybackError::NumberOutOfRange { number });
+    }
+    let mut keys = Vec::new();
+    if number == 0 {
+        keys.push("0".to_string());
+    }
+    for (scale, word) in SCALES {
+        let group = number / scale % 1000;
+        if group == 0 {
+            continue;
+        }
+        if !(grammar == Grammar::Turkish && group == 1 && word == "thousand") {
+            below_thousand(group, grammar, &mut keys);
+        }
+        keys.push(word.to_string());
+    }
+    below_thousand(number % 1000, grammar, &mut keys);
+    if ordinal {
+        if let Some(last) = keys.pop() {
+            keys.push(format!("ordinal_{}", last));
+        }
+    }
+    Ok(keys)
+}
+
+fn below_thousand(number: u64, grammar: Grammar, keys: &mut Vec<String>) {
+    let (hundreds, rest) = (number / 100, number % 100);
+    if hundreds > 0 {
+        if !(grammar == Grammar::Turkish && hundreds == 1) {
+            keys.push(hundreds.to_string());
+        }
+        keys.push("hundred".to_string());
+    }
+    let (tens, units) = (rest / 10, rest % 10);
+    if grammar == Grammar::English && (1..20).contains(&rest) {
+        keys.push(rest.to_string());
+        return;
+    }
+    if tens > 0 {
+        keys.push((tens * 10).to_string());
+    }
+    if units > 0 {
+        keys.push(units.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn say(number: u64, ordinal: bool, grammar: Grammar) -> Vec<String> {
+        super::number(number, ordinal, grammar).unwrap()
+    }
+
+    #[test]
+    fn numbers_follow_each_languages_rules() {
+        use Grammar::{English, Turkish};
+        assert_eq!(say(0, false, English), ["0"]);
+        assert_eq!(say(12, false, English), ["12"]);
+        assert_eq!(say(12, false, Turkish), ["10", "2"]);
+        assert_eq!(say(115, false, English), ["1", "hundred", "15"]);
+        assert_eq!(say(115, false, Turkish), ["hundred", "10", "5"]);
+        assert_eq!(say(1_100, false, English), ["1", "thousand", "1", "hundred"]);
+        assert_eq!(say(1_100, false, Turkish), ["thousand", "hundred"]);
+        assert_eq!(say(2_000_017, false, Turkish), ["2", "million", "10", "7"]);
+        assert_eq!(say(1_001_000, false, Turkish), ["1", "million", "thousand"]);
+        assert_eq!(say(MAX_NUMBER, false, English).len(), 19);
+
+        assert_eq!(say(21, true, English), ["20", "ordinal_1"]);
+        assert_eq!(say(12, true, English), ["ordinal_12"]);
+        assert_eq!(say(100, true, Turkish), ["ordinal_hundred"]);
+        assert_eq!(say(0, true, Turkish), ["ordinal_0"]);
+
+        assert!(matches!(number(MAX_NUMBER + 1, false, English), Err(PlaybackError::NumberOutOfRange { .. })));
+        assert_eq!(Grammar::for_language(Some("tr-TR")), Turkish);
+        assert_eq!(Grammar::for_language(Some("tk")), English);
+    }
+
+    #[test]
+    fn digit_strings_are_read_one_segment_per_digit() {
+        assert_eq!(digits("4271").unwrap(), ["4", "2", "7", "1"]);
+        assert_eq!(digits("007").unwrap(), ["0", "0", "7"]);
+        for invalid in ["", "42a", "4 2", "-1", &"1".repeat(MAX_DIGITS + 1)] {
+            assert!(matches!(digits(invalid), Err(PlaybackError::InvalidDigits { .. })), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,1776 @@
+// Konfigürasyon yapıları ve başlangıç doğrulaması.
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::net::{AddrParseError, IpAddr, SocketAddr};
+use std::time::Duration;
+
+use ::config::{Config, File, FileFormat};
+use serde::{Deserialize, Serialize, Serializer};
+use tonic::metadata::AsciiMetadataKey;
+use tracing::{error, warn};
+use tracing_subscriber::EnvFilter;
+
+use crate::codec::{self, Codec};
+use crate::error::ConfigError;
+use crate::labels;
+use crate::recording;
+use crate::red;
+use crate::rtcp;
+use crate::request_id;
+use crate::scheduler;
+use crate::transport;
+use crate::vad;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GrpcConfig {
+    pub host: String,
+    pub port: u16,
+    // Boş değilse host/port yerine bu "IP:port" adreslerinin her biri dinlenir.
+    #[serde(default)]
+    pub listen: Vec<String>,
+    // false ise host/port dinlenmez; gRPC yalnızca unix_socket_path üzerinden sunulur.
+    #[serde(default = "default_tcp")]
+    pub tcp: bool,
+    // Ayarlıysa gRPC bu Unix domain socket'te de sunulur (yalnızca unix).
+    #[serde(default)]
+    pub unix_socket_path: Option<String>,
+    // Soket dosyasının izinleri, ör. 0o660.
+    #[serde(default = "default_unix_socket_mode")]
+    pub unix_socket_mode: u32,
+    // Sinyalleşmenin istek kimliğini taşıdığı metadata anahtarı; yoksa kimlik yerelde üretilir.
+    #[serde(default = "default_request_id_header")]
+    pub request_id_header: String,
+}
+fn default_tcp() -> bool { true }
+impl GrpcConfig {
+    /// Dinlenecek TCP adresleri: `listen` ya da yoksa tek `host:port`.
+    pub fn listen_addrs(&self) -> Result<Vec<SocketAddr>, AddrParseError> {
+        if self.listen.is_empty() {
+            return Ok(vec![SocketAddr::new(self.host.parse()?, self.port)]);
+        }
+        self.listen.iter().map(|addr| addr.parse()).collect()
+    }
+}
+fn default_unix_socket_mode() -> u32 { 0o660 }
+fn default_request_id_header() -> String { request_id::DEFAULT_HEADER.to_string() }
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RtpConfig {
+    pub host: String,
+    // GenerateSdp'nin c= ve o= satırlarına yazdığı adres; boşsa `host` (0.0.0.0 değilse).
+    #[serde(default)]
+    pub advertise_address: String,
+    pub min_port: u16,
+    pub max_port: u16,
+    // Hem izin listesi hem tercih sırası: istek codec belirtmezse ilk etkin codec kullanılır.
+    #[serde(default = "default_codecs")]
+    pub codecs: Vec<String>,
+    // RED anlaşılan oturumlarda her pakette tekrar gönderilecek önceki çerçeve sayısı (1-2).
+    #[serde(default = "default_red_generations")]
+    pub red_generations: usize,
+    // AllocatePort'ta RFC 4571 TCP taşıması istenebilir mi; kapalıysa istek UDP alır.
+    #[serde(default = "default_allow_tcp")]
+    pub allow_tcp: bool,
+    // Aralık tükenince aralık dışından port alınsın mı; kapalıysa tahsis reddedilir.
+    #[serde(default)]
+    pub allow_overflow: bool,
+    // Taşma portlarının aralığı; UDP'de RTP/RTCP çifti bu aralıktan alınır. İkisi de 0 ise
+    // işletim sisteminin atadığı geçici port kullanılır ve RTCP RTP portundan geçer.
+    #[serde(default)]
+    pub overflow_min_port: u16,
+    #[serde(default)]
+    pub overflow_max_port: u16,
+    // Linux'ta `recvmmsg` feature'ıyla bir okuma çağrısında alınacak en fazla paket; 1 ise ve
+    // diğer platformlarda paketler tek tek okunur.
+    #[serde(default = "default_recv_batch")]
+    pub recv_batch: usize,
+    // Giden sesi gönderen zamanlayıcı görevlerinin sayısı; 0 ise işlemci sayısı kadar.
+    #[serde(default)]
+    pub send_schedulers: usize,
+    // AllocatePort ve SetImpairment giden yola ağ bozulması uygulayabilir mi; yalnızca test
+    // node'larında ve `impairment` feature'ıyla.
+    #[serde(default)]
+    pub enable_impairments: bool,
+}
+fn default_codecs() -> Vec<String> { vec!["pcmu".to_string(), "pcma".to_string()] }
+fn default_red_generations() -> usize { 1 }
+fn default_allow_tcp() -> bool { true }
+fn default_recv_batch() -> usize { 8 }
+
+impl RtpConfig {
+    /// SDP'de duyurulacak adres: `advertise_address`, yoksa belirli bir adrese bağlıysa `host`.
+    pub fn advertise_address(&self) -> Option<IpAddr> {
+        let address = if self.advertise_address.is_empty() { &self.host } else { &self.advertise_address };
+        address.parse().ok().filter(|address: &IpAddr| !address.is_unspecified())
+    }
+
+    /// Başlatılacak gönderim zamanlayıcısı sayısı.
+    pub fn send_schedulers(&self) -> usize {
+        match self.send_schedulers {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+    }
+
+    /// Config'deki sırayla, tanınan codec'ler. Bilinmeyen isimler atlanır.
+    pub fn enabled_codecs(&self) -> Vec<&'static dyn Codec> {
+        let mut codecs: Vec<&'static dyn Codec> = Vec::new();
+        for codec in self.codecs.iter().filter_map(|name| codec::registry().by_name(name)) {
+            if !codecs.contains(&codec) { codecs.push(codec); }
+        }
+        codecs
+    }
+
+    /// Taşma aralığı; `None` ise işletim sistemi port atar.
+    pub fn overflow_range(&self) -> Option<(u16, u16)> {
+        let range = (self.overflow_min_port, self.overflow_max_port);
+        (range != (0, 0)).then_some(range)
+    }
+}
+/// Binary'ye gömülü tam konfigürasyon; dosyadaki değerler bunun üzerine yazılır.
+const BUILTIN_CONFIG: &str = include_str!("../config/builtin.toml");
+const CONFIG_FILE: &str = "config/default";
+// config crate'inin `File::with_name` ile denediği uzantılar.
+const CONFIG_EXTENSIONS: [&str; 7] = ["toml", "json", "yaml", "yml", "ini", "ron", "json5"];
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct AnnouncementConfig {
+    // İlk RTP paketinde çalınacak anonsun adı; yoksa ya da boşsa karşılama anonsu çalınmaz.
+    pub welcome: Option<String>,
+    // Aynı portta yeni bir gelen akış kilitlendiğinde karşılama anonsu yeniden çalınsın mı.
+    #[serde(default)]
+    pub replay_welcome: bool,
+    // Oturum en uzun süreye ulaşınca, kapanmadan önce çalınacak anonsun adı; yoksa doğrudan kapanır.
+    pub max_duration: Option<String>,
+    // Karşılamanın altında döngüyle çalınacak fon müziği anonsunun adı; yoksa karşılama yalın çalınır.
+    pub welcome_bed: Option<String>,
+    // Fon müziğinin ön plana göre kısması (dB); PlayAnnouncement kendi değerini verebilir.
+    pub bed_gain_db: Option<f32>,
+    // Dili verilmeyen anons dosyalarının dili; istenen dilde varyant yoksa bu dile düşülür.
+    pub default_language: Option<String>,
+    // SayDigits/SayNumber parça anonslarının ad öneki ("say_" ile 7 rakamı "say_7" anonsudur).
+    pub segment_prefix: Option<String>,
+    // Çalan bitince sırayla çalınmak üzere oturum başına bekletilebilecek en fazla oynatma.
+    pub max_queued: Option<usize>,
+    #[serde(default)]
+    pub prompts: HashMap<String, PromptConfig>,
+    // UploadAnnouncement ile yüklenen anonsların dizini; yoksa yükleme kapalıdır.
+    pub upload_dir: Option<String>,
+    // Yüklenebilecek en büyük WAV dosyası (bayt).
+    pub max_upload_bytes: Option<u64>,
+    // `http://` yollu anonsların indirildiği dizin; yoksa URL'li anonslar kullanılamaz.
+    pub url_cache_dir: Option<String>,
+    // Önbellek dizininin üst sınırı (bayt); aşılınca en eski kopyalar silinir.
+    pub url_cache_max_bytes: Option<u64>,
+    // İndirilen kopyanın sunucuya sorulmadan çalınacağı süre.
+    pub url_revalidate_s: Option<u64>,
+    // Bellekteki çözülmüş anonsların toplam üst sınırı (bayt); 0 ise önceden yüklenmeyenler hep
+    // diskten akıtılır.
+    pub cache_max_bytes: Option<u64>,
+    // Bundan büyük anons dosyaları önbelleğe alınmaz, diskten akıtılır (bayt).
+    pub cache_max_entry_bytes: Option<u64>,
+}
+impl AnnouncementConfig {
+    /// Karşılama anonsunun adı; boş değer anonsun kapalı olduğu anlamına gelir.
+    pub fn welcome_name(&self) -> Option<&str> {
+        self.welcome.as_deref().filter(|name| !name.is_empty())
+    }
+
+    pub fn welcome_bed(&self) -> Option<&str> {
+        self.welcome_bed.as_deref().filter(|name| !name.is_empty())
+    }
+
+    pub fn bed_gain_db(&self) -> f32 {
+        self.bed_gain_db.unwrap_or(DEFAULT_BED_GAIN_DB)
+    }
+
+    pub fn default_language(&self) -> Option<&str> {
+        self.default_language.as_deref().filter(|language| !language.is_empty())
+    }
+
+    pub fn segment_prefix(&self) -> &str {
+        self.segment_prefix.as_deref().unwrap_or(DEFAULT_SEGMENT_PREFIX)
+    }
+
+    pub fn max_queued(&self) -> usize {
+        self.max_queued.unwrap_or(DEFAULT_MAX_QUEUED)
+    }
+
+    pub fn upload_dir(&self) -> Option<&str> {
+        self.upload_dir.as_deref().filter(|dir| !dir.is_empty())
+    }
+
+    pub fn max_upload_bytes(&self) -> u64 {
+        self.max_upload_bytes.unwrap_or(DEFAULT_MAX_UPLOAD_BYTES)
+    }
+
+    pub fn url_cache_dir(&self) -> Option<&str> {
+        self.url_cache_dir.as_deref().filter(|dir| !dir.is_empty())
+    }
+
+    pub fn url_cache_max_bytes(&self) -> u64 {
+        self.url_cache_max_bytes.unwrap_or(DEFAULT_URL_CACHE_MAX_BYTES)
+    }
+
+    pub fn url_revalidate(&self) -> Duration {
+        Duration::from_secs(self.url_revalidate_s.unwrap_or(DEFAULT_URL_REVALIDATE_S))
+    }
+
+    pub fn cache_max_bytes(&self) -> u64 {
+        self.cache_max_bytes.unwrap_or(DEFAULT_CACHE_MAX_BYTES)
+    }
+
+    pub fn cache_max_entry_bytes(&self) -> u64 {
+        self.cache_max_entry_bytes.unwrap_or(DEFAULT_CACHE_MAX_ENTRY_BYTES)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PromptConfig {
+    pub path: String,
+    #[serde(default)]
+    pub gain_db: f32,
+    #[serde(default, rename = "loop")]
+    pub looped: bool,
+    // `path` dosyasının dili; yoksa `announcement.default_language`.
+    pub language: Option<String>,
+    #[serde(default)]
+    pub preload: bool,
+    // Dil -> aynı anonsun o dildeki dosyası; kazanç, döngü ve önceden yükleme ayarları ortaktır.
+    #[serde(default)]
+    pub languages: BTreeMap<String, String>,
+}
+impl PromptConfig {
+    /// Yol bir `http://`, `https://` ya da `s3://bucket/anahtar` adresiyse onu döner; dosya yoluysa
+    /// `None`.
+    pub fn url(&self) -> Option<&str> {
+        url_of(&self.path)
+    }
+}
+
+fn url_of(path: &str) -> Option<&str> {
+    ["http://", "https://", "s3://"].iter().any(|scheme| path.starts_with(scheme)).then_some(path)
+}
+
+/// Dil etiketleri (ör. "tr", "en-GB") olaylarda ve istek alanlarında aynen kullanılır.
+fn valid_language(language: &str) -> bool {
+    (1..=MAX_LANGUAGE_LEN).contains(&language.len()) && language.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat { #[default] Text, Json, }
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LogConfig {
+    #[serde(default)]
+    pub format: LogFormat,
+    // EnvFilter söz dizimi: "info", "debug", "info,media=trace" ...
+    #[serde(default = "default_log_level")]
+    pub level: String,
+}
+impl Default for LogConfig {
+    fn default() -> Self { Self { format: LogFormat::default(), level: default_log_level() } }
+}
+fn default_log_level() -> String { "info".to_string() }
+// Zamanlayıcılar için üst sınır (bir gün); daha büyük değerler büyük ihtimalle birim hatasıdır.
+const MAX_TIMER_SECS: u64 = 86_400;
+
+// Bundan kısa sessizlik eşikleri sıradan jitter'ı ve kayıpları yeni akış sanar.
+const MIN_NEW_STREAM_GAP_MS: u64 = 200;
+
+// Birkaç paketlik pcap dosyası için gereken en küçük boyut.
+const MIN_CAPTURE_BYTES: u64 = 4096;
+// Fon müziği konuşmanın belirgin biçimde altında kalsın.
+const DEFAULT_BED_GAIN_DB: f32 = -18.0;
+const DEFAULT_SEGMENT_PREFIX: &str = "say_";
+const DEFAULT_MAX_QUEUED: usize = 8;
+
+// BCP 47 etiketlerinin pratikte kullanılan uzunluğu.
+const MAX_LANGUAGE_LEN: usize = 16;
+// Kayıt şifreleme anahtarının adı şifreli dosyanın başlığında tek baytlık uzunlukla tutulur.
+const MAX_KEY_ID_LEN: usize = 64;
+// SDES öğesinin uzunluğu tek bayttır.
+const MAX_SDES_TEXT_LEN: usize = 255;
+
+// WAV başlığı ve bir saniyelik 8 kHz ses.
+const MIN_AUDIO_DUMP_BYTES: u64 = 44 + 16_000;
+
+// Yüklenen anonslar için: 10 MiB, 8 kHz 16 bit monoda yaklaşık 11 dakika. Alt sınır WAV başlığı
+// ve birkaç çerçevelik ses içindir.
+const DEFAULT_MAX_UPLOAD_BYTES: u64 = 10 * 1024 * 1024;
+const MIN_UPLOAD_BYTES: u64 = 4096;
+const DEFAULT_URL_CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+const DEFAULT_URL_REVALIDATE_S: u64 = 300;
+// Anons önbelleği: 64 MiB yaklaşık 70 dakikalık 8 kHz ses; tek girdi en fazla 4 MiB (~4 dakika).
+const DEFAULT_CACHE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_CACHE_MAX_ENTRY_BYTES: u64 = 4 * 1024 * 1024;
+
+// Bildirim kancası sınırları; daha uzun süren bir deneme kuyruğu tıkar.
+const MAX_HOOK_TIMEOUT_MS: u64 = 60_000;
+const MAX_HOOK_CONCURRENCY: usize = 64;
+// Yük örneklemesi: 100 ms'den sık örnek CPU ölçümünü tick çözünürlüğüne boğar, dakikadan seyreği
+// aşırı yüke geç tepki verir. Gecikme eşiği media_send_loop_lag_seconds'ın en büyük kovasından küçük olmalı.
+const MIN_ADMISSION_INTERVAL_MS: u64 = 100;
+const MAX_ADMISSION_INTERVAL_MS: u64 = 60_000;
+const MAX_ADMISSION_SEND_LAG_MS: u64 = 1000;
+// Büyük bir kaydın tek PUT'u için üst sınır.
+const MAX_STORAGE_TIMEOUT_MS: u64 = 600_000;
+// ASR tamponu: birkaç çerçeveden kısası her takılmada ses kaybettirir, dakikadan uzunu canlı
+// transkripsiyonu anlamsız kılar.
+const MIN_ASR_BUFFER_MS: u64 = 100;
+const MAX_ASR_BUFFER_MS: u64 = 60_000;
+
+// Daha uzun bekleme süresi bastırmayı cümle aralarında hiç devreye sokmaz.
+const MAX_HANGOVER_MS: u64 = 5_000;
+
+// Gelen paket sınırının kova boyutu alt sınırı; daha azını birkaç paketlik jitter kümelenmesi aşar.
+const MIN_INBOUND_BURST: u32 = 10;
+
+// Boyutla döndürülen CDR dosyasının alt sınırı; daha küçüğü her kayıtta yeni dosya açtırır.
+const MIN_CDR_FILE_BYTES: u64 = 4096;
+// Port günlüğü dosyasının alt sınırı; aynı gerekçeyle.
+const MIN_PORT_JOURNAL_FILE_BYTES: u64 = 4096;
+
+// Telesekreter algılamanın penceresi; kısası kararı görmeden biter, uzunu aramayı bekletir.
+const MIN_AMD_WINDOW_MS: u64 = 1_000;
+const MAX_AMD_WINDOW_MS: u64 = 30_000;
+
+/// Desteklenen paketleme süreleri (ms).
+pub const SUPPORTED_PTIMES: [u64; 4] = [10, 20, 30, 40];
+
+// Zamanlayıcılar; 0 değerli zaman aşımları devre dışı demektir.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(default)]
+pub struct TimersConfig {
+    pub ptime_ms: u64,
+    pub media_timeout_s: u64,
+    pub first_packet_timeout_s: u64,
+    pub keepalive_interval_s: u64,
+    pub shutdown_grace_s: u64,
+    // Kapanış sinyalinden çıkışa kadar geçebilecek en uzun süre; dolunca kalan oturumlar kesilir.
+    pub shutdown_deadline_s: u64,
+    pub heartbeat_interval_s: u64,
+    // Bu süreyi aşan port tahsisleri uyarı olarak loglanır.
+    pub slow_allocation_ms: u64,
+    // Gelen akışta bu kadar ms sessizlikten sonra gelen paketler yeni akış sayılır; 0 kapatır.
+    pub new_stream_gap_ms: u64,
+    // Oturumun tahsisten itibaren en uzun ömrü; AllocatePort max_duration_s ile oturum başına ezilir.
+    pub max_session_duration_s: u64,
+    // Kapanıştan bu kadar önce session_expiring olayı yazılır.
+    pub max_duration_warning_s: u64,
+}
+impl Default for TimersConfig {
+    fn default() -> Self {
+        Self {
+            ptime_ms: 20, media_timeout_s: 0, first_packet_timeout_s: 0, keepalive_interval_s: 0, shutdown_grace_s: 0, heartbeat_interval_s: 60, slow_allocation_ms: 100, new_stream_gap_ms: 0,
+            shutdown_deadline_s: 25, max_session_duration_s: 0, max_duration_warning_s: 60,
+        }
+    }
+}
+impl TimersConfig {
+    pub fn ptime(&self) -> Duration { Duration::from_millis(self.ptime_ms) }
+    pub fn media_timeout(&self) -> Option<Duration> { non_zero_secs(self.media_timeout_s) }
+    pub fn first_packet_timeout(&self) -> Option<Duration> { non_zero_secs(self.first_packet_timeout_s) }
+    pub fn keepalive_interval(&self) -> Option<Duration> { non_zero_secs(self.keepalive_interval_s) }
+    pub fn shutdown_grace(&self) -> Option<Duration> { non_zero_secs(self.shutdown_grace_s) }
+    pub fn shutdown_deadline(&self) -> Option<Duration> { non_zero_secs(self.shutdown_deadline_s) }
+    pub fn heartbeat_interval(&self) -> Option<Duration> { non_zero_secs(self.heartbeat_interval_s) }
+    pub fn slow_allocation(&self) -> Option<Duration> { (self.slow_allocation_ms > 0).then(|| Duration::from_millis(self.slow_allocation_ms)) }
+    pub fn new_stream_gap(&self) -> Option<Duration> { (self.new_stream_gap_ms > 0).then(|| Duration::from_millis(self.new_stream_gap_ms)) }
+    pub fn max_session_duration(&self) -> Option<Duration> { non_zero_secs(self.max_session_duration_s) }
+    pub fn max_duration_warning(&self) -> Option<Duration> { non_zero_secs(self.max_duration_warning_s) }
+}
+fn non_zero_secs(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// `metrics.session_label_values` için üst sınır.
+const MAX_SESSION_LABEL_VALUES: usize = 1000;
+
+/// Metriklerin nereye ihraç edileceği; aynı sayaçların iki yoldan birden gitmemesi için tek seçim.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsExporter { #[default] Prometheus, Otlp, }
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub exporter: MetricsExporter,
+    // Yalnızca prometheus ihracında kullanılır.
+    pub bind: String,
+    // Prometheus etiketi olarak yayınlanan oturum etiketi anahtarları; boşsa hiçbiri.
+    pub session_labels: Vec<String>,
+    // Anahtar başına ayrı seri alan en fazla değer; sonrakiler "other" serisinde toplanır.
+    pub session_label_values: usize,
+}
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true, exporter: MetricsExporter::default(), bind: "127.0.0.1:9090".to_string(),
+            session_labels: Vec::new(), session_label_values: 20,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct RtcpConfig {
+    // Giden SDES'teki CNAME; boşsa `kullanıcı@makine`.
+    pub cname: String,
+    // Oturum boyunca SR ya da RR gönderme aralığı; 0 ise yalnızca kapanışta gönderilir.
+    pub report_interval_s: u64,
+}
+impl Default for RtcpConfig {
+    fn default() -> Self { Self { cname: String::new(), report_interval_s: 5 } }
+}
+impl RtcpConfig {
+    pub fn report_interval(&self) -> Option<Duration> {
+        non_zero_secs(self.report_interval_s)
+    }
+
+    pub fn cname(&self) -> std::borrow::Cow<'_, str> {
+        match self.cname.trim() {
+            "" => rtcp::default_cname().into(),
+            cname => cname.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct HealthConfig {
+    pub enabled: bool,
+    // metrics.bind ile aynıysa iki uç nokta tek dinleyiciyi paylaşır.
+    pub bind: String,
+}
+impl Default for HealthConfig {
+    fn default() -> Self { Self { enabled: true, bind: "127.0.0.1:9090".to_string() } }
+}
+
+/// `[admission]`: node aşırı yüklüyken yeni oynatmaların reddi (bkz. admission.rs).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct AdmissionConfig {
+    // Kapalıysa yük örneklenmez ve hiçbir oynatma reddedilmez.
+    pub enabled: bool,
+    // CPU kullanımı ve gönderim gecikmesi bu aralıkla örneklenir.
+    pub interval_ms: u64,
+    // Sürecin CPU kullanımı, bütün çekirdeklerin yüzdesi olarak; 0 bu eşiği kapatır.
+    pub max_cpu_percent: f64,
+    // Aralıktaki gönderim döngüsü gecikmesinin 95. yüzdeliği; 0 bu eşiği kapatır.
+    pub max_send_lag_ms: u64,
+    // Bozulmuş durumdan çıkmak için eşiklerin altında kalan ardışık örnek sayısı.
+    pub recovery_samples: u32,
+    // Bozulmuşken karşılama anonsu reddedilmek yerine atlanır; kapalıysa her zaman çalınır.
+    pub skip_welcome: bool,
+}
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self { enabled: false, interval_ms: 1000, max_cpu_percent: 90.0, max_send_lag_ms: 20, recovery_samples: 3, skip_welcome: true }
+    }
+}
+impl AdmissionConfig {
+    pub fn interval(&self) -> Duration { Duration::from_millis(self.interval_ms) }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    // OTLP/gRPC collector adresi.
+    pub endpoint: String,
+    pub service_name: String,
+}
+impl Default for TelemetryConfig {
+    fn default() -> Self { Self { enabled: false, endpoint: "http://127.0.0.1:4317".to_string(), service_name: "media".to_string() } }
+}
+
+// Gelen akış kalitesi izleme eşikleri.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(default)]
+pub struct QualityConfig {
+    // Uzak saat sapması bu değeri (ppm) aşınca uyarı olayı yazılır; 0 kapatır.
+    pub skew_warning_ppm: u32,
+    // Paket gönderirken bu kadar saniye hiç paket gelmezse tek yönlü ses şüphesi yazılır; 0 kapatır.
+    pub one_way_inbound_s: u64,
+    // Paket gelirken bu kadar saniye her gönderim başarısız olursa aynısı; 0 kapatır.
+    pub one_way_outbound_s: u64,
+}
+impl Default for QualityConfig {
+    fn default() -> Self { Self { skew_warning_ppm: 500, one_way_inbound_s: 10, one_way_outbound_s: 5 } }
+}
+impl QualityConfig {
+    pub fn skew_warning(&self) -> Option<f64> { (self.skew_warning_ppm > 0).then_some(self.skew_warning_ppm as f64) }
+    pub fn one_way_inbound(&self) -> Option<Duration> { non_zero_secs(self.one_way_inbound_s) }
+    pub fn one_way_outbound(&self) -> Option<Duration> { non_zero_secs(self.one_way_outbound_s) }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct CaptureConfig {
+    // pcap dosyalarının yazılacağı dizin (yoksa oluşturulur).
+    pub directory: String,
+    // Dosya başına üst sınır; dolunca o oturumun yakalaması durur.
+    pub max_file_bytes: u64,
+}
+impl Default for CaptureConfig {
+    fn default() -> Self { Self { directory: "captures".to_string(), max_file_bytes: 10 * 1024 * 1024 } }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct StateDumpConfig {
+    // SIGUSR1 ile yazılan durum dökümlerinin dizini (yoksa oluşturulur).
+    pub directory: String,
+}
+impl Default for StateDumpConfig {
+    fn default() -> Self { Self { directory: "state_dumps".to_string() } }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct AudioDumpConfig {
+    // StartAudioDump ile açılan çözülmüş ses dökümlerinin dizini (yoksa oluşturulur).
+    pub directory: String,
+    // Dosya başına üst sınır; dolunca o yönün dökümü durur.
+    pub max_file_bytes: u64,
+    // Aynı anda döken en fazla oturum; aşan istekler reddedilir, 0 dökümü kapatır.
+    pub max_sessions: usize,
+}
+impl Default for AudioDumpConfig {
+    fn default() -> Self { Self { directory: "audio_dumps".to_string(), max_file_bytes: 50 * 1024 * 1024, max_sessions: 2 } }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct RecordingConfig {
+    // Kayıtların yazılacağı dizin (yoksa oluşturulur); isimler buna göre görecelidir.
+    pub directory: String,
+    // {session_id}, {call_id}, {port}, {start_time} yer tutucularıyla dosya adı; uzantı yoksa
+    // biçimin uzantısı eklenir.
+    pub filename_template: String,
+    // StartRecording'de biçim verilmezse kullanılan.
+    pub format: RecordingFormat,
+    // Bu süreye (saniye) ulaşan kayıt numaralı bir devam dosyasına geçer; 0 sınırsız.
+    pub max_duration_s: u64,
+    // Dizindeki toplam boyut bu değere ulaşınca yeni kayıt reddedilir, sürenler durur; 0 sınırsız.
+    pub max_total_bytes: u64,
+    pub encryption: RecordingEncryptionConfig,
+}
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            directory: "recordings".to_string(), filename_template: "{session_id}_{start_time}".to_string(), format: RecordingFormat::Pcm16Wav,
+            max_duration_s: 0, max_total_bytes: 0, encryption: RecordingEncryptionConfig::default(),
+        }
+    }
+}
+
+/// Kayıt dosyasının biçimi.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecordingFormat {
+    // 16 bit lineer PCM WAV.
+    #[default] Pcm16Wav,
+    // G.711 µ-law WAV (biçim etiketi 7); örnek başına bir bayt.
+    UlawWav,
+    // G.711 A-law WAV (biçim etiketi 6).
+    AlawWav,
+    // Başlıksız µ-law (.ulaw).
+    RawUlaw,
+}
+
+impl RecordingFormat {
+    pub const ALL: [RecordingFormat; 4] = [RecordingFormat::Pcm16Wav, RecordingFormat::UlawWav, RecordingFormat::AlawWav, RecordingFormat::RawUlaw];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RecordingFormat::Pcm16Wav => "pcm16-wav",
+            RecordingFormat::UlawWav => "ulaw-wav",
+            RecordingFormat::AlawWav => "alaw-wav",
+            RecordingFormat::RawUlaw => "raw-ulaw",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.as_str() == name)
+    }
+
+    /// Şablonda uzantı yoksa dosya adına eklenen.
+    pub fn extension(self) -> &'static str {
+        match self {
+            RecordingFormat::RawUlaw => "ulaw",
+            _ => "wav",
+        }
+    }
+
+    /// Dosyanın G.711 yasasıyla kodlanan codec; oturum bu codec'teyse gelen yük çözülmeden yazılır.
+    pub fn g711_codec(self) -> Option<&'static str> {
+        match self {
+            RecordingFormat::Pcm16Wav => None,
+            RecordingFormat::UlawWav | RecordingFormat::RawUlaw => Some("pcmu"),
+            RecordingFormat::AlawWav => Some("pcma"),
+        }
+    }
+}
+
+/// Kayıtların diskte şifrelenmesi (recording-encryption feature'ı gerekir).
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct RecordingEncryptionConfig {
+    pub enabled: bool,
+    // Şifreli dosyaya ve oturum özetine yazılan anahtar adı; anahtar döndürülünce değişir.
+    pub key_id: String,
+    // 64 onaltılık karakterlik (256 bit) anahtarı tutan dosya, ör. KMS'in bağladığı bir sır.
+    pub key_file: String,
+}
+impl RecordingConfig {
+    pub fn max_duration(&self) -> Option<Duration> { non_zero_secs(self.max_duration_s) }
+    pub fn max_total_bytes(&self) -> Option<u64> { (self.max_total_bytes > 0).then_some(self.max_total_bytes) }
+}
+
+/// Köprülenen bacaklar arasında RFC 4733 tuşlarının nasıl taşınacağı.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DtmfMode {
+    // Olay paketleri yük tipi, sıra numarası ve zaman damgası çevrilerek aynen aktarılır.
+    #[default] Relay,
+    // Olay çözülür ve karşı bacakta kendi süresiyle yeniden üretilir.
+    Regenerate,
+}
+
+/// Yeniden köprülemede karşı bacağı başka bir oturuma geçen, köprüsüz kalan bacağa ya da
+/// SetDirection(sendonly) ile bekletilen uca gönderilen dolgu ses.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DetachedAudio {
+    // Hiçbir şey gönderilmez.
+    None,
+    // Bacak yeniden köprülenene, bekletme bitene ya da oturum kapanana kadar sessizlik.
+    #[default] Silence,
+    // `detached_prompt` ya da `hold.prompt` anonsu; bekleme müziği için anons `loop = true` tanımlanır.
+    Prompt,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct BridgeConfig {
+    pub dtmf: DtmfMode,
+    pub detached_audio: DetachedAudio,
+    // `detached_audio = "prompt"` iken çalınacak anonsun adı.
+    pub detached_prompt: Option<String>,
+    // Bir oturuma TapSession ile eklenebilecek dinleme bacağı sayısı; 0 dinlemeyi kapatır.
+    pub max_taps: usize,
+}
+impl Default for BridgeConfig {
+    fn default() -> Self { Self { dtmf: DtmfMode::default(), detached_audio: DetachedAudio::default(), detached_prompt: None, max_taps: 2 } }
+}
+
+/// `[hold]`: SetDirection(sendonly) ile bekletilen uca, çalan bir şey yoksa giden ses.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct HoldConfig {
+    pub audio: DetachedAudio,
+    // `audio = "prompt"` iken çalınacak anonsun adı.
+    pub prompt: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(default)]
+pub struct SilenceSuppressionConfig {
+    // Açıksa CN anlaşılan oturumlarda (AllocatePort comfort_noise) giden sessizlik gönderilmez.
+    pub enabled: bool,
+    // Bu seviyenin (dBov) altındaki çerçeveler sessizlik sayılır.
+    pub threshold_dbov: i32,
+    // Konuşma bittikten sonra bastırmaya geçmeden önce göndermeye devam edilen süre.
+    pub hangover_ms: u64,
+    // Anonslar da bastırılsın mı; kapalıyken anonslar her zaman eksiksiz gönderilir.
+    pub announcements: bool,
+}
+impl Default for SilenceSuppressionConfig {
+    fn default() -> Self { Self { enabled: false, threshold_dbov: -50, hangover_ms: 200, announcements: false } }
+}
+impl SilenceSuppressionConfig {
+    pub fn hangover(&self) -> Duration { Duration::from_millis(self.hangover_ms) }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(default)]
+pub struct FaxDetectionConfig {
+    // Faks tonu algılanınca çalan anons durdurulur ve kuyruk boşaltılır.
+    pub stop_playback: bool,
+    // Bu seviyenin (dBov) altındaki ses ton sayılmaz.
+    pub min_level_dbov: i32,
+}
+impl Default for FaxDetectionConfig {
+    fn default() -> Self { Self { stop_playback: false, min_level_dbov: -45 } }
+}
+
+/// Telesekreter algılamanın eşikleri (bkz. amd.rs).
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(default)]
+pub struct AmdConfig {
+    // Bu seviyenin (dBov) üstündeki çerçeveler konuşma sayılır.
+    pub threshold_dbov: i32,
+    // Analizin en uzun süresi; dolunca sonuç not_sure olur ve analiz biter.
+    pub window_ms: u64,
+    pub initial_silence_ms: u64,
+    pub greeting_ms: u64,
+    pub after_greeting_silence_ms: u64,
+    pub min_word_ms: u64,
+    pub between_words_silence_ms: u64,
+    pub max_words: u32,
+}
+impl Default for AmdConfig {
+    fn default() -> Self {
+        Self {
+            threshold_dbov: -40, window_ms: 5000, initial_silence_ms: 2500, greeting_ms: 1500, after_greeting_silence_ms: 800,
+            min_word_ms: 100, between_words_silence_ms: 50, max_words: 3,
+        }
+    }
+}
+
+/// Gelen paket seli `flood_trip_s` boyunca sürerse ne yapılacağı.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FloodAction {
+    // Yalnızca fazla paketler atılmaya devam eder.
+    #[default] None,
+    // Oturum `inbound_flood` sebebiyle kapatılır.
+    Teardown,
+    // Seli gönderen adresin paketleri oturum boyunca atılır.
+    Block,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    // Saniyede kabul edilen AllocatePort isteği; 0 sınırsız.
+    pub allocations_per_s: u32,
+    // Boşta biriken ve art arda harcanabilen istek sayısı.
+    pub allocation_burst: u32,
+    // Oturum başına saniyede kabul edilen gelen paket; fazlası ayrıştırılmadan atılır. 0 sınırsız.
+    pub inbound_packets_per_s: u32,
+    // Jitter kümelenmesiyle art arda gelebilecek paket sayısı; sınır bunlara hiç dokunmaz.
+    pub inbound_burst: u32,
+    // Sınırlama bu kadar saniye kesintisiz sürerse `flood_action` uygulanır.
+    pub flood_trip_s: u64,
+    pub flood_action: FloodAction,
+}
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self { allocations_per_s: 0, allocation_burst: 20, inbound_packets_per_s: 0, inbound_burst: 50, flood_trip_s: 10, flood_action: FloodAction::None }
+    }
+}
+impl RateLimitConfig {
+    pub fn flood_trip(&self) -> Option<Duration> {
+        (self.flood_action != FloodAction::None).then(|| non_zero_secs(self.flood_trip_s)).flatten()
+    }
+}
+
+/// Oturum kapanınca istatistiklerin nereye bildirileceği.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HookKind {
+    #[default] None,
+    // `url` adresine JSON gövdeli HTTP POST.
+    Http,
+    // `command` çalıştırılır, JSON stdin'den verilir.
+    Command,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct HookConfig {
+    pub kind: HookKind,
+    // Yalnızca http://; TLS için yerel bir vekil kullanın.
+    pub url: String,
+    // Program ve argümanları; kabuk üzerinden çalıştırılmaz.
+    pub command: Vec<String>,
+    // Tek bir denemenin üst sınırı; aşılırsa istek iptal edilir, komut öldürülür.
+    pub timeout_ms: u64,
+    // İlk başarısız denemeden sonra kaç kez daha deneneceği.
+    pub retries: u32,
+    // Bekleyen bildirim sınırı; dolunca yenileri düşürülür.
+    pub queue_size: usize,
+    // Aynı anda en fazla kaç bildirimin işleneceği.
+    pub concurrency: usize,
+}
+impl Default for HookConfig {
+    fn default() -> Self {
+        Self { kind: HookKind::None, url: String::new(), command: Vec::new(), timeout_ms: 5000, retries: 3, queue_size: 1000, concurrency: 4 }
+    }
+}
+impl HookConfig {
+    pub fn timeout(&self) -> Duration { Duration::from_millis(self.timeout_ms) }
+}
+
+/// `[dtmf_hook]`: algılanan her DTMF tuşunun POST edildiği uç nokta (bkz. dtmf_hook.rs).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct DtmfHookConfig {
+    // Yalnızca http://; AllocatePort'ta dtmf_hook_url verilmeyen oturumlar için. Boşsa yalnızca
+    // adresi tahsiste verilen oturumların tuşları bildirilir.
+    pub url: String,
+    // Tek bir denemenin üst sınırı.
+    pub timeout_ms: u64,
+    // İlk başarısız denemeden sonra kaç kez daha deneneceği.
+    pub retries: u32,
+    // Bekleyen bildirim sınırı; dolunca yenileri düşürülür.
+    pub queue_size: usize,
+    // Aynı anda en fazla kaç bildirimin işleneceği.
+    pub concurrency: usize,
+}
+impl Default for DtmfHookConfig {
+    fn default() -> Self {
+        Self { url: String::new(), timeout_ms: 2000, retries: 2, queue_size: 1000, concurrency: 4 }
+    }
+}
+impl DtmfHookConfig {
+    pub fn timeout(&self) -> Duration { Duration::from_millis(self.timeout_ms) }
+}
+
+/// Oturum sonu kayıtlarının (CDR) biçimi.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CdrFormat {
+    #[default] None,
+    // Satır başına bir JSON nesnesi (NDJSON).
+    Json,
+    // Başlık satırlı CSV; dosya her açıldığında boşsa başlık yazılır.
+    Csv,
+}
+
+/// CDR dosyasının diske ne sıklıkla zorlanacağı.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CdrFsync {
+    // Her kayıttan sonra; çökmede en fazla yazılmakta olan kayıt kaybolur.
+    Always,
+    // Kuyrukta bekleyen kayıtlar yazıldıktan sonra bir kez.
+    #[default] Batch,
+    // Hiç; işletim sistemine bırakılır.
+    Never,
+}
+
+/// CDR dosyasının ne zaman kenara alınıp yenisinin açılacağı.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CdrRotation {
+    #[default] None,
+    // UTC gün değişince.
+    Daily,
+    // Dosya `max_file_bytes`'a ulaşınca.
+    Size,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct CdrConfig {
+    pub format: CdrFormat,
+    // Kayıtların eklendiği dosya (dizini yoksa oluşturulur); döndürülen dosyalar yanına
+    // `{path}.{unix_saniye}` adıyla alınır.
+    pub path: String,
+    pub fsync: CdrFsync,
+    pub rotate: CdrRotation,
+    // `rotate = "size"` iken dosya başına üst sınır.
+    pub max_file_bytes: u64,
+    // Yazılmayı bekleyen kayıt sınırı; dolunca yenileri düşürülür.
+    pub queue_size: usize,
+}
+impl Default for CdrConfig {
+    fn default() -> Self {
+        Self {
+            format: CdrFormat::None, path: "cdr/media.cdr".to_string(), fsync: CdrFsync::Batch, rotate: CdrRotation::None,
+            max_file_bytes: 100 * 1024 * 1024, queue_size: 10_000,
+        }
+    }
+}
+
+/// `[port_journal]`: hangi oturumun hangi portu ne zaman kullandığının kalıcı kaydı (bkz. port_journal.rs).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct PortJournalConfig {
+    pub enabled: bool,
+    // Kayıtların eklendiği dosya (dizini yoksa oluşturulur); döndürülen dosyalar yanına
+    // `{path}.1` (en yeni) ... `{path}.{max_files}` adıyla alınır.
+    pub path: String,
+    // Dosya bu boyuta ulaşınca döndürülür.
+    pub max_file_bytes: u64,
+    // Saklanan döndürülmüş dosya sayısı; en eskisi silinir.
+    pub max_files: u32,
+    // Yazılmayı bekleyen kayıt sınırı; dolunca yenileri düşürülür.
+    pub queue_size: usize,
+    // QueryPortHistory için bellekte tutulan son kayıtlar; henüz yazılmamış kayıtlar da buradan okunur.
+    pub recent_entries: usize,
+}
+impl Default for PortJournalConfig {
+    fn default() -> Self {
+        Self { enabled: false, path: "journal/ports.log".to_string(), max_file_bytes: 10 * 1024 * 1024, max_files: 10, queue_size: 10_000, recent_entries: 10_000 }
+    }
+}
+
+/// Nesne deposu kimlik bilgilerinin kaynağı.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectStorageAuth {
+    // access_key_id ve secret_access_key.
+    #[default] Static,
+    // EC2 instance metadata servisinden (IMDSv2) alınan geçici kimlik.
+    InstanceProfile,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ObjectStorageConfig {
+    pub enabled: bool,
+    // S3 uyumlu uç nokta; yalnızca http://, path-style adresleme (uç/bucket/anahtar).
+    pub endpoint: String,
+    pub region: String,
+    pub auth: ObjectStorageAuth,
+    pub access_key_id: String,
+    #[serde(serialize_with = "redacted")]
+    pub secret_access_key: String,
+    // auth = "instance_profile" için metadata servisinin adresi.
+    pub metadata_endpoint: String,
+    // Biten kayıtların yükleneceği bucket; boşsa kayıtlar yüklenmez.
+    pub recording_bucket: String,
+    // Nesne anahtarı bu önek ve kaydın recording.directory'ye göre yolu.
+    pub recording_prefix: String,
+    // Yüklenen kaydın yerel kopyası silinsin mi.
+    pub delete_uploaded: bool,
+    // Tek bir isteğin üst sınırı.
+    pub timeout_ms: u64,
+    // İlk başarısız denemeden sonra kaç kez daha deneneceği.
+    pub retries: u32,
+    // Denemeleri tükenen kayıtların taşındığı dizin; boşsa kayıt yerinde kalır ve yeniden denenmez.
+    pub spool_dir: String,
+    // Spool dizinindeki kayıtların yeniden deneme aralığı.
+    pub spool_retry_s: u64,
+    // Aynı anda en fazla kaç yüklemenin süreceği.
+    pub concurrency: usize,
+}
+impl Default for ObjectStorageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, endpoint: String::new(), region: "us-east-1".to_string(), auth: ObjectStorageAuth::Static,
+            access_key_id: String::new(), secret_access_key: String::new(), metadata_endpoint: "http://169.254.169.254".to_string(),
+            recording_bucket: String::new(), recording_prefix: String::new(), delete_uploaded: false,
+            timeout_ms: 30_000, retries: 3, spool_dir: String::new(), spool_retry_s: 60, concurrency: 4,
+        }
+    }
+}
+impl ObjectStorageConfig {
+    pub fn timeout(&self) -> Duration { Duration::from_millis(self.timeout_ms) }
+    pub fn spool_retry(&self) -> Duration { Duration::from_secs(self.spool_retry_s.max(1)) }
+}
+// Gizli anahtar başlangıçta yazılan config satırına düşmemeli.
+impl std::fmt::Debug for ObjectStorageConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectStorageConfig")
+            .field("enabled", &self.enabled)
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("auth", &self.auth)
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &if self.secret_access_key.is_empty() { "" } else { "<redacted>" })
+            .field("metadata_endpoint", &self.metadata_endpoint)
+            .field("recording_bucket", &self.recording_bucket)
+            .field("recording_prefix", &self.recording_prefix)
+            .field("delete_uploaded", &self.delete_uploaded)
+            .field("timeout_ms", &self.timeout_ms)
+            .field("retries", &self.retries)
+            .field("spool_dir", &self.spool_dir)
+            .field("spool_retry_s", &self.spool_retry_s)
+            .field("concurrency", &self.concurrency)
+            .finish()
+    }
+}
+
+/// `[asr]`: AllocatePort(asr) oturumlarının gelen sesini akıtan dış tanıma servisi (proto/asr.proto).
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct AsrConfig {
+    pub enabled: bool,
+    // Recognizer servisinin adresi; yalnızca http://.
+    pub endpoint: String,
+    // Boş değilse akış `authorization: Bearer <token>` metadata'sıyla açılır.
+    #[serde(serialize_with = "redacted")]
+    pub auth_token: String,
+    // Servise iletilen dil; boşsa servisin varsayılanı.
+    pub language: String,
+    // false ise yalnızca kesin sonuçlar istenir.
+    pub interim_results: bool,
+    // Servise gönderilmeyi bekleyen en fazla ses; aşılınca en eski çerçeveler düşer.
+    pub buffer_ms: u64,
+    pub connect_timeout_ms: u64,
+    // Kopan bağlantının ilk yeniden deneme aralığı; her denemede ikiye katlanır.
+    pub retry_initial_ms: u64,
+    pub retry_max_ms: u64,
+}
+impl Default for AsrConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, endpoint: String::new(), auth_token: String::new(), language: String::new(), interim_results: true,
+            buffer_ms: 5000, connect_timeout_ms: 3000, retry_initial_ms: 500, retry_max_ms: 30_000,
+        }
+    }
+}
+impl AsrConfig {
+    pub fn connect_timeout(&self) -> Duration { Duration::from_millis(self.connect_timeout_ms.max(1)) }
+    pub fn retry_initial(&self) -> Duration { Duration::from_millis(self.retry_initial_ms.max(1)) }
+    pub fn retry_max(&self) -> Duration { Duration::from_millis(self.retry_max_ms).max(self.retry_initial()) }
+    /// `buffer_ms` kadar sesin `sample_rate`'teki örnek sayısı.
+    pub fn buffer_samples(&self, sample_rate: u32) -> usize { (self.buffer_ms * sample_rate as u64 / 1000) as usize }
+}
+// Jeton başlangıçta yazılan config satırına düşmemeli.
+impl std::fmt::Debug for AsrConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsrConfig")
+            .field("enabled", &self.enabled)
+            .field("endpoint", &self.endpoint)
+            .field("auth_token", &if self.auth_token.is_empty() { "" } else { "<redacted>" })
+            .field("language", &self.language)
+            .field("interim_results", &self.interim_results)
+            .field("buffer_ms", &self.buffer_ms)
+            .field("connect_timeout_ms", &self.connect_timeout_ms)
+            .field("retry_initial_ms", &self.retry_initial_ms)
+            .field("retry_max_ms", &self.retry_max_ms)
+            .finish()
+    }
+}
+
+// Gizli değerler DumpState çıktısına da düşmemeli; yalnızca tanımlı olup olmadıkları görünür.
+fn redacted<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(if value.is_empty() { "" } else { "<redacted>" })
+}
+
+/// `[[tenants]]`: RTP aralığının bir müşteriye ayrılmış parçası.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct TenantConfig {
+    // Harf, rakam, '-' ve '_'; metrik etiketlerinde ve olaylarda kullanılır.
+    pub name: String,
+    // rtp.min_port-rtp.max_port içinde, diğer kiracılarla çakışmayan aralık; yalnızca bu kiracının
+    // tahsisleri buradan port alır, taşma aralığına geçilmez.
+    pub min_port: u16,
+    pub max_port: u16,
+    // Aynı anda en fazla kaç oturum; 0 sınırsız.
+    pub max_sessions: u32,
+    // Boş değilse tahsis isteği `authorization: Bearer <token>` metadata'sıyla gelmeli.
+    #[serde(serialize_with = "redacted")]
+    pub auth_token: String,
+}
+impl TenantConfig {
+    pub fn max_sessions(&self) -> Option<u32> { (self.max_sessions > 0).then_some(self.max_sessions) }
+}
+// Jeton başlangıçta yazılan config satırına düşmemeli.
+impl std::fmt::Debug for TenantConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantConfig")
+            .field("name", &self.name)
+            .field("min_port", &self.min_port)
+            .field("max_port", &self.max_port)
+            .field("max_sessions", &self.max_sessions)
+            .field("auth_token", &if self.auth_token.is_empty() { "" } else { "<redacted>" })
+            .finish()
+    }
+}
+
+/// `[[interfaces]]`: çok bacaklı sunucularda AllocatePort'ta adıyla seçilen yerel RTP adresi.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct InterfaceConfig {
+    // Harf, rakam, '-' ve '_'; AllocatePort'ta, olaylarda ve GetServerStatus'ta kullanılır.
+    pub name: String,
+    // Oturum soketlerinin bağlandığı yerel IP; medya bu adresin arayüzünden çıkar.
+    pub address: String,
+    // SDP'de duyurulan adres (NAT arkasında public IP); boşsa `address`.
+    pub advertise_address: String,
+    // rtp.min_port-rtp.max_port içinde alt aralık; ikisi de 0 ise bütün RTP aralığı.
+    pub min_port: u16,
+    pub max_port: u16,
+}
+impl InterfaceConfig {
+    pub fn advertise_address(&self) -> Option<IpAddr> {
+        let address = if self.advertise_address.is_empty() { &self.address } else { &self.advertise_address };
+        address.parse().ok()
+    }
+
+    /// Arayüzün port aralığı; alt aralık verilmediyse RTP aralığı.
+    pub fn port_range(&self, rtp: &RtpConfig) -> (u16, u16) {
+        match (self.min_port, self.max_port) {
+            (0, 0) => (rtp.min_port, rtp.max_port),
+            range => range,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Settings {
+    pub grpc: GrpcConfig,
+    pub rtp: RtpConfig,
+    #[serde(default)]
+    pub announcement: AnnouncementConfig,
+    #[serde(default)]
+    pub log: LogConfig,
+    #[serde(default)]
+    pub timers: TimersConfig,
+    #[serde(default)]
+    pub quality: QualityConfig,
+    #[serde(default)]
+    pub rtcp: RtcpConfig,
+    #[serde(default)]
+    pub bridge: BridgeConfig,
+    #[serde(default)]
+    pub hold: HoldConfig,
+    #[serde(default)]
+    pub silence_suppression: SilenceSuppressionConfig,
+    #[serde(default)]
+    pub fax_detection: FaxDetectionConfig,
+    #[serde(default)]
+    pub amd: AmdConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub capture: CaptureConfig,
+    #[serde(default)]
+    pub audio_dump: AudioDumpConfig,
+    #[serde(default)]
+    pub state_dump: StateDumpConfig,
+    #[serde(default)]
+    pub recording: RecordingConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub hook: HookConfig,
+    #[serde(default)]
+    pub dtmf_hook: DtmfHookConfig,
+    #[serde(default)]
+    pub cdr: CdrConfig,
+    #[serde(default)]
+    pub port_journal: PortJournalConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub health: HealthConfig,
+    #[serde(default)]
+    pub admission: AdmissionConfig,
+    #[serde(default)]
+    pub object_storage: ObjectStorageConfig,
+    #[serde(default)]
+    pub asr: AsrConfig,
+    #[serde(default)]
+    pub tenants: Vec<TenantConfig>,
+    #[serde(default)]
+    pub interfaces: Vec<InterfaceConfig>,
+}
+
+/// `min`-`max` aralığında çift bir RTP portu ve bir üstündeki tek RTCP portu birlikte var mı
+/// (bkz. `rtp::bind_rtp_port`); `min` `max`'tan büyük olmamalıdır.
+fn has_rtcp_pair(min: u16, max: u16) -> bool {
+    let first_even = min as u32 + min as u32 % 2;
+    first_even < max as u32
+}
+
+/// Doğrulamada bulunan tek bir sorun: hangi anahtar, ne yanlış, nasıl düzeltilir.
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub key: String,
+    pub message: String,
+    pub suggestion: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} (öneri: {})", self.key, self.message, self.suggestion)
+    }
+}
+
+impl Settings {
+    /// Gömülü varsayılanları, varsa config dosyasıyla ezerek okur.
+    /// Doğrulama için `ensure_valid` ayrıca çağrılmalıdır.
+    pub fn read() -> Result<Self, ::config::ConfigError> {
+        Config::builder()
+            .add_source(File::from_str(BUILTIN_CONFIG, FileFormat::Toml))
+            .add_source(File::with_name(CONFIG_FILE).required(false))
+            .build()?
+            .try_deserialize::<Settings>()
+    }
+
+    /// Yalnızca gömülü varsayılanlar; config dosyasına bakılmaz (testler ve gömülü kullanım için).
+    pub fn builtin() -> Self {
+        Config::builder()
+            .add_source(File::from_str(BUILTIN_CONFIG, FileFormat::Toml))
+            .build()
+            .and_then(|config| config.try_deserialize::<Settings>())
+            .expect("gömülü konfigürasyon geçerli olmalı")
+    }
+
+    /// `config/default.*` dosyalarından biri mevcut mu? Yoksa yalnızca gömülü varsayılanlar geçerlidir.
+    pub fn config_file_present() -> bool {
+        CONFIG_EXTENSIONS.iter().any(|ext| std::path::Path::new(&format!("{}.{}", CONFIG_FILE, ext)).is_file())
+    }
+
+    /// Doğrulama hatalarını loglar; herhangi bir hata varsa servis başlamaz.
+    pub fn ensure_valid(&self) -> Result<(), ConfigError> {
+        let issues = self.validate();
+        if !issues.is_empty() {
+            for issue in &issues {
+                error!(key = %issue.key, suggestion = %issue.suggestion, "Konfigürasyon hatası: {}", issue.message);
+            }
+            return Err(ConfigError::Invalid { count: issues.len() });
+        }
+        for name in self.rtp.codecs.iter().filter(|name| codec::registry().by_name(name).is_none()) {
+            warn!(key = "rtp.codecs", codec = %name, "Bilinmeyen codec yok sayıldı");
+        }
+        Ok(())
+    }
+
+    /// Bütün kontrolleri çalıştırır ve bulunan sorunların tamamını döner (ilk hatada durmaz).
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        let mut issue = |key: &str, message: String, suggestion: &str| {
+            issues.push(ConfigIssue { key: key.to_string(), message, suggestion: suggestion.to_string() });
+        };
+
+        if self.grpc.listen.is_empty() {
+            if self.grpc.host.parse::<IpAddr>().is_err() {
+                issue("grpc.host", format!("'{}' geçerli bir IP adresi değil", self.grpc.host), "\"0.0.0.0\" veya \"127.0.0.1\" gibi bir IP adresi yazın");
+            }
+            if self.grpc.port == 0 {
+                issue("grpc.port", "port 0 olamaz".to_string(), "50052 gibi sabit bir port seçin");
+            }
+        }
+        let mut listen: Vec<SocketAddr> = Vec::new();
+        for addr in &self.grpc.listen {
+            match addr.parse::<SocketAddr>() {
+                Ok(parsed) if parsed.port() == 0 => issue("grpc.listen", format!("'{}': port 0 olamaz", addr), "\"10.0.0.5:50052\" gibi sabit bir port seçin"),
+                Ok(parsed) if listen.contains(&parsed) => issue("grpc.listen", format!("'{}' birden fazla kez yazılmış", addr), "tekrarlanan adresi silin"),
+                Ok(parsed) => listen.push(parsed),
+                Err(_) => issue("grpc.listen", format!("'{}' geçerli bir adres değil", addr), "\"10.0.0.5:50052\" gibi IP:port yazın"),
+            }
+        }
+        match &self.grpc.unix_socket_path {
+            None if !self.grpc.tcp => {
+                issue("grpc.tcp", "TCP kapalı ve unix_socket_path ayarlı değil".to_string(), "tcp = true yazın veya unix_socket_path ile bir soket yolu verin");
+            }
+            Some(path) if !cfg!(unix) => {
+                issue("grpc.unix_socket_path", format!("'{}': Unix soketleri bu platformda desteklenmiyor", path), "satırı silin ve host/port kullanın");
+            }
+            Some(path) if path.trim().is_empty() => {
+                issue("grpc.unix_socket_path", "soket yolu boş olamaz".to_string(), "\"/run/media/grpc.sock\" gibi bir yol yazın");
+            }
+            _ => {}
+        }
+        if self.grpc.request_id_header.parse::<AsciiMetadataKey>().is_err() {
+            issue("grpc.request_id_header", format!("'{}' geçerli bir metadata anahtarı değil", self.grpc.request_id_header), "\"x-request-id\" gibi küçük harfli bir başlık adı yazın");
+        }
+        if self.grpc.unix_socket_mode > 0o777 {
+            issue("grpc.unix_socket_mode", format!("{:#o} geçerli bir dosya izni değil", self.grpc.unix_socket_mode), "0o660 gibi bir değer yazın");
+        }
+        if self.rtp.host.parse::<IpAddr>().is_err() {
+            issue("rtp.host", format!("'{}' geçerli bir IP adresi değil", self.rtp.host), "\"0.0.0.0\" veya dinlenecek arayüzün IP adresini yazın");
+        }
+        match self.rtp.advertise_address.parse::<IpAddr>() {
+            _ if self.rtp.advertise_address.is_empty() => {}
+            Ok(address) if !address.is_unspecified() => {}
+            _ => issue("rtp.advertise_address", format!("'{}' duyurulabilecek bir IP adresi değil", self.rtp.advertise_address), "sunucunun public IP'sini yazın ya da boş bırakın"),
+        }
+
+        let (min, max) = (self.rtp.min_port, self.rtp.max_port);
+        if min == 0 {
+            issue("rtp.min_port", "port 0 olamaz".to_string(), "10000 gibi bir değer kullanın");
+        }
+        if min > max {
+            issue("rtp.min_port", format!("min_port ({}) max_port'tan ({}) büyük", min, max), "iki değeri yer değiştirin");
+        } else if !has_rtcp_pair(min, max) {
+            issue("rtp.max_port", format!("port aralığı {}-{} en az bir çift/tek RTP/RTCP port çifti içermeli", min, max), "max_port değerini artırın");
+        }
+        if let Some((overflow_min, overflow_max)) = self.rtp.overflow_range() {
+            if overflow_min == 0 || overflow_max == 0 {
+                issue("rtp.overflow_min_port", "taşma aralığının iki ucu da verilmeli".to_string(), "ikisini de 0 bırakın (geçici port) ya da ikisini de yazın");
+            } else if overflow_min > overflow_max {
+                issue("rtp.overflow_min_port", format!("overflow_min_port ({}) overflow_max_port'tan ({}) büyük", overflow_min, overflow_max), "iki değeri yer değiştirin");
+            } else if overflow_min <= max && min <= overflow_max {
+                issue("rtp.overflow_min_port", format!("taşma aralığı {}-{} RTP aralığıyla ({}-{}) çakışıyor", overflow_min, overflow_max, min, max), "aralık dışında bir taşma aralığı seçin");
+            } else if !has_rtcp_pair(overflow_min, overflow_max) {
+                issue("rtp.overflow_max_port", format!("taşma aralığı {}-{} en az bir çift/tek RTP/RTCP port çifti içermeli", overflow_min, overflow_max), "overflow_max_port değerini artırın");
+            }
+        }
+        for (i, tenant) in self.tenants.iter().enumerate() {
+            let key = |field: &str| format!("tenants[{}].{}", i, field);
+            if tenant.name.is_empty() || tenant.name.len() > 64 || !tenant.name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_') {
+                issue(&key("name"), format!("'{}' geçerli bir kiracı adı değil", tenant.name), "harf, rakam, '-' ve '_' kullanın (en fazla 64 karakter)");
+            } else if self.tenants[..i].iter().any(|other| other.name == tenant.name) {
+                issue(&key("name"), format!("'{}' birden fazla kez tanımlı", tenant.name), "her kiracıya ayrı bir ad verin");
+            }
+            let (tenant_min, tenant_max) = (tenant.min_port, tenant.max_port);
+            if tenant_min > tenant_max {
+                issue(&key("min_port"), format!("min_port ({}) max_port'tan ({}) büyük", tenant_min, tenant_max), "iki değeri yer değiştirin");
+            } else if tenant_min < min || tenant_max > max {
+                issue(&key("min_port"), format!("aralık {}-{} RTP aralığının ({}-{}) dışına taşıyor", tenant_min, tenant_max, min, max), "rtp.min_port-rtp.max_port içinde bir aralık seçin");
+            } else if let Some(other) = self.tenants[..i].iter().find(|other| tenant_min <= other.max_port && other.min_port <= tenant_max) {
+                issue(&key("min_port"), format!("aralık {}-{} '{}' kiracısının aralığıyla ({}-{}) çakışıyor", tenant_min, tenant_max, other.name, other.min_port, other.max_port), "kiracılara ayrık aralıklar verin");
+            } else if !has_rtcp_pair(tenant_min, tenant_max) {
+                issue(&key("max_port"), format!("aralık {}-{} en az bir çift/tek RTP/RTCP port çifti içermeli", tenant_min, tenant_max), "max_port değerini artırın");
+            }
+            if !tenant.auth_token.is_empty() && self.tenants[..i].iter().any(|other| other.auth_token == tenant.auth_token) {
+                issue(&key("auth_token"), "aynı jeton başka bir kiracıda da tanımlı".to_string(), "her kiracıya ayrı bir jeton verin");
+            }
+        }
+        for (i, interface) in self.interfaces.iter().enumerate() {
+            let key = |field: &str| format!("interfaces[{}].{}", i, field);
+            if interface.name.is_empty() || interface.name.len() > 64 || !interface.name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_') {
+                issue(&key("name"), format!("'{}' geçerli bir arayüz adı değil", interface.name), "harf, rakam, '-' ve '_' kullanın (en fazla 64 karakter)");
+            } else if self.interfaces[..i].iter().any(|other| other.name == interface.name) {
+                issue(&key("name"), format!("'{}' birden fazla kez tanımlı", interface.name), "her arayüze ayrı bir ad verin");
+            }
+            if !interface.address.parse::<IpAddr>().is_ok_and(|address| !address.is_unspecified()) {
+                issue(&key("address"), format!("'{}' bu sunucudaki bir IP adresi olmalı", interface.address), "arayüzün yerel IP'sini yazın (ör. \"10.0.0.5\")");
+            }
+            if !interface.advertise_address.is_empty() && !interface.advertise_address.parse::<IpAddr>().is_ok_and(|address| !address.is_unspecified()) {
+                issue(&key("advertise_address"), format!("'{}' duyurulabilecek bir IP adresi değil", interface.advertise_address), "public IP'yi yazın ya da boş bırakın");
+            }
+            let (interface_min, interface_max) = interface.port_range(&self.rtp);
+            if interface_min > interface_max {
+                issue(&key("min_port"), format!("min_port ({}) max_port'tan ({}) büyük", interface_min, interface_max), "iki değeri yer değiştirin");
+            } else if interface_min < min || interface_max > max {
+                issue(&key("min_port"), format!("aralık {}-{} RTP aralığının ({}-{}) dışına taşıyor", interface_min, interface_max, min, max), "rtp.min_port-rtp.max_port içinde bir aralık seçin ya da ikisini de 0 bırakın");
+            } else if !has_rtcp_pair(interface_min, interface_max) {
+                issue(&key("max_port"), format!("aralık {}-{} en az bir çift/tek RTP/RTCP port çifti içermeli", interface_min, interface_max), "max_port değerini artırın ya da ikisini de 0 bırakın");
+            }
+        }
+        let grpc_ports: Vec<u16> = match self.grpc.listen_addrs() {
+            Ok(addrs) => addrs.iter().map(SocketAddr::port).collect(),
+            Err(_) => vec![self.grpc.port],
+        };
+        for port in grpc_ports.into_iter().filter(|_| self.grpc.tcp) {
+            if (min..=max).contains(&port) {
+                issue("grpc.port", format!("gRPC portu ({}) RTP port aralığının ({}-{}) içinde", port, min, max), "gRPC portunu RTP aralığının dışına taşıyın");
+            }
+        }
+
+        if self.rtp.enabled_codecs().is_empty() {
+            let known = codec::registry().names();
+            issue("rtp.codecs", format!("etkin codec yok ({:?})", self.rtp.codecs), &format!("şunlardan en az birini yazın: {}", known.join(", ")));
+        }
+
+        if !(1..=transport::MAX_RECV_BATCH).contains(&self.rtp.recv_batch) {
+            issue("rtp.recv_batch", format!("{} desteklenmiyor", self.rtp.recv_batch), &format!("1 ile {} arasında bir değer kullanın", transport::MAX_RECV_BATCH));
+        }
+        if self.rtp.send_schedulers > scheduler::MAX_SCHEDULERS {
+            issue("rtp.send_schedulers", format!("{} desteklenmiyor", self.rtp.send_schedulers), &format!("0 (işlemci sayısı) ile {} arasında bir değer kullanın", scheduler::MAX_SCHEDULERS));
+        }
+        if !(1..=red::MAX_GENERATIONS).contains(&self.rtp.red_generations) {
+            issue("rtp.red_generations", format!("{} desteklenmiyor", self.rtp.red_generations), &format!("1 ile {} arasında bir değer kullanın", red::MAX_GENERATIONS));
+        }
+
+        if !SUPPORTED_PTIMES.contains(&self.timers.ptime_ms) {
+            issue("timers.ptime_ms", format!("{} ms desteklenmiyor", self.timers.ptime_ms), &format!("şunlardan birini kullanın: {:?}", SUPPORTED_PTIMES));
+        }
+        let timeouts = [
+            ("timers.media_timeout_s", self.timers.media_timeout_s),
+            ("timers.first_packet_timeout_s", self.timers.first_packet_timeout_s),
+            ("timers.keepalive_interval_s", self.timers.keepalive_interval_s),
+            ("timers.shutdown_grace_s", self.timers.shutdown_grace_s),
+            ("timers.shutdown_deadline_s", self.timers.shutdown_deadline_s),
+            ("timers.heartbeat_interval_s", self.timers.heartbeat_interval_s),
+            ("timers.max_session_duration_s", self.timers.max_session_duration_s),
+        ];
+        for (key, value) in timeouts {
+            if value > MAX_TIMER_SECS {
+                issue(key, format!("{} saniye çok büyük", value), &format!("en fazla {} saniye kullanın, devre dışı bırakmak için 0 yazın", MAX_TIMER_SECS));
+            }
+        }
+        if let (Some(grace), Some(deadline)) = (self.timers.shutdown_grace(), self.timers.shutdown_deadline()) {
+            if grace >= deadline {
+                issue("timers.shutdown_grace_s", format!("{} saniye, shutdown_deadline_s ({}) dolmadan bitmez", grace.as_secs(), deadline.as_secs()), "bekleme süresini kapanış süresinden kısa tutun; kalan süre kayıtların ve kancaların kapanmasına kalır");
+            }
+        }
+        let gap = self.timers.new_stream_gap_ms;
+        if gap > 0 && gap < MIN_NEW_STREAM_GAP_MS {
+            issue("timers.new_stream_gap_ms", format!("{} ms çok küçük", gap), &format!("en az {} ms kullanın, devre dışı bırakmak için 0 yazın", MIN_NEW_STREAM_GAP_MS));
+        }
+
+        if let Err(e) = recording::validate_template(&self.recording.filename_template) {
+            issue("recording.filename_template", e.to_string(), "yalnızca {session_id}, {call_id}, {port} ve {start_time} kullanın; yol dizinin dışına çıkmamalı");
+        }
+        if self.recording.encryption.enabled && self.recording.format != RecordingFormat::Pcm16Wav {
+            issue(
+                "recording.format", format!("'{}' şifrelenemez", self.recording.format.as_str()),
+                "şifreli kayıtlar için \"pcm16-wav\" kullanın",
+            );
+        }
+        let cname = self.rtcp.cname();
+        if cname.len() > MAX_SDES_TEXT_LEN {
+            issue("rtcp.cname", format!("{} bayt çok uzun", cname.len()), &format!("en fazla {} bayt kullanın", MAX_SDES_TEXT_LEN));
+        }
+
+        let encryption = &self.recording.encryption;
+        if encryption.enabled {
+            let id = &encryption.key_id;
+            if id.is_empty() || id.len() > MAX_KEY_ID_LEN || !id.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.')) {
+                issue("recording.encryption.key_id", format!("'{}' geçersiz", id), &format!("en fazla {} karakter; harf, rakam, '-', '_' ve '.' kullanın", MAX_KEY_ID_LEN));
+            }
+            if encryption.key_file.trim().is_empty() {
+                issue("recording.encryption.key_file", "anahtar dosyası verilmedi".to_string(), "256 bitlik anahtarı onaltılık olarak tutan dosyanın yolunu yazın");
+            }
+        }
+        if self.recording.max_duration_s > MAX_TIMER_SECS {
+            issue("recording.max_duration_s", format!("{} saniye çok büyük", self.recording.max_duration_s), &format!("en fazla {} saniye kullanın, sınırsız için 0 yazın", MAX_TIMER_SECS));
+        }
+
+        let suppression = &self.silence_suppression;
+        if !(vad::MIN_LEVEL_DBOV..=0).contains(&suppression.threshold_dbov) {
+            issue("silence_suppression.threshold_dbov", format!("{} dBov aralık dışında", suppression.threshold_dbov), &format!("{} ile 0 arasında bir değer kullanın, ör. -50", vad::MIN_LEVEL_DBOV));
+        }
+        if suppression.hangover_ms > MAX_HANGOVER_MS {
+            issue("silence_suppression.hangover_ms", format!("{} ms çok büyük", suppression.hangover_ms), &format!("en fazla {} ms kullanın", MAX_HANGOVER_MS));
+        }
+
+        if self.rate_limit.allocations_per_s > 0 && self.rate_limit.allocation_burst == 0 {
+            issue("rate_limit.allocation_burst", "0 iken hiçbir tahsis kabul edilmez".to_string(), "en az 1 yazın, sınırı kapatmak için allocations_per_s = 0 kullanın");
+        }
+        let limit = &self.rate_limit;
+        if limit.inbound_packets_per_s > 0 && limit.inbound_burst < MIN_INBOUND_BURST {
+            issue("rate_limit.inbound_burst", format!("{} paket jitter kümelenmesini karşılamaz", limit.inbound_burst), &format!("en az {} yazın", MIN_INBOUND_BURST));
+        }
+        if limit.inbound_packets_per_s > 0 && limit.flood_action != FloodAction::None && !(1..=MAX_TIMER_SECS).contains(&limit.flood_trip_s) {
+            issue("rate_limit.flood_trip_s", format!("{} saniye desteklenmiyor", limit.flood_trip_s), &format!("1 ile {} saniye arasında bir değer kullanın", MAX_TIMER_SECS));
+        }
+
+        match self.hook.kind {
+            HookKind::Http if !self.hook.url.starts_with("http://") => {
+                issue("hook.url", format!("'{}' geçerli bir http:// adresi değil", self.hook.url), "\"http://127.0.0.1:8080/sessions\" gibi bir adres yazın");
+            }
+            HookKind::Command if self.hook.command.first().is_none_or(|program| program.trim().is_empty()) => {
+                issue("hook.command", "komut boş olamaz".to_string(), "[\"/usr/local/bin/on-session-end\"] gibi program ve argümanları yazın");
+            }
+            _ => {}
+        }
+        if self.hook.kind != HookKind::None {
+            if !(1..=MAX_HOOK_TIMEOUT_MS).contains(&self.hook.timeout_ms) {
+                issue("hook.timeout_ms", format!("{} ms desteklenmiyor", self.hook.timeout_ms), &format!("1 ile {} ms arasında bir değer kullanın", MAX_HOOK_TIMEOUT_MS));
+            }
+            if self.hook.queue_size == 0 {
+                issue("hook.queue_size", "kuyruk boyutu 0 olamaz".to_string(), "1000 gibi bir değer kullanın");
+            }
+            if !(1..=MAX_HOOK_CONCURRENCY).contains(&self.hook.concurrency) {
+                issue("hook.concurrency", format!("{} desteklenmiyor", self.hook.concurrency), &format!("1 ile {} arasında bir değer kullanın", MAX_HOOK_CONCURRENCY));
+            }
+        }
+        let dtmf_hook = &self.dtmf_hook;
+        if !dtmf_hook.url.is_empty() && !dtmf_hook.url.starts_with("http://") {
+            issue("dtmf_hook.url", format!("'{}' geçerli bir http:// adresi değil", dtmf_hook.url), "\"http://127.0.0.1:8080/digits\" gibi bir adres yazın ya da boş bırakın");
+        }
+        if !(1..=MAX_HOOK_TIMEOUT_MS).contains(&dtmf_hook.timeout_ms) {
+            issue("dtmf_hook.timeout_ms", format!("{} ms desteklenmiyor", dtmf_hook.timeout_ms), &format!("1 ile {} ms arasında bir değer kullanın", MAX_HOOK_TIMEOUT_MS));
+        }
+        if dtmf_hook.queue_size == 0 {
+            issue("dtmf_hook.queue_size", "kuyruk boyutu 0 olamaz".to_string(), "1000 gibi bir değer kullanın");
+        }
+        if !(1..=MAX_HOOK_CONCURRENCY).contains(&dtmf_hook.concurrency) {
+            issue("dtmf_hook.concurrency", format!("{} desteklenmiyor", dtmf_hook.concurrency), &format!("1 ile {} arasında bir değer kullanın", MAX_HOOK_CONCURRENCY));
+        }
+        let admission = &self.admission;
+        if admission.enabled {
+            if !(MIN_ADMISSION_INTERVAL_MS..=MAX_ADMISSION_INTERVAL_MS).contains(&admission.interval_ms) {
+                issue("admission.interval_ms", format!("{} ms desteklenmiyor", admission.interval_ms), &format!("{} ile {} ms arasında bir değer kullanın", MIN_ADMISSION_INTERVAL_MS, MAX_ADMISSION_INTERVAL_MS));
+            }
+            if !(0.0..=100.0).contains(&admission.max_cpu_percent) {
+                issue("admission.max_cpu_percent", format!("{} geçerli bir yüzde değil", admission.max_cpu_percent), "0 ile 100 arasında bir değer kullanın; 0 eşiği kapatır");
+            }
+            if admission.max_send_lag_ms >= MAX_ADMISSION_SEND_LAG_MS {
+                issue("admission.max_send_lag_ms", format!("{} ms ölçülemiyor", admission.max_send_lag_ms), &format!("{} ms'den küçük bir değer kullanın; 0 eşiği kapatır", MAX_ADMISSION_SEND_LAG_MS));
+            }
+            if admission.max_cpu_percent == 0.0 && admission.max_send_lag_ms == 0 {
+                issue("admission", "iki eşik de kapalı; node hiçbir zaman bozulmuş sayılmaz".to_string(), "max_cpu_percent ya da max_send_lag_ms verin veya enabled = false yazın");
+            }
+            if admission.recovery_samples == 0 {
+                issue("admission.recovery_samples", "örnek sayısı 0 olamaz".to_string(), "3 gibi bir değer kullanın");
+            }
+        }
+        let storage = &self.object_storage;
+        if storage.enabled {
+            if !storage.endpoint.starts_with("http://") {
+                issue("object_storage.endpoint", format!("'{}' geçerli bir http:// adresi değil", storage.endpoint), "\"http://127.0.0.1:9000\" gibi bir adres yazın; TLS için yerel bir vekil kullanın");
+            }
+            if storage.region.trim().is_empty() {
+                issue("object_storage.region", "bölge boş olamaz".to_string(), "\"us-east-1\" gibi bir bölge yazın");
+            }
+            match storage.auth {
+                ObjectStorageAuth::Static if storage.access_key_id.is_empty() || storage.secret_access_key.is_empty() => {
+                    issue("object_storage.access_key_id", "statik kimlik için access_key_id ve secret_access_key gerekli".to_string(), "anahtarları yazın ya da auth = \"instance_profile\" kullanın");
+                }
+                ObjectStorageAuth::InstanceProfile if !storage.metadata_endpoint.starts_with("http://") => {
+                    issue("object_storage.metadata_endpoint", format!("'{}' geçerli bir http:// adresi değil", storage.metadata_endpoint), "\"http://169.254.169.254\" kullanın");
+                }
+                _ => {}
+            }
+            if !(1..=MAX_STORAGE_TIMEOUT_MS).contains(&storage.timeout_ms) {
+                issue("object_storage.timeout_ms", format!("{} ms desteklenmiyor", storage.timeout_ms), &format!("1 ile {} ms arasında bir değer kullanın", MAX_STORAGE_TIMEOUT_MS));
+            }
+            if !(1..=MAX_HOOK_CONCURRENCY).contains(&storage.concurrency) {
+                issue("object_storage.concurrency", format!("{} desteklenmiyor", storage.concurrency), &format!("1 ile {} arasında bir değer kullanın", MAX_HOOK_CONCURRENCY));
+            }
+        }
+        let asr = &self.asr;
+        if asr.enabled {
+            if !asr.endpoint.starts_with("http://") {
+                issue("asr.endpoint", format!("'{}' geçerli bir http:// adresi değil", asr.endpoint), "\"http://127.0.0.1:50061\" gibi bir adres yazın; TLS için yerel bir vekil kullanın");
+            }
+            if !(MIN_ASR_BUFFER_MS..=MAX_ASR_BUFFER_MS).contains(&asr.buffer_ms) {
+                issue("asr.buffer_ms", format!("{} ms desteklenmiyor", asr.buffer_ms), &format!("{} ile {} ms arasında bir değer kullanın", MIN_ASR_BUFFER_MS, MAX_ASR_BUFFER_MS));
+            }
+            if asr.retry_initial_ms == 0 || asr.retry_max_ms < asr.retry_initial_ms {
+                issue("asr.retry_max_ms", format!("{}-{} ms geçerli bir aralık değil", asr.retry_initial_ms, asr.retry_max_ms), "retry_initial_ms en az 1, retry_max_ms ondan küçük olmayacak şekilde yazın");
+            }
+        }
+        if self.cdr.format != CdrFormat::None {
+            if self.cdr.path.trim().is_empty() {
+                issue("cdr.path", "dosya yolu boş olamaz".to_string(), "\"cdr/media.cdr\" gibi bir yol yazın");
+            }
+            if self.cdr.rotate == CdrRotation::Size && self.cdr.max_file_bytes < MIN_CDR_FILE_BYTES {
+                issue("cdr.max_file_bytes", format!("{} bayt çok küçük", self.cdr.max_file_bytes), &format!("en az {} bayt kullanın", MIN_CDR_FILE_BYTES));
+            }
+            if self.cdr.queue_size == 0 {
+                issue("cdr.queue_size", "kuyruk boyutu 0 olamaz".to_string(), "10000 gibi bir değer kullanın");
+            }
+        }
+        if self.port_journal.enabled {
+            let journal = &self.port_journal;
+            if journal.path.trim().is_empty() {
+                issue("port_journal.path", "dosya yolu boş olamaz".to_string(), "\"journal/ports.log\" gibi bir yol yazın");
+            }
+            if journal.max_file_bytes < MIN_PORT_JOURNAL_FILE_BYTES {
+                issue("port_journal.max_file_bytes", format!("{} bayt çok küçük", journal.max_file_bytes), &format!("en az {} bayt kullanın", MIN_PORT_JOURNAL_FILE_BYTES));
+            }
+            if journal.max_files == 0 {
+                issue("port_journal.max_files", "en az bir döndürülmüş dosya saklanmalı".to_string(), "10 gibi bir değer kullanın");
+            }
+            if journal.queue_size == 0 {
+                issue("port_journal.queue_size", "kuyruk boyutu 0 olamaz".to_string(), "10000 gibi bir değer kullanın");
+            }
+        }
+
+        if !(vad::MIN_LEVEL_DBOV..=0).contains(&self.fax_detection.min_level_dbov) {
+            issue(
+                "fax_detection.min_level_dbov", format!("{} dBov aralık dışında", self.fax_detection.min_level_dbov),
+                &format!("{} ile 0 arasında bir değer kullanın, ör. -45", vad::MIN_LEVEL_DBOV),
+            );
+        }
+        if !(vad::MIN_LEVEL_DBOV..=0).contains(&self.amd.threshold_dbov) {
+            issue(
+                "amd.threshold_dbov", format!("{} dBov aralık dışında", self.amd.threshold_dbov),
+                &format!("{} ile 0 arasında bir değer kullanın, ör. -40", vad::MIN_LEVEL_DBOV),
+            );
+        }
+        if !(MIN_AMD_WINDOW_MS..=MAX_AMD_WINDOW_MS).contains(&self.amd.window_ms) {
+            issue(
+                "amd.window_ms", format!("{} ms aralık dışında", self.amd.window_ms),
+                &format!("{} ile {} arasında bir değer kullanın, ör. 5000", MIN_AMD_WINDOW_MS, MAX_AMD_WINDOW_MS),
+            );
+        }
+        for (key, value) in [("amd.initial_silence_ms", self.amd.initial_silence_ms), ("amd.greeting_ms", self.amd.greeting_ms), ("amd.after_greeting_silence_ms", self.amd.after_greeting_silence_ms)] {
+            if value == 0 || value > self.amd.window_ms {
+                issue(key, format!("{} ms, 1 ile amd.window_ms ({}) arasında olmalı", value, self.amd.window_ms), "amd.window_ms'ten kısa bir süre kullanın");
+            }
+        }
+        if self.amd.min_word_ms == 0 || self.amd.between_words_silence_ms == 0 {
+            issue("amd.min_word_ms", "amd.min_word_ms ve amd.between_words_silence_ms 0 olamaz".to_string(), "ör. 100 ve 50 kullanın");
+        }
+        if self.amd.max_words == 0 {
+            issue("amd.max_words", "0 kelime ile her sonuç machine olur".to_string(), "ör. 3 kullanın");
+        }
+
+        let prometheus = self.metrics.exporter == MetricsExporter::Prometheus;
+        if self.metrics.enabled && prometheus && self.metrics.bind.parse::<SocketAddr>().is_err() {
+            issue("metrics.bind", format!("'{}' geçerli bir adres değil", self.metrics.bind), "\"127.0.0.1:9090\" gibi IP:port yazın");
+        }
+        if self.metrics.enabled && !prometheus && !self.telemetry.enabled {
+            issue("metrics.exporter", "otlp ihracı için telemetry.enabled kapalı".to_string(), "[telemetry] altında enabled = true yazın veya exporter = \"prometheus\" kullanın");
+        }
+        let label_keys: HashMap<String, String> = self.metrics.session_labels.iter().map(|key| (key.clone(), String::new())).collect();
+        if let Err(e) = labels::validate(&label_keys) {
+            issue("metrics.session_labels", e.to_string(), "küçük harf, rakam ve _ içeren en fazla 16 anahtar yazın");
+        }
+        if !self.metrics.session_labels.is_empty() && !(1..=MAX_SESSION_LABEL_VALUES).contains(&self.metrics.session_label_values) {
+            issue(
+                "metrics.session_label_values", format!("{} geçersiz", self.metrics.session_label_values),
+                &format!("1 ile {} arasında bir değer kullanın", MAX_SESSION_LABEL_VALUES),
+            );
+        }
+
+        if self.health.enabled && self.health.bind.parse::<SocketAddr>().is_err() {
+            issue("health.bind", format!("'{}' geçerli bir adres değil", self.health.bind), "\"0.0.0.0:9090\" gibi IP:port yazın");
+        }
+
+        if self.telemetry.enabled {
+            let endpoint = &self.telemetry.endpoint;
+            if !(endpoint.starts_with("http://") || endpoint.starts_with("https://")) {
+                issue("telemetry.endpoint", format!("'{}' geçerli bir URL değil", endpoint), "\"http://127.0.0.1:4317\" gibi şema içeren bir adres yazın");
+            }
+            if self.telemetry.service_name.trim().is_empty() {
+                issue("telemetry.service_name", "servis adı boş olamaz".to_string(), "\"media\" gibi bir ad yazın");
+            }
+        }
+
+        if self.capture.directory.trim().is_empty() {
+            issue("capture.directory", "dizin boş olamaz".to_string(), "\"captures\" gibi bir dizin yazın");
+        }
+        if self.capture.max_file_bytes < MIN_CAPTURE_BYTES {
+            issue("capture.max_file_bytes", format!("{} bayt çok küçük", self.capture.max_file_bytes), &format!("en az {} bayt kullanın", MIN_CAPTURE_BYTES));
+        }
+        if self.audio_dump.directory.trim().is_empty() {
+            issue("audio_dump.directory", "dizin boş olamaz".to_string(), "\"audio_dumps\" gibi bir dizin yazın");
+        }
+        if self.audio_dump.max_file_bytes < MIN_AUDIO_DUMP_BYTES {
+            issue("audio_dump.max_file_bytes", format!("{} bayt çok küçük", self.audio_dump.max_file_bytes), &format!("en az {} bayt kullanın", MIN_AUDIO_DUMP_BYTES));
+        }
+
+        if let Err(e) = EnvFilter::try_new(&self.log.level) {
+            issue("log.level", format!("'{}' geçerli bir filtre değil: {}", self.log.level, e), "\"info\", \"debug\" veya \"info,media=debug\" gibi bir değer kullanın");
+        }
+
+        if let Some(welcome) = self.announcement.welcome_name() {
+            if !self.announcement.prompts.contains_key(welcome) {
+                issue("announcement.welcome", format!("'{}' adlı anons tanımlı değil", welcome), "[announcement.prompts] altında bu isimde bir girdi ekleyin");
+            }
+        }
+        if self.bridge.detached_audio == DetachedAudio::Prompt {
+            match &self.bridge.detached_prompt {
+                None => issue("bridge.detached_prompt", "detached_audio = \"prompt\" iken anons adı verilmeli".to_string(), "[announcement.prompts] altındaki bir anonsun adını yazın"),
+                Some(name) if !self.announcement.prompts.contains_key(name) => {
+                    issue("bridge.detached_prompt", format!("'{}' adlı anons tanımlı değil", name), "[announcement.prompts] altında bu isimde bir girdi ekleyin");
+                }
+                Some(_) => {}
+            }
+        }
+        if self.hold.audio == DetachedAudio::Prompt {
+            match &self.hold.prompt {
+                None => issue("hold.prompt", "audio = \"prompt\" iken anons adı verilmeli".to_string(), "[announcement.prompts] altındaki bir anonsun adını yazın"),
+                Some(name) if !self.announcement.prompts.contains_key(name) => {
+                    issue("hold.prompt", format!("'{}' adlı anons tanımlı değil", name), "[announcement.prompts] altında bu isimde bir girdi ekleyin");
+                }
+                Some(_) => {}
+            }
+        }
+        if let Some(name) = self.announcement.welcome_bed() {
+            if !self.announcement.prompts.contains_key(name) {
+                issue("announcement.welcome_bed", format!("'{}' adlı anons tanımlı değil", name), "[announcement.prompts] altında bu isimde bir girdi ekleyin");
+            }
+        }
+        if let Err(e) = crate::playback::bed_gain(self.announcement.bed_gain_db()) {
+            issue("announcement.bed_gain_db", e.to_string(), &format!("{} ile {} arasında bir değer kullanın", crate::playback::MIN_BED_GAIN_DB, crate::playback::MAX_BED_GAIN_DB));
+        }
+        if let Some(name) = self.announcement.max_duration.as_deref().filter(|name| !name.is_empty()) {
+            if !self.announcement.prompts.contains_key(name) {
+                issue("announcement.max_duration", format!("'{}' adlı anons tanımlı değil", name), "[announcement.prompts] altında bu isimde bir girdi ekleyin");
+            }
+        }
+        if self.announcement.max_upload_bytes() < MIN_UPLOAD_BYTES {
+            issue("announcement.max_upload_bytes", format!("{} bayt çok küçük", self.announcement.max_upload_bytes()), &format!("en az {} bayt kullanın", MIN_UPLOAD_BYTES));
+        }
+        let mut names: Vec<&String> = self.announcement.prompts.keys().collect();
+        names.sort();
+        if self.announcement.url_cache_max_bytes() < MIN_UPLOAD_BYTES {
+            issue("announcement.url_cache_max_bytes", format!("{} bayt çok küçük", self.announcement.url_cache_max_bytes()), &format!("en az {} bayt kullanın", MIN_UPLOAD_BYTES));
+        }
+        let (cache_max, entry_max) = (self.announcement.cache_max_bytes(), self.announcement.cache_max_entry_bytes());
+        if cache_max > 0 && entry_max > cache_max {
+            issue("announcement.cache_max_entry_bytes", format!("{} bayt, cache_max_bytes'tan ({}) büyük", entry_max, cache_max), "cache_max_bytes'tan küçük bir değer kullanın");
+        }
+        if let Some(language) = self.announcement.default_language().filter(|language| !valid_language(language)) {
+            issue("announcement.default_language", format!("'{}' geçersiz", language), "\"tr\", \"en\" ya da \"en-GB\" gibi bir dil etiketi yazın");
+        }
+        for name in names {
+            let prompt = &self.announcement.prompts[name];
+            let mut files = vec![(format!("announcement.prompts.{}.path", name), &prompt.path)];
+            files.extend(prompt.languages.iter().map(|(language, path)| (format!("announcement.prompts.{}.languages.{}", name, language), path)));
+            for (key, path) in files {
+                match url_of(path) {
+                    Some(url) => {
+                        if let Err(reason) = crate::url_cache::check_url(url) {
+                            issue(&key, format!("'{}' kullanılamaz: {}", url, reason), "http://sunucu/yol.wav ya da s3://kova/anahtar biçiminde bir adres yazın");
+                        }
+                        if url.starts_with("s3://") && !self.object_storage.enabled {
+                            issue(&key, "s3:// anonsları için nesne deposu kapalı".to_string(), "[object_storage] altında enabled = true yazın");
+                        }
+                        if self.announcement.url_cache_dir().is_none() {
+                            issue(&key, "URL'li anonslar için önbellek dizini tanımlı değil".to_string(), "announcement.url_cache_dir değerini ayarlayın");
+                        }
+                        if prompt.preload {
+                            issue(&format!("announcement.prompts.{}.preload", name), "URL'li anonslar önceden yüklenemez".to_string(), "preload = false yapın; dosya ilk çalmada indirilir");
+                        }
+                    }
+                    None => {
+                        if let Err(e) = std::fs::File::open(path) {
+                            issue(&key, format!("'{}' okunamadı: {}", path, e), "dosya yolunu ve okuma iznini kontrol edin");
+                        }
+                    }
+                }
+            }
+            if let Some(language) = prompt.language.as_deref().filter(|language| !valid_language(language)) {
+                issue(&format!("announcement.prompts.{}.language", name), format!("'{}' geçersiz", language), "\"tr\", \"en\" ya da \"en-GB\" gibi bir dil etiketi yazın");
+            }
+            for language in prompt.languages.keys().filter(|language| !valid_language(language)) {
+                issue(&format!("announcement.prompts.{}.languages", name), format!("'{}' geçersiz bir dil etiketi", language), "\"tr\", \"en\" ya da \"en-GB\" gibi bir dil etiketi yazın");
+            }
+            if !prompt.languages.is_empty() {
+                match prompt.language.as_deref().or(self.announcement.default_language()) {
+                    None => issue(
+                        &format!("announcement.prompts.{}.language", name), "dil varyantları var ama path dosyasının dili bilinmiyor".to_string(),
+                        "language ya da announcement.default_language değerini yazın",
+                    ),
+                    Some(language) if prompt.languages.contains_key(language) => issue(
+                        &format!("announcement.prompts.{}.languages.{}", name, language), "path dosyasının diliyle aynı".to_string(),
+                        "bu girdiyi kaldırın; path zaten bu dilin dosyasıdır",
+                    ),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(settings: &Settings) -> Vec<String> {
+        settings.validate().into_iter().map(|issue| issue.key).collect()
+    }
+
+    #[test]
+    fn tenant_ranges_must_hold_an_rtp_rtcp_pair() {
+        let mut settings = Settings::builtin();
+        let tenant = |min_port, max_port| TenantConfig { name: "acme".to_string(), min_port, max_port, ..TenantConfig::default() };
+        settings.tenants = vec![tenant(10001, 10002)];
+        assert_eq!(keys(&settings), ["tenants[0].max_port"]);
+        settings.tenants = vec![tenant(10001, 10003)];
+        assert!(keys(&settings).is_empty());
+    }
+
+    #[test]
+    fn interface_ranges_must_hold_an_rtp_rtcp_pair() {
+        let mut settings = Settings::builtin();
+        let interface = |min_port, max_port| InterfaceConfig { name: "carrier".to_string(), address: "10.0.0.5".to_string(), min_port, max_port, ..InterfaceConfig::default() };
+        settings.interfaces = vec![interface(10000, 10000)];
+        assert_eq!(keys(&settings), ["interfaces[0].max_port"]);
+        // 0-0 bütün RTP aralığıdır.
+        settings.interfaces = vec![interface(10000, 10001), interface(0, 0)];
+        assert!(keys(&settings).is_empty());
+    }
+
+    #[test]
+    fn overflow_ranges_must_hold_an_rtp_rtcp_pair() {
+        let mut settings = Settings::builtin();
+        (settings.rtp.overflow_min_port, settings.rtp.overflow_max_port) = (30001, 30001);
+        assert_eq!(keys(&settings), ["rtp.overflow_max_port"]);
+        (settings.rtp.overflow_min_port, settings.rtp.overflow_max_port) = (30001, 30002);
+        assert_eq!(keys(&settings), ["rtp.overflow_max_port"]);
+        (settings.rtp.overflow_min_port, settings.rtp.overflow_max_port) = (30000, 30001);
+        assert!(keys(&settings).is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+// Tuş bildirimi: gelen RFC 4733 olayından algılanan her DTMF tuşu JSON olarak bir HTTP uç
+// noktasına POST edilir; adres tahsiste verilmişse oturumunki, yoksa `dtmf_hook.url`. Tuşu
+// algılayan medya görevi yalnızca oturum kancasıyla ortak kuyruğa (`hook::Queue`) `try_send` yapar;
+// teslimatı `concurrency` işçi görev üstlenir, her deneme zaman aşımıyla sınırlıdır ve başarısızlar
+// `retries` kez artan aralıklarla yeniden denenir. Kuyruk doluysa bildirim düşürülür; ölü bir uç
+// nokta görev biriktirmez ve medyayı geciktirmez.
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::config::DtmfHookConfig;
+use crate::hook::{Notice, Queue, Target};
+use crate::metrics::{self, HookOutcome};
+
+/// Tuşun nasıl algılandığı; şimdilik yalnızca RFC 4733 telephone-event.
+pub const METHOD_RFC4733: &str = "rfc4733";
+
+/// Tuş başına gönderilen gövde.
+#[derive(Debug, Clone, Serialize)]
+pub struct DigitReport {
+    pub session_id: String,
+    pub call_id: String,
+    /// 0-9, *, # ya da A-D.
+    pub digit: char,
+    pub duration_ms: u64,
+    pub method: &'static str,
+    /// Tuşun bittiği an, Unix milisaniye.
+    pub timestamp_ms: u64,
+}
+
+impl Notice for DigitReport {
+    fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    fn count(outcome: HookOutcome) {
+        metrics::get().dtmf_hook_report(outcome);
+    }
+}
+
+pub struct DtmfHook {
+    url: Option<String>,
+    queue: Queue<DigitReport>,
+}
+
+impl DtmfHook {
+    /// İşçileri başlatır; adres tahsiste de verilebildiğinden `url` boşken de kurulur. Tokio
+    /// çalışma zamanı içinde çağrılmalı.
+    pub fn start(config: &DtmfHookConfig) -> DtmfHook {
+        let url = Some(config.url.clone()).filter(|url| !url.is_empty());
+        DtmfHook { url, queue: Queue::start("dtmf", config.timeout(), config.retries, config.queue_size, config.concurrency) }
+    }
+
+    /// Bildirimi oturumun adresine, yoksa node'un adresine kuyruklar; adres yoksa, kuyruk doluysa ya
+    /// da kanca kapandıysa `false` döner. Beklemez.
+    pub fn submit(&self, url: Option<&str>, report: DigitReport) -> bool {
+        let Some(url) = url.or(self.url.as_deref()) else { return false };
+        self.queue.submit(Target::Http(url.to_string()), report)
+    }
+
+    /// Yeni bildirim almayı bırakır ve bekleyenleri bekler (bkz. `hook::Queue::flush`).
+    pub async fn flush(&self) {
+        self.queue.flush().await;
+    }
+}
+
+static DTMF_HOOK: OnceLock<DtmfHook> = OnceLock::new();
+
+/// Tuş bildirimlerini süreç geneli için kurar; yalnızca ilk çağrı etkilidir.
+pub fn install(config: &DtmfHookConfig) {
+    if DTMF_HOOK.get().is_none() {
+        let _ = DTMF_HOOK.set(DtmfHook::start(config));
+    }
+}
+
+/// Tuş algılanınca çağrılır; kanca kurulu değilse ya da adres yoksa hiçbir şey yapmaz, asla beklemez.
+pub fn digit(url: Option<&str>, report: DigitReport) {
+    if let Some(hook) = DTMF_HOOK.get() {
+        hook.submit(url, report);
+    }
+}
+
+/// Süreç kapanırken bekleyen bildirimleri gönderir (bkz. `DtmfHook::flush`).
+pub async fn flush() {
+    if let Some(hook) = DTMF_HOOK.get() {
+        hook.flush().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+
+    use super::*;
+    use crate::hook::flaky_endpoint;
+
+    fn report(digit: char) -> DigitReport {
+        DigitReport { session_id: "abc".to_string(), call_id: "call-1".to_string(), digit, duration_ms: 120, method: METHOD_RFC4733, timestamp_ms: 1_700_000_000_000 }
+    }
+
+    #[tokio::test]
+    async fn digits_go_to_the_session_url_and_failures_are_retried() {
+        // İlk deneme reddedilir; bildirim yeniden denenmeli.
+        let (base, requests) = flaky_endpoint(StatusCode::BAD_GATEWAY, StatusCode::NO_CONTENT);
+        let config = DtmfHookConfig { url: format!("{base}/node"), retries: 1, ..DtmfHookConfig::default() };
+        let hook = DtmfHook::start(&config);
+        assert!(hook.submit(Some(&format!("{base}/session")), report('#')));
+        hook.flush().await;
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|(path, _)| path == "/session"));
+        let body: serde_json::Value = serde_json::from_slice(&requests[1].1).unwrap();
+        assert_eq!(body, serde_json::json!({
+            "session_id": "abc", "call_id": "call-1", "digit": "#", "duration_ms": 120, "method": "rfc4733", "timestamp_ms": 1_700_000_000_000u64,
+        }));
+    }
+
+    #[tokio::test]
+    async fn digits_without_a_url_or_room_are_not_queued() {
+        let hook = DtmfHook::start(&DtmfHookConfig { queue_size: 1, concurrency: 1, ..DtmfHookConfig::default() });
+        assert!(!hook.submit(None, report('1')));
+        // Ulaşılamayan adres; işçi henüz çalışmadı, ilk bildirim kuyruğu doldurur.
+        assert!(hook.submit(Some("http://127.0.0.1:9/"), report('1')));
+        assert!(!hook.submit(Some("http://127.0.0.1:9/"), report('2')));
+    }
+}
@@ -0,0 +1,344 @@
+// Kayıtların diskte şifrelenmesi. Şifreli kayıt WAV değil, parça parça AES-256-GCM ile
+// mühürlenmiş ham PCM taşıyan bir kaptır; `decrypt-recording` alt komutu onu tekrar WAV'a çevirir.
+//
+// Biçim (tamsayılar big-endian):
+//   başlık:  "MEDIAREC" | sürüm (1) | örnekleme hızı u32 | anahtar kimliği uzunluğu u8 |
+//            anahtar kimliği | nonce öneki (7 bayt, rastgele)
+//   parça:   uzunluk u32 (üst bit: son parça) | şifreli metin + 16 baytlık etiket
+// Parça nonce'u `önek | sıra u32 | son (0/1)`, ek doğrulanan veri başlığın tamamıdır. Son parça
+// bayrağı nonce'a girdiğinden kesilmiş bir dosya son parçası olmadan biter ve ayırt edilir; o
+// zamana kadarki tam parçalar çözülebilir. Dosya `.part` adıyla yazılır, kapanınca yerine taşınır.
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::RecordingEncryptionConfig;
+use crate::error::DecryptError;
+
+const MAGIC: &[u8; 8] = b"MEDIAREC";
+const VERSION: u8 = 1;
+const NONCE_PREFIX_LEN: usize = 7;
+const TAG_LEN: usize = 16;
+// Parça başına düz metin; 8 kHz'de yaklaşık 4 saniye. Çökmede en fazla bu kadar ses kaybolur.
+const CHUNK_LEN: usize = 64 * 1024;
+const LAST_CHUNK: u32 = 1 << 31;
+/// Şifreli kayıtların uzantısı; kayıt adının sonuna eklenir (`x.wav.enc`).
+pub const EXTENSION: &str = "enc";
+
+#[cfg(feature = "recording-encryption")]
+mod aead {
+    use aes_gcm::aead::{AeadInPlace, KeyInit};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    pub struct Cipher(Aes256Gcm);
+
+    impl Cipher {
+        pub fn new(key: &[u8; 32]) -> Option<Self> {
+            Some(Cipher(Aes256Gcm::new(key.into())))
+        }
+
+        pub fn seal(&self, nonce: &[u8; 12], aad: &[u8], buf: &mut Vec<u8>) {
+            self.0.encrypt_in_place(Nonce::from_slice(nonce), aad, buf).expect("chunk fits in memory");
+        }
+
+        /// Etiket doğrulanmazsa `false` döner.
+        pub fn open(&self, nonce: &[u8; 12], aad: &[u8], buf: &mut Vec<u8>) -> bool {
+            self.0.decrypt_in_place(Nonce::from_slice(nonce), aad, buf).is_ok()
+        }
+    }
+}
+
+#[cfg(not(feature = "recording-encryption"))]
+mod aead {
+    use std::convert::Infallible;
+
+    // Feature kapalıyken anahtar kurulamaz; bu tipin değeri yoktur.
+    pub struct Cipher(Infallible);
+
+    impl Cipher {
+        pub fn new(_key: &[u8; 32]) -> Option<Self> {
+            None
+        }
+
+        pub fn seal(&self, _nonce: &[u8; 12], _aad: &[u8], _buf: &mut Vec<u8>) {
+            match self.0 {}
+        }
+
+        pub fn open(&self, _nonce: &[u8; 12], _aad: &[u8], _buf: &mut Vec<u8>) -> bool {
+            match self.0 {}
+        }
+    }
+}
+
+/// Kimliğiyle birlikte bir kayıt anahtarı. Anahtar baytları loglara yazılmaz.
+pub struct RecordingKey {
+    id: String,
+    cipher: aead::Cipher,
+}
+
+impl std::fmt::Debug for RecordingKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordingKey").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
+impl RecordingKey {
+    pub fn new(id: &str, key: &[u8; 32]) -> Result<Self, String> {
+        if id.len() > u8::MAX as usize {
+            return Err(format!("key id is longer than {} bytes", u8::MAX));
+        }
+        let cipher = aead::Cipher::new(key).ok_or("this build lacks the recording-encryption feature")?;
+        Ok(RecordingKey { id: id.to_string(), cipher })
+    }
+
+    /// 64 onaltılık karakterlik (32 bayt) anahtar dosyasını okur; boşluklar yok sayılır.
+    pub fn from_file(id: &str, path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("failed to read key file {}: {}", path, e))?;
+        let hex: String = text.split_whitespace().collect();
+        let invalid = || format!("key file {} must hold 64 hex characters (a 256-bit key)", path);
+        if hex.len() != 64 {
+            return Err(invalid());
+        }
+        let mut key = [0u8; 32];
+        for (byte, pair) in key.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            *byte = std::str::from_utf8(pair).ok().and_then(|pair| u8::from_str_radix(pair, 16).ok()).ok_or_else(invalid)?;
+        }
+        Self::new(id, &key)
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn nonce(prefix: &[u8; NONCE_PREFIX_LEN], counter: u32, last: bool) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+        nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&counter.to_be_bytes());
+        nonce[11] = last as u8;
+        nonce
+    }
+}
+
+/// Config'te şifreleme açıksa anahtarı yükler.
+pub fn load(config: &RecordingEncryptionConfig) -> Result<Option<Arc<RecordingKey>>, String> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    RecordingKey::from_file(&config.key_id, &config.key_file).map(|key| Some(Arc::new(key)))
+}
+
+/// Kaydın diskteki adı: `x.wav` -> `x.wav.enc`.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(EXTENSION);
+    PathBuf::from(name)
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+/// Şifreli olarak yazılan bir kayıt dosyası.
+pub struct EncryptedWav {
+    key: Arc<RecordingKey>,
+    file: File,
+    path: PathBuf,
+    header: Vec<u8>,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    chunk: Vec<u8>,
+}
+
+impl EncryptedWav {
+    /// `path` yoksa `<path>.part` dosyasını açar ve başlığı yazar. Var olan dosya ezilmez.
+    pub fn create(path: &Path, key: Arc<RecordingKey>, sample_rate: u32) -> io::Result<Self> {
+        if path.exists() {
+            return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+        }
+        let mut file = File::create_new(partial_path(path))?;
+        let prefix: [u8; NONCE_PREFIX_LEN] = rand::random();
+        let mut header = Vec::with_capacity(MAGIC.len() + 6 + key.id.len() + NONCE_PREFIX_LEN);
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.extend_from_slice(&sample_rate.to_be_bytes());
+        header.push(key.id.len() as u8);
+        header.extend_from_slice(key.id.as_bytes());
+        header.extend_from_slice(&prefix);
+        file.write_all(&header)?;
+        Ok(EncryptedWav { key, file, path: path.to_path_buf(), header, prefix, counter: 0, chunk: Vec::with_capacity(CHUNK_LEN + TAG_LEN) })
+    }
+
+    pub fn write_samples(&mut self, samples: &[i16]) -> io::Result<()> {
+        for sample in samples {
+            self.chunk.extend_from_slice(&sample.to_le_bytes());
+            if self.chunk.len() == CHUNK_LEN {
+                self.seal(false)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn seal(&mut self, last: bool) -> io::Result<()> {
+        let nonce = RecordingKey::nonce(&self.prefix, self.counter, last);
+        self.key.cipher.seal(&nonce, &self.header, &mut self.chunk);
+        let len = self.chunk.len() as u32 | if last { LAST_CHUNK } else { 0 };
+        self.file.write_all(&len.to_be_bytes())?;
+        self.file.write_all(&self.chunk)?;
+        self.chunk.clear();
+        self.counter += 1;
+        Ok(())
+    }
+
+    /// Son parçayı yazar, diske indirir ve dosyayı asıl adına taşır.
+    pub fn finalize(mut self) -> io::Result<()> {
+        self.seal(true)?;
+        self.file.sync_all()?;
+        fs::rename(partial_path(&self.path), &self.path)
+    }
+}
+
+/// Çözülen bir kaydın özeti.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decrypted {
+    pub key_id: String,
+    pub sample_rate: u32,
+    pub samples: u64,
+    /// Dosya son parçadan önce bitiyor; yazılan ses son tam parçaya kadardır.
+    pub truncated: bool,
+}
+
+/// Şifreli kaydı çözüp `output`'a WAV olarak yazar. Kesilmiş dosyada son tam parçaya kadar
+/// yazar ve `truncated` döner; etiketi doğrulanmayan parça hatadır.
+pub fn decrypt_recording(input: &Path, output: &Path, key: &RecordingKey) -> Result<Decrypted, DecryptError> {
+    let mut reader = io::BufReader::new(File::open(input)?);
+    let mut fixed = [0u8; 14];
+    reader.read_exact(&mut fixed).map_err(|_| DecryptError::NotEncrypted)?;
+    if &fixed[..8] != MAGIC {
+        return Err(DecryptError::NotEncrypted);
+    }
+    if fixed[8] != VERSION {
+        return Err(DecryptError::UnsupportedVersion { version: fixed[8] });
+    }
+    let sample_rate = u32::from_be_bytes(fixed[9..13].try_into().unwrap());
+    // Süre hesabı örnekleme hızına böler; 0 ancak bozulmuş başlıkta olur.
+    if sample_rate == 0 {
+        return Err(DecryptError::InvalidSampleRate { sample_rate });
+    }
+    let mut rest = vec![0u8; fixed[13] as usize + NONCE_PREFIX_LEN];
+    reader.read_exact(&mut rest).map_err(|_| DecryptError::NotEncrypted)?;
+    let key_id = String::from_utf8_lossy(&rest[..fixed[13] as usize]).into_owned();
+    let prefix: [u8; NONCE_PREFIX_LEN] = rest[fixed[13] as usize..].try_into().unwrap();
+    let header = [&fixed[..], &rest].concat();
+
+    let spec = hound::WavSpec { channels: 1, sample_rate, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut writer = hound::WavWriter::create(output, spec).map_err(io::Error::other)?;
+    let (mut counter, mut samples, mut truncated) = (0u32, 0u64, true);
+    let mut chunk = Vec::with_capacity(CHUNK_LEN + TAG_LEN);
+    loop {
+        let mut len = [0u8; 4];
+        if reader.read_exact(&mut len).is_err() {
+            break;
+        }
+        let len = u32::from_be_bytes(len);
+        let last = len & LAST_CHUNK != 0;
+        let len = (len & !LAST_CHUNK) as usize;
+        if !(TAG_LEN..=CHUNK_LEN + TAG_LEN).contains(&len) {
+            return Err(DecryptError::Corrupt { chunk: counter });
+        }
+        chunk.resize(len, 0);
+        if reader.read_exact(&mut chunk).is_err() {
+            break;
+        }
+        if !key.cipher.open(&RecordingKey::nonce(&prefix, counter, last), &header, &mut chunk) {
+            return Err(DecryptError::Authentication { chunk: counter, key_id });
+        }
+        for pair in chunk.chunks_exact(2) {
+            writer.write_sample(i16::from_le_bytes([pair[0], pair[1]])).map_err(io::Error::other)?;
+        }
+        samples += chunk.len() as u64 / 2;
+        counter += 1;
+        if last {
+            truncated = false;
+            if reader.read(&mut [0u8; 1])? != 0 {
+                return Err(DecryptError::Corrupt { chunk: counter });
+            }
+            break;
+        }
+    }
+    writer.finalize().map_err(io::Error::other)?;
+    Ok(Decrypted { key_id, sample_rate, samples, truncated })
+}
+
+#[cfg(all(test, feature = "recording-encryption"))]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("media-encryption-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn key(id: &str, byte: u8) -> Arc<RecordingKey> {
+        Arc::new(RecordingKey::new(id, &[byte; 32]).unwrap())
+    }
+
+    fn samples(path: &Path) -> Vec<i16> {
+        hound::WavReader::open(path).unwrap().into_samples().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn encrypted_recording_round_trips_and_hides_the_audio() {
+        let dir = temp_dir("round-trip");
+        let path = dir.join("call.wav.enc");
+        let audio: Vec<i16> = (0..100_000).map(|i| (i % 2000) as i16 - 1000).collect();
+        let mut file = EncryptedWav::create(&path, key("k1", 7), 8000).unwrap();
+        file.write_samples(&audio).unwrap();
+        assert!(!path.exists(), "written under .part until finalized");
+        file.finalize().unwrap();
+
+        let raw = fs::read(&path).unwrap();
+        let plain: Vec<u8> = audio.iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert!(!raw.windows(64).any(|w| w == &plain[..64]));
+        let output = dir.join("call.wav");
+        let decrypted = decrypt_recording(&path, &output, &key("k1", 7)).unwrap();
+        assert_eq!(decrypted, Decrypted { key_id: "k1".to_string(), sample_rate: 8000, samples: 100_000, truncated: false });
+        assert_eq!(samples(&output), audio);
+
+        let wrong = decrypt_recording(&path, &output, &key("k1", 8));
+        assert!(matches!(wrong, Err(DecryptError::Authentication { chunk: 0, .. })));
+
+        // Başlıktaki örnekleme hızı 0 ise parçalara geçilmez.
+        let mut raw = raw;
+        raw[9..13].copy_from_slice(&0u32.to_be_bytes());
+        fs::write(&path, raw).unwrap();
+        let corrupt = decrypt_recording(&path, &output, &key("k1", 7));
+        assert!(matches!(corrupt, Err(DecryptError::InvalidSampleRate { sample_rate: 0 })));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn crashed_recording_decrypts_up_to_the_last_complete_chunk() {
+        let dir = temp_dir("truncated");
+        let path = dir.join("call.wav.enc");
+        let mut file = EncryptedWav::create(&path, key("k1", 7), 8000).unwrap();
+        // İki parça diske yazıldı, üçüncüsü bellekte; süreç ölmüş ve ikinci parça yarım kalmış gibi.
+        file.write_samples(&vec![5; CHUNK_LEN + CHUNK_LEN / 4]).unwrap();
+        drop(file);
+        let partial = partial_path(&path);
+        let len = fs::metadata(&partial).unwrap().len();
+        fs::OpenOptions::new().write(true).open(&partial).unwrap().set_len(len - 10).unwrap();
+
+        let output = dir.join("call.wav");
+        let decrypted = decrypt_recording(&partial, &output, &key("k1", 7)).unwrap();
+        assert!(decrypted.truncated);
+        assert_eq!(decrypted.samples, CHUNK_LEN as u64 / 2);
+        assert_eq!(samples(&output).len(), CHUNK_LEN / 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
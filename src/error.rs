@@ -0,0 +1,637 @@
+// Crate genelindeki hata tipleri ve gRPC `Status` eşlemesi.
+//
+// Her alan kendi enum'unu taşır; handler'lar bunları `?` ile döner ve hangi hatanın hangi gRPC
+// koduna karşılık geldiği yalnızca `Error::code` içinde belirlenir.
+use std::io;
+use std::net::{AddrParseError, SocketAddr};
+use std::time::Duration;
+
+use thiserror::Error;
+use tonic::{Code, Status};
+
+use crate::audit::{PlaybackFailure, SendFailure};
+use crate::codec::Codec;
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read configuration: {0}")]
+    Read(#[from] ::config::ConfigError),
+    #[error("configuration is invalid: {count} issue(s) found")]
+    Invalid { count: usize },
+    #[error("invalid log filter '{level}': {reason}")]
+    InvalidLogLevel { level: String, reason: String },
+    #[error("log level is not managed by this process")]
+    LogLevelUnmanaged,
+    #[error("failed to initialise logging: {0}")]
+    Logging(String),
+    #[error("failed to initialise object storage: {0}")]
+    ObjectStorage(String),
+    #[error("recording encryption: {0}")]
+    RecordingEncryption(String),
+}
+
+#[derive(Debug, Error)]
+pub enum AllocationError {
+    #[error("unknown codec '{name}'")]
+    UnknownCodec { name: String },
+    #[error("codec {codec} is not enabled on this node")]
+    CodecDisabled { codec: &'static dyn Codec },
+    #[error("no codec is enabled on this node")]
+    NoCodecEnabled,
+    #[error("no free RTP port in {min_port}-{max_port} after {attempts} attempts")]
+    PortsExhausted { min_port: u16, max_port: u16, attempts: u32 },
+    #[error("failed to bind RTP port {port}: {source}")]
+    Bind { port: u16, source: io::Error },
+    #[error("RED payload type {payload_type} is not a dynamic payload type (96-127)")]
+    InvalidRedPayloadType { payload_type: u32 },
+    #[error("telephone-event payload type {payload_type} is not a dynamic payload type (96-127)")]
+    InvalidDtmfPayloadType { payload_type: u32 },
+    #[error("audio level extension id {id} is not a one-byte header extension id (1-14)")]
+    InvalidAudioLevelId { id: u32 },
+    #[error("initial sequence number {sequence} does not fit in 16 bits")]
+    InvalidInitialSequence { sequence: u32 },
+    #[error("payload type {payload_type} is requested for both RED and telephone-event")]
+    PayloadTypeConflict { payload_type: u32 },
+    #[error("allocation rate limit exceeded; retry in {} ms", .retry_after.as_millis())]
+    RateLimited { retry_after: Duration },
+    #[error("unknown tenant '{name}'")]
+    UnknownTenant { name: String },
+    #[error("tenant '{tenant}' requires an auth token")]
+    TenantTokenRequired { tenant: String },
+    #[error("auth token does not belong to any tenant")]
+    InvalidTenantToken,
+    #[error("auth token belongs to tenant '{tenant}', not '{requested}'")]
+    TenantMismatch { tenant: String, requested: String },
+    #[error("tenant '{tenant}' has reached its limit of {max_sessions} sessions")]
+    TenantLimit { tenant: String, max_sessions: u32 },
+    #[error("unknown interface '{name}'")]
+    UnknownInterface { name: String },
+    #[error("every RTP port is assigned to a tenant; the request must name one")]
+    NoSharedPorts,
+    #[error("remote address '{address}' is not an IP:port pair")]
+    InvalidRemoteAddress { address: String },
+    #[error("{count} labels given; at most {max} are allowed")]
+    TooManyLabels { count: usize, max: usize },
+    #[error("label '{key}' is invalid: {reason}")]
+    InvalidLabel { key: String, reason: &'static str },
+    #[error("DTMF hook URL '{url}' is not an http:// address")]
+    InvalidDtmfHookUrl { url: String },
+    #[error("live transcription is not available on this node (needs the asr feature and asr.enabled)")]
+    AsrUnavailable,
+}
+
+#[derive(Debug, Error)]
+pub enum PlaybackError {
+    #[error("prompt '{name}' is not defined{}", suggestion_hint(.suggestions))]
+    UnknownPrompt { name: String, suggestions: Vec<String> },
+    #[error("failed to open WAV file {path}: {source}")]
+    Open { path: String, source: hound::Error },
+    #[error("unsupported WAV format in {path}: {spec}; expected 16-bit 8000 Hz mono PCM")]
+    UnsupportedFormat { path: String, spec: String },
+    #[error("failed to read WAV file {path}: {source}")]
+    Read { path: String, source: hound::Error },
+    #[error("{} prompt(s) failed to load:\n{}", .failures.len(), .failures.join("\n"))]
+    Library { failures: Vec<String> },
+    #[error("failed to send RTP to {target}: {source}")]
+    Send { target: SocketAddr, source: io::Error },
+    #[error("failed to build RTP packet: {0}")]
+    Packet(#[from] BuildError),
+    #[error("URL announcements are disabled on this node (announcement.url_cache_dir is not set)")]
+    UrlCacheDisabled,
+    #[error("invalid announcement URL '{url}': {reason}")]
+    InvalidUrl { url: String, reason: String },
+    #[error("failed to fetch announcement from {url}: {reason}")]
+    Fetch { url: String, reason: String },
+    #[error("playback rate {rate} is out of range; use {}..={}", crate::playback::MIN_RATE, crate::playback::MAX_RATE)]
+    InvalidRate { rate: f32 },
+    #[error("bed gain {gain_db} dB is out of range; use {}..={}", crate::playback::MIN_BED_GAIN_DB, crate::playback::MAX_BED_GAIN_DB)]
+    InvalidBedGain { gain_db: f32 },
+    #[error("'{digits}' is not a digit string; use 1..={} characters 0-9", crate::compose::MAX_DIGITS)]
+    InvalidDigits { digits: String },
+    #[error("number {number} is out of range; use 0..={}", crate::compose::MAX_NUMBER)]
+    NumberOutOfRange { number: u64 },
+    #[error("missing segment prompt(s): {}", .missing.join(", "))]
+    MissingSegments { missing: Vec<String> },
+}
+
+impl PlaybackError {
+    /// Olaylarda ve oturum özetinde görünen makine okunur sebep.
+    /// Soket gönderim hatasıysa sınıfı.
+    pub fn send_failure(&self) -> Option<SendFailure> {
+        match self {
+            PlaybackError::Send { source, .. } => Some(crate::transport::send_failure(source)),
+            _ => None,
+        }
+    }
+
+    pub fn failure(&self) -> PlaybackFailure {
+        match self {
+            PlaybackError::UnknownPrompt { .. } | PlaybackError::MissingSegments { .. } => PlaybackFailure::UnknownPrompt,
+            PlaybackError::Open { source: hound::Error::IoError(e), .. } if e.kind() == io::ErrorKind::NotFound => PlaybackFailure::FileMissing,
+            PlaybackError::Open { source: hound::Error::IoError(_), .. } => PlaybackFailure::ReadError,
+            PlaybackError::Open { .. } | PlaybackError::UnsupportedFormat { .. } => PlaybackFailure::BadFormat,
+            // İstek çalmadan önce reddedilir; olaylara yansımaz.
+            PlaybackError::InvalidRate { .. } | PlaybackError::InvalidBedGain { .. } => PlaybackFailure::BadFormat,
+            PlaybackError::InvalidDigits { .. } | PlaybackError::NumberOutOfRange { .. } => PlaybackFailure::BadFormat,
+            PlaybackError::Read { .. } | PlaybackError::Library { .. } => PlaybackFailure::ReadError,
+            PlaybackError::Send { .. } | PlaybackError::Packet(_) => PlaybackFailure::SendError,
+            PlaybackError::UrlCacheDisabled | PlaybackError::InvalidUrl { .. } | PlaybackError::Fetch { .. } => PlaybackFailure::FetchError,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("invalid port {port}")]
+    InvalidPort { port: u32 },
+    #[error("no active session on port {port}")]
+    NotFound { port: u16 },
+    #[error("no active session {session_id}")]
+    SessionIdNotFound { session_id: String },
+    #[error("session {session_id} is not on port {port}")]
+    SessionIdMismatch { session_id: String, port: u32 },
+    #[error("session on port {port} has not received RTP yet; remote address unknown")]
+    RemoteUnknown { port: u16 },
+    #[error("session on port {port} is already capturing to {path}")]
+    AlreadyCapturing { port: u16, path: String },
+    #[error("failed to start capture at {path}: {source}")]
+    Capture { path: String, source: io::Error },
+    #[error("audio dumps are disabled on this node (audio_dump.max_sessions is 0)")]
+    AudioDumpDisabled,
+    #[error("{max_sessions} session(s) are already dumping audio")]
+    AudioDumpLimit { max_sessions: usize },
+    #[error("failed to start audio dump at {path}: {source}")]
+    AudioDump { path: String, source: io::Error },
+    #[error("session on port {port} cannot be bridged to itself")]
+    SelfBridge { port: u16 },
+    #[error("session on port {port} is already bridged to port {peer}")]
+    AlreadyBridged { port: u16, peer: u16 },
+    #[error("session on port {port} is not bridged")]
+    NotBridged { port: u16 },
+    #[error("session on port {port} is already playing '{playing}'; set enqueue or interrupt")]
+    PlaybackBusy { port: u16, playing: String },
+    #[error("playback queue of session on port {port} is full ({max_queued} queued)")]
+    PlaybackQueueFull { port: u16, max_queued: usize },
+    #[error("enqueue and interrupt cannot both be set")]
+    ConflictingPlayModes,
+    #[error("session on port {port} cannot tap itself")]
+    SelfTap { port: u16 },
+    #[error("session on port {port} is a tap leg; it cannot be tapped, bridged or play audio")]
+    TapLeg { port: u16 },
+    #[error("session on port {port} is already tapping port {observed}")]
+    AlreadyTapping { port: u16, observed: u16 },
+    #[error("session on port {port} is being tapped and cannot become a tap leg")]
+    Tapped { port: u16 },
+    #[error("session on port {port} already has {max_taps} tap(s)")]
+    TapLimit { port: u16, max_taps: usize },
+    #[error("session on port {port} is not tapping a session")]
+    NotTapping { port: u16 },
+    #[error("node is overloaded ({cause}); new playbacks are refused")]
+    Overloaded { cause: &'static str },
+}
+
+#[derive(Debug, Error)]
+pub enum DecryptError {
+    #[error("usage: media decrypt-recording <input> <output.wav> [--key-file PATH]")]
+    Usage,
+    #[error("not an encrypted recording")]
+    NotEncrypted,
+    #[error("unsupported encrypted recording version {version}")]
+    UnsupportedVersion { version: u8 },
+    #[error("encrypted recording header is corrupt: invalid sample rate {sample_rate}")]
+    InvalidSampleRate { sample_rate: u32 },
+    #[error("chunk {chunk} failed authentication; wrong key for '{key_id}' or the file was modified")]
+    Authentication { chunk: u32, key_id: String },
+    #[error("encrypted recording is corrupt at chunk {chunk}")]
+    Corrupt { chunk: u32 },
+    #[error("recording is truncated; {recovered_ms} ms up to the last complete chunk were written to {output}")]
+    Truncated { recovered_ms: u64, output: String },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// `media replay-pcap` kaydı okuyamadı ya da oynatamadı.
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("usage: media replay-pcap <file.pcap> <IP:port> [--speed N] [--ssrc SSRC] [--flow SRC[,DST]]")]
+    Usage,
+    #[error("failed to read '{path}': {source}")]
+    Read { path: String, source: io::Error },
+    #[error("not a readable pcap file: {reason}")]
+    Format { reason: String },
+    #[error("unsupported pcap link type {link_type}; Ethernet, raw IP, Linux SLL and BSD loopback are read")]
+    UnsupportedLinkType { link_type: u32 },
+    #[error("pcap holds several RTP streams, choose one with --ssrc or --flow: {streams}")]
+    MultipleStreams { streams: String },
+    #[error("failed to send to {target}: {source}")]
+    Send { target: SocketAddr, source: io::Error },
+}
+
+#[derive(Debug, Error)]
+pub enum RecordingError {
+    #[error("unknown placeholder {{{placeholder}}} in recording name template '{template}'")]
+    UnknownPlaceholder { template: String, placeholder: String },
+    #[error("recording name '{name}' must be a relative path inside the recording directory")]
+    InvalidName { name: String },
+    #[error("session on port {port} is already recording to {path}")]
+    AlreadyRecording { port: u16, path: String },
+    #[error("session on port {port} is not recording")]
+    NotRecording { port: u16 },
+    #[error("recording directory uses {used} bytes, at or above the {limit}-byte limit")]
+    DiskQuotaExceeded { used: u64, limit: u64 },
+    #[error("recording file {path} already exists")]
+    FileExists { path: String },
+    #[error("unknown recording format '{format}'; use pcm16-wav, ulaw-wav, alaw-wav or raw-ulaw")]
+    UnknownFormat { format: String },
+    #[error("{format} recordings cannot be encrypted; encrypted recordings are pcm16-wav")]
+    NotEncryptable { format: &'static str },
+    #[error("failed to write recording {path}: {source}")]
+    Io { path: String, source: io::Error },
+}
+
+#[derive(Debug, Error)]
+pub enum PromptStoreError {
+    #[error("announcement uploads are disabled on this node (announcement.upload_dir is not set)")]
+    Disabled,
+    #[error("invalid announcement name '{name}'; use 1-64 letters, digits, '-' or '_'")]
+    InvalidName { name: String },
+    #[error("announcement upload exceeds the {limit}-byte limit")]
+    TooLarge { limit: u64 },
+    #[error("unknown announcement '{name}'")]
+    NotFound { name: String },
+    #[error("announcement '{name}' is in use by {by}")]
+    InUse { name: String, by: String },
+    #[error("uploaded announcement is not a playable prompt: {0}")]
+    Invalid(PlaybackError),
+    #[error("failed to store announcement at {path}: {source}")]
+    Io { path: String, source: io::Error },
+}
+
+/// GenerateSdp'ye verilen uzak gövde okunamadı ya da oturumun medya ayarlarıyla anlaşmıyor.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SdpError {
+    #[error("malformed SDP at line {line} '{text}': {reason}")]
+    Malformed { line: usize, text: String, reason: &'static str },
+    #[error("remote SDP has no active RTP audio stream")]
+    NoAudio,
+    #[error("remote SDP has no connection address for its audio stream")]
+    NoConnection,
+    #[error("remote SDP does not offer the session codec {codec} (offered: {offered})")]
+    NoCommonCodec { codec: &'static str, offered: String },
+    #[error("remote SDP offers {offered} but the session transport is {transport}")]
+    TransportMismatch { offered: String, transport: &'static str },
+    #[error("session sends RED with payload type {payload_type} but the remote SDP does not offer it")]
+    RedNotOffered { payload_type: u8 },
+    #[error("an answer needs the remote offer in remote_sdp")]
+    OfferRequired,
+    #[error("remote_sdp is only accepted when answering")]
+    UnexpectedRemoteSdp,
+    #[error("no address to advertise; set rtp.advertise_address or bind rtp.host to a specific address")]
+    NoAdvertiseAddress,
+}
+
+/// SetImpairment ya da ayırmadaki bozulma ayarı uygulanamadı.
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum ImpairmentError {
+    #[error("this build does not include network impairments (build with the 'impairment' feature)")]
+    NotBuilt,
+    #[error("network impairments are disabled on this node (rtp.enable_impairments is false)")]
+    Disabled,
+    #[error("{field} must be between 0 and 100, got {value}")]
+    InvalidPercent { field: &'static str, value: f32 },
+    #[error("jitter_ms {jitter_ms} exceeds the limit of {max}")]
+    InvalidJitter { jitter_ms: u32, max: u32 },
+}
+
+/// ImportSessions'a verilen belge okunamadı; hiçbir oturum kurulmaz.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MigrationError {
+    #[error("session state is not a valid export document: {reason}")]
+    InvalidState { reason: String },
+    #[error("session state has format version {version}; this node reads versions {oldest} to {supported}")]
+    UnsupportedVersion { version: u32, oldest: u32, supported: u32 },
+}
+
+/// Gelen RTP baytları geçerli bir paket değil. Kimliği doğrulanmamış porttan gelir; paket
+/// düşürülür, oturum etkilenmez.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("RTP packet too short: {len} bytes")]
+    TooShort { len: usize },
+    #[error("unsupported RTP version {version}")]
+    Version { version: u8 },
+    #[error("CSRC list of {count} entries runs past the end of a {len}-byte packet")]
+    CsrcOverrun { count: usize, len: usize },
+    #[error("header extension runs past the end of a {len}-byte packet")]
+    ExtensionOverrun { len: usize },
+    #[error("invalid padding length {padding} in a {len}-byte packet")]
+    Padding { padding: u8, len: usize },
+}
+
+/// Gelen RTCP baytları geçerli bir paket değil; RTP'deki gibi paket düşürülür.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum RtcpError {
+    #[error("RTCP packet too short: {len} bytes")]
+    TooShort { len: usize },
+    #[error("unsupported RTCP version {version}")]
+    Version { version: u8 },
+    #[error("RTCP length of {declared} bytes runs past the end of a {len}-byte packet")]
+    LengthOverrun { declared: usize, len: usize },
+    #[error("report block of {declared} bytes runs past the end of a {len}-byte packet")]
+    BlockOverrun { declared: usize, len: usize },
+}
+
+/// RFC 2198 RED yükü blok başlıklarıyla uyuşmuyor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum RedError {
+    #[error("RED block headers run past the end of a {len}-byte payload")]
+    Truncated { len: usize },
+    #[error("RED block of {declared} bytes runs past the end of a {len}-byte payload")]
+    BlockOverrun { declared: usize, len: usize },
+    #[error("RED payload carries more than 15 redundant blocks")]
+    TooManyBlocks,
+}
+
+/// Giden RTP paketi çağıranın tamponuna yazılamadı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum BuildError {
+    #[error("packet needs {needed} bytes but the buffer holds {available}")]
+    BufferTooSmall { needed: usize, available: usize },
+    #[error("{count} CSRCs exceed the RTP limit of 15")]
+    TooManyCsrcs { count: usize },
+    #[error("header extension of {len} bytes is not a multiple of 4")]
+    ExtensionNotAligned { len: usize },
+    #[error("padding length must be at least 1")]
+    ZeroPadding,
+    #[error("{count} report blocks exceed the RTCP limit of 31")]
+    TooManyReportBlocks { count: usize },
+    #[error("RTCP text of {len} bytes exceeds the limit of 255")]
+    TextTooLong { len: usize },
+}
+
+/// Oturum kapanış kancasının tek bir denemesi başarısız oldu; yalnızca loglanır, yeniden denenir.
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("hook request failed: {0}")]
+    Request(String),
+    #[error("hook endpoint answered {status}")]
+    Status { status: u16 },
+    #[error("failed to run hook command '{program}': {source}")]
+    Spawn { program: String, source: io::Error },
+    #[error("hook command exited with {status}")]
+    Exit { status: std::process::ExitStatus },
+    #[error("hook attempt timed out after {timeout_ms} ms")]
+    Timeout { timeout_ms: u64 },
+}
+
+/// Bir CDR kaydı dosyaya yazılamadı; kayıt düşer, sayılır ve loglanır.
+#[derive(Debug, Error)]
+pub enum CdrError {
+    #[error("failed to write CDR file '{path}': {source}")]
+    Write { path: String, source: io::Error },
+    #[error("failed to rotate CDR file '{path}': {source}")]
+    Rotate { path: String, source: io::Error },
+}
+
+/// Port günlüğü yazılamadı ya da okunamadı. Yazma hataları yalnızca sayılır ve loglanır; tahsis ve
+/// kapanış etkilenmez.
+#[derive(Debug, Error)]
+pub enum PortJournalError {
+    #[error("port journal is disabled on this node")]
+    Disabled,
+    #[error("time range is empty: from_ms {from_ms} is after to_ms {to_ms}")]
+    InvalidRange { from_ms: u64, to_ms: u64 },
+    #[error("failed to write port journal '{path}': {source}")]
+    Write { path: String, source: io::Error },
+    #[error("failed to rotate port journal '{path}': {source}")]
+    Rotate { path: String, source: io::Error },
+    #[error("failed to read port journal '{path}': {source}")]
+    Read { path: String, source: io::Error },
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Allocation(#[from] AllocationError),
+    #[error(transparent)]
+    Playback(#[from] PlaybackError),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    Recording(#[from] RecordingError),
+    #[error(transparent)]
+    PromptStore(#[from] PromptStoreError),
+    #[error(transparent)]
+    Sdp(#[from] SdpError),
+    #[error(transparent)]
+    Impairment(#[from] ImpairmentError),
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+    #[error(transparent)]
+    PortJournal(#[from] PortJournalError),
+    #[error("invalid listen address: {0}")]
+    Address(#[from] AddrParseError),
+    #[error("failed to start {server} listener on {addr}: {reason}")]
+    Listen { server: &'static str, addr: SocketAddr, reason: String },
+    #[error("failed to start {server} listener on unix socket {path}: {reason}")]
+    ListenUnix { server: &'static str, path: String, reason: String },
+    #[error("telemetry setup failed: {0}")]
+    Telemetry(String),
+    #[error(transparent)]
+    Decrypt(#[from] DecryptError),
+    #[error(transparent)]
+    Replay(#[from] ReplayError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Bütün handler'lar için tek hata -> gRPC kodu eşlemesi.
+    pub fn code(&self) -> Code {
+        match self {
+            Error::Allocation(AllocationError::UnknownCodec { .. } | AllocationError::CodecDisabled { .. }) => Code::InvalidArgument,
+            Error::Allocation(AllocationError::InvalidRedPayloadType { .. } | AllocationError::InvalidDtmfPayloadType { .. }) => Code::InvalidArgument,
+            Error::Allocation(AllocationError::InvalidAudioLevelId { .. } | AllocationError::InvalidInitialSequence { .. }) => Code::InvalidArgument,
+            Error::Allocation(AllocationError::PayloadTypeConflict { .. } | AllocationError::InvalidRemoteAddress { .. }) => Code::InvalidArgument,
+            Error::Allocation(AllocationError::TooManyLabels { .. } | AllocationError::InvalidLabel { .. }) => Code::InvalidArgument,
+            Error::Allocation(AllocationError::InvalidDtmfHookUrl { .. }) => Code::InvalidArgument,
+            Error::Allocation(AllocationError::PortsExhausted { .. } | AllocationError::RateLimited { .. }) => Code::ResourceExhausted,
+            Error::Allocation(AllocationError::UnknownTenant { .. } | AllocationError::UnknownInterface { .. }) => Code::InvalidArgument,
+            Error::Allocation(AllocationError::TenantTokenRequired { .. } | AllocationError::InvalidTenantToken) => Code::Unauthenticated,
+            Error::Allocation(AllocationError::TenantMismatch { .. }) => Code::PermissionDenied,
+            Error::Allocation(AllocationError::TenantLimit { .. }) => Code::ResourceExhausted,
+            Error::Allocation(AllocationError::NoSharedPorts | AllocationError::AsrUnavailable) => Code::FailedPrecondition,
+            Error::Allocation(AllocationError::NoCodecEnabled | AllocationError::Bind { .. }) => Code::Internal,
+            Error::Playback(PlaybackError::UnknownPrompt { .. } | PlaybackError::MissingSegments { .. }) => Code::NotFound,
+            Error::Playback(PlaybackError::UrlCacheDisabled) => Code::FailedPrecondition,
+            Error::Playback(PlaybackError::InvalidUrl { .. } | PlaybackError::InvalidRate { .. } | PlaybackError::InvalidBedGain { .. }) => Code::InvalidArgument,
+            Error::Playback(PlaybackError::InvalidDigits { .. } | PlaybackError::NumberOutOfRange { .. }) => Code::InvalidArgument,
+            Error::Playback(_) => Code::Internal,
+            Error::Session(SessionError::InvalidPort { .. } | SessionError::SessionIdMismatch { .. }) => Code::InvalidArgument,
+            Error::Session(SessionError::NotFound { .. } | SessionError::SessionIdNotFound { .. }) => Code::NotFound,
+            Error::Session(SessionError::RemoteUnknown { .. }) => Code::FailedPrecondition,
+            Error::Session(SessionError::AlreadyCapturing { .. }) => Code::AlreadyExists,
+            Error::Session(SessionError::Capture { .. } | SessionError::AudioDump { .. }) => Code::Internal,
+            Error::Session(SessionError::AudioDumpDisabled) => Code::FailedPrecondition,
+            Error::Session(SessionError::AudioDumpLimit { .. }) => Code::ResourceExhausted,
+            Error::Session(SessionError::SelfBridge { .. }) => Code::InvalidArgument,
+            Error::Session(SessionError::AlreadyBridged { .. } | SessionError::NotBridged { .. }) => Code::FailedPrecondition,
+            Error::Session(SessionError::PlaybackBusy { .. }) => Code::FailedPrecondition,
+            Error::Session(SessionError::PlaybackQueueFull { .. }) => Code::ResourceExhausted,
+            Error::Session(SessionError::ConflictingPlayModes) => Code::InvalidArgument,
+            Error::Session(SessionError::SelfTap { .. }) => Code::InvalidArgument,
+            Error::Session(SessionError::TapLeg { .. } | SessionError::AlreadyTapping { .. } | SessionError::Tapped { .. } | SessionError::NotTapping { .. }) => Code::FailedPrecondition,
+            Error::Session(SessionError::TapLimit { .. } | SessionError::Overloaded { .. }) => Code::ResourceExhausted,
+            Error::Recording(RecordingError::UnknownPlaceholder { .. } | RecordingError::InvalidName { .. } | RecordingError::UnknownFormat { .. }) => Code::InvalidArgument,
+            Error::Recording(RecordingError::AlreadyRecording { .. } | RecordingError::FileExists { .. }) => Code::AlreadyExists,
+            Error::Recording(RecordingError::NotRecording { .. } | RecordingError::NotEncryptable { .. }) => Code::FailedPrecondition,
+            Error::Recording(RecordingError::DiskQuotaExceeded { .. }) => Code::ResourceExhausted,
+            Error::Recording(RecordingError::Io { .. }) => Code::Internal,
+            Error::PromptStore(PromptStoreError::Disabled | PromptStoreError::InUse { .. }) => Code::FailedPrecondition,
+            Error::PromptStore(PromptStoreError::NotFound { .. }) => Code::NotFound,
+            Error::PromptStore(PromptStoreError::InvalidName { .. } | PromptStoreError::Invalid(_)) => Code::InvalidArgument,
+            Error::PromptStore(PromptStoreError::TooLarge { .. }) => Code::ResourceExhausted,
+            Error::PromptStore(PromptStoreError::Io { .. }) => Code::Internal,
+            Error::Sdp(SdpError::Malformed { .. } | SdpError::NoAudio | SdpError::NoConnection) => Code::InvalidArgument,
+            Error::Sdp(SdpError::OfferRequired | SdpError::UnexpectedRemoteSdp) => Code::InvalidArgument,
+            Error::Sdp(SdpError::NoCommonCodec { .. } | SdpError::TransportMismatch { .. } | SdpError::RedNotOffered { .. }) => Code::FailedPrecondition,
+            Error::Sdp(SdpError::NoAdvertiseAddress) => Code::FailedPrecondition,
+            Error::Impairment(ImpairmentError::NotBuilt | ImpairmentError::Disabled) => Code::FailedPrecondition,
+            Error::Impairment(ImpairmentError::InvalidPercent { .. } | ImpairmentError::InvalidJitter { .. }) => Code::InvalidArgument,
+            Error::Migration(MigrationError::InvalidState { .. } | MigrationError::UnsupportedVersion { .. }) => Code::InvalidArgument,
+            Error::PortJournal(PortJournalError::Disabled) => Code::FailedPrecondition,
+            Error::PortJournal(PortJournalError::InvalidRange { .. }) => Code::InvalidArgument,
+            Error::PortJournal(PortJournalError::Write { .. } | PortJournalError::Rotate { .. } | PortJournalError::Read { .. }) => Code::Internal,
+            Error::Config(ConfigError::InvalidLogLevel { .. }) => Code::InvalidArgument,
+            Error::Config(ConfigError::LogLevelUnmanaged) => Code::FailedPrecondition,
+            Error::Config(_) | Error::Address(_) | Error::Listen { .. } | Error::ListenUnix { .. } | Error::Telemetry(_) | Error::Decrypt(_) | Error::Replay(_) | Error::Io(_) => Code::Internal,
+        }
+    }
+}
+
+// gRPC istemcilerinin yeniden deneme politikasının okuduğu bekleme ipucu.
+const RETRY_PUSHBACK: &str = "grpc-retry-pushback-ms";
+
+impl From<Error> for Status {
+    fn from(error: Error) -> Self {
+        let mut status = Status::new(error.code(), error.to_string());
+        if let Error::Allocation(AllocationError::RateLimited { retry_after }) = &error {
+            status.metadata_mut().insert(RETRY_PUSHBACK, (retry_after.as_millis() as u64).into());
+        }
+        status
+    }
+}
+
+macro_rules! status_from {
+    ($($ty:ty),*) => {$(
+        impl From<$ty> for Status {
+            fn from(error: $ty) -> Self {
+                Error::from(error).into()
+            }
+        }
+    )*};
+}
+status_from!(ConfigError, AllocationError, PlaybackError, SessionError, RecordingError, PromptStoreError, SdpError, ImpairmentError, MigrationError, PortJournalError);
+
+fn suggestion_hint(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!("; did you mean: {}", suggestions.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_map_to_grpc_codes() {
+        let cases: Vec<(Error, Code)> = vec![
+            (AllocationError::UnknownCodec { name: "opus".into() }.into(), Code::InvalidArgument),
+            (AllocationError::CodecDisabled { codec: &crate::codec::Pcma }.into(), Code::InvalidArgument),
+            (AllocationError::PortsExhausted { min_port: 10000, max_port: 10001, attempts: 100 }.into(), Code::ResourceExhausted),
+            (AllocationError::Bind { port: 10000, source: io::ErrorKind::AddrNotAvailable.into() }.into(), Code::Internal),
+            (AllocationError::UnknownTenant { name: "acme".into() }.into(), Code::InvalidArgument),
+            (AllocationError::UnknownInterface { name: "lan".into() }.into(), Code::InvalidArgument),
+            (AllocationError::TenantTokenRequired { tenant: "acme".into() }.into(), Code::Unauthenticated),
+            (AllocationError::InvalidTenantToken.into(), Code::Unauthenticated),
+            (AllocationError::TenantMismatch { tenant: "acme".into(), requested: "globex".into() }.into(), Code::PermissionDenied),
+            (AllocationError::TenantLimit { tenant: "acme".into(), max_sessions: 10 }.into(), Code::ResourceExhausted),
+            (AllocationError::NoSharedPorts.into(), Code::FailedPrecondition),
+            (AllocationError::AsrUnavailable.into(), Code::FailedPrecondition),
+            (AllocationError::InvalidDtmfHookUrl { url: "https://example.com".into() }.into(), Code::InvalidArgument),
+            (AllocationError::InvalidRemoteAddress { address: "sbc.example".into() }.into(), Code::InvalidArgument),
+            (AllocationError::InvalidLabel { key: "Queue".into(), reason: "x" }.into(), Code::InvalidArgument),
+            (PlaybackError::UnknownPrompt { name: "welcom".into(), suggestions: vec![] }.into(), Code::NotFound),
+            (PlaybackError::UrlCacheDisabled.into(), Code::FailedPrecondition),
+            (PlaybackError::InvalidUrl { url: "ftp://x".into(), reason: "x".into() }.into(), Code::InvalidArgument),
+            (PlaybackError::InvalidRate { rate: 4.0 }.into(), Code::InvalidArgument),
+            (PlaybackError::InvalidBedGain { gain_db: 6.0 }.into(), Code::InvalidArgument),
+            (PlaybackError::InvalidDigits { digits: "12a".into() }.into(), Code::InvalidArgument),
+            (PlaybackError::NumberOutOfRange { number: u64::MAX }.into(), Code::InvalidArgument),
+            (PlaybackError::MissingSegments { missing: vec!["say_3".into()] }.into(), Code::NotFound),
+            (SessionError::InvalidPort { port: 70000 }.into(), Code::InvalidArgument),
+            (SessionError::NotFound { port: 10000 }.into(), Code::NotFound),
+            (SessionError::SessionIdNotFound { session_id: "0123456789abcdef".to_string() }.into(), Code::NotFound),
+            (SessionError::SessionIdMismatch { session_id: "0123456789abcdef".to_string(), port: 10000 }.into(), Code::InvalidArgument),
+            (SessionError::RemoteUnknown { port: 10000 }.into(), Code::FailedPrecondition),
+            (SessionError::AlreadyCapturing { port: 10000, path: "a.pcap".into() }.into(), Code::AlreadyExists),
+            (SessionError::AudioDumpDisabled.into(), Code::FailedPrecondition),
+            (SessionError::AudioDumpLimit { max_sessions: 2 }.into(), Code::ResourceExhausted),
+            (SessionError::AlreadyBridged { port: 10000, peer: 10002 }.into(), Code::FailedPrecondition),
+            (SessionError::PlaybackBusy { port: 10000, playing: "welcome".into() }.into(), Code::FailedPrecondition),
+            (SessionError::PlaybackQueueFull { port: 10000, max_queued: 8 }.into(), Code::ResourceExhausted),
+            (SessionError::ConflictingPlayModes.into(), Code::InvalidArgument),
+            (SessionError::SelfTap { port: 10000 }.into(), Code::InvalidArgument),
+            (SessionError::TapLeg { port: 10000 }.into(), Code::FailedPrecondition),
+            (SessionError::AlreadyTapping { port: 10000, observed: 10002 }.into(), Code::FailedPrecondition),
+            (SessionError::Tapped { port: 10000 }.into(), Code::FailedPrecondition),
+            (SessionError::TapLimit { port: 10000, max_taps: 2 }.into(), Code::ResourceExhausted),
+            (SessionError::NotTapping { port: 10000 }.into(), Code::FailedPrecondition),
+            (SessionError::Overloaded { cause: "cpu" }.into(), Code::ResourceExhausted),
+            (RecordingError::DiskQuotaExceeded { used: 2048, limit: 1024 }.into(), Code::ResourceExhausted),
+            (RecordingError::UnknownFormat { format: "mp3".into() }.into(), Code::InvalidArgument),
+            (RecordingError::NotEncryptable { format: "ulaw-wav" }.into(), Code::FailedPrecondition),
+            (SdpError::Malformed { line: 3, text: "s".into(), reason: "expected <type>=<value>" }.into(), Code::InvalidArgument),
+            (SdpError::OfferRequired.into(), Code::InvalidArgument),
+            (SdpError::NoCommonCodec { codec: "pcmu", offered: "PCMA/8000".into() }.into(), Code::FailedPrecondition),
+            (SdpError::NoAdvertiseAddress.into(), Code::FailedPrecondition),
+            (ImpairmentError::Disabled.into(), Code::FailedPrecondition),
+            (ImpairmentError::InvalidPercent { field: "loss_pct", value: 120.0 }.into(), Code::InvalidArgument),
+            (MigrationError::InvalidState { reason: "expected value at line 1 column 1".into() }.into(), Code::InvalidArgument),
+            (MigrationError::UnsupportedVersion { version: 3, oldest: 1, supported: 2 }.into(), Code::InvalidArgument),
+            (PortJournalError::Disabled.into(), Code::FailedPrecondition),
+            (PortJournalError::InvalidRange { from_ms: 2_000, to_ms: 1_000 }.into(), Code::InvalidArgument),
+            (PortJournalError::Read { path: "journal/ports.log".into(), source: io::Error::from(io::ErrorKind::PermissionDenied) }.into(), Code::Internal),
+            (ConfigError::InvalidLogLevel { level: "loud".into(), reason: "x".into() }.into(), Code::InvalidArgument),
+            (ConfigError::LogLevelUnmanaged.into(), Code::FailedPrecondition),
+        ];
+        for (error, code) in cases {
+            let message = error.to_string();
+            let status = Status::from(error);
+            assert_eq!(status.code(), code, "{}", message);
+            assert_eq!(status.message(), message);
+        }
+    }
+
+    #[test]
+    fn messages_carry_context() {
+        let status = Status::from(SessionError::NotFound { port: 12345 });
+        assert_eq!(status.message(), "no active session on port 12345");
+
+        let error = PlaybackError::UnknownPrompt { name: "welcom".into(), suggestions: vec!["welcome".into()] };
+        assert_eq!(error.to_string(), "prompt 'welcom' is not defined; did you mean: welcome");
+
+        let error = AllocationError::PortsExhausted { min_port: 10000, max_port: 10001, attempts: 100 };
+        assert_eq!(error.to_string(), "no free RTP port in 10000-10001 after 100 attempts");
+
+        let status = Status::from(AllocationError::RateLimited { retry_after: Duration::from_millis(250) });
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        assert_eq!(status.metadata().get(RETRY_PUSHBACK).unwrap(), "250");
+    }
+}
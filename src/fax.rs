@@ -0,0 +1,223 @@
+// Gelen seste faks tonları: arayan faksın CNG'si (1100 Hz, 0,5 s açık / 3 s kapalı, T.30) ve
+// cevaplayan faksın CED'i (2100 Hz, 2,6–4 s sürekli, V.25). Çözülmüş ses 10 ms'lik bloklara
+// bölünür; her blokta iki frekansın Goertzel gücü bloğun toplam enerjisiyle karşılaştırılır ve
+// gücün çoğu tek frekanstaysa blok o tonu taşır. CNG, süresi 0,5 s ±%20 olan bir darbe bitince;
+// CED, 500 ms kesintisiz sürünce bildirilir. CED'in yankı gidericileri kapatan genlik modülasyonlu
+// biçimi (ANSam, V.8: 15 Hz, %20 derinlik) tonun blok genliklerindeki dalgalanmadan ayırt edilir.
+// Her ton oturum başına bir kez bildirilir; arayan faks CNG'yi 3 saniyede bir tekrarlar.
+use std::time::Duration;
+
+use crate::audit::FaxTone;
+use crate::config::FaxDetectionConfig;
+
+const BLOCK_MS: u64 = 10;
+const CNG_HZ: f64 = 1100.0;
+const CED_HZ: f64 = 2100.0;
+// Bloğun gücünün bu oranı tek frekanstaysa blok tonu taşır; 10 ms'lik blokta CNG'nin ±38 Hz
+// toleransına denk gelir.
+const TONE_RATIO: f64 = 0.6;
+const CNG_MIN_MS: u64 = 400;
+const CNG_MAX_MS: u64 = 700;
+const CED_MIN_MS: u64 = 500;
+// Ton bu kadar blok kesilmeden bitmiş sayılmaz; tek bir kayıp paket darbeyi bölmez.
+const HANGOVER_BLOCKS: u32 = 2;
+// ANSam'in %20'lik modülasyonu blok genliklerinde ~0,2 derinlik verir; düz CED'de ~0.
+const AM_MIN_DEPTH: f64 = 0.1;
+
+/// Tek bir algılama; `duration` tonun algılanana kadar süren kısmı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaxDetection {
+    pub tone: FaxTone,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Run {
+    blocks: u32,
+    // Tonun kesildiği ardışık bloklar.
+    gap: u32,
+}
+
+impl Run {
+    /// Bloğu işler; ton kesildiyse o ana kadarki uzunluğu (blok) döner.
+    fn observe(&mut self, present: bool) -> Option<u32> {
+        if present {
+            self.blocks += self.gap + 1;
+            self.gap = 0;
+            return None;
+        }
+        if self.blocks == 0 {
+            return None;
+        }
+        self.gap += 1;
+        if self.gap < HANGOVER_BLOCKS {
+            return None;
+        }
+        let blocks = self.blocks;
+        *self = Run::default();
+        Some(blocks)
+    }
+}
+
+#[derive(Debug)]
+pub struct FaxDetector {
+    block: usize,
+    sample_rate: u32,
+    min_power: f64,
+    pending: Vec<i16>,
+    cng: Run,
+    ced: Run,
+    // Süren CED'in blok genlikleri; modülasyon derinliği bunlardan ölçülür.
+    ced_levels: Vec<f64>,
+    cng_detected: bool,
+    ced_detected: bool,
+}
+
+impl FaxDetector {
+    pub fn new(config: &FaxDetectionConfig, sample_rate: u32) -> Self {
+        let block = (sample_rate as u64 * BLOCK_MS / 1000) as usize;
+        let full_scale = i16::MAX as f64 * i16::MAX as f64;
+        FaxDetector {
+            block, sample_rate, min_power: full_scale * 10f64.powf(config.min_level_dbov as f64 / 10.0),
+            pending: Vec::with_capacity(block), cng: Run::default(), ced: Run::default(), ced_levels: Vec::new(),
+            cng_detected: false, ced_detected: false,
+        }
+    }
+
+    /// Gelen çerçeveyi ekler; tamamlanan bloklarda algılanan tonları döner.
+    pub fn push(&mut self, pcm: &[i16]) -> Vec<FaxDetection> {
+        let mut detections = Vec::new();
+        for &sample in pcm {
+            self.pending.push(sample);
+            if self.pending.len() == self.block {
+                self.process_block(&mut detections);
+                self.pending.clear();
+            }
+        }
+        detections
+    }
+
+    fn process_block(&mut self, detections: &mut Vec<FaxDetection>) {
+        let energy: f64 = self.pending.iter().map(|&s| s as f64 * s as f64).sum();
+        let loud = energy / self.block as f64 >= self.min_power;
+        // Saf bir tonda Goertzel gücü enerjinin N/2 katıdır; oran 1'e yaklaşır.
+        let present = |power: f64| loud && power / (energy * self.block as f64 / 2.0) >= TONE_RATIO;
+        let ced_power = goertzel(&self.pending, CED_HZ, self.sample_rate);
+        let (cng, ced) = (present(goertzel(&self.pending, CNG_HZ, self.sample_rate)), present(ced_power));
+        let ms = |blocks: u32| blocks as u64 * BLOCK_MS;
+
+        if let Some(blocks) = self.cng.observe(cng) {
+            if !self.cng_detected && (CNG_MIN_MS..=CNG_MAX_MS).contains(&ms(blocks)) {
+                self.cng_detected = true;
+                detections.push(FaxDetection { tone: FaxTone::Cng, duration: Duration::from_millis(ms(blocks)) });
+            }
+        }
+
+        match self.ced.observe(ced) {
+            Some(_) => self.ced_levels.clear(),
+            None if ced => self.ced_levels.push(ced_power.sqrt()),
+            None => {}
+        }
+        if !self.ced_detected && ms(self.ced.blocks) >= CED_MIN_MS {
+            self.ced_detected = true;
+            let tone = if modulation_depth(&self.ced_levels) >= AM_MIN_DEPTH { FaxTone::CedAm } else { FaxTone::Ced };
+            detections.push(FaxDetection { tone, duration: Duration::from_millis(ms(self.ced.blocks)) });
+        }
+    }
+}
+
+/// `frequency`'deki Goertzel gücü (|X(k)|²).
+fn goertzel(samples: &[i16], frequency: f64, sample_rate: u32) -> f64 {
+    let coeff = 2.0 * (std::f64::consts::TAU * frequency / sample_rate as f64).cos();
+    let (mut s1, mut s2) = (0.0, 0.0);
+    for &sample in samples {
+        let s = sample as f64 + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s;
+    }
+    s1 * s1 + s2 * s2 - coeff * s1 * s2
+}
+
+/// Genliklerin (en büyük − en küçük) / (en büyük + en küçük) oranı.
+fn modulation_depth(levels: &[f64]) -> f64 {
+    let max = levels.iter().copied().fold(f64::MIN, f64::max);
+    let min = levels.iter().copied().fold(f64::MAX, f64::min);
+    if levels.is_empty() || max + min <= 0.0 {
+        return 0.0;
+    }
+    (max - min) / (max + min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 8000;
+
+    /// `ms` süren, `frequency`'de, `am_depth` derinliğinde 15 Hz ile modüle edilmiş ton.
+    fn tone(frequency: f64, ms: u64, amplitude: f64, am_depth: f64) -> Vec<i16> {
+        let rate = RATE as f64;
+        (0..(RATE as u64 * ms / 1000) as usize)
+            .map(|i| {
+                let t = i as f64 / rate;
+                let envelope = 1.0 + am_depth * (std::f64::consts::TAU * 15.0 * t).sin();
+                (amplitude * envelope * (std::f64::consts::TAU * frequency * t).sin()) as i16
+            })
+            .collect()
+    }
+
+    fn silence(ms: u64) -> Vec<i16> {
+        vec![0; (RATE as u64 * ms / 1000) as usize]
+    }
+
+    /// Sesi 20 ms'lik paketler halinde verip algılamaları toplar.
+    fn detect(detector: &mut FaxDetector, audio: &[i16]) -> Vec<FaxDetection> {
+        audio.chunks(160).flat_map(|frame| detector.push(frame)).collect()
+    }
+
+    fn new_detector() -> FaxDetector {
+        FaxDetector::new(&FaxDetectionConfig::default(), RATE)
+    }
+
+    #[test]
+    fn cng_cadence_is_detected_once_and_other_bursts_are_not() {
+        let mut detector = new_detector();
+        let mut audio = tone(CNG_HZ + 25.0, 500, 8000.0, 0.0);
+        audio.extend(silence(3000));
+        audio.extend(tone(CNG_HZ, 500, 8000.0, 0.0));
+        audio.extend(silence(100));
+        let detections = detect(&mut detector, &audio);
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].tone, FaxTone::Cng);
+        assert!((480..=520).contains(&(detections[0].duration.as_millis() as u64)), "{:?}", detections[0]);
+
+        // Çok kısa ya da çok uzun 1100 Hz, 1000 Hz ve eşiğin altındaki ton CNG sayılmaz.
+        for audio in [tone(CNG_HZ, 200, 8000.0, 0.0), tone(CNG_HZ, 1200, 8000.0, 0.0), tone(1000.0, 500, 8000.0, 0.0), tone(CNG_HZ, 500, 50.0, 0.0)] {
+            let mut detector = new_detector();
+            let mut audio = audio;
+            audio.extend(silence(100));
+            assert!(detect(&mut detector, &audio).is_empty());
+        }
+    }
+
+    #[test]
+    fn ced_and_its_amplitude_modulated_variant_are_told_apart() {
+        let mut detector = new_detector();
+        let detections = detect(&mut detector, &tone(CED_HZ, 2600, 8000.0, 0.0));
+        assert_eq!(detections, [FaxDetection { tone: FaxTone::Ced, duration: Duration::from_millis(CED_MIN_MS) }]);
+
+        let mut detector = new_detector();
+        let mut audio = silence(200);
+        audio.extend(tone(CED_HZ, 2600, 8000.0, 0.2));
+        let detections = detect(&mut detector, &audio);
+        assert_eq!(detections.iter().map(|d| d.tone).collect::<Vec<_>>(), [FaxTone::CedAm]);
+
+        // Kısa bir 2100 Hz vuruşu ya da konuşma benzeri iki tonlu ses CED değildir.
+        let mut detector = new_detector();
+        let mut audio = tone(CED_HZ, 300, 8000.0, 0.0);
+        audio.extend(silence(100));
+        let mixed: Vec<i16> = tone(CED_HZ, 1000, 4000.0, 0.0).iter().zip(tone(700.0, 1000, 4000.0, 0.0)).map(|(a, b)| a + b).collect();
+        audio.extend(mixed);
+        assert!(detect(&mut detector, &audio).is_empty());
+    }
+}
@@ -0,0 +1,1388 @@
+// MediaManager gRPC servisi: port tahsisi, anons, yakalama, oturum istatistikleri ve çalışma anı ayarları.
+use std::net::SocketAddr;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use tokio::time::Instant;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{debug, error, info, instrument, warn, Instrument, Span};
+
+use crate::admission;
+use crate::announcement::{PromptLibrary, ReloadOutcome};
+use crate::audio_level;
+use crate::audit::{self, FaxTone, MuteReason, PlaybackFailure, TapEndReason, UnbridgeReason};
+use crate::bridge;
+use crate::build_info;
+use crate::cdr;
+use crate::codec::{self, Codec};
+use crate::compose::{self, Grammar};
+use crate::config::{DetachedAudio, InterfaceConfig, RecordingFormat, RtpConfig, Settings, TenantConfig};
+use crate::encryption::RecordingKey;
+use crate::error::{AllocationError, ConfigError, ImpairmentError, PlaybackError, RecordingError, SdpError, SessionError, PromptStoreError};
+use crate::health::Health;
+use crate::impairment::Impairment;
+use crate::labels;
+use crate::logging;
+use crate::media::media_manager_server::MediaManager;
+use crate::media::{AllocatePortRequest, AllocatePortResponse, PlayAnnouncementRequest, PlayAnnouncementResponse};
+use crate::media::{CodecInfo, ListCodecsRequest, ListCodecsResponse, SetLogLevelRequest, SetLogLevelResponse};
+use crate::media::{ListSessionsRequest, ListSessionsResponse, SessionSummary};
+use crate::media::{GetSessionStatsRequest, GetSessionStatsResponse, RemoteReceptionReport, StartAudioDumpRequest, StartAudioDumpResponse, StartCaptureRequest, StartCaptureResponse};
+use crate::media::{BridgeSessionsRequest, BridgeSessionsResponse, UnbridgeSessionsRequest, UnbridgeSessionsResponse};
+use crate::media::{StartRecordingRequest, StartRecordingResponse, StopRecordingRequest, StopRecordingResponse};
+use crate::media::{AnnouncementCacheStatus, GetServerStatusRequest, GetServerStatusResponse, GetVersionRequest, GetVersionResponse, InterfaceStatus, TenantStatus, TransportKind};
+use crate::media::{AnnouncementInfo, FileChunk, ListAnnouncementsRequest, ListAnnouncementsResponse, UploadResult};
+use crate::media::{DeleteAnnouncementRequest, DeleteAnnouncementResponse, DumpStateRequest, DumpStateResponse};
+use crate::media::{ReloadAnnouncementsRequest, ReloadAnnouncementsResponse, ReloadedAnnouncement};
+use crate::media::{GenerateSdpRequest, GenerateSdpResponse, SdpRole, SetImpairmentRequest, SetImpairmentResponse, SetMuteRequest, SetMuteResponse};
+use crate::media::{SayDigitsRequest, SayNumberRequest, SayResponse, StopPlaybackRequest, StopPlaybackResponse};
+use crate::media::{ExportSessionsRequest, ExportSessionsResponse, ExportedSession, ImportSessionsRequest, ImportSessionsResponse, ImportedSession};
+use crate::media::{TapSessionRequest, TapSessionResponse, UntapSessionRequest, UntapSessionResponse};
+use crate::media::{MediaDirection, SetDirectionRequest, SetDirectionResponse};
+use crate::media::{PortTenancy, QueryPortHistoryRequest, QueryPortHistoryResponse, ReleasePortRequest, ReleasePortResponse};
+use crate::metrics::{self, AllocationFailure, AllocationOutcome, TenantMetrics};
+use crate::migration::{self, SessionExport};
+use crate::playback::{self, PlayMode, Playback};
+use crate::port_journal;
+use crate::ratelimit::TokenBucket;
+use crate::request_id;
+use crate::state;
+use crate::red::{self, RedConfig};
+use crate::rtp::{bind_rtp_port, Bound, PortPool, StreamSeed};
+use crate::rtcp;
+use crate::scheduler::SendScheduler;
+use crate::sdp::{self, Direction, LocalAudio};
+use crate::session::{release_session, rtp_session_handler, ActiveSessions, RtpSession};
+use crate::source::SilenceSource;
+use crate::telemetry;
+use crate::tap::{self, Tap};
+
+/// `MediaManager` gRPC servisi.
+#[derive(Debug)]
+pub struct MyMediaManager {
+    active_sessions: ActiveSessions,
+    settings: Arc<Settings>,
+    prompts: Arc<PromptLibrary>,
+    // Global subscriber'ı kurmayan gömülü kullanımlarda (ör. testler) yoktur; SetLogLevel reddedilir.
+    log_handle: Option<logging::LogReloadHandle>,
+    // Bağlanmış gRPC dinleyicileri; GetServerStatus'ta raporlanır.
+    listen_addresses: Vec<String>,
+    // `rate_limit.allocations_per_s` 0 ise yok.
+    allocation_limit: Option<Mutex<TokenBucket>>,
+    // `recording.encryption` açıksa kayıtlar bu anahtarla şifrelenir.
+    recording_key: Option<Arc<RecordingKey>>,
+    // DumpState'te raporlanır; gömülü kullanımda yoktur.
+    health: Option<Arc<Health>>,
+    // Oturumların giden sesini gönderen görevler.
+    scheduler: Arc<SendScheduler>,
+}
+
+impl MyMediaManager {
+    pub fn new(active_sessions: ActiveSessions, settings: Arc<Settings>, prompts: Arc<PromptLibrary>, log_handle: Option<logging::LogReloadHandle>) -> Self {
+        let limit = settings.rate_limit;
+        let allocation_limit = (limit.allocations_per_s > 0).then(|| Mutex::new(TokenBucket::new(limit.allocations_per_s, limit.allocation_burst)));
+        let scheduler = Arc::new(SendScheduler::start(settings.rtp.send_schedulers(), settings.timers.ptime()));
+        Self { active_sessions, settings, prompts, log_handle, listen_addresses: Vec::new(), allocation_limit, recording_key: None, health: None, scheduler }
+    }
+
+    /// Dinleyiciler bağlandıktan sonra gerçek adreslerle çağrılır.
+    pub fn with_listen_addresses(mut self, addresses: Vec<String>) -> Self {
+        self.listen_addresses = addresses;
+        self
+    }
+
+    /// Sunucunun sağlık durumu; DumpState çıktısına girer.
+    pub fn with_health(mut self, health: Arc<Health>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Başlangıçta yüklenen kayıt şifreleme anahtarı.
+    pub fn with_recording_key(mut self, key: Option<Arc<RecordingKey>>) -> Self {
+        self.recording_key = key;
+        self
+    }
+}
+
+#[tonic::async_trait]
+impl MediaManager for MyMediaManager {
+    #[instrument(skip(self, request), fields(call_id = %request.get_ref().call_id, request_id = %request_id::of(&request)))]
+    async fn allocate_port(&self, request: Request<AllocatePortRequest>) -> Result<Response<AllocatePortResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        info!("AllocatePort isteği alındı...");
+        let started = Instant::now();
+        self.check_allocation_rate(started)?;
+        let slow = self.settings.timers.slow_allocation();
+        let codec = self.select_codec(&request.get_ref().codec)
+            .inspect_err(|_| {
+                metrics::get().allocation_failed(AllocationFailure::InvalidCodec);
+                record_allocation(AllocationOutcome::Error, 0, started.elapsed(), slow);
+            })?;
+        let payload_types = self.red_config(request.get_ref().red_payload_type)
+            .and_then(|red| Ok((red, self.dtmf_payload_type(request.get_ref().dtmf_payload_type, red)?)))
+            .and_then(|(red, dtmf)| Ok((red, dtmf, audio_level_id(request.get_ref().audio_level_id)?)))
+            .and_then(|(red, dtmf, audio_level)| Ok((red, dtmf, audio_level, stream_seed(request.get_ref())?)))
+            .and_then(|(red, dtmf, audio_level, seed)| Ok((red, dtmf, audio_level, seed, send_only(&request.get_ref().remote_address)?)))
+            .and_then(|(red, dtmf, audio_level, seed, send_only)| Ok((red, dtmf, audio_level, seed, send_only, labels::validate(&request.get_ref().labels)?)))
+            .inspect_err(|_| {
+                metrics::get().allocation_failed(AllocationFailure::InvalidCodec);
+                record_allocation(AllocationOutcome::Error, 0, started.elapsed(), slow);
+            })?;
+        let (red, dtmf, audio_level_id, seed, send_only, labels) = payload_types;
+        let dtmf_hook_url = dtmf_hook_url(&request.get_ref().dtmf_hook_url)
+            .inspect_err(|_| {
+                metrics::get().allocation_failed(AllocationFailure::InvalidCodec);
+                record_allocation(AllocationOutcome::Error, 0, started.elapsed(), slow);
+            })?;
+        let interface = self.interface(&request.get_ref().interface)
+            .inspect_err(|_| {
+                metrics::get().allocation_failed(AllocationFailure::InvalidInterface);
+                record_allocation(AllocationOutcome::Error, 0, started.elapsed(), slow);
+            })?;
+        let impairment = impairment(&self.settings.rtp, request.get_ref().impairment.as_ref())
+            .inspect_err(|e| {
+                metrics::get().allocation_failed(AllocationFailure::InvalidCodec);
+                record_allocation(AllocationOutcome::Error, 0, started.elapsed(), slow);
+                warn!(error = %e, "Ağ bozulması uygulanamadı");
+            })?;
+        let asr = request.get_ref().asr;
+        if asr && !(cfg!(feature = "asr") && self.settings.asr.enabled) {
+            metrics::get().allocation_failed(AllocationFailure::InvalidCodec);
+            record_allocation(AllocationOutcome::Error, 0, started.elapsed(), slow);
+            warn!("Canlı transkripsiyon bu node'da kullanılamıyor");
+            return Err(AllocationError::AsrUnavailable.into());
+        }
+        let tenant = self.tenant(&request)
+            .and_then(|tenant| tenant.map(|config| reserve_tenant_slot(config).map(|counters| (config, counters))).transpose())
+            .inspect_err(|e| {
+                let reason = match e {
+                    AllocationError::TenantLimit { .. } => AllocationFailure::TenantLimit,
+                    _ => AllocationFailure::InvalidTenant,
+                };
+                metrics::get().allocation_failed(reason);
+                record_allocation(AllocationOutcome::Error, 0, started.elapsed(), slow);
+                warn!(error = %e, "Kiracı tahsisi reddedildi");
+            })?;
+        // TCP'de uzak uç bağlanana kadar gönderilemez; yalnızca gönderen oturumlar UDP'dir.
+        let transport = match request.get_ref().transport() {
+            TransportKind::Tcp if self.settings.rtp.allow_tcp && send_only.is_none() => TransportKind::Tcp,
+            _ => TransportKind::Udp,
+        };
+        let pool = match (&tenant, interface) {
+            (Some((config, _)), _) => PortPool::tenant(config),
+            (None, Some(interface)) => PortPool::interface(&self.settings.rtp, interface, &self.settings.tenants),
+            (None, None) => PortPool::shared(&self.settings.rtp, &self.settings.tenants),
+        };
+        let host = interface.map_or(self.settings.rtp.host.as_str(), |interface| interface.address.as_str());
+        let rtcp_mux = request.get_ref().rtcp_mux;
+        let (bound, attempts) = bind_rtp_port(&self.settings.rtp, host, &pool, transport, rtcp_mux).await;
+        let Bound { port, transport: sock, rtcp: rtcp_sock, overflow } = bound
+            .inspect_err(|e| {
+                metrics::get().allocation_failed(AllocationFailure::Exhausted);
+                if let Some((_, counters)) = &tenant {
+                    counters.active_sessions.dec();
+                    counters.allocation_failures.inc();
+                }
+                let outcome = match e {
+                    AllocationError::PortsExhausted { .. } => AllocationOutcome::Exhausted,
+                    _ => AllocationOutcome::Error,
+                };
+                record_allocation(outcome, attempts, started.elapsed(), slow);
+                error!(
+                    error = %e, attempts, tenant = tenant.as_ref().map(|(config, _)| config.name.as_str()),
+                    interface = interface.map(|interface| interface.name.as_str()), "RTP portu atanamadı",
+                );
+            })?;
+        metrics::get().allocations.inc();
+        metrics::get().active_sessions.inc();
+        if overflow {
+            metrics::get().overflow_allocations.inc();
+            metrics::get().overflow_sessions.inc();
+            warn!(rtp_port = port, attempts, "RTP port aralığı tükendi, aralık dışından port alındı");
+        }
+
+        let request_id = request_id::of(&request).to_string();
+        let mut session = RtpSession::new(port, codec, sock, &request.get_ref().call_id).with_request_id(&request_id);
+        if seed != StreamSeed::default() {
+            session = session.with_stream_seed(seed);
+        }
+        if let Some(red) = red {
+            session = session.with_red(red);
+        }
+        if let Some(payload_type) = dtmf {
+            session = session.with_dtmf(payload_type);
+        }
+        if let Some(url) = dtmf_hook_url {
+            session = session.with_dtmf_hook(url);
+        }
+        if overflow {
+            session = session.with_overflow();
+        }
+        if let Some(rtcp_sock) = rtcp_sock {
+            session = session.with_rtcp_port(rtcp_sock);
+        }
+        if let Some((_, counters)) = tenant.clone() {
+            counters.allocations.inc();
+            session = session.with_tenant(counters);
+        }
+        if let Some(id) = audio_level_id {
+            session = session.with_audio_level(id);
+        }
+        if let Some(interface) = interface {
+            session = session.with_interface(&interface.name, interface.advertise_address());
+        }
+        if self.settings.rtp.recv_batch > 1 {
+            session = session.with_recv_batch(self.settings.rtp.recv_batch);
+        }
+        let symmetric_rtp = request.get_ref().symmetric_rtp;
+        if let Some(remote) = send_only {
+            session = session.with_send_only(remote, symmetric_rtp);
+        }
+        let max_duration = match request.get_ref().max_duration_s {
+            0 => self.settings.timers.max_session_duration(),
+            secs => Some(Duration::from_secs(secs as u64)),
+        };
+        if let Some(max_duration) = max_duration {
+            session = session.with_max_duration(max_duration);
+        }
+        if self.settings.rate_limit.inbound_packets_per_s > 0 {
+            session = session.with_inbound_limit(&self.settings.rate_limit);
+        }
+        if let Some(interval) = self.settings.rtcp.report_interval() {
+            session = session.with_rtcp_reports(interval);
+        }
+        if !request.get_ref().language.is_empty() {
+            session = session.with_language(&request.get_ref().language);
+        }
+        // Denetim olayı için; etiketler oturuma taşınır.
+        let label_list = labels::format(&labels);
+        if !labels.is_empty() {
+            session = session.with_labels(labels);
+        }
+        if request.get_ref().fax_detection {
+            session = session.with_fax_detection(&self.settings.fax_detection);
+        }
+        if request.get_ref().amd {
+            session = session.with_amd(&self.settings.amd);
+        }
+        #[cfg(feature = "asr")]
+        if asr {
+            session = session.with_asr(&self.settings.asr);
+        }
+        let welcome = !request.get_ref().skip_welcome;
+        if !welcome {
+            session = session.without_welcome();
+        }
+        // RFC 3389'un 13 numaralı yük tipi yalnızca 8 kHz içindir.
+        let suppression = request.get_ref().comfort_noise && self.settings.silence_suppression.enabled && codec.clock_rate() == 8000;
+        if suppression {
+            session = session.with_silence_suppression(&self.settings.silence_suppression);
+        }
+        let session = Arc::new(session);
+        if let Some(impairment) = impairment {
+            let _entered = session.span.enter();
+            session.set_impairment(impairment);
+        }
+        let session_id = session.session_id.clone();
+        let rtcp_port = session.rtcp_port();
+        let (ssrc, (initial_sequence, initial_timestamp)) = (session.stream.ssrc, session.stream.initial());
+        let capture_path = if request.get_ref().capture {
+            // Yakalama açılamazsa tahsis yine de başarılı olur.
+            session.start_capture(&self.settings.capture)
+                .inspect_err(|e| warn!(parent: &session.span, error = %e, "pcap yakalaması başlatılamadı"))
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+        self.start_session(session);
+
+        info!(
+            target: audit::TARGET, event = audit::SESSION_ALLOCATED,
+            session_id = %session_id, call_id = %request.get_ref().call_id, request_id = %request_id, rtp_port = port, rtcp_port, rtcp_mux, codec = %codec,
+            red_payload_type = red.map(|r| r.payload_type), dtmf_payload_type = dtmf, silence_suppression = suppression, welcome,
+            max_duration_s = max_duration.map(|d| d.as_secs()), ssrc, transport = transport.as_str_name().to_lowercase(),
+            audio_level_id, overflow, tenant = tenant.as_ref().map(|(config, _)| config.name.as_str()),
+            language = Some(request.get_ref().language.as_str()).filter(|language| !language.is_empty()),
+            send_only = send_only.map(tracing::field::display), symmetric_rtp = send_only.map(|_| symmetric_rtp),
+            labels = Some(label_list.as_str()).filter(|labels| !labels.is_empty()),
+            fax_detection = request.get_ref().fax_detection, amd = request.get_ref().amd,
+            interface = interface.map(|interface| interface.name.as_str()), asr, dtmf_hook = dtmf_hook_url.is_some(),
+        );
+        record_allocation(AllocationOutcome::Success, attempts, started.elapsed(), slow);
+        let reply = AllocatePortResponse {
+            port: port as u32,
+            codec: codec.name().to_string(),
+            payload_type: codec.payload_type() as u32,
+            session_id,
+            capture_path,
+            ssrc,
+            initial_sequence: initial_sequence as u32,
+            initial_timestamp,
+            transport: transport as i32,
+            overflow,
+            advertise_address: interface.map_or_else(|| self.settings.rtp.advertise_address(), InterfaceConfig::advertise_address)
+                .map(|address| address.to_string())
+                .unwrap_or_default(),
+            rtcp_port: rtcp_port.unwrap_or(0) as u32,
+        };
+        let mut response = Response::new(reply);
+        request_id::attach(&mut response, &self.settings.grpc.request_id_header, &request_id);
+        Ok(response)
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn play_announcement(&self, request: Request<PlayAnnouncementRequest>) -> Result<Response<PlayAnnouncementResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let mode = PlayMode::requested(req.enqueue, req.interrupt)?;
+        let rate = playback::requested_rate(req.rate)?;
+        let bed = match req.bed.as_str() {
+            "" => None,
+            name => Some((self.prompts.get(name)?, playback::bed_gain(req.bed_gain_db.unwrap_or(self.settings.announcement.bed_gain_db()))?)),
+        };
+        let prompt = match req.url.as_str() {
+            "" => self.prompts.get(&req.name)?,
+            url => self.prompts.remote(url, &req.name)?,
+        };
+        if session.remote_addr.lock().unwrap().is_none() {
+            return Err(SessionError::RemoteUnknown { port: session.port }.into());
+        }
+        admission::admit()?;
+        let language = Some(req.language.as_str()).filter(|language| !language.is_empty()).or(session.language.as_deref());
+        let prompt = {
+            let _entered = session.span.enter();
+            prompt.in_language(language)
+        };
+
+        info!(rtp_port = session.port, prompt = %prompt.name, rate, bed = bed.as_ref().map(|(bed, _)| bed.name.as_str()), "Anons çalma isteği alındı");
+        let playback = {
+            let _entered = session.span.enter();
+            let playback = Playback::prompt(&prompt).map(|playback| playback.with_rate(rate));
+            match &bed {
+                Some((bed, gain_db)) => playback.and_then(|playback| playback.with_bed(bed, *gain_db)),
+                None => playback,
+            }
+            .inspect_err(|e| playback::load_failed(&session, &prompt.name, e))?
+        };
+        let playback_id = session.submit(playback, mode).await?;
+        Ok(Response::new(PlayAnnouncementResponse { playback_id }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn say_digits(&self, request: Request<SayDigitsRequest>) -> Result<Response<SayResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let mode = PlayMode::requested(req.enqueue, req.interrupt)?;
+        let keys = compose::digits(&req.digits)?;
+        self.say(req.port, &req.session_id, &req.language, mode, format!("digits:{}", req.digits), |_| Ok(keys)).await
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn say_number(&self, request: Request<SayNumberRequest>) -> Result<Response<SayResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let mode = PlayMode::requested(req.enqueue, req.interrupt)?;
+        let name = format!("{}:{}", if req.ordinal { "ordinal" } else { "number" }, req.number);
+        self.say(req.port, &req.session_id, &req.language, mode, name, |language| compose::number(req.number, req.ordinal, Grammar::for_language(language))).await
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn stop_playback(&self, request: Request<StopPlaybackRequest>) -> Result<Response<StopPlaybackResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let (stopped_playback_id, flushed) = session.stop_playback(req.flush).await?;
+        info!(rtp_port = session.port, stopped_playback_id, flushed, "Oynatma durduruldu");
+        Ok(Response::new(StopPlaybackResponse { stopped_playback_id, flushed: flushed as u32 }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn set_log_level(&self, request: Request<SetLogLevelRequest>) -> Result<Response<SetLogLevelResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let level = request.into_inner().level;
+        let handle = self.log_handle.as_ref().ok_or(ConfigError::LogLevelUnmanaged)?;
+        logging::set_level(handle, &level)?;
+        info!(level = %level, "Log seviyesi güncellendi");
+        Ok(Response::new(SetLogLevelResponse {}))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn start_capture(&self, request: Request<StartCaptureRequest>) -> Result<Response<StartCaptureResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let _entered = session.span.enter();
+        let path = session.start_capture(&self.settings.capture)
+            .inspect_err(|e| warn!(error = %e, "pcap yakalaması başlatılamadı"))?;
+        Ok(Response::new(StartCaptureResponse { path }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn start_audio_dump(&self, request: Request<StartAudioDumpRequest>) -> Result<Response<StartAudioDumpResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let _entered = session.span.enter();
+        let [inbound_path, outbound_path] = session.start_audio_dump(&self.settings.audio_dump)
+            .inspect_err(|e| warn!(error = %e, "Ses dökümü başlatılamadı"))?;
+        Ok(Response::new(StartAudioDumpResponse { inbound_path, outbound_path }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn start_recording(&self, request: Request<StartRecordingRequest>) -> Result<Response<StartRecordingResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let _entered = session.span.enter();
+        let name = Some(req.name.as_str()).filter(|name| !name.is_empty());
+        let format = match req.format.as_str() {
+            "" => None,
+            format => Some(RecordingFormat::parse(format).ok_or_else(|| RecordingError::UnknownFormat { format: format.to_string() })?),
+        };
+        let path = session.start_recording(&self.settings.recording, name, format, self.recording_key.clone())
+            .inspect_err(|e| warn!(error = %e, "Kayıt başlatılamadı"))?;
+        Ok(Response::new(StartRecordingResponse { path }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn stop_recording(&self, request: Request<StopRecordingRequest>) -> Result<Response<StopRecordingResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let summary = session.stop_recording().await?;
+        Ok(Response::new(StopRecordingResponse {
+            paths: summary.paths,
+            duration_ms: summary.duration.as_millis() as u64,
+            key_id: summary.key_id.unwrap_or_default(),
+        }))
+    }
+
+    async fn list_sessions(&self, request: Request<ListSessionsRequest>) -> Result<Response<ListSessionsResponse>, Status> {
+        let filter = request.into_inner().labels;
+        let now = Instant::now();
+        let mut sessions: Vec<SessionSummary> = self.active_sessions.lock().unwrap().values()
+            .filter(|session| labels::matches(&session.labels, &filter))
+            .map(|session| SessionSummary {
+                port: session.port as u32,
+                session_id: session.session_id.clone(),
+                call_id: session.call_id.clone(),
+                tenant: session.tenant.as_ref().map_or("", |t| t.name).to_string(),
+                codec: session.codec().name().to_string(),
+                remote_address: session.remote_addr.lock().unwrap().map(|addr| addr.to_string()).unwrap_or_default(),
+                age_ms: (now - session.allocated_at).as_millis() as u64,
+                labels: session.labels.clone().into_iter().collect(),
+                inbound_muted: session.inbound_muted(),
+                outbound_muted: session.outbound_muted(),
+                taps: session.taps.lock().unwrap().iter().filter_map(Tap::leg).map(|leg| leg.port as u32).collect(),
+                tapping: session.tap_source().map_or(0, |observed| observed.port as u32),
+                direction: media_direction(session.direction()) as i32,
+            })
+            .collect();
+        sessions.sort_by_key(|session| session.port);
+        Ok(Response::new(ListSessionsResponse { sessions }))
+    }
+
+    async fn get_session_stats(&self, request: Request<GetSessionStatsRequest>) -> Result<Response<GetSessionStatsResponse>, Status> {
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let stats = &session.stats;
+        let inbound = stats.inbound.lock().unwrap();
+        let quality = inbound.quality(session.codec().clock_rate(), self.settings.timers.ptime());
+        let playback_failure = *stats.playback_failure.lock().unwrap();
+        let fax_tone = *session.fax_tone.lock().unwrap();
+        let amd_result = *session.amd_result.lock().unwrap();
+        let remote_report = *stats.remote_report.lock().unwrap();
+        let now = Instant::now();
+        let (send_bitrate_bps, receive_bitrate_bps) = (stats.send_bitrate.lock().unwrap().current_bps(now), stats.receive_bitrate.lock().unwrap().current_bps(now));
+        Ok(Response::new(GetSessionStatsResponse {
+            duration_ms: session.allocated_at.elapsed().as_millis() as u64,
+            packets_sent: stats.packets_sent.load(Ordering::Relaxed),
+            bytes_sent: stats.bytes_sent.load(Ordering::Relaxed),
+            packets_received: stats.packets_received.load(Ordering::Relaxed),
+            bytes_received: stats.bytes_received.load(Ordering::Relaxed),
+            packets_malformed: stats.packets_malformed.load(Ordering::Relaxed),
+            packets_reflected: stats.packets_reflected.load(Ordering::Relaxed),
+            fax_tone: fax_tone.map_or("", FaxTone::as_str).to_string(),
+            amd_result: amd_result.map_or("", |result| result.verdict.as_str()).to_string(),
+            packets_lost: inbound.sequence.lost(),
+            packets_duplicated: inbound.sequence.duplicates(),
+            jitter_ms: inbound.jitter.jitter_ms(session.codec().clock_rate()),
+            clock_skew_ppm: inbound.skew.skew_ppm(),
+            r_factor: quality.map(|q| q.r_factor),
+            mos: quality.map(|q| q.mos),
+            red_recovered: stats.red_recovered.load(Ordering::Relaxed),
+            announcements_failed: stats.announcements_failed.load(Ordering::Relaxed),
+            playback_failure: playback_failure.map_or("", PlaybackFailure::as_str).to_string(),
+            ssrc: session.stream.ssrc,
+            remote_ssrc: inbound.remote_ssrc,
+            remote_audio_level: inbound.audio_level.map(|a| a.level as u32),
+            remote_voice: inbound.audio_level.is_some_and(|a| a.voice),
+            send_bitrate_bps,
+            receive_bitrate_bps,
+            inbound_muted: session.inbound_muted(),
+            outbound_muted: session.outbound_muted(),
+            direction: media_direction(session.direction()) as i32,
+            remote_report: remote_report.map(|report| RemoteReceptionReport {
+                age_ms: now.saturating_duration_since(report.received_at).as_millis() as u64,
+                fraction_lost: report.fraction_lost as f64 / 256.0,
+                cumulative_lost: report.cumulative_lost,
+                jitter_ms: report.jitter as f64 * 1000.0 / session.codec().clock_rate() as f64,
+                round_trip_ms: report.round_trip.map(|rtt| rtt.as_secs_f64() * 1000.0),
+            }),
+        }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn bridge_sessions(&self, request: Request<BridgeSessionsRequest>) -> Result<Response<BridgeSessionsResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let (a, b) = (self.addressed(req.port_a, &req.session_id_a)?, self.addressed(req.port_b, &req.session_id_b)?);
+        let detached = {
+            let _sessions = self.active_sessions.lock().unwrap();
+            bridge::bridge(&a, &b, self.settings.bridge.dtmf)?
+        };
+        for leg in detached {
+            self.play_detached_audio(&leg);
+        }
+        Ok(Response::new(BridgeSessionsResponse {}))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn unbridge_sessions(&self, request: Request<UnbridgeSessionsRequest>) -> Result<Response<UnbridgeSessionsResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let _sessions = self.active_sessions.lock().unwrap();
+        let peer_port = bridge::unbridge(&session, UnbridgeReason::Request)
+            .ok_or(SessionError::NotBridged { port: session.port })?;
+        Ok(Response::new(UnbridgeSessionsResponse { peer_port: peer_port as u32 }))
+    }
+
+    async fn get_server_status(&self, _request: Request<GetServerStatusRequest>) -> Result<Response<GetServerStatusResponse>, Status> {
+        let cache = self.prompts.cache_stats();
+        let sessions = self.active_sessions.lock().unwrap().values().cloned().collect::<Vec<_>>();
+        let load = admission::last_load();
+        Ok(Response::new(GetServerStatusResponse {
+            listen_addresses: self.listen_addresses.clone(),
+            active_sessions: sessions.len() as u32,
+            tenants: self.settings.tenants.iter()
+                .map(|tenant| {
+                    let counters = metrics::get().tenant(&tenant.name);
+                    TenantStatus {
+                        name: tenant.name.clone(), min_port: tenant.min_port as u32, max_port: tenant.max_port as u32,
+                        active_sessions: counters.active_sessions.get().max(0) as u32, max_sessions: tenant.max_sessions,
+                        allocations: counters.allocations.get(), allocation_failures: counters.allocation_failures.get(),
+                    }
+                })
+                .collect(),
+            announcement_cache: Some(AnnouncementCacheStatus {
+                bytes: cache.bytes, max_bytes: cache.max_bytes, entries: cache.entries as u32, pinned_entries: cache.pinned_entries as u32,
+                hits: cache.hits, misses: cache.misses, hit_ratio: cache.hit_ratio(), evictions: cache.evictions,
+            }),
+            interfaces: self.settings.interfaces.iter()
+                .map(|interface| {
+                    let (min_port, max_port) = interface.port_range(&self.settings.rtp);
+                    InterfaceStatus {
+                        name: interface.name.clone(), address: interface.address.clone(),
+                        advertise_address: interface.advertise_address().map(|address| address.to_string()).unwrap_or_default(),
+                        min_port: min_port as u32, max_port: max_port as u32,
+                        active_sessions: sessions.iter().filter(|session| session.interface.as_deref() == Some(interface.name.as_str())).count() as u32,
+                        ports: PortPool::interface(&self.settings.rtp, interface, &self.settings.tenants).len(),
+                    }
+                })
+                .collect(),
+            admission_state: admission::state().to_string(),
+            admission_cause: admission::cause().map(|cause| cause.as_str().to_string()).unwrap_or_default(),
+            cpu_percent: load.cpu_percent,
+            send_lag_p95_ms: load.send_lag_p95.as_secs_f64() * 1000.0,
+        }))
+    }
+
+    async fn get_version(&self, _request: Request<GetVersionRequest>) -> Result<Response<GetVersionResponse>, Status> {
+        Ok(Response::new(GetVersionResponse {
+            version: build_info::VERSION.to_string(),
+            git_commit: build_info::GIT_COMMIT.to_string(),
+            git_dirty: build_info::GIT_DIRTY,
+            build_timestamp: build_info::BUILD_TIMESTAMP.to_string(),
+            features: build_info::FEATURES.iter().map(|feature| feature.to_string()).collect(),
+            schema_version: build_info::SCHEMA_VERSION,
+        }))
+    }
+
+    async fn dump_state(&self, _request: Request<DumpStateRequest>) -> Result<Response<DumpStateResponse>, Status> {
+        let dump = state::snapshot(&self.active_sessions, &self.prompts, &self.settings, self.health.as_deref());
+        let json = serde_json::to_string_pretty(&dump).map_err(|e| Status::internal(e.to_string()))?;
+        info!(sessions = dump.sessions.len(), bytes = json.len(), "Durum dökümü istendi");
+        Ok(Response::new(DumpStateResponse { json }))
+    }
+
+    async fn list_codecs(&self, _request: Request<ListCodecsRequest>) -> Result<Response<ListCodecsResponse>, Status> {
+        let codecs = self.settings.rtp.enabled_codecs().into_iter()
+            .map(|c| CodecInfo { name: c.name().to_string(), payload_type: c.payload_type() as u32, clock_rate: c.clock_rate() })
+            .collect();
+        Ok(Response::new(ListCodecsResponse { codecs }))
+    }
+
+    #[instrument(skip(self, request), fields(request_id = %request_id::of(&request)))]
+    async fn upload_announcement(&self, request: Request<Streaming<FileChunk>>) -> Result<Response<UploadResult>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let limit = self.prompts.max_upload_bytes();
+        let mut chunks = request.into_inner();
+        let (mut name, mut data) = (None, Vec::new());
+        while let Some(chunk) = chunks.message().await? {
+            name.get_or_insert(chunk.name);
+            if (data.len() + chunk.data.len()) as u64 > limit {
+                return Err(PromptStoreError::TooLarge { limit }.into());
+            }
+            data.extend_from_slice(&chunk.data);
+        }
+
+        // Yazma, fsync ve doğrulama bloklar.
+        let (name, prompts) = (name.unwrap_or_default(), self.prompts.clone());
+        let (name, uploaded) = tokio::task::spawn_blocking(move || prompts.upload(&name, &data).map(|uploaded| (name, uploaded)))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))??;
+        let duration_ms = uploaded.duration.as_millis() as u64;
+        info!(
+            target: audit::TARGET, event = audit::PROMPT_UPLOADED,
+            prompt = %name, file = %uploaded.path, size_bytes = uploaded.size_bytes, duration_ms, sha256 = %uploaded.sha256,
+        );
+        Ok(Response::new(UploadResult { name, size_bytes: uploaded.size_bytes, duration_ms, sha256: uploaded.sha256 }))
+    }
+
+    async fn list_announcements(&self, _request: Request<ListAnnouncementsRequest>) -> Result<Response<ListAnnouncementsResponse>, Status> {
+        let prompts = self.prompts.clone();
+        let list = tokio::task::spawn_blocking(move || prompts.list()).await.map_err(|e| Status::internal(e.to_string()))?;
+        let announcements = list.into_iter()
+            .map(|prompt| {
+                let mut info = AnnouncementInfo {
+                    name: prompt.name, path: prompt.path, cached: prompt.cached, languages: prompt.languages, plays: prompt.plays,
+                    active_playbacks: prompt.active_playbacks as u32, ..AnnouncementInfo::default()
+                };
+                match prompt.file {
+                    Ok(file) => {
+                        info.size_bytes = file.size_bytes;
+                        info.duration_ms = file.duration.as_millis() as u64;
+                        info.sample_rate = file.sample_rate;
+                        info.bits_per_sample = file.bits_per_sample as u32;
+                        info.channels = file.channels as u32;
+                        info.sha256 = file.sha256;
+                    }
+                    Err(error) => info.error = error,
+                }
+                info
+            })
+            .collect();
+        Ok(Response::new(ListAnnouncementsResponse { announcements }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn delete_announcement(&self, request: Request<DeleteAnnouncementRequest>) -> Result<Response<DeleteAnnouncementResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let name = request.into_inner().name;
+        let bridge = &self.settings.bridge;
+        if bridge.detached_audio == DetachedAudio::Prompt && bridge.detached_prompt.as_deref() == Some(name.as_str()) {
+            return Err(PromptStoreError::InUse { name, by: "bridge.detached_prompt".to_string() }.into());
+        }
+        let hold = &self.settings.hold;
+        if hold.audio == DetachedAudio::Prompt && hold.prompt.as_deref() == Some(name.as_str()) {
+            return Err(PromptStoreError::InUse { name, by: "hold.prompt".to_string() }.into());
+        }
+        let path = self.prompts.delete(&name)?;
+        info!(target: audit::TARGET, event = audit::PROMPT_DELETED, prompt = %name, file = %path);
+        Ok(Response::new(DeleteAnnouncementResponse { path }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn reload_announcements(&self, request: Request<ReloadAnnouncementsRequest>) -> Result<Response<ReloadAnnouncementsResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let (names, prompts) = (request.into_inner().names, self.prompts.clone());
+        // Dosyaları okuyup çözmek bloklar.
+        let reloaded = tokio::task::spawn_blocking(move || prompts.reload(&names)).await.map_err(|e| Status::internal(e.to_string()))??;
+        let announcements = reloaded.into_iter()
+            .map(|(name, outcome)| {
+                let kind = match outcome {
+                    ReloadOutcome::Unchanged => crate::media::ReloadOutcome::Unchanged,
+                    ReloadOutcome::Changed => crate::media::ReloadOutcome::Changed,
+                    ReloadOutcome::Missing { .. } => crate::media::ReloadOutcome::Missing,
+                    ReloadOutcome::Invalid { .. } => crate::media::ReloadOutcome::Invalid,
+                };
+                ReloadedAnnouncement { name, outcome: kind as i32, reason: outcome.reason().unwrap_or_default().to_string() }
+            })
+            .collect();
+        Ok(Response::new(ReloadAnnouncementsResponse { announcements }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn set_impairment(&self, request: Request<SetImpairmentRequest>) -> Result<Response<SetImpairmentResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let _entered = session.span.enter();
+        let impairment = impairment(&self.settings.rtp, req.impairment.as_ref())
+            .inspect_err(|e| warn!(error = %e, "Ağ bozulması uygulanamadı"))?;
+        session.set_impairment(impairment.unwrap_or_default());
+        Ok(Response::new(SetImpairmentResponse {}))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn generate_sdp(&self, request: Request<GenerateSdpRequest>) -> Result<Response<GenerateSdpResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let address = session.advertise_address.or_else(|| self.settings.rtp.advertise_address()).ok_or(SdpError::NoAdvertiseAddress)?;
+        let tcp = session.transport.kind() == TransportKind::Tcp;
+        // Yalnızca gönderen oturum gelen akışı hedef yapmaz; yine de gelen paketleri sayar. SetDirection
+        // bunun üstüne uygulanır.
+        let direction = session.direction();
+        let local = Direction::from_flags(direction.sends(), direction.receives() && (!session.send_only || session.symmetric_rtp));
+        // Henüz medya göndermemiş oturum, node'da açık ve aynı saat hızındaki diğer codec'lere geçebilir:
+        // teklifte oturumun codec'inden sonra sıralanır, cevapta oturumunki teklifte yoksa seçilir.
+        let current = session.codec();
+        let alternatives: Vec<&'static dyn Codec> = if session.codec_negotiable() {
+            self.settings.rtp.enabled_codecs().into_iter().filter(|codec| *codec != current && codec.clock_rate() == current.clock_rate()).collect()
+        } else {
+            Vec::new()
+        };
+        let (answer, direction) = match (req.role(), req.remote_sdp.as_str()) {
+            (SdpRole::Offer, "") => {
+                session.set_offered_codecs(alternatives.clone());
+                (None, local)
+            }
+            (SdpRole::Offer, _) => return Err(SdpError::UnexpectedRemoteSdp.into()),
+            (SdpRole::Answer, "") => return Err(SdpError::OfferRequired.into()),
+            (SdpRole::Answer, remote) => {
+                let codecs: Vec<&'static dyn Codec> = std::iter::once(current).chain(alternatives.iter().copied()).collect();
+                let offer = sdp::parse(remote).inspect_err(|e| warn!(parent: &session.span, error = %e, "Uzak SDP teklifine cevap verilemedi"))?;
+                let answer = sdp::answer(&offer, &codecs, session.red, tcp, local)
+                    .inspect_err(|e| warn!(parent: &session.span, error = %e, "Uzak SDP teklifine cevap verilemedi"))?;
+                // Bu arada medya gönderilmeye başlandıysa oturum artık codec değiştiremez.
+                if !session.renegotiate_codec(answer.codec).instrument(session.span.clone()).await {
+                    let error = SdpError::NoCommonCodec { codec: current.name(), offered: offer.offered() };
+                    warn!(parent: &session.span, error = %error, "Uzak SDP teklifine cevap verilemedi");
+                    return Err(error.into());
+                }
+                (Some(answer), answer.direction)
+            }
+        };
+        let _entered = session.span.enter();
+        let remote = answer.filter(|answer| session.apply_answer(answer)).and_then(|answer| answer.remote);
+        let body = sdp::generate(&LocalAudio {
+            session_id: u64::from_str_radix(&session.session_id, 16).unwrap_or_default(),
+            version: session.next_sdp_version(),
+            address,
+            port: session.port,
+            rtcp_port: session.rtcp_port(),
+            tcp,
+            codec: session.codec(),
+            alternatives: if answer.is_some() { &[] } else { &alternatives },
+            red: session.red,
+            dtmf_payload_type: session.dtmf_payload_type(),
+            comfort_noise: session.suppressor.is_some() && answer.is_none_or(|answer| answer.comfort_noise),
+            audio_level_id: session.audio_level_id,
+            ptime_ms: self.settings.timers.ptime().as_millis() as u64,
+            ssrc: session.stream.ssrc,
+            cname: rtcp::cname(),
+            direction,
+        });
+        info!(rtp_port = session.port, role = req.role().as_str_name(), remote = ?remote, "SDP gövdesi üretildi");
+        Ok(Response::new(GenerateSdpResponse {
+            sdp: body,
+            remote_address: remote.map(|addr| addr.to_string()).unwrap_or_default(),
+            dtmf_payload_type: session.dtmf_payload_type().unwrap_or(0) as u32,
+        }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn export_sessions(&self, request: Request<ExportSessionsRequest>) -> Result<Response<ExportSessionsResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        // Bilinmeyen bir port ya da kimlik varsa hiçbir oturuma dokunulmadan reddedilir.
+        let mut sessions = match (req.ports.as_slice(), req.session_ids.as_slice()) {
+            ([], []) => self.active_sessions.lock().unwrap().values().filter(|session| labels::matches(&session.labels, &req.labels)).cloned().collect(),
+            (ports, session_ids) => ports.iter().map(|&port| self.session(port))
+                .chain(session_ids.iter().map(|session_id| self.addressed(0, session_id)))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        sessions.sort_by_key(|session| session.port);
+        sessions.dedup_by_key(|session| session.port);
+        let (mut exported, mut reports) = (Vec::with_capacity(sessions.len()), Vec::with_capacity(sessions.len()));
+        for session in sessions {
+            // Oynatmalar ve kayıt taşınmaz; burada biter. Oturum kapanıyorsa ikisi de zaten bitmiştir.
+            let (stopped_playback_id, flushed) = session.stop_playback(true).await.unwrap_or_default();
+            let recording_paths = session.stop_recording().await.map(|summary| summary.paths).unwrap_or_default();
+            let _entered = session.span.enter();
+            let state = SessionExport::of(&session, Instant::now());
+            info!(
+                target: audit::TARGET, event = audit::SESSION_EXPORTED,
+                remote = state.remote_address.map(tracing::field::display), ssrc = state.ssrc, next_sequence = state.next_sequence,
+                next_timestamp = state.next_timestamp, stopped_playback_id, flushed_playbacks = flushed,
+                recordings = Some(recording_paths.join(",")).filter(|recordings| !recordings.is_empty()),
+            );
+            metrics::get().sessions_exported.inc();
+            reports.push(ExportedSession {
+                port: session.port as u32, session_id: session.session_id.clone(), call_id: session.call_id.clone(), stopped_playback_id,
+                flushed_playbacks: flushed as u32, recording_paths,
+            });
+            exported.push(state);
+        }
+        info!(sessions = reports.len(), "Oturumlar dışa aktarıldı");
+        Ok(Response::new(ExportSessionsResponse { state: migration::encode(exported), sessions: reports }))
+    }
+
+    #[instrument(skip(self, request), fields(request_id = %request_id::of(&request)))]
+    async fn import_sessions(&self, request: Request<ImportSessionsRequest>) -> Result<Response<ImportSessionsResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let request_id = request_id::of(&request).to_string();
+        let exported = migration::decode(&request.get_ref().state)
+            .inspect_err(|e| warn!(error = %e, "Oturum belgesi okunamadı"))?;
+        let mut sessions = Vec::with_capacity(exported.len());
+        for state in &exported {
+            let imported = self.import_session(state, &request_id).await.unwrap_or_else(|e| {
+                warn!(previous_port = state.port, previous_session_id = %state.session_id, error = %e, "Oturum içe aktarılamadı");
+                ImportedSession {
+                    previous_port: state.port as u32, previous_session_id: state.session_id.clone(), call_id: state.call_id.clone(), error: e.to_string(),
+                    ..ImportedSession::default()
+                }
+            });
+            sessions.push(imported);
+        }
+        let failed = sessions.iter().filter(|session| !session.error.is_empty()).count();
+        info!(sessions = sessions.len(), failed, "Oturumlar içe aktarıldı");
+        Ok(Response::new(ImportSessionsResponse { sessions }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn set_mute(&self, request: Request<SetMuteRequest>) -> Result<Response<SetMuteResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        session.set_mute(req.inbound, req.outbound, MuteReason::Request);
+        Ok(Response::new(SetMuteResponse {}))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn set_direction(&self, request: Request<SetDirectionRequest>) -> Result<Response<SetDirectionResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        let direction = session_direction(req.direction());
+        let previous = session.set_direction(direction);
+        if direction == Direction::SendOnly && previous != Direction::SendOnly {
+            self.play_hold_audio(&session).await?;
+        }
+        Ok(Response::new(SetDirectionResponse { previous: media_direction(previous) as i32 }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn tap_session(&self, request: Request<TapSessionRequest>) -> Result<Response<TapSessionResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let (observed, leg) = (self.addressed(req.port, &req.session_id)?, self.addressed(req.tap_port, &req.tap_session_id)?);
+        {
+            let _sessions = self.active_sessions.lock().unwrap();
+            tap::attach(&observed, &leg, self.settings.bridge.max_taps, self.settings.timers.ptime())?;
+        }
+        // Bacakta süren anons (ör. karşılama) karışımla aynı akışa girmesin.
+        leg.stop_playback(true).await?;
+        Ok(Response::new(TapSessionResponse {}))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn untap_session(&self, request: Request<UntapSessionRequest>) -> Result<Response<UntapSessionResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let leg = self.addressed(req.tap_port, &req.tap_session_id)?;
+        let _sessions = self.active_sessions.lock().unwrap();
+        let port = tap::detach(&leg, TapEndReason::Request).ok_or(SessionError::NotTapping { port: leg.port })?;
+        Ok(Response::new(UntapSessionResponse { port: port as u32 }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn query_port_history(&self, request: Request<QueryPortHistoryRequest>) -> Result<Response<QueryPortHistoryResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let QueryPortHistoryRequest { port, from_ms, to_ms } = request.into_inner();
+        let port = u16::try_from(port).map_err(|_| SessionError::InvalidPort { port })?;
+        let to_ms = if to_ms == 0 { cdr::unix_millis(SystemTime::now()) } else { to_ms };
+        let tenancies = tokio::task::spawn_blocking(move || port_journal::history(port, from_ms, to_ms))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))??;
+        let tenancies = tenancies
+            .into_iter()
+            .map(|tenancy| PortTenancy {
+                session_id: tenancy.session_id,
+                call_id: tenancy.call_id,
+                tenant: tenancy.tenant,
+                allocated_at_ms: tenancy.allocated_at_ms,
+                released_at_ms: tenancy.released_at_ms,
+                remote_addresses: tenancy.remotes,
+                ended_by_restart: tenancy.ended_by_restart,
+            })
+            .collect();
+        Ok(Response::new(QueryPortHistoryResponse { tenancies }))
+    }
+
+    #[instrument(skip(self), fields(request_id = %request_id::of(&request)))]
+    async fn release_port(&self, request: Request<ReleasePortRequest>) -> Result<Response<ReleasePortResponse>, Status> {
+        telemetry::set_remote_parent(&Span::current(), request.metadata());
+        let req = request.into_inner();
+        let session = self.addressed(req.port, &req.session_id)?;
+        if !release_session(&session, &self.active_sessions).await {
+            warn!(rtp_port = session.port, session_id = %session.session_id, "Bırakılan oturumun kapanışı sürüyor");
+        }
+        let duration_ms = session.allocated_at.elapsed().as_millis() as u64;
+        Ok(Response::new(ReleasePortResponse { session_id: session.session_id.clone(), duration_ms }))
+    }
+}
+
+impl MyMediaManager {
+    /// Yeniden köprülemede karşısız kalan bacağa `bridge.detached_audio` sesini kurar; bacak tekrar
+    /// köprülenince dinleyici bunu durdurur.
+    fn play_detached_audio(&self, leg: &RtpSession) {
+        let bridge = &self.settings.bridge;
+        if let Some(playback) = self.filler(leg, bridge.detached_audio, bridge.detached_prompt.as_deref()) {
+            leg.play(playback.until_bridged());
+        }
+    }
+
+    /// SetDirection(sendonly) ile bekletilen oturuma `[hold]` sesini kurar; çalan bir anons ya da
+    /// köprüden gelen ses varsa o sürer. Oturum sendonly'den çıkınca zamanlayıcı bunu durdurur.
+    async fn play_hold_audio(&self, session: &RtpSession) -> Result<(), SessionError> {
+        if session.bridged_peer().is_some() || session.remote_addr.lock().unwrap().is_none() {
+            return Ok(());
+        }
+        let hold = &self.settings.hold;
+        let Some(playback) = self.filler(session, hold.audio, hold.prompt.as_deref()) else { return Ok(()) };
+        match session.submit(playback.until_resumed(), PlayMode::Reject).await {
+            Ok(_) | Err(SessionError::PlaybackBusy { .. } | SessionError::TapLeg { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Köprüsüz kalan ya da bekletilen bacağın dolgu sesi; `None` ya da açılamayan anons için yok.
+    fn filler(&self, session: &RtpSession, audio: DetachedAudio, prompt: Option<&str>) -> Option<Playback> {
+        match (audio, prompt) {
+            (DetachedAudio::None, _) => None,
+            (DetachedAudio::Prompt, Some(name)) => {
+                let _entered = session.span.enter();
+                self.prompts.get(name).and_then(|prompt| Playback::prompt(&prompt.in_language(session.language.as_deref())))
+                    .inspect_err(|e| playback::load_failed(session, name, e))
+                    .ok()
+            }
+            _ => Some(Playback::new("silence", Box::new(SilenceSource::new(None)))),
+        }
+    }
+
+    /// Hız sınırı aşıldıysa isteği soket bağlanmadan reddeder; sayaç havuz tükenmesinden ayrıdır.
+    fn check_allocation_rate(&self, now: Instant) -> Result<(), AllocationError> {
+        let Some(limit) = &self.allocation_limit else { return Ok(()) };
+        limit.lock().unwrap().try_acquire(now).map_err(|retry_after| {
+            metrics::get().allocation_failed(AllocationFailure::RateLimited);
+            debug!(retry_after_ms = retry_after.as_millis() as u64, "AllocatePort hız sınırına takıldı");
+            AllocationError::RateLimited { retry_after }
+        })
+    }
+
+    /// İstekteki codec'i etkin listeye göre seçer; boş istek ilk tercih edilen codec'i alır.
+    fn select_codec(&self, requested: &str) -> Result<&'static dyn Codec, AllocationError> {
+        let enabled = self.settings.rtp.enabled_codecs();
+        if requested.is_empty() {
+            return enabled.first().copied().ok_or(AllocationError::NoCodecEnabled);
+        }
+        let codec = codec::registry().by_name(requested)
+            .ok_or_else(|| AllocationError::UnknownCodec { name: requested.to_string() })?;
+        if !enabled.contains(&codec) {
+            return Err(AllocationError::CodecDisabled { codec });
+        }
+        Ok(codec)
+    }
+
+    /// İstekteki RED yük tipini doğrular; 0 RED'in anlaşılmadığı anlamına gelir.
+    fn red_config(&self, payload_type: u32) -> Result<Option<RedConfig>, AllocationError> {
+        if payload_type == 0 {
+            return Ok(None);
+        }
+        match u8::try_from(payload_type) {
+            Ok(pt) if red::DYNAMIC_PAYLOAD_TYPES.contains(&pt) => {
+                Ok(Some(RedConfig { payload_type: pt, generations: self.settings.rtp.red_generations }))
+            }
+            _ => Err(AllocationError::InvalidRedPayloadType { payload_type }),
+        }
+    }
+
+    /// İstekteki telephone-event yük tipini doğrular; 0 anlaşılmadığı anlamına gelir.
+    fn dtmf_payload_type(&self, payload_type: u32, red: Option<RedConfig>) -> Result<Option<u8>, AllocationError> {
+        if payload_type == 0 {
+            return Ok(None);
+        }
+        match u8::try_from(payload_type) {
+            Ok(pt) if red.is_some_and(|r| r.payload_type == pt) => Err(AllocationError::PayloadTypeConflict { payload_type }),
+            Ok(pt) if red::DYNAMIC_PAYLOAD_TYPES.contains(&pt) => Ok(Some(pt)),
+            _ => Err(AllocationError::InvalidDtmfPayloadType { payload_type }),
+        }
+    }
+
+    /// İstekteki `[[interfaces]]` arayüzü; ad boşsa `None` ve port rtp.host'a bağlanır.
+    fn interface(&self, name: &str) -> Result<Option<&InterfaceConfig>, AllocationError> {
+        if name.is_empty() {
+            return Ok(None);
+        }
+        self.settings.interfaces.iter().find(|interface| interface.name == name).map(Some)
+            .ok_or_else(|| AllocationError::UnknownInterface { name: name.to_string() })
+    }
+
+    /// İsteğin kiracısı: `authorization: Bearer <jeton>` metadata'sından ya da istekteki addan.
+    /// İkisi de yoksa `None`; port paylaşılan aralıktan alınır.
+    fn tenant(&self, request: &Request<AllocatePortRequest>) -> Result<Option<&TenantConfig>, AllocationError> {
+        let tenants = &self.settings.tenants;
+        let token = request.metadata().get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty() && !tenants.is_empty());
+        let by_token = token
+            .map(|token| tenants.iter().find(|t| !t.auth_token.is_empty() && t.auth_token == token).ok_or(AllocationError::InvalidTenantToken))
+            .transpose()?;
+        match (by_token, request.get_ref().tenant.as_str()) {
+            (tenant, "") => Ok(tenant),
+            (Some(tenant), name) if tenant.name == name => Ok(Some(tenant)),
+            (Some(tenant), name) => Err(AllocationError::TenantMismatch { tenant: tenant.name.clone(), requested: name.to_string() }),
+            (None, name) => match tenants.iter().find(|t| t.name == name) {
+                None => Err(AllocationError::UnknownTenant { name: name.to_string() }),
+                Some(tenant) if !tenant.auth_token.is_empty() => Err(AllocationError::TenantTokenRequired { tenant: tenant.name.clone() }),
+                Some(tenant) => Ok(Some(tenant)),
+            },
+        }
+    }
+
+    /// SayDigits ve SayNumber: dili çözer (istek, oturum, varsayılan dil), `keys` ile parça
+    /// anahtarlarını çıkarır ve bütün parçalar varsa tek bir oynatma olarak `mode` ile çalar.
+    async fn say(
+        &self, port: u32, session_id: &str, language: &str, mode: PlayMode, name: String, keys: impl FnOnce(Option<&str>) -> Result<Vec<String>, PlaybackError>,
+    ) -> Result<Response<SayResponse>, Status> {
+        let session = self.addressed(port, session_id)?;
+        if session.remote_addr.lock().unwrap().is_none() {
+            return Err(SessionError::RemoteUnknown { port: session.port }.into());
+        }
+        admission::admit()?;
+        let language = Some(language).filter(|language| !language.is_empty())
+            .or(session.language.as_deref())
+            .or(self.prompts.default_language());
+        let (segments, playback) = {
+            let _entered = session.span.enter();
+            let keys = keys(language)?;
+            let segments = self.prompts.segments(&keys, language)
+                .inspect_err(|e| warn!(prompt = %name, error = %e, "Birleşik anonsun parçaları eksik"))?;
+            info!(rtp_port = session.port, prompt = %name, segments = segments.len(), "Birleşik anons çalma isteği alındı");
+            let playback = Playback::sequence(name.clone(), language, &segments)
+                .inspect_err(|e| playback::load_failed(&session, &name, e))?;
+            (segments, playback)
+        };
+        let playback_id = session.submit(playback, mode).await?;
+        Ok(Response::new(SayResponse { segments: segments.iter().map(|segment| segment.name.clone()).collect(), playback_id }))
+    }
+
+    /// Oturumu tabloya ekler ve dinleyici görevini başlatır.
+    fn start_session(&self, session: Arc<RtpSession>) {
+        port_journal::allocated(&session);
+        self.active_sessions.lock().unwrap().insert(session.clone());
+        let span = session.span.clone();
+        let handler = rtp_session_handler(session, self.prompts.clone(), self.settings.timers, self.settings.quality, self.active_sessions.clone(), self.scheduler.clone());
+        tokio::spawn(handler.instrument(span));
+    }
+
+    /// Başka bir node'un dışa aktardığı oturumu paylaşılan aralıktan alınan yeni bir portta aynı
+    /// akışla kurar. Tahsis gibi sayılır; karşılama çalınmaz, yük tipleri bu node'un kurallarıyla
+    /// yeniden doğrulanır.
+    async fn import_session(&self, state: &SessionExport, request_id: &str) -> Result<ImportedSession, AllocationError> {
+        let started = Instant::now();
+        let slow = self.settings.timers.slow_allocation();
+        let (codec, red, dtmf, audio_level_id, labels) = self.select_codec(&state.codec)
+            .and_then(|codec| Ok((codec, self.red_config(state.red_payload_type.unwrap_or(0).into())?)))
+            .and_then(|(codec, red)| Ok((codec, red, self.dtmf_payload_type(state.dtmf_payload_type.unwrap_or(0).into(), red)?)))
+            .and_then(|(codec, red, dtmf)| Ok((codec, red, dtmf, audio_level_id(state.audio_level_id.unwrap_or(0).into())?)))
+            .and_then(|(codec, red, dtmf, audio_level)| Ok((codec, red, dtmf, audio_level, labels::validate(&state.labels.clone().into_iter().collect())?)))
+            .inspect_err(|_| {
+                metrics::get().allocation_failed(AllocationFailure::InvalidCodec);
+                record_allocation(AllocationOutcome::Error, 0, started.elapsed(), slow);
+            })?;
+        let pool = PortPool::shared(&self.settings.rtp, &self.settings.tenants);
+        let (bound, attempts) = bind_rtp_port(&self.settings.rtp, &self.settings.rtp.host, &pool, TransportKind::Udp, state.rtcp_mux).await;
+        let Bound { port, transport: sock, rtcp: rtcp_sock, overflow } = bound
+            .inspect_err(|e| {
+                metrics::get().allocation_failed(AllocationFailure::Exhausted);
+                let outcome = match e {
+                    AllocationError::PortsExhausted { .. } => AllocationOutcome::Exhausted,
+                    _ => AllocationOutcome::Error,
+                };
+                record_allocation(outcome, attempts, started.elapsed(), slow);
+            })?;
+        metrics::get().allocations.inc();
+        metrics::get().active_sessions.inc();
+        metrics::get().sessions_imported.inc();
+        if overflow {
+            metrics::get().overflow_allocations.inc();
+            metrics::get().overflow_sessions.inc();
+        }
+
+        let seed = StreamSeed { ssrc: Some(state.ssrc), sequence: Some(state.next_sequence), timestamp: Some(state.next_timestamp) };
+        let mut session = RtpSession::new(port, codec, sock, &state.call_id).with_request_id(request_id).with_stream_seed(seed).without_welcome();
+        if let Some(red) = red {
+            session = session.with_red(red);
+        }
+        if let Some(payload_type) = dtmf {
+            session = session.with_dtmf(payload_type);
+        }
+        if let Some(id) = audio_level_id {
+            session = session.with_audio_level(id);
+        }
+        if overflow {
+            session = session.with_overflow();
+        }
+        if let Some(rtcp_sock) = rtcp_sock {
+            session = session.with_rtcp_port(rtcp_sock);
+        }
+        if self.settings.rtp.recv_batch > 1 {
+            session = session.with_recv_batch(self.settings.rtp.recv_batch);
+        }
+        // Uzak uç re-INVITE'tan önce de eski hedefte dinler; yeni akışı gelince hedef oraya kilitlenir.
+        if let Some(remote) = state.remote_address {
+            session = session.with_send_only(remote, true);
+        }
+        if let Some(max_duration) = self.settings.timers.max_session_duration() {
+            session = session.with_max_duration(max_duration);
+        }
+        if self.settings.rate_limit.inbound_packets_per_s > 0 {
+            session = session.with_inbound_limit(&self.settings.rate_limit);
+        }
+        if let Some(interval) = self.settings.rtcp.report_interval() {
+            session = session.with_rtcp_reports(interval);
+        }
+        if let Some(language) = &state.language {
+            session = session.with_language(language);
+        }
+        if !labels.is_empty() {
+            session = session.with_labels(labels);
+        }
+        let session = Arc::new(session);
+        let session_id = session.session_id.clone();
+        self.start_session(session);
+
+        info!(
+            target: audit::TARGET, event = audit::SESSION_IMPORTED,
+            rtp_port = port, previous_port = state.port, previous_session_id = %state.session_id, call_id = %state.call_id, session_id = %session_id,
+            codec = %codec, remote = state.remote_address.map(tracing::field::display), ssrc = state.ssrc, overflow,
+        );
+        record_allocation(AllocationOutcome::Success, attempts, started.elapsed(), slow);
+        Ok(ImportedSession {
+            previous_port: state.port as u32,
+            previous_session_id: state.session_id.clone(),
+            call_id: state.call_id.clone(),
+            port: port as u32,
+            session_id,
+            advertise_address: self.settings.rtp.advertise_address().map(|address| address.to_string()).unwrap_or_default(),
+            error: String::new(),
+        })
+    }
+
+    /// İstekteki port numarasına ait aktif oturum.
+    fn session(&self, port: u32) -> Result<Arc<RtpSession>, SessionError> {
+        let port = u16::try_from(port).map_err(|_| SessionError::InvalidPort { port })?;
+        self.active_sessions.lock().unwrap().get(&port).cloned().ok_or(SessionError::NotFound { port })
+    }
+
+    /// Oturumu `session_id` verildiyse kimliğiyle, verilmediyse portuyla bulur. İkisi de
+    /// verildiyse o kimlikteki oturum o portta olmalıdır.
+    fn addressed(&self, port: u32, session_id: &str) -> Result<Arc<RtpSession>, SessionError> {
+        if session_id.is_empty() {
+            return self.session(port);
+        }
+        let session = self.active_sessions.lock().unwrap()
+            .by_id(session_id)
+            .cloned()
+            .ok_or_else(|| SessionError::SessionIdNotFound { session_id: session_id.to_string() })?;
+        if port != 0 && u32::from(session.port) != port {
+            return Err(SessionError::SessionIdMismatch { session_id: session_id.to_string(), port });
+        }
+        Ok(session)
+    }
+}
+
+/// Oturumun yönünün gRPC karşılığı.
+fn media_direction(direction: Direction) -> MediaDirection {
+    match direction {
+        Direction::SendRecv => MediaDirection::SendRecv,
+        Direction::SendOnly => MediaDirection::SendOnly,
+        Direction::RecvOnly => MediaDirection::RecvOnly,
+        Direction::Inactive => MediaDirection::Inactive,
+    }
+}
+
+fn session_direction(direction: MediaDirection) -> Direction {
+    match direction {
+        MediaDirection::SendRecv => Direction::SendRecv,
+        MediaDirection::SendOnly => Direction::SendOnly,
+        MediaDirection::RecvOnly => Direction::RecvOnly,
+        MediaDirection::Inactive => Direction::Inactive,
+    }
+}
+
+/// Kiracının oturum sınırından bir yer ayırır; port alınamazsa `active_sessions` geri azaltılmalı.
+fn reserve_tenant_slot(tenant: &TenantConfig) -> Result<Arc<TenantMetrics>, AllocationError> {
+    let counters = metrics::get().tenant(&tenant.name);
+    counters.active_sessions.inc();
+    if tenant.max_sessions().is_some_and(|max| counters.active_sessions.get() > max as i64) {
+        counters.active_sessions.dec();
+        counters.allocation_failures.inc();
+        return Err(AllocationError::TenantLimit { tenant: tenant.name.clone(), max_sessions: tenant.max_sessions });
+    }
+    Ok(counters)
+}
+
+/// İstekteki RFC 6464 uzantı kimliğini doğrular; 0 anlaşılmadığı anlamına gelir.
+fn audio_level_id(id: u32) -> Result<Option<u8>, AllocationError> {
+    match u8::try_from(id) {
+        Ok(0) => Ok(None),
+        Ok(id) if audio_level::IDS.contains(&id) => Ok(Some(id)),
+        _ => Err(AllocationError::InvalidAudioLevelId { id }),
+    }
+}
+
+/// İstenen bozulma; verilmediyse ya da bütün değerleri 0 ise yok. Açmak node'da feature'ı ve
+/// `rtp.enable_impairments`'ı gerektirir, kapatmak her zaman serbesttir.
+fn impairment(rtp: &RtpConfig, requested: Option<&crate::media::Impairment>) -> Result<Option<Impairment>, ImpairmentError> {
+    let Some(requested) = requested else { return Ok(None) };
+    let impairment = Impairment {
+        loss_pct: requested.loss_pct, jitter_ms: requested.jitter_ms, reorder_pct: requested.reorder_pct, duplicate_pct: requested.duplicate_pct,
+    }.validate()?;
+    if impairment.is_off() {
+        return Ok(None);
+    }
+    if !cfg!(feature = "impairment") {
+        return Err(ImpairmentError::NotBuilt);
+    }
+    if !rtp.enable_impairments {
+        return Err(ImpairmentError::Disabled);
+    }
+    Ok(Some(impairment))
+}
+
+/// İstekte sabitlenen akış başlangıç değerleri.
+fn stream_seed(request: &AllocatePortRequest) -> Result<StreamSeed, AllocationError> {
+    let sequence = request.initial_sequence
+        .map(|sequence| u16::try_from(sequence).map_err(|_| AllocationError::InvalidInitialSequence { sequence }))
+        .transpose()?;
+    Ok(StreamSeed { ssrc: request.ssrc, sequence, timestamp: request.initial_timestamp })
+}
+
+/// Yalnızca gönderen oturumun hedefi; boşsa oturum ilk gelen paketle kilitlenir.
+fn send_only(address: &str) -> Result<Option<SocketAddr>, AllocationError> {
+    match address {
+        "" => Ok(None),
+        address => address.parse().map(Some).map_err(|_| AllocationError::InvalidRemoteAddress { address: address.to_string() }),
+    }
+}
+
+/// Oturumun tuş bildirim adresi; boşsa node'un `dtmf_hook.url`'si kullanılır.
+fn dtmf_hook_url(url: &str) -> Result<Option<&str>, AllocationError> {
+    match url {
+        "" => Ok(None),
+        url if url.starts_with("http://") && url.len() > "http://".len() => Ok(Some(url)),
+        url => Err(AllocationError::InvalidDtmfHookUrl { url: url.to_string() }),
+    }
+}
+
+/// Tahsis süresini histograma yazar; `slow` aşıldıysa deneme sayısıyla uyarı loglar.
+fn record_allocation(outcome: AllocationOutcome, attempts: u32, elapsed: Duration, slow: Option<Duration>) {
+    metrics::get().allocation_duration(outcome).observe(elapsed);
+    if slow.is_some_and(|threshold| elapsed > threshold) {
+        warn!(
+            outcome = outcome.label(), attempts, elapsed_ms = elapsed.as_secs_f64() * 1000.0,
+            "Port tahsisi yavaş sürdü"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::net::UdpSocket;
+    use crate::config::RtpConfig;
+    use crate::rtp::MAX_BIND_ATTEMPTS;
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    #[tokio::test]
+    async fn saturated_port_range_records_latency_and_warns() {
+        let occupied = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = occupied.local_addr().unwrap().port();
+        let rtp = RtpConfig { host: "127.0.0.1".to_string(), advertise_address: String::new(), min_port: port, max_port: port, codecs: vec![], red_generations: 1, allow_tcp: true,
+            allow_overflow: false, overflow_min_port: 0, overflow_max_port: 0, recv_batch: 1, send_schedulers: 0, enable_impairments: false,
+        };
+
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let _guard = tracing::subscriber::set_default(
+            tracing_subscriber::fmt().with_writer(move || writer.clone()).with_ansi(false).finish(),
+        );
+        let histogram = metrics::get().allocation_duration(AllocationOutcome::Exhausted);
+        let before = histogram.count();
+
+        let started = Instant::now();
+        let (result, attempts) = bind_rtp_port(&rtp, &rtp.host, &PortPool::shared(&rtp, &[]), TransportKind::Udp, false).await;
+        assert!(matches!(result.unwrap_err(), AllocationError::PortsExhausted { attempts: MAX_BIND_ATTEMPTS, .. }));
+        assert_eq!(attempts, MAX_BIND_ATTEMPTS);
+        // Eşik sıfır: her ölçüm onu aşar.
+        record_allocation(AllocationOutcome::Exhausted, attempts, started.elapsed(), Some(Duration::ZERO));
+
+        assert_eq!(histogram.count(), before + 1);
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("Port tahsisi yavaş sürdü"), "{}", output);
+        assert!(output.contains("attempts=100"), "{}", output);
+        assert!(output.contains("outcome=\"exhausted\""), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn exhausted_range_overflows_only_when_allowed() {
+        let occupied = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = occupied.local_addr().unwrap().port();
+        let mut rtp = RtpConfig {
+            host: "127.0.0.1".to_string(), advertise_address: String::new(), min_port: port, max_port: port, codecs: vec![], red_generations: 1, allow_tcp: true,
+            allow_overflow: false, overflow_min_port: 0, overflow_max_port: 0, recv_batch: 1, send_schedulers: 0, enable_impairments: false,
+        };
+        assert!(matches!(bind_rtp_port(&rtp, &rtp.host, &PortPool::shared(&rtp, &[]), TransportKind::Udp, false).await.0, Err(AllocationError::PortsExhausted { .. })));
+
+        // İşletim sisteminin atadığı port.
+        rtp.allow_overflow = true;
+        let (bound, attempts) = bind_rtp_port(&rtp, &rtp.host, &PortPool::shared(&rtp, &[]), TransportKind::Udp, false).await;
+        let bound = bound.unwrap();
+        assert!(bound.overflow && bound.port != port && bound.rtcp.is_none());
+        assert_eq!((bound.transport.local_addr().unwrap().port(), attempts), (bound.port, MAX_BIND_ATTEMPTS + 1));
+
+        // Taşma aralığından RTP/RTCP çifti; o da doluysa tahsis yine reddedilir.
+        let spare_port = loop {
+            let probe = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let even = probe.local_addr().unwrap().port() & !1;
+            drop(probe);
+            if std::net::UdpSocket::bind(("127.0.0.1", even)).is_ok() && std::net::UdpSocket::bind(("127.0.0.1", even + 1)).is_ok() {
+                break even;
+            }
+        };
+        (rtp.overflow_min_port, rtp.overflow_max_port) = (spare_port, spare_port + 1);
+        let held = bind_rtp_port(&rtp, &rtp.host, &PortPool::shared(&rtp, &[]), TransportKind::Udp, false).await.0.unwrap();
+        assert_eq!((held.port, held.overflow), (spare_port, true));
+        assert_eq!(held.rtcp.as_ref().map(|socket| socket.local_addr().unwrap().port()), Some(spare_port + 1));
+        assert!(matches!(bind_rtp_port(&rtp, &rtp.host, &PortPool::shared(&rtp, &[]), TransportKind::Udp, false).await.0, Err(AllocationError::PortsExhausted { min_port, .. }) if min_port == spare_port));
+    }
+}
@@ -0,0 +1,83 @@
+// Sunucunun genel durumu. Heartbeat ve /healthz, /readyz uç noktaları buradan okur.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::admission;
+use crate::metrics;
+
+#[derive(Debug, Default)]
+pub struct Health {
+    // gRPC portu bağlandı ve sunucu çalışıyor.
+    grpc_serving: AtomicBool,
+    // Son okunan konfigürasyon doğrulamadan geçti (başlangıçta ve SIGHUP'ta güncellenir).
+    config_valid: AtomicBool,
+    // Kapanış başladı: yeni iş beklenmiyor, aktif oturumların bitmesi bekleniyor.
+    draining: AtomicBool,
+}
+
+impl Health {
+    pub fn set_grpc_serving(&self, serving: bool) {
+        self.grpc_serving.store(serving, Ordering::Relaxed);
+    }
+
+    pub fn is_grpc_serving(&self) -> bool {
+        self.grpc_serving.load(Ordering::Relaxed)
+    }
+
+    pub fn set_config_valid(&self, valid: bool) {
+        self.config_valid.store(valid, Ordering::Relaxed);
+    }
+
+    pub fn set_draining(&self, draining: bool) {
+        self.draining.store(draining, Ordering::Relaxed);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+
+    /// Loglarda kullanılan kısa durum adı.
+    pub fn state(&self) -> &'static str {
+        if self.is_draining() { "draining" } else { "serving" }
+    }
+
+    /// Hazır olmama sebepleri; boşsa node yeni çağrı alabilir.
+    pub fn readiness_failures(&self) -> Vec<&'static str> {
+        let mut failures = Vec::new();
+        if !self.is_grpc_serving() {
+            failures.push("grpc_not_serving");
+        }
+        if !self.config_valid.load(Ordering::Relaxed) {
+            failures.push("config_invalid");
+        }
+        if self.is_draining() {
+            failures.push("draining");
+        }
+        let pool_size = metrics::get().port_pool_size.get();
+        if pool_size > 0 && metrics::get().active_sessions.get() >= pool_size {
+            failures.push("port_pool_exhausted");
+        }
+        // Bozulmuş node yeni anons çalamaz; yük dengeleyici yeni çağrıları başka node'a versin.
+        if admission::cause().is_some() {
+            failures.push("overloaded");
+        }
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readiness_follows_drain_state() {
+        let health = Health::default();
+        health.set_grpc_serving(true);
+        health.set_config_valid(true);
+        assert!(health.readiness_failures().is_empty());
+
+        health.set_draining(true);
+        assert_eq!(health.readiness_failures(), vec!["draining"]);
+        health.set_draining(false);
+        assert!(health.readiness_failures().is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+// Sessiz bir node'da "sağlıklı ve boşta" ile "takılmış" durumlarını ayırt etmek için periyodik
+// durum satırı. Değerler metriklerle aynı atomiklerden okunur; aralık değerleri iki satır
+// arasındaki farktır.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::info;
+
+use crate::admission;
+use crate::health::Health;
+use crate::metrics::{self, Metrics};
+
+/// Kümülatif sayaçların anlık görüntüsü.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Snapshot {
+    allocations: u64,
+    releases: u64,
+    packets_sent: u64,
+    packets_received: u64,
+}
+
+impl Snapshot {
+    fn take(metrics: &Metrics) -> Self {
+        Self {
+            allocations: metrics.allocations.get(),
+            releases: metrics.releases.get(),
+            packets_sent: metrics.rtp_packets_sent.get(),
+            packets_received: metrics.rtp_packets_received.get(),
+        }
+    }
+
+    fn since(&self, previous: &Snapshot) -> Snapshot {
+        Snapshot {
+            allocations: self.allocations.saturating_sub(previous.allocations),
+            releases: self.releases.saturating_sub(previous.releases),
+            packets_sent: self.packets_sent.saturating_sub(previous.packets_sent),
+            packets_received: self.packets_received.saturating_sub(previous.packets_received),
+        }
+    }
+}
+
+/// Her `period`'da bir durum satırı yazar; süreç bitene kadar çalışır.
+pub async fn run(period: Duration, health: Arc<Health>) {
+    let mut ticker = interval(period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // İlk tick hemen döner; ilk satır bir aralık sonra yazılır.
+    ticker.tick().await;
+    let mut previous = Snapshot::take(metrics::get());
+    loop {
+        ticker.tick().await;
+        let metrics = metrics::get();
+        let current = Snapshot::take(metrics);
+        let delta = current.since(&previous);
+        previous = current;
+
+        let active = metrics.active_sessions.get();
+        let pool_size = metrics.port_pool_size.get();
+        let utilization = if pool_size > 0 { active as f64 / pool_size as f64 } else { 0.0 };
+        info!(
+            active_sessions = active,
+            pool_utilization = utilization,
+            allocations = delta.allocations,
+            teardowns = delta.releases,
+            rtp_packets_in = delta.packets_received,
+            rtp_packets_out = delta.packets_sent,
+            state = health.state(),
+            admission = admission::state(),
+            interval_s = period.as_secs(),
+            "Heartbeat"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_is_difference_between_snapshots() {
+        let previous = Snapshot { allocations: 10, releases: 8, packets_sent: 1000, packets_received: 900 };
+        let current = Snapshot { allocations: 13, releases: 8, packets_sent: 1500, packets_received: 1250 };
+        assert_eq!(
+            current.since(&previous),
+            Snapshot { allocations: 3, releases: 0, packets_sent: 500, packets_received: 350 }
+        );
+    }
+}
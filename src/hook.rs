@@ -0,0 +1,406 @@
+// Oturum kapanış kancası: oturum özeti JSON olarak bir HTTP uç noktasına POST edilir ya da bir
+// komutun stdin'ine yazılır. Kapanış yalnızca sınırlı kuyruğa `try_send` yapar; teslimatı sabit
+// sayıda işçi görev üstlenir. Her deneme bir zaman aşımıyla sınırlıdır, başarısızlar artan
+// aralıklarla yeniden denenir. Kuyruk doluysa bildirim düşürülür; kanca ne kadar uzun süre
+// cevap vermezse versin görev ve bellek birikmez, oturum kapanışı hiç beklemez. Kuyruk (`Queue`)
+// tuş bildirimleriyle (dtmf_hook.rs) ortaktır.
+use std::process::Stdio;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use hyper::client::HttpConnector;
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, Client, Method, Request};
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::config::{HookConfig, HookKind};
+use crate::error::HookError;
+use crate::labels::Labels;
+use crate::metrics::{self, HookOutcome};
+use crate::object_store::RecordingUpload;
+
+const BACKOFF_BASE: Duration = Duration::from_millis(200);
+const BACKOFF_MAX: Duration = Duration::from_secs(10);
+
+/// Kancaya gönderilen oturum özeti; `session_summary` olayının alanlarının bir alt kümesi.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionReport {
+    pub session_id: String,
+    pub call_id: String,
+    pub request_id: String,
+    pub port: u16,
+    pub duration_ms: u64,
+    pub packets_sent: u64,
+    pub bytes_sent: u64,
+    pub packets_received: u64,
+    pub bytes_received: u64,
+    pub packets_lost: u64,
+    pub packets_duplicated: u64,
+    pub jitter_ms: f64,
+    /// Hiç paket gelmediyse yok.
+    pub mos: Option<f64>,
+    pub announcements_failed: u64,
+    /// Son başarısız anonsun sebebi (bkz. `audit::PlaybackFailure`); yoksa yok.
+    pub playback_failure: Option<&'static str>,
+    /// Son soket gönderim hatasının sınıfı (bkz. `audit::SendFailure`); hata olmadıysa yok.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_error: Option<&'static str>,
+    pub recordings: Vec<String>,
+    pub teardown_reason: &'static str,
+    /// Oturumun `[[tenants]]` kiracısı; kiracısızsa yok.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<&'static str>,
+    /// Nesne deposu açıksa kayıtların yükleme sonuçları; bildirim yüklemeler bitince gönderilir.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub uploads: Vec<RecordingUpload>,
+    /// StartAudioDump açıldıysa gelen ve giden döküm dosyaları.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub audio_dumps: Vec<String>,
+    /// Kayıt şifrelendiyse anahtarın kimliği.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recording_key_id: Option<String>,
+    /// AllocatePort'ta verilen etiketler.
+    #[serde(skip_serializing_if = "Labels::is_empty")]
+    pub labels: Labels,
+}
+
+/// Bildirimin gönderileceği yer.
+#[derive(Debug, Clone)]
+pub(crate) enum Target {
+    /// Gövde bu adrese POST edilir.
+    Http(String),
+    /// Gövde bu komutun stdin'ine yazılır.
+    Command(Vec<String>),
+}
+
+/// `Queue`'nun teslim ettiği bildirim; JSON'a çevrilip gönderilir.
+pub(crate) trait Notice: Serialize + Send + Sync + 'static {
+    /// Loglarda bildirimin ait olduğu oturum.
+    fn session_id(&self) -> &str;
+
+    /// Teslimatın, başarısızlığın ya da düşürmenin sonucunu bildirimin metriğine sayar.
+    fn count(outcome: HookOutcome);
+}
+
+/// Bildirimleri sınırlı bir kuyruk ve sabit sayıda işçi görev üzerinden teslim eder: `submit`
+/// beklemez, kuyruk doluysa ya da kuyruk kapandıysa bildirimi düşürür; her deneme bir zaman
+/// aşımıyla sınırlıdır, başarısızlar `backoff` aralıklarıyla yeniden denenir.
+pub(crate) struct Queue<T: Notice> {
+    // Log satırlarında kuyruğu ayırır ("session", "dtmf").
+    name: &'static str,
+    // Kapanışta alınır; işçiler kuyruk boşalınca çıkar.
+    queue: Mutex<Option<mpsc::Sender<(Target, T)>>>,
+    workers: Mutex<Vec<JoinHandle<()>>>,
+    timeout: Duration,
+}
+
+struct Delivery {
+    name: &'static str,
+    client: Client<HttpConnector>,
+    timeout: Duration,
+    retries: u32,
+}
+
+impl<T: Notice> Queue<T> {
+    /// `concurrency` işçiyi `queue_size` bildirimlik kuyrukla başlatır; her deneme `timeout` ile
+    /// sınırlıdır, ilk başarısızlıktan sonra `retries` kez daha denenir. Tokio çalışma zamanı içinde
+    /// çağrılmalı.
+    pub(crate) fn start(name: &'static str, timeout: Duration, retries: u32, queue_size: usize, concurrency: usize) -> Self {
+        let delivery = Arc::new(Delivery { name, client: Client::new(), timeout, retries });
+        let (queue, notices) = mpsc::channel::<(Target, T)>(queue_size.max(1));
+        let notices = Arc::new(tokio::sync::Mutex::new(notices));
+        let workers = (0..concurrency.max(1))
+            .map(|_| {
+                let (delivery, notices) = (delivery.clone(), notices.clone());
+                tokio::spawn(async move {
+                    loop {
+                        let Some((target, notice)) = notices.lock().await.recv().await else { break };
+                        delivery.deliver(&target, &notice).await;
+                    }
+                })
+            })
+            .collect();
+        Queue { name, queue: Mutex::new(Some(queue)), workers: Mutex::new(workers), timeout }
+    }
+
+    /// Bildirimi kuyruğa koyar; kuyruk doluysa ya da kapandıysa düşürür ve `false` döner.
+    pub(crate) fn submit(&self, target: Target, notice: T) -> bool {
+        let queue = self.queue.lock().unwrap();
+        let Some(queue) = queue.as_ref() else { return false };
+        match queue.try_send((target, notice)) {
+            Ok(()) => true,
+            Err(e) => {
+                let (_, notice) = e.into_inner();
+                T::count(HookOutcome::Dropped);
+                warn!(hook = self.name, session_id = %notice.session_id(), "Kanca kuyruğu dolu, bildirim düşürüldü");
+                false
+            }
+        }
+    }
+
+    /// Yeni bildirim almayı bırakır ve bekleyenlerin teslimatı için en fazla bir deneme süresi bekler;
+    /// bu sürede bitmeyenler bırakılır.
+    pub(crate) async fn flush(&self) {
+        self.queue.lock().unwrap().take();
+        let workers = std::mem::take(&mut *self.workers.lock().unwrap());
+        let drained = async { for worker in workers { let _ = worker.await; } };
+        if tokio::time::timeout(self.timeout, drained).await.is_err() {
+            warn!(hook = self.name, "Bekleyen kanca bildirimleri kapanışta tamamlanamadı");
+        }
+    }
+}
+
+impl Delivery {
+    async fn deliver<T: Notice>(&self, target: &Target, notice: &T) {
+        let body = match serde_json::to_vec(notice) {
+            Ok(body) => body,
+            Err(e) => { warn!(hook = self.name, session_id = %notice.session_id(), error = %e, "Kanca bildirimi JSON'a çevrilemedi"); return; }
+        };
+        let mut attempt = 0;
+        loop {
+            let result = match tokio::time::timeout(self.timeout, self.attempt(target, &body)).await {
+                Ok(result) => result,
+                Err(_) => Err(HookError::Timeout { timeout_ms: self.timeout.as_millis() as u64 }),
+            };
+            match result {
+                Ok(()) => {
+                    T::count(HookOutcome::Delivered);
+                    debug!(hook = self.name, session_id = %notice.session_id(), attempts = attempt + 1, "Kanca bildirimi iletildi");
+                    return;
+                }
+                Err(e) if attempt < self.retries => {
+                    debug!(hook = self.name, session_id = %notice.session_id(), attempt = attempt + 1, error = %e, "Kanca denemesi başarısız, yeniden denenecek");
+                    attempt += 1;
+                    tokio::time::sleep(backoff(attempt)).await;
+                }
+                Err(e) => {
+                    T::count(HookOutcome::Failed);
+                    warn!(hook = self.name, session_id = %notice.session_id(), attempts = attempt + 1, error = %e, "Kanca bildirimi iletilemedi");
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn attempt(&self, target: &Target, body: &[u8]) -> Result<(), HookError> {
+        match target {
+            Target::Http(url) => post(&self.client, url, body).await,
+            Target::Command(argv) => {
+                let program = argv.first().map(String::as_str).unwrap_or_default();
+                let spawn = |source| HookError::Spawn { program: program.to_string(), source };
+                // Zaman aşımında gelecek düşürülünce süreç de öldürülür.
+                let mut child = Command::new(program)
+                    .args(&argv[1.min(argv.len())..])
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .map_err(spawn)?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    // Komut stdin'i okumadan çıkabilir; sonucu çıkış kodu belirler.
+                    let _ = stdin.write_all(body).await;
+                }
+                let status = child.wait().await.map_err(spawn)?;
+                if !status.success() {
+                    return Err(HookError::Exit { status });
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Notice for SessionReport {
+    fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    fn count(outcome: HookOutcome) {
+        metrics::get().hook_report(outcome);
+    }
+}
+
+pub struct Hook {
+    target: Target,
+    queue: Queue<SessionReport>,
+}
+
+impl Hook {
+    /// İşçileri başlatır; `kind = "none"` ise kanca yoktur. Tokio çalışma zamanı içinde çağrılmalı.
+    pub fn start(config: &HookConfig) -> Option<Hook> {
+        let target = match config.kind {
+            HookKind::None => return None,
+            HookKind::Http => Target::Http(config.url.clone()),
+            HookKind::Command => Target::Command(config.command.clone()),
+        };
+        let queue = Queue::start("session", config.timeout(), config.retries, config.queue_size, config.concurrency);
+        Some(Hook { target, queue })
+    }
+
+    /// Bildirimi kuyruğa koyar; kuyruk doluysa ya da kanca kapandıysa düşürür ve `false` döner.
+    pub fn submit(&self, report: SessionReport) -> bool {
+        self.queue.submit(self.target.clone(), report)
+    }
+
+    /// Yeni bildirim almayı bırakır ve bekleyenleri bekler (bkz. `Queue::flush`).
+    pub async fn flush(&self) {
+        self.queue.flush().await;
+    }
+}
+
+/// JSON gövdeyi `url`'ye POST eder; 2xx dışındaki cevaplar başarısızdır.
+pub(crate) async fn post(client: &Client<HttpConnector>, url: &str, body: &[u8]) -> Result<(), HookError> {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_vec()))
+        .map_err(|e| HookError::Request(e.to_string()))?;
+    let response = client.request(request).await.map_err(|e| HookError::Request(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(HookError::Status { status: response.status().as_u16() });
+    }
+    Ok(())
+}
+
+/// n. yeniden denemeden önceki bekleme: taban süre her denemede ikiye katlanır.
+pub(crate) fn backoff(attempt: u32) -> Duration {
+    backoff_between(BACKOFF_BASE, BACKOFF_MAX, attempt)
+}
+
+/// `backoff`, kendi taban ve tavan süresiyle yeniden deneyen başka gönderiler için (ör. kayıt yükleme).
+pub(crate) fn backoff_between(base: Duration, max: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1 << (attempt - 1).min(16)).min(max)
+}
+
+static HOOK: OnceLock<Hook> = OnceLock::new();
+
+/// Config'deki kancayı süreç geneli için kurar; yalnızca ilk çağrı etkilidir.
+pub fn install(config: &HookConfig) {
+    if let Some(hook) = Hook::start(config) {
+        let _ = HOOK.set(hook);
+    }
+}
+
+/// Oturum kapanışında çağrılır; kanca kurulu değilse hiçbir şey yapmaz, asla beklemez.
+pub fn session_ended(report: SessionReport) {
+    if let Some(hook) = HOOK.get() {
+        hook.submit(report);
+    }
+}
+
+/// Süreç kapanırken bekleyen bildirimleri gönderir (bkz. `Hook::flush`).
+pub async fn flush() {
+    if let Some(hook) = HOOK.get() {
+        hook.flush().await;
+    }
+}
+
+/// Testler için yerel HTTP uç noktası: ilk isteğe `first`, sonrakilere `then` ile cevap verir.
+/// Adresi (`http://127.0.0.1:port`) ve gelen isteklerin yolları ile gövdelerini döner.
+#[cfg(test)]
+pub(crate) fn flaky_endpoint(first: hyper::StatusCode, then: hyper::StatusCode) -> (String, Arc<Mutex<Vec<(String, hyper::body::Bytes)>>>) {
+    use std::convert::Infallible;
+
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Response, Server};
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = requests.clone();
+    let make = make_service_fn(move |_| {
+        let requests = received.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let requests = requests.clone();
+                async move {
+                    let path = request.uri().path().to_string();
+                    let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                    let status = {
+                        let mut requests = requests.lock().unwrap();
+                        requests.push((path, body));
+                        if requests.len() == 1 { first } else { then }
+                    };
+                    Ok::<_, Infallible>(Response::builder().status(status).body(Body::empty()).unwrap())
+                }
+            }))
+        }
+    });
+    let server = Server::bind(&std::net::SocketAddr::from(([127, 0, 0, 1], 0))).serve(make);
+    let base = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+    (base, requests)
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+
+    use super::*;
+
+    fn report(session_id: &str) -> SessionReport {
+        SessionReport {
+            session_id: session_id.to_string(), call_id: "call-1".to_string(), request_id: "req-1".to_string(), port: 10000, duration_ms: 1500,
+            packets_sent: 75, bytes_sent: 12900, packets_received: 70, bytes_received: 12040, packets_lost: 5,
+            packets_duplicated: 0, jitter_ms: 1.5, mos: Some(4.2),
+            announcements_failed: 0, playback_failure: None, send_error: None, recordings: vec!["x.wav".to_string()],
+            teardown_reason: "media_timeout", tenant: None, uploads: Vec::new(), audio_dumps: Vec::new(), recording_key_id: None,
+            labels: Labels::from([("queue".to_string(), "sales".to_string())]),
+        }
+    }
+
+    fn config(kind: HookKind) -> HookConfig {
+        HookConfig { kind, timeout_ms: 2000, retries: 2, ..HookConfig::default() }
+    }
+
+    #[tokio::test]
+    async fn command_hook_receives_the_report_on_stdin() {
+        let path = std::env::temp_dir().join(format!("media-hook-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let command = vec!["sh".to_string(), "-c".to_string(), format!("cat > '{}'", path.display())];
+        let hook = Hook::start(&HookConfig { command, ..config(HookKind::Command) }).unwrap();
+
+        assert!(hook.submit(report("abc")));
+        hook.flush().await;
+
+        let body: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(body["session_id"], "abc");
+        assert_eq!(body["packets_lost"], 5);
+        assert_eq!(body["recordings"][0], "x.wav");
+        assert_eq!(body["teardown_reason"], "media_timeout");
+        assert_eq!(body["labels"], serde_json::json!({"queue": "sales"}));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn http_hook_retries_until_the_endpoint_accepts() {
+        // İlk deneme reddedilir; kanca yeniden denemeli.
+        let (base, requests) = flaky_endpoint(StatusCode::SERVICE_UNAVAILABLE, StatusCode::OK);
+        let hook = Hook::start(&HookConfig { url: format!("{base}/sessions"), ..config(HookKind::Http) }).unwrap();
+        assert!(hook.submit(report("abc")));
+        hook.flush().await;
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let body: serde_json::Value = serde_json::from_slice(&requests[1].1).unwrap();
+        assert_eq!(body["call_id"], "call-1");
+        assert_eq!(requests[0], requests[1]);
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_reports_instead_of_waiting() {
+        let command = vec!["true".to_string()];
+        let hook = Hook::start(&HookConfig { command, queue_size: 1, concurrency: 1, ..config(HookKind::Command) }).unwrap();
+
+        // İşçi henüz çalışmadı; ilk bildirim kuyruğu doldurur.
+        assert!(hook.submit(report("a")));
+        assert!(!hook.submit(report("b")));
+        hook.flush().await;
+        assert!(!hook.submit(report("c")));
+    }
+}
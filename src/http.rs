@@ -0,0 +1,57 @@
+// Küçük HTTP sunucusu: Prometheus /metrics ve Kubernetes probları için /healthz, /readyz.
+// Metrik ve sağlık uç noktaları aynı adrese yapılandırılırsa tek dinleyici ikisini de sunar.
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+
+use crate::health::Health;
+
+/// Bir dinleyicinin sunduğu yollar.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Routes {
+    pub metrics: bool,
+    pub health: bool,
+}
+
+/// Port hemen bağlanır; dönen future `shutdown` tamamlanana kadar sunucuyu çalıştırır.
+pub fn serve(
+    addr: SocketAddr,
+    routes: Routes,
+    health: Arc<Health>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<impl Future<Output = Result<(), hyper::Error>>, hyper::Error> {
+    let make_service = make_service_fn(move |_| {
+        let health = health.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(req, routes, health.clone()))) }
+    });
+    Ok(Server::try_bind(&addr)?.serve(make_service).with_graceful_shutdown(shutdown))
+}
+
+async fn handle(req: Request<Body>, routes: Routes, health: Arc<Health>) -> Result<Response<Body>, Infallible> {
+    let response = match (req.method(), req.uri().path()) {
+        #[cfg(feature = "metrics")]
+        (&Method::GET, "/metrics") if routes.metrics => Response::builder()
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(Body::from(crate::metrics::get().render())),
+        (&Method::GET, "/healthz") if routes.health => json(StatusCode::OK, r#"{"status":"ok"}"#.to_string()),
+        (&Method::GET, "/readyz") if routes.health => {
+            let failures = health.readiness_failures();
+            if failures.is_empty() {
+                json(StatusCode::OK, r#"{"status":"ready"}"#.to_string())
+            } else {
+                let reasons: Vec<String> = failures.iter().map(|f| format!("\"{}\"", f)).collect();
+                json(StatusCode::SERVICE_UNAVAILABLE, format!(r#"{{"status":"not_ready","failing":[{}]}}"#, reasons.join(",")))
+            }
+        }
+        _ => Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()),
+    };
+    Ok(response.expect("sabit başlıklarla yanıt oluşturulamadı"))
+}
+
+fn json(status: StatusCode, body: String) -> Result<Response<Body>, hyper::http::Error> {
+    Response::builder().status(status).header("Content-Type", "application/json").body(Body::from(body))
+}
@@ -0,0 +1,158 @@
+// Test için giden yolda ağ bozulması: softphone'ların kayıp, titreşim ve sıra bozulması altındaki
+// davranışını tc/netem kurmadan denemek için. Oturumun giden her paketi (RTP, keepalive, RTCP)
+// sokete verilmeden önce olasılıkla düşürülür, çoğaltılır, bir sonraki paketle yer değiştirmek
+// üzere bekletilir ya da 0-`jitter_ms` arası rastgele bir gecikmeyle gönderilir. Paket mantığı
+// yalnızca `impairment` feature'ıyla derlenir; o zaman da `rtp.enable_impairments` kapalıysa
+// hiçbir istek bozulmayı açamaz. Bekletilen paket, sonraki paket düşse de, onu izleyen ilk
+// gönderilen paketten sonra çıkar; oturum biterken bekleyen paket atılır.
+#[cfg(feature = "impairment")]
+use std::net::SocketAddr;
+#[cfg(feature = "impairment")]
+use std::time::Duration;
+
+#[cfg(feature = "impairment")]
+use rand::prelude::*;
+
+use crate::error::ImpairmentError;
+
+/// En fazla titreşim; daha büyüğü jitter tamponlarını değil zaman aşımlarını dener.
+pub const MAX_JITTER_MS: u32 = 1000;
+
+/// Oturumun bozulma ayarı; yüzdeler 0-100.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Impairment {
+    pub loss_pct: f32,
+    pub jitter_ms: u32,
+    pub reorder_pct: f32,
+    pub duplicate_pct: f32,
+}
+
+impl Impairment {
+    /// Hepsi 0 ise bozulma kapalıdır.
+    pub fn is_off(&self) -> bool {
+        *self == Impairment::default()
+    }
+
+    pub fn validate(self) -> Result<Self, ImpairmentError> {
+        for (field, pct) in [("loss_pct", self.loss_pct), ("reorder_pct", self.reorder_pct), ("duplicate_pct", self.duplicate_pct)] {
+            if !(0.0..=100.0).contains(&pct) {
+                return Err(ImpairmentError::InvalidPercent { field, value: pct });
+            }
+        }
+        if self.jitter_ms > MAX_JITTER_MS {
+            return Err(ImpairmentError::InvalidJitter { jitter_ms: self.jitter_ms, max: MAX_JITTER_MS });
+        }
+        Ok(self)
+    }
+}
+
+/// Sokete verilecek bir paket; `delay` sıfırsa hemen.
+#[cfg(feature = "impairment")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outgoing {
+    pub delay: Duration,
+    pub bytes: Vec<u8>,
+    pub target: SocketAddr,
+}
+
+#[cfg(feature = "impairment")]
+#[derive(Debug)]
+pub struct Impairer {
+    impairment: Impairment,
+    rng: StdRng,
+    // Sonraki paketten sonra gönderilmek üzere bekletilen paket.
+    held: Option<(Vec<u8>, SocketAddr)>,
+}
+
+#[cfg(feature = "impairment")]
+impl Impairer {
+    pub fn new(impairment: Impairment) -> Self {
+        Impairer::seeded(impairment, StdRng::from_entropy())
+    }
+
+    fn seeded(impairment: Impairment, rng: StdRng) -> Self {
+        Impairer { impairment, rng, held: None }
+    }
+
+    pub fn impairment(&self) -> Impairment {
+        self.impairment
+    }
+
+    /// Giden paketin yerine sokete verilecekler, sırayla; paket düştüyse ya da bekletildiyse boş.
+    pub fn process(&mut self, bytes: &[u8], target: SocketAddr) -> Vec<Outgoing> {
+        let Impairment { loss_pct, jitter_ms, reorder_pct, duplicate_pct } = self.impairment;
+        if self.chance(loss_pct) {
+            return Vec::new();
+        }
+        if self.held.is_none() && self.chance(reorder_pct) {
+            self.held = Some((bytes.to_vec(), target));
+            return Vec::new();
+        }
+        let copies = if self.chance(duplicate_pct) { 2 } else { 1 };
+        let mut outgoing = Vec::with_capacity(copies + 1);
+        for _ in 0..copies {
+            let delay = Duration::from_millis(self.rng.gen_range(0..=jitter_ms) as u64);
+            outgoing.push(Outgoing { delay, bytes: bytes.to_vec(), target });
+        }
+        if let Some((bytes, target)) = self.held.take() {
+            // Yer değiştirdiği paketten önce çıkmasın diye onun gecikmesini alır.
+            let delay = outgoing.last().map_or(Duration::ZERO, |last| last.delay);
+            outgoing.push(Outgoing { delay, bytes, target });
+        }
+        outgoing
+    }
+
+    fn chance(&mut self, pct: f32) -> bool {
+        pct > 0.0 && self.rng.gen::<f32>() * 100.0 < pct
+    }
+}
+
+#[cfg(all(test, feature = "impairment"))]
+mod tests {
+    use super::*;
+
+    const TARGET: SocketAddr = SocketAddr::new(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST), 4000);
+
+    fn impairer(impairment: Impairment) -> Impairer {
+        Impairer::seeded(impairment, StdRng::seed_from_u64(7))
+    }
+
+    /// Sıra numarasını taşıyan 1000 paketi işler; sokete verilenleri döner.
+    fn run(impairer: &mut Impairer) -> Vec<Outgoing> {
+        (0..1000u32).flat_map(|i| impairer.process(&i.to_be_bytes(), TARGET)).collect()
+    }
+
+    #[test]
+    fn losses_and_duplicates_follow_their_rates() {
+        let sent = run(&mut impairer(Impairment { loss_pct: 20.0, ..Impairment::default() }));
+        assert!((750..=850).contains(&sent.len()), "{}", sent.len());
+        assert!(sent.iter().all(|out| out.delay == Duration::ZERO && out.target == TARGET));
+
+        let sent = run(&mut impairer(Impairment { duplicate_pct: 10.0, ..Impairment::default() }));
+        assert!((1050..=1150).contains(&sent.len()), "{}", sent.len());
+        assert_eq!(run(&mut impairer(Impairment::default())).len(), 1000);
+    }
+
+    #[test]
+    fn reordered_packets_follow_their_successor_and_jitter_is_bounded() {
+        let mut reorder = impairer(Impairment { reorder_pct: 50.0, ..Impairment::default() });
+        let order: Vec<u32> = run(&mut reorder).iter().map(|out| u32::from_be_bytes(out.bytes[..4].try_into().unwrap())).collect();
+        let swaps = order.windows(2).filter(|pair| pair[0] > pair[1]).count();
+        assert!(swaps > 200, "{}", swaps);
+        // Her paket en fazla bir sonrakiyle yer değiştirir; son bekletilen paket hâlâ beklemede olabilir.
+        assert!(order.iter().enumerate().all(|(position, &sequence)| (sequence as usize).abs_diff(position) <= 1));
+        assert!(order.len() >= 999);
+
+        let sent = run(&mut impairer(Impairment { jitter_ms: 40, ..Impairment::default() }));
+        assert!(sent.iter().all(|out| out.delay <= Duration::from_millis(40)));
+        assert!(sent.iter().any(|out| out.delay >= Duration::from_millis(30)));
+    }
+
+    #[test]
+    fn out_of_range_settings_are_rejected() {
+        assert!(Impairment { loss_pct: 101.0, ..Impairment::default() }.validate().is_err());
+        assert!(Impairment { duplicate_pct: -1.0, ..Impairment::default() }.validate().is_err());
+        assert!(Impairment { jitter_ms: MAX_JITTER_MS + 1, ..Impairment::default() }.validate().is_err());
+        assert!(Impairment::default().is_off());
+    }
+}
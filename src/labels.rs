@@ -0,0 +1,168 @@
+// Oturum etiketleri: AllocatePort'ta verilen küçük bir anahtar/değer kümesi (kampanya, kuyruk,
+// müşteri, ortam...). Etiketler oturumda saklanır; denetim olaylarına, oturum özetine, CDR'a,
+// kapanış kancasına ve ListSessions'a aynen geçer. `metrics.session_labels` listesindeki anahtarlar
+// ayrıca Prometheus etiketi olur: her anahtarın ilk `metrics.session_label_values` farklı değeri
+// kendi serisini alır, sonrakiler "other" serisinde toplanır. Böylece istemci metrik deposunun
+// kardinalitesini büyütemez; listede olmayan anahtarlar metriklere hiç yansımaz.
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::error::AllocationError;
+use crate::metrics::LabelMetrics;
+
+/// Oturum başına en fazla etiket.
+pub const MAX_LABELS: usize = 16;
+pub const MAX_KEY_LEN: usize = 64;
+pub const MAX_VALUE_LEN: usize = 128;
+/// Değer sınırını aşan etiketlerin toplandığı seri.
+pub const OTHER_VALUE: &str = "other";
+
+/// Anahtara göre sıralı; olaylarda ve CDR'da hep aynı sırayla yazılır.
+pub type Labels = BTreeMap<String, String>;
+
+/// İstekteki etiketleri doğrular. Anahtarlar Prometheus etiket adı kuralına uyar (`[a-z_][a-z0-9_]*`,
+/// `__` ile başlamaz); değerler kontrol karakteri içermez.
+pub fn validate(labels: &HashMap<String, String>) -> Result<Labels, AllocationError> {
+    if labels.len() > MAX_LABELS {
+        return Err(AllocationError::TooManyLabels { count: labels.len(), max: MAX_LABELS });
+    }
+    let invalid = |key: &str, reason: &'static str| AllocationError::InvalidLabel { key: key.to_string(), reason };
+    for (key, value) in labels {
+        let mut chars = key.chars();
+        if !chars.next().is_some_and(|c| c.is_ascii_lowercase() || c == '_') || !chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            return Err(invalid(key, "keys must match [a-z_][a-z0-9_]*"));
+        }
+        if key.starts_with("__") {
+            return Err(invalid(key, "keys starting with '__' are reserved"));
+        }
+        if key.len() > MAX_KEY_LEN {
+            return Err(invalid(key, "key is longer than 64 bytes"));
+        }
+        if value.len() > MAX_VALUE_LEN {
+            return Err(invalid(key, "value is longer than 128 bytes"));
+        }
+        if value.chars().any(char::is_control) {
+            return Err(invalid(key, "value contains control characters"));
+        }
+    }
+    Ok(labels.iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+}
+
+/// Denetim olayları için `anahtar=değer` çiftleri, virgülle ayrılmış; etiket yoksa boş.
+pub fn format(labels: &Labels) -> String {
+    labels.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join(",")
+}
+
+/// Oturum `filter`'daki her etiketi taşıyor mu; boş değer yalnızca anahtarın varlığını arar.
+pub fn matches(labels: &Labels, filter: &HashMap<String, String>) -> bool {
+    filter.iter().all(|(key, value)| labels.get(key).is_some_and(|actual| value.is_empty() || actual == value))
+}
+
+/// İzin verilen anahtarların metrik serileri; seriler ilk kullanımda eklenir ve hiç silinmez.
+#[derive(Debug, Default)]
+pub struct LabelSeries {
+    keys: Vec<&'static str>,
+    max_values: usize,
+    series: Vec<Arc<LabelMetrics>>,
+}
+
+impl LabelSeries {
+    pub const fn new() -> Self {
+        LabelSeries { keys: Vec::new(), max_values: 0, series: Vec::new() }
+    }
+
+    /// İzin listesini kurar; listeden çıkan anahtarların mevcut serileri yayınlanmaya devam eder.
+    pub fn configure(&mut self, keys: &[String], max_values: usize) {
+        // Etiket adları 'static; config'den gelir ve sayıları sınırlıdır.
+        self.keys = keys.iter().map(|key| self.key(key)).collect();
+        self.max_values = max_values;
+    }
+
+    fn key(&self, key: &str) -> &'static str {
+        self.keys.iter().chain(self.series.iter().map(|s| &s.key)).find(|k| **k == key).copied()
+            .unwrap_or_else(|| Box::leak(key.to_string().into_boxed_str()))
+    }
+
+    /// Oturum etiketlerinden izin listesindekilerin serileri; anahtarın değer sınırı dolduysa
+    /// yeni değerler "other" serisine gider.
+    pub fn resolve(&mut self, labels: &Labels) -> Vec<Arc<LabelMetrics>> {
+        let mut resolved = Vec::new();
+        for key in self.keys.clone() {
+            let Some(value) = labels.get(key) else { continue };
+            let known = self.series.iter().filter(|s| s.key == key && s.value != OTHER_VALUE);
+            let value = match known.clone().find(|s| s.value == value) {
+                Some(series) => {
+                    resolved.push(series.clone());
+                    continue;
+                }
+                None if known.count() >= self.max_values => OTHER_VALUE,
+                None => value.as_str(),
+            };
+            resolved.push(self.series(key, value));
+        }
+        resolved
+    }
+
+    fn series(&mut self, key: &'static str, value: &str) -> Arc<LabelMetrics> {
+        if let Some(series) = self.series.iter().find(|s| s.key == key && s.value == value) {
+            return series.clone();
+        }
+        let value = match value {
+            OTHER_VALUE => OTHER_VALUE,
+            value => Box::leak(value.to_string().into_boxed_str()),
+        };
+        let series = Arc::new(LabelMetrics::new(key, value));
+        self.series.push(series.clone());
+        series
+    }
+
+    pub fn all(&self) -> &[Arc<LabelMetrics>] {
+        &self.series
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn labels_are_validated_formatted_and_filtered() {
+        let valid = validate(&labels(&[("queue", "sales"), ("campaign", "spring-2026"), ("env", "")])).unwrap();
+        assert_eq!(format(&valid), "campaign=spring-2026,env=,queue=sales");
+        assert!(matches(&valid, &labels(&[("queue", "sales"), ("campaign", "")])));
+        assert!(!matches(&valid, &labels(&[("queue", "support")])));
+        assert!(!matches(&valid, &labels(&[("customer", "")])));
+        assert!(matches(&valid, &HashMap::new()));
+
+        for pairs in [[("Queue", "x")], [("9lives", "x")], [("__name", "x")], [("queue", "a\nb")]] {
+            assert!(matches!(validate(&labels(&pairs)), Err(AllocationError::InvalidLabel { .. })), "{:?}", pairs);
+        }
+        let long_key = "k".repeat(MAX_KEY_LEN + 1);
+        assert!(validate(&labels(&[(long_key.as_str(), "x")])).is_err());
+        let long_value = "v".repeat(MAX_VALUE_LEN + 1);
+        assert!(validate(&labels(&[("queue", long_value.as_str())])).is_err());
+        let many: HashMap<String, String> = (0..=MAX_LABELS).map(|i| (format!("k{}", i), String::new())).collect();
+        assert!(matches!(validate(&many), Err(AllocationError::TooManyLabels { count: 17, max: 16 })));
+    }
+
+    #[test]
+    fn only_allowed_keys_become_series_and_values_are_capped() {
+        let mut series = LabelSeries::new();
+        let session = |pairs: &[(&str, &str)]| validate(&labels(pairs)).unwrap();
+        assert!(series.resolve(&session(&[("queue", "sales")])).is_empty());
+
+        series.configure(&["queue".to_string(), "env".to_string()], 2);
+        let names = |resolved: Vec<Arc<LabelMetrics>>| resolved.iter().map(|s| (s.key, s.value)).collect::<Vec<_>>();
+        assert_eq!(names(series.resolve(&session(&[("queue", "sales"), ("customer", "acme"), ("env", "prod")]))), [("queue", "sales"), ("env", "prod")]);
+        assert_eq!(names(series.resolve(&session(&[("queue", "support")]))), [("queue", "support")]);
+        // Üçüncü değer sınırı aşar; dördüncü de aynı "other" serisini paylaşır.
+        assert_eq!(names(series.resolve(&session(&[("queue", "billing")]))), [("queue", OTHER_VALUE)]);
+        assert_eq!(names(series.resolve(&session(&[("queue", "retention")]))), [("queue", OTHER_VALUE)]);
+        assert_eq!(names(series.resolve(&session(&[("queue", "sales")]))), [("queue", "sales")]);
+        assert_eq!(series.all().len(), 4);
+    }
+}
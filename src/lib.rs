@@ -0,0 +1,70 @@
+// tonic::Status büyük bir tip; handler yardımcılarında Result<_, Status> dönmek normal.
+#![allow(clippy::result_large_err)]
+
+// Zamanlama kararları (anons temposu, zaman aşımları, keepalive) yalnızca `tokio::time` ile
+// alınır; testler `start_paused` altında sanal zamanı ilerletir. `SystemTime` sadece dosya
+// adları ve pcap kayıt zamanları gibi duvar saati değerleri içindir.
+
+pub mod admission;
+pub mod amd;
+pub mod announcement;
+pub mod appender;
+#[cfg(feature = "asr")]
+pub mod asr;
+pub mod audio_dump;
+pub mod audio_level;
+pub mod audit;
+pub mod bridge;
+pub mod build_info;
+pub mod capture;
+pub mod cdr;
+pub mod codec;
+pub mod compose;
+pub mod config;
+pub mod dtmf_hook;
+pub mod encryption;
+pub mod error;
+pub mod fax;
+pub mod grpc;
+pub mod health;
+pub mod heartbeat;
+pub mod hook;
+pub mod http;
+pub mod impairment;
+pub mod labels;
+pub mod logging;
+pub mod metrics;
+pub mod migration;
+pub mod mixer;
+pub mod object_store;
+pub mod one_way;
+pub mod playback;
+pub mod port_journal;
+pub mod prompt_cache;
+pub mod quality;
+pub mod ratelimit;
+pub mod recording;
+#[cfg(all(target_os = "linux", feature = "recvmmsg"))]
+pub mod recvmmsg;
+pub mod red;
+pub mod replay;
+pub mod request_id;
+pub mod rtcp;
+pub mod rtp;
+pub mod scheduler;
+pub mod sdp;
+pub mod session;
+pub mod source;
+pub mod state;
+pub mod stats;
+#[cfg(all(unix, feature = "systemd"))]
+pub mod systemd;
+pub mod tap;
+pub mod telemetry;
+pub mod transport;
+#[cfg(unix)]
+pub mod uds;
+pub mod url_cache;
+pub mod vad;
+
+pub mod media { tonic::include_proto!("media"); }
@@ -0,0 +1,44 @@
+// Log altyapısı: insan okuyabilir (text) veya JSON çıktı, çalışma anında değiştirilebilir seviye.
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+use crate::config::{LogConfig, LogFormat, TelemetryConfig};
+use crate::error::ConfigError;
+use crate::telemetry;
+
+pub type LogReloadHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Global subscriber'ı kurar. `RUST_LOG` tanımlıysa başlangıçta config'deki seviyeyi ezer.
+/// Telemetri açıksa span'ler ayrıca OTLP'ye gönderilir; seviye filtresi her iki çıktıya da uygulanır.
+pub fn init(config: &LogConfig, telemetry_config: &TelemetryConfig) -> Result<LogReloadHandle, ConfigError> {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => parse_filter(&config.level)?,
+    };
+    let (filter_layer, handle) = reload::Layer::new(filter);
+    let telemetry_layer = telemetry::tracing_layer(telemetry_config).map_err(|e| ConfigError::Logging(e.to_string()))?;
+    let registry = tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(telemetry_layer);
+
+    let installed = match config.format {
+        LogFormat::Text => registry.with(fmt::layer()).try_init(),
+        // Olay alanları (rtp_port, remote, ...) mesajın içine gömülmeden üst seviye JSON alanı olur.
+        LogFormat::Json => registry
+            .with(fmt::layer().json().flatten_event(true).with_current_span(true).with_span_list(false))
+            .try_init(),
+    };
+    installed.map_err(|e| ConfigError::Logging(e.to_string()))?;
+    Ok(handle)
+}
+
+/// Log seviyesini yeniden başlatmadan değiştirir (ör. "debug" veya "info,media=trace").
+pub fn set_level(handle: &LogReloadHandle, level: &str) -> Result<(), ConfigError> {
+    let filter = parse_filter(level)?;
+    handle.reload(filter).map_err(|e| ConfigError::Logging(e.to_string()))
+}
+
+fn parse_filter(level: &str) -> Result<EnvFilter, ConfigError> {
+    EnvFilter::try_new(level).map_err(|e| ConfigError::InvalidLogLevel { level: level.to_string(), reason: e.to_string() })
+}
@@ -1,176 +1,401 @@
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tokio::net::UdpSocket;
-use tokio::time::interval;
-use rand::prelude::*;
-use tonic::{transport::Server, Request, Response, Status};
-use config::{Config, File};
-use serde::Deserialize;
-use tracing::{info, error, instrument, Level};
-use tracing_subscriber::FmtSubscriber;
-use hound; // hound'u modül olarak import etmemiz yeterli
-
-pub mod media { tonic::include_proto!("media"); }
-use media::media_manager_server::{MediaManager, MediaManagerServer};
-use media::{AllocatePortRequest, AllocatePortResponse};
-
-#[derive(Debug, Deserialize, Clone)]
-struct GrpcConfig { host: String, port: u16, }
-#[derive(Debug, Deserialize, Clone)]
-struct RtpConfig { host: String, min_port: u16, max_port: u16, }
-#[derive(Debug, Deserialize, Clone)]
-struct AnnouncementConfig { welcome_file_path: String, }
-#[derive(Debug, Deserialize, Clone)]
-struct Settings {
-    grpc: GrpcConfig,
-    rtp: RtpConfig,
-    announcement: AnnouncementConfig,
-}
-
-type ActiveSessions = Arc<Mutex<Vec<u16>>>;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
 
-#[derive(Debug)]
-pub struct MyMediaManager {
-    active_sessions: ActiveSessions,
-    settings: Arc<Settings>,
-}
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+use tracing::{error, info, warn};
 
-#[tonic::async_trait]
-impl MediaManager for MyMediaManager {
-    #[instrument(skip(self))]
-    async fn allocate_port(&self, _request: Request<AllocatePortRequest>) -> Result<Response<AllocatePortResponse>, Status> {
-        info!("AllocatePort isteği alındı...");
-        let (port, sock) = bind_rtp_port(&self.settings.rtp).await
-            .map_err(|e| { error!(error = %e, "RTP portu atanamadı"); Status::internal("RTP portu atanamadı") })?;
-        
-        let shared_sock = Arc::new(sock);
-        tokio::spawn(rtp_session_handler(shared_sock, self.active_sessions.clone(), port, self.settings.clone()));
-
-        info!(rtp_port = port, "Yeni RTP portu atandı");
-        let reply = AllocatePortResponse { port: port as u32 };
-        Ok(Response::new(reply))
-    }
-}
+use media::announcement::PromptLibrary;
+use media::config::{MetricsExporter, Settings};
+use media::encryption::{self, RecordingKey};
+use media::error::{ConfigError, DecryptError, Error, ReplayError};
+use media::grpc::MyMediaManager;
+use media::health::Health;
+use media::media::media_manager_server::MediaManagerServer;
+use media::replay::{self, Filter};
+use media::request_id::RequestIdInterceptor;
+use media::session::{force_stop_sessions, stop_all_sessions, wait_for_sessions, ActiveSessions};
+#[cfg(unix)]
+use media::state;
+use media::{admission, build_info, cdr, dtmf_hook, heartbeat, hook, http, logging, metrics, object_store, port_journal, rtcp, telemetry};
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let subscriber = FmtSubscriber::builder().with_max_level(Level::INFO).finish();
-    tracing::subscriber::set_global_default(subscriber)?;
-    let settings = Config::builder()
-        .add_source(File::with_name("config/default"))
-        .build()?
-        .try_deserialize::<Settings>()?;
+async fn main() -> Result<(), Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|command| command == "replay-pcap") {
+        return replay_pcap(&args[1..]).await;
+    }
+    let settings = Settings::read().map_err(ConfigError::from)?;
+    if args.first().is_some_and(|command| command == "decrypt-recording") {
+        return decrypt_recording(&settings, &args[1..]);
+    }
+    let log_handle = logging::init(&settings.log, &settings.telemetry)?;
+    if !Settings::config_file_present() {
+        warn!(
+            grpc = %format!("{}:{}", settings.grpc.host, settings.grpc.port),
+            rtp_ports = %format!("{}-{}", settings.rtp.min_port, settings.rtp.max_port),
+            announcement = settings.announcement.welcome_name().unwrap_or("yok"),
+            "Konfigürasyon dosyası bulunamadı, gömülü varsayılanlar kullanılıyor"
+        );
+    }
+    info!(
+        version = build_info::VERSION, git_commit = build_info::GIT_COMMIT, git_dirty = build_info::GIT_DIRTY,
+        build_timestamp = build_info::BUILD_TIMESTAMP, features = %build_info::FEATURES.join(","), schema_version = build_info::SCHEMA_VERSION,
+        "Media sunucusu başlatılıyor"
+    );
+    settings.ensure_valid()?;
     info!(config = ?settings, "Konfigürasyon yüklendi");
+    let health = Arc::new(Health::default());
+    health.set_config_valid(true);
+    let prompts = PromptLibrary::load(&settings.announcement)?;
+    let recording_key = encryption::load(&settings.recording.encryption).map_err(ConfigError::RecordingEncryption)?;
+    if let Some(key) = &recording_key {
+        info!(key_id = key.id(), "Kayıtlar şifrelenerek yazılacak");
+    }
+
+    #[cfg(not(feature = "otel"))]
+    if settings.telemetry.enabled {
+        warn!("otel feature'ı olmadan derlendi, OpenTelemetry ihracı devre dışı");
+    }
+    #[cfg(feature = "impairment")]
+    if settings.rtp.enable_impairments {
+        warn!("Ağ bozulması açık: istekler oturumların giden paketlerini düşürebilir ve geciktirebilir; production'da kapatın");
+    }
+    #[cfg(not(feature = "impairment"))]
+    if settings.rtp.enable_impairments {
+        warn!("impairment feature'ı olmadan derlendi, ağ bozulması devre dışı");
+    }
+    #[cfg(feature = "asr")]
+    if settings.asr.enabled {
+        info!(endpoint = %settings.asr.endpoint, "Canlı transkripsiyon açık");
+    }
+    #[cfg(not(feature = "asr"))]
+    if settings.asr.enabled {
+        warn!("asr feature'ı olmadan derlendi, canlı transkripsiyon devre dışı");
+    }
 
-    let active_sessions = Arc::new(Mutex::new(Vec::new()));
-    let addr = format!("{}:{}", settings.grpc.host, settings.grpc.port).parse()?;
-    let manager = MyMediaManager {
-        active_sessions,
-        settings: Arc::new(settings),
+    metrics::get().register_tenants(&settings.tenants);
+    metrics::get().configure_session_labels(&settings.metrics);
+    let prometheus = settings.metrics.exporter == MetricsExporter::Prometheus;
+    let http_shutdown = Arc::new(Notify::new());
+    let mut http_servers = Vec::new();
+    for (addr, routes) in http_listeners(&settings)? {
+        let shutdown = http_shutdown.clone();
+        let server = http::serve(addr, routes, health.clone(), async move { shutdown.notified().await })
+            .map_err(|e| Error::Listen { server: "HTTP", addr, reason: e.to_string() })?;
+        info!(address = %addr, metrics = routes.metrics, health = routes.health, "HTTP uç noktası başlatılıyor...");
+        http_servers.push(tokio::spawn(async move {
+            if let Err(e) = server.await { error!(error = %e, "HTTP sunucusu durdu"); }
+        }));
+    }
+    #[cfg(not(feature = "metrics"))]
+    if settings.metrics.enabled && prometheus {
+        warn!("metrics feature'ı olmadan derlendi, /metrics uç noktası devre dışı");
+    }
+    #[cfg(feature = "otel")]
+    if settings.metrics.enabled && !prometheus {
+        telemetry::start_metrics(&settings.telemetry).map_err(|e| Error::Telemetry(e.to_string()))?;
+        info!(endpoint = %settings.telemetry.endpoint, "Metrikler OTLP ile gönderiliyor");
+    }
+    #[cfg(not(feature = "otel"))]
+    if settings.metrics.enabled && !prometheus {
+        warn!("otel feature'ı olmadan derlendi, OTLP metrik ihracı devre dışı");
+    }
+    metrics::get().port_pool_size.set((settings.rtp.max_port - settings.rtp.min_port) as i64 + 1);
+
+    hook::install(&settings.hook);
+    dtmf_hook::install(&settings.dtmf_hook);
+    cdr::install(&settings.cdr);
+    port_journal::install(&settings.port_journal);
+    rtcp::install(&settings.rtcp);
+    object_store::install(&settings.object_storage, &settings.recording.directory).map_err(ConfigError::ObjectStorage)?;
+    #[cfg(not(feature = "object-storage"))]
+    if settings.object_storage.enabled {
+        warn!("object-storage feature'ı olmadan derlendi, nesne deposu devre dışı");
+    }
+
+    if let Some(period) = settings.timers.heartbeat_interval() {
+        tokio::spawn(heartbeat::run(period, health.clone()));
+    }
+    if settings.admission.enabled {
+        tokio::spawn(admission::run(settings.admission.clone()));
+    }
+
+    let active_sessions = ActiveSessions::default();
+    let shutdown_grace = settings.timers.shutdown_grace();
+    let shutdown_deadline = settings.timers.shutdown_deadline();
+    let grpc_config = settings.grpc.clone();
+    // Dinleyiciler burada bağlanır; böylece /readyz yalnızca gerçekten dinlenen bir port için hazır der.
+    // Adreslerden biri bağlanamazsa hiçbiri sunulmadan başlangıç başarısız olur.
+    let mut tcp_listeners = Vec::new();
+    if grpc_config.tcp {
+        for addr in grpc_config.listen_addrs()? {
+            let listener = TcpListener::bind(addr).await
+                .map_err(|e| Error::Listen { server: "gRPC", addr, reason: e.to_string() })?;
+            tcp_listeners.push(listener);
+        }
+    }
+    #[cfg(unix)]
+    let socket = match &grpc_config.unix_socket_path {
+        Some(path) => Some(
+            media::uds::SocketFile::bind(path.as_ref(), grpc_config.unix_socket_mode)
+                .map_err(|e| Error::ListenUnix { server: "gRPC", path: path.clone(), reason: e.to_string() })?,
+        ),
+        None => None,
     };
-    let grpc_server = Server::builder().add_service(MediaManagerServer::new(manager)).serve(addr);
 
-    info!(address = %addr, "gRPC sunucusu başlatılıyor...");
-    tokio::spawn(grpc_server);
+    let mut listen_addresses = Vec::new();
+    for listener in &tcp_listeners {
+        listen_addresses.push(listener.local_addr()?.to_string());
+    }
+    #[cfg(unix)]
+    if let Some((file, _)) = &socket {
+        listen_addresses.push(format!("unix:{}", file.path().display()));
+    }
+    let (settings, prompts) = (Arc::new(settings), Arc::new(prompts));
+    #[cfg(unix)]
+    tokio::spawn(reload_on_sighup(log_handle.clone(), health.clone(), prompts.clone()));
+    #[cfg(unix)]
+    tokio::spawn(dump_state_on_sigusr1(active_sessions.clone(), prompts.clone(), settings.clone(), health.clone()));
+    let manager = MyMediaManager::new(active_sessions.clone(), settings, prompts, Some(log_handle))
+        .with_listen_addresses(listen_addresses)
+        .with_recording_key(recording_key)
+        .with_health(health.clone());
+    let service = InterceptedService::new(MediaManagerServer::from_arc(Arc::new(manager)), RequestIdInterceptor::new(&grpc_config.request_id_header));
+    let grpc_shutdown = Arc::new(Notify::new());
+    let mut grpc_servers = Vec::new();
+    health.set_grpc_serving(true);
+    for listener in tcp_listeners {
+        let addr = listener.local_addr()?;
+        let incoming = TcpIncoming::from_listener(listener, true, None)
+            .map_err(|e| Error::Listen { server: "gRPC", addr, reason: e.to_string() })?;
+        info!(address = %addr, "gRPC sunucusu başlatılıyor...");
+        let shutdown = grpc_shutdown.clone();
+        let server = Server::builder().add_service(service.clone()).serve_with_incoming_shutdown(incoming, async move { shutdown.notified().await });
+        grpc_servers.push(spawn_grpc(server, health.clone()));
+    }
+    #[cfg(unix)]
+    let _socket_file = match socket {
+        Some((file, incoming)) => {
+            info!(path = %file.path().display(), mode = %format!("{:#o}", grpc_config.unix_socket_mode), "gRPC sunucusu Unix soketinde başlatılıyor...");
+            let shutdown = grpc_shutdown.clone();
+            let server = Server::builder().add_service(service.clone()).serve_with_incoming_shutdown(incoming, async move { shutdown.notified().await });
+            grpc_servers.push(spawn_grpc(server, health.clone()));
+            Some(file)
+        }
+        None => None,
+    };
+    #[cfg(all(unix, feature = "systemd"))]
+    let notifier = media::systemd::Notifier::from_env().map(Arc::new);
+    #[cfg(all(unix, feature = "systemd"))]
+    if let Some(notifier) = &notifier {
+        notifier.ready();
+        if let Some(period) = media::systemd::watchdog_interval() {
+            tokio::spawn(media::systemd::run_watchdog(notifier.clone(), period, health.clone(), active_sessions.clone()));
+        }
+    }
+
+    let signal = shutdown_signal().await?;
+    info!(signal, "Sunucu kapatılıyor...");
+    #[cfg(all(unix, feature = "systemd"))]
+    if let Some(notifier) = &notifier {
+        notifier.stopping();
+    }
+    health.set_draining(true);
+    let drain = async {
+        if let Some(grace) = shutdown_grace {
+            wait_for_sessions(&active_sessions, grace).await;
+        }
+        stop_all_sessions(&active_sessions).await;
+        hook::flush().await;
+        dtmf_hook::flush().await;
+        // Yazıcı ayrı bir iş parçacığında; son fsync'i beklerken çalışma zamanı bloklanmaz.
+        let _ = tokio::task::spawn_blocking(cdr::flush).await;
+        let _ = tokio::task::spawn_blocking(port_journal::flush).await;
+        http_shutdown.notify_waiters();
+        grpc_shutdown.notify_waiters();
+        for server in http_servers.into_iter().chain(grpc_servers) {
+            let _ = server.await;
+        }
+    };
+    let graceful = match shutdown_deadline {
+        Some(deadline) => tokio::time::timeout(deadline, drain).await.is_ok(),
+        None => { drain.await; true }
+    };
+    if !graceful {
+        let sessions = force_stop_sessions(&active_sessions).await;
+        error!(sessions, deadline_s = shutdown_deadline.unwrap_or_default().as_secs(), "Kapanış süresi doldu, kalan oturumlar kesildi");
+        telemetry::shutdown();
+        // Çalışma zamanı düşerken takılmış engelleyici görevleri bekler; beklemeden çıkılır.
+        std::process::exit(EXIT_SHUTDOWN_DEADLINE);
+    }
+    telemetry::shutdown();
+    Ok(())
+}
+
+// Kapanış süresi dolup oturumlar kesildiğinde çıkış kodu; hata ile çıkıştan (1) ayrılır.
+const EXIT_SHUTDOWN_DEADLINE: i32 = 2;
+
+/// `media decrypt-recording <girdi> <çıktı.wav> [--key-file YOL]`: şifreli kaydı WAV'a çevirir.
+/// Anahtar dosyası verilmezse `recording.encryption.key_file` kullanılır. Kesilmiş dosyada
+/// kurtarılan kısım yazılır ama komut hatayla biter.
+fn decrypt_recording(settings: &Settings, args: &[String]) -> Result<(), Error> {
+    let (input, output, key_file) = match args {
+        [input, output] => (input, output, &settings.recording.encryption.key_file),
+        [input, output, flag, key_file] if flag == "--key-file" => (input, output, key_file),
+        _ => return Err(DecryptError::Usage.into()),
+    };
+    let key = RecordingKey::from_file(&settings.recording.encryption.key_id, key_file).map_err(ConfigError::RecordingEncryption)?;
+    let decrypted = encryption::decrypt_recording(Path::new(input), Path::new(output), &key)?;
+    let duration_ms = decrypted.samples * 1000 / decrypted.sample_rate as u64;
+    println!("{} -> {}: key_id={} sample_rate={} duration_ms={}", input, output, decrypted.key_id, decrypted.sample_rate, duration_ms);
+    if decrypted.truncated {
+        return Err(DecryptError::Truncated { recovered_ms: duration_ms, output: output.clone() }.into());
+    }
+    Ok(())
+}
 
-    tokio::signal::ctrl_c().await?;
-    info!("Sunucu kapatılıyor...");
+/// `media replay-pcap <dosya.pcap> <IP:port> [--speed N] [--ssrc SSRC] [--flow KAYNAK[,HEDEF]]`:
+/// kayıttaki RTP akışını yerel bir soketten oturumun portuna, kayıttaki aralıklarla gönderir.
+async fn replay_pcap(args: &[String]) -> Result<(), Error> {
+    let [path, target, options @ ..] = args else { return Err(ReplayError::Usage.into()) };
+    let target: SocketAddr = target.parse().map_err(|_| ReplayError::Usage)?;
+    let (mut speed, mut filter) = (1.0, Filter::Only);
+    for option in options.chunks(2) {
+        match option {
+            [flag, value] if flag == "--speed" => {
+                speed = value.parse().ok().filter(|speed| *speed > 0.0 && *speed <= replay::MAX_SPEED).ok_or(ReplayError::Usage)?;
+            }
+            [flag, value] if flag == "--ssrc" => filter = Filter::ssrc(value).ok_or(ReplayError::Usage)?,
+            [flag, value] if flag == "--flow" => filter = Filter::flow(value).ok_or(ReplayError::Usage)?,
+            _ => return Err(ReplayError::Usage.into()),
+        }
+    }
+    let pcap = std::fs::read(path).map_err(|source| ReplayError::Read { path: path.clone(), source })?;
+    let plan = replay::plan(&pcap, filter)?;
+    let socket = UdpSocket::bind(if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }).await?;
+    let replayed = replay::replay(&plan, &socket, target, speed).await?;
+    let skipped = plan.skipped;
+    println!(
+        "{} -> {}: replayed={} skipped={} (not_udp={} not_rtp={} filtered={})",
+        path, target, replayed, skipped.total(), skipped.not_udp, skipped.not_rtp, skipped.filtered,
+    );
     Ok(())
 }
 
-async fn bind_rtp_port(rtp_config: &RtpConfig) -> Result<(u16, UdpSocket), std::io::Error> {
-    let mut rng = SmallRng::from_entropy();
-    for _ in 0..100 {
-        let port = rng.gen_range(rtp_config.min_port..=rtp_config.max_port);
-        let addr_str = format!("{}:{}", rtp_config.host, port);
-        if let Ok(socket) = UdpSocket::bind(&addr_str).await {
-            return Ok((port, socket));
+/// SIGINT (ctrl-c) ya da unix'te SIGTERM gelene kadar bekler ve gelen sinyalin adını döner.
+async fn shutdown_signal() -> std::io::Result<&'static str> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut term = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result.map(|()| "SIGINT"),
+            _ = term.recv() => Ok("SIGTERM"),
         }
     }
-    Err(std::io::Error::new(std::io::ErrorKind::AddrInUse, "Boş port bulunamadı"))
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.map(|()| "SIGINT")
+}
+
+/// gRPC sunucusunu çalıştırır; sunuculardan biri durursa /readyz hazır değil der.
+fn spawn_grpc<F>(server: F, health: Arc<Health>) -> JoinHandle<()>
+where
+    F: Future<Output = Result<(), tonic::transport::Error>> + Send + 'static,
+{
+    tokio::spawn(async move {
+        if let Err(e) = server.await { error!(error = %e, "gRPC sunucusu durdu"); }
+        health.set_grpc_serving(false);
+    })
 }
 
-async fn rtp_session_handler(sock: Arc<UdpSocket>, active_sessions: ActiveSessions, port: u16, settings: Arc<Settings>) {
-    active_sessions.lock().unwrap().push(port);
-    info!(rtp_port = port, "Yeni RTP oturumu için dinleyici başlatıldı");
-
-    let mut remote_addr: Option<std::net::SocketAddr> = None;
-    let mut buf = [0u8; 2048];
-
-    loop {
-        if let Ok((_len, addr)) = sock.recv_from(&mut buf).await {
-            if remote_addr.is_none() {
-                info!(remote = %addr, rtp_port = port, "İlk RTP paketi alındı, ses gönderimi başlıyor...");
-                remote_addr = Some(addr);
-                
-                let sock_clone = Arc::clone(&sock);
-                tokio::spawn(send_welcome_announcement(sock_clone, addr, settings.clone()));
+/// Etkin HTTP uç noktalarını adrese göre gruplar; aynı adresi kullananlar tek dinleyiciyi paylaşır.
+fn http_listeners(settings: &Settings) -> Result<Vec<(SocketAddr, http::Routes)>, std::net::AddrParseError> {
+    let mut listeners: Vec<(SocketAddr, http::Routes)> = Vec::new();
+    let mut add = |bind: &str, set: fn(&mut http::Routes)| -> Result<(), std::net::AddrParseError> {
+        let addr: SocketAddr = bind.parse()?;
+        match listeners.iter_mut().find(|(existing, _)| *existing == addr) {
+            Some((_, routes)) => set(routes),
+            None => {
+                let mut routes = http::Routes::default();
+                set(&mut routes);
+                listeners.push((addr, routes));
             }
         }
+        Ok(())
+    };
+    if cfg!(feature = "metrics") && settings.metrics.enabled && settings.metrics.exporter == MetricsExporter::Prometheus {
+        add(&settings.metrics.bind, |routes| routes.metrics = true)?;
+    }
+    if settings.health.enabled {
+        add(&settings.health.bind, |routes| routes.health = true)?;
     }
+    Ok(listeners)
 }
 
-async fn send_welcome_announcement(sock: Arc<UdpSocket>, target_addr: std::net::SocketAddr, settings: Arc<Settings>) {
-    let file_path = &settings.announcement.welcome_file_path;
-    let reader = match hound::WavReader::open(file_path) {
-        Ok(r) => r,
-        Err(e) => { error!(file = %file_path, error = %e, "WAV dosyası açılamadı"); return; }
+/// SIGUSR1 alındığında DumpState'in belgesini `state_dump.directory`'ye yazar; gRPC'ye
+/// ulaşılamadığında durum buradan alınır.
+#[cfg(unix)]
+async fn dump_state_on_sigusr1(active_sessions: ActiveSessions, prompts: Arc<PromptLibrary>, settings: Arc<Settings>, health: Arc<Health>) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut usr1 = match signal(SignalKind::user_defined1()) {
+        Ok(s) => s,
+        Err(e) => { error!(error = %e, "SIGUSR1 dinleyicisi kurulamadı"); return; }
     };
-    
-    let spec = reader.spec();
-    if spec.channels != 1 || spec.sample_rate != 8000 || spec.bits_per_sample != 16 {
-        error!(file = %file_path, ?spec, "WAV dosyası formatı desteklenmiyor. Lütfen 16-bit, 8000Hz, Mono, PCM formatında kaydedin.");
-        return;
-    }
-    
-    let samples_per_packet = 160;
-    let mut interval = interval(Duration::from_millis(20));
-    let ssrc: u32 = rand::thread_rng().gen();
-    let mut sequence_number: u16 = rand::thread_rng().gen();
-    let mut timestamp: u32 = rand::thread_rng().gen();
-    let payload_type: u8 = 0; // PCMU
-
-    let samples: Vec<u8> = reader.into_samples::<i16>()
-        .map(|s| pcm16_to_g711_ulaw(s.unwrap()))
-        .collect();
-
-    info!(remote = %target_addr, file = %file_path, samples = samples.len(), "Anons gönderimi başlıyor...");
-
-    for chunk in samples.chunks(samples_per_packet) {
-        interval.tick().await;
-
-        let mut rtp_packet = Vec::with_capacity(12 + chunk.len());
-        rtp_packet.push(0x80);
-        rtp_packet.push(payload_type);
-        rtp_packet.extend_from_slice(&sequence_number.to_be_bytes());
-        rtp_packet.extend_from_slice(&timestamp.to_be_bytes());
-        rtp_packet.extend_from_slice(&ssrc.to_be_bytes());
-        rtp_packet.extend_from_slice(chunk);
-
-        if let Err(e) = sock.send_to(&rtp_packet, target_addr).await {
-            error!("RTP paketi gönderilemedi: {}", e);
-            break;
+    while usr1.recv().await.is_some() {
+        let dump = state::snapshot(&active_sessions, &prompts, &settings, Some(&health));
+        match state::write(settings.state_dump.directory.as_ref(), &dump) {
+            Ok(path) => info!(file = %path.display(), sessions = dump.sessions.len(), "SIGUSR1: durum dökümü yazıldı"),
+            Err(e) => warn!(directory = %settings.state_dump.directory, error = %e, "SIGUSR1: durum dökümü yazılamadı"),
         }
-        
-        sequence_number = sequence_number.wrapping_add(1);
-        timestamp = timestamp.wrapping_add(samples_per_packet as u32);
     }
-    info!(remote = %target_addr, file = %file_path, "Anons gönderimi tamamlandı.");
 }
 
-fn pcm16_to_g711_ulaw(sample: i16) -> u8 {
-    const BIAS: i16 = 0x84;
-    const CLIP: i16 = 32635;
-    let sign = (sample >> 8) & 0x80;
-    let mut val = sample.abs();
-    if val > CLIP { val = CLIP; }
-    val += BIAS;
-    let exponent = match val {
-        0..=0x00FF => 0, 0x0100..=0x01FF => 1, 0x0200..=0x03FF => 2,
-        0x0400..=0x07FF => 3, 0x0800..=0x0FFF => 4, 0x1000..=0x1FFF => 5,
-        0x2000..=0x3FFF => 6, _ => 7,
+/// SIGHUP alındığında config dosyasını yeniden okur, log seviyesini uygular ve yolu değişen
+/// anonsları yeniden doğrular. Diğer ayarlar için yeniden başlatma gerekir; geçersiz bir dosya
+/// /readyz'yi hazır değil yapar ve anonslara dokunulmaz.
+#[cfg(unix)]
+async fn reload_on_sighup(log_handle: logging::LogReloadHandle, health: Arc<Health>, prompts: Arc<PromptLibrary>) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hup = match signal(SignalKind::hangup()) {
+        Ok(s) => s,
+        Err(e) => { error!(error = %e, "SIGHUP dinleyicisi kurulamadı"); return; }
     };
-    let mantissa = (val >> (exponent + 3)) & 0x0F;
-    let ulaw = !(sign | (exponent << 4) | mantissa);
-    ulaw as u8
-}
\ No newline at end of file
+    while hup.recv().await.is_some() {
+        match Settings::read() {
+            Ok(settings) => {
+                let issues = settings.validate();
+                for issue in &issues {
+                    warn!(key = %issue.key, suggestion = %issue.suggestion, "SIGHUP: konfigürasyon hatası: {}", issue.message);
+                }
+                health.set_config_valid(issues.is_empty());
+                match logging::set_level(&log_handle, &settings.log.level) {
+                    Ok(()) => info!(level = %settings.log.level, "SIGHUP: log seviyesi güncellendi"),
+                    Err(e) => warn!(level = %settings.log.level, error = %e, "SIGHUP: log seviyesi uygulanamadı"),
+                }
+                if !issues.is_empty() {
+                    continue;
+                }
+                let (prompts, announcement) = (prompts.clone(), settings.announcement);
+                match tokio::task::spawn_blocking(move || prompts.reconfigure(&announcement)).await {
+                    Ok(reloaded) => {
+                        for (name, outcome) in reloaded {
+                            info!(prompt = %name, outcome = outcome.label(), reason = outcome.reason(), "SIGHUP: yolu değişen anons yeniden doğrulandı");
+                        }
+                    }
+                    Err(e) => warn!(error = %e, "SIGHUP: anonslar yeniden doğrulanamadı"),
+                }
+            }
+            Err(e) => {
+                health.set_config_valid(false);
+                warn!(error = %e, "SIGHUP: konfigürasyon okunamadı, log seviyesi değişmedi");
+            }
+        }
+    }
+}
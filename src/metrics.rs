@@ -0,0 +1,675 @@
+// Prometheus metrikleri. Sayaçlar paket yolundan kilitsiz atomiklerle güncellenir, hat hızı
+// ortalaması kısa bir kilitle; /metrics yolu (bkz. http.rs) `metrics` feature'ı arkasındadır.
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::audit::{AmdVerdict, FaxTone, OneWayDirection, RecordingUploadStatus, SendFailure};
+use crate::build_info;
+use crate::config::{MetricsConfig, TenantConfig};
+use crate::error::ParseError;
+use crate::labels::{LabelSeries, Labels};
+use crate::stats::Bitrate;
+
+#[derive(Debug)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    const fn new() -> Self { Self(AtomicU64::new(0)) }
+    pub fn inc(&self) { self.0.fetch_add(1, Ordering::Relaxed); }
+    pub fn add(&self, n: u64) { self.0.fetch_add(n, Ordering::Relaxed); }
+    pub fn get(&self) -> u64 { self.0.load(Ordering::Relaxed) }
+}
+
+#[derive(Debug)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    const fn new() -> Self { Self(AtomicI64::new(0)) }
+    pub fn inc(&self) { self.0.fetch_add(1, Ordering::Relaxed); }
+    pub fn dec(&self) { self.0.fetch_sub(1, Ordering::Relaxed); }
+    pub fn add(&self, v: i64) { self.0.fetch_add(v, Ordering::Relaxed); }
+    pub fn set(&self, v: i64) { self.0.store(v, Ordering::Relaxed); }
+    pub fn get(&self) -> i64 { self.0.load(Ordering::Relaxed) }
+}
+
+/// Sabit kova sınırlı histogram; toplam mikro saniye cinsinden tutulur.
+pub struct Histogram<const N: usize> {
+    bounds: [f64; N],
+    counts: [AtomicU64; N],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl<const N: usize> Histogram<N> {
+    const fn new(bounds: [f64; N]) -> Self {
+        Self { bounds, counts: [const { AtomicU64::new(0) }; N], count: AtomicU64::new(0), sum_micros: AtomicU64::new(0) }
+    }
+
+    pub fn observe(&self, value: Duration) {
+        let secs = value.as_secs_f64();
+        if let Some(i) = self.bounds.iter().position(|&bound| secs <= bound) {
+            self.counts[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(value.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn bounds(&self) -> &[f64; N] {
+        &self.bounds
+    }
+
+    /// Kova başına gözlem sayıları (kümülatif değil) ve toplam; son kovayı aşanlar yalnızca toplamda.
+    pub fn counts(&self) -> ([u64; N], u64) {
+        (std::array::from_fn(|i| self.counts[i].load(Ordering::Relaxed)), self.count.load(Ordering::Relaxed))
+    }
+
+    #[cfg(test)]
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} histogram", name, help, name);
+        self.render_series(out, name, None);
+    }
+
+    /// Başlık satırları olmadan tek bir seri; etiketli histogramlar için art arda çağrılır.
+    fn render_series(&self, out: &mut String, name: &str, label: Option<(&str, &str)>) {
+        let prefix = label.map(|(key, value)| format!("{}=\"{}\",", key, value)).unwrap_or_default();
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{{}le=\"{}\"}} {}", name, prefix, bound, cumulative);
+        }
+        let total = self.count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{{}le=\"+Inf\"}} {}", name, prefix, total);
+        let labels = label.map(|(key, value)| format!("{{{}=\"{}\"}}", key, value)).unwrap_or_default();
+        let _ = writeln!(out, "{}_sum{} {}", name, labels, self.sum_micros.load(Ordering::Relaxed) as f64 / 1e6);
+        let _ = writeln!(out, "{}_count{} {}", name, labels, total);
+    }
+}
+
+/// `media_allocation_failures_total` için `reason` etiketi.
+#[derive(Debug, Clone, Copy)]
+pub enum AllocationFailure {
+    Exhausted,
+    InvalidCodec,
+    /// Hız sınırına takıldı; port havuzuna hiç dokunulmadı.
+    RateLimited,
+    /// Kiracı bilinmiyor ya da jetonu eksik veya yanlış.
+    InvalidTenant,
+    /// Kiracının oturum sınırı doldu.
+    TenantLimit,
+    /// İstenen `[[interfaces]]` arayüzü tanımlı değil.
+    InvalidInterface,
+}
+
+impl AllocationFailure {
+    const ALL: [AllocationFailure; 6] = [
+        AllocationFailure::Exhausted, AllocationFailure::InvalidCodec, AllocationFailure::RateLimited,
+        AllocationFailure::InvalidTenant, AllocationFailure::TenantLimit, AllocationFailure::InvalidInterface,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            AllocationFailure::Exhausted => "exhausted",
+            AllocationFailure::InvalidCodec => "invalid_codec",
+            AllocationFailure::RateLimited => "rate_limited",
+            AllocationFailure::InvalidTenant => "invalid_tenant",
+            AllocationFailure::TenantLimit => "tenant_limit",
+            AllocationFailure::InvalidInterface => "invalid_interface",
+        }
+    }
+}
+
+/// Bir kiracının sayaçları; `tenant` etiketiyle yayınlanır.
+#[derive(Debug)]
+pub struct TenantMetrics {
+    pub name: &'static str,
+    pub active_sessions: Gauge,
+    pub allocations: Counter,
+    // Aralık tükendiği ya da oturum sınırı dolduğu için reddedilen tahsisler.
+    pub allocation_failures: Counter,
+    pub releases: Counter,
+    pub port_pool_size: Gauge,
+}
+
+/// İzin verilen bir oturum etiketi değerinin sayaçları; etiket adı anahtarın kendisidir.
+#[derive(Debug)]
+pub struct LabelMetrics {
+    pub key: &'static str,
+    pub value: &'static str,
+    pub active_sessions: Gauge,
+    pub sessions: Counter,
+    // Oturum kapanınca eklenir.
+    pub packets_received: Counter,
+    pub packets_lost: Counter,
+}
+
+impl LabelMetrics {
+    pub(crate) fn new(key: &'static str, value: &'static str) -> Self {
+        LabelMetrics { key, value, active_sessions: Gauge::new(), sessions: Counter::new(), packets_received: Counter::new(), packets_lost: Counter::new() }
+    }
+}
+
+/// `media_rtp_packets_malformed_total` için `reason` etiketi; `ParseError` varyantlarına karşılık gelir.
+#[derive(Debug, Clone, Copy)]
+pub enum MalformedPacket {
+    TooShort,
+    Version,
+    CsrcOverrun,
+    ExtensionOverrun,
+    Padding,
+}
+
+impl MalformedPacket {
+    const ALL: [MalformedPacket; 5] = [
+        MalformedPacket::TooShort, MalformedPacket::Version, MalformedPacket::CsrcOverrun,
+        MalformedPacket::ExtensionOverrun, MalformedPacket::Padding,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            MalformedPacket::TooShort => "too_short",
+            MalformedPacket::Version => "version",
+            MalformedPacket::CsrcOverrun => "csrc_overrun",
+            MalformedPacket::ExtensionOverrun => "extension_overrun",
+            MalformedPacket::Padding => "padding",
+        }
+    }
+}
+
+impl From<&ParseError> for MalformedPacket {
+    fn from(error: &ParseError) -> Self {
+        match error {
+            ParseError::TooShort { .. } => MalformedPacket::TooShort,
+            ParseError::Version { .. } => MalformedPacket::Version,
+            ParseError::CsrcOverrun { .. } => MalformedPacket::CsrcOverrun,
+            ParseError::ExtensionOverrun { .. } => MalformedPacket::ExtensionOverrun,
+            ParseError::Padding { .. } => MalformedPacket::Padding,
+        }
+    }
+}
+
+/// `media_hook_reports_total` ve `media_dtmf_hook_reports_total` için `outcome` etiketi.
+#[derive(Debug, Clone, Copy)]
+pub enum HookOutcome {
+    Delivered,
+    Failed,
+    /// Kuyruk doluydu.
+    Dropped,
+}
+
+impl HookOutcome {
+    const ALL: [HookOutcome; 3] = [HookOutcome::Delivered, HookOutcome::Failed, HookOutcome::Dropped];
+
+    fn label(self) -> &'static str {
+        match self {
+            HookOutcome::Delivered => "delivered",
+            HookOutcome::Failed => "failed",
+            HookOutcome::Dropped => "dropped",
+        }
+    }
+}
+
+/// `media_cdr_records_total` ve `media_port_journal_records_total` için `outcome` etiketi.
+#[derive(Debug, Clone, Copy)]
+pub enum RecordOutcome {
+    Written,
+    /// Dosyaya yazılamadı ya da döndürülemedi.
+    Failed,
+    /// Kuyruk doluydu.
+    Dropped,
+}
+
+impl RecordOutcome {
+    const ALL: [RecordOutcome; 3] = [RecordOutcome::Written, RecordOutcome::Failed, RecordOutcome::Dropped];
+
+    fn label(self) -> &'static str {
+        match self {
+            RecordOutcome::Written => "written",
+            RecordOutcome::Failed => "failed",
+            RecordOutcome::Dropped => "dropped",
+        }
+    }
+}
+
+/// `media_allocation_duration_seconds` için `outcome` etiketi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocationOutcome {
+    Success,
+    Exhausted,
+    Error,
+}
+
+impl AllocationOutcome {
+    const ALL: [AllocationOutcome; 3] = [AllocationOutcome::Success, AllocationOutcome::Exhausted, AllocationOutcome::Error];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AllocationOutcome::Success => "success",
+            AllocationOutcome::Exhausted => "exhausted",
+            AllocationOutcome::Error => "error",
+        }
+    }
+}
+
+const ALLOCATION_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
+
+pub struct Metrics {
+    pub active_sessions: Gauge,
+    pub allocations: Counter,
+    pub overflow_allocations: Counter,
+    pub overflow_sessions: Gauge,
+    pub releases: Counter,
+    pub sessions_exported: Counter,
+    pub sessions_imported: Counter,
+    allocation_failures: [Counter; AllocationFailure::ALL.len()],
+    pub rtp_packets_sent: Counter,
+    pub rtp_bytes_sent: Counter,
+    pub rtp_packets_received: Counter,
+    pub rtp_bytes_received: Counter,
+    rtp_packets_malformed: [Counter; MalformedPacket::ALL.len()],
+    // Bütün oturumların hat hızı; kayan ortalama doğrusal olduğundan oturum hızlarının toplamıdır.
+    send_bitrate: Mutex<Bitrate>,
+    receive_bitrate: Mutex<Bitrate>,
+    pub rtp_packets_flood_dropped: Counter,
+    pub rtp_packets_reflected: Counter,
+    pub rtp_recv_calls: Counter,
+    pub red_recovered: Counter,
+    pub bridge_packets_relayed: Counter,
+    pub dtmf_events_relayed: Counter,
+    pub taps_active: Gauge,
+    pub tap_packets_sent: Counter,
+    pub frames_suppressed: Counter,
+    pub asr_frames_dropped: Counter,
+    pub asr_reconnects: Counter,
+    pub asr_transcripts: Counter,
+    pub announcements_started: Counter,
+    pub announcements_completed: Counter,
+    pub announcements_failed: Counter,
+    pub playbacks_rejected: Counter,
+    pub welcomes_skipped: Counter,
+    pub admission_degraded: Gauge,
+    pub announcement_cache_bytes: Gauge,
+    pub announcement_cache_entries: Gauge,
+    pub announcement_cache_hits: Counter,
+    pub announcement_cache_misses: Counter,
+    pub announcement_cache_evictions: Counter,
+    hook_reports: [Counter; HookOutcome::ALL.len()],
+    dtmf_hook_reports: [Counter; HookOutcome::ALL.len()],
+    send_errors: [Counter; SendFailure::ALL.len()],
+    one_way_audio: [Counter; OneWayDirection::ALL.len()],
+    pub one_way_audio_sessions: Gauge,
+    recording_uploads: [Counter; RecordingUploadStatus::ALL.len()],
+    cdr_records: [Counter; RecordOutcome::ALL.len()],
+    port_journal_records: [Counter; RecordOutcome::ALL.len()],
+    fax_tones: [Counter; FaxTone::ALL.len()],
+    amd_results: [Counter; AmdVerdict::ALL.len()],
+    pub send_loop_lag: Histogram<10>,
+    allocation_duration: [Histogram<10>; AllocationOutcome::ALL.len()],
+    pub port_pool_size: Gauge,
+    // Config'deki sırayla; kiracılar ilk kullanımda eklenir ve hiç silinmez.
+    tenants: Mutex<Vec<Arc<TenantMetrics>>>,
+    // `metrics.session_labels` izin listesi ve değer serileri.
+    session_labels: Mutex<LabelSeries>,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            active_sessions: Gauge::new(),
+            allocations: Counter::new(),
+            overflow_allocations: Counter::new(),
+            overflow_sessions: Gauge::new(),
+            releases: Counter::new(),
+            sessions_exported: Counter::new(),
+            sessions_imported: Counter::new(),
+            allocation_failures: [const { Counter::new() }; AllocationFailure::ALL.len()],
+            rtp_packets_sent: Counter::new(),
+            rtp_bytes_sent: Counter::new(),
+            rtp_packets_received: Counter::new(),
+            rtp_bytes_received: Counter::new(),
+            rtp_packets_malformed: [const { Counter::new() }; MalformedPacket::ALL.len()],
+            send_bitrate: Mutex::new(Bitrate::new()),
+            receive_bitrate: Mutex::new(Bitrate::new()),
+            rtp_packets_flood_dropped: Counter::new(),
+            rtp_packets_reflected: Counter::new(),
+            rtp_recv_calls: Counter::new(),
+            red_recovered: Counter::new(),
+            bridge_packets_relayed: Counter::new(),
+            dtmf_events_relayed: Counter::new(),
+            taps_active: Gauge::new(),
+            tap_packets_sent: Counter::new(),
+            frames_suppressed: Counter::new(),
+            asr_frames_dropped: Counter::new(),
+            asr_reconnects: Counter::new(),
+            asr_transcripts: Counter::new(),
+            announcements_started: Counter::new(),
+            announcements_completed: Counter::new(),
+            announcements_failed: Counter::new(),
+            playbacks_rejected: Counter::new(),
+            welcomes_skipped: Counter::new(),
+            admission_degraded: Gauge::new(),
+            announcement_cache_bytes: Gauge::new(),
+            announcement_cache_entries: Gauge::new(),
+            announcement_cache_hits: Counter::new(),
+            announcement_cache_misses: Counter::new(),
+            announcement_cache_evictions: Counter::new(),
+            hook_reports: [const { Counter::new() }; HookOutcome::ALL.len()],
+            dtmf_hook_reports: [const { Counter::new() }; HookOutcome::ALL.len()],
+            send_errors: [const { Counter::new() }; SendFailure::ALL.len()],
+            one_way_audio: [const { Counter::new() }; OneWayDirection::ALL.len()],
+            one_way_audio_sessions: Gauge::new(),
+            recording_uploads: [const { Counter::new() }; RecordingUploadStatus::ALL.len()],
+            cdr_records: [const { Counter::new() }; RecordOutcome::ALL.len()],
+            port_journal_records: [const { Counter::new() }; RecordOutcome::ALL.len()],
+            fax_tones: [const { Counter::new() }; FaxTone::ALL.len()],
+            amd_results: [const { Counter::new() }; AmdVerdict::ALL.len()],
+            send_loop_lag: Histogram::new([0.0005, 0.001, 0.002, 0.005, 0.01, 0.02, 0.05, 0.1, 0.5, 1.0]),
+            allocation_duration: [const { Histogram::new(ALLOCATION_BUCKETS) }; AllocationOutcome::ALL.len()],
+            port_pool_size: Gauge::new(),
+            tenants: Mutex::new(Vec::new()),
+            session_labels: Mutex::new(LabelSeries::new()),
+        }
+    }
+
+    /// Config'deki kiracıları sıfır değerlerle kaydeder; OTLP enstrümanları kurulmadan önce çağrılmalı.
+    pub fn register_tenants(&self, tenants: &[TenantConfig]) {
+        for tenant in tenants {
+            self.tenant(&tenant.name).port_pool_size.set((tenant.max_port as i64 - tenant.min_port as i64) + 1);
+        }
+    }
+
+    /// `name` kiracısının sayaçları; yoksa oluşturulur.
+    pub fn tenant(&self, name: &str) -> Arc<TenantMetrics> {
+        let mut tenants = self.tenants.lock().unwrap();
+        if let Some(tenant) = tenants.iter().find(|t| t.name == name) {
+            return tenant.clone();
+        }
+        // Etiket değerleri 'static; kiracı adları config'den gelir ve sayıları sınırlıdır.
+        let tenant = Arc::new(TenantMetrics {
+            name: Box::leak(name.to_string().into_boxed_str()), active_sessions: Gauge::new(), allocations: Counter::new(),
+            allocation_failures: Counter::new(), releases: Counter::new(), port_pool_size: Gauge::new(),
+        });
+        tenants.push(tenant.clone());
+        tenant
+    }
+
+    /// Prometheus etiketi olacak oturum etiketlerini kurar; listede olmayan anahtarlar yayınlanmaz.
+    pub fn configure_session_labels(&self, config: &MetricsConfig) {
+        self.session_labels.lock().unwrap().configure(&config.session_labels, config.session_label_values);
+    }
+
+    /// Oturum etiketlerinin izin verilen serileri; yoksa oluşturulur.
+    pub fn session_labels(&self, labels: &Labels) -> Vec<Arc<LabelMetrics>> {
+        self.session_labels.lock().unwrap().resolve(labels)
+    }
+
+    pub fn allocation_failed(&self, reason: AllocationFailure) {
+        self.allocation_failures[reason as usize].inc();
+    }
+
+    pub fn hook_report(&self, outcome: HookOutcome) {
+        self.hook_reports[outcome as usize].inc();
+    }
+
+    pub fn dtmf_hook_report(&self, outcome: HookOutcome) {
+        self.dtmf_hook_reports[outcome as usize].inc();
+    }
+
+    pub fn send_failed(&self, failure: SendFailure) {
+        self.send_errors[failure as usize].inc();
+    }
+
+    pub fn one_way_audio_suspected(&self, direction: OneWayDirection) {
+        self.one_way_audio[direction as usize].inc();
+        self.one_way_audio_sessions.inc();
+    }
+
+    pub fn fax_tone_detected(&self, tone: FaxTone) {
+        self.fax_tones[tone as usize].inc();
+    }
+
+    pub fn amd_result(&self, verdict: AmdVerdict) {
+        self.amd_results[verdict as usize].inc();
+    }
+
+    pub fn recording_upload(&self, status: RecordingUploadStatus) {
+        self.recording_uploads[status as usize].inc();
+    }
+
+    pub fn cdr_record(&self, outcome: RecordOutcome) {
+        self.cdr_records[outcome as usize].inc();
+    }
+
+    pub fn port_journal_record(&self, outcome: RecordOutcome) {
+        self.port_journal_records[outcome as usize].inc();
+    }
+
+    pub fn allocation_duration(&self, outcome: AllocationOutcome) -> &Histogram<10> {
+        &self.allocation_duration[outcome as usize]
+    }
+
+    pub fn packet_sent(&self, bytes: usize, wire_bytes: usize, now: Instant) {
+        self.rtp_packets_sent.inc();
+        self.rtp_bytes_sent.add(bytes as u64);
+        self.send_bitrate.lock().unwrap().record(wire_bytes, now);
+    }
+
+    pub fn packet_received(&self, bytes: usize, wire_bytes: usize, now: Instant) {
+        self.rtp_packets_received.inc();
+        self.rtp_bytes_received.add(bytes as u64);
+        self.receive_bitrate.lock().unwrap().record(wire_bytes, now);
+    }
+
+    pub fn packet_malformed(&self, error: &ParseError) {
+        self.rtp_packets_malformed[MalformedPacket::from(error) as usize].inc();
+    }
+
+    /// Bütün sayaç ve göstergelerin anlık değerleri. Prometheus ve OTLP ihracı aynı listeyi kullanır;
+    /// etiketli metrikler aynı isimle art arda gelir.
+    pub fn samples(&self) -> Vec<Sample> {
+        let mut samples = vec![
+            Sample::gauge("media_active_sessions", "Aktif RTP oturumu sayısı", self.active_sessions.get() as f64),
+            Sample::counter("media_allocations_total", "Başarılı port tahsisleri", self.allocations.get()),
+            Sample::counter("media_allocations_overflow_total", "Havuz tükendiği için RTP aralığının dışından yapılan tahsisler", self.overflow_allocations.get()),
+            Sample::gauge("media_overflow_sessions", "Taşma portundaki aktif oturumlar", self.overflow_sessions.get() as f64),
+            Sample::counter("media_releases_total", "Sonlanan oturumlar", self.releases.get()),
+            Sample::counter("media_sessions_exported_total", "Başka bir node'a taşınmak üzere dışa aktarılan oturumlar", self.sessions_exported.get()),
+            Sample::counter("media_sessions_imported_total", "Başka bir node'dan içe aktarılıp yeni portta kurulan oturumlar", self.sessions_imported.get()),
+        ];
+        for reason in AllocationFailure::ALL {
+            let failures = Sample::counter("media_allocation_failures_total", "Başarısız port tahsisleri", self.allocation_failures[reason as usize].get());
+            samples.push(Sample { label: Some(("reason", reason.label())), ..failures });
+        }
+        let pool_size = self.port_pool_size.get();
+        let utilization = if pool_size > 0 { self.active_sessions.get() as f64 / pool_size as f64 } else { 0.0 };
+        let (cache_hits, cache_misses) = (self.announcement_cache_hits.get(), self.announcement_cache_misses.get());
+        let cache_hit_ratio = if cache_hits + cache_misses > 0 { cache_hits as f64 / (cache_hits + cache_misses) as f64 } else { 0.0 };
+        samples.extend([
+            Sample::counter("media_rtp_packets_sent_total", "Gönderilen RTP paketleri", self.rtp_packets_sent.get()),
+            Sample::counter("media_rtp_bytes_sent_total", "Gönderilen RTP baytları", self.rtp_bytes_sent.get()),
+            Sample::counter("media_rtp_packets_received_total", "Alınan RTP paketleri", self.rtp_packets_received.get()),
+            Sample::counter("media_rtp_bytes_received_total", "Alınan RTP baytları", self.rtp_bytes_received.get()),
+            Sample::gauge("media_send_bitrate_bps", "Gönderim hat hızı, IP/UDP başlıkları dahil (5 sn kayan ortalama)", self.send_bitrate.lock().unwrap().current_bps(Instant::now())),
+            Sample::gauge("media_receive_bitrate_bps", "Alım hat hızı, IP/UDP başlıkları dahil (5 sn kayan ortalama)", self.receive_bitrate.lock().unwrap().current_bps(Instant::now())),
+        ]);
+        for reason in MalformedPacket::ALL {
+            let malformed = Sample::counter("media_rtp_packets_malformed_total", "Ayrıştırılamayan gelen RTP paketleri", self.rtp_packets_malformed[reason as usize].get());
+            samples.push(Sample { label: Some(("reason", reason.label())), ..malformed });
+        }
+        samples.extend([
+            Sample::counter("media_rtp_packets_flood_dropped_total", "Gelen paket sınırını aştığı için ayrıştırılmadan atılan paketler", self.rtp_packets_flood_dropped.get()),
+            Sample::counter("media_rtp_packets_reflected_total", "Geri yansıyan kendi giden paketlerimiz; atılır", self.rtp_packets_reflected.get()),
+            Sample::counter("media_rtp_recv_calls_total", "Gelen paketler için yapılan okuma çağrıları; recvmmsg ile bir çağrı birden çok paket okur", self.rtp_recv_calls.get()),
+            Sample::counter("media_red_recovered_total", "RED yedeğinden kurtarılan kayıp çerçeveler", self.red_recovered.get()),
+            Sample::counter("media_bridge_packets_relayed_total", "Köprünün karşı bacağına aktarılan paketler", self.bridge_packets_relayed.get()),
+            Sample::counter("media_dtmf_events_relayed_total", "Köprünün karşı bacağına aktarılan RFC 4733 tuşları", self.dtmf_events_relayed.get()),
+            Sample::gauge("media_taps_active", "Bir oturumu dinleyen dinleme bacakları", self.taps_active.get() as f64),
+            Sample::counter("media_tap_packets_sent_total", "Dinleme bacaklarına gönderilen karışım paketleri", self.tap_packets_sent.get()),
+            Sample::counter("media_frames_suppressed_total", "Sessizlik bastırmayla ses yerine CN gönderilen ya da hiç gönderilmeyen çerçeveler", self.frames_suppressed.get()),
+            Sample::counter("media_asr_frames_dropped_total", "ASR tamponu dolduğu için tanıma servisine gönderilmeden düşen ses çerçeveleri", self.asr_frames_dropped.get()),
+            Sample::counter("media_asr_reconnects_total", "Kopan ya da kurulamayan ASR akışlarının yeniden denenmesi", self.asr_reconnects.get()),
+            Sample::counter("media_asr_transcripts_total", "Tanıma servisinden alınan ara ve kesin sonuçlar", self.asr_transcripts.get()),
+            Sample::counter("media_announcements_started_total", "Başlayan anonslar", self.announcements_started.get()),
+            Sample::counter("media_announcements_completed_total", "Tamamlanan anonslar", self.announcements_completed.get()),
+            Sample::counter("media_announcements_failed_total", "Başarısız anonslar", self.announcements_failed.get()),
+            Sample::counter("media_playbacks_rejected_total", "Node aşırı yüklü olduğu için reddedilen oynatma istekleri", self.playbacks_rejected.get()),
+            Sample::counter("media_welcomes_skipped_total", "Node aşırı yüklü olduğu için çalınmayan karşılama anonsları", self.welcomes_skipped.get()),
+            Sample::gauge("media_admission_degraded", "Node aşırı yüklü sayılıyor ve yeni oynatmaları reddediyor (1) ya da normal (0)", self.admission_degraded.get() as f64),
+            Sample::gauge("media_announcement_cache_bytes", "Anons önbelleğindeki çözülmüş sesin boyutu (bayt)", self.announcement_cache_bytes.get() as f64),
+            Sample::gauge("media_announcement_cache_entries", "Anons önbelleğindeki dosyalar", self.announcement_cache_entries.get() as f64),
+            Sample::counter("media_announcement_cache_hits_total", "Önbellekten çalınan anonslar", cache_hits),
+            Sample::counter("media_announcement_cache_misses_total", "Önbellekte bulunamayan anonslar", cache_misses),
+            Sample::counter("media_announcement_cache_evictions_total", "Bütçe aşıldığı için önbellekten atılan anonslar", self.announcement_cache_evictions.get()),
+            Sample::gauge("media_announcement_cache_hit_ratio", "Önbellekten çalınan anonsların oranı", cache_hit_ratio),
+        ]);
+        for outcome in HookOutcome::ALL {
+            let reports = Sample::counter("media_hook_reports_total", "Oturum kapanış kancası bildirimleri", self.hook_reports[outcome as usize].get());
+            samples.push(Sample { label: Some(("outcome", outcome.label())), ..reports });
+        }
+        for failure in SendFailure::ALL {
+            let errors = Sample::counter("media_send_errors_total", "Yeniden denemelerden sonra başarısız kalan soket gönderimleri, hata sınıfına göre", self.send_errors[failure as usize].get());
+            samples.push(Sample { label: Some(("class", failure.as_str())), ..errors });
+        }
+        for direction in OneWayDirection::ALL {
+            let suspected = Sample::counter("media_one_way_audio_suspected_total", "Tek yönlü ses şüphesi yazılan oturumlar, kesilen yöne göre", self.one_way_audio[direction as usize].get());
+            samples.push(Sample { label: Some(("direction", direction.as_str())), ..suspected });
+        }
+        samples.push(Sample::gauge("media_one_way_audio_sessions", "Şu anda tek yönlü ses şüphesi süren oturumlar", self.one_way_audio_sessions.get() as f64));
+        for outcome in HookOutcome::ALL {
+            let reports = Sample::counter("media_dtmf_hook_reports_total", "DTMF tuş bildirimleri", self.dtmf_hook_reports[outcome as usize].get());
+            samples.push(Sample { label: Some(("outcome", outcome.label())), ..reports });
+        }
+        for status in RecordingUploadStatus::ALL {
+            let uploads = Sample::counter("media_recording_uploads_total", "Nesne deposuna kayıt yüklemeleri", self.recording_uploads[status as usize].get());
+            samples.push(Sample { label: Some(("outcome", status.as_str())), ..uploads });
+        }
+        for tone in FaxTone::ALL {
+            let tones = Sample::counter("media_fax_tones_detected_total", "Gelen seste algılanan faks tonları", self.fax_tones[tone as usize].get());
+            samples.push(Sample { label: Some(("tone", tone.as_str())), ..tones });
+        }
+        for verdict in AmdVerdict::ALL {
+            let results = Sample::counter("media_amd_results_total", "Telesekreter algılama sonuçları", self.amd_results[verdict as usize].get());
+            samples.push(Sample { label: Some(("result", verdict.as_str())), ..results });
+        }
+        for outcome in RecordOutcome::ALL {
+            let records = Sample::counter("media_cdr_records_total", "Oturum sonu kayıtları (CDR)", self.cdr_records[outcome as usize].get());
+            samples.push(Sample { label: Some(("outcome", outcome.label())), ..records });
+        }
+        for outcome in RecordOutcome::ALL {
+            let records = Sample::counter("media_port_journal_records_total", "Port günlüğü kayıtları (tahsis, kilitlenme, bırakma)", self.port_journal_records[outcome as usize].get());
+            samples.push(Sample { label: Some(("outcome", outcome.label())), ..records });
+        }
+        samples.extend([
+            Sample::gauge("media_port_pool_size", "RTP port havuzundaki port sayısı", pool_size as f64),
+            Sample::gauge("media_port_pool_utilization", "Kullanılan port oranı", utilization),
+        ]);
+        let tenants = self.tenants.lock().unwrap();
+        // Her metriğin kiracı serileri art arda gelir.
+        let per_tenant = |sample: Sample, value: fn(&TenantMetrics) -> f64| {
+            tenants.iter().map(move |t| Sample { label: Some(("tenant", t.name)), value: value(t), ..sample }).collect::<Vec<_>>()
+        };
+        samples.extend(per_tenant(Sample::gauge("media_tenant_active_sessions", "Kiracının aktif oturumları", 0.0), |t| t.active_sessions.get() as f64));
+        samples.extend(per_tenant(Sample::counter("media_tenant_allocations_total", "Kiracının başarılı port tahsisleri", 0), |t| t.allocations.get() as f64));
+        samples.extend(per_tenant(
+            Sample::counter("media_tenant_allocation_failures_total", "Kiracının aralığı tükendiği ya da oturum sınırı dolduğu için reddedilen tahsisler", 0),
+            |t| t.allocation_failures.get() as f64,
+        ));
+        samples.extend(per_tenant(Sample::counter("media_tenant_releases_total", "Kiracının sonlanan oturumları", 0), |t| t.releases.get() as f64));
+        samples.extend(per_tenant(Sample::gauge("media_tenant_port_pool_size", "Kiracının port aralığındaki port sayısı", 0.0), |t| t.port_pool_size.get() as f64));
+        let session_labels = self.session_labels.lock().unwrap();
+        // Etiket adı serinin anahtarıdır; aynı metrikte farklı anahtarların serileri art arda gelir.
+        let per_label = |sample: Sample, value: fn(&LabelMetrics) -> f64| {
+            session_labels.all().iter().map(move |l| Sample { label: Some((l.key, l.value)), value: value(l), ..sample }).collect::<Vec<_>>()
+        };
+        samples.extend(per_label(Sample::gauge("media_session_label_active_sessions", "Etiket değerini taşıyan aktif oturumlar", 0.0), |l| l.active_sessions.get() as f64));
+        samples.extend(per_label(Sample::counter("media_session_label_sessions_total", "Etiket değeriyle açılan oturumlar", 0), |l| l.sessions.get() as f64));
+        samples.extend(per_label(
+            Sample::counter("media_session_label_packets_received_total", "Etiket değerini taşıyan biten oturumların aldığı RTP paketleri", 0),
+            |l| l.packets_received.get() as f64,
+        ));
+        samples.extend(per_label(
+            Sample::counter("media_session_label_packets_lost_total", "Etiket değerini taşıyan biten oturumlarda kaybolan RTP paketleri", 0),
+            |l| l.packets_lost.get() as f64,
+        ));
+        samples
+    }
+
+    /// Prometheus metin formatı (0.0.4).
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# HELP media_build_info Çalışan derleme; değer her zaman 1\n# TYPE media_build_info gauge\n\
+             media_build_info{{version=\"{}\",git_commit=\"{}\",git_dirty=\"{}\",build_timestamp=\"{}\",features=\"{}\",schema_version=\"{}\"}} 1",
+            build_info::VERSION, build_info::GIT_COMMIT, build_info::GIT_DIRTY, build_info::BUILD_TIMESTAMP, build_info::FEATURES.join(","), build_info::SCHEMA_VERSION,
+        );
+        let mut previous = "";
+        for sample in self.samples() {
+            if sample.name != previous {
+                let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", sample.name, sample.help, sample.name, sample.kind.as_str());
+                previous = sample.name;
+            }
+            match sample.label {
+                // Oturum etiketlerinin değerleri istemciden gelir; tırnak ve ters bölü kaçırılır.
+                Some((key, value)) => { let _ = writeln!(out, "{}{{{}=\"{}\"}} {}", sample.name, key, value.replace('\\', "\\\\").replace('"', "\\\""), sample.value); }
+                None => { let _ = writeln!(out, "{} {}", sample.name, sample.value); }
+            }
+        }
+        self.send_loop_lag.render(&mut out, "media_send_loop_lag_seconds", "Gönderim döngüsünün planlanan zamandan gecikmesi");
+
+        let name = "media_allocation_duration_seconds";
+        let _ = writeln!(out, "# HELP {} AllocatePort süresi (port arama dahil)\n# TYPE {} histogram", name, name);
+        for outcome in AllocationOutcome::ALL {
+            self.allocation_duration(outcome).render_series(&mut out, name, Some(("outcome", outcome.label())));
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Counter,
+    Gauge,
+}
+
+impl Kind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Kind::Counter => "counter",
+            Kind::Gauge => "gauge",
+        }
+    }
+}
+
+/// Tek bir metrik değeri; `label` en fazla bir etiket taşır.
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: Kind,
+    pub label: Option<(&'static str, &'static str)>,
+    pub value: f64,
+}
+
+impl Sample {
+    fn counter(name: &'static str, help: &'static str, value: u64) -> Self {
+        Self { name, help, kind: Kind::Counter, label: None, value: value as f64 }
+    }
+
+    fn gauge(name: &'static str, help: &'static str, value: f64) -> Self {
+        Self { name, help, kind: Kind::Gauge, label: None, value }
+    }
+}
+
+static METRICS: Metrics = Metrics::new();
+
+pub fn get() -> &'static Metrics {
+    &METRICS
+}
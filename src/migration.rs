@@ -0,0 +1,150 @@
+// Oturumların başka bir node'a yeniden bağlanması: bakım için boşaltılan node'un ExportSessions'ı
+// seçilen oturumların sinyalleşmeyi ilgilendiren durumunu sürümlü bir JSON belgesine yazar, başka
+// bir node'un ImportSessions'ı belgedeki her oturumu yeni bir portta aynı codec, yük tipleri ve
+// giden akışla (SSRC, sıra numarası, zaman damgası) kurar. Soketler taşınamaz; bu şeffaf bir devir
+// değil, yeniden bağlamadır: sinyalleşme uzak uçları yeni porta re-INVITE edene kadar medya eski
+// node'dan akar. Kayıtlar, oynatmalar ve köprüler taşınmaz.
+use std::net::SocketAddr;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::build_info;
+use crate::cdr;
+use crate::error::MigrationError;
+use crate::labels::Labels;
+use crate::session::RtpSession;
+
+/// Belgenin biçim sürümü; alanların anlamı değişirse artırılır. Okuyucu `OLDEST_FORMAT_VERSION`'dan
+/// bu yana yazılmış belgeleri kabul eder; eksik alanlar o sürümdeki davranışla doldurulur.
+/// 2: `rtcp_mux` eklendi; 1'de RTCP her zaman RTP portundan geçiyordu.
+pub const FORMAT_VERSION: u32 = 2;
+pub const OLDEST_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Document {
+    version: u32,
+    exported_at_ms: u64,
+    // Belgeyi yazan node'un sürümü; yalnızca hata ayıklama için.
+    node_version: String,
+    sessions: Vec<SessionExport>,
+}
+
+// Sürüm, belgenin geri kalanı okunmadan önce denetlenir.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+/// Bir oturumun taşınan durumu.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionExport {
+    pub port: u16,
+    pub session_id: String,
+    pub call_id: String,
+    pub codec: String,
+    pub red_payload_type: Option<u8>,
+    pub dtmf_payload_type: Option<u8>,
+    pub audio_level_id: Option<u8>,
+    /// Kilitlenen ya da tahsiste verilen uzak adres; henüz yoksa yok.
+    pub remote_address: Option<SocketAddr>,
+    pub ssrc: u32,
+    /// Giden akışın bir sonraki paketinin sıra numarası ve zaman damgası.
+    pub next_sequence: u16,
+    pub next_timestamp: u32,
+    pub language: Option<String>,
+    pub labels: Labels,
+    /// RTCP RTP portundan geçiyordu; içe aktarılan oturum da ayrı RTCP portu almaz. 1. sürüm
+    /// belgelerinde yoktur ve o sürümde RTCP hep RTP portundan geçtiği için `true` sayılır.
+    #[serde(default = "rtcp_mux_before_v2")]
+    pub rtcp_mux: bool,
+}
+
+fn rtcp_mux_before_v2() -> bool {
+    true
+}
+
+impl SessionExport {
+    /// Oturumun `at` anındaki durumu. Oturum bu node'da sürdüğü sürece akışı ilerlemeye devam eder.
+    pub fn of(session: &RtpSession, at: Instant) -> Self {
+        let (next_sequence, next_timestamp) = session.stream.resume_point(at);
+        SessionExport {
+            port: session.port,
+            session_id: session.session_id.clone(),
+            call_id: session.call_id.clone(),
+            codec: session.codec().name().to_string(),
+            red_payload_type: session.red.map(|red| red.payload_type),
+            dtmf_payload_type: session.dtmf_payload_type(),
+            audio_level_id: session.audio_level_id,
+            remote_address: *session.remote_addr.lock().unwrap(),
+            ssrc: session.stream.ssrc,
+            next_sequence,
+            next_timestamp,
+            language: session.language.clone(),
+            labels: session.labels.clone(),
+            rtcp_mux: session.rtcp_port().is_none(),
+        }
+    }
+}
+
+/// ImportSessions'a aynen verilecek belge.
+pub fn encode(sessions: Vec<SessionExport>) -> Vec<u8> {
+    let document = Document { version: FORMAT_VERSION, exported_at_ms: cdr::unix_millis(std::time::SystemTime::now()), node_version: build_info::VERSION.to_string(), sessions };
+    serde_json::to_vec(&document).expect("migration document serializes")
+}
+
+/// Belgedeki oturumlar, dışa aktarıldıkları sırayla.
+pub fn decode(state: &[u8]) -> Result<Vec<SessionExport>, MigrationError> {
+    let invalid = |e: serde_json::Error| MigrationError::InvalidState { reason: e.to_string() };
+    let header: Header = serde_json::from_slice(state).map_err(invalid)?;
+    if !(OLDEST_FORMAT_VERSION..=FORMAT_VERSION).contains(&header.version) {
+        return Err(MigrationError::UnsupportedVersion { version: header.version, oldest: OLDEST_FORMAT_VERSION, supported: FORMAT_VERSION });
+    }
+    let document: Document = serde_json::from_slice(state).map_err(invalid)?;
+    Ok(document.sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exported() -> SessionExport {
+        SessionExport {
+            port: 31000, session_id: "00000000000000ab".to_string(), call_id: "call-1".to_string(), codec: "pcmu".to_string(),
+            red_payload_type: None, dtmf_payload_type: Some(101), audio_level_id: None, remote_address: Some("192.0.2.10:4000".parse().unwrap()),
+            ssrc: 0x1234_5678, next_sequence: 65535, next_timestamp: 160, language: Some("tr".to_string()),
+            labels: Labels::from([("queue".to_string(), "sales".to_string())]), rtcp_mux: false,
+        }
+    }
+
+    #[test]
+    fn documents_round_trip_and_other_versions_are_refused() {
+        let state = encode(vec![exported()]);
+        assert_eq!(decode(&state).unwrap(), [exported()]);
+
+        let mut document: serde_json::Value = serde_json::from_slice(&state).unwrap();
+        document["version"] = (FORMAT_VERSION + 1).into();
+        assert!(matches!(decode(&serde_json::to_vec(&document).unwrap()), Err(MigrationError::UnsupportedVersion { version, .. }) if version == FORMAT_VERSION + 1));
+        assert!(matches!(decode(b"{\"version\": 1, \"sessions\": 3}"), Err(MigrationError::InvalidState { .. })));
+        assert!(matches!(decode(b"not json"), Err(MigrationError::InvalidState { .. })));
+        assert!(matches!(decode(b"{\"version\": 0, \"sessions\": []}"), Err(MigrationError::UnsupportedVersion { version: 0, .. })));
+    }
+
+    #[test]
+    fn version_1_documents_import_with_rtcp_on_the_rtp_port() {
+        // rtcp_mux eklenmeden önce yazılmış bir belge.
+        let state = br#"{"version": 1, "exported_at_ms": 1760000000000, "node_version": "0.9.0", "sessions": [{
+            "port": 31000, "session_id": "00000000000000ab", "call_id": "call-1", "codec": "pcmu",
+            "red_payload_type": null, "dtmf_payload_type": 101, "audio_level_id": null, "remote_address": "192.0.2.10:4000",
+            "ssrc": 305419896, "next_sequence": 65535, "next_timestamp": 160, "language": "tr", "labels": {"queue": "sales"}
+        }]}"#;
+        let sessions = decode(state).unwrap();
+        assert_eq!(sessions, [SessionExport { rtcp_mux: true, ..exported() }]);
+
+        // Yeniden dışa aktarılınca yeni sürümle ve aynı değerle yazılır.
+        let state = encode(sessions);
+        let document: serde_json::Value = serde_json::from_slice(&state).unwrap();
+        assert_eq!((document["version"].as_u64(), document["sessions"][0]["rtcp_mux"].as_bool()), (Some(FORMAT_VERSION as u64), Some(true)));
+        assert_eq!(decode(&state).unwrap(), [SessionExport { rtcp_mux: true, ..exported() }]);
+    }
+}
@@ -0,0 +1,148 @@
+// Konferans karışımı: katılımcıların çerçevelerini toplayıp tek akış olarak verir ve o çerçevede
+// konuşanların SSRC'lerini giden paketlerin CSRC listesine taşır (RFC 3550 6.1). Kayıt ve
+// izleme araçları böylece her pakette kimin sesinin olduğunu görür.
+use crate::rtp::MAX_CSRCS;
+use crate::source::{AudioSource, FrameFuture};
+
+/// Bir katılımcının çerçevesi bu RMS seviyesinin (yaklaşık -50 dBov) üstündeyse katkıda bulunan
+/// sayılır; arka plan gürültüsü ve konfor gürültüsü CSRC listesine girmez.
+pub const ACTIVE_RMS: f64 = 100.0;
+
+struct Member {
+    ssrc: u32,
+    source: Box<dyn AudioSource>,
+    frame: Vec<i16>,
+}
+
+/// Katılımcı kaynaklarını örnek örnek toplayan kaynak. Biten katılımcı çıkarılır; hepsi bitince
+/// karışım da biter.
+#[derive(Default)]
+pub struct Mixer {
+    members: Vec<Member>,
+    contributors: Vec<u32>,
+    // Çerçeve başına toplam ve (seviye, ssrc) listesi; ayırmalar tekrar kullanılsın diye alanda.
+    sum: Vec<i32>,
+    active: Vec<(f64, u32)>,
+}
+
+impl Mixer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Katılımcı ekler; `ssrc` katılımcının gelen akışının SSRC'sidir.
+    pub fn add(&mut self, ssrc: u32, source: Box<dyn AudioSource>) {
+        self.members.push(Member { ssrc, source, frame: Vec::new() });
+    }
+
+    pub fn remove(&mut self, ssrc: u32) {
+        self.members.retain(|member| member.ssrc != ssrc);
+    }
+}
+
+impl AudioSource for Mixer {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            frame.clear();
+            self.active.clear();
+            self.sum.clear();
+            self.sum.resize(samples, 0);
+            let mut index = 0;
+            while index < self.members.len() {
+                let member = &mut self.members[index];
+                if !member.source.next_frame(samples, &mut member.frame).await? {
+                    self.members.swap_remove(index);
+                    continue;
+                }
+                let mut energy = 0.0;
+                for (total, &sample) in self.sum.iter_mut().zip(&member.frame) {
+                    *total += sample as i32;
+                    energy += sample as f64 * sample as f64;
+                }
+                let rms = (energy / samples as f64).sqrt();
+                if rms > ACTIVE_RMS {
+                    self.active.push((rms, member.ssrc));
+                }
+                index += 1;
+            }
+            // 15'ten fazla konuşan varsa en yüksek seviyeliler yazılır.
+            self.active.sort_by(|a, b| b.0.total_cmp(&a.0));
+            self.contributors.clear();
+            self.contributors.extend(self.active.iter().take(MAX_CSRCS).map(|&(_, ssrc)| ssrc));
+
+            if self.members.is_empty() {
+                return Ok(false);
+            }
+            frame.extend(self.sum.iter().map(|&total| total.clamp(i16::MIN as i32, i16::MAX as i32) as i16));
+            Ok(true)
+        })
+    }
+
+    fn contributors(&self) -> &[u32] {
+        &self.contributors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::playback::{Playback, Player};
+    use crate::rtp::RtpPacketRef;
+    use crate::session::RtpSession;
+    use crate::source::SampleSource;
+
+    /// Her çerçeve için konuşuyor mu bilgisinden 160 örneklik çerçeveler kurar.
+    fn speaker(talking: &[bool]) -> Box<dyn AudioSource> {
+        let samples = talking.iter().flat_map(|&on| [if on { 2000 } else { 10 }; 160]).collect();
+        Box::new(SampleSource::new(Arc::new(samples), false))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn three_party_mix_lists_active_speakers_as_csrcs() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = peer.local_addr().unwrap();
+        let mut mixer = Mixer::new();
+        mixer.add(0xA, speaker(&[false, true, true, false, false]));
+        mixer.add(0xB, speaker(&[false, false, true, true, false]));
+        mixer.add(0xC, speaker(&[false, false, false, true, false]));
+
+        let mut player = Player::new(session.codec(), Duration::from_millis(20));
+        player.install(&session, Playback::new("conference", Box::new(mixer)));
+        while player.is_playing() {
+            player.tick().await;
+            player.send_frame(&session, target).await;
+        }
+
+        let mut buf = [0u8; 2048];
+        let mut csrcs = Vec::new();
+        while let Ok((len, _)) = peer.try_recv_from(&mut buf) {
+            let packet = RtpPacketRef::parse(&buf[..len]).unwrap();
+            assert_eq!(packet.csrc_count(), packet.csrcs().count());
+            assert_eq!(packet.payload().len(), 160);
+            let mut list: Vec<u32> = packet.csrcs().collect();
+            list.sort();
+            csrcs.push(list);
+        }
+        // Kimse konuşmazken paketler CC=0, karışım olmayan oturumlarla aynı biçimdedir.
+        assert_eq!(csrcs, [vec![], vec![0xA], vec![0xA, 0xB], vec![0xB, 0xC], vec![]]);
+    }
+
+    #[tokio::test]
+    async fn mix_saturates_and_caps_csrcs_at_fifteen() {
+        let mut mixer = Mixer::new();
+        for ssrc in 0..20 {
+            mixer.add(ssrc, Box::new(SampleSource::new(Arc::new(vec![3000 + ssrc as i16; 160]), false)));
+        }
+        let mut frame = Vec::new();
+        assert!(mixer.next_frame(160, &mut frame).await.unwrap());
+        assert!(frame.iter().all(|&sample| sample == i16::MAX));
+        // En yüksek seviyeli 15 katılımcı.
+        let mut contributors = mixer.contributors().to_vec();
+        contributors.sort();
+        assert_eq!(contributors, (5..20).collect::<Vec<u32>>());
+        assert!(!mixer.next_frame(160, &mut frame).await.unwrap());
+    }
+}
@@ -0,0 +1,158 @@
+// Tek yönlü ses bekçisi: dinleyici görevi saniyede bir oturumun iki yönüne bakar. İki yön de bir
+// kez aktıktan sonra paket gönderirken uzun süre hiç paket gelmiyorsa (`inbound`) ya da paket
+// gelirken her gönderim başarısız oluyorsa (`outbound`) tek yönlü ses şüphesi bildirilir. Şüphe
+// kesilen yön yeniden akınca ya da tek yönlü ses beklenir olunca (bekletme) kalkar; yalnızca
+// öteki yönün durması şüpheyi kaldırmaz, aksi halde aralıklı çalan anonslarla olay tekrar tekrar
+// yazılırdı.
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::audit::OneWayDirection;
+use crate::config::QualityConfig;
+
+/// Denetim aralığı.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// Son paketi bundan yeni olan yön akıyor sayılır.
+pub const ACTIVE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Bir denetimde oturumdan okunan etkinlik.
+#[derive(Debug, Clone, Copy)]
+pub struct Activity {
+    /// Kilitli akıştan son gelen paket.
+    pub last_received: Option<Instant>,
+    /// Son başarılı gönderim; hiç gönderilmediyse yok.
+    pub last_sent: Option<Instant>,
+    /// Son başarılı gönderimden sonraki ilk ve son gönderim hatası.
+    pub send_failures: Option<(Instant, Instant)>,
+    /// Tek yönlü ses beklenir (bekletme, yalnızca gönderen oturum, dinleme bacağı).
+    pub expected: bool,
+}
+
+/// Bekçinin bildirdiği değişiklik.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// `silent` kesilen yönün son başarılı paketinden beri geçen süre.
+    Suspected { direction: OneWayDirection, silent: Duration },
+    /// `duration` yönün kesildiği andan kalkışa kadar geçen süre.
+    Cleared { direction: OneWayDirection, duration: Duration },
+}
+
+#[derive(Debug)]
+pub struct Watchdog {
+    inbound_after: Option<Duration>,
+    outbound_after: Option<Duration>,
+    // Şüphelenilen yön ve kesildiği an.
+    suspected: Option<(OneWayDirection, Instant)>,
+    first: Option<OneWayDirection>,
+    // Kalkan şüphelerin toplam süresi.
+    total: Duration,
+}
+
+impl Watchdog {
+    /// İki eşik de kapalıysa bekçi yoktur.
+    pub fn new(config: &QualityConfig) -> Option<Self> {
+        let (inbound_after, outbound_after) = (config.one_way_inbound(), config.one_way_outbound());
+        (inbound_after.is_some() || outbound_after.is_some())
+            .then_some(Watchdog { inbound_after, outbound_after, suspected: None, first: None, total: Duration::ZERO })
+    }
+
+    pub fn check(&mut self, activity: &Activity, now: Instant) -> Option<Transition> {
+        if let Some((direction, since)) = self.suspected {
+            let recovered = match direction {
+                OneWayDirection::Inbound => activity.last_received.is_some_and(|at| at > since),
+                OneWayDirection::Outbound => activity.send_failures.is_none_or(|(first, _)| first != since),
+            };
+            if !recovered && !activity.expected {
+                return None;
+            }
+            self.suspected = None;
+            let duration = now.saturating_duration_since(since);
+            self.total += duration;
+            return Some(Transition::Cleared { direction, duration });
+        }
+        let (direction, since) = self.detect(activity, now)?;
+        self.suspected = Some((direction, since));
+        self.first.get_or_insert(direction);
+        Some(Transition::Suspected { direction, silent: now.saturating_duration_since(since) })
+    }
+
+    // Kesilen yön ve kesildiği an; iki yön de bir kez akmadıysa aranmaz.
+    fn detect(&self, activity: &Activity, now: Instant) -> Option<(OneWayDirection, Instant)> {
+        let (Some(received), Some(sent)) = (activity.last_received, activity.last_sent) else { return None };
+        if activity.expected {
+            return None;
+        }
+        let active = |at: Instant| now.saturating_duration_since(at) < ACTIVE_WINDOW;
+        if let Some(after) = self.inbound_after {
+            if active(sent) && now.saturating_duration_since(received) >= after {
+                return Some((OneWayDirection::Inbound, received));
+            }
+        }
+        let (first, last) = activity.send_failures?;
+        let after = self.outbound_after?;
+        (active(received) && active(last) && now.saturating_duration_since(first) >= after).then_some((OneWayDirection::Outbound, first))
+    }
+
+    /// Oturum özeti için ilk şüphelenilen yön ve şüphelerin `now`'a kadar toplam süresi.
+    pub fn summary(&self, now: Instant) -> (Option<OneWayDirection>, Duration) {
+        let ongoing = self.suspected.map_or(Duration::ZERO, |(_, since)| now.saturating_duration_since(since));
+        (self.first, self.total + ongoing)
+    }
+
+    pub fn is_suspected(&self) -> bool {
+        self.suspected.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(start: Instant, received: u64, sent: u64) -> Activity {
+        let at = |ms: u64| Some(start + Duration::from_millis(ms));
+        Activity { last_received: at(received), last_sent: at(sent), send_failures: None, expected: false }
+    }
+
+    #[test]
+    fn silence_while_sending_is_raised_once_and_cleared_by_inbound_audio() {
+        let mut watchdog = Watchdog::new(&QualityConfig::default()).unwrap();
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Gelen ses hiç akmadıysa ya da gönderilen yoksa aranmaz.
+        assert_eq!(watchdog.check(&Activity { last_received: None, ..activity(start, 0, 20_000) }, at(20_000)), None);
+        assert_eq!(watchdog.check(&activity(start, 0, 5_000), at(20_000)), None);
+        assert_eq!(watchdog.check(&activity(start, 1_000, 10_500), at(10_900)), None);
+        let suspected = watchdog.check(&activity(start, 1_000, 11_500), at(11_900));
+        assert_eq!(suspected, Some(Transition::Suspected { direction: OneWayDirection::Inbound, silent: Duration::from_millis(10_900) }));
+        // Gönderim dursa da şüphe sürer; gelen paketle kalkar.
+        assert_eq!(watchdog.check(&activity(start, 1_000, 11_500), at(30_000)), None);
+        assert_eq!(watchdog.summary(at(30_000)), (Some(OneWayDirection::Inbound), Duration::from_millis(29_000)));
+        let cleared = watchdog.check(&activity(start, 30_500, 30_500), at(31_000));
+        assert_eq!(cleared, Some(Transition::Cleared { direction: OneWayDirection::Inbound, duration: Duration::from_millis(30_000) }));
+
+        // Bekletmede aranmaz; beklenir olunca var olan şüphe kalkar.
+        let held = Activity { expected: true, ..activity(start, 31_000, 60_000) };
+        assert_eq!(watchdog.check(&held, at(60_000)), None);
+        assert!(watchdog.check(&activity(start, 31_000, 60_000), at(60_000)).is_some());
+        assert!(matches!(watchdog.check(&held, at(61_000)), Some(Transition::Cleared { .. })));
+        assert_eq!(watchdog.summary(at(90_000)), (Some(OneWayDirection::Inbound), Duration::from_millis(60_000)));
+    }
+
+    #[test]
+    fn failing_sends_while_receiving_are_raised_until_a_send_succeeds() {
+        let mut watchdog = Watchdog::new(&QualityConfig::default()).unwrap();
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let failing = |received, first, last| Activity { send_failures: Some((at(first), at(last))), ..activity(start, received, 1_000) };
+
+        assert_eq!(watchdog.check(&failing(5_900, 1_020, 5_900), at(6_000)), None);
+        // Hatalar sürmüyorsa (gönderilecek ses yok) şüphe yazılmaz.
+        assert_eq!(watchdog.check(&failing(6_900, 1_020, 3_000), at(7_000)), None);
+        let suspected = watchdog.check(&failing(6_900, 1_020, 6_900), at(7_000));
+        assert_eq!(suspected, Some(Transition::Suspected { direction: OneWayDirection::Outbound, silent: Duration::from_millis(5_980) }));
+        assert_eq!(watchdog.check(&activity(start, 7_900, 7_500), at(8_000)), Some(Transition::Cleared { direction: OneWayDirection::Outbound, duration: Duration::from_millis(6_980) }));
+        assert!(Watchdog::new(&QualityConfig { one_way_inbound_s: 0, one_way_outbound_s: 0, ..QualityConfig::default() }).is_none());
+    }
+}
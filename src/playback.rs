@@ -0,0 +1,763 @@
+// Oturumun tempolu göndericisi: kurulu ses kaynağından ptime'lık çerçeveler alır, oturumun
+// codec'iyle kodlar ve RTP olarak gönderir. Oturum başına tek gönderici vardır; yeni bir kaynak
+// kurmak çalanı değiştirir, böylece iki anons aynı akışa karışmaz. İstekle gelen oynatmalar
+// çalan varsa reddedilir, sıraya alınır ya da çalanı keser (`PlayMode`); sıradakiler çalan
+// bitince başlar, oturum kapanınca çalınmadan atılır.
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::pending;
+use std::net::SocketAddr;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::{interval, Instant, Interval};
+use tracing::{debug, info, warn};
+
+use crate::announcement::{Prompt, PromptClaim};
+use crate::audio_level::AudioLevel;
+use crate::audit::{self, PlaybackStopReason, SendFailure};
+use crate::codec::Codec;
+use crate::error::{PlaybackError, SessionError};
+use crate::metrics;
+use crate::red::{RedConfig, RedEncoder};
+use crate::rtp::{RtpPacket, COMFORT_NOISE_PT, MAX_PACKET_LEN};
+use crate::session::RtpSession;
+use crate::source::{AudioSource, BedSource, RateSource, SequenceSource};
+use crate::tap::{self, Direction};
+use crate::transport;
+use crate::vad::Frame;
+
+// Erişilebilirlik için yavaş tekrar ile testlerde hızlı çalma arası; dışı anlaşılmaz olur.
+pub const MIN_RATE: f32 = 0.5;
+pub const MAX_RATE: f32 = 2.0;
+// Fon müziği ön plandan yüksek olamaz; -60 dB'nin altı zaten duyulmaz.
+pub const MIN_BED_GAIN_DB: f32 = -60.0;
+pub const MAX_BED_GAIN_DB: f32 = 0.0;
+
+/// Çalınacak bir kaynak ve denetim kayıtlarında görünen tanımı.
+pub struct Playback {
+    pub name: String,
+    pub file: Option<String>,
+    pub language: Option<String>,
+    pub source: Box<dyn AudioSource>,
+    /// Oturum yeniden köprülenince durur; köprüden ayrılan bacağın dolgu sesi için.
+    pub until_bridged: bool,
+    /// Oturum sendonly'den çıkınca durur; bekletilen uca giden bekleme sesi için.
+    pub until_resumed: bool,
+    /// Adlandırılmış anons; sessizlik bastırması varsayılan olarak anonsları kırpmaz.
+    pub announcement: bool,
+    /// Çalma hızı; 1.0 dışındaki değerlerde kaynak yeniden örneklenir.
+    pub rate: f32,
+    /// Altına karışan fon müziği anonsunun adı ve kısması (dB).
+    pub bed: Option<(String, f32)>,
+    // Çalarken anonsların (ve fonun) silinmesini engeller.
+    _claims: Vec<PromptClaim>,
+    _bed_claim: Option<PromptClaim>,
+}
+
+impl Playback {
+    pub fn new(name: impl Into<String>, source: Box<dyn AudioSource>) -> Self {
+        Playback {
+            name: name.into(), file: None, language: None, source, until_bridged: false, until_resumed: false, announcement: false, rate: 1.0, bed: None,
+            _claims: Vec::new(), _bed_claim: None,
+        }
+    }
+
+    pub fn until_bridged(mut self) -> Self {
+        self.until_bridged = true;
+        self
+    }
+
+    pub fn until_resumed(mut self) -> Self {
+        self.until_resumed = true;
+        self
+    }
+
+    // Köprüden ayrılan ya da bekletilen bacağın dolgusu.
+    fn is_filler(&self) -> bool {
+        self.until_bridged || self.until_resumed
+    }
+
+    /// Adlandırılmış anonsun kaynağını açar.
+    pub fn prompt(prompt: &Prompt) -> Result<Self, PlaybackError> {
+        Ok(Playback {
+            name: prompt.name.clone(),
+            file: Some(prompt.config.path.clone()),
+            language: prompt.config.language.clone(),
+            source: prompt.source()?,
+            until_bridged: false,
+            until_resumed: false,
+            announcement: true,
+            rate: 1.0,
+            bed: None,
+            _claims: vec![prompt.claim()],
+            _bed_claim: None,
+        })
+    }
+
+    /// Parça anonslarını art arda, tek bir oynatma olarak çalar: tek başlama ve tek bitiş olayı,
+    /// parçalar arasında boşluk yok. Bütün parçaların kaynağı baştan açılır; biri açılamazsa hiç
+    /// çalınmaz.
+    pub fn sequence(name: impl Into<String>, language: Option<&str>, segments: &[Arc<Prompt>]) -> Result<Self, PlaybackError> {
+        let sources = segments.iter().map(|segment| segment.source()).collect::<Result<Vec<_>, _>>()?;
+        Ok(Playback {
+            language: language.map(str::to_string),
+            announcement: true,
+            _claims: segments.iter().map(|segment| segment.claim()).collect(),
+            ..Playback::new(name, Box::new(SequenceSource::new(sources)))
+        })
+    }
+
+    /// `bed` anonsunu döngüyle, `gain_db` kısılmış olarak altına karıştırır; oynatma bitince ya
+    /// da durunca fon da durur. Hızdan sonra uygulanırsa fon normal hızda çalar.
+    pub fn with_bed(mut self, bed: &Prompt, gain_db: f32) -> Result<Self, PlaybackError> {
+        let music = bed.bed_source()?;
+        self.source = Box::new(BedSource::new(self.source, music, gain_db));
+        self.bed = Some((bed.name.clone(), gain_db));
+        self._bed_claim = Some(bed.claim());
+        Ok(self)
+    }
+
+    /// Kaynağı `rate` kat hızla çalar; 1.0'da kaynağa dokunulmaz, çıktı birebir aynı kalır.
+    pub fn with_rate(mut self, rate: f32) -> Self {
+        if rate != 1.0 {
+            self.source = Box::new(RateSource::new(self.source, rate as f64));
+            self.rate = rate;
+        }
+        self
+    }
+}
+
+/// İstekteki çalma hızını doğrular; 0 (alan verilmemiş) normal hızdır.
+pub fn requested_rate(rate: f32) -> Result<f32, PlaybackError> {
+    match rate {
+        0.0 => Ok(1.0),
+        rate if (MIN_RATE..=MAX_RATE).contains(&rate) => Ok(rate),
+        rate => Err(PlaybackError::InvalidRate { rate }),
+    }
+}
+
+/// Fon kısmasını doğrular.
+pub fn bed_gain(gain_db: f32) -> Result<f32, PlaybackError> {
+    match gain_db {
+        gain_db if (MIN_BED_GAIN_DB..=MAX_BED_GAIN_DB).contains(&gain_db) => Ok(gain_db),
+        gain_db => Err(PlaybackError::InvalidBedGain { gain_db }),
+    }
+}
+
+/// Kaynak açılamadığında çağrılır; başlamadan biten oynatmayı kaydeder.
+pub fn load_failed(session: &RtpSession, name: &str, error: &PlaybackError) {
+    metrics::get().announcements_failed.inc();
+    session.stats.playback_failed(error.failure());
+    warn!(
+        target: audit::TARGET, event = audit::PLAYBACK_STOPPED,
+        prompt = %name, packets = 0u64, played_ms = 0u64, reason = PlaybackStopReason::LoadError.as_str(), failure = error.failure().as_str(), error = %error,
+    );
+}
+
+/// Bir şey çalarken gelen oynatmanın ne yapacağı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayMode {
+    /// Çalan varsa oynatma reddedilir.
+    Reject,
+    /// Kuyruğun sonuna eklenir, öncekiler bitince çalar.
+    Enqueue,
+    /// Çalan `replaced` sebebiyle durur, oynatma hemen başlar; kuyruk korunur.
+    Interrupt,
+}
+
+impl PlayMode {
+    /// İstekteki iki bayrak; ikisi birden verilemez.
+    pub fn requested(enqueue: bool, interrupt: bool) -> Result<Self, SessionError> {
+        match (enqueue, interrupt) {
+            (true, true) => Err(SessionError::ConflictingPlayModes),
+            (true, false) => Ok(PlayMode::Enqueue),
+            (false, true) => Ok(PlayMode::Interrupt),
+            (false, false) => Ok(PlayMode::Reject),
+        }
+    }
+}
+
+struct Current {
+    id: u64,
+    playback: Playback,
+    packets: u64,
+    // Kaynaktan okunan örnekler; bastırılan çerçeveler de sayılır.
+    samples: u64,
+}
+
+/// Oturumun göndericisi. Oturumda gönderim zamanlayıcısının dilim görevi tempolar
+/// (`send_scheduled`); oturumdan bağımsız kullanımda (konferans karıştırıcısı, testler) kendi
+/// zamanlayıcısı vardır ve kaynak yokken durur.
+pub struct Player {
+    codec: &'static dyn Codec,
+    ptime: Duration,
+    samples_per_frame: usize,
+    pacer: Option<Interval>,
+    // Son tikin planlanan anı; zaman damgası gecikmeden değil plandan hesaplanır.
+    scheduled: Instant,
+    // Son oynatmanın bittiği an; hiç çalmadıysa göndericinin kurulduğu an.
+    idle_since: Instant,
+    current: Option<Current>,
+    // Çalan bitince sırayla başlayacaklar ve kimlikleri.
+    queue: VecDeque<(u64, Playback)>,
+    max_queued: usize,
+    // Oturumdaki oynatmaların kimliği 1'den başlar.
+    next_id: u64,
+    frame: Vec<i16>,
+    payload: Vec<u8>,
+    // RED anlaşıldıysa yük tipi, kodlayıcı ve sarılmış yük tamponu.
+    red: Option<(u8, RedEncoder, Vec<u8>)>,
+    wire: [u8; MAX_PACKET_LEN],
+}
+
+impl Player {
+    pub fn new(codec: &'static dyn Codec, ptime: Duration) -> Self {
+        let samples_per_frame = codec.samples_per_frame(ptime);
+        Player {
+            codec, ptime, samples_per_frame, pacer: None, scheduled: Instant::now(), idle_since: Instant::now(), current: None,
+            queue: VecDeque::new(), max_queued: 0, next_id: 1,
+            frame: Vec::with_capacity(samples_per_frame),
+            payload: Vec::with_capacity(samples_per_frame),
+            red: None,
+            wire: [0; MAX_PACKET_LEN],
+        }
+    }
+
+    /// Sonraki çerçeveleri `codec` ile kodlar; çerçeve boyu değişmemesi için saat hızı aynı olmalı.
+    pub fn set_codec(&mut self, codec: &'static dyn Codec) {
+        debug_assert_eq!(codec.samples_per_frame(self.ptime), self.samples_per_frame);
+        self.codec = codec;
+    }
+
+    /// Giden çerçeveleri RFC 2198 RED ile, önceki `generations` çerçeveyle birlikte gönderir.
+    pub fn with_red(mut self, red: RedConfig) -> Self {
+        let capacity = self.payload.capacity() * (red.generations + 1) + 4 * red.generations + 1;
+        self.red = Some((red.payload_type, RedEncoder::new(red.generations), Vec::with_capacity(capacity)));
+        self
+    }
+
+    /// Çalan bitince başlamak üzere en fazla `max_queued` oynatma bekletir.
+    pub fn with_queue(mut self, max_queued: usize) -> Self {
+        self.max_queued = max_queued;
+        self
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Çalmıyorsa son oynatmanın bittiği an.
+    pub fn idle_since(&self) -> Instant {
+        self.idle_since
+    }
+
+    /// Çalan, köprü kurulunca durması gereken bir dolgu mu.
+    pub fn plays_until_bridged(&self) -> bool {
+        self.current.as_ref().is_some_and(|c| c.playback.until_bridged)
+    }
+
+    /// Çalan, oturum sendonly'den çıkınca durması gereken bir bekleme sesi mi.
+    pub fn plays_until_resumed(&self) -> bool {
+        self.current.as_ref().is_some_and(|c| c.playback.until_resumed)
+    }
+
+    /// Kaynağı çalmaya başlar ve oynatmanın kimliğini döner; çalan varsa `replaced` sebebiyle
+    /// durur. Tempo kesintisiz sürer, yeni kaynağın ilk çerçevesi bir sonraki tikte gider.
+    pub fn install(&mut self, session: &RtpSession, playback: Playback) -> u64 {
+        self.stop(session, PlaybackStopReason::Replaced);
+        let id = self.next_id();
+        // Dinleme bacağının akışı dinlenen oturumun karışımına ayrılmıştır.
+        if session.tap_source().is_some() {
+            debug!(prompt = %playback.name, "Dinleme bacağında anons çalınmaz");
+            return id;
+        }
+        self.start(session, id, playback);
+        id
+    }
+
+    /// İstekle gelen oynatma. Köprü ve bekleme dolgusu çalan sayılmaz, her kipte yerini bırakır.
+    pub fn submit(&mut self, session: &RtpSession, playback: Playback, mode: PlayMode) -> Result<u64, SessionError> {
+        if session.tap_source().is_some() {
+            return Err(SessionError::TapLeg { port: session.port });
+        }
+        let busy = self.current.as_ref().filter(|current| !current.playback.is_filler());
+        match (busy, mode) {
+            (Some(current), PlayMode::Reject) => Err(SessionError::PlaybackBusy { port: session.port, playing: current.playback.name.clone() }),
+            (Some(_), PlayMode::Enqueue) if self.queue.len() >= self.max_queued => {
+                Err(SessionError::PlaybackQueueFull { port: session.port, max_queued: self.max_queued })
+            }
+            (Some(_), PlayMode::Enqueue) => {
+                let id = self.next_id();
+                info!(target: audit::TARGET, event = audit::PLAYBACK_QUEUED, playback_id = id, prompt = %playback.name, position = self.queue.len() + 1);
+                self.queue.push_back((id, playback));
+                Ok(id)
+            }
+            _ => Ok(self.install(session, playback)),
+        }
+    }
+
+    /// StopPlayback: `flush` ise önce kuyruğu boşaltır, sonra çalanı durdurur. Durdurulanın
+    /// kimliğini ve kuyruktan atılan oynatma sayısını döner; `flush` yoksa sıradaki başlar.
+    pub fn stop_current(&mut self, session: &RtpSession, flush: bool) -> (Option<u64>, usize) {
+        let flushed = if flush { self.flush(PlaybackStopReason::Flushed) } else { 0 };
+        let stopped = self.current.as_ref().map(|current| current.id);
+        self.stop(session, PlaybackStopReason::Stopped);
+        (stopped, flushed)
+    }
+
+    /// Kuyrukta bekleyenleri çalmadan atar.
+    pub fn flush(&mut self, reason: PlaybackStopReason) -> usize {
+        let flushed = self.queue.len();
+        for (id, playback) in self.queue.drain(..) {
+            info!(target: audit::TARGET, event = audit::PLAYBACK_STOPPED, playback_id = id, prompt = %playback.name, packets = 0u64, played_ms = 0u64, reason = reason.as_str());
+        }
+        flushed
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn start(&mut self, session: &RtpSession, id: u64, playback: Playback) {
+        info!(
+            target: audit::TARGET, event = audit::PLAYBACK_STARTED, playback_id = id,
+            prompt = %playback.name, file = playback.file.as_deref().unwrap_or("-"), codec = %self.codec,
+            language = playback.language.as_deref().unwrap_or("-"), samples = playback.source.total_samples(), rate = playback.rate,
+            bed = playback.bed.as_ref().map(|(name, _)| name.as_str()), bed_gain_db = playback.bed.as_ref().map(|&(_, gain_db)| gain_db),
+        );
+        metrics::get().announcements_started.inc();
+        session.stats.announcements_started.fetch_add(1, Ordering::Relaxed);
+        *session.stats.playing.lock().unwrap() = Some(playback.name.clone());
+        self.current = Some(Current { id, playback, packets: 0, samples: 0 });
+    }
+
+    /// Çalanı verilen sebeple durdurur (barge-in, oturum sonu); çalan yoksa bir şey yapmaz.
+    /// Oturum sonunda ve faks tonunda kuyrukta bekleyenler de atılır.
+    pub fn stop(&mut self, session: &RtpSession, reason: PlaybackStopReason) {
+        self.finish(session, reason, None);
+        if matches!(reason, PlaybackStopReason::SessionEnded | PlaybackStopReason::FaxTone) {
+            self.flush(reason);
+        }
+    }
+
+    /// Kendi zamanlayıcısıyla bir sonraki çerçevenin zamanını bekler; çalan yoksa hiç dönmez.
+    pub async fn tick(&mut self) {
+        if self.current.is_none() {
+            return pending().await;
+        }
+        let ptime = self.ptime;
+        self.scheduled = self.pacer.get_or_insert_with(|| interval(ptime)).tick().await;
+        metrics::get().send_loop_lag.observe(self.scheduled.elapsed());
+    }
+
+    /// Dışarıdan tempolanan gönderim: `scheduled` anına ait çerçeveyi gönderir.
+    pub async fn send_scheduled(&mut self, session: &RtpSession, target: SocketAddr, scheduled: Instant) {
+        self.scheduled = scheduled;
+        self.send_frame(session, target).await;
+    }
+
+    /// Kaynaktan bir çerçeve alıp `target`'a gönderir; kaynak biterse ya da hata olursa çalmayı kapatır.
+    pub async fn send_frame(&mut self, session: &RtpSession, target: SocketAddr) {
+        let Some(current) = &mut self.current else { return };
+        match current.playback.source.next_frame(self.samples_per_frame, &mut self.frame).await {
+            // Kaynak henüz hazır değil (ör. indiriliyor); bu tik atlanır.
+            Ok(true) if self.frame.is_empty() => return,
+            Ok(true) => current.samples += self.frame.len() as u64,
+            Ok(false) => return self.finish(session, PlaybackStopReason::Completed, None),
+            // Hiç ses okunmadan gelen hata (ör. indirilemeyen anons) yükleme hatasıdır.
+            Err(e) if current.samples == 0 => return self.finish(session, PlaybackStopReason::LoadError, Some(e)),
+            Err(e) => return self.finish(session, PlaybackStopReason::DecodeError, Some(e)),
+        }
+
+        // Susturulan çerçeve sessizlik olarak gider; akış sayaçları ve bastırma kararı sürer.
+        if session.outbound_muted() {
+            self.frame.fill(0);
+        }
+        tap::feed(session, Direction::Outbound, &self.frame);
+        let marker = match session.suppress(&self.frame, current.playback.announcement, self.scheduled) {
+            Frame::Send { marker } => marker,
+            Frame::Suppress => return,
+            Frame::ComfortNoise { level } => {
+                let (sequence, timestamp) = session.stream.next(self.scheduled, 0);
+                let level = [level];
+                let packet = RtpPacket::new(COMFORT_NOISE_PT, sequence, timestamp, session.stream.ssrc, &level);
+                let Ok(len) = packet.write(&mut self.wire) else { return };
+                match session.send_to(&self.wire[..len], target).await {
+                    Ok(_) => {
+                        session.mark_sent(target, &self.wire[..len]);
+                        session.capture_sent(target, &self.wire[..len]);
+                    }
+                    // Yeniden denemelere rağmen dolu kalan tamponda yalnızca bu paket düşer.
+                    Err(e) if transport::send_failure(&e) == SendFailure::NoBuffers => {}
+                    Err(e) => self.finish(session, PlaybackStopReason::SendError, Some(PlaybackError::Send { target, source: e })),
+                }
+                return;
+            }
+        };
+
+        session.dump_outbound(&self.frame);
+        self.payload.clear();
+        self.codec.encode(&self.frame, &mut self.payload);
+        let increment = self.codec.timestamp_increment(self.codec.frame_duration(self.frame.len()));
+        let (sequence, timestamp) = session.stream.next(self.scheduled, increment);
+        let (payload_type, payload) = match &mut self.red {
+            Some((payload_type, encoder, wrapped)) => {
+                encoder.encode(self.codec.payload_type(), timestamp, &self.payload, wrapped);
+                (*payload_type, wrapped.as_slice())
+            }
+            None => (self.codec.payload_type(), self.payload.as_slice()),
+        };
+        let mut level = [0u8; 4];
+        let packet = RtpPacket {
+            marker,
+            csrcs: current.playback.source.contributors(),
+            extension: session.audio_level_id.map(|id| AudioLevel::measure(&self.frame).extension(id, &mut level)),
+            ..RtpPacket::new(payload_type, sequence, timestamp, session.stream.ssrc, payload)
+        };
+        let sent = match packet.write(&mut self.wire) {
+            Ok(len) => session.send_to(&self.wire[..len], target).await
+                .map(|_| len)
+                .map_err(|source| PlaybackError::Send { target, source }),
+            Err(e) => Err(PlaybackError::from(e)),
+        };
+        match sent {
+            Ok(len) => {
+                session.mark_sent(target, &self.wire[..len]);
+                session.capture_sent(target, &self.wire[..len]);
+                current.packets += 1;
+            }
+            Err(e) if e.send_failure() == Some(SendFailure::NoBuffers) => {}
+            Err(e) => self.finish(session, PlaybackStopReason::SendError, Some(e)),
+        }
+    }
+
+    /// Çalanı kapatır; yerine yenisi kurulmuyorsa ve oturum sürüyorsa kuyruktaki sıradaki başlar.
+    fn finish(&mut self, session: &RtpSession, reason: PlaybackStopReason, error: Option<PlaybackError>) {
+        let Some(current) = self.current.take() else { return };
+        self.pacer = None;
+        self.idle_since = Instant::now();
+        *session.stats.playing.lock().unwrap() = None;
+        let (id, name, packets) = (current.id, &current.playback.name, current.packets);
+        // Kaynaklar 8 kHz PCM verir.
+        let played_ms = current.samples / 8;
+        match (reason, error) {
+            (PlaybackStopReason::Completed, _) => {
+                metrics::get().announcements_completed.inc();
+                info!(target: audit::TARGET, event = audit::PLAYBACK_STOPPED, playback_id = id, prompt = %name, packets, played_ms, reason = reason.as_str());
+            }
+            (_, Some(e)) => {
+                metrics::get().announcements_failed.inc();
+                session.stats.playback_failed(e.failure());
+                warn!(
+                    target: audit::TARGET, event = audit::PLAYBACK_STOPPED, playback_id = id, prompt = %name, packets, played_ms, reason = reason.as_str(),
+                    failure = e.failure().as_str(), send_error = e.send_failure().map(SendFailure::as_str), error = %e,
+                );
+            }
+            (_, None) => {
+                info!(target: audit::TARGET, event = audit::PLAYBACK_STOPPED, playback_id = id, prompt = %name, packets, played_ms, reason = reason.as_str());
+            }
+        }
+        if !matches!(reason, PlaybackStopReason::Replaced | PlaybackStopReason::SessionEnded | PlaybackStopReason::FaxTone) {
+            if let Some((id, playback)) = self.queue.pop_front() {
+                self.start(session, id, playback);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Player {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Player")
+            .field("codec", &self.codec.name())
+            .field("playing", &self.current.as_ref().map(|current| (current.id, &current.playback.name)))
+            .field("queued", &self.queue.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::time::Instant;
+
+    use crate::rtp::RtpPacketRef;
+    use crate::source::SampleSource;
+
+    fn samples(name: &str, samples: Vec<i16>) -> Playback {
+        Playback::new(name, Box::new(SampleSource::new(Arc::new(samples), false)))
+    }
+
+    /// Oturumdan bağımsız kullanımdaki gibi kendi zamanlayıcısıyla, çalan bitene kadar tikler.
+    async fn play_to_end(session: Arc<RtpSession>, target: SocketAddr, playback: Playback, ptime: Duration) -> Player {
+        let mut player = Player::new(session.codec(), ptime);
+        player.install(&session, playback);
+        while player.is_playing() {
+            player.tick().await;
+            player.send_frame(&session, target).await;
+        }
+        player
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn announcement_uses_configured_ptime() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = peer.local_addr().unwrap();
+        tokio::spawn(play_to_end(session, target, samples("test", vec![0; 320 * 3]), Duration::from_millis(40)));
+
+        let mut buf = [0u8; 2048];
+        let mut arrivals = Vec::new();
+        let mut timestamps = Vec::new();
+        for _ in 0..3 {
+            let (len, _) = peer.recv_from(&mut buf).await.unwrap();
+            assert_eq!(len, 12 + 320);
+            arrivals.push(Instant::now());
+            timestamps.push(u32::from_be_bytes(buf[4..8].try_into().unwrap()));
+        }
+        for i in 1..3 {
+            assert_eq!(arrivals[i] - arrivals[i - 1], Duration::from_millis(40));
+            assert_eq!(timestamps[i].wrapping_sub(timestamps[i - 1]), 320);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn red_packets_carry_the_previous_frame() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = peer.local_addr().unwrap();
+        let mut player = Player::new(session.codec(), Duration::from_millis(20)).with_red(RedConfig { payload_type: 99, generations: 1 });
+        player.install(&session, samples("test", vec![0; 160 * 2]));
+        while player.is_playing() {
+            player.tick().await;
+            player.send_frame(&session, target).await;
+        }
+
+        let mut buf = [0u8; 2048];
+        let mut previous = None;
+        for redundant in [0, 1] {
+            let (len, _) = peer.recv_from(&mut buf).await.unwrap();
+            let packet = RtpPacketRef::parse(&buf[..len]).unwrap();
+            assert_eq!(packet.payload_type(), 99);
+            let mut blocks = Vec::new();
+            crate::red::parse(packet.payload(), packet.timestamp(), &mut blocks).unwrap();
+            assert_eq!(blocks.len(), redundant + 1);
+            assert!(blocks.iter().all(|b| b.payload_type == 0 && b.data.len() == 160));
+            // Yedek, bir önceki paketin birincil yüküdür.
+            if let Some(previous) = previous {
+                assert_eq!(blocks[0].timestamp, previous);
+            }
+            previous = Some(packet.timestamp());
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ten_second_announcement_sends_500_packets_in_virtual_time() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = peer.local_addr().unwrap();
+        let wall = std::time::Instant::now();
+        let started = Instant::now();
+        let mut sender = tokio::spawn(play_to_end(session.clone(), target, samples("test", vec![0; 8000 * 10]), Duration::from_millis(20)));
+
+        // Soket tamponu 500 paketi tutmaz; gönderim sürerken okunur.
+        let mut buf = [0u8; 2048];
+        let mut received = 0;
+        let mut last_arrival = started;
+        loop {
+            tokio::select! {
+                biased;
+                result = peer.recv_from(&mut buf) => { result.unwrap(); received += 1; last_arrival = Instant::now(); }
+                _ = &mut sender => break,
+            }
+        }
+        while peer.try_recv_from(&mut buf).is_ok() {
+            received += 1;
+        }
+        // İlk paket hemen gider; son paket 499 aralık sonra.
+        assert_eq!(last_arrival - started, Duration::from_millis(20 * 499));
+        assert!(wall.elapsed() < Duration::from_secs(2), "took {:?} of real time", wall.elapsed());
+        assert_eq!(received, 500);
+        assert_eq!(session.stats.packets_sent.load(Ordering::Relaxed), 500);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn installing_a_source_replaces_the_current_one() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = peer.local_addr().unwrap();
+        let mut player = Player::new(session.codec(), Duration::from_millis(20));
+        player.install(&session, samples("first", vec![0; 160 * 10]));
+        for _ in 0..2 {
+            player.tick().await;
+            player.send_frame(&session, target).await;
+        }
+        player.install(&session, samples("second", vec![0; 160 * 3]));
+        while player.is_playing() {
+            player.tick().await;
+            player.send_frame(&session, target).await;
+        }
+
+        // İlk kaynaktan 2, ikinciden 3 paket; ikisi de tek gönderici üzerinden.
+        assert_eq!(session.stats.packets_sent.load(Ordering::Relaxed), 5);
+        assert_eq!(session.stats.announcements_started.load(Ordering::Relaxed), 2);
+        assert_eq!(session.stats.announcements_failed.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn queued_playbacks_play_in_order_until_flushed() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = peer.local_addr().unwrap();
+        let mut player = Player::new(session.codec(), Duration::from_millis(20)).with_queue(2);
+        assert_eq!(player.submit(&session, samples("first", vec![0; 160 * 2]), PlayMode::Reject).unwrap(), 1);
+        let busy = player.submit(&session, samples("busy", vec![0; 160]), PlayMode::Reject);
+        assert!(matches!(busy, Err(SessionError::PlaybackBusy { ref playing, .. }) if playing == "first"));
+        assert_eq!(player.submit(&session, samples("second", vec![0; 160 * 3]), PlayMode::Enqueue).unwrap(), 2);
+        assert_eq!(player.submit(&session, samples("third", vec![0; 160]), PlayMode::Enqueue).unwrap(), 3);
+        let full = player.submit(&session, samples("fourth", vec![0; 160]), PlayMode::Enqueue);
+        assert!(matches!(full, Err(SessionError::PlaybackQueueFull { max_queued: 2, .. })));
+        while player.is_playing() {
+            player.tick().await;
+            player.send_frame(&session, target).await;
+        }
+
+        // Üç oynatma sırayla ve aralarında boşluk olmadan tek akışta: 2 + 3 + 1 paket.
+        let mut buf = [0u8; 2048];
+        let mut sequences = Vec::new();
+        while let Ok((len, _)) = peer.try_recv_from(&mut buf) {
+            sequences.push(RtpPacketRef::parse(&buf[..len]).unwrap().sequence());
+        }
+        assert_eq!(sequences.len(), 6);
+        assert!(sequences.windows(2).all(|pair| pair[1] == pair[0].wrapping_add(1)));
+        assert_eq!(session.stats.announcements_started.load(Ordering::Relaxed), 3);
+
+        // Durdurmak sıradakini başlatır, flush kuyruğu da boşaltır.
+        player.submit(&session, samples("a", vec![0; 160 * 5]), PlayMode::Reject).unwrap();
+        player.submit(&session, samples("b", vec![0; 160 * 5]), PlayMode::Enqueue).unwrap();
+        player.submit(&session, samples("c", vec![0; 160 * 5]), PlayMode::Enqueue).unwrap();
+        assert_eq!(player.stop_current(&session, false), (Some(4), 0));
+        assert!(player.is_playing());
+        assert_eq!(player.stop_current(&session, true), (Some(5), 1));
+        assert!(!player.is_playing());
+        assert_eq!(player.stop_current(&session, true), (None, 0));
+
+        // Oturum sonunda kuyruktakiler başlamadan atılır.
+        player.submit(&session, samples("d", vec![0; 160 * 5]), PlayMode::Reject).unwrap();
+        player.submit(&session, samples("e", vec![0; 160 * 5]), PlayMode::Enqueue).unwrap();
+        player.stop(&session, PlaybackStopReason::SessionEnded);
+        assert!(!player.is_playing());
+        assert_eq!(session.stats.announcements_started.load(Ordering::Relaxed), 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn back_to_back_plays_continue_one_stream() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = peer.local_addr().unwrap();
+        let ptime = Duration::from_millis(20);
+        let mut player = play_to_end(session.clone(), target, samples("first", vec![0; 160 * 3]), ptime).await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        player.install(&session, samples("second", vec![0; 160 * 2]));
+        while player.is_playing() {
+            player.tick().await;
+            player.send_frame(&session, target).await;
+        }
+
+        let mut buf = [0u8; 2048];
+        let mut packets = Vec::new();
+        while let Ok((len, _)) = peer.try_recv_from(&mut buf) {
+            let packet = RtpPacketRef::parse(&buf[..len]).unwrap();
+            packets.push((packet.sequence(), packet.timestamp(), packet.ssrc()));
+        }
+        assert_eq!(packets.len(), 5);
+        let (seq0, ts0, ssrc) = packets[0];
+        let offsets: Vec<(u16, u32)> = packets.iter()
+            .map(|&(seq, ts, s)| { assert_eq!(s, ssrc); (seq.wrapping_sub(seq0), ts.wrapping_sub(ts0)) })
+            .collect();
+        // İlk anons 0, 20, 40 ms'de; 60 ms'de biter, bir saniye sessizlik, ikinci anons 1060 ms'de.
+        assert_eq!(offsets, [(0, 0), (1, 160), (2, 320), (3, 8480), (4, 8640)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_one_is_byte_identical_and_other_rates_scale_the_duration() {
+        let ramp: Vec<i16> = (0..1600).map(|i| (i * 16) as i16).collect();
+        let mut outputs = Vec::new();
+        for rate in [None, Some(1.0), Some(2.0), Some(0.5)] {
+            let (session, peer) = RtpSession::for_test().await;
+            let target = peer.local_addr().unwrap();
+            let mut playback = samples("ramp", ramp.clone());
+            if let Some(rate) = rate {
+                playback = playback.with_rate(rate);
+            }
+            let started = Instant::now();
+            play_to_end(session, target, playback, Duration::from_millis(20)).await;
+            let mut buf = [0u8; 2048];
+            let mut payloads = Vec::new();
+            while let Ok((len, _)) = peer.try_recv_from(&mut buf) {
+                // Sıra numarası, zaman damgası ve SSRC oturuma göre rastgele; yük ve başlığın geri kalanı karşılaştırılır.
+                payloads.push([&buf[..2], &buf[12..len]].concat());
+            }
+            outputs.push((payloads, started.elapsed()));
+        }
+        assert_eq!(outputs[0], outputs[1]);
+        // Paketler yine 20 ms arayla gider; 200 ms'lik içerik 2x'te 5, 0.5x'te 20 pakettir.
+        assert_eq!(outputs[2].0.len(), 5);
+        assert_eq!(outputs[3].0.len(), 20);
+        assert!(outputs.iter().all(|(payloads, elapsed)| *elapsed == Duration::from_millis(20 * payloads.len() as u64)));
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn truncated_wav_plays_up_to_the_corruption_and_leaves_the_session_usable() {
+        // Başlık 800 örnek bildirir; dosya 400. örneğin ortasında kesilir.
+        let path = std::env::temp_dir().join(format!("media-truncated-{}.wav", std::process::id()));
+        let spec = hound::WavSpec { channels: 1, sample_rate: 8000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..800 {
+            writer.write_sample(1000i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(44 + 2 * 400 + 1).unwrap();
+        let source = crate::source::WavSource::open(path.to_str().unwrap(), 0.0).unwrap();
+
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let _guard = tracing::subscriber::set_default(
+            tracing_subscriber::fmt().with_writer(move || writer.clone()).with_ansi(false).finish(),
+        );
+        let (session, peer) = RtpSession::for_test().await;
+        let target = peer.local_addr().unwrap();
+        let mut player = play_to_end(session.clone(), target, Playback::new("truncated", Box::new(source)), Duration::from_millis(20)).await;
+
+        // İki tam çerçeve ve hatadan önce okunan 80 örneklik kısa çerçeve.
+        let mut buf = [0u8; 2048];
+        let mut lengths = Vec::new();
+        while let Ok((len, _)) = peer.try_recv_from(&mut buf) {
+            lengths.push(len - 12);
+        }
+        assert_eq!(lengths, [160, 160, 80]);
+        assert_eq!(session.stats.announcements_failed.load(Ordering::Relaxed), 1);
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("packets=3 played_ms=50 reason=\"decode_error\" failure=\"read_error\""), "{}", output);
+        assert!(output.contains("failed to read WAV file"), "{}", output);
+
+        player.install(&session, samples("next", vec![0; 160 * 2]));
+        while player.is_playing() {
+            player.tick().await;
+            player.send_frame(&session, target).await;
+        }
+        assert_eq!(session.stats.packets_sent.load(Ordering::Relaxed), 5);
+        assert_eq!(session.stats.announcements_failed.load(Ordering::Relaxed), 1);
+        std::fs::remove_file(path).unwrap();
+    }
+}
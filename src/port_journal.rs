@@ -0,0 +1,397 @@
+// Port günlüğü: güvenlik duvarı loglarıyla adli eşleştirme için hangi oturumun hangi portu ne zaman
+// kullandığı yerel bir dosyaya NDJSON olarak eklenir. Her tahsis, gelen akışın kilitlenmesi (uzak
+// adres) ve bırakma bir satırdır; node her açılışta bir `started` satırı yazar, böylece bırakması
+// yazılamayan (çöken süreçteki) oturumlar bir sonraki açılışta bitmiş sayılır. Yazma CDR'deki gibi
+// `appender` kuyruğundan ayrı bir iş parçacığında yapılır: tahsis yolu diski beklemez, kuyruk
+// dolarsa ya da yazma başarısız olursa kayıt düşer, sayılır ve WARN loglanır. Dosya boyutla
+// `{path}.1` ... `{path}.{max_files}` olarak döndürülür. QueryPortHistory dosyaları eskiden yeniye
+// okur ve henüz yazılmamış olabilecek son kayıtları bellekten ekler.
+use std::collections::{HashSet, VecDeque};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::appender::{AppendFile, Appender, Sink};
+use crate::cdr;
+use crate::config::PortJournalConfig;
+use crate::error::PortJournalError;
+use crate::metrics::{self, RecordOutcome};
+use crate::session::RtpSession;
+
+/// Günlük satırının türü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// Node açıldı; önceki süreçten bırakması yazılmamış oturumlar burada biter.
+    Started,
+    Allocated,
+    /// Gelen akış kilitlendi ya da değişti; `remote` akışın adresi.
+    Latched,
+    Released,
+}
+
+/// Günlüğün tek satırı; zaman Unix milisaniyesi. `started` satırında yalnızca `at_ms` ve `event` olur.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub at_ms: u64,
+    pub event: JournalEvent,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub port: u16,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub session_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub call_id: String,
+    // `[[tenants]]` kiracısı; kiracısızsa yazılmaz.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub tenant: String,
+    // Tahsiste ya da kilitlenmede bilinen uzak adres.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+}
+
+fn is_zero(port: &u16) -> bool {
+    *port == 0
+}
+
+impl JournalRecord {
+    fn started(at: SystemTime) -> Self {
+        JournalRecord { at_ms: cdr::unix_millis(at), event: JournalEvent::Started, port: 0, session_id: String::new(), call_id: String::new(), tenant: String::new(), remote: None }
+    }
+
+    fn of(event: JournalEvent, session: &RtpSession, remote: Option<SocketAddr>) -> Self {
+        JournalRecord {
+            at_ms: cdr::unix_millis(SystemTime::now()), event, port: session.port, session_id: session.session_id.clone(), call_id: session.call_id.clone(),
+            tenant: session.tenant.as_ref().map_or_else(String::new, |tenant| tenant.name.to_string()), remote: remote.map(|addr| addr.to_string()),
+        }
+    }
+}
+
+/// Bir oturumun portu kullandığı süre; QueryPortHistory'nin cevabı.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tenancy {
+    pub session_id: String,
+    pub call_id: String,
+    pub tenant: String,
+    /// Tahsis satırı döndürülüp silindiyse yok.
+    pub allocated_at_ms: Option<u64>,
+    /// Oturum sürüyorsa yok.
+    pub released_at_ms: Option<u64>,
+    /// Kilitlenen uzak adresler, ilk görüldükleri sırayla.
+    pub remotes: Vec<String>,
+    /// Bırakma yazılmadan node yeniden açıldı; `released_at_ms` açılış anıdır.
+    pub ended_by_restart: bool,
+}
+
+/// Zamana göre sıralı kayıtlardan `port`'u `[from_ms, to_ms]` aralığında kullanan oturumlar,
+/// tahsis sırasıyla.
+pub fn tenancies(records: &[JournalRecord], port: u16, from_ms: u64, to_ms: u64) -> Vec<Tenancy> {
+    let (mut open, mut closed): (Vec<Tenancy>, Vec<Tenancy>) = (Vec::new(), Vec::new());
+    for record in records {
+        if record.event == JournalEvent::Started {
+            closed.extend(open.drain(..).map(|tenancy| Tenancy { released_at_ms: Some(record.at_ms), ended_by_restart: true, ..tenancy }));
+            continue;
+        }
+        if record.port != port {
+            continue;
+        }
+        let index = match open.iter().position(|tenancy| tenancy.session_id == record.session_id) {
+            Some(index) if record.event != JournalEvent::Allocated => index,
+            _ => {
+                open.push(Tenancy {
+                    session_id: record.session_id.clone(), call_id: record.call_id.clone(), tenant: record.tenant.clone(),
+                    allocated_at_ms: (record.event == JournalEvent::Allocated).then_some(record.at_ms), released_at_ms: None, remotes: Vec::new(),
+                    ended_by_restart: false,
+                });
+                open.len() - 1
+            }
+        };
+        let tenancy = &mut open[index];
+        if let Some(remote) = record.remote.as_ref().filter(|remote| !tenancy.remotes.contains(remote)) {
+            tenancy.remotes.push(remote.clone());
+        }
+        if record.event == JournalEvent::Released {
+            tenancy.released_at_ms = Some(record.at_ms);
+            closed.push(open.remove(index));
+        }
+    }
+    closed.extend(open);
+    closed.retain(|tenancy| tenancy.allocated_at_ms.unwrap_or(0) <= to_ms && tenancy.released_at_ms.is_none_or(|at| at >= from_ms));
+    closed.sort_by_key(|tenancy| tenancy.allocated_at_ms.unwrap_or(0));
+    closed
+}
+
+/// `{path}.{n}`; 1 en yeni döndürülmüş dosyadır.
+fn rotated(path: &Path, n: u32) -> PathBuf {
+    PathBuf::from(format!("{}.{}", path.display(), n))
+}
+
+/// `JournalRecord`'ları NDJSON satırı olarak yazar ve dosyayı boyutla `{path}.1` ... olarak döndürür.
+pub struct JournalWriter {
+    file: AppendFile,
+    max_file_bytes: u64,
+    max_files: u32,
+}
+
+impl JournalWriter {
+    pub fn new(config: &PortJournalConfig) -> Self {
+        JournalWriter { file: AppendFile::new(PathBuf::from(&config.path), None), max_file_bytes: config.max_file_bytes, max_files: config.max_files }
+    }
+
+    /// Kaydı ekler; dosya dolacaksa önce döndürür.
+    pub fn write(&mut self, record: &JournalRecord) -> Result<(), PortJournalError> {
+        let line = serde_json::to_string(record).map_err(|e| self.write_error(io::Error::other(e)))? + "\n";
+        self.file.open().map_err(|e| self.write_error(e))?;
+        if self.file.exceeds(line.len(), self.max_file_bytes) {
+            self.rotate().map_err(|source| PortJournalError::Rotate { path: self.file.path().display().to_string(), source })?;
+        }
+        self.file.append(&line).map_err(|e| self.write_error(e))
+    }
+
+    /// Yazılan kayıtları diske zorlar.
+    pub fn sync(&mut self) -> Result<(), PortJournalError> {
+        self.file.sync().map_err(|e| self.write_error(e))
+    }
+
+    fn write_error(&self, source: io::Error) -> PortJournalError {
+        PortJournalError::Write { path: self.file.path().display().to_string(), source }
+    }
+
+    // Döndürülmüş dosyalar birer kayar, en eskisinin üstüne yazılır.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.sync()?;
+        let path = self.file.path().to_path_buf();
+        for n in (1..self.max_files).rev() {
+            let from = rotated(&path, n);
+            if from.exists() {
+                fs::rename(from, rotated(&path, n + 1))?;
+            }
+        }
+        self.file.rotate_to(&rotated(&path, 1))
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
+
+impl Sink for JournalWriter {
+    type Record = JournalRecord;
+
+    fn deliver(&mut self, record: JournalRecord) {
+        match self.write(&record) {
+            Ok(()) => metrics::get().port_journal_record(RecordOutcome::Written),
+            Err(e) => {
+                metrics::get().port_journal_record(RecordOutcome::Failed);
+                warn!(rtp_port = record.port, session_id = %record.session_id, error = %e, "Port günlüğüne yazılamadı");
+            }
+        }
+    }
+
+    fn end_batch(&mut self) {
+        if let Err(e) = self.sync() {
+            warn!(error = %e, "Port günlüğü diske zorlanamadı");
+        }
+    }
+
+    fn dropped(record: JournalRecord) {
+        metrics::get().port_journal_record(RecordOutcome::Dropped);
+        warn!(rtp_port = record.port, session_id = %record.session_id, "Port günlüğü kuyruğu dolu, kayıt düşürüldü");
+    }
+}
+
+pub struct Journal {
+    path: PathBuf,
+    max_files: u32,
+    appender: Appender<JournalWriter>,
+    recent: Mutex<VecDeque<JournalRecord>>,
+    recent_entries: usize,
+}
+
+impl Journal {
+    /// Yazıcı iş parçacığını başlatır ve açılış satırını kuyruğa koyar.
+    pub fn start(config: &PortJournalConfig) -> Option<Journal> {
+        let appender = match Appender::start("port-journal", config.queue_size, JournalWriter::new(config)) {
+            Ok(appender) => appender,
+            Err(e) => { warn!(error = %e, "Port günlüğü yazıcısı başlatılamadı"); return None }
+        };
+        let journal = Journal {
+            path: PathBuf::from(&config.path), max_files: config.max_files, appender, recent: Mutex::new(VecDeque::new()), recent_entries: config.recent_entries,
+        };
+        journal.submit(JournalRecord::started(SystemTime::now()));
+        Some(journal)
+    }
+
+    /// Kaydı belleğe ve kuyruğa koyar; kuyruk doluysa ya da yazıcı kapandıysa dosyaya yazılmaz.
+    pub fn submit(&self, record: JournalRecord) {
+        if self.recent_entries > 0 {
+            let mut recent = self.recent.lock().unwrap();
+            if recent.len() == self.recent_entries {
+                recent.pop_front();
+            }
+            recent.push_back(record.clone());
+        }
+        self.appender.submit(record);
+    }
+
+    /// `port`'u `[from_ms, to_ms]` aralığında kullanan oturumlar (bkz. `tenancies`). Dosyaları okur; bekler.
+    pub fn history(&self, port: u16, from_ms: u64, to_ms: u64) -> Result<Vec<Tenancy>, PortJournalError> {
+        if from_ms > to_ms {
+            return Err(PortJournalError::InvalidRange { from_ms, to_ms });
+        }
+        let relevant = |record: &JournalRecord| record.event == JournalEvent::Started || record.port == port;
+        let mut records = Vec::new();
+        for path in (1..=self.max_files).rev().map(|n| rotated(&self.path, n)).chain([self.path.clone()]) {
+            let file = match File::open(&path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(source) => return Err(PortJournalError::Read { path: path.display().to_string(), source }),
+            };
+            for line in BufReader::new(file).lines() {
+                let line = line.map_err(|source| PortJournalError::Read { path: path.display().to_string(), source })?;
+                // Çöken süreçten kalan yarım satır atlanır.
+                match serde_json::from_str::<JournalRecord>(&line) {
+                    Ok(record) if relevant(&record) => records.push(record),
+                    _ => {}
+                }
+            }
+        }
+        // Son kayıtlar kuyrukta bekliyor ya da yazılamamış olabilir; okuma sırasında dönen dosya da
+        // aynı satırı iki kez verebilir.
+        records.extend(self.recent.lock().unwrap().iter().filter(|record| relevant(record)).cloned());
+        let mut seen = HashSet::new();
+        records.retain(|record| seen.insert(record.clone()));
+        records.sort_by_key(|record| record.at_ms);
+        Ok(tenancies(&records, port, from_ms, to_ms))
+    }
+
+    /// Bkz. `Appender::flush`.
+    pub fn flush(&self) {
+        self.appender.flush();
+    }
+}
+
+static JOURNAL: OnceLock<Journal> = OnceLock::new();
+
+/// Config'deki port günlüğünü süreç geneli için kurar; yalnızca ilk çağrı etkilidir.
+pub fn install(config: &PortJournalConfig) {
+    if !config.enabled || JOURNAL.get().is_some() {
+        return;
+    }
+    if let Some(journal) = Journal::start(config) {
+        let _ = JOURNAL.set(journal);
+    }
+}
+
+fn submit(record: impl FnOnce() -> JournalRecord) {
+    if let Some(journal) = JOURNAL.get() {
+        journal.submit(record());
+    }
+}
+
+/// Oturum tabloya eklenirken çağrılır; günlük kapalıysa hiçbir şey yapmaz, asla beklemez.
+pub fn allocated(session: &RtpSession) {
+    submit(|| JournalRecord::of(JournalEvent::Allocated, session, *session.remote_addr.lock().unwrap()));
+}
+
+/// Gelen akış `remote`'tan kilitlendi ya da oraya geçti.
+pub fn latched(session: &RtpSession, remote: SocketAddr) {
+    submit(|| JournalRecord::of(JournalEvent::Latched, session, Some(remote)));
+}
+
+/// Oturum kapanışında çağrılır.
+pub fn released(session: &RtpSession) {
+    submit(|| JournalRecord::of(JournalEvent::Released, session, *session.remote_addr.lock().unwrap()));
+}
+
+/// Günlük kapalıysa `Disabled` (bkz. `Journal::history`).
+pub fn history(port: u16, from_ms: u64, to_ms: u64) -> Result<Vec<Tenancy>, PortJournalError> {
+    JOURNAL.get().ok_or(PortJournalError::Disabled)?.history(port, from_ms, to_ms)
+}
+
+/// Süreç kapanırken bekleyen kayıtları yazar (bkz. `Journal::flush`).
+pub fn flush() {
+    if let Some(journal) = JOURNAL.get() {
+        journal.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(name: &str) -> PortJournalConfig {
+        let dir = std::env::temp_dir().join(format!("media-port-journal-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        PortJournalConfig { enabled: true, path: dir.join("ports.log").display().to_string(), max_file_bytes: 4096, max_files: 2, queue_size: 8, recent_entries: 4 }
+    }
+
+    fn record(at_ms: u64, event: JournalEvent, port: u16, session_id: &str, remote: Option<&str>) -> JournalRecord {
+        JournalRecord {
+            at_ms, event, port, session_id: session_id.to_string(), call_id: format!("call-{}", session_id), tenant: String::new(),
+            remote: remote.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn tenancies_follow_allocation_latching_release_and_restarts() {
+        use JournalEvent::*;
+        let records = [
+            record(1_000, Allocated, 41230, "a", None),
+            record(1_200, Latched, 41230, "a", Some("192.0.2.10:4000")),
+            record(1_300, Allocated, 41232, "x", None),
+            record(1_500, Latched, 41230, "a", Some("192.0.2.11:4000")),
+            record(2_000, Released, 41230, "a", Some("192.0.2.11:4000")),
+            record(3_000, Allocated, 41230, "b", Some("198.51.100.7:5000")),
+            JournalRecord::started(std::time::UNIX_EPOCH + std::time::Duration::from_millis(9_000)),
+            // Tahsis satırı döndürülerek silinmiş bir oturumun bırakması.
+            record(9_500, Latched, 41230, "c", Some("203.0.113.1:6000")),
+        ];
+        let all = tenancies(&records, 41230, 0, 10_000);
+        assert_eq!(all.iter().map(|t| t.session_id.as_str()).collect::<Vec<_>>(), ["c", "a", "b"]);
+        assert_eq!(all[1].remotes, ["192.0.2.10:4000", "192.0.2.11:4000"]);
+        assert_eq!((all[1].allocated_at_ms, all[1].released_at_ms, all[1].ended_by_restart), (Some(1_000), Some(2_000), false));
+        assert_eq!((all[2].released_at_ms, all[2].ended_by_restart, all[2].call_id.as_str()), (Some(9_000), true, "call-b"));
+        assert_eq!((all[0].allocated_at_ms, all[0].released_at_ms), (None, None));
+
+        // 14:05'te portta kim vardı: yalnızca o anı kapsayanlar.
+        let at = tenancies(&records, 41230, 2_500, 2_500);
+        assert_eq!(at.iter().map(|t| t.session_id.as_str()).collect::<Vec<_>>(), ["c"]);
+        let at = tenancies(&records, 41230, 3_500, 3_500);
+        assert_eq!(at.iter().map(|t| t.session_id.as_str()).collect::<Vec<_>>(), ["c", "b"]);
+        assert!(tenancies(&records, 41234, 0, 10_000).is_empty());
+    }
+
+    #[test]
+    fn rotated_files_and_unwritten_records_are_all_queried() {
+        let config = config("query");
+        let mut writer = JournalWriter::new(&config);
+        let long = |at_ms, session_id: &str| JournalRecord { call_id: "x".repeat(1500), ..record(at_ms, JournalEvent::Allocated, 41000, session_id, None) };
+        for n in 0..8u64 {
+            writer.write(&long(n, &format!("s{}", n))).unwrap();
+        }
+        let dir = writer.path().parent().unwrap().to_path_buf();
+        let mut names: Vec<String> = fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name().into_string().unwrap()).collect();
+        names.sort();
+        // İki döndürülmüş dosya saklanır; en eski iki kayıt silinir.
+        assert_eq!(names, ["ports.log", "ports.log.1", "ports.log.2"]);
+        fs::write(writer.path(), fs::read_to_string(writer.path()).unwrap() + "{\"at_ms\": 9, \"ev").unwrap();
+
+        let journal = Journal::start(&config).unwrap();
+        journal.flush();
+        // Bellekte kalan ama yazılamayan kayıt da görülür.
+        journal.submit(record(20, JournalEvent::Released, 41000, "s7", Some("192.0.2.1:4000")));
+        let history = journal.history(41000, 0, 100).unwrap();
+        assert_eq!(history.iter().map(|t| t.session_id.as_str()).collect::<Vec<_>>(), ["s2", "s3", "s4", "s5", "s6", "s7"]);
+        // Açılış satırı önceki süreçten açık kalan oturumları bitirir.
+        assert!(history[..5].iter().all(|t| t.ended_by_restart));
+        assert_eq!((history[5].released_at_ms, history[5].ended_by_restart), (Some(20), false));
+        assert!(matches!(journal.history(41000, 100, 0), Err(PortJournalError::InvalidRange { .. })));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,270 @@
+// Anons önbelleği: çalınan anonsların çözülmüş (kazancı uygulanmış) PCM örnekleri toplam bir bayt
+// bütçesiyle bellekte tutulur; bütçe aşılınca en uzun süredir çalınmayan girdi atılır. Karşılama
+// anonsu ve `preload = true` anonslar sabitlenir: hiç atılmaz ve bütçeden yer kaplar, bütçeyi tek
+// başlarına doldururlarsa diğer girdiler eklendikleri anda atılır. Dosyası
+// `cache_max_entry_bytes`'tan büyük anonslar önbelleğe alınmaz, her çalmada diskten akıtılır.
+// Anahtar dosya yolu ve kazançtır; aynı dosyayı aynı kazançla çalan anonslar tek girdiyi paylaşır.
+// Oynatma örneklerin kendi kopyasını tuttuğundan atılan girdi çalmakta olan oynatmayı kesmez.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+use crate::metrics;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    path: String,
+    gain_bits: u32,
+}
+
+impl CacheKey {
+    pub fn new(path: &str, gain_db: f32) -> Self {
+        CacheKey { path: path.to_string(), gain_bits: gain_db.to_bits() }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    samples: Arc<Vec<i16>>,
+    bytes: u64,
+    pinned: bool,
+    // Son kullanıldığı anın sırası; en küçüğü ilk atılır.
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<CacheKey, Entry>,
+    bytes: u64,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl State {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn add(&mut self, key: CacheKey, entry: Entry) {
+        let bytes = entry.bytes;
+        if let Some(replaced) = self.entries.insert(key, entry) {
+            self.forget(&replaced);
+        }
+        self.bytes += bytes;
+        metrics::get().announcement_cache_bytes.add(bytes as i64);
+        metrics::get().announcement_cache_entries.inc();
+    }
+
+    fn remove(&mut self, key: &CacheKey) -> bool {
+        match self.entries.remove(key) {
+            Some(entry) => {
+                self.forget(&entry);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn forget(&mut self, entry: &Entry) {
+        self.bytes -= entry.bytes;
+        metrics::get().announcement_cache_bytes.add(-(entry.bytes as i64));
+        metrics::get().announcement_cache_entries.dec();
+    }
+}
+
+/// GetServerStatus ve DumpState için önbelleğin anlık durumu.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct CacheStats {
+    pub bytes: u64,
+    pub max_bytes: u64,
+    pub entries: usize,
+    /// Karşılama ve `preload = true` anonsları; hiç atılmaz.
+    pub pinned_entries: usize,
+    pub hits: u64,
+    /// Önbellekte bulunmayan çalmalar; sınırı aşıp diskten akıtılanlar dahil.
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// Önbellekten çalınan anonsların oranı; hiç çalma yoksa 0.
+    pub fn hit_ratio(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PromptCache {
+    max_bytes: u64,
+    max_entry_bytes: u64,
+    state: Mutex<State>,
+}
+
+impl PromptCache {
+    pub fn new(max_bytes: u64, max_entry_bytes: u64) -> Self {
+        PromptCache { max_bytes, max_entry_bytes, state: Mutex::default() }
+    }
+
+    /// Dosyası `file_bytes` olan anons önbelleğe alınabilir mi; alınamıyorsa diskten akıtılır.
+    pub fn admits(&self, file_bytes: u64) -> bool {
+        self.max_bytes > 0 && file_bytes <= self.max_entry_bytes
+    }
+
+    /// Önbellekteki örnekler; isabet ya da ıska olarak sayılır.
+    pub fn get(&self, key: &CacheKey) -> Option<Arc<Vec<i16>>> {
+        let mut state = self.state.lock().unwrap();
+        let now = state.tick();
+        match state.entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = now;
+                let samples = entry.samples.clone();
+                state.hits += 1;
+                metrics::get().announcement_cache_hits.inc();
+                Some(samples)
+            }
+            None => {
+                state.misses += 1;
+                metrics::get().announcement_cache_misses.inc();
+                None
+            }
+        }
+    }
+
+    /// Anons önbellekte mi; kullanım sayılmaz.
+    pub fn contains(&self, key: &CacheKey) -> bool {
+        self.state.lock().unwrap().entries.contains_key(key)
+    }
+
+    /// Dosyadan okunan örnekleri ekler ve bütçeye dönülene kadar en uzun süredir kullanılmayan
+    /// girdileri atar. Aynı anonsu bu arada başka bir oynatma eklediyse onunki döner. Sınırdan
+    /// büyük örnekler eklenmez, çağıran yine de çalar.
+    pub fn insert(&self, key: CacheKey, samples: Arc<Vec<i16>>) -> Arc<Vec<i16>> {
+        let bytes = samples.len() as u64 * 2;
+        if self.max_bytes == 0 || bytes > self.max_entry_bytes {
+            return samples;
+        }
+        let mut state = self.state.lock().unwrap();
+        let now = state.tick();
+        if let Some(entry) = state.entries.get_mut(&key) {
+            entry.last_used = now;
+            return entry.samples.clone();
+        }
+        state.add(key, Entry { samples: samples.clone(), bytes, pinned: false, last_used: now });
+        self.evict(&mut state);
+        samples
+    }
+
+    /// Örnekleri sabitler; aynı anahtardaki girdinin yerine geçer ve hiç atılmaz.
+    pub fn pin(&self, key: CacheKey, samples: Arc<Vec<i16>>) {
+        let mut state = self.state.lock().unwrap();
+        let now = state.tick();
+        let bytes = samples.len() as u64 * 2;
+        state.add(key, Entry { samples, bytes, pinned: true, last_used: now });
+        self.evict(&mut state);
+    }
+
+    /// Dosyası değişen ya da silinen anonsun girdisini (sabitlenmiş olsa da) düşürür.
+    pub fn remove(&self, key: &CacheKey) {
+        self.state.lock().unwrap().remove(key);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats {
+            bytes: state.bytes,
+            max_bytes: self.max_bytes,
+            entries: state.entries.len(),
+            pinned_entries: state.entries.values().filter(|entry| entry.pinned).count(),
+            hits: state.hits,
+            misses: state.misses,
+            evictions: state.evictions,
+        }
+    }
+
+    fn evict(&self, state: &mut State) {
+        while state.bytes > self.max_bytes {
+            let oldest = state.entries.iter()
+                .filter(|(_, entry)| !entry.pinned)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            let Some(key) = oldest else { break };
+            state.remove(&key);
+            state.evictions += 1;
+            metrics::get().announcement_cache_evictions.inc();
+        }
+    }
+}
+
+impl Drop for PromptCache {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap();
+        metrics::get().announcement_cache_bytes.add(-(state.bytes as i64));
+        metrics::get().announcement_cache_entries.add(-(state.entries.len() as i64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> CacheKey {
+        CacheKey::new(name, 0.0)
+    }
+
+    fn samples(bytes: usize) -> Arc<Vec<i16>> {
+        Arc::new(vec![0; bytes / 2])
+    }
+
+    #[test]
+    fn over_budget_evicts_least_recently_used_and_keeps_pinned() {
+        let cache = PromptCache::new(1000, 600);
+        cache.pin(key("welcome"), samples(400));
+        cache.insert(key("a"), samples(200));
+        cache.insert(key("b"), samples(200));
+        assert!(cache.get(&key("a")).is_some());
+        cache.insert(key("c"), samples(200));
+        assert_eq!(cache.stats().bytes, 1000);
+
+        // b en uzun süredir kullanılmayandır; a az önce çalındı.
+        cache.insert(key("d"), samples(200));
+        assert!(!cache.contains(&key("b")));
+        assert!(["welcome", "a", "c", "d"].iter().all(|name| cache.contains(&key(name))));
+
+        // Büyük girdi yer açmak için bütün sabitlenmemiş girdileri atar; sabitlenmiş kalır.
+        cache.insert(key("e"), samples(600));
+        assert!(cache.contains(&key("welcome")) && cache.contains(&key("e")));
+        assert_eq!(cache.get(&key("missing")), None);
+        assert_eq!(cache.stats(), CacheStats {
+            bytes: 1000, max_bytes: 1000, entries: 2, pinned_entries: 1, hits: 1, misses: 1, evictions: 4,
+        });
+        assert_eq!(cache.stats().hit_ratio(), 0.5);
+
+        // Sınırdan büyük örnekler çalınır ama tutulmaz.
+        assert!(!cache.admits(601));
+        assert_eq!(cache.insert(key("big"), samples(800)).len(), 400);
+        assert!(!cache.contains(&key("big")));
+
+        // Bütçeyi tek başına dolduran sabitlenmiş girdi diğerlerini atar, kendisi kalır.
+        cache.pin(key("welcome"), samples(1200));
+        assert_eq!((cache.stats().bytes, cache.stats().entries), (1200, 1));
+        assert_eq!(cache.insert(key("f"), samples(200)).len(), 100);
+        assert!(!cache.contains(&key("f")));
+    }
+
+    #[test]
+    fn zero_budget_keeps_only_pinned_entries() {
+        let cache = PromptCache::new(0, 600);
+        assert!(!cache.admits(1));
+        cache.pin(key("welcome"), samples(400));
+        cache.insert(key("a"), samples(200));
+        assert_eq!(cache.stats().entries, 1);
+        assert!(cache.get(&key("welcome")).is_some());
+    }
+}
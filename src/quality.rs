@@ -0,0 +1,171 @@
+// Çağrı kalitesi tahmini: kayıp örüntüsü (RFC 3611 4.7.2 burst/gap ölçümleri) ve ITU-T G.107
+// E-modeliyle R faktörü ve MOS.
+
+/// RFC 3611'in önerdiği Gmin: arasında en az bu kadar alınmış paket olan kayıplar ayrı "gap"
+/// kayıplarıdır, daha sıkışıkları bir "burst" oluşturur.
+pub const GMIN: u64 = 16;
+
+// G.711 için G.113 Ek I değerleri: ekipman bozulması 0, PLC ile kayıp dayanıklılığı 25.1.
+const G711_IE: f64 = 0.0;
+const G711_BPL: f64 = 25.1;
+// G.107 varsayılanlarıyla Ro - Is - A.
+const R_DEFAULT: f64 = 93.2;
+
+/// RFC 3611 Ek A.2'deki durum makinesi; paketler sıra numarası sırasıyla alındı veya kayıp
+/// olarak işlenir. Aynı sayımlardan G.107'nin iki durumlu kayıp modeli için BurstR çıkar.
+#[derive(Debug, Default)]
+pub struct BurstTracker {
+    // Son kayıptan beri alınan paketler ve içinde bulunulan burst'teki kayıplar.
+    pkt: u64,
+    lost: u64,
+    c11: u64,
+    c13: u64,
+    c14: u64,
+    c22: u64,
+    c23: u64,
+    c33: u64,
+    received: u64,
+    losses: u64,
+    // İki durumlu model geçişleri: alındı -> kayıp ve kayıp -> alındı.
+    received_to_lost: u64,
+    lost_to_received: u64,
+}
+
+/// Bir ölçüm anındaki RFC 3611 burst/gap değerleri.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BurstMetrics {
+    /// Burst dönemlerinde kayıp oranı, 0..=1.
+    pub burst_density: f64,
+    /// Gap dönemlerinde kayıp oranı, 0..=1.
+    pub gap_density: f64,
+    pub burst_duration_ms: u64,
+    pub gap_duration_ms: u64,
+}
+
+impl BurstTracker {
+    /// Bir paketin alındığını, öncesinde `missing` paketin kaybolduğunu kaydeder.
+    pub fn observe(&mut self, missing: u64) {
+        if missing > 0 {
+            self.lose();
+            // Aynı boşluktaki diğer kayıplar arada alınan paket olmadan gelir.
+            self.lost += missing - 1;
+            self.c33 += missing - 1;
+            self.losses += missing;
+            // Boşluk bu paketle kapanır: bir alındı -> kayıp, bir kayıp -> alındı geçişi.
+            self.received_to_lost += 1;
+            self.lost_to_received += 1;
+        }
+        self.pkt += 1;
+        self.received += 1;
+    }
+
+    fn lose(&mut self) {
+        if self.pkt >= GMIN {
+            if self.lost == 1 { self.c14 += 1 } else { self.c13 += 1 }
+            self.lost = 1;
+            self.c11 += self.pkt;
+        } else {
+            self.lost += 1;
+            if self.pkt == 0 {
+                self.c33 += 1;
+            } else {
+                self.c23 += 1;
+                self.c22 += self.pkt - 1;
+            }
+        }
+        self.pkt = 0;
+    }
+
+    /// `packet_ms` paket başına süredir (ptime).
+    pub fn metrics(&self, packet_ms: u64) -> BurstMetrics {
+        // Son kayıptan sonraki alınan paketler henüz c11'e eklenmemiş gap'e aittir.
+        let c11 = self.c11 + self.pkt;
+        let (c13, c14, c22, c23, c33) = (self.c13, self.c14, self.c22, self.c23, self.c33);
+        let (c31, c32) = (c13, c23);
+        let total = c11 + c14 + c13 + c22 + c23 + c31 + c32 + c33;
+        let ratio = |a: u64, b: u64| if b == 0 { 0.0 } else { a as f64 / b as f64 };
+
+        let p32 = ratio(c32, c31 + c32 + c33);
+        let p23 = if c22 + c23 == 0 { 1.0 } else { 1.0 - ratio(c22, c22 + c23) };
+        let burst_density = if c13 == 0 && c23 == 0 && c33 == 0 { 0.0 } else { p23 / (p23 + p32) };
+        let gap_density = ratio(c14, c11 + c14);
+        let (gap_duration_ms, burst_duration_ms) = match ((c11 + c14 + c13) * packet_ms).checked_div(c13) {
+            Some(gap) => (gap, (total * packet_ms / c13).saturating_sub(gap)),
+            // Hiç burst yoksa bütün akış tek bir gap'tir.
+            None => (total * packet_ms, 0),
+        };
+        BurstMetrics { burst_density, gap_density, burst_duration_ms, gap_duration_ms }
+    }
+
+    /// G.107 BurstR: 1 rastgele kayıp, büyük değerler kümelenmiş kayıp demektir.
+    pub fn burst_ratio(&self) -> f64 {
+        if self.losses == 0 || self.received == 0 {
+            return 1.0;
+        }
+        let p = self.received_to_lost as f64 / self.received as f64;
+        let q = self.lost_to_received as f64 / self.losses as f64;
+        if p + q == 0.0 { 1.0 } else { (1.0 / (p + q)).max(1.0) }
+    }
+}
+
+/// E-modeli R faktörü (G.107, G.711 varsayılanları). `loss` kayıp ve atılma oranı (0..=1),
+/// `one_way_delay_ms` ağ, jitter tamponu ve paketleme dahil ağızdan kulağa gecikme.
+pub fn r_factor(loss: f64, burst_ratio: f64, one_way_delay_ms: f64) -> f64 {
+    let ppl = loss.clamp(0.0, 1.0) * 100.0;
+    let ie_eff = G711_IE + (95.0 - G711_IE) * ppl / (ppl / burst_ratio.max(1.0) + G711_BPL);
+    let d = one_way_delay_ms.max(0.0);
+    let id = 0.024 * d + if d > 177.3 { 0.11 * (d - 177.3) } else { 0.0 };
+    (R_DEFAULT - id - ie_eff).clamp(0.0, 100.0)
+}
+
+/// G.107 Ek B ile R faktöründen MOS (1..=4.5).
+pub fn mos(r: f64) -> f64 {
+    if r <= 0.0 {
+        1.0
+    } else if r >= 100.0 {
+        4.5
+    } else {
+        1.0 + 0.035 * r + r * (r - 60.0) * (100.0 - r) * 7e-6
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emodel_matches_reference_points() {
+        // Kayıpsız, gecikmesiz G.711: R = 93.2, MOS ~4.41.
+        assert!((r_factor(0.0, 1.0, 0.0) - 93.2).abs() < 1e-9);
+        assert!((mos(93.2) - 4.41).abs() < 0.01);
+        // %2 rastgele kayıp Ie_eff'i ~7.0 artırır; kümelenmiş kayıp daha kötüdür.
+        let random = r_factor(0.02, 1.0, 0.0);
+        assert!((random - 86.19).abs() < 0.01, "{random}");
+        assert!(r_factor(0.02, 3.0, 0.0) < random);
+        // 300 ms gecikme 177.3 ms eşiğini aşar.
+        assert!((r_factor(0.0, 1.0, 300.0) - (93.2 - 7.2 - 13.497)).abs() < 0.01);
+        assert_eq!((mos(-5.0), mos(120.0)), (1.0, 4.5));
+    }
+
+    #[test]
+    fn burst_tracker_separates_bursts_from_isolated_loss() {
+        let mut tracker = BurstTracker::default();
+        // 50 paket, tek kayıp, 50 paket; sonra sıkışık 3 kayıp (2 + 1) ve 40 paket.
+        for n in 0..50 { tracker.observe(u64::from(n == 49)); }
+        for _ in 0..50 { tracker.observe(0); }
+        tracker.observe(2);
+        tracker.observe(0);
+        tracker.observe(1);
+        for _ in 0..40 { tracker.observe(0); }
+
+        let metrics = tracker.metrics(20);
+        assert!(metrics.gap_density > 0.0 && metrics.gap_density < 0.02, "{metrics:?}");
+        assert!(metrics.burst_density > 0.5, "{metrics:?}");
+        assert!(metrics.burst_duration_ms > 0 && metrics.burst_duration_ms < metrics.gap_duration_ms, "{metrics:?}");
+        assert!(tracker.burst_ratio() > 1.0);
+
+        let clean = BurstTracker::default();
+        assert_eq!(clean.metrics(20).burst_density, 0.0);
+        assert_eq!(clean.burst_ratio(), 1.0);
+    }
+}
@@ -0,0 +1,136 @@
+// Jeton kovası hız sınırlayıcı. AllocatePort isteklerini soket bağlamadan önce, oturumların gelen
+// paket selini ayrıştırmadan önce keser; kova `burst` jetonla dolu başlar ve saniyede `rate` jeton
+// dolar. Zaman `tokio::time` ile ölçülür.
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate_per_s: u32, burst: u32) -> Self {
+        Self { rate: rate_per_s as f64, burst: burst as f64, tokens: burst as f64, updated: Instant::now() }
+    }
+
+    /// Bir jeton harcar; kova boşsa bir sonraki jetonun birikmesine kalan süreyi döner.
+    pub fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+    }
+}
+
+// Bu kadar süre paket atılmazsa sel bitmiş sayılır.
+const FLOOD_QUIET: Duration = Duration::from_secs(1);
+
+/// Gelen bir paket için sel korumasının kararı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inbound {
+    Pass,
+    Drop,
+    /// Bir sınırlama dönemi bu paketle başladı; paket atılır.
+    Engaged,
+    /// Sınırlama `trip_after` boyunca durulmadı; paket atılır.
+    Tripped { flooding: Duration },
+}
+
+/// Oturumun gelen paketleri için jeton kovası ve sel süresinin takibi.
+#[derive(Debug)]
+pub struct FloodGuard {
+    bucket: TokenBucket,
+    trip_after: Option<Duration>,
+    // Süren sınırlama döneminin başı ve son atılan paketin zamanı.
+    limiting: Option<(Instant, Instant)>,
+}
+
+impl FloodGuard {
+    pub fn new(packets_per_s: u32, burst: u32, trip_after: Option<Duration>) -> Self {
+        FloodGuard { bucket: TokenBucket::new(packets_per_s, burst), trip_after, limiting: None }
+    }
+
+    pub fn admit(&mut self, now: Instant) -> Inbound {
+        let ongoing = self.limiting.filter(|&(_, last)| now.saturating_duration_since(last) < FLOOD_QUIET);
+        if self.bucket.try_acquire(now).is_ok() {
+            self.limiting = ongoing;
+            return Inbound::Pass;
+        }
+        let Some((since, _)) = ongoing else {
+            self.limiting = Some((now, now));
+            return Inbound::Engaged;
+        };
+        self.limiting = Some((since, now));
+        let flooding = now.saturating_duration_since(since);
+        match self.trip_after {
+            Some(trip_after) if flooding >= trip_after => Inbound::Tripped { flooding },
+            _ => Inbound::Drop,
+        }
+    }
+
+    /// Sel kaynağı engellendikten sonra yeni bir dönem başlatır.
+    pub fn reset(&mut self) {
+        self.limiting = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn burst_is_spent_then_refills_at_the_rate() {
+        let mut bucket = TokenBucket::new(10, 3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(bucket.try_acquire(now), Ok(()));
+        }
+        assert_eq!(bucket.try_acquire(now), Err(Duration::from_millis(100)));
+
+        // 250 ms'de iki jeton birikir, yarım jeton kalır.
+        let later = now + Duration::from_millis(250);
+        assert_eq!(bucket.try_acquire(later), Ok(()));
+        assert_eq!(bucket.try_acquire(later), Ok(()));
+        assert_eq!(bucket.try_acquire(later), Err(Duration::from_millis(50)));
+
+        // Uzun bir boşluk kovayı `burst`'ün üstüne çıkarmaz.
+        let idle = later + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(bucket.try_acquire(idle), Ok(()));
+        }
+        assert!(bucket.try_acquire(idle).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn jitter_bursts_pass_and_a_sustained_flood_trips_once() {
+        let mut guard = FloodGuard::new(50, 10, Some(Duration::from_secs(2)));
+        let start = Instant::now();
+        // Bir saniyelik gecikmeden sonra art arda gelen 10 paket ve normal tempo.
+        assert!((0..10).all(|_| guard.admit(start) == Inbound::Pass));
+        assert!((1..=50).all(|i| guard.admit(start + Duration::from_millis(20 * i)) == Inbound::Pass));
+
+        // Milisaniyede bir paket: kova boşalınca tek bir başlangıç, sonra atılır ve geçenler serpiştirilir.
+        let flood_start = start + Duration::from_secs(2);
+        let verdicts: Vec<Inbound> = (0..3000).map(|ms| guard.admit(flood_start + Duration::from_millis(ms))).collect();
+        assert_eq!(verdicts.iter().filter(|v| **v == Inbound::Engaged).count(), 1);
+        let passed = verdicts.iter().filter(|v| **v == Inbound::Pass).count();
+        assert!((150..=170).contains(&passed), "{}", passed);
+        let tripped = verdicts.iter().position(|v| matches!(v, Inbound::Tripped { .. })).unwrap();
+        assert!((2000..2020).contains(&tripped), "{}", tripped);
+
+        // Sel durulunca dönem biter; yeni bir sel yeniden başlangıç sayılır.
+        let calm = flood_start + Duration::from_secs(5);
+        assert_eq!(guard.admit(calm), Inbound::Pass);
+        assert!((1..10).all(|_| guard.admit(calm) == Inbound::Pass));
+        assert_eq!(guard.admit(calm), Inbound::Engaged);
+    }
+}
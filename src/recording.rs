@@ -0,0 +1,591 @@
+// Oturum kaydı: gelen ses çözülüp 16 bit PCM WAV, G.711 (µ-law ya da A-law) WAV ya da başlıksız
+// µ-law dosyasına yazılır. G.711 biçimlerinde oturum aynı codec'teyse paketin yükü çözülmeden
+// yazılır. Dosya adı config'deki ya da isteğin verdiği şablondan üretilir; `max_duration_s` dolunca numaralı devam dosyasına geçilir,
+// dizin `max_total_bytes`'a ulaşınca kayıt durur. Mevcut dosyalar hiçbir zaman silinmez veya
+// ezilmez. Yazma ayrı bir görevde yapılır; medya yolu yalnızca kanala `try_send` yapar. Anahtar
+// verilirse dosyalar `encryption` kabında, adlarının sonuna `.enc` eklenerek yazılır.
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn, Span};
+
+use crate::audit::{self, RecordingStopReason};
+use crate::codec;
+use crate::config::{RecordingConfig, RecordingFormat};
+use crate::encryption::{self, EncryptedWav, RecordingKey};
+use crate::error::RecordingError;
+
+// Yaklaşık 5 saniyelik 20 ms çerçeve.
+const CHANNEL_CAPACITY: usize = 256;
+const WAV_HEADER_LEN: u64 = 44;
+// G.711 WAV: 18 baytlık fmt (cbSize dahil) ve örnek sayısını taşıyan fact parçaları.
+const G711_WAV_HEADER_LEN: u64 = 58;
+const WAVE_FORMAT_ALAW: u16 = 6;
+const WAVE_FORMAT_MULAW: u16 = 7;
+
+/// Dosya adı şablonundaki yer tutucuların değerleri.
+#[derive(Debug, Clone, Copy)]
+pub struct NameVars<'a> {
+    pub session_id: &'a str,
+    pub call_id: &'a str,
+    pub port: u16,
+    /// Kaydın başladığı an, Unix saniyesi.
+    pub start_time: u64,
+}
+
+/// Şablonu doldurup kayıt dizinine göre görece bir yol üretir. Uzantı yoksa `extension` eklenir.
+pub fn render(template: &str, vars: &NameVars, extension: &str) -> Result<PathBuf, RecordingError> {
+    let unknown = |placeholder: &str| RecordingError::UnknownPlaceholder { template: template.to_string(), placeholder: placeholder.to_string() };
+    let mut name = String::with_capacity(template.len() + 32);
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        name.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let close = after.find('}').ok_or_else(|| unknown(after))?;
+        match &after[..close] {
+            "session_id" => name.push_str(vars.session_id),
+            "call_id" => name.push_str(vars.call_id),
+            "port" => name.push_str(&vars.port.to_string()),
+            "start_time" => name.push_str(&vars.start_time.to_string()),
+            other => return Err(unknown(other)),
+        }
+        rest = &after[close + 1..];
+    }
+    name.push_str(rest);
+
+    // call_id sinyalleşmeden gelir; ne içerirse içersin yol dizinin dışına çıkmamalı.
+    let path = PathBuf::from(&name);
+    if name.is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(RecordingError::InvalidName { name });
+    }
+    Ok(if path.extension().is_none() { path.with_extension(extension) } else { path })
+}
+
+/// Config doğrulaması için: şablon örnek değerlerle geçerli bir yol üretiyor mu.
+pub fn validate_template(template: &str) -> Result<(), RecordingError> {
+    render(template, &NameVars { session_id: "0123456789abcdef", call_id: "call", port: 10000, start_time: 0 }, "wav").map(drop)
+}
+
+/// `x.wav` kaydının `part`. dosyası: `x-2.wav`, `x-3.wav`, ...
+fn continuation(path: &Path, part: u32) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(ext) => path.with_file_name(format!("{}-{}.{}", stem, part, ext.to_string_lossy())),
+        None => path.with_file_name(format!("{}-{}", stem, part)),
+    }
+}
+
+/// Dizindeki (alt dizinler dahil) dosyaların toplam boyutu; okunamayanlar sayılmaz.
+fn directory_usage(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else { return 0 };
+    entries.flatten().map(|entry| match entry.metadata() {
+        Ok(meta) if meta.is_dir() => directory_usage(&entry.path()),
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    }).sum()
+}
+
+/// Dosyadaki bir örneğin boyutu (bayt).
+fn bytes_per_sample(format: RecordingFormat) -> u64 {
+    match format {
+        RecordingFormat::Pcm16Wav => 2,
+        _ => 1,
+    }
+}
+
+fn header_len(format: RecordingFormat) -> u64 {
+    match format {
+        RecordingFormat::Pcm16Wav => WAV_HEADER_LEN,
+        RecordingFormat::UlawWav | RecordingFormat::AlawWav => G711_WAV_HEADER_LEN,
+        RecordingFormat::RawUlaw => 0,
+    }
+}
+
+// Yazıcıya giden bir çerçeve.
+enum Frame {
+    Pcm(Vec<i16>),
+    // Dosyanın G.711 yasasıyla kodlanmış gelen yük; olduğu gibi yazılır.
+    G711(Vec<u8>),
+}
+
+impl Frame {
+    fn len(&self) -> usize {
+        match self {
+            Frame::Pcm(pcm) => pcm.len(),
+            Frame::G711(bytes) => bytes.len(),
+        }
+    }
+}
+
+/// G.711 WAV başlığı; `data_len` tek sayıysa veri parçasının sonuna bir dolgu baytı gelir.
+fn g711_wav_header(format_tag: u16, sample_rate: u32, data_len: u32) -> [u8; G711_WAV_HEADER_LEN as usize] {
+    let mut header = [0u8; G711_WAV_HEADER_LEN as usize];
+    let mut at = 0;
+    let mut put = |bytes: &[u8]| {
+        header[at..at + bytes.len()].copy_from_slice(bytes);
+        at += bytes.len();
+    };
+    put(b"RIFF");
+    put(&(G711_WAV_HEADER_LEN as u32 - 8 + data_len + data_len % 2).to_le_bytes());
+    put(b"WAVEfmt ");
+    put(&18u32.to_le_bytes());
+    put(&format_tag.to_le_bytes());
+    put(&1u16.to_le_bytes());
+    put(&sample_rate.to_le_bytes());
+    // Bayt hızı, blok hizası, örnek başına bit ve ek bilgi boyutu (cbSize).
+    put(&sample_rate.to_le_bytes());
+    put(&1u16.to_le_bytes());
+    put(&8u16.to_le_bytes());
+    put(&0u16.to_le_bytes());
+    put(b"fact");
+    put(&4u32.to_le_bytes());
+    put(&data_len.to_le_bytes());
+    put(b"data");
+    put(&data_len.to_le_bytes());
+    header
+}
+
+// G.711 kayıt dosyası; WAV başlığı boyutlar belli olunca kapanışta yazılır.
+struct G711Writer {
+    file: BufWriter<File>,
+    format: RecordingFormat,
+    sample_rate: u32,
+    data_len: u32,
+}
+
+impl G711Writer {
+    fn create(file: File, format: RecordingFormat, sample_rate: u32) -> io::Result<Self> {
+        let mut file = BufWriter::new(file);
+        file.write_all(&vec![0; header_len(format) as usize])?;
+        Ok(G711Writer { file, format, sample_rate, data_len: 0 })
+    }
+
+    fn write(&mut self, frame: &Frame, range: Range<usize>) -> io::Result<()> {
+        let encode = match self.format {
+            RecordingFormat::AlawWav => codec::pcm16_to_g711_alaw,
+            _ => codec::pcm16_to_g711_ulaw,
+        };
+        match frame {
+            Frame::Pcm(pcm) => {
+                let bytes: Vec<u8> = pcm[range.clone()].iter().map(|&sample| encode(sample)).collect();
+                self.file.write_all(&bytes)?;
+            }
+            Frame::G711(bytes) => self.file.write_all(&bytes[range.clone()])?,
+        }
+        self.data_len += range.len() as u32;
+        Ok(())
+    }
+
+    fn finalize(mut self) -> io::Result<()> {
+        let format_tag = match self.format {
+            RecordingFormat::UlawWav => WAVE_FORMAT_MULAW,
+            RecordingFormat::AlawWav => WAVE_FORMAT_ALAW,
+            _ => return self.file.flush(),
+        };
+        if self.data_len % 2 == 1 {
+            self.file.write_all(&[0])?;
+        }
+        let mut file = self.file.into_inner().map_err(io::IntoInnerError::into_error)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&g711_wav_header(format_tag, self.sample_rate, self.data_len))
+    }
+}
+
+// Açık kayıt dosyası: düz WAV, G.711 dosyası ya da şifreli kap.
+enum Sink {
+    Wav(WavWriter<BufWriter<File>>),
+    G711(G711Writer),
+    Encrypted(EncryptedWav),
+}
+
+impl Sink {
+    fn write(&mut self, frame: &Frame, range: Range<usize>) -> io::Result<()> {
+        match (self, frame) {
+            (Sink::Wav(writer), Frame::Pcm(pcm)) => pcm[range].iter().try_for_each(|&sample| writer.write_sample(sample)).map_err(io::Error::other),
+            (Sink::Encrypted(writer), Frame::Pcm(pcm)) => writer.write_samples(&pcm[range]),
+            (Sink::G711(writer), frame) => writer.write(frame, range),
+            (_, Frame::G711(_)) => Err(io::Error::other("G.711 audio sent to a PCM recording")),
+        }
+    }
+
+    fn finalize(self) -> io::Result<()> {
+        match self {
+            Sink::Wav(writer) => writer.finalize().map_err(io::Error::other),
+            Sink::G711(writer) => writer.finalize(),
+            Sink::Encrypted(writer) => writer.finalize(),
+        }
+    }
+}
+
+/// Kaydın diskteki adı; şifreliyse `.enc` eklenir.
+fn on_disk(path: &Path, key: Option<&Arc<RecordingKey>>) -> PathBuf {
+    match key {
+        Some(_) => encryption::encrypted_path(path),
+        None => path.to_path_buf(),
+    }
+}
+
+fn create(path: &Path, format: RecordingFormat, sample_rate: u32, key: Option<&Arc<RecordingKey>>) -> Result<Sink, RecordingError> {
+    let io_error = |source: io::Error| match source.kind() {
+        io::ErrorKind::AlreadyExists => RecordingError::FileExists { path: path.display().to_string() },
+        _ => RecordingError::Io { path: path.display().to_string(), source },
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    // Var olan dosya ezilmez.
+    if let Some(key) = key {
+        return EncryptedWav::create(path, key.clone(), sample_rate).map(Sink::Encrypted).map_err(io_error);
+    }
+    let file = File::create_new(path).map_err(io_error)?;
+    if format != RecordingFormat::Pcm16Wav {
+        return G711Writer::create(file, format, sample_rate).map(Sink::G711).map_err(io_error);
+    }
+    let spec = WavSpec { channels: 1, sample_rate, bits_per_sample: 16, sample_format: SampleFormat::Int };
+    WavWriter::new(BufWriter::new(file), spec).map(Sink::Wav).map_err(|e| RecordingError::Io { path: path.display().to_string(), source: io::Error::other(e) })
+}
+
+#[derive(Debug, Default)]
+struct Progress {
+    paths: Vec<String>,
+    samples: u64,
+}
+
+/// Biten kaydın dosyaları, sırayla, ve toplam ses süresi.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordingSummary {
+    pub paths: Vec<String>,
+    pub duration: Duration,
+    /// Şifreli kayıtta anahtarın kimliği.
+    pub key_id: Option<String>,
+}
+
+// Yazıcı görevinin sınırları.
+struct Plan {
+    path: PathBuf,
+    format: RecordingFormat,
+    sample_rate: u32,
+    // Dosya başına örnek; dolunca devam dosyasına geçilir.
+    max_samples: Option<u64>,
+    // Kayıt başladığında dizin sınırına kalan bayt.
+    budget: Option<u64>,
+    key: Option<Arc<RecordingKey>>,
+}
+
+/// Süren bir kayda ses göndermek için tutamak.
+#[derive(Debug)]
+pub struct Recording {
+    format: RecordingFormat,
+    sample_rate: u32,
+    key_id: Option<String>,
+    tx: mpsc::Sender<Frame>,
+    progress: Arc<Mutex<Progress>>,
+    writer: JoinHandle<()>,
+}
+
+impl Recording {
+    /// İlk dosyayı oluşturur ve yazıcı görevini başlatır. `name` verilmezse config'deki şablon
+    /// kullanılır; verilirse o da aynı yer tutucularla bir şablondur. `key` verilirse dosyalar
+    /// şifrelenir; şifreli kap yalnızca 16 bit PCM taşır.
+    pub fn start(config: &RecordingConfig, name: Option<&str>, format: RecordingFormat, vars: &NameVars, sample_rate: u32, key: Option<Arc<RecordingKey>>) -> Result<Self, RecordingError> {
+        if key.is_some() && format != RecordingFormat::Pcm16Wav {
+            return Err(RecordingError::NotEncryptable { format: format.as_str() });
+        }
+        let relative = render(name.unwrap_or(&config.filename_template), vars, format.extension())?;
+        let directory = Path::new(&config.directory);
+        let limit = config.max_total_bytes();
+        let used = limit.map_or(0, |_| directory_usage(directory));
+        if let Some(limit) = limit.filter(|&limit| used >= limit) {
+            return Err(RecordingError::DiskQuotaExceeded { used, limit });
+        }
+        let path = directory.join(relative);
+        let writer = create(&on_disk(&path, key.as_ref()), format, sample_rate, key.as_ref())?;
+
+        let plan = Plan {
+            format,
+            sample_rate,
+            max_samples: config.max_duration().map(|d| d.as_secs() * sample_rate as u64),
+            budget: limit.map(|limit| limit - used),
+            path,
+            key,
+        };
+        let key_id = plan.key.as_ref().map(|key| key.id().to_string());
+        let first = on_disk(&plan.path, plan.key.as_ref());
+        let progress = Arc::new(Mutex::new(Progress { paths: vec![first.display().to_string()], samples: 0 }));
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let task_progress = progress.clone();
+        let span = Span::current();
+        let writer = tokio::task::spawn_blocking(move || span.in_scope(|| run_writer(writer, rx, plan, &task_progress)));
+        Ok(Recording { format, sample_rate, key_id, tx, progress, writer })
+    }
+
+    pub fn format(&self) -> RecordingFormat {
+        self.format
+    }
+
+    /// Kaydın ilk dosyası.
+    pub fn path(&self) -> String {
+        self.progress.lock().unwrap().paths[0].clone()
+    }
+
+    /// Şimdiye kadar açılan dosyalar.
+    pub fn paths(&self) -> Vec<String> {
+        self.progress.lock().unwrap().paths.clone()
+    }
+
+    /// Bir çerçevelik PCM'i kuyruğa ekler; yazıcı yetişemiyorsa çerçeve düşer.
+    pub fn record(&self, pcm: &[i16]) {
+        let _ = self.tx.try_send(Frame::Pcm(pcm.to_vec()));
+    }
+
+    /// Dosyanın G.711 yasasıyla (`RecordingFormat::g711_codec`) kodlanmış bir çerçeveyi çözmeden
+    /// kuyruğa ekler.
+    pub fn record_g711(&self, payload: &[u8]) {
+        let _ = self.tx.try_send(Frame::G711(payload.to_vec()));
+    }
+
+    /// Kuyrukta kalanları yazar, dosyaları kapatır ve sonucu döner.
+    pub async fn finish(self) -> RecordingSummary {
+        let Recording { sample_rate, key_id, tx, progress, writer, .. } = self;
+        drop(tx);
+        let _ = writer.await;
+        let progress = progress.lock().unwrap();
+        RecordingSummary {
+            paths: progress.paths.clone(),
+            duration: Duration::from_micros(progress.samples * 1_000_000 / sample_rate as u64),
+            key_id,
+        }
+    }
+
+    /// Beklemeden bitirir; dosyalar arka planda kapanır. Açılmış dosyaları döner.
+    pub fn close(self) -> Vec<String> {
+        self.paths()
+    }
+}
+
+fn run_writer(mut writer: Sink, mut rx: mpsc::Receiver<Frame>, plan: Plan, progress: &Mutex<Progress>) {
+    let mut path = on_disk(&plan.path, plan.key.as_ref());
+    let (header, sample_bytes) = (header_len(plan.format), bytes_per_sample(plan.format));
+    let (mut part, mut part_samples, mut written) = (1, 0u64, header);
+    let reason = 'run: loop {
+        let Some(frame) = rx.blocking_recv() else { break RecordingStopReason::Stopped };
+        let mut offset = 0;
+        while offset < frame.len() {
+            if plan.max_samples.is_some_and(|max| part_samples >= max) {
+                part += 1;
+                let next = on_disk(&continuation(&plan.path, part), plan.key.as_ref());
+                match create(&next, plan.format, plan.sample_rate, plan.key.as_ref()) {
+                    Ok(next_writer) => {
+                        if let Err(e) = std::mem::replace(&mut writer, next_writer).finalize() {
+                            warn!(file = %path.display(), error = %e, "Kayıt dosyası kapatılamadı");
+                        }
+                    }
+                    Err(e) => {
+                        warn!(file = %next.display(), error = %e, "Kayıt devam dosyası açılamadı, kayıt durduruldu");
+                        break 'run RecordingStopReason::WriteError;
+                    }
+                }
+                path = next;
+                progress.lock().unwrap().paths.push(path.display().to_string());
+                part_samples = 0;
+                written += header;
+            }
+            let left = frame.len() - offset;
+            let room = plan.max_samples.map_or(left, |max| left.min((max - part_samples) as usize));
+            if plan.budget.is_some_and(|budget| written + room as u64 * sample_bytes > budget) {
+                warn!(file = %path.display(), "Kayıt dizini boyut sınırına ulaştı, kayıt durduruldu");
+                break 'run RecordingStopReason::DiskQuota;
+            }
+            if let Err(e) = writer.write(&frame, offset..offset + room) {
+                warn!(file = %path.display(), error = %e, "Kayıt dosyasına yazılamadı, kayıt durduruldu");
+                break 'run RecordingStopReason::WriteError;
+            }
+            part_samples += room as u64;
+            written += room as u64 * sample_bytes;
+            progress.lock().unwrap().samples += room as u64;
+            offset += room;
+        }
+    };
+    if let Err(e) = writer.finalize() {
+        warn!(file = %path.display(), error = %e, "Kayıt dosyası kapatılamadı");
+    }
+    let progress = progress.lock().unwrap();
+    info!(
+        target: audit::TARGET, event = audit::RECORDING_STOPPED,
+        files = %progress.paths.join(","), duration_ms = progress.samples * 1000 / plan.sample_rate as u64, reason = reason.as_str(),
+        key_id = plan.key.as_ref().map(|key| key.id()),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VARS: NameVars = NameVars { session_id: "abc", call_id: "call-1", port: 10000, start_time: 1700000000 };
+
+    /// Test başına boş bir geçici dizin.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("media-recording-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn config(dir: &Path, max_duration_s: u64, max_total_bytes: u64) -> RecordingConfig {
+        RecordingConfig { directory: dir.display().to_string(), max_duration_s, max_total_bytes, ..RecordingConfig::default() }
+    }
+
+    fn samples(path: &str) -> usize {
+        hound::WavReader::open(path).unwrap().len() as usize
+    }
+
+    #[test]
+    fn template_fills_placeholders_and_stays_inside_the_directory() {
+        assert_eq!(render("{session_id}_{start_time}.wav", &VARS, "ulaw").unwrap(), Path::new("abc_1700000000.wav"));
+        assert_eq!(render("{port}/{call_id}", &VARS, "wav").unwrap(), Path::new("10000/call-1.wav"));
+        assert_eq!(render("{port}/{call_id}", &VARS, "ulaw").unwrap(), Path::new("10000/call-1.ulaw"));
+        assert!(matches!(render("{caller}.wav", &VARS, "wav"), Err(RecordingError::UnknownPlaceholder { placeholder, .. }) if placeholder == "caller"));
+        assert!(matches!(render("{port", &VARS, "wav"), Err(RecordingError::UnknownPlaceholder { .. })));
+        for name in ["/etc/x.wav", "../x.wav", "{call_id}/x", ""] {
+            let vars = NameVars { call_id: "..", ..VARS };
+            assert!(matches!(render(name, &vars, "wav"), Err(RecordingError::InvalidName { .. })), "{name}");
+        }
+        assert_eq!(continuation(Path::new("d/x.wav"), 2), Path::new("d/x-2.wav"));
+    }
+
+    #[tokio::test]
+    async fn long_recording_rotates_into_numbered_continuations() {
+        let dir = temp_dir("rotate");
+        let recording = Recording::start(&config(&dir, 1, 0), Some("call"), RecordingFormat::Pcm16Wav, &VARS, 8000, None).unwrap();
+        for _ in 0..20 {
+            recording.record(&[100; 1000]);
+        }
+        let summary = recording.finish().await;
+
+        let base = dir.join("call.wav").display().to_string();
+        assert_eq!(summary.paths, [base.clone(), dir.join("call-2.wav").display().to_string(), dir.join("call-3.wav").display().to_string()]);
+        assert_eq!(summary.paths.iter().map(|p| samples(p)).collect::<Vec<_>>(), [8000, 8000, 4000]);
+        assert_eq!(summary.duration, Duration::from_millis(2500));
+        // Aynı isimle ikinci kayıt mevcut dosyayı ezmez.
+        let again = Recording::start(&config(&dir, 1, 0), Some("call"), RecordingFormat::Pcm16Wav, &VARS, 8000, None);
+        assert!(matches!(again, Err(RecordingError::FileExists { path }) if path == base));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Her biçimde aynı sese ait tests/golden altındaki dosya; `MEDIA_WRITE_GOLDEN=1` fixture'ı
+    /// karşılaştırmak yerine yeniden yazar.
+    fn assert_fixture(path: &str, format: RecordingFormat) {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("recording-{}.{}", format.as_str(), format.extension()));
+        let written = fs::read(path).unwrap();
+        if std::env::var_os("MEDIA_WRITE_GOLDEN").is_some() {
+            fs::write(&fixture, &written).unwrap();
+        }
+        assert_eq!(written, fs::read(&fixture).unwrap(), "{} differs from {}", path, fixture.display());
+    }
+
+    #[tokio::test]
+    async fn every_format_matches_its_fixture_and_reports_the_duration() {
+        let dir = temp_dir("formats");
+        // Tek sayıda örnek: G.711 WAV'ın veri parçası dolgu baytıyla biter.
+        let audio: Vec<i16> = (0..801).map(|i| ((i * 397) % 16000) as i16 - 8000).collect();
+        for format in RecordingFormat::ALL {
+            let recording = Recording::start(&config(&dir, 0, 0), Some(format.as_str()), format, &VARS, 8000, None).unwrap();
+            for frame in audio.chunks(160) {
+                recording.record(frame);
+            }
+            let summary = recording.finish().await;
+            assert_eq!(summary.paths, [dir.join(format!("{}.{}", format.as_str(), format.extension())).display().to_string()]);
+            assert_eq!(summary.duration, Duration::from_micros(100_125));
+            assert_fixture(&summary.paths[0], format);
+
+            let file = fs::read(&summary.paths[0]).unwrap();
+            let (data, encode): (&[u8], fn(i16) -> u8) = match format {
+                RecordingFormat::Pcm16Wav => {
+                    assert_eq!(hound::WavReader::open(&summary.paths[0]).unwrap().into_samples::<i16>().map(Result::unwrap).collect::<Vec<_>>(), audio);
+                    continue;
+                }
+                RecordingFormat::RawUlaw => (&file, codec::pcm16_to_g711_ulaw),
+                RecordingFormat::UlawWav | RecordingFormat::AlawWav => {
+                    let tag = if format == RecordingFormat::UlawWav { WAVE_FORMAT_MULAW } else { WAVE_FORMAT_ALAW };
+                    assert_eq!(file[..G711_WAV_HEADER_LEN as usize], g711_wav_header(tag, 8000, 801));
+                    assert_eq!((&file[20..22], &file[34..36], file.len()), (&tag.to_le_bytes()[..], &8u16.to_le_bytes()[..], 58 + 802));
+                    let encode = if format == RecordingFormat::UlawWav { codec::pcm16_to_g711_ulaw } else { codec::pcm16_to_g711_alaw };
+                    (&file[G711_WAV_HEADER_LEN as usize..file.len() - 1], encode)
+                }
+            };
+            assert_eq!(data, audio.iter().map(|&sample| encode(sample)).collect::<Vec<_>>(), "{}", format.as_str());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn g711_recording_writes_encoded_audio_as_is_and_rotates() {
+        let dir = temp_dir("g711");
+        let recording = Recording::start(&config(&dir, 1, 0), Some("call"), RecordingFormat::UlawWav, &VARS, 8000, None).unwrap();
+        let payload: Vec<u8> = (0..=255).cycle().take(12_000).collect();
+        for frame in payload.chunks(160) {
+            recording.record_g711(frame);
+        }
+        let summary = recording.finish().await;
+
+        assert_eq!(summary.paths, [dir.join("call.wav").display().to_string(), dir.join("call-2.wav").display().to_string()]);
+        assert_eq!(summary.duration, Duration::from_millis(1500));
+        let (first, second) = (fs::read(&summary.paths[0]).unwrap(), fs::read(&summary.paths[1]).unwrap());
+        assert_eq!(first[..G711_WAV_HEADER_LEN as usize], g711_wav_header(WAVE_FORMAT_MULAW, 8000, 8000));
+        assert_eq!(second[..G711_WAV_HEADER_LEN as usize], g711_wav_header(WAVE_FORMAT_MULAW, 8000, 4000));
+        assert_eq!([&first[G711_WAV_HEADER_LEN as usize..], &second[G711_WAV_HEADER_LEN as usize..]].concat(), payload);
+
+        // Şifreli kap yalnızca PCM taşır.
+        #[cfg(feature = "recording-encryption")]
+        {
+            let key = Arc::new(RecordingKey::new("k1", &[7; 32]).unwrap());
+            let refused = Recording::start(&config(&dir, 0, 0), Some("enc"), RecordingFormat::RawUlaw, &VARS, 8000, Some(key));
+            assert!(matches!(refused, Err(RecordingError::NotEncryptable { format: "raw-ulaw" })));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "recording-encryption")]
+    #[tokio::test]
+    async fn encrypted_recording_rotates_under_enc_names_and_reports_the_key() {
+        let dir = temp_dir("encrypted");
+        let key = Arc::new(RecordingKey::new("k1", &[7; 32]).unwrap());
+        let recording = Recording::start(&config(&dir, 1, 0), Some("call"), RecordingFormat::Pcm16Wav, &VARS, 8000, Some(key.clone())).unwrap();
+        for _ in 0..12 {
+            recording.record(&[100; 1000]);
+        }
+        let summary = recording.finish().await;
+
+        assert_eq!(summary.paths, [dir.join("call.wav.enc").display().to_string(), dir.join("call-2.wav.enc").display().to_string()]);
+        assert_eq!(summary.key_id.as_deref(), Some("k1"));
+        let output = dir.join("call-2.wav");
+        let decrypted = encryption::decrypt_recording(Path::new(&summary.paths[1]), &output, &key).unwrap();
+        assert_eq!((decrypted.samples, decrypted.truncated), (4000, false));
+        assert!(!dir.join("call.wav").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn disk_limit_stops_recording_and_refuses_new_ones() {
+        let dir = temp_dir("quota");
+        let limit = WAV_HEADER_LEN + 2 * 3000;
+        let recording = Recording::start(&config(&dir, 0, limit), Some("first"), RecordingFormat::Pcm16Wav, &VARS, 8000, None).unwrap();
+        for _ in 0..5 {
+            recording.record(&[1; 1000]);
+        }
+        let summary = recording.finish().await;
+        // Sınırı aşacak çerçeve yazılmaz; dosya geçerli bir WAV olarak kapanır.
+        assert_eq!(samples(&summary.paths[0]), 3000);
+
+        let refused = Recording::start(&config(&dir, 0, limit), Some("second"), RecordingFormat::Pcm16Wav, &VARS, 8000, None);
+        assert!(matches!(refused, Err(RecordingError::DiskQuotaExceeded { used, limit: l }) if used == limit && l == limit));
+        assert!(dir.join("first.wav").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
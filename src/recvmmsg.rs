@@ -0,0 +1,83 @@
+// Linux recvmmsg(2): soketteki birden çok datagram tek sistem çağrısıyla okunur. Binlerce oturumda
+// her paket için ayrı recv_from çağrısı CPU'nun çoğunu sistem çağrılarına harcar. Çekirdek ya da
+// seccomp çağrıya izin vermiyorsa (ENOSYS, EPERM) süreç boyunca tek tek okumaya dönülür.
+// Paketler çekirdeğin alım kuyruğundaki sırayla gelir.
+use std::io;
+use std::mem;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::os::fd::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::io::Interest;
+use tokio::net::UdpSocket;
+use tracing::warn;
+
+use crate::transport::{RecvBatch, MAX_RECV_BATCH, RECV_BUFFER};
+
+static UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// Soket okunabilir olunca `batch` kadar paketi tek çağrıda okur ve sayısını döner; recvmmsg
+/// kullanılamıyorsa `None`, çağıran tek paket okur.
+pub async fn recv(sock: &UdpSocket, batch: &mut RecvBatch) -> Option<io::Result<usize>> {
+    if UNAVAILABLE.load(Ordering::Relaxed) {
+        return None;
+    }
+    let fd = sock.as_raw_fd();
+    match sock.async_io(Interest::READABLE, || recv_now(fd, batch)).await {
+        Err(e) if matches!(e.raw_os_error(), Some(libc::ENOSYS | libc::EPERM)) => {
+            if !UNAVAILABLE.swap(true, Ordering::Relaxed) {
+                warn!(error = %e, "recvmmsg kullanılamıyor, gelen paketler tek tek okunacak");
+            }
+            None
+        }
+        result => Some(result),
+    }
+}
+
+fn recv_now(fd: RawFd, batch: &mut RecvBatch) -> io::Result<usize> {
+    let count = batch.buffers.len().min(MAX_RECV_BATCH);
+    // SAFETY: üç dizi de sıfırla geçerli düz C yapılarıdır.
+    let mut addrs: [libc::sockaddr_storage; MAX_RECV_BATCH] = unsafe { mem::zeroed() };
+    let mut iovecs: [libc::iovec; MAX_RECV_BATCH] = unsafe { mem::zeroed() };
+    let mut headers: [libc::mmsghdr; MAX_RECV_BATCH] = unsafe { mem::zeroed() };
+    for (i, buffer) in batch.buffers.iter_mut().take(count).enumerate() {
+        iovecs[i] = libc::iovec { iov_base: buffer.as_mut_ptr().cast(), iov_len: RECV_BUFFER };
+        let header = &mut headers[i].msg_hdr;
+        header.msg_name = (&mut addrs[i] as *mut libc::sockaddr_storage).cast();
+        header.msg_namelen = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        header.msg_iov = &mut iovecs[i];
+        header.msg_iovlen = 1;
+    }
+    // SAFETY: başlıklar, adresler ve tamponlar çağrı boyunca yaşar; çekirdek en fazla `count`
+    // başlığı doldurur ve her birine kendi tamponunun uzunluğu kadar yazar.
+    let received = unsafe { libc::recvmmsg(fd, headers.as_mut_ptr(), count as libc::c_uint, libc::MSG_DONTWAIT, std::ptr::null_mut()) };
+    if received < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    batch.received.clear();
+    for (slot, header) in headers.iter().take(received as usize).enumerate() {
+        // IPv4 ya da IPv6 soketinde başka bir aile gelmez.
+        let Some(addr) = socket_addr(&addrs[slot]) else { continue };
+        // Tampondan büyük datagram recv_from'daki gibi kesilir.
+        batch.received.push((slot, (header.msg_len as usize).min(RECV_BUFFER), addr));
+    }
+    Ok(batch.received.len())
+}
+
+fn socket_addr(storage: &libc::sockaddr_storage) -> Option<SocketAddr> {
+    match storage.ss_family as libc::c_int {
+        libc::AF_INET => {
+            // SAFETY: aile AF_INET ise çekirdek bir sockaddr_in yazmıştır.
+            let addr = unsafe { &*(storage as *const libc::sockaddr_storage).cast::<libc::sockaddr_in>() };
+            let ip = Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr));
+            Some(SocketAddr::V4(SocketAddrV4::new(ip, u16::from_be(addr.sin_port))))
+        }
+        libc::AF_INET6 => {
+            // SAFETY: aile AF_INET6 ise çekirdek bir sockaddr_in6 yazmıştır.
+            let addr = unsafe { &*(storage as *const libc::sockaddr_storage).cast::<libc::sockaddr_in6>() };
+            let ip = Ipv6Addr::from(addr.sin6_addr.s6_addr);
+            Some(SocketAddr::V6(SocketAddrV6::new(ip, u16::from_be(addr.sin6_port), addr.sin6_flowinfo, addr.sin6_scope_id)))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,167 @@
+// RFC 2198 yedekli ses (RED): her paket birincil yükün yanında önceki bir veya iki yükü de
+// taşır; kaybolan bir paketin sesi sonraki paketteki kopyadan kurtarılır. Yük tipi dinamiktir
+// ve sinyalleşme tarafından tahsis isteğinde verilir.
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+use crate::error::RedError;
+
+/// Birincil yükle birlikte taşınabilecek en fazla önceki yük.
+pub const MAX_GENERATIONS: usize = 2;
+/// RED'in kullanabileceği dinamik yük tipleri.
+pub const DYNAMIC_PAYLOAD_TYPES: RangeInclusive<u8> = 96..=127;
+// Blok başlığı alanlarının sınırları: 14 bit zaman damgası farkı, 10 bit blok uzunluğu.
+const MAX_OFFSET: u32 = 0x3FFF;
+const MAX_BLOCK_LEN: usize = 0x3FF;
+
+/// Oturumda RED kullanımı: sinyalleşmenin verdiği yük tipi ve paket başına önceki yük sayısı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedConfig {
+    pub payload_type: u8,
+    pub generations: usize,
+}
+
+/// Giden yükleri son `generations` yükle birlikte RED olarak sarar.
+#[derive(Debug)]
+pub struct RedEncoder {
+    generations: usize,
+    // Eskiden yeniye (zaman damgası, yük).
+    history: VecDeque<(u32, Vec<u8>)>,
+}
+
+impl RedEncoder {
+    pub fn new(generations: usize) -> Self {
+        let generations = generations.min(MAX_GENERATIONS);
+        RedEncoder { generations, history: VecDeque::with_capacity(generations + 1) }
+    }
+
+    /// `primary`'yi önceki yüklerle birlikte `out`'a yazar ve geçmişe ekler. Zaman damgası farkı
+    /// 14 bite sığmayan (ör. sessizlikten sonraki ilk paket) ya da 1023 bayttan uzun yükler
+    /// yedeklenmez.
+    pub fn encode(&mut self, payload_type: u8, timestamp: u32, primary: &[u8], out: &mut Vec<u8>) {
+        out.clear();
+        let usable = |&&(ts, ref data): &&(u32, Vec<u8>)| {
+            let offset = timestamp.wrapping_sub(ts);
+            offset > 0 && offset <= MAX_OFFSET && data.len() <= MAX_BLOCK_LEN
+        };
+        for (ts, data) in self.history.iter().filter(usable) {
+            let header = (timestamp.wrapping_sub(*ts) << 10) | data.len() as u32;
+            out.push(0x80 | payload_type);
+            out.extend_from_slice(&header.to_be_bytes()[1..]);
+        }
+        out.push(payload_type & 0x7F);
+        for (_, data) in self.history.iter().filter(usable) {
+            out.extend_from_slice(data);
+        }
+        out.extend_from_slice(primary);
+
+        if self.generations == 0 {
+            return;
+        }
+        // En eski girdinin tamponu yeniden kullanılır.
+        let mut entry = if self.history.len() == self.generations { self.history.pop_front().unwrap() } else { (0, Vec::new()) };
+        entry.0 = timestamp;
+        entry.1.clear();
+        entry.1.extend_from_slice(primary);
+        self.history.push_back(entry);
+    }
+}
+
+/// RED paketindeki tek bir yük.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedBlock<'a> {
+    pub payload_type: u8,
+    pub timestamp: u32,
+    pub data: &'a [u8],
+}
+
+/// RED yükünü bloklarına ayırır; bloklar eskiden yeniye, birincil yük en sondadır. `timestamp`
+/// RTP başlığındaki (birincil yükün) zaman damgasıdır. `blocks` temizlenip doldurulur.
+pub fn parse<'a>(payload: &'a [u8], timestamp: u32, blocks: &mut Vec<RedBlock<'a>>) -> Result<(), RedError> {
+    blocks.clear();
+    let len = payload.len();
+    let mut pos = 0;
+    // Blok uzunlukları başlıklar bitene kadar burada tutulur.
+    let mut lengths = [0usize; 16];
+    loop {
+        let &first = payload.get(pos).ok_or(RedError::Truncated { len })?;
+        if first & 0x80 == 0 {
+            blocks.push(RedBlock { payload_type: first & 0x7F, timestamp, data: &[] });
+            pos += 1;
+            break;
+        }
+        let header = payload.get(pos..pos + 4).ok_or(RedError::Truncated { len })?;
+        if blocks.len() == lengths.len() - 1 {
+            return Err(RedError::TooManyBlocks);
+        }
+        let fields = u32::from_be_bytes([0, header[1], header[2], header[3]]);
+        lengths[blocks.len()] = (fields & 0x3FF) as usize;
+        blocks.push(RedBlock { payload_type: first & 0x7F, timestamp: timestamp.wrapping_sub(fields >> 10), data: &[] });
+        pos += 4;
+    }
+    let redundant = blocks.len() - 1;
+    for (block, &block_len) in blocks[..redundant].iter_mut().zip(&lengths) {
+        let data = payload.get(pos..pos + block_len).ok_or(RedError::BlockOverrun { declared: block_len, len })?;
+        block.data = data;
+        pos += block_len;
+    }
+    blocks[redundant].data = &payload[pos..];
+    Ok(())
+}
+
+/// RED yükündeki birincil blok; yük ayrıştırılamazsa `None`.
+pub fn primary(payload: &[u8], timestamp: u32) -> Option<RedBlock<'_>> {
+    let mut blocks = Vec::with_capacity(MAX_GENERATIONS + 1);
+    parse(payload, timestamp, &mut blocks).ok()?;
+    blocks.pop()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoder_carries_previous_payloads_with_offsets() {
+        let mut encoder = RedEncoder::new(2);
+        let mut out = Vec::new();
+        encoder.encode(0, 160, &[1, 1], &mut out);
+        assert_eq!(out, [0x00, 1, 1], "first packet has no history");
+        encoder.encode(0, 320, &[2, 2, 2], &mut out);
+        // F=1, PT 0, offset 160, uzunluk 2; son başlık F=0; önce yedek, sonra birincil.
+        assert_eq!(out, [0x80, 0x02, 0x80, 0x02, 0x00, 1, 1, 2, 2, 2]);
+        encoder.encode(0, 480, &[3], &mut out);
+        assert_eq!(out, [0x80, 0x05, 0x00, 0x02, 0x80, 0x02, 0x80, 0x03, 0x00, 1, 1, 2, 2, 2, 3]);
+
+        let mut blocks = Vec::new();
+        parse(&out, 480, &mut blocks).unwrap();
+        assert_eq!(blocks, [
+            RedBlock { payload_type: 0, timestamp: 160, data: &[1, 1] },
+            RedBlock { payload_type: 0, timestamp: 320, data: &[2, 2, 2] },
+            RedBlock { payload_type: 0, timestamp: 480, data: &[3] },
+        ]);
+    }
+
+    #[test]
+    fn blocks_that_do_not_fit_the_header_are_not_repeated() {
+        let mut encoder = RedEncoder::new(1);
+        let mut out = Vec::new();
+        encoder.encode(8, 0, &[0xD5; 4], &mut out);
+        // 2 saniyelik fark (16000) 14 bite sığar, 5 saniyelik sığmaz.
+        encoder.encode(8, 16_000, &[0xD5; 4], &mut out);
+        assert_eq!(out.len(), 4 + 1 + 4 + 4);
+        encoder.encode(8, 40_000, &[0xD5; 4], &mut out);
+        assert_eq!(out, [0x08, 0xD5, 0xD5, 0xD5, 0xD5]);
+    }
+
+    #[test]
+    fn parser_rejects_truncated_headers_and_blocks() {
+        let mut blocks = Vec::new();
+        assert_eq!(parse(&[], 0, &mut blocks), Err(RedError::Truncated { len: 0 }));
+        assert_eq!(parse(&[0x80, 0x02, 0x80], 0, &mut blocks), Err(RedError::Truncated { len: 3 }));
+        assert_eq!(parse(&[0x80, 0x02, 0x80, 0x05, 0x00, 1, 2], 0, &mut blocks), Err(RedError::BlockOverrun { declared: 5, len: 7 }));
+        assert_eq!(parse(&[0x80; 64], 0, &mut blocks), Err(RedError::TooManyBlocks));
+        // Yalnız birincil başlık, boş yük.
+        parse(&[0x00], 7, &mut blocks).unwrap();
+        assert_eq!(blocks, [RedBlock { payload_type: 0, timestamp: 7, data: &[] }]);
+    }
+}
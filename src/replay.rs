@@ -0,0 +1,307 @@
+// pcap'ten RTP yeniden oynatma: gerçek çağrı kayıtlarını gelen yola (jitter tamponu, tuş
+// algılama, kayıt) vermek için. Dosyadaki UDP paketlerinden RTP olanlar seçilir, hedefleri
+// oturumun portuyla değiştirilip yerel bir soketten, kayıttaki aralıklar korunarak (istenirse
+// hızlandırılarak) gönderilir. Birden fazla akış taşıyan kayıtta SSRC'yle ya da UDP akışıyla
+// (kaynak, istenirse hedef) biri seçilmelidir; seçilmezse akışlar listelenip reddedilir.
+// Klasik pcap (mikro ve nanosaniye, iki bayt sırası) ve Ethernet (802.1Q dahil), ham IP, Linux
+// SLL ve BSD loopback bağlantı tipleri okunur; pcapng ve parçalanmış IP paketleri okunmaz.
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+use tokio::time::{sleep_until, Instant};
+
+use crate::error::ReplayError;
+use crate::rtp::RtpPacketRef;
+
+const LINKTYPE_NULL: u32 = 0;
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+// En fazla hızlandırma; ötesinde paketler aralıksız gider.
+pub const MAX_SPEED: f64 = 100.0;
+
+/// Oynatılacak akış.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Filter {
+    /// Kayıtta tek akış olmalıdır.
+    #[default]
+    Only,
+    Ssrc(u32),
+    Flow { source: SocketAddr, destination: Option<SocketAddr> },
+}
+
+impl Filter {
+    /// `KAYNAK` ya da `KAYNAK,HEDEF` (ör. "10.0.0.5:4000,10.0.0.9:20000").
+    pub fn flow(flow: &str) -> Option<Filter> {
+        let (source, destination) = match flow.split_once(',') {
+            Some((source, destination)) => (source, Some(destination.parse().ok()?)),
+            None => (flow, None),
+        };
+        Some(Filter::Flow { source: source.parse().ok()?, destination })
+    }
+
+    /// Ondalık ya da 0x önekli onaltılık SSRC.
+    pub fn ssrc(ssrc: &str) -> Option<Filter> {
+        let ssrc = match ssrc.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => ssrc.parse().ok()?,
+        };
+        Some(Filter::Ssrc(ssrc))
+    }
+
+    fn matches(&self, datagram: &Datagram, ssrc: u32) -> bool {
+        match *self {
+            Filter::Only => true,
+            Filter::Ssrc(wanted) => ssrc == wanted,
+            Filter::Flow { source, destination } => datagram.source == source && destination.is_none_or(|d| datagram.destination == d),
+        }
+    }
+}
+
+/// Kayıttaki bir UDP paketi; `at` kaydın saatine göredir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Datagram {
+    pub at: Duration,
+    pub source: SocketAddr,
+    pub destination: SocketAddr,
+    pub payload: Vec<u8>,
+}
+
+/// Oynatılmayan paketler, sebebine göre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Skipped {
+    /// UDP değil ya da parçalanmış IP paketi.
+    pub not_udp: usize,
+    /// UDP ama RTP değil (RTCP, SIP, STUN...).
+    pub not_rtp: usize,
+    /// Seçilmeyen akışlara ait RTP.
+    pub filtered: usize,
+}
+
+impl Skipped {
+    pub fn total(&self) -> usize {
+        self.not_udp + self.not_rtp + self.filtered
+    }
+}
+
+/// Seçilen akışın paketleri, kayıt sırasıyla.
+#[derive(Debug, Clone)]
+pub struct Plan {
+    pub packets: Vec<Datagram>,
+    pub skipped: Skipped,
+}
+
+/// pcap dosyasını okuyup oynatılacak paketleri seçer.
+pub fn plan(pcap: &[u8], filter: Filter) -> Result<Plan, ReplayError> {
+    let (datagrams, not_udp) = read_pcap(pcap)?;
+    let mut skipped = Skipped { not_udp, ..Skipped::default() };
+    // (kaynak, hedef, SSRC) -> paket sayısı; birden fazla akışı listelemek için.
+    let mut streams: BTreeMap<(SocketAddr, SocketAddr, u32), usize> = BTreeMap::new();
+    let mut packets = Vec::new();
+    for datagram in datagrams {
+        let Some(ssrc) = rtp_ssrc(&datagram.payload) else {
+            skipped.not_rtp += 1;
+            continue;
+        };
+        if !filter.matches(&datagram, ssrc) {
+            skipped.filtered += 1;
+            continue;
+        }
+        *streams.entry((datagram.source, datagram.destination, ssrc)).or_default() += 1;
+        packets.push(datagram);
+    }
+    if filter == Filter::Only && streams.len() > 1 {
+        let streams = streams.iter()
+            .map(|((source, destination, ssrc), count)| format!("ssrc=0x{:08x} {} -> {} ({} packets)", ssrc, source, destination, count))
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Err(ReplayError::MultipleStreams { streams });
+    }
+    Ok(Plan { packets, skipped })
+}
+
+/// Paketleri `socket`'ten `target`'a, kayıttaki aralıkları `speed` kat hızlandırarak gönderir;
+/// gönderilen paket sayısını döner.
+pub async fn replay(plan: &Plan, socket: &UdpSocket, target: SocketAddr, speed: f64) -> Result<usize, ReplayError> {
+    let (Some(first), true) = (plan.packets.first(), speed > 0.0) else { return Ok(0) };
+    let started = Instant::now();
+    for packet in &plan.packets {
+        let offset = packet.at.saturating_sub(first.at);
+        sleep_until(started + offset.div_f64(speed)).await;
+        socket.send_to(&packet.payload, target).await.map_err(|source| ReplayError::Send { target, source })?;
+    }
+    Ok(plan.packets.len())
+}
+
+/// RTCP'nin (RFC 5761: yük tipi 72-76) dışındaki geçerli RTP paketinin SSRC'si.
+fn rtp_ssrc(payload: &[u8]) -> Option<u32> {
+    let packet = RtpPacketRef::parse(payload).ok()?;
+    (!(72..=76).contains(&packet.payload_type())).then(|| packet.ssrc())
+}
+
+/// Bütün UDP paketleri ve UDP olmayan kayıtların sayısı.
+fn read_pcap(bytes: &[u8]) -> Result<(Vec<Datagram>, usize), ReplayError> {
+    let format = |reason: &str| ReplayError::Format { reason: reason.to_string() };
+    let header = bytes.get(..24).ok_or_else(|| format("shorter than the pcap global header"))?;
+    let magic = [header[0], header[1], header[2], header[3]];
+    let (big_endian, nanos) = match magic {
+        [0xd4, 0xc3, 0xb2, 0xa1] => (false, false),
+        [0xa1, 0xb2, 0xc3, 0xd4] => (true, false),
+        [0x4d, 0x3c, 0xb2, 0xa1] => (false, true),
+        [0xa1, 0xb2, 0x3c, 0x4d] => (true, true),
+        [0x0a, 0x0d, 0x0d, 0x0a] => return Err(format("pcapng is not supported; convert it with 'editcap -F pcap'")),
+        _ => return Err(format("unknown magic number")),
+    };
+    let u32_at = |buf: &[u8], at: usize| {
+        let raw = [buf[at], buf[at + 1], buf[at + 2], buf[at + 3]];
+        if big_endian { u32::from_be_bytes(raw) } else { u32::from_le_bytes(raw) }
+    };
+    let link_type = u32_at(header, 20) & 0xffff;
+    if ![LINKTYPE_NULL, LINKTYPE_ETHERNET, LINKTYPE_RAW, LINKTYPE_LINUX_SLL].contains(&link_type) {
+        return Err(ReplayError::UnsupportedLinkType { link_type });
+    }
+
+    let (mut datagrams, mut not_udp, mut offset) = (Vec::new(), 0, 24);
+    while offset < bytes.len() {
+        let record = bytes.get(offset..offset + 16).ok_or_else(|| format("truncated record header"))?;
+        let (secs, fraction, captured) = (u32_at(record, 0), u32_at(record, 4), u32_at(record, 8) as usize);
+        let frame = bytes.get(offset + 16..offset + 16 + captured).ok_or_else(|| format("truncated record"))?;
+        offset += 16 + captured;
+        let at = Duration::from_secs(secs as u64) + if nanos { Duration::from_nanos(fraction as u64) } else { Duration::from_micros(fraction as u64) };
+        match ip_payload(link_type, frame).and_then(udp) {
+            Some((source, destination, payload)) => datagrams.push(Datagram { at, source, destination, payload: payload.to_vec() }),
+            None => not_udp += 1,
+        }
+    }
+    Ok((datagrams, not_udp))
+}
+
+/// Bağlantı katmanı başlığından sonraki IP paketi.
+fn ip_payload(link_type: u32, frame: &[u8]) -> Option<&[u8]> {
+    match link_type {
+        // 4 baytlık adres ailesi, IP paketi başlığında sürümünü taşır.
+        LINKTYPE_NULL => frame.get(4..),
+        LINKTYPE_RAW => Some(frame),
+        LINKTYPE_LINUX_SLL => {
+            let protocol = u16::from_be_bytes([*frame.get(14)?, *frame.get(15)?]);
+            frame.get(16..).filter(|_| is_ip(protocol))
+        }
+        _ => {
+            let mut at = 12;
+            let mut ethertype = u16::from_be_bytes([*frame.get(at)?, *frame.get(at + 1)?]);
+            // 802.1Q ve QinQ etiketleri.
+            while ethertype == 0x8100 || ethertype == 0x88a8 {
+                at += 4;
+                ethertype = u16::from_be_bytes([*frame.get(at)?, *frame.get(at + 1)?]);
+            }
+            frame.get(at + 2..).filter(|_| is_ip(ethertype))
+        }
+    }
+}
+
+fn is_ip(ethertype: u16) -> bool {
+    ethertype == 0x0800 || ethertype == 0x86dd
+}
+
+/// IPv4 ya da IPv6 paketindeki UDP'nin adresleri ve yükü.
+fn udp(ip: &[u8]) -> Option<(SocketAddr, SocketAddr, &[u8])> {
+    let (source, destination, segment): (IpAddr, IpAddr, &[u8]) = match ip.first()? >> 4 {
+        4 => {
+            let header_len = (ip[0] & 0x0f) as usize * 4;
+            let total_len = u16::from_be_bytes([*ip.get(2)?, *ip.get(3)?]) as usize;
+            // Parçalanmış paketin yükü tek başına bir datagram değildir.
+            let fragment = u16::from_be_bytes([*ip.get(6)?, *ip.get(7)?]) & 0x3fff;
+            if *ip.get(9)? != 17 || fragment != 0 || header_len < 20 {
+                return None;
+            }
+            let source = Ipv4Addr::from(<[u8; 4]>::try_from(ip.get(12..16)?).ok()?);
+            let destination = Ipv4Addr::from(<[u8; 4]>::try_from(ip.get(16..20)?).ok()?);
+            (source.into(), destination.into(), ip.get(header_len..total_len.min(ip.len()))?)
+        }
+        6 => {
+            if *ip.get(6)? != 17 {
+                return None;
+            }
+            let source = Ipv6Addr::from(<[u8; 16]>::try_from(ip.get(8..24)?).ok()?);
+            let destination = Ipv6Addr::from(<[u8; 16]>::try_from(ip.get(24..40)?).ok()?);
+            (source.to_canonical(), destination.to_canonical(), ip.get(40..)?)
+        }
+        _ => return None,
+    };
+    let udp_len = u16::from_be_bytes([*segment.get(4)?, *segment.get(5)?]) as usize;
+    let payload = segment.get(8..udp_len.min(segment.len()))?;
+    let port = |at: usize| u16::from_be_bytes([segment[at], segment[at + 1]]);
+    Some((SocketAddr::new(source, port(0)), SocketAddr::new(destination, port(2)), payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::capture;
+    use crate::rtp::{RtpPacket, MAX_PACKET_LEN};
+
+    fn rtp(sequence: u16, ssrc: u32) -> Vec<u8> {
+        let mut wire = [0u8; MAX_PACKET_LEN];
+        let len = RtpPacket::new(0, sequence, sequence as u32 * 160, ssrc, &[0xFF; 160]).write(&mut wire).unwrap();
+        wire[..len].to_vec()
+    }
+
+    /// İki akış (birinin 20 ms'de bir 5 paketi, diğerinin 2 paketi) ve bir SIP paketi.
+    fn two_streams() -> (Vec<u8>, SocketAddr, SocketAddr) {
+        let (caller, callee): (SocketAddr, SocketAddr) = ("10.0.0.5:4000".parse().unwrap(), "[2001:db8::9]:6000".parse().unwrap());
+        let node: SocketAddr = "10.0.0.9:20000".parse().unwrap();
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut packets: Vec<(SystemTime, SocketAddr, SocketAddr, Vec<u8>)> = (0..5)
+            .map(|i| (start + Duration::from_millis(20 * i as u64), caller, node, rtp(i, 0x1111)))
+            .collect();
+        packets.push((start + Duration::from_millis(5), callee, node, rtp(1, 0x2222)));
+        packets.push((start + Duration::from_millis(25), callee, node, rtp(2, 0x2222)));
+        packets.push((start + Duration::from_millis(7), caller, "10.0.0.9:5060".parse().unwrap(), b"INVITE sip:bob SIP/2.0\r\n".to_vec()));
+        packets.sort_by_key(|packet| packet.0);
+        let mut pcap = Vec::new();
+        capture::write_packets(&mut pcap, packets.iter().map(|(at, src, dst, payload)| (*at, *src, *dst, payload.as_slice()))).unwrap();
+        (pcap, caller, callee)
+    }
+
+    #[test]
+    fn multiple_streams_need_a_filter_by_ssrc_or_flow() {
+        let (pcap, caller, callee) = two_streams();
+        let Err(ReplayError::MultipleStreams { streams }) = plan(&pcap, Filter::Only) else { panic!("two streams replayed together") };
+        assert!(streams.contains("ssrc=0x00001111 10.0.0.5:4000 -> 10.0.0.9:20000 (5 packets)"), "{}", streams);
+
+        let by_ssrc = plan(&pcap, Filter::ssrc("0x1111").unwrap()).unwrap();
+        assert_eq!(by_ssrc.packets.len(), 5);
+        assert_eq!(by_ssrc.skipped, Skipped { not_udp: 0, not_rtp: 1, filtered: 2 });
+        assert_eq!(by_ssrc.packets[4].at - by_ssrc.packets[0].at, Duration::from_millis(80));
+
+        let by_flow = plan(&pcap, Filter::flow(&format!("{},10.0.0.9:20000", callee)).unwrap()).unwrap();
+        assert_eq!((by_flow.packets.len(), by_flow.skipped.total()), (2, 6));
+        assert!(by_flow.packets.iter().all(|packet| packet.source == callee));
+        assert_eq!(plan(&pcap, Filter::flow(&caller.to_string()).unwrap()).unwrap().packets.len(), 5);
+
+        assert!(matches!(plan(&[0x0a, 0x0d, 0x0d, 0x0a], Filter::Only), Err(ReplayError::Format { .. })));
+        assert!(matches!(plan(&pcap[..pcap.len() - 3], Filter::Only), Err(ReplayError::Format { .. })));
+    }
+
+    #[tokio::test]
+    async fn replay_keeps_the_recorded_spacing_scaled_by_speed() {
+        let (pcap, ..) = two_streams();
+        let plan = plan(&pcap, Filter::Ssrc(0x1111)).unwrap();
+        let (socket, receiver) = (UdpSocket::bind("127.0.0.1:0").await.unwrap(), UdpSocket::bind("127.0.0.1:0").await.unwrap());
+        let started = Instant::now();
+        let replayed = replay(&plan, &socket, receiver.local_addr().unwrap(), 2.0).await.unwrap();
+        assert_eq!(replayed, 5);
+        // 80 ms'lik kayıt iki kat hızda 40 ms sürer.
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(40) && elapsed < Duration::from_millis(80), "{:?}", elapsed);
+        let mut buf = [0u8; MAX_PACKET_LEN];
+        for sequence in 0..5 {
+            let (len, from) = receiver.recv_from(&mut buf).await.unwrap();
+            assert_eq!((RtpPacketRef::parse(&buf[..len]).unwrap().sequence(), from), (sequence, socket.local_addr().unwrap()));
+        }
+    }
+}
@@ -0,0 +1,84 @@
+// İstek kimliği: sinyalleşme tarafının gRPC metadata'sında gönderdiği kimlik (varsayılan
+// `x-request-id`) interceptor'da okunur ve isteğe eklenti olarak eklenir. Başlık yoksa ya da
+// geçersizse yerelde üretilir; AllocatePort bunu cevap metadata'sında geri döner. Handler span'leri
+// ve oturum span'i kimliği taşır, böylece oturumun bütün olayları çağrıyla ilişkilendirilebilir.
+use rand::Rng;
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Request, Response, Status};
+
+pub const DEFAULT_HEADER: &str = "x-request-id";
+// Loglara taşınacak değerin üst sınırı; daha uzunu yok sayılıp yenisi üretilir.
+const MAX_LEN: usize = 128;
+
+/// İsteğin kimliği; interceptor her isteğe ekler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    fn generate() -> Self {
+        RequestId(format!("{:032x}", rand::thread_rng().gen::<u128>()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestIdInterceptor {
+    header: AsciiMetadataKey,
+}
+
+impl RequestIdInterceptor {
+    /// `header` config doğrulamasından geçmiştir; geçersizse varsayılan başlık kullanılır.
+    pub fn new(header: &str) -> Self {
+        let header = header.parse().unwrap_or_else(|_| AsciiMetadataKey::from_static(DEFAULT_HEADER));
+        Self { header }
+    }
+}
+
+impl Interceptor for RequestIdInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let id = request.metadata().get(&self.header)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| !value.is_empty() && value.len() <= MAX_LEN)
+            .map(|value| RequestId(value.to_string()))
+            .unwrap_or_else(RequestId::generate);
+        request.extensions_mut().insert(id);
+        Ok(request)
+    }
+}
+
+/// Interceptor'ın eklediği kimlik; interceptor'sız gömülü kullanımda boş.
+pub fn of<T>(request: &Request<T>) -> &str {
+    request.extensions().get::<RequestId>().map_or("", |id| id.0.as_str())
+}
+
+/// Kimliği cevap metadata'sına yazar; çağıran kendi göndermediyse üretileni buradan öğrenir.
+pub fn attach<T>(response: &mut Response<T>, header: &str, id: &str) {
+    if let (Ok(key), Ok(value)) = (header.parse::<AsciiMetadataKey>(), id.parse::<AsciiMetadataValue>()) {
+        response.metadata_mut().insert(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intercept(value: Option<&str>) -> String {
+        let mut request = Request::new(());
+        if let Some(value) = value {
+            request.metadata_mut().insert(DEFAULT_HEADER, value.parse().unwrap());
+        }
+        let request = RequestIdInterceptor::new(DEFAULT_HEADER).call(request).unwrap();
+        of(&request).to_string()
+    }
+
+    #[test]
+    fn caller_id_is_kept_and_missing_or_oversized_ids_are_generated() {
+        assert_eq!(intercept(Some("sip-proxy-42")), "sip-proxy-42");
+        for value in [None, Some(""), Some(&"x".repeat(MAX_LEN + 1) as &str)] {
+            let id = intercept(value);
+            assert_eq!(id.len(), 32, "{:?}", value);
+            assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+        }
+        assert_ne!(intercept(None), intercept(None));
+    }
+}
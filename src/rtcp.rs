@@ -0,0 +1,513 @@
+// RTCP: RFC 3550 birleşik (compound) paketleri (SR ya da RR, SDES CNAME, BYE) ve RFC 3611
+// genişletilmiş raporlar (XR) ile VoIP metrikleri bloğu. Bloklar sabit yerleşimli olduğundan okuma
+// da yazma da paket tamponunun üzerinde, ayırma yapmadan çalışır.
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::RtcpConfig;
+use crate::error::{BuildError, RtcpError};
+
+/// RTCP ortak başlığı: V/P/sayaç, paket tipi, 32 bitlik kelime cinsinden uzunluk - 1.
+pub const RTCP_HEADER_LEN: usize = 4;
+/// RFC 3550 paket tipleri.
+pub const SR_PT: u8 = 200;
+pub const RR_PT: u8 = 201;
+pub const SDES_PT: u8 = 202;
+pub const BYE_PT: u8 = 203;
+/// SDES CNAME öğesinin tipi.
+const CNAME_ITEM: u8 = 1;
+const SENDER_INFO_LEN: usize = 20;
+const REPORT_BLOCK_LEN: usize = 24;
+// Başlıktaki 5 bitlik sayaç alanının sınırı.
+const MAX_COUNT: usize = 31;
+// 1900 (NTP) ile 1970 (Unix) başlangıçları arasındaki saniye.
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+static CNAME: OnceLock<String> = OnceLock::new();
+
+/// Node'un CNAME'ini `rtcp.cname`'den kurar; başlangıçta bir kez çağrılır.
+pub fn install(config: &RtcpConfig) {
+    let _ = CNAME.set(config.cname().to_string());
+}
+
+/// Bütün oturumların SDES'inde giden CNAME; kurulmadıysa `kullanıcı@makine`.
+pub fn cname() -> &'static str {
+    CNAME.get_or_init(default_cname)
+}
+
+/// `USER` (yoksa "media") ve makine adından (yoksa "localhost") `kullanıcı@makine`.
+pub fn default_cname() -> String {
+    let user = std::env::var("USER").ok().filter(|user| !user.is_empty()).unwrap_or_else(|| "media".to_string());
+    let host = std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .or_else(|_| std::fs::read_to_string("/etc/hostname"))
+        .map(|host| host.trim().to_string())
+        .ok()
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "localhost".to_string());
+    format!("{}@{}", user, host)
+}
+
+/// Duvar saatinin 64 bitlik NTP zaman damgası (üst 32 bit saniye, alt 32 bit kesir).
+pub fn ntp_now() -> u64 {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let fraction = ((since_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+    ((since_epoch.as_secs() + NTP_UNIX_OFFSET) << 32) | fraction
+}
+
+/// Alım raporlarının LSR ve DLSR alanlarının kullandığı orta 32 bit (1/65536 saniye birimi).
+pub fn ntp_middle(ntp_timestamp: u64) -> u32 {
+    (ntp_timestamp >> 16) as u32
+}
+
+/// 1/65536 saniye birimindeki süre.
+pub fn compact_duration(units: u32) -> Duration {
+    Duration::from_micros(units as u64 * 1_000_000 / 65_536)
+}
+
+/// RFC 3550 6.4.1: `arrival` anında (orta 32 bit) gelen bloktan gidiş dönüş süresi; blok SR'a
+/// dayanmıyorsa (LSR 0) ya da saatler tutarsızsa `None`.
+pub fn round_trip(block: &ReportBlock, arrival: u32) -> Option<Duration> {
+    if block.last_sr == 0 {
+        return None;
+    }
+    let rtt = arrival.wrapping_sub(block.last_sr).wrapping_sub(block.delay_since_last_sr);
+    // Negatif çıkan (sarmış) değer dönüş yolunun olanaksız olduğunu gösterir.
+    (rtt < 1 << 31).then(|| compact_duration(rtt))
+}
+
+/// SR'ın gönderici bilgisi (RFC 3550 6.4.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SenderInfo {
+    pub ntp_timestamp: u64,
+    /// `ntp_timestamp` anına denk gelen RTP zaman damgası.
+    pub rtp_timestamp: u32,
+    pub packets: u32,
+    /// Yalnızca yük baytları; RTP başlıkları ve dolgu sayılmaz.
+    pub octets: u32,
+}
+
+/// Gelen bir akış için alım raporu bloğu (RFC 3550 6.4.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReportBlock {
+    pub ssrc: u32,
+    /// Son rapordan beri kaybolanların oranı, 256 üzerinden.
+    pub fraction_lost: u8,
+    /// 24 bite sığdırılır.
+    pub cumulative_lost: i32,
+    pub highest_sequence: u32,
+    /// Codec saat birimlerinde.
+    pub jitter: u32,
+    pub last_sr: u32,
+    pub delay_since_last_sr: u32,
+}
+
+impl ReportBlock {
+    fn read(buf: &[u8]) -> Self {
+        let u32_at = |i: usize| u32::from_be_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]);
+        let lost = u32_at(4);
+        ReportBlock {
+            ssrc: u32_at(0),
+            fraction_lost: (lost >> 24) as u8,
+            // 24 bitlik işaretli değer.
+            cumulative_lost: ((lost << 8) as i32) >> 8,
+            highest_sequence: u32_at(8),
+            jitter: u32_at(12),
+            last_sr: u32_at(16),
+            delay_since_last_sr: u32_at(20),
+        }
+    }
+
+    fn write(&self, buf: &mut [u8]) {
+        let lost = self.cumulative_lost.clamp(-0x80_0000, 0x7F_FFFF) as u32 & 0xFF_FFFF;
+        buf[0..4].copy_from_slice(&self.ssrc.to_be_bytes());
+        buf[4..8].copy_from_slice(&(((self.fraction_lost as u32) << 24) | lost).to_be_bytes());
+        buf[8..12].copy_from_slice(&self.highest_sequence.to_be_bytes());
+        buf[12..16].copy_from_slice(&self.jitter.to_be_bytes());
+        buf[16..20].copy_from_slice(&self.last_sr.to_be_bytes());
+        buf[20..24].copy_from_slice(&self.delay_since_last_sr.to_be_bytes());
+    }
+}
+
+/// Birleşik RTCP paketini tamponun üzerine sırayla yazar. RFC 3550 6.1: ilk paket SR ya da RR
+/// olmalı, CNAME taşıyan SDES her birleşik pakette bulunmalı; BYE sona konur. Uzunluk alanları
+/// her paket için yazılır; SDES ve BYE metinleri 32 bit sınırına sıfırla doldurulur, P biti
+/// kullanılmaz.
+pub struct CompoundWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> CompoundWriter<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        CompoundWriter { buf, len: 0 }
+    }
+
+    /// Bu akıştan RTP gönderildiyse SR.
+    pub fn sender_report(&mut self, ssrc: u32, sender: &SenderInfo, blocks: &[ReportBlock]) -> Result<&mut Self, BuildError> {
+        let packet = self.packet(SR_PT, blocks.len(), 4 + SENDER_INFO_LEN + REPORT_BLOCK_LEN * blocks.len())?;
+        packet[4..8].copy_from_slice(&ssrc.to_be_bytes());
+        packet[8..16].copy_from_slice(&sender.ntp_timestamp.to_be_bytes());
+        packet[16..20].copy_from_slice(&sender.rtp_timestamp.to_be_bytes());
+        packet[20..24].copy_from_slice(&sender.packets.to_be_bytes());
+        packet[24..28].copy_from_slice(&sender.octets.to_be_bytes());
+        for (block, out) in blocks.iter().zip(packet[28..].chunks_exact_mut(REPORT_BLOCK_LEN)) {
+            block.write(out);
+        }
+        Ok(self)
+    }
+
+    /// Henüz RTP gönderilmediyse SR yerine RR.
+    pub fn receiver_report(&mut self, ssrc: u32, blocks: &[ReportBlock]) -> Result<&mut Self, BuildError> {
+        let packet = self.packet(RR_PT, blocks.len(), 4 + REPORT_BLOCK_LEN * blocks.len())?;
+        packet[4..8].copy_from_slice(&ssrc.to_be_bytes());
+        for (block, out) in blocks.iter().zip(packet[8..].chunks_exact_mut(REPORT_BLOCK_LEN)) {
+            block.write(out);
+        }
+        Ok(self)
+    }
+
+    /// Tek parçalı, yalnızca CNAME öğesi taşıyan SDES. Öğe listesi en az bir sıfır baytla biter.
+    pub fn sdes_cname(&mut self, ssrc: u32, cname: &str) -> Result<&mut Self, BuildError> {
+        let text = short_text(cname)?;
+        let packet = self.packet(SDES_PT, 1, padded(4 + 2 + text.len() + 1))?;
+        packet[4..8].copy_from_slice(&ssrc.to_be_bytes());
+        packet[8] = CNAME_ITEM;
+        packet[9] = text.len() as u8;
+        packet[10..10 + text.len()].copy_from_slice(text);
+        Ok(self)
+    }
+
+    /// Tek kaynaklı BYE; `reason` varsa uzunluk baytıyla eklenir.
+    pub fn bye(&mut self, ssrc: u32, reason: Option<&str>) -> Result<&mut Self, BuildError> {
+        let text = reason.map(short_text).transpose()?;
+        let packet = self.packet(BYE_PT, 1, padded(4 + text.map_or(0, |text| 1 + text.len())))?;
+        packet[4..8].copy_from_slice(&ssrc.to_be_bytes());
+        if let Some(text) = text {
+            packet[8] = text.len() as u8;
+            packet[9..9 + text.len()].copy_from_slice(text);
+        }
+        Ok(self)
+    }
+
+    /// Yazılan baytlar.
+    pub fn finish(self) -> usize {
+        self.len
+    }
+
+    /// Başlığı yazılmış, gövdesi sıfırlanmış paket; `body` başlıktan sonraki bayt sayısıdır ve
+    /// 4'ün katıdır.
+    fn packet(&mut self, packet_type: u8, count: usize, body: usize) -> Result<&mut [u8], BuildError> {
+        if count > MAX_COUNT {
+            return Err(BuildError::TooManyReportBlocks { count });
+        }
+        let size = RTCP_HEADER_LEN + body;
+        let needed = self.len + size;
+        if self.buf.len() < needed {
+            return Err(BuildError::BufferTooSmall { needed, available: self.buf.len() });
+        }
+        let packet = &mut self.buf[self.len..needed];
+        packet.fill(0);
+        packet[0] = 0x80 | count as u8;
+        packet[1] = packet_type;
+        packet[2..4].copy_from_slice(&((size / 4 - 1) as u16).to_be_bytes());
+        self.len = needed;
+        Ok(packet)
+    }
+}
+
+fn short_text(text: &str) -> Result<&[u8], BuildError> {
+    match text.len() {
+        len if len > 255 => Err(BuildError::TextTooLong { len }),
+        _ => Ok(text.as_bytes()),
+    }
+}
+
+fn padded(len: usize) -> usize {
+    len.div_ceil(4) * 4
+}
+/// RFC 3611 XR paket tipi.
+pub const XR_PT: u8 = 207;
+/// VoIP Metrics Report Block (RFC 3611 4.7) tipi ve başlık dahil uzunluğu.
+pub const VOIP_METRICS_BT: u8 = 7;
+const VOIP_METRICS_LEN: usize = 36;
+
+/// RFC 3611'de "bilinmiyor" anlamına gelen 8 bitlik değer (R faktörü, MOS, seviyeler).
+pub const UNAVAILABLE: u8 = 127;
+
+/// VoIP metrikleri bloğu. Oranlar 0..=1, seviyeler dBm; bilinmeyen alanlar `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct VoipMetrics {
+    /// Raporun ilgili olduğu akışın SSRC'si.
+    pub ssrc: u32,
+    pub loss_rate: f64,
+    pub discard_rate: f64,
+    pub burst_density: f64,
+    pub gap_density: f64,
+    pub burst_duration_ms: u16,
+    pub gap_duration_ms: u16,
+    pub round_trip_delay_ms: u16,
+    pub end_system_delay_ms: u16,
+    pub gmin: u8,
+    pub r_factor: Option<u8>,
+    pub mos_lq: Option<f64>,
+    pub mos_cq: Option<f64>,
+    pub jb_nominal_ms: u16,
+    pub jb_maximum_ms: u16,
+    pub jb_abs_max_ms: u16,
+}
+
+impl VoipMetrics {
+    /// Bloğu `buf`'a yazar ve yazılan bayt sayısını döner.
+    fn write(&self, buf: &mut [u8]) -> usize {
+        let fraction = |rate: f64| (rate.clamp(0.0, 1.0) * 256.0).min(255.0) as u8;
+        let mos = |mos: Option<f64>| mos.map_or(UNAVAILABLE, |m| (m.clamp(1.0, 5.0) * 10.0).round() as u8);
+        let block = &mut buf[..VOIP_METRICS_LEN];
+        block[0] = VOIP_METRICS_BT;
+        block[1] = 0;
+        block[2..4].copy_from_slice(&((VOIP_METRICS_LEN / 4 - 1) as u16).to_be_bytes());
+        block[4..8].copy_from_slice(&self.ssrc.to_be_bytes());
+        block[8..12].copy_from_slice(&[fraction(self.loss_rate), fraction(self.discard_rate), fraction(self.burst_density), fraction(self.gap_density)]);
+        block[12..14].copy_from_slice(&self.burst_duration_ms.to_be_bytes());
+        block[14..16].copy_from_slice(&self.gap_duration_ms.to_be_bytes());
+        block[16..18].copy_from_slice(&self.round_trip_delay_ms.to_be_bytes());
+        block[18..20].copy_from_slice(&self.end_system_delay_ms.to_be_bytes());
+        // Sinyal, gürültü ve RERL ölçülmüyor.
+        block[20..24].copy_from_slice(&[UNAVAILABLE, UNAVAILABLE, UNAVAILABLE, self.gmin]);
+        block[24..28].copy_from_slice(&[self.r_factor.unwrap_or(UNAVAILABLE), UNAVAILABLE, mos(self.mos_lq), mos(self.mos_cq)]);
+        // RX config: PLC ve jitter tamponu türü bilinmiyor (0).
+        block[28..30].copy_from_slice(&[0, 0]);
+        block[30..32].copy_from_slice(&self.jb_nominal_ms.to_be_bytes());
+        block[32..34].copy_from_slice(&self.jb_maximum_ms.to_be_bytes());
+        block[34..36].copy_from_slice(&self.jb_abs_max_ms.to_be_bytes());
+        VOIP_METRICS_LEN
+    }
+
+    fn read(block: &[u8]) -> Self {
+        let u16_at = |i: usize| u16::from_be_bytes([block[i], block[i + 1]]);
+        let fraction = |byte: u8| byte as f64 / 256.0;
+        let known = |byte: u8| (byte != UNAVAILABLE).then_some(byte);
+        VoipMetrics {
+            ssrc: u32::from_be_bytes([block[4], block[5], block[6], block[7]]),
+            loss_rate: fraction(block[8]),
+            discard_rate: fraction(block[9]),
+            burst_density: fraction(block[10]),
+            gap_density: fraction(block[11]),
+            burst_duration_ms: u16_at(12),
+            gap_duration_ms: u16_at(14),
+            round_trip_delay_ms: u16_at(16),
+            end_system_delay_ms: u16_at(18),
+            gmin: block[23],
+            r_factor: known(block[24]),
+            mos_lq: known(block[26]).map(|m| m as f64 / 10.0),
+            mos_cq: known(block[27]).map(|m| m as f64 / 10.0),
+            jb_nominal_ms: u16_at(30),
+            jb_maximum_ms: u16_at(32),
+            jb_abs_max_ms: u16_at(34),
+        }
+    }
+}
+
+/// Aynı port ya da bağlantıdan gelen paket RTCP mi (RFC 5761 4): sürüm 2 ve ikinci bayt RTCP
+/// paket tiplerinin (192-223) aralığında. Bu aralık RTP'de işaret biti kurulu 64-95 yük
+/// tiplerine denk gelir; bunlar RTP'de kullanılmaz.
+pub fn is_rtcp(buf: &[u8]) -> bool {
+    buf.len() >= RTCP_HEADER_LEN && buf[0] >> 6 == 2 && (192..=223).contains(&buf[1])
+}
+
+/// Tek bir VoIP metrikleri bloğu taşıyan XR paketi yazar; `sender_ssrc` bizim akışımızdır.
+pub fn write_xr(sender_ssrc: u32, metrics: &VoipMetrics, buf: &mut [u8]) -> Result<usize, BuildError> {
+    let needed = RTCP_HEADER_LEN + 4 + VOIP_METRICS_LEN;
+    if buf.len() < needed {
+        return Err(BuildError::BufferTooSmall { needed, available: buf.len() });
+    }
+    buf[0] = 0x80;
+    buf[1] = XR_PT;
+    buf[2..4].copy_from_slice(&((needed / 4 - 1) as u16).to_be_bytes());
+    buf[4..8].copy_from_slice(&sender_ssrc.to_be_bytes());
+    Ok(8 + metrics.write(&mut buf[8..]))
+}
+
+/// Birleşik paketin ilk paketini ayırır; kalanla birlikte döner.
+fn split_packet(buf: &[u8]) -> Result<(&[u8], &[u8]), RtcpError> {
+    if buf.len() < RTCP_HEADER_LEN {
+        return Err(RtcpError::TooShort { len: buf.len() });
+    }
+    let version = buf[0] >> 6;
+    if version != 2 {
+        return Err(RtcpError::Version { version });
+    }
+    let declared = 4 * (u16::from_be_bytes([buf[2], buf[3]]) as usize + 1);
+    if declared > buf.len() {
+        return Err(RtcpError::LengthOverrun { declared, len: buf.len() });
+    }
+    Ok(buf.split_at(declared))
+}
+
+/// Birleşik paketteki bir SR ya da RR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// Raporu gönderen akışın SSRC'si.
+    pub ssrc: u32,
+    /// Yalnızca SR'da.
+    pub sender: Option<SenderInfo>,
+    pub blocks: Vec<ReportBlock>,
+}
+
+/// Birleşik paketteki SR ve RR'ler; diğer paket tipleri atlanır.
+pub fn parse_reports(buf: &[u8]) -> Result<Vec<Report>, RtcpError> {
+    let mut reports = Vec::new();
+    let mut rest = buf;
+    while !rest.is_empty() {
+        let (packet, next) = split_packet(rest)?;
+        rest = next;
+        let info_len = match packet[1] {
+            SR_PT => SENDER_INFO_LEN,
+            RR_PT => 0,
+            _ => continue,
+        };
+        let count = (packet[0] & 0x1F) as usize;
+        let needed = 8 + info_len + REPORT_BLOCK_LEN * count;
+        if needed > packet.len() {
+            return Err(RtcpError::BlockOverrun { declared: needed, len: packet.len() });
+        }
+        let u32_at = |i: usize| u32::from_be_bytes([packet[i], packet[i + 1], packet[i + 2], packet[i + 3]]);
+        let sender = (info_len > 0).then(|| SenderInfo {
+            ntp_timestamp: (u32_at(8) as u64) << 32 | u32_at(12) as u64,
+            rtp_timestamp: u32_at(16),
+            packets: u32_at(20),
+            octets: u32_at(24),
+        });
+        let blocks = packet[8 + info_len..needed].chunks_exact(REPORT_BLOCK_LEN).map(ReportBlock::read).collect();
+        reports.push(Report { ssrc: u32_at(4), sender, blocks });
+    }
+    Ok(reports)
+}
+
+/// XR paketindeki VoIP metrikleri blokları; bilinmeyen blok tipleri atlanır. Paket XR değilse
+/// boş liste döner. `buf` birleşik (compound) bir RTCP paketi olabilir.
+pub fn parse_voip_metrics(buf: &[u8]) -> Result<Vec<VoipMetrics>, RtcpError> {
+    let mut reports = Vec::new();
+    let mut rest = buf;
+    while !rest.is_empty() {
+        let (packet, next) = split_packet(rest)?;
+        if packet[1] == XR_PT && packet.len() >= 8 {
+            let mut blocks = &packet[8..];
+            while blocks.len() >= 4 {
+                let block_len = 4 * (u16::from_be_bytes([blocks[2], blocks[3]]) as usize + 1);
+                if block_len > blocks.len() {
+                    return Err(RtcpError::BlockOverrun { declared: block_len, len: blocks.len() });
+                }
+                if blocks[0] == VOIP_METRICS_BT && block_len == VOIP_METRICS_LEN {
+                    reports.push(VoipMetrics::read(&blocks[..block_len]));
+                }
+                blocks = &blocks[block_len..];
+            }
+        }
+        rest = next;
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        let s: String = s.split_whitespace().collect();
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn compound_sr_sdes_bye_golden_bytes() {
+        let sender = SenderInfo { ntp_timestamp: 0xE1B2_C3D4_8000_0000, rtp_timestamp: 160_000, packets: 50, octets: 8000 };
+        let block = ReportBlock { ssrc: 0xDEAD_BEEF, fraction_lost: 0x40, cumulative_lost: 5, highest_sequence: 0x0001_03E8, jitter: 16, ..ReportBlock::default() };
+        let mut buf = [0u8; 128];
+        let mut writer = CompoundWriter::new(&mut buf);
+        writer.sender_report(0x1234_5678, &sender, &[block]).unwrap().sdes_cname(0x1234_5678, "media@host").unwrap();
+        let len = writer.finish();
+        // SR: RC=1, 13 kelime; SDES: 10 baytlık CNAME + bitiş sıfırı + 3 dolgu, 6 kelime.
+        let wire = hex("81c8 000c 12345678  e1b2c3d4 80000000  00027100 00000032 00001f40
+                        deadbeef 40000005 000103e8 00000010 00000000 00000000
+                        81ca 0005 12345678  01 0a 6d656469614068 6f7374 00000000");
+        assert_eq!(&buf[..len], &wire[..]);
+
+        // RR ile başlayan, gerekçeli BYE'la biten paket: 12 baytlık gerekçe 3 sıfırla tamamlanır.
+        let mut writer = CompoundWriter::new(&mut buf);
+        writer.receiver_report(1, &[]).unwrap().sdes_cname(1, "a@b").unwrap().bye(1, Some("max_duration")).unwrap();
+        let len = writer.finish();
+        let wire = hex("80c9 0001 00000001  81ca 0003 00000001 0103 61 40 62 00 0000  81cb 0005 00000001 0c 6d61785f6475726174696f6e 000000");
+        assert_eq!(&buf[..len], &wire[..]);
+        // Her paketin uzunluk alanı bir sonrakinin başına denk gelir.
+        assert_eq!(parse_voip_metrics(&buf[..len]), Ok(Vec::new()));
+
+        let mut small = [0u8; 8];
+        assert_eq!(CompoundWriter::new(&mut small).sdes_cname(1, "media@host").err(), Some(BuildError::BufferTooSmall { needed: 24, available: 8 }));
+        assert_eq!(CompoundWriter::new(&mut buf).bye(1, Some(&"x".repeat(256))).err(), Some(BuildError::TextTooLong { len: 256 }));
+        assert_eq!(CompoundWriter::new(&mut buf).receiver_report(1, &[block; 32]).err(), Some(BuildError::TooManyReportBlocks { count: 32 }));
+    }
+
+    #[test]
+    fn reports_parse_back_and_give_the_round_trip_time() {
+        let sender = SenderInfo { ntp_timestamp: 0xE1B2_C3D4_8000_0000, rtp_timestamp: 160_000, packets: 50, octets: 8000 };
+        let block = ReportBlock { ssrc: 7, fraction_lost: 0x40, cumulative_lost: -3, highest_sequence: 0x0001_03E8, jitter: 16, last_sr: 0xC3D4_8000, delay_since_last_sr: 0x0001_0000 };
+        let mut buf = [0u8; 256];
+        let mut writer = CompoundWriter::new(&mut buf);
+        writer.sender_report(1, &sender, &[block]).unwrap().sdes_cname(1, "a@b").unwrap().receiver_report(2, &[]).unwrap().bye(1, None).unwrap();
+        let len = writer.finish();
+        let reports = parse_reports(&buf[..len]).unwrap();
+        assert_eq!(reports, [Report { ssrc: 1, sender: Some(sender), blocks: vec![block] }, Report { ssrc: 2, sender: None, blocks: Vec::new() }]);
+
+        // SR 0xC3D4_8000'de gitti, karşıda 1 s bekledi, 1,25 s sonra döndü: RTT 250 ms.
+        assert_eq!(ntp_middle(sender.ntp_timestamp), 0xC3D4_8000);
+        assert_eq!(round_trip(&block, 0xC3D4_8000 + 0x0001_4000), Some(Duration::from_millis(250)));
+        assert_eq!(round_trip(&block, 0xC3D4_8000), None);
+        assert_eq!(round_trip(&ReportBlock { last_sr: 0, ..block }, 0xC3D5_8000), None);
+        assert_eq!(parse_reports(&hex("81c9 0001 00000001")), Err(RtcpError::BlockOverrun { declared: 32, len: 8 }));
+    }
+
+    #[test]
+    fn voip_metrics_golden_bytes_and_round_trip() {
+        let metrics = VoipMetrics {
+            ssrc: 0x1234_5678,
+            loss_rate: 0.05,
+            burst_density: 0.5,
+            gap_density: 0.01,
+            burst_duration_ms: 120,
+            gap_duration_ms: 5000,
+            round_trip_delay_ms: 80,
+            gmin: 16,
+            r_factor: Some(84),
+            mos_lq: Some(4.1),
+            ..VoipMetrics::default()
+        };
+        let mut buf = [0u8; 64];
+        let len = write_xr(0xDEAD_BEEF, &metrics, &mut buf).unwrap();
+        let wire = hex("80cf 000a deadbeef  07 00 0008 12345678  0c 00 80 02  0078 1388  0050 0000  7f7f7f10  547f297f  0000 0000  0000 0000");
+        assert_eq!(&buf[..len], &wire[..]);
+
+        let parsed = parse_voip_metrics(&wire).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!((parsed[0].ssrc, parsed[0].r_factor, parsed[0].mos_lq, parsed[0].mos_cq), (0x1234_5678, Some(84), Some(4.1), None));
+        assert_eq!((parsed[0].burst_duration_ms, parsed[0].gap_duration_ms, parsed[0].round_trip_delay_ms), (120, 5000, 80));
+        assert!((parsed[0].loss_rate - 0.05).abs() < 1.0 / 256.0);
+    }
+
+    #[test]
+    fn parser_skips_other_packets_and_blocks_and_rejects_overruns() {
+        // Önce bir RR (PT 201, rapor yok), sonra RRT bloğu (BT 4) ve VoIP bloğu taşıyan XR.
+        let mut compound = hex("80c9 0001 00000001");
+        let mut metrics = [0u8; 64];
+        let len = write_xr(1, &VoipMetrics { ssrc: 7, ..VoipMetrics::default() }, &mut metrics).unwrap();
+        let mut xr = metrics[..len].to_vec();
+        xr.splice(8..8, hex("04 00 0002 00000000 00000000"));
+        xr[3] += 3;
+        compound.extend_from_slice(&xr);
+        let parsed = parse_voip_metrics(&compound).unwrap();
+        assert_eq!(parsed.iter().map(|m| m.ssrc).collect::<Vec<_>>(), [7]);
+
+        assert_eq!(parse_voip_metrics(&[0x80, 207, 0, 9, 0, 0, 0, 1]), Err(RtcpError::LengthOverrun { declared: 40, len: 8 }));
+        assert_eq!(parse_voip_metrics(&hex("80cf 0002 00000001 07000008")), Err(RtcpError::BlockOverrun { declared: 36, len: 4 }));
+        assert_eq!(parse_voip_metrics(&[0x40, 207, 0, 0]), Err(RtcpError::Version { version: 1 }));
+        assert_eq!(parse_voip_metrics(&[0x80, 207]), Err(RtcpError::TooShort { len: 2 }));
+    }
+}
@@ -0,0 +1,617 @@
+// RTP portu tahsisi ve RTP paketlerinin ayrıştırılması/kurulması (RFC 3550 5.1).
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use rand::prelude::*;
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::time::Instant;
+
+use crate::config::{InterfaceConfig, RtpConfig, TenantConfig};
+use crate::error::{AllocationError, BuildError, ParseError};
+use crate::media::TransportKind;
+use crate::transport::{TcpTransport, Transport};
+
+pub const MAX_BIND_ATTEMPTS: u32 = 100;
+
+/// Tahsis için bağlanan port.
+#[derive(Debug)]
+pub struct Bound {
+    pub port: u16,
+    pub transport: Transport,
+    /// RTP portunun bir üstündeki tek porta bağlanan RTCP soketi (RFC 3550 11). TCP'de ve
+    /// işletim sisteminin atadığı portta yoktur; RTCP RTP portundan geçer (RFC 5761, RFC 4571).
+    pub rtcp: Option<UdpSocket>,
+    /// Havuz tükendiği için RTP aralığının dışından alındı (`rtp.allow_overflow`).
+    pub overflow: bool,
+}
+
+/// Tahsisin port çekebileceği kapalı aralıklar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortPool {
+    ranges: Vec<(u16, u16)>,
+    // Tükenince `rtp.allow_overflow`'a göre aralık dışına geçilebilir mi; kiracılar geçemez.
+    overflow: bool,
+}
+
+impl PortPool {
+    /// Kiracısız tahsislerin havuzu: RTP aralığının kiracılara ayrılmamış kısmı.
+    pub fn shared(rtp_config: &RtpConfig, tenants: &[TenantConfig]) -> Self {
+        PortPool::unclaimed((rtp_config.min_port, rtp_config.max_port), rtp_config.allow_overflow, tenants)
+    }
+
+    /// Arayüzün alt aralığının kiracılara ayrılmamış kısmı; taşma paylaşılan havuzdaki gibidir.
+    pub fn interface(rtp_config: &RtpConfig, interface: &InterfaceConfig, tenants: &[TenantConfig]) -> Self {
+        PortPool::unclaimed(interface.port_range(rtp_config), rtp_config.allow_overflow, tenants)
+    }
+
+    fn unclaimed((min, max): (u16, u16), overflow: bool, tenants: &[TenantConfig]) -> Self {
+        let mut claimed: Vec<(u16, u16)> = tenants.iter().map(|t| (t.min_port, t.max_port)).collect();
+        claimed.sort_unstable();
+        let (mut ranges, mut next) = (Vec::new(), Some(min));
+        for (min_port, max_port) in claimed {
+            if let Some(start) = next.filter(|&start| start < min_port) {
+                ranges.push((start, min_port - 1));
+            }
+            next = next.and_then(|start| if start > max_port { Some(start) } else { max_port.checked_add(1) });
+        }
+        if let Some(start) = next.filter(|&start| start <= max) {
+            ranges.push((start, max));
+        }
+        PortPool { ranges, overflow }
+    }
+
+    pub fn tenant(tenant: &TenantConfig) -> Self {
+        PortPool { ranges: vec![(tenant.min_port, tenant.max_port)], overflow: false }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.ranges.iter().map(|&(min_port, max_port)| (max_port - min_port) as u32 + 1).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    fn contains(&self, port: u16) -> bool {
+        self.ranges.iter().any(|&(min_port, max_port)| (min_port..=max_port).contains(&port))
+    }
+
+    fn nth(&self, mut index: u32) -> u16 {
+        for &(min_port, max_port) in &self.ranges {
+            let len = (max_port - min_port) as u32 + 1;
+            if index < len {
+                return min_port + index as u16;
+            }
+            index -= len;
+        }
+        unreachable!("index is below len()")
+    }
+}
+
+/// `host` üzerinde havuzdan rastgele port dener. Dönen sayı, başarılı olan dahil yapılan deneme sayısıdır.
+/// Port doluluğu dışındaki hatalarda (ör. adres bu makinede yok) tekrar denemeden döner. UDP'de
+/// RTP çift porta, RTCP bir üstündeki tek porta bağlanır; iki portu da havuzda olmayan ya da
+/// biri dolu olan çift atlanır. `rtcp_mux` ise RTCP RTP portundan geçeceğinden (RFC 5761) tek port
+/// yeterlidir. TCP'de port dinlemeye açılır (bkz. transport.rs). Havuz tükenmişse ve `rtp.allow_overflow` açıksa
+/// taşma aralığı, o da yoksa işletim sisteminin atadığı bir port denenir; kiracı havuzları taşmaz.
+pub async fn bind_rtp_port(rtp_config: &RtpConfig, host: &str, pool: &PortPool, kind: TransportKind, rtcp_mux: bool) -> (Result<Bound, AllocationError>, u32) {
+    if pool.is_empty() {
+        return (Err(AllocationError::NoSharedPorts), 0);
+    }
+    let pair = kind == TransportKind::Udp && !rtcp_mux;
+    let (bound, attempts) = bind_in_pool(host, pool, kind, pair).await;
+    match bound {
+        Err(AllocationError::PortsExhausted { .. }) if pool.overflow => {
+            let (overflow, extra) = match rtp_config.overflow_range() {
+                Some(range) => bind_in_pool(host, &PortPool { ranges: vec![range], overflow: false }, kind, pair).await,
+                None => (bind(host, 0, kind, false).await.map_err(|source| AllocationError::Bind { port: 0, source }), 1),
+            };
+            (overflow.map(|bound| Bound { overflow: true, ..bound }), attempts + extra)
+        }
+        bound => (bound, attempts),
+    }
+}
+
+async fn bind_in_pool(host: &str, pool: &PortPool, kind: TransportKind, pair: bool) -> (Result<Bound, AllocationError>, u32) {
+    let mut rng = SmallRng::from_entropy();
+    for attempt in 1..=MAX_BIND_ATTEMPTS {
+        let port = pool.nth(rng.gen_range(0..pool.len()));
+        // RTP çift portta, RTCP bir üstünde.
+        let port = if pair { port & !1 } else { port };
+        if pair && !(pool.contains(port) && pool.contains(port + 1)) {
+            continue;
+        }
+        match bind(host, port, kind, pair).await {
+            Ok(bound) => return (Ok(bound), attempt),
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => continue,
+            Err(source) => return (Err(AllocationError::Bind { port, source }), attempt),
+        }
+    }
+    let (min_port, max_port) = (pool.ranges[0].0, pool.ranges[pool.ranges.len() - 1].1);
+    (Err(AllocationError::PortsExhausted { min_port, max_port, attempts: MAX_BIND_ATTEMPTS }), MAX_BIND_ATTEMPTS)
+}
+
+/// `pair` ise RTCP soketi `port`'un bir üstüne bağlanır. `port` 0 ise işletim sistemi bir geçici
+/// port atar; dönen port bağlanan porttur.
+async fn bind(host: &str, port: u16, kind: TransportKind, pair: bool) -> std::io::Result<Bound> {
+    let addr_str = format!("{}:{}", host, port);
+    let transport = match kind {
+        TransportKind::Udp => UdpSocket::bind(&addr_str).await.map(Transport::from)?,
+        TransportKind::Tcp => TcpListener::bind(&addr_str).await.and_then(TcpTransport::new).map(Transport::Tcp)?,
+    };
+    let rtcp = if pair { Some(UdpSocket::bind(format!("{}:{}", host, port + 1)).await?) } else { None };
+    Ok(Bound { port: transport.local_addr()?.port(), transport, rtcp, overflow: false })
+}
+
+/// Sabit RTP başlığının uzunluğu (CSRC ve uzantı hariç).
+pub const HEADER_LEN: usize = 12;
+/// Gönderim tamponları için üst sınır; tek bir Ethernet MTU'su.
+pub const MAX_PACKET_LEN: usize = 1500;
+/// CC alanı 4 bittir; bir pakette en fazla 15 CSRC taşınır.
+pub const MAX_CSRCS: usize = 15;
+
+/// RFC 3550 5.3.1 başlık uzantısı: profile özel 16 bit kimlik ve 4 baytın katı uzunlukta veri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RtpExtension<'a> {
+    pub profile: u16,
+    pub data: &'a [u8],
+}
+
+/// Ayrıştırılmış bir RTP paketi; alanlar girdinin üzerinden okunur, kopyalama yapılmaz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RtpPacketRef<'a> {
+    buf: &'a [u8],
+    header_len: usize,
+    payload_end: usize,
+}
+
+impl<'a> RtpPacketRef<'a> {
+    /// Kimliği doğrulanmamış bir UDP portundan gelen baytları ayrıştırır. CSRC listesi, uzantı
+    /// veya dolgu paketin dışına taşan girdiler hata döner; hiçbir girdi paniğe yol açmaz.
+    pub fn parse(buf: &'a [u8]) -> Result<Self, ParseError> {
+        let len = buf.len();
+        if len < HEADER_LEN {
+            return Err(ParseError::TooShort { len });
+        }
+        let version = buf[0] >> 6;
+        if version != 2 {
+            return Err(ParseError::Version { version });
+        }
+        let count = (buf[0] & 0x0F) as usize;
+        let mut header_len = HEADER_LEN + 4 * count;
+        if header_len > len {
+            return Err(ParseError::CsrcOverrun { count, len });
+        }
+        if buf[0] & 0x10 != 0 {
+            let words = buf.get(header_len + 2..header_len + 4).ok_or(ParseError::ExtensionOverrun { len })?;
+            header_len += 4 + 4 * u16::from_be_bytes([words[0], words[1]]) as usize;
+            if header_len > len {
+                return Err(ParseError::ExtensionOverrun { len });
+            }
+        }
+        let mut payload_end = len;
+        if buf[0] & 0x20 != 0 {
+            let padding = buf[len - 1];
+            if padding == 0 || header_len + padding as usize > len {
+                return Err(ParseError::Padding { padding, len });
+            }
+            payload_end -= padding as usize;
+        }
+        Ok(RtpPacketRef { buf, header_len, payload_end })
+    }
+
+    pub fn version(&self) -> u8 {
+        self.buf[0] >> 6
+    }
+
+    pub fn padding(&self) -> bool {
+        self.buf[0] & 0x20 != 0
+    }
+
+    pub fn extension(&self) -> Option<RtpExtension<'a>> {
+        if self.buf[0] & 0x10 == 0 {
+            return None;
+        }
+        let start = HEADER_LEN + 4 * self.csrc_count();
+        Some(RtpExtension {
+            profile: u16::from_be_bytes([self.buf[start], self.buf[start + 1]]),
+            data: &self.buf[start + 4..self.header_len],
+        })
+    }
+
+    pub fn marker(&self) -> bool {
+        self.buf[1] & 0x80 != 0
+    }
+
+    pub fn payload_type(&self) -> u8 {
+        self.buf[1] & 0x7F
+    }
+
+    pub fn sequence(&self) -> u16 {
+        u16::from_be_bytes([self.buf[2], self.buf[3]])
+    }
+
+    pub fn timestamp(&self) -> u32 {
+        u32::from_be_bytes([self.buf[4], self.buf[5], self.buf[6], self.buf[7]])
+    }
+
+    pub fn ssrc(&self) -> u32 {
+        u32::from_be_bytes([self.buf[8], self.buf[9], self.buf[10], self.buf[11]])
+    }
+
+    pub fn csrc_count(&self) -> usize {
+        (self.buf[0] & 0x0F) as usize
+    }
+
+    pub fn csrcs(&self) -> impl Iterator<Item = u32> + 'a {
+        self.buf[HEADER_LEN..HEADER_LEN + 4 * self.csrc_count()]
+            .chunks_exact(4)
+            .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+    }
+
+    /// Dolgu hariç yük.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.buf[self.header_len..self.payload_end]
+    }
+}
+
+/// Giden paket tanımı; `write` çağıranın tamponuna yazar, gönderim yolunda bellek ayırmaz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RtpPacket<'a> {
+    pub marker: bool,
+    pub payload_type: u8,
+    pub sequence: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+    pub csrcs: &'a [u32],
+    pub extension: Option<RtpExtension<'a>>,
+    /// Sona eklenecek dolgu baytı sayısı (son bayt dahil); 0 ise dolgu yok.
+    pub padding: u8,
+    pub payload: &'a [u8],
+}
+
+impl<'a> RtpPacket<'a> {
+    /// CSRC, uzantı ve dolgu olmadan bir paket.
+    pub fn new(payload_type: u8, sequence: u16, timestamp: u32, ssrc: u32, payload: &'a [u8]) -> Self {
+        RtpPacket { marker: false, payload_type, sequence, timestamp, ssrc, csrcs: &[], extension: None, padding: 0, payload }
+    }
+
+    /// Kablodaki uzunluk.
+    pub fn encoded_len(&self) -> usize {
+        let extension = self.extension.map_or(0, |e| 4 + e.data.len());
+        HEADER_LEN + 4 * self.csrcs.len() + extension + self.payload.len() + self.padding as usize
+    }
+
+    /// Paketi `buf`'ın başına yazar ve yazılan bayt sayısını döner.
+    pub fn write(&self, buf: &mut [u8]) -> Result<usize, BuildError> {
+        if self.csrcs.len() > MAX_CSRCS {
+            return Err(BuildError::TooManyCsrcs { count: self.csrcs.len() });
+        }
+        if let Some(extension) = self.extension {
+            if extension.data.len() % 4 != 0 || extension.data.len() / 4 > u16::MAX as usize {
+                return Err(BuildError::ExtensionNotAligned { len: extension.data.len() });
+            }
+        }
+        let needed = self.encoded_len();
+        if needed > buf.len() {
+            return Err(BuildError::BufferTooSmall { needed, available: buf.len() });
+        }
+
+        let mut first = 0x80 | self.csrcs.len() as u8;
+        if self.padding > 0 { first |= 0x20; }
+        if self.extension.is_some() { first |= 0x10; }
+        buf[0] = first;
+        buf[1] = (self.marker as u8) << 7 | (self.payload_type & 0x7F);
+        buf[2..4].copy_from_slice(&self.sequence.to_be_bytes());
+        buf[4..8].copy_from_slice(&self.timestamp.to_be_bytes());
+        buf[8..12].copy_from_slice(&self.ssrc.to_be_bytes());
+        let mut at = HEADER_LEN;
+        for csrc in self.csrcs {
+            buf[at..at + 4].copy_from_slice(&csrc.to_be_bytes());
+            at += 4;
+        }
+        if let Some(extension) = self.extension {
+            buf[at..at + 2].copy_from_slice(&extension.profile.to_be_bytes());
+            buf[at + 2..at + 4].copy_from_slice(&((extension.data.len() / 4) as u16).to_be_bytes());
+            buf[at + 4..at + 4 + extension.data.len()].copy_from_slice(extension.data);
+            at += 4 + extension.data.len();
+        }
+        buf[at..at + self.payload.len()].copy_from_slice(self.payload);
+        at += self.payload.len();
+        if self.padding > 0 {
+            let end = at + self.padding as usize;
+            buf[at..end - 1].fill(0);
+            buf[end - 1] = self.padding;
+            at = end;
+        }
+        Ok(at)
+    }
+}
+
+fn digest(packet: &RtpPacketRef) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (packet.timestamp(), packet.payload()).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// RFC 3389 konfor gürültüsü payload type'ı (8 kHz).
+pub const COMFORT_NOISE_PT: u8 = 13;
+
+// Yansıma denetimi için hatırlanan son giden paketler; 20 ms ptime'da 1,28 saniye.
+const SENT_HISTORY: usize = 64;
+
+/// Bir oturumun giden RTP akışı. SSRC ve başlangıç değerleri tahsiste bir kez seçilir; anons,
+/// ton ve keepalive gibi bütün göndericiler sıra numarasını ve zaman damgasını buradan alır,
+/// böylece karşı uç tek ve kesintisiz bir akış görür.
+#[derive(Debug)]
+pub struct RtpStream {
+    pub ssrc: u32,
+    // İlk paketin sıra numarası ve zaman damgası.
+    initial: (u16, u32),
+    clock_rate: u32,
+    state: Mutex<StreamState>,
+    // Son giden paketlerin sıra numarası ve zaman damgası ile yükünün özeti.
+    sent: Mutex<VecDeque<(u16, u64)>>,
+}
+
+#[derive(Debug)]
+struct StreamState {
+    sequence: u16,
+    base_timestamp: u32,
+    started: Option<Instant>,
+    // Son paketin hemen arkasından gelecek paketin zaman damgası.
+    contiguous: u32,
+}
+
+/// Akışın başlangıç değerleri; verilmeyenler rastgele seçilir. Sabitlenmiş değerler yalnızca
+/// yakalanan RTP'nin altın kayıtlarla karşılaştırıldığı test ve tekrar senaryoları içindir.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamSeed {
+    pub ssrc: Option<u32>,
+    pub sequence: Option<u16>,
+    pub timestamp: Option<u32>,
+}
+
+impl RtpStream {
+    pub fn new(clock_rate: u32) -> Self {
+        Self::seeded(clock_rate, StreamSeed::default())
+    }
+
+    pub fn seeded(clock_rate: u32, seed: StreamSeed) -> Self {
+        let mut rng = rand::thread_rng();
+        let (ssrc, sequence, base_timestamp) = (rng.gen(), rng.gen(), rng.gen());
+        let (sequence, base_timestamp) = (seed.sequence.unwrap_or(sequence), seed.timestamp.unwrap_or(base_timestamp));
+        RtpStream {
+            ssrc: seed.ssrc.unwrap_or(ssrc),
+            initial: (sequence, base_timestamp),
+            clock_rate,
+            state: Mutex::new(StreamState { sequence, base_timestamp, started: None, contiguous: base_timestamp }),
+            sent: Mutex::new(VecDeque::with_capacity(SENT_HISTORY)),
+        }
+    }
+
+    /// Giden paketi yansıma denetimi için hatırlar; bu akışa ait olmayanlar atlanır.
+    pub fn remember_sent(&self, packet: &RtpPacketRef) {
+        if packet.ssrc() != self.ssrc {
+            return;
+        }
+        let mut sent = self.sent.lock().unwrap();
+        if sent.len() == SENT_HISTORY {
+            sent.pop_front();
+        }
+        sent.push_back((packet.sequence(), digest(packet)));
+    }
+
+    /// Gelen paket bu akışın yakın zamanda gönderdiği bir paketin kendisi mi (uzak uç ya da
+    /// arada bir cihaz paketlerimizi geri yansıtıyor): SSRC, sıra numarası, zaman damgası ve yük
+    /// aynı. Yalnızca SSRC çakışması yansıma sayılmaz.
+    pub fn is_reflection(&self, packet: &RtpPacketRef) -> bool {
+        packet.ssrc() == self.ssrc && self.sent.lock().unwrap().contains(&(packet.sequence(), digest(packet)))
+    }
+
+    /// Akışın ilk paketinin taşıdığı (ya da taşıyacağı) sıra numarası ve zaman damgası.
+    pub fn initial(&self) -> (u16, u32) {
+        self.initial
+    }
+
+    /// `at` anında gönderilecek, zaman damgasını `increment` kadar ilerleten paketin (bkz.
+    /// `Codec::timestamp_increment`) sıra numarası ve zaman damgası. Zaman damgası akışın başından
+    /// beri geçen süreyi izler; sessiz aralıklarda bu süre kadar ilerler, ardışık paketlerde ise
+    /// tam olarak önceki paketin artışı kadar.
+    pub fn next(&self, at: Instant, increment: u32) -> (u16, u32) {
+        let mut state = self.state.lock().unwrap();
+        state.started.get_or_insert(at);
+        let timestamp = self.timestamp_for(&state, at);
+        let sequence = state.sequence;
+        state.sequence = sequence.wrapping_add(1);
+        state.contiguous = timestamp.wrapping_add(increment);
+        (sequence, timestamp)
+    }
+
+    /// `at` anının akış saatindeki karşılığı (RTCP SR için); paket göndermez. Akış henüz
+    /// başlamadıysa ilk zaman damgası.
+    pub fn timestamp_at(&self, at: Instant) -> u32 {
+        let state = self.state.lock().unwrap();
+        let Some(started) = state.started else { return state.base_timestamp };
+        let elapsed = (at.saturating_duration_since(started).as_nanos() * self.clock_rate as u128 / 1_000_000_000) as u32;
+        state.base_timestamp.wrapping_add(elapsed)
+    }
+
+    /// `at` anında gönderilecek paketin alacağı sıra numarası ve zaman damgası; akışı ilerletmez.
+    /// Oturum başka bir node'a taşınırken akışın oradan devam etmesi için (bkz. migration.rs).
+    pub fn resume_point(&self, at: Instant) -> (u16, u32) {
+        let state = self.state.lock().unwrap();
+        match state.started {
+            Some(_) => (state.sequence, self.timestamp_for(&state, at)),
+            None => (state.sequence, state.base_timestamp),
+        }
+    }
+
+    // Saatin gösterdiği ile son paketin arkasından geleceği zaman damgasından ileride olanı.
+    fn timestamp_for(&self, state: &StreamState, at: Instant) -> u32 {
+        let started = state.started.unwrap_or(at);
+        let elapsed = (at.saturating_duration_since(started).as_nanos() * self.clock_rate as u128 / 1_000_000_000) as u32;
+        let by_clock = state.base_timestamp.wrapping_add(elapsed);
+        if (by_clock.wrapping_sub(state.contiguous) as i32) > 0 { by_clock } else { state.contiguous }
+    }
+
+    /// Zaman damgasını ilerletmeden bir sonraki sıra numarası; aynı zaman damgasını taşıyan
+    /// paketler (RFC 4733 olay güncellemeleri) için.
+    pub fn next_sequence(&self) -> u16 {
+        let mut state = self.state.lock().unwrap();
+        let sequence = state.sequence;
+        state.sequence = sequence.wrapping_add(1);
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn shared_pool_skips_tenant_ranges() {
+        let rtp = RtpConfig {
+            host: "127.0.0.1".to_string(), advertise_address: String::new(), min_port: 10000, max_port: 10099, codecs: vec![], red_generations: 1, allow_tcp: true,
+            allow_overflow: true, overflow_min_port: 0, overflow_max_port: 0, recv_batch: 1, send_schedulers: 0, enable_impairments: false,
+        };
+        let tenant = |min_port, max_port| TenantConfig { name: "t".to_string(), min_port, max_port, ..TenantConfig::default() };
+        let pool = PortPool::shared(&rtp, &[tenant(10090, 10099), tenant(10010, 10019), tenant(10020, 10029)]);
+        assert_eq!(pool.ranges, [(10000, 10009), (10030, 10089)]);
+        assert_eq!((pool.len(), pool.nth(9), pool.nth(10), pool.nth(69)), (70, 10009, 10030, 10089));
+        assert!(pool.overflow && !PortPool::tenant(&tenant(10010, 10019)).overflow);
+        assert!(PortPool::shared(&rtp, &[tenant(10000, 10099)]).is_empty());
+
+        // Arayüzün alt aralığı da kiracılarınkini atlar; alt aralığı olmayan arayüz bütün aralığı kullanır.
+        let interface = |min_port, max_port| InterfaceConfig { name: "lan".to_string(), address: "10.0.0.5".to_string(), min_port, max_port, ..InterfaceConfig::default() };
+        assert_eq!(PortPool::interface(&rtp, &interface(10005, 10040), &[tenant(10010, 10019)]).ranges, [(10005, 10009), (10020, 10040)]);
+        assert_eq!(PortPool::interface(&rtp, &interface(0, 0), &[]), PortPool::shared(&rtp, &[]));
+    }
+
+    fn hex(s: &str) -> Vec<u8> {
+        let s: String = s.split_whitespace().collect();
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn build(packet: &RtpPacket) -> Vec<u8> {
+        let mut buf = [0u8; MAX_PACKET_LEN];
+        let len = packet.write(&mut buf).unwrap();
+        assert_eq!(len, packet.encoded_len());
+        buf[..len].to_vec()
+    }
+
+    #[test]
+    fn plain_packet_golden_bytes() {
+        let packet = RtpPacket { marker: true, ..RtpPacket::new(0, 0x1234, 320, 0xDEAD_BEEF, &[0xFF, 0x7F]) };
+        let wire = hex("80 80 1234 00000140 deadbeef ff7f");
+        assert_eq!(build(&packet), wire);
+
+        let parsed = RtpPacketRef::parse(&wire).unwrap();
+        assert_eq!((parsed.version(), parsed.padding(), parsed.marker()), (2, false, true));
+        assert_eq!((parsed.payload_type(), parsed.sequence(), parsed.timestamp(), parsed.ssrc()), (0, 0x1234, 320, 0xDEAD_BEEF));
+        assert_eq!(parsed.csrcs().count(), 0);
+        assert_eq!(parsed.extension(), None);
+        assert_eq!(parsed.payload(), &[0xFF, 0x7F]);
+    }
+
+    #[test]
+    fn csrc_extension_and_padding_golden_bytes() {
+        let packet = RtpPacket {
+            csrcs: &[0x1111_1111, 0x2222_2222],
+            extension: Some(RtpExtension { profile: 0xBEDE, data: &[0x10, 0x01, 0x00, 0x00] }),
+            padding: 3,
+            ..RtpPacket::new(8, 0xFFFF, 0xFFFF_FF60, 0x0102_0304, &[0xD5; 4])
+        };
+        let wire = hex("b2 08 ffff ffffff60 01020304  11111111 22222222  bede 0001 10010000  d5d5d5d5  000003");
+        assert_eq!(build(&packet), wire);
+
+        let parsed = RtpPacketRef::parse(&wire).unwrap();
+        assert!(parsed.padding());
+        assert_eq!(parsed.payload_type(), 8);
+        assert_eq!(parsed.csrcs().collect::<Vec<_>>(), [0x1111_1111, 0x2222_2222]);
+        assert_eq!(parsed.extension(), Some(RtpExtension { profile: 0xBEDE, data: &[0x10, 0x01, 0x00, 0x00] }));
+        assert_eq!(parsed.payload(), &[0xD5; 4]);
+    }
+
+    #[test]
+    fn parser_honors_each_header_flag() {
+        // (paket, CSRC'ler, uzantı, yük); başlık hep "PT 0, seq 1, ts 0, ssrc 1".
+        let ext = |profile, data| Some(RtpExtension { profile, data });
+        type Case<'a> = (&'a str, &'a [u32], Option<RtpExtension<'a>>, &'a [u8]);
+        let cases: [Case; 7] = [
+            ("81 00 0001 00000000 00000001  aaaaaaaa  0102", &[0xAAAA_AAAA], None, &[1, 2]),
+            ("90 00 0001 00000000 00000001  1000 0000  0102", &[], ext(0x1000, &[]), &[1, 2]),
+            // İki baytlık başlık biçimi (RFC 8285) ve ROC ipucu gibi 4 baytlık veri.
+            ("90 00 0001 00000000 00000001  0100 0001 12345678  ff", &[], ext(0x0100, &[0x12, 0x34, 0x56, 0x78]), &[0xFF]),
+            ("a0 00 0001 00000000 00000001  0102  000003", &[], None, &[1, 2]),
+            // Yükün tamamı dolgu.
+            ("a0 00 0001 00000000 00000001  01", &[], None, &[]),
+            ("91 00 0001 00000000 00000001  aaaaaaaa  bede 0001 11223344  09", &[0xAAAA_AAAA], ext(0xBEDE, &[0x11, 0x22, 0x33, 0x44]), &[9]),
+            ("b0 00 0001 00000000 00000001  bede 0000  0708  0002", &[], ext(0xBEDE, &[]), &[7, 8]),
+        ];
+        for (wire, csrcs, extension, payload) in cases {
+            let wire = hex(wire);
+            let parsed = RtpPacketRef::parse(&wire).unwrap();
+            assert_eq!((parsed.sequence(), parsed.ssrc()), (1, 1), "{wire:02x?}");
+            assert_eq!(parsed.csrcs().collect::<Vec<_>>(), csrcs, "{wire:02x?}");
+            assert_eq!(parsed.extension(), extension, "{wire:02x?}");
+            assert_eq!(parsed.payload(), payload, "{wire:02x?}");
+        }
+    }
+
+    #[test]
+    fn builder_rejects_what_it_cannot_encode() {
+        let payload = [0u8; 160];
+        let mut small = [0u8; 100];
+        assert_eq!(RtpPacket::new(0, 0, 0, 0, &payload).write(&mut small), Err(BuildError::BufferTooSmall { needed: 172, available: 100 }));
+        let csrcs = [0u32; 16];
+        assert_eq!(RtpPacket { csrcs: &csrcs, ..RtpPacket::new(0, 0, 0, 0, &[]) }.write(&mut small), Err(BuildError::TooManyCsrcs { count: 16 }));
+        let extension = Some(RtpExtension { profile: 0xBEDE, data: &[1, 2, 3] });
+        assert_eq!(RtpPacket { extension, ..RtpPacket::new(0, 0, 0, 0, &[]) }.write(&mut small), Err(BuildError::ExtensionNotAligned { len: 3 }));
+    }
+
+    #[test]
+    fn stream_timestamps_follow_samples_and_silent_gaps() {
+        let stream = RtpStream::new(8000);
+        let t0 = Instant::now();
+        let (seq0, ts0) = stream.next(t0, 160);
+        // Zamanında gelen ve biraz geciken paketler ardışık kalır.
+        assert_eq!(stream.next(t0 + Duration::from_millis(20), 160), (seq0.wrapping_add(1), ts0.wrapping_add(160)));
+        assert_eq!(stream.next(t0 + Duration::from_millis(39), 160), (seq0.wrapping_add(2), ts0.wrapping_add(320)));
+        // Bir saniyelik sessizlikten sonra zaman damgası geçen süre kadar ilerler.
+        assert_eq!(stream.next(t0 + Duration::from_millis(1060), 160), (seq0.wrapping_add(3), ts0.wrapping_add(8480)));
+        // Yüksüz paket (CN) süre taşımaz.
+        assert_eq!(stream.next(t0 + Duration::from_millis(1060), 0), (seq0.wrapping_add(4), ts0.wrapping_add(8640)));
+        // Devam noktası akışı ilerletmez; taşınan akış bir sonraki paketten sürer.
+        let resume = stream.resume_point(t0 + Duration::from_millis(2060));
+        assert_eq!(resume, (seq0.wrapping_add(5), ts0.wrapping_add(16480)));
+        assert_eq!(stream.next(t0 + Duration::from_millis(2060), 160), resume);
+    }
+
+    #[test]
+    fn parser_rejects_overruns() {
+        // Fuzz regresyonları: taşan CSRC sayısı, kesik uzantı başlığı, paketten büyük uzantı,
+        // sıfır ve paketten büyük dolgu, kısa ve sürüm 2 olmayan girdiler.
+        let padding_into_extension = hex("b0 00 0001 00000000 00000001  bede 0001 11223344  05");
+        let csrc_and_long_extension = hex("91 00 0001 00000000 00000001  aaaaaaaa  bede 0002 11223344");
+        let cases: [(&[u8], ParseError); 10] = [
+            (&[0x8F, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], ParseError::CsrcOverrun { count: 15, len: 12 }),
+            (&[0x90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xBE], ParseError::ExtensionOverrun { len: 13 }),
+            (&[0x90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xBE, 0xDE, 0xFF, 0xFF], ParseError::ExtensionOverrun { len: 16 }),
+            (&[0xA0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], ParseError::Padding { padding: 0, len: 14 }),
+            (&[0xA0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF], ParseError::Padding { padding: 0xFF, len: 13 }),
+            (&[0x80, 0, 0, 0], ParseError::TooShort { len: 4 }),
+            (&[0x40, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], ParseError::Version { version: 1 }),
+            (&[], ParseError::TooShort { len: 0 }),
+            // Bayrak birleşimleri: dolgu uzantıya taşıyor, CSRC'den sonra uzantı paketten uzun.
+            (&padding_into_extension, ParseError::Padding { padding: 5, len: 21 }),
+            (&csrc_and_long_extension, ParseError::ExtensionOverrun { len: 24 }),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(RtpPacketRef::parse(input), Err(expected), "{input:02x?}");
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Giden sesin zamanlayıcıları. Oturumlar birkaç dilime paylaştırılır; her dilimin tek bir görevi
+// ptime'da bir uyanır ve dilimdeki çalan oturumlara sırayla birer çerçeve gönderir. Binlerce
+// oturumda her oturumun kendi 20 ms zamanlayıcısıyla uyanması yerine dilim başına bir uyanış
+// olur; oturum görevleri yalnızca gelen paketler, istekler ve süreler için uyanır. Göndericinin
+// durumu oturumdadır (`RtpSession::player`), bu yüzden dilim görevi ve oturum görevi aynı
+// göndericiye sırayla erişir. Dilimde çalan oturum kalmayınca görev, yeni bir oynatma başlayana
+// kadar uyur.
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Instant};
+use tracing::Instrument;
+
+use crate::audit::PlaybackStopReason;
+use crate::metrics;
+use crate::sdp::Direction;
+use crate::session::RtpSession;
+
+/// `rtp.send_schedulers` için üst sınır.
+pub const MAX_SCHEDULERS: usize = 256;
+
+#[derive(Debug, Default)]
+struct Shard {
+    sessions: Mutex<Vec<Arc<RtpSession>>>,
+    // Dilimdeki bir oturumda oynatma başladı; uyuyan görev tempoyu yeniden başlatır.
+    wake: Notify,
+}
+
+/// Dilim görevleri; düşürülünce görevler durur.
+#[derive(Debug)]
+pub struct SendScheduler {
+    shards: Vec<Arc<Shard>>,
+    tasks: Vec<JoinHandle<()>>,
+    next: AtomicUsize,
+}
+
+impl SendScheduler {
+    /// `shards` görevi başlatır; çalışan bir tokio çalışma zamanı içinde çağrılmalıdır.
+    pub fn start(shards: usize, ptime: Duration) -> Self {
+        let shards: Vec<Arc<Shard>> = (0..shards.max(1)).map(|_| Arc::default()).collect();
+        let tasks = shards.iter().map(|shard| tokio::spawn(run(shard.clone(), ptime))).collect();
+        SendScheduler { shards, tasks, next: AtomicUsize::new(0) }
+    }
+
+    /// Oturumu sıradaki dilime ekler; dönen üyelik düşürülünce oturum dilimden çıkar.
+    pub fn join(&self, session: Arc<RtpSession>) -> Membership {
+        let shard = self.shards[self.next.fetch_add(1, Ordering::Relaxed) % self.shards.len()].clone();
+        shard.sessions.lock().unwrap().push(session.clone());
+        Membership { shard, session }
+    }
+}
+
+impl Drop for SendScheduler {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Oturumun dilim üyeliği.
+#[derive(Debug)]
+pub struct Membership {
+    shard: Arc<Shard>,
+    session: Arc<RtpSession>,
+}
+
+impl Membership {
+    /// Oturumda oynatma başlamış olabilir; dilim uyuyorsa tempoyu başlatır.
+    pub fn wake(&self) {
+        self.shard.wake.notify_one();
+    }
+}
+
+impl Drop for Membership {
+    fn drop(&mut self) {
+        self.shard.sessions.lock().unwrap().retain(|session| !Arc::ptr_eq(session, &self.session));
+    }
+}
+
+async fn run(shard: Arc<Shard>, ptime: Duration) {
+    let mut due = Vec::new();
+    loop {
+        shard.wake.notified().await;
+        // İlk tik hemen gelir; yeni oynatmanın ilk çerçevesi beklemeden gider.
+        let mut pacer = interval(ptime);
+        loop {
+            let scheduled = pacer.tick().await;
+            due.clear();
+            due.extend(shard.sessions.lock().unwrap().iter().cloned());
+            let mut playing = false;
+            for session in due.drain(..) {
+                let span = session.span.clone();
+                playing |= send(&session, scheduled).instrument(span).await;
+            }
+            if !playing {
+                break;
+            }
+        }
+    }
+}
+
+/// Çalan oturuma bir çerçeve gönderir; oturum hâlâ çalıyorsa true. Oynatma burada biterse oturum
+/// görevi uyandırılır (veda anonsu sonrası kapanış ve zaman aşımı için).
+async fn send(session: &RtpSession, scheduled: Instant) -> bool {
+    let mut player = session.player.lock().await;
+    if !player.is_playing() {
+        return false;
+    }
+    let direction = session.direction();
+    // Bekleme sesi oturum sendonly'den çıkınca biter; kuyrukta bekleyen varsa yerine o başlar.
+    if player.plays_until_resumed() && direction != Direction::SendOnly {
+        player.stop(session, PlaybackStopReason::Resumed);
+        if !player.is_playing() {
+            session.playback_idle.notify_one();
+            return false;
+        }
+    }
+    // Göndermeyen yönde (recvonly, inactive) oynatma yerinde durur; yön değişince oturum görevi
+    // dilimi yeniden uyandırır.
+    if !direction.sends() {
+        return false;
+    }
+    metrics::get().send_loop_lag.observe(scheduled.elapsed());
+    let target = *session.remote_addr.lock().unwrap();
+    match target {
+        // Köprüden ayrılınca başlayan dolgu, yeniden köprülenen bacakta aktarılan sesle karışmaz.
+        _ if player.plays_until_bridged() && session.bridged_peer().is_some() => {
+            player.stop(session, PlaybackStopReason::Bridged);
+        }
+        Some(target) => player.send_scheduled(session, target, scheduled).await,
+        None => player.stop(session, PlaybackStopReason::SendError),
+    }
+    let playing = player.is_playing();
+    if !playing {
+        session.playback_idle.notify_one();
+    }
+    playing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::playback::Playback;
+    use crate::source::SampleSource;
+
+    #[tokio::test(start_paused = true)]
+    async fn one_task_paces_every_playing_session_then_sleeps() {
+        let scheduler = SendScheduler::start(1, Duration::from_millis(20));
+        let mut legs = Vec::new();
+        for frames in [3, 5] {
+            let (session, peer) = crate::session::RtpSession::for_test().await;
+            *session.remote_addr.lock().unwrap() = Some(peer.local_addr().unwrap());
+            let playback = Playback::new("test", Box::new(SampleSource::new(Arc::new(vec![0; 160 * frames]), false)));
+            session.player.lock().await.install(&session, playback);
+            let membership = scheduler.join(session.clone());
+            membership.wake();
+            legs.push((session, Some(peer), membership, frames));
+        }
+
+        let readers: Vec<_> = legs.iter_mut().map(|(_, peer, _, frames)| {
+            let (peer, frames) = (peer.take().unwrap(), *frames);
+            tokio::spawn(async move {
+                let mut buf = [0u8; 2048];
+                let mut timestamps = Vec::new();
+                for _ in 0..frames {
+                    peer.recv_from(&mut buf).await.unwrap();
+                    timestamps.push(u32::from_be_bytes(buf[4..8].try_into().unwrap()));
+                }
+                (timestamps, peer.try_recv_from(&mut buf).is_err())
+            })
+        }).collect();
+        let mut finished = Vec::new();
+        for ((session, _, _, frames), reader) in legs.iter().zip(readers) {
+            let (timestamps, drained) = reader.await.unwrap();
+            // Zaman damgası planlanan tikten hesaplanır; her tik 20 ms'lik bir çerçevedir.
+            assert_eq!(timestamps.len(), *frames);
+            assert!(timestamps.windows(2).all(|pair| pair[1].wrapping_sub(pair[0]) == 160), "{:?}", timestamps);
+            assert!(drained);
+            session.playback_idle.notified().await;
+            assert!(!session.player.lock().await.is_playing());
+            finished.push(*session.last_sent.lock().unwrap());
+        }
+        // İki oturum aynı tiklerde gönderildi: uzun olanın son paketi iki tik sonra gitti.
+        assert_eq!(finished[1] - finished[0], Duration::from_millis(40));
+
+        assert_eq!(scheduler.shards[0].sessions.lock().unwrap().len(), 2);
+        legs.clear();
+        assert!(scheduler.shards[0].sessions.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,448 @@
+// SDP (RFC 4566) teklif/cevap (RFC 3264) gövdeleri: GenerateSdp oturumun anlaşılmış durumundan
+// yerel gövdeyi üretir, cevap verirken uzak teklifin ses akışını okur. m= satırında oturumun
+// codec'i, oturum henüz medya göndermediyse ardından node'da açık diğer codec'ler tercih sırasıyla,
+// sonra da oturumda açık olan RED, telephone-event ve CN listelenir; cevapta ortak codec seçilir.
+// UDP'de RTCP ayrı tek porttadır (a=rtcp), ayrı portu olmayan oturumda RTP portundadır
+// (rtcp-mux); TCP oturumlarında (RFC 4571) node portu dinlediği için pasif taraftır. Ayrıştırıcı ses akışı için gerekenleri okur, tanımadığı satırları
+// ve ilk etkin ses akışı dışındaki akışları atlar; biçimi bozuk satırı numarasıyla bildirir.
+use std::fmt::Write;
+use std::net::{IpAddr, SocketAddr};
+
+use crate::codec::Codec;
+use crate::error::SdpError;
+use crate::red::RedConfig;
+use crate::rtp::COMFORT_NOISE_PT;
+
+// rtpmap'i olmayan yük tipleri bunlarla okunur (RFC 3551).
+const STATIC_PAYLOAD_TYPES: [(u8, &str, u32); 3] = [(0, "PCMU", 8000), (8, "PCMA", 8000), (13, "CN", 8000)];
+const AUDIO_LEVEL_URI: &str = "urn:ietf:params:rtp-hdrext:ssrc-audio-level";
+// RFC 4733'ün DTMF olayları: 0-9, *, #, A-D ve flash.
+const DTMF_EVENTS: &str = "0-16";
+
+/// Akışın yönü (RFC 3264 5.1); belirtilmemişse sendrecv.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    SendRecv,
+    SendOnly,
+    RecvOnly,
+    Inactive,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::SendRecv, Direction::SendOnly, Direction::RecvOnly, Direction::Inactive];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Direction::SendRecv => "sendrecv",
+            Direction::SendOnly => "sendonly",
+            Direction::RecvOnly => "recvonly",
+            Direction::Inactive => "inactive",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Direction::ALL.into_iter().find(|d| d.as_str() == value)
+    }
+
+    pub fn from_flags(sends: bool, receives: bool) -> Self {
+        match (sends, receives) {
+            (true, true) => Direction::SendRecv,
+            (true, false) => Direction::SendOnly,
+            (false, true) => Direction::RecvOnly,
+            (false, false) => Direction::Inactive,
+        }
+    }
+
+    pub fn sends(self) -> bool {
+        matches!(self, Direction::SendRecv | Direction::SendOnly)
+    }
+
+    pub fn receives(self) -> bool {
+        matches!(self, Direction::SendRecv | Direction::RecvOnly)
+    }
+
+    /// `offer`'a cevabın yönü: yerel uç yalnızca teklifin izin verdiği yönde gönderir ve alır.
+    pub fn answer(self, offer: Direction) -> Direction {
+        Direction::from_flags(self.sends() && offer.receives(), self.receives() && offer.sends())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RtpMap {
+    pub payload_type: u8,
+    pub encoding: String,
+    pub clock_rate: u32,
+}
+
+/// Uzak gövdenin ilk etkin ses akışı.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteAudio {
+    /// Akışın ya da yoksa oturumun c= adresi.
+    pub address: IpAddr,
+    pub port: u16,
+    pub protocol: String,
+    /// m= satırındaki sırayla, yani uzak ucun tercih sırasıyla.
+    pub payload_types: Vec<u8>,
+    pub rtpmaps: Vec<RtpMap>,
+    pub ptime_ms: Option<u32>,
+    pub direction: Direction,
+    pub rtcp_mux: bool,
+}
+
+impl RemoteAudio {
+    /// `encoding`/`clock_rate`'i taşıyan ilk yük tipi; kodlama adında büyük/küçük harf ayrılmaz.
+    pub fn payload_type(&self, encoding: &str, clock_rate: u32) -> Option<u8> {
+        self.payload_types.iter().copied()
+            .find(|&pt| self.encoding(pt).is_some_and(|(name, rate)| name.eq_ignore_ascii_case(encoding) && rate == clock_rate))
+    }
+
+    /// Yük tipinin kodlama adı ve saat hızı: rtpmap'ten, yoksa statik tiplerden.
+    fn encoding(&self, payload_type: u8) -> Option<(&str, u32)> {
+        self.rtpmaps.iter().find(|map| map.payload_type == payload_type).map(|map| (map.encoding.as_str(), map.clock_rate))
+            .or_else(|| STATIC_PAYLOAD_TYPES.iter().find(|(pt, ..)| *pt == payload_type).map(|&(_, name, rate)| (name, rate)))
+    }
+
+    /// Uzak ucun RTP adresi; adres belirtilmemişse (0.0.0.0, bekletme) yok.
+    pub fn endpoint(&self) -> Option<SocketAddr> {
+        (!self.address.is_unspecified()).then(|| SocketAddr::new(self.address, self.port))
+    }
+
+    pub fn tcp(&self) -> bool {
+        self.protocol.starts_with("TCP/")
+    }
+
+    /// Teklifteki kodlamalar m= sırasıyla; hata mesajları için.
+    pub(crate) fn offered(&self) -> String {
+        self.payload_types.iter()
+            .map(|&pt| self.encoding(pt).map_or_else(|| pt.to_string(), |(name, rate)| format!("{}/{}", name, rate)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Session,
+    Audio,
+    // Atlanan akışlar: ses dışı, reddedilmiş (port 0) ya da ilkinden sonraki ses akışları.
+    Other,
+}
+
+/// Uzak gövdenin ilk etkin ses akışını okur.
+pub fn parse(body: &str) -> Result<RemoteAudio, SdpError> {
+    let mut section = Section::Session;
+    let (mut session_address, mut session_direction) = (None, None);
+    let (mut audio, mut audio_address, mut audio_direction) = (None::<RemoteAudio>, None, None);
+    let mut started = false;
+
+    for (index, text) in body.lines().enumerate() {
+        if text.is_empty() {
+            continue;
+        }
+        let malformed = |reason: &'static str| SdpError::Malformed { line: index + 1, text: text.to_string(), reason };
+        let (kind, value) = match text.as_bytes() {
+            [kind @ b'a'..=b'z', b'=', ..] => (*kind, &text[2..]),
+            _ => return Err(malformed("expected <type>=<value>")),
+        };
+        if !started {
+            if (kind, value) != (b'v', "0") {
+                return Err(malformed("the body must start with v=0"));
+            }
+            started = true;
+            continue;
+        }
+        match kind {
+            b'c' => {
+                let address = connection_address(value).ok_or_else(|| malformed("expected IN IP4|IP6 <address>"))?;
+                match section {
+                    Section::Session => session_address = Some(address),
+                    Section::Audio => audio_address = Some(address),
+                    Section::Other => {}
+                }
+            }
+            b'm' => {
+                section = Section::Other;
+                let mut fields = value.split_ascii_whitespace();
+                let (Some(media), Some(port), Some(protocol)) = (fields.next(), fields.next(), fields.next()) else {
+                    return Err(malformed("expected <media> <port> <proto> <format>..."));
+                };
+                let port = port.split('/').next().and_then(|port| port.parse::<u16>().ok()).ok_or_else(|| malformed("invalid port"))?;
+                if media != "audio" || !protocol.contains("RTP/AVP") || port == 0 || audio.is_some() {
+                    continue;
+                }
+                let payload_types = fields.map(|pt| pt.parse::<u8>().ok().filter(|pt| *pt < 128)).collect::<Option<Vec<_>>>()
+                    .filter(|pts| !pts.is_empty())
+                    .ok_or_else(|| malformed("expected payload types 0-127"))?;
+                audio = Some(RemoteAudio {
+                    address: IpAddr::from([0, 0, 0, 0]), port, protocol: protocol.to_string(), payload_types, rtpmaps: Vec::new(),
+                    ptime_ms: None, direction: Direction::SendRecv, rtcp_mux: false,
+                });
+                section = Section::Audio;
+            }
+            b'a' => {
+                let (name, attribute) = value.split_once(':').unwrap_or((value, ""));
+                if let Some(direction) = Direction::parse(name) {
+                    match section {
+                        Section::Session => session_direction = Some(direction),
+                        Section::Audio => audio_direction = Some(direction),
+                        Section::Other => {}
+                    }
+                    continue;
+                }
+                let Some(audio) = audio.as_mut().filter(|_| section == Section::Audio) else { continue };
+                match name {
+                    "rtpmap" => audio.rtpmaps.push(rtpmap(attribute).ok_or_else(|| malformed("expected rtpmap:<payload type> <encoding>/<clock rate>"))?),
+                    "ptime" => audio.ptime_ms = Some(attribute.parse().ok().filter(|ms| *ms > 0).ok_or_else(|| malformed("invalid ptime"))?),
+                    "rtcp-mux" => audio.rtcp_mux = true,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !started {
+        return Err(SdpError::Malformed { line: 1, text: String::new(), reason: "the body must start with v=0" });
+    }
+    let mut audio = audio.ok_or(SdpError::NoAudio)?;
+    audio.address = audio_address.or(session_address).ok_or(SdpError::NoConnection)?;
+    audio.direction = audio_direction.or(session_direction).unwrap_or(Direction::SendRecv);
+    Ok(audio)
+}
+
+/// `IN IP4 <adres>[/ttl]`.
+fn connection_address(value: &str) -> Option<IpAddr> {
+    let mut fields = value.split_ascii_whitespace();
+    let (Some("IN"), Some(family), Some(address), None) = (fields.next(), fields.next(), fields.next(), fields.next()) else { return None };
+    let address: IpAddr = address.split('/').next()?.parse().ok()?;
+    match family {
+        "IP4" if address.is_ipv4() => Some(address),
+        "IP6" if address.is_ipv6() => Some(address),
+        _ => None,
+    }
+}
+
+/// `<yük tipi> <kodlama>/<saat hızı>[/<kanal>]`.
+fn rtpmap(value: &str) -> Option<RtpMap> {
+    let (payload_type, format) = value.split_once(' ')?;
+    let mut parts = format.trim().split('/');
+    let (encoding, clock_rate) = (parts.next().filter(|e| !e.is_empty())?, parts.next()?.parse().ok()?);
+    Some(RtpMap { payload_type: payload_type.parse().ok().filter(|pt| *pt < 128)?, encoding: encoding.to_string(), clock_rate })
+}
+
+/// Teklife cevap olarak oturumda anlaşılanlar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Answer {
+    /// Teklifte de olan codec; oturumunkinden farklıysa oturum buna geçer.
+    pub codec: &'static dyn Codec,
+    /// Teklifin RTP adresi; belirtilmemişse yok.
+    pub remote: Option<SocketAddr>,
+    /// Teklifin telephone-event yük tipi; teklifte yoksa tuşlar aktarılmaz.
+    pub dtmf_payload_type: Option<u8>,
+    /// Oturum CN gönderiyorsa teklif de kabul ediyor mu.
+    pub comfort_noise: bool,
+    pub direction: Direction,
+}
+
+/// Teklifi oturumun medya ayarlarıyla karşılaştırır. `codecs` oturumun geçebileceği codec'lerdir,
+/// tercih sırasıyla ve ilki oturumun codec'i; teklifte aynı yük tipiyle olan ilki seçilir, hiçbiri
+/// yoksa cevap verilemez. RED teklifte aynı yük tipiyle yoksa da cevap verilemez; telephone-event'in
+/// yük tipi tekliften alınır.
+pub fn answer(offer: &RemoteAudio, codecs: &[&'static dyn Codec], red: Option<RedConfig>, tcp: bool, local: Direction) -> Result<Answer, SdpError> {
+    if offer.tcp() != tcp {
+        let transport = if tcp { "TCP" } else { "UDP" };
+        return Err(SdpError::TransportMismatch { offered: offer.protocol.clone(), transport });
+    }
+    let codec = codecs.iter().copied()
+        .find(|codec| offer.payload_type(codec.name(), codec.clock_rate()) == Some(codec.payload_type()))
+        .ok_or_else(|| SdpError::NoCommonCodec { codec: codecs.first().map_or("", |codec| codec.name()), offered: offer.offered() })?;
+    if let Some(red) = red.filter(|red| offer.encoding(red.payload_type).is_none_or(|(name, _)| !name.eq_ignore_ascii_case("red"))) {
+        return Err(SdpError::RedNotOffered { payload_type: red.payload_type });
+    }
+    let dtmf_payload_type = offer.payload_type("telephone-event", codec.clock_rate())
+        .filter(|pt| red.is_none_or(|red| red.payload_type != *pt));
+    Ok(Answer {
+        codec,
+        remote: offer.endpoint(),
+        dtmf_payload_type,
+        comfort_noise: offer.payload_type("CN", codec.clock_rate()) == Some(COMFORT_NOISE_PT),
+        direction: local.answer(offer.direction),
+    })
+}
+
+/// Yerel gövdenin içeriği; GenerateSdp oturumdan doldurur.
+#[derive(Debug, Clone, Copy)]
+pub struct LocalAudio<'a> {
+    /// o= satırının oturum kimliği ve sürümü.
+    pub session_id: u64,
+    pub version: u64,
+    pub address: IpAddr,
+    pub port: u16,
+    /// Oturumun ayrı RTCP portu; yoksa RTCP RTP portundan geçer.
+    pub rtcp_port: Option<u16>,
+    pub tcp: bool,
+    pub codec: &'a dyn Codec,
+    /// Oturumun codec'inden sonra, tercih sırasıyla listelenecek diğer codec'ler.
+    pub alternatives: &'a [&'a dyn Codec],
+    pub red: Option<RedConfig>,
+    pub dtmf_payload_type: Option<u8>,
+    pub comfort_noise: bool,
+    pub audio_level_id: Option<u8>,
+    pub ptime_ms: u64,
+    pub ssrc: u32,
+    pub cname: &'a str,
+    pub direction: Direction,
+}
+
+/// Satırları CRLF ile biten SDP gövdesi.
+pub fn generate(local: &LocalAudio) -> String {
+    let family = if local.address.is_ipv4() { "IP4" } else { "IP6" };
+    let (codec, rate) = (local.codec, local.codec.clock_rate());
+    let mut payload_types = vec![codec.payload_type()];
+    payload_types.extend(local.alternatives.iter().map(|alternative| alternative.payload_type()));
+    payload_types.extend(local.red.map(|red| red.payload_type));
+    payload_types.extend(local.dtmf_payload_type);
+    if local.comfort_noise {
+        payload_types.push(COMFORT_NOISE_PT);
+    }
+    let protocol = if local.tcp { "TCP/RTP/AVP" } else { "RTP/AVP" };
+
+    let mut lines = vec![
+        "v=0".to_string(),
+        format!("o=- {} {} IN {} {}", local.session_id, local.version, family, local.address),
+        "s=-".to_string(),
+        format!("c=IN {} {}", family, local.address),
+        "t=0 0".to_string(),
+        format!("m=audio {} {} {}", local.port, protocol, payload_types.iter().map(u8::to_string).collect::<Vec<_>>().join(" ")),
+    ];
+    for codec in std::iter::once(codec).chain(local.alternatives.iter().copied()) {
+        lines.push(format!("a=rtpmap:{} {}/{}", codec.payload_type(), codec.name().to_ascii_uppercase(), codec.clock_rate()));
+    }
+    if let Some(red) = local.red {
+        let blocks = vec![codec.payload_type().to_string(); red.generations + 1];
+        lines.push(format!("a=rtpmap:{} red/{}", red.payload_type, rate));
+        lines.push(format!("a=fmtp:{} {}", red.payload_type, blocks.join("/")));
+    }
+    if let Some(pt) = local.dtmf_payload_type {
+        lines.push(format!("a=rtpmap:{} telephone-event/{}", pt, rate));
+        lines.push(format!("a=fmtp:{} {}", pt, DTMF_EVENTS));
+    }
+    if local.comfort_noise {
+        lines.push(format!("a=rtpmap:{} CN/{}", COMFORT_NOISE_PT, rate));
+    }
+    lines.push(format!("a=ptime:{}", local.ptime_ms));
+    if let Some(id) = local.audio_level_id {
+        lines.push(format!("a=extmap:{} {}", id, AUDIO_LEVEL_URI));
+    }
+    if local.tcp {
+        lines.push("a=setup:passive".to_string());
+        lines.push("a=connection:new".to_string());
+    } else {
+        lines.push(format!("a=rtcp:{}", local.rtcp_port.unwrap_or(local.port)));
+    }
+    if local.tcp || local.rtcp_port.is_none() {
+        lines.push("a=rtcp-mux".to_string());
+    }
+    lines.push(format!("a=ssrc:{} cname:{}", local.ssrc, local.cname));
+    lines.push(format!("a={}", local.direction.as_str()));
+
+    lines.iter().fold(String::new(), |mut body, line| {
+        let _ = write!(body, "{}\r\n", line);
+        body
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{Pcma, Pcmu};
+
+    const OFFER: &str = "v=0\r\no=alice 2890844526 2890844526 IN IP4 192.0.2.10\r\ns=-\r\nc=IN IP4 192.0.2.10\r\nt=0 0\r\n\
+        m=audio 49170 RTP/AVP 8 0 101 13\r\na=rtpmap:8 PCMA/8000\r\na=rtpmap:101 telephone-event/8000\r\na=fmtp:101 0-15\r\n\
+        a=ptime:20\r\na=sendonly\r\nm=video 51372 RTP/AVP 96\r\nc=IN IP4 192.0.2.99\r\na=rtpmap:96 H264/90000\r\n";
+
+    fn local(codec: &dyn Codec) -> LocalAudio<'_> {
+        LocalAudio {
+            session_id: 42, version: 1, address: "203.0.113.5".parse().unwrap(), port: 10000, rtcp_port: None, tcp: false, codec, alternatives: &[], red: None,
+            dtmf_payload_type: None, comfort_noise: false, audio_level_id: None, ptime_ms: 20, ssrc: 0x1234, cname: "media@node", direction: Direction::SendRecv,
+        }
+    }
+
+    #[test]
+    fn offer_lists_the_session_codec_and_its_extras() {
+        assert_eq!(generate(&local(&Pcmu)), "v=0\r\no=- 42 1 IN IP4 203.0.113.5\r\ns=-\r\nc=IN IP4 203.0.113.5\r\nt=0 0\r\n\
+            m=audio 10000 RTP/AVP 0\r\na=rtpmap:0 PCMU/8000\r\na=ptime:20\r\na=rtcp:10000\r\na=rtcp-mux\r\na=ssrc:4660 cname:media@node\r\na=sendrecv\r\n");
+
+        let full = LocalAudio {
+            address: "2001:db8::5".parse().unwrap(), tcp: true, red: Some(RedConfig { payload_type: 100, generations: 2 }),
+            dtmf_payload_type: Some(101), comfort_noise: true, audio_level_id: Some(1), direction: Direction::SendOnly, ..local(&Pcma)
+        };
+        let body = generate(&full);
+        for line in [
+            "c=IN IP6 2001:db8::5", "m=audio 10000 TCP/RTP/AVP 8 100 101 13", "a=rtpmap:100 red/8000", "a=fmtp:100 8/8/8",
+            "a=rtpmap:101 telephone-event/8000", "a=fmtp:101 0-16", "a=rtpmap:13 CN/8000", "a=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level",
+            "a=setup:passive", "a=rtcp-mux", "a=sendonly",
+        ] {
+            assert!(body.contains(&format!("{}\r\n", line)), "{} missing from\n{}", line, body);
+        }
+        assert!(!body.contains("a=rtcp:"));
+
+        // Diğer codec'ler oturumunkinden sonra, verilen sırayla.
+        let alternatives = generate(&LocalAudio { alternatives: &[&Pcma], ..local(&Pcmu) });
+        for line in ["m=audio 10000 RTP/AVP 0 8", "a=rtpmap:0 PCMU/8000\r\na=rtpmap:8 PCMA/8000"] {
+            assert!(alternatives.contains(&format!("{}\r\n", line)), "{} missing from\n{}", line, alternatives);
+        }
+
+        // Ayrı RTCP portu olan oturum rtcp-mux önermez.
+        let paired = generate(&LocalAudio { rtcp_port: Some(10001), ..local(&Pcmu) });
+        assert!(paired.contains("a=rtcp:10001\r\n") && !paired.contains("a=rtcp-mux"), "{}", paired);
+
+        // Üretilen gövde ayrıştırıcıdan geçer.
+        let parsed = parse(&body).unwrap();
+        assert_eq!((parsed.port, parsed.payload_types, parsed.direction, parsed.rtcp_mux), (10000, vec![8, 100, 101, 13], Direction::SendOnly, true));
+    }
+
+    #[test]
+    fn answer_picks_up_the_remote_endpoint_and_payload_types() {
+        let offer = parse(OFFER).unwrap();
+        assert_eq!(offer.endpoint(), Some("192.0.2.10:49170".parse().unwrap()));
+        assert_eq!((offer.ptime_ms, offer.direction, offer.payload_type("pcmu", 8000)), (Some(20), Direction::SendOnly, Some(0)));
+
+        let answer = answer(&offer, &[&Pcma], None, false, Direction::SendRecv).unwrap();
+        assert_eq!(answer, Answer {
+            codec: &Pcma, remote: Some("192.0.2.10:49170".parse().unwrap()), dtmf_payload_type: Some(101), comfort_noise: true, direction: Direction::RecvOnly,
+        });
+
+        assert!(matches!(super::answer(&offer, &[&Pcmu], Some(RedConfig { payload_type: 100, generations: 1 }), false, Direction::SendRecv),
+            Err(SdpError::RedNotOffered { payload_type: 100 })));
+        assert!(matches!(super::answer(&offer, &[&Pcmu], None, true, Direction::SendRecv), Err(SdpError::TransportMismatch { .. })));
+        let only_pcma = parse(&OFFER.replace("8 0 101 13", "8 101")).unwrap();
+        let Err(SdpError::NoCommonCodec { offered, .. }) = super::answer(&only_pcma, &[&Pcmu], None, false, Direction::SendRecv) else { panic!() };
+        assert_eq!(offered, "PCMA/8000, telephone-event/8000");
+        // Oturum geçebiliyorsa teklifteki diğer açık codec seçilir; ikisi de varsa oturumunki kalır.
+        assert!(super::answer(&only_pcma, &[&Pcmu, &Pcma], None, false, Direction::SendRecv).is_ok_and(|answer| answer.codec == &Pcma as &dyn Codec));
+        assert!(super::answer(&offer, &[&Pcmu, &Pcma], None, false, Direction::SendRecv).is_ok_and(|answer| answer.codec == &Pcmu as &dyn Codec));
+
+        // Bekletilen (0.0.0.0) akışın adresi hedef olmaz.
+        let held = parse(&OFFER.replace("c=IN IP4 192.0.2.10", "c=IN IP4 0.0.0.0")).unwrap();
+        assert_eq!(held.endpoint(), None);
+    }
+
+    #[test]
+    fn malformed_lines_are_reported_with_their_number() {
+        let error = |body: &str| match parse(body) {
+            Err(SdpError::Malformed { line, text, .. }) => (line, text),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(error("o=- 1 1 IN IP4 192.0.2.1\r\n"), (1, "o=- 1 1 IN IP4 192.0.2.1".to_string()));
+        assert_eq!(error(""), (1, String::new()));
+        assert_eq!(error(&OFFER.replace("c=IN IP4 192.0.2.10", "c=IN IP4 not-an-ip")), (4, "c=IN IP4 not-an-ip".to_string()));
+        assert_eq!(error(&OFFER.replace("a=ptime:20", "a=ptime:x")), (10, "a=ptime:x".to_string()));
+        assert_eq!(error(&OFFER.replace("49170", "port")).0, 6);
+        assert_eq!(error(&OFFER.replace("a=rtpmap:8 PCMA/8000", "a=rtpmap:8")).0, 7);
+        assert_eq!(error(&OFFER.replace("s=-", "session")), (3, "session".to_string()));
+
+        assert!(matches!(parse("v=0\r\ns=-\r\nm=audio 0 RTP/AVP 0\r\n"), Err(SdpError::NoAudio)));
+        assert!(matches!(parse("v=0\r\ns=-\r\nm=audio 4000 RTP/AVP 0\r\n"), Err(SdpError::NoConnection)));
+    }
+}
@@ -0,0 +1,2454 @@
+// RTP oturumu: soket, uzak adres, istatistikler ve oturumun yaşam döngüsünü yöneten dinleyici görevi.
+use std::collections::HashMap;
+use std::future::pending;
+use std::net::{IpAddr, SocketAddr};
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, SystemTime};
+
+use rand::prelude::*;
+#[cfg(test)]
+use tokio::net::UdpSocket;
+use tokio::sync::{mpsc, oneshot, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, interval_at, sleep, sleep_until, timeout, Instant, Interval, MissedTickBehavior};
+use tracing::{debug, error, info, info_span, warn, Instrument, Span};
+
+use crate::admission;
+use crate::amd::{AmdDetector, AmdResult};
+use crate::announcement::PromptLibrary;
+#[cfg(feature = "asr")]
+use crate::asr::{proto::StreamConfig, AsrStream};
+use crate::audio_dump::AudioDump;
+use crate::audio_level::AudioLevel;
+use crate::audit::{self, FaxTone, MuteReason, OneWayDirection, PlaybackFailure, PlaybackStopReason, SendFailure, StreamChangeTrigger, TapEndReason, TeardownReason, UnbridgeReason};
+use crate::bridge::{self, Relay, TelephoneEvent};
+use crate::capture::Capture;
+use crate::cdr::{self, CdrRecord};
+use crate::codec::Codec;
+use crate::config::{AmdConfig, AudioDumpConfig, CaptureConfig, FaxDetectionConfig, FloodAction, QualityConfig, RateLimitConfig, RecordingConfig, RecordingFormat, SilenceSuppressionConfig, TimersConfig};
+#[cfg(feature = "asr")]
+use crate::config::AsrConfig;
+use crate::dtmf_hook::{self, DigitReport};
+use crate::encryption::RecordingKey;
+use crate::error::{BuildError, RecordingError, SessionError};
+use crate::fax::{FaxDetection, FaxDetector};
+use crate::hook::{self, SessionReport};
+use crate::impairment::Impairment;
+#[cfg(feature = "impairment")]
+use crate::impairment::{Impairer, Outgoing};
+use crate::labels::{self, Labels};
+use crate::metrics::{self, LabelMetrics, TenantMetrics};
+use crate::object_store::{self, RecordingUpload};
+use crate::one_way;
+use crate::playback::{self, PlayMode, Playback, Player};
+use crate::port_journal;
+use crate::ratelimit::{FloodGuard, Inbound};
+use crate::recording::{NameVars, Recording, RecordingSummary};
+use crate::red::{self as rfc2198, RedBlock, RedConfig};
+use crate::rtcp;
+use crate::rtp::{RtpPacket, RtpPacketRef, RtpStream, StreamSeed, COMFORT_NOISE_PT, MAX_PACKET_LEN};
+use crate::scheduler::SendScheduler;
+use crate::sdp;
+use crate::state::SessionState;
+use crate::stats::{InboundStats, RemoteReport, SessionStats};
+use crate::tap::{self, Direction, Tap};
+use crate::transport::{self, Transport};
+use crate::vad::{Frame, Suppressor};
+
+#[derive(Debug)]
+pub struct RtpSession {
+    pub port: u16,
+    pub session_id: String,
+    pub call_id: String,
+    // Tahsis isteğinin kimliği (bkz. request_id.rs); oturum span'i de taşır.
+    pub request_id: String,
+    // Medya gönderilmeden önce SDP cevabıyla ya da teklifteki başka bir codec'le gelen ilk sesle
+    // değişebilir (bkz. `renegotiate_codec`); okumak için `codec()`.
+    codec: Mutex<&'static dyn Codec>,
+    // Son SDP teklifinde oturumun codec'inden sonra listelenenler; gelen ses bunlardan biriyle
+    // gelirse oturum ona geçer.
+    offered_codecs: Mutex<Vec<&'static dyn Codec>>,
+    // Sinyalleşmede RED anlaşıldıysa; giden ses RED ile sarılır, gelen RED yedekleri kurtarılır.
+    pub red: Option<RedConfig>,
+    // Sinyalleşmede anlaşılan RFC 4733 telephone-event yük tipi; 0 ise anlaşılmadı ve köprüde tuş
+    // aktarılmaz. GenerateSdp cevabı tekliftekiyle değiştirir.
+    dtmf_payload_type: AtomicU8,
+    // Sinyalleşmede anlaşılan RFC 6464 ses seviyesi uzantısının kimliği; yoksa uzantı okunmaz ve
+    // giden paketlere eklenmez.
+    pub audio_level_id: Option<u8>,
+    // CN anlaşıldıysa ve node'da açıksa giden akışın sessizlik bastırma durumu.
+    pub(crate) suppressor: Option<Mutex<Suppressor>>,
+    // Bir okuma çağrısında alınacak en fazla paket; toplu okuma yoksa 1.
+    pub(crate) recv_batch: usize,
+    // Gelen paket sınırı; yoksa her paket işlenir.
+    pub(crate) inbound_limit: Option<RateLimitConfig>,
+    // Oturum boyunca SR ya da RR gönderme aralığı; yoksa yalnızca kapanışta gönderilir.
+    pub(crate) report_interval: Option<Duration>,
+    pub(crate) transport: Arc<Transport>,
+    pub local_addr: SocketAddr,
+    pub(crate) remote_addr: Mutex<Option<SocketAddr>>,
+    // RTP portunun bir üstündeki RTCP soketi ve uzak ucun RTCP'sinin son geldiği adres; soket
+    // yoksa RTCP RTP portundan geçer (rtcp-mux).
+    pub(crate) rtcp: Option<UdpSocket>,
+    rtcp_remote: Mutex<Option<SocketAddr>>,
+    // Giden akış; oturumdaki bütün göndericiler paylaşır.
+    pub(crate) stream: RtpStream,
+    // Keepalive kararı için son giden paketin zamanı.
+    pub(crate) last_sent: Mutex<Instant>,
+    // Oturuma ait bütün görevler (dinleyici, anons, keepalive) bu span içinde çalışır.
+    pub(crate) span: Span,
+    pub(crate) allocated_at: Instant,
+    // Tahsisin duvar saati; yalnızca CDR için.
+    allocated_wall: SystemTime,
+    pub(crate) stats: SessionStats,
+    // Yakalama bir kez başlatılır ve oturum bitene kadar sürer; kapalıyken maliyeti tek bir atomik okumadır.
+    pub(crate) capture: OnceLock<Capture>,
+    // Süren kayıt ve oturum boyunca biten kayıtların dosyaları (oturum özeti için).
+    recording: Mutex<Option<Recording>>,
+    recorded: Mutex<Vec<String>>,
+    // Kayıtlardan biri şifrelendiyse anahtarın kimliği.
+    recording_key_id: Mutex<Option<String>>,
+    // Hata ayıklama dökümü; oturum ortasında açılabilir, oturum bitince kapanır.
+    audio_dump: Mutex<Option<AudioDump>>,
+    // Nesne deposuna süren kayıt yüklemeleri; kanca bildirimi bunları bekler.
+    uploads: Mutex<Vec<JoinHandle<Vec<RecordingUpload>>>>,
+    // Dışarıdan sonlandırma isteği; oturum her zaman dinleyici görevinin sonunda kapanır.
+    pub(crate) stop: Notify,
+    pub(crate) stop_reason: Mutex<Option<TeardownReason>>,
+    // Oturumun tahsisten itibaren en uzun ömrü; dolunca kapanır.
+    pub(crate) max_duration: Option<Duration>,
+    // Tahsiste karşılama anonsu istenmediyse false; ilk pakette ve yeni akışta çalınmaz.
+    pub(crate) auto_welcome: bool,
+    // Tahsiste istenen anons dili; oturumun anonsları bu dildeki dosyadan çalınır.
+    pub language: Option<String>,
+    // Port, havuz tükendiği için RTP aralığının dışından alındı.
+    pub overflow: bool,
+    // Hedefi tahsiste verilen, yalnızca gönderen oturum: ilk paket beklenmez, gelen paketler
+    // sayılır ama `symmetric_rtp` yoksa hedefi değiştirmez.
+    pub send_only: bool,
+    pub symmetric_rtp: bool,
+    // Portun alındığı `[[tenants]]` aralığının sahibi; kiracısızsa yok.
+    pub tenant: Option<Arc<TenantMetrics>>,
+    // Portun bağlandığı `[[interfaces]]` arayüzü ve SDP'de duyurulan adresi; rtp.host'a bağlıysa yok.
+    pub interface: Option<String>,
+    pub(crate) advertise_address: Option<IpAddr>,
+    // Tahsiste verildiyse tuşların bildirildiği adres; yoksa `dtmf_hook.url`.
+    dtmf_hook_url: Option<String>,
+    // Tahsiste verilen etiketler ve `metrics.session_labels`'ta izin verilenlerin serileri.
+    pub labels: Labels,
+    label_metrics: Vec<Arc<LabelMetrics>>,
+    // Tahsiste istendiyse gelen sesin faks tonu algılayıcısı ve algılanınca çalanın durdurulup
+    // durdurulmayacağı.
+    fax: Option<Mutex<FaxDetector>>,
+    fax_stops_playback: bool,
+    // İlk algılanan faks tonu; oturum özetine yazılır.
+    pub(crate) fax_tone: Mutex<Option<FaxTone>>,
+    // Tahsiste istendiyse süren telesekreter analizi; sonuç verilince düşer ve ses çözülmez olur.
+    amd: Mutex<Option<AmdDetector>>,
+    pub(crate) amd_result: Mutex<Option<AmdResult>>,
+    // GenerateSdp'nin son ürettiği gövdenin o= sürümü.
+    sdp_version: AtomicU64,
+    // Tahsiste ya da SetImpairment ile açılan giden yol bozulması; kapalıyken yok.
+    #[cfg(feature = "impairment")]
+    impairer: Mutex<Option<Impairer>>,
+    // Tahsiste istendiyse gelen sesin akıtıldığı tanıma servisi akışı (bkz. asr.rs).
+    #[cfg(feature = "asr")]
+    asr: Option<AsrStream>,
+    // SetMute ile susturulan yönler: susturulan gelen ses tüketicilere sessizlik olarak verilir,
+    // susturulan giden ses sessizlik olarak kodlanır. Paketler yine sayılır.
+    inbound_muted: AtomicBool,
+    outbound_muted: AtomicBool,
+    // SetDirection ile ayarlanan medya yönü (`sdp::Direction::ALL` sırası) ve gelen sesin en son
+    // beklenmeye başladığı an; zaman aşımları bekletme sırasında işlemez, dönüşte buradan sayılır.
+    direction: AtomicU8,
+    receiving_since: Mutex<Instant>,
+    // İlk paketin karşılama anonsu başlatıldı mı.
+    welcomed: AtomicBool,
+    // Yansıyan paketlerimiz için uyarı yazıldı mı; oturum başına bir kez yazılır.
+    reflection_warned: AtomicBool,
+    // Köprülüyse bu bacaktan gelenleri karşı bacağa aktaran yön.
+    pub(crate) bridge: Mutex<Option<Relay>>,
+    // Oturumu dinleyen dinleme bacakları ve bu oturum bir dinleme bacağıysa dinlediği oturum (bkz. tap.rs).
+    pub(crate) taps: Mutex<Vec<Tap>>,
+    pub(crate) tapping: Mutex<Option<Weak<RtpSession>>>,
+    // Oturumun göndericisi: dinleyici görevi oynatmaları kurar, gönderim zamanlayıcısı çalar.
+    pub(crate) player: tokio::sync::Mutex<Player>,
+    // Zamanlayıcıda biten oynatma dinleyici görevini uyandırır.
+    pub(crate) playback_idle: Notify,
+    // Dinleyici görevindeki göndericiye iletilen istekler; alıcı ucu dinleyici başlarken alınır.
+    playback: mpsc::UnboundedSender<PlaybackRequest>,
+    playback_requests: Mutex<Option<mpsc::UnboundedReceiver<PlaybackRequest>>>,
+}
+
+// Göndericinin sahibi dinleyici görevi olduğundan oynatmalar ona kanalla iletilir; yanıt
+// bekleyen isteklerin sonucu `reply` ile döner.
+enum PlaybackRequest {
+    Play { playback: Playback, mode: PlayMode, reply: Option<oneshot::Sender<Result<u64, SessionError>>> },
+    Stop { flush: bool, reply: oneshot::Sender<(Option<u64>, usize)> },
+}
+
+impl RtpSession {
+    pub fn new(port: u16, codec: &'static dyn Codec, transport: impl Into<Transport>, call_id: &str) -> Self {
+        let transport = transport.into();
+        let local_addr = transport.local_addr().unwrap_or_else(|_| SocketAddr::from(([0, 0, 0, 0], port)));
+        let session_id = format!("{:016x}", rand::thread_rng().gen::<u64>());
+        // Oturum tahsis isteğinden uzun yaşar; isteğin çocuğu değil, onu takip eden kök span'dir.
+        let span = info_span!(parent: None, "session", rtp_port = port, session_id = %session_id, call_id = %call_id, request_id = tracing::field::Empty, tenant = tracing::field::Empty, remote = tracing::field::Empty);
+        span.follows_from(Span::current());
+        let (playback, playback_requests) = mpsc::unbounded_channel();
+        Self {
+            port, session_id, call_id: call_id.to_string(), request_id: String::new(), codec: Mutex::new(codec), offered_codecs: Mutex::new(Vec::new()), red: None, dtmf_payload_type: AtomicU8::new(0), audio_level_id: None, suppressor: None, recv_batch: 1, inbound_limit: None, report_interval: None, transport: Arc::new(transport), local_addr, remote_addr: Mutex::new(None),
+            rtcp: None, rtcp_remote: Mutex::new(None),
+            stream: RtpStream::new(codec.clock_rate()),
+            last_sent: Mutex::new(Instant::now()), span, allocated_at: Instant::now(), allocated_wall: SystemTime::now(), stats: SessionStats::default(),
+            capture: OnceLock::new(),
+            recording: Mutex::new(None),
+            recorded: Mutex::new(Vec::new()),
+            recording_key_id: Mutex::new(None),
+            audio_dump: Mutex::new(None),
+            uploads: Mutex::new(Vec::new()),
+            stop: Notify::new(),
+            stop_reason: Mutex::new(None),
+            max_duration: None,
+            auto_welcome: true,
+            language: None,
+            overflow: false,
+            send_only: false,
+            symmetric_rtp: false,
+            tenant: None,
+            interface: None,
+            advertise_address: None,
+            dtmf_hook_url: None,
+            labels: Labels::new(),
+            label_metrics: Vec::new(),
+            fax: None,
+            fax_stops_playback: false,
+            fax_tone: Mutex::new(None),
+            amd: Mutex::new(None),
+            amd_result: Mutex::new(None),
+            sdp_version: AtomicU64::new(0),
+            #[cfg(feature = "impairment")]
+            impairer: Mutex::new(None),
+            #[cfg(feature = "asr")]
+            asr: None,
+            inbound_muted: AtomicBool::new(false),
+            outbound_muted: AtomicBool::new(false),
+            direction: AtomicU8::new(sdp::Direction::SendRecv as u8),
+            receiving_since: Mutex::new(Instant::now()),
+            welcomed: AtomicBool::new(false),
+            reflection_warned: AtomicBool::new(false),
+            bridge: Mutex::new(None),
+            taps: Mutex::new(Vec::new()),
+            tapping: Mutex::new(None),
+            player: tokio::sync::Mutex::new(Player::new(codec, TimersConfig::default().ptime())),
+            playback_idle: Notify::new(),
+            playback,
+            playback_requests: Mutex::new(Some(playback_requests)),
+        }
+    }
+
+    /// Tahsis isteğinin kimliğini oturuma ve span'ine yazar; boşsa span alanı boş kalır.
+    pub fn with_request_id(self, request_id: &str) -> Self {
+        if !request_id.is_empty() {
+            self.span.record("request_id", request_id);
+        }
+        RtpSession { request_id: request_id.to_string(), ..self }
+    }
+
+    /// Oturumu RFC 2198 RED ile kurar; oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_red(self, red: RedConfig) -> Self {
+        RtpSession { red: Some(red), ..self }
+    }
+
+    /// Oturumun telephone-event yük tipini kurar; oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_dtmf(self, payload_type: u8) -> Self {
+        RtpSession { dtmf_payload_type: AtomicU8::new(payload_type), ..self }
+    }
+
+    pub fn dtmf_payload_type(&self) -> Option<u8> {
+        Some(self.dtmf_payload_type.load(Ordering::Relaxed)).filter(|pt| *pt != 0)
+    }
+
+    /// Oturumun RFC 6464 ses seviyesi uzantı kimliğini kurar; oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_audio_level(self, id: u8) -> Self {
+        RtpSession { audio_level_id: Some(id), ..self }
+    }
+
+    /// Giden akışın SSRC'sini, ilk sıra numarasını ve zaman damgasını sabitler; oturum
+    /// paylaşılmadan önce çağrılmalıdır.
+    pub fn with_stream_seed(self, seed: StreamSeed) -> Self {
+        RtpSession { stream: RtpStream::seeded(self.codec().clock_rate(), seed), ..self }
+    }
+
+    /// Oturumu tahsisten `max_duration` sonra kapatır; oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_max_duration(self, max_duration: Duration) -> Self {
+        RtpSession { max_duration: Some(max_duration), ..self }
+    }
+
+    /// Karşılama anonsunu bu oturum için kapatır (köprü bacakları, yalnızca kayıt); oturum
+    /// paylaşılmadan önce çağrılmalıdır.
+    pub fn without_welcome(self) -> Self {
+        RtpSession { auto_welcome: false, ..self }
+    }
+
+    /// Oturumun anonslarını `language` dilinde çalar; o dilde dosyası olmayan anons varsayılan
+    /// dilde çalınır. Oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_language(self, language: &str) -> Self {
+        RtpSession { language: Some(language.to_string()), ..self }
+    }
+
+    /// Portun bağlandığı arayüzü ve SDP'de duyurulacak adresini kaydeder; oturum paylaşılmadan
+    /// önce çağrılmalıdır.
+    pub fn with_interface(self, name: &str, advertise_address: Option<IpAddr>) -> Self {
+        RtpSession { interface: Some(name.to_string()), advertise_address, ..self }
+    }
+
+    /// Gelen tuşları node'un `dtmf_hook.url`'si yerine bu adrese bildirir (bkz. dtmf_hook.rs);
+    /// oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_dtmf_hook(self, url: &str) -> Self {
+        RtpSession { dtmf_hook_url: Some(url.to_string()), ..self }
+    }
+
+    /// Gelen paketleri okuma çağrısı başına en fazla `recv_batch` paketle okur (bkz.
+    /// `Transport::batch`); oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_recv_batch(self, recv_batch: usize) -> Self {
+        RtpSession { recv_batch, ..self }
+    }
+
+    /// Gelen paketi beklemeden `remote`'a gönderir; karşılama oturum başlar başlamaz çalar ve ilk
+    /// paket zaman aşımı uygulanmaz. `symmetric_rtp` ise gelen akış hedefi yine değiştirebilir.
+    /// Oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_send_only(self, remote: SocketAddr, symmetric_rtp: bool) -> Self {
+        self.span.record("remote", tracing::field::display(remote));
+        RtpSession { remote_addr: Mutex::new(Some(remote)), send_only: true, symmetric_rtp, ..self }
+    }
+
+    /// Oturumun şu anki codec'i; medya gönderilmeden önce yeniden anlaşılabilir.
+    pub fn codec(&self) -> &'static dyn Codec {
+        *self.codec.lock().unwrap()
+    }
+
+    /// Codec hâlâ değişebilir mi: oturumdan henüz hiç RTP gönderilmedi.
+    pub(crate) fn codec_negotiable(&self) -> bool {
+        self.stats.packets_sent.load(Ordering::Relaxed) == 0
+    }
+
+    /// SDP teklifinde oturumun codec'inden sonra listelenen codec'leri kaydeder.
+    pub(crate) fn set_offered_codecs(&self, codecs: Vec<&'static dyn Codec>) {
+        *self.offered_codecs.lock().unwrap() = codecs;
+    }
+
+    /// `payload_type` son teklifte listelenen başka bir codec'inse o codec.
+    fn offered_codec(&self, payload_type: u8) -> Option<&'static dyn Codec> {
+        self.offered_codecs.lock().unwrap().iter().copied().find(|codec| codec.payload_type() == payload_type)
+    }
+
+    /// Oturumu `codec`'e geçirir. Yalnızca henüz RTP gönderilmemişken ve aynı saat hızındaki bir
+    /// codec'e geçilebilir: akışın zaman damgaları, kayıt ve algılayıcılar saat hızıyla kuruldu.
+    /// Geçildiyse ya da codec zaten buysa true.
+    pub(crate) async fn renegotiate_codec(&self, codec: &'static dyn Codec) -> bool {
+        // Sonuç ne olursa olsun codec artık anlaşılmıştır; teklif edilenler geçerli değildir.
+        self.offered_codecs.lock().unwrap().clear();
+        let current = self.codec();
+        if current == codec {
+            return true;
+        }
+        if current.clock_rate() != codec.clock_rate() {
+            return false;
+        }
+        // Oynatıcı kilitliyken çerçeve gönderilemez; kontrol ve geçiş arasına paket girmez.
+        let mut player = self.player.lock().await;
+        if !self.codec_negotiable() {
+            return false;
+        }
+        *self.codec.lock().unwrap() = codec;
+        player.set_codec(codec);
+        info!(from = %current, to = %codec, "Oturumun codec'i yeniden anlaşıldı");
+        true
+    }
+
+    /// GenerateSdp'nin o= satırına yazacağı sürüm; üretilen her gövdede bir artar.
+    pub(crate) fn next_sdp_version(&self) -> u64 {
+        self.sdp_version.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Teklife verilen cevabı oturuma uygular: telephone-event yük tipi teklifinki olur, uzak adres
+    /// henüz bilinmiyorsa teklifin adresi hedef olur (gelen akış sonra yine kilitlenir). Hedef
+    /// böylece ayarlandıysa true.
+    pub(crate) fn apply_answer(&self, answer: &sdp::Answer) -> bool {
+        self.dtmf_payload_type.store(answer.dtmf_payload_type.unwrap_or(0), Ordering::Relaxed);
+        let Some(remote) = answer.remote else { return false };
+        let mut target = self.remote_addr.lock().unwrap();
+        if target.is_some() {
+            return false;
+        }
+        *target = Some(remote);
+        self.span.record("remote", tracing::field::display(remote));
+        true
+    }
+
+    /// Giden yola bozulma uygular; bütün değerleri 0 ise kaldırır. Değerler doğrulanmış ve node'da
+    /// izin verilmiş olmalıdır; `impairment` feature'ı olmadan etkisizdir.
+    pub fn set_impairment(&self, impairment: Impairment) {
+        #[cfg(feature = "impairment")]
+        {
+            *self.impairer.lock().unwrap() = (!impairment.is_off()).then(|| Impairer::new(impairment));
+        }
+        info!(
+            target: audit::TARGET, event = audit::IMPAIRMENT_CHANGED,
+            loss_pct = impairment.loss_pct, jitter_ms = impairment.jitter_ms, reorder_pct = impairment.reorder_pct, duplicate_pct = impairment.duplicate_pct,
+        );
+    }
+
+    /// Yönlerin susturmasını ayarlar; durum değiştiyse denetim kaydı yazar ve true döner.
+    pub fn set_mute(&self, inbound: bool, outbound: bool, reason: MuteReason) -> bool {
+        let changed = self.inbound_muted.swap(inbound, Ordering::Relaxed) != inbound
+            | (self.outbound_muted.swap(outbound, Ordering::Relaxed) != outbound);
+        if changed {
+            info!(target: audit::TARGET, parent: &self.span, event = audit::MUTE_CHANGED, inbound, outbound, reason = reason.as_str());
+        }
+        changed
+    }
+
+    pub fn inbound_muted(&self) -> bool {
+        self.inbound_muted.load(Ordering::Relaxed)
+    }
+
+    pub fn outbound_muted(&self) -> bool {
+        self.outbound_muted.load(Ordering::Relaxed)
+    }
+
+    pub fn direction(&self) -> sdp::Direction {
+        sdp::Direction::ALL[self.direction.load(Ordering::Relaxed) as usize]
+    }
+
+    /// Medya yönünü değiştirir ve öncekini döner. Yön değiştiyse denetim kaydı yazılır ve dinleyici
+    /// görevi süreleri yeniden hesaplayıp duran oynatmayı sürdürür.
+    pub fn set_direction(&self, direction: sdp::Direction) -> sdp::Direction {
+        let previous = sdp::Direction::ALL[self.direction.swap(direction as u8, Ordering::Relaxed) as usize];
+        if previous == direction {
+            return previous;
+        }
+        if direction.receives() && !previous.receives() {
+            *self.receiving_since.lock().unwrap() = Instant::now();
+        }
+        self.playback_idle.notify_one();
+        info!(target: audit::TARGET, parent: &self.span, event = audit::DIRECTION_CHANGED, direction = direction.as_str(), previous = previous.as_str());
+        previous
+    }
+
+    /// Paketi oturumun soketinden gönderir; bozulma açıksa paket önce ondan geçer ve düşse de
+    /// gönderilmiş sayılır. Bütün giden yollar (oynatma, köprü, keepalive, RTCP) bunu kullanır.
+    pub(crate) async fn send_to(&self, bytes: &[u8], target: SocketAddr) -> io::Result<usize> {
+        transport::send_retrying(|| self.send_once(bytes, target)).await
+            .inspect_err(|e| self.send_failed(e, target))
+    }
+
+    async fn send_once(&self, bytes: &[u8], target: SocketAddr) -> io::Result<usize> {
+        #[cfg(feature = "impairment")]
+        if let Some(now) = self.impair(bytes, target) {
+            for out in now {
+                self.transport.send_to(&out.bytes, out.target).await?;
+            }
+            return Ok(bytes.len());
+        }
+        self.transport.send_to(bytes, target).await
+    }
+
+    /// `send_to`'nun beklemeyen hali; soket tamponu doluysa paket yeniden denenmeden düşer.
+    pub(crate) fn try_send_to(&self, bytes: &[u8], target: SocketAddr) -> io::Result<usize> {
+        self.try_send_once(bytes, target).inspect_err(|e| self.send_failed(e, target))
+    }
+
+    fn try_send_once(&self, bytes: &[u8], target: SocketAddr) -> io::Result<usize> {
+        #[cfg(feature = "impairment")]
+        if let Some(now) = self.impair(bytes, target) {
+            for out in now {
+                self.transport.try_send_to(&out.bytes, out.target)?;
+            }
+            return Ok(bytes.len());
+        }
+        self.transport.try_send_to(bytes, target)
+    }
+
+    /// Gönderim hatasını sınıfına göre sayar ve ele alır (bkz. `SendFailure`): ulaşılamayan uçta
+    /// oturum kapanır, güvenlik duvarı reddi operatör için hata olarak loglanır. Aynı sınıf art arda
+    /// tekrar loglanmaz; son sınıf oturum özetine yazılır.
+    fn send_failed(&self, error: &io::Error, target: SocketAddr) {
+        let failure = transport::send_failure(error);
+        metrics::get().send_failed(failure);
+        if failure == SendFailure::Unreachable {
+            self.stop(TeardownReason::RemoteUnreachable);
+        }
+        let (now, last_sent) = (Instant::now(), *self.last_sent.lock().unwrap());
+        match &mut *self.stats.send_failures.lock().unwrap() {
+            Some((first, last)) if *first > last_sent => *last = now,
+            failures => *failures = Some((now, now)),
+        }
+        if self.stats.send_error.lock().unwrap().replace(failure) == Some(failure) {
+            return;
+        }
+        let class = failure.as_str();
+        match failure {
+            SendFailure::Unreachable => warn!(parent: &self.span, target = %target, class, error = %error, "Uzak uç ulaşılamıyor, oturum kapatılıyor"),
+            SendFailure::Denied => error!(parent: &self.span, target = %target, class, error = %error, "Giden RTP yerel güvenlik duvarınca reddedildi; iptables/nftables kurallarını denetleyin"),
+            SendFailure::NoBuffers => warn!(parent: &self.span, target = %target, class, error = %error, "Soket gönderim tamponu dolu, paketler düşüyor"),
+            SendFailure::Other => warn!(parent: &self.span, target = %target, class, error = %error, "RTP gönderilemedi"),
+        }
+    }
+
+    /// Bozulma açıksa paketi ondan geçirir: gecikecekleri zamanlar, hemen gidecekleri döner.
+    #[cfg(feature = "impairment")]
+    fn impair(&self, bytes: &[u8], target: SocketAddr) -> Option<Vec<Outgoing>> {
+        let outgoing = self.impairer.lock().unwrap().as_mut()?.process(bytes, target);
+        let (now, delayed): (Vec<_>, Vec<_>) = outgoing.into_iter().partition(|out| out.delay.is_zero());
+        for out in delayed {
+            let transport = self.transport.clone();
+            tokio::spawn(async move {
+                sleep(out.delay).await;
+                if let Err(e) = transport.send_to(&out.bytes, out.target).await {
+                    debug!(target = %out.target, error = %e, "Geciktirilen paket gönderilemedi");
+                }
+            }.instrument(self.span.clone()));
+        }
+        Some(now)
+    }
+
+    /// Gelen akış hedef adresi belirler mi; yalnızca gönderen oturumlarda `symmetric_rtp` ile.
+    fn follows_inbound(&self) -> bool {
+        !self.send_only || self.symmetric_rtp
+    }
+
+    /// Oturumu taşma portunda açılmış olarak işaretler; oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_overflow(self) -> Self {
+        RtpSession { overflow: true, ..self }
+    }
+
+    /// Oturumu kiracıya bağlar; sayaçlar oturum kapanınca güncellenir.
+    pub fn with_tenant(self, tenant: Arc<TenantMetrics>) -> Self {
+        self.span.record("tenant", tenant.name);
+        RtpSession { tenant: Some(tenant), ..self }
+    }
+
+    /// Oturumun etiketlerini kurar; izin verilen etiketlerin serileri burada sayılır, oturum
+    /// kapanınca güncellenir. Oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_labels(self, labels: Labels) -> Self {
+        let label_metrics = metrics::get().session_labels(&labels);
+        for series in &label_metrics {
+            series.sessions.inc();
+            series.active_sessions.inc();
+        }
+        RtpSession { labels, label_metrics, ..self }
+    }
+
+    /// Gelen seste CNG ve CED faks tonlarını arar (bkz. fax.rs); oturum paylaşılmadan önce
+    /// çağrılmalıdır.
+    pub fn with_fax_detection(self, config: &FaxDetectionConfig) -> Self {
+        let detector = FaxDetector::new(config, self.codec().sample_rate());
+        RtpSession { fax: Some(Mutex::new(detector)), fax_stops_playback: config.stop_playback, ..self }
+    }
+
+    /// Gelen sesin insan mı telesekreter mi olduğunu analiz eder (bkz. amd.rs); oturum
+    /// paylaşılmadan önce çağrılmalıdır.
+    pub fn with_amd(self, config: &AmdConfig) -> Self {
+        let detector = AmdDetector::new(config, self.codec().sample_rate());
+        RtpSession { amd: Mutex::new(Some(detector)), ..self }
+    }
+
+    /// Gelen sesi tanıma servisine akıtır (bkz. asr.rs); dil tahsisteki dil, yoksa `asr.language`.
+    /// Oturum paylaşılmadan önce çağrılmalıdır.
+    #[cfg(feature = "asr")]
+    pub fn with_asr(self, config: &AsrConfig) -> Self {
+        let stream = StreamConfig {
+            session_id: self.session_id.clone(),
+            call_id: self.call_id.clone(),
+            sample_rate: self.codec().sample_rate(),
+            language: self.language.clone().unwrap_or_else(|| config.language.clone()),
+            interim_results: config.interim_results,
+        };
+        let asr = AsrStream::start(config, stream, self.span.clone());
+        RtpSession { asr: Some(asr), ..self }
+    }
+
+    fn transcribing(&self) -> bool {
+        #[cfg(feature = "asr")]
+        {
+            self.asr.is_some()
+        }
+        #[cfg(not(feature = "asr"))]
+        {
+            false
+        }
+    }
+
+    /// Giden sessizliği bastırır (bkz. vad.rs); oturum paylaşılmadan önce çağrılmalıdır.
+    pub fn with_silence_suppression(self, config: &SilenceSuppressionConfig) -> Self {
+        RtpSession { suppressor: Some(Mutex::new(Suppressor::new(config))), ..self }
+    }
+
+    /// Oturum boyunca her `interval`'da SR ya da RR gönderir (bkz. `send_report`); oturum
+    /// paylaşılmadan önce çağrılmalıdır.
+    pub fn with_rtcp_reports(self, interval: Duration) -> Self {
+        RtpSession { report_interval: Some(interval), ..self }
+    }
+
+    /// RTCP'yi RTP portu yerine `socket`'in portundan gönderip alır; oturum paylaşılmadan önce
+    /// çağrılmalıdır.
+    pub fn with_rtcp_port(self, socket: UdpSocket) -> Self {
+        RtpSession { rtcp: Some(socket), ..self }
+    }
+
+    /// Ayrı RTCP portu; yoksa RTCP RTP portundan geçer.
+    pub fn rtcp_port(&self) -> Option<u16> {
+        self.rtcp.as_ref().and_then(|socket| socket.local_addr().ok()).map(|addr| addr.port())
+    }
+
+    fn rtcp_local_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.local_addr.ip(), self.rtcp_port().unwrap_or(self.port))
+    }
+
+    /// Gelen paketleri `rate_limit.inbound_*` ile sınırlar (bkz. `InboundGuard`); oturum
+    /// paylaşılmadan önce çağrılmalıdır.
+    pub fn with_inbound_limit(self, config: &RateLimitConfig) -> Self {
+        RtpSession { inbound_limit: Some(*config), ..self }
+    }
+
+    /// Giden `pcm` çerçevesi gönderilmeli mi; bastırma kapalıysa her zaman gönderilir.
+    pub(crate) fn suppress(&self, pcm: &[i16], announcement: bool, now: Instant) -> Frame {
+        let Some(suppressor) = &self.suppressor else { return Frame::Send { marker: false } };
+        let frame = suppressor.lock().unwrap().decide(pcm, announcement, now);
+        if !matches!(frame, Frame::Send { .. }) {
+            metrics::get().frames_suppressed.inc();
+        }
+        frame
+    }
+
+    /// Köprülüyse karşı bacak.
+    pub fn bridged_peer(&self) -> Option<Arc<RtpSession>> {
+        self.bridge.lock().unwrap().as_ref().and_then(Relay::peer)
+    }
+
+    /// Dinleme bacağıysa dinlediği oturum.
+    pub fn tap_source(&self) -> Option<Arc<RtpSession>> {
+        self.tapping.lock().unwrap().as_ref().and_then(Weak::upgrade)
+    }
+
+    /// Kaynağı oturumun göndericisine kurar; çalan varsa onun yerine geçer.
+    pub fn play(&self, playback: Playback) {
+        // Dinleyici bittiyse oturum kapanıyordur; istek sessizce düşer.
+        let _ = self.playback.send(PlaybackRequest::Play { playback, mode: PlayMode::Interrupt, reply: None });
+    }
+
+    /// Oynatmayı `mode` ile göndericiye iletir ve kimliğini döner.
+    pub async fn submit(&self, playback: Playback, mode: PlayMode) -> Result<u64, SessionError> {
+        let (reply, result) = oneshot::channel();
+        self.playback.send(PlaybackRequest::Play { playback, mode, reply: Some(reply) })
+            .map_err(|_| SessionError::NotFound { port: self.port })?;
+        result.await.map_err(|_| SessionError::NotFound { port: self.port })?
+    }
+
+    /// Çalanı durdurur, `flush` ise kuyruğu da boşaltır; durdurulanın kimliğini ve kuyruktan
+    /// atılanların sayısını döner.
+    pub async fn stop_playback(&self, flush: bool) -> Result<(Option<u64>, usize), SessionError> {
+        let (reply, result) = oneshot::channel();
+        self.playback.send(PlaybackRequest::Stop { flush, reply }).map_err(|_| SessionError::NotFound { port: self.port })?;
+        result.await.map_err(|_| SessionError::NotFound { port: self.port })
+    }
+
+    /// Dinleyici görevinden oturumu verilen sebeple kapatmasını ister.
+    pub fn stop(&self, reason: TeardownReason) {
+        self.stop_reason.lock().unwrap().get_or_insert(reason);
+        self.stop.notify_one();
+    }
+
+    /// pcap yakalamasını başlatır ve dosya yolunu döner.
+    pub fn start_capture(&self, config: &CaptureConfig) -> Result<String, SessionError> {
+        let already_capturing = |path: String| SessionError::AlreadyCapturing { port: self.port, path };
+        if let Some(capture) = self.capture.get() {
+            return Err(already_capturing(capture.path().display().to_string()));
+        }
+        let started = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+        let path = std::path::Path::new(&config.directory).join(format!("{}_{}_{}.pcap", self.session_id, self.port, started));
+        let capture = Capture::start(path.clone(), config.max_file_bytes)
+            .map_err(|source| SessionError::Capture { path: path.display().to_string(), source })?;
+        let path = capture.path().display().to_string();
+        self.capture.set(capture).map_err(|_| already_capturing(path.clone()))?;
+        info!(file = %path, "pcap yakalaması başladı");
+        Ok(path)
+    }
+
+    /// Çözülmüş ses dökümünü o andan itibaren başlatır ve gelen/giden dosyaların yollarını döner;
+    /// döküm zaten sürüyorsa aynı yolları döner.
+    pub fn start_audio_dump(&self, config: &AudioDumpConfig) -> Result<[String; 2], SessionError> {
+        let mut current = self.audio_dump.lock().unwrap();
+        if let Some(dump) = current.as_ref() {
+            return Ok(dump.paths());
+        }
+        let dump = AudioDump::start(config, &self.session_id, self.port, self.codec().sample_rate())?;
+        let paths = dump.paths();
+        *current = Some(dump);
+        info!(inbound = %paths[0], outbound = %paths[1], "Ses dökümü başladı");
+        Ok(paths)
+    }
+
+    /// Döküm sürüyorsa kodlanmak üzere olan giden çerçeveyi ekler.
+    pub(crate) fn dump_outbound(&self, pcm: &[i16]) {
+        if let Some(dump) = self.audio_dump.lock().unwrap().as_ref() {
+            dump.outbound(pcm);
+        }
+    }
+
+    /// Gelen sesin kaydını başlatır ve ilk dosyanın yolunu döner. `format` verilmezse
+    /// `recording.format`; `key` verilirse kayıt şifrelenir.
+    pub fn start_recording(&self, config: &RecordingConfig, name: Option<&str>, format: Option<RecordingFormat>, key: Option<Arc<RecordingKey>>) -> Result<String, RecordingError> {
+        let mut current = self.recording.lock().unwrap();
+        if let Some(recording) = current.as_ref() {
+            return Err(RecordingError::AlreadyRecording { port: self.port, path: recording.path() });
+        }
+        let start_time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+        let vars = NameVars { session_id: &self.session_id, call_id: &self.call_id, port: self.port, start_time };
+        let key_id = key.as_ref().map(|key| key.id().to_string());
+        let format = format.unwrap_or(config.format);
+        let recording = Recording::start(config, name, format, &vars, self.codec().sample_rate(), key)?;
+        if key_id.is_some() {
+            *self.recording_key_id.lock().unwrap() = key_id;
+        }
+        let path = recording.path();
+        info!(target: audit::TARGET, event = audit::RECORDING_STARTED, file = %path, format = format.as_str());
+        *current = Some(recording);
+        Ok(path)
+    }
+
+    /// Kaydı bitirir; dosyalar kapanınca bütün parçaları döner. Yükleme açıksa parçalar arka
+    /// planda nesne deposuna gönderilir.
+    pub async fn stop_recording(&self) -> Result<RecordingSummary, RecordingError> {
+        let recording = self.recording.lock().unwrap().take().ok_or(RecordingError::NotRecording { port: self.port })?;
+        let summary = recording.finish().await;
+        self.recorded.lock().unwrap().extend(summary.paths.iter().cloned());
+        let paths = summary.paths.clone();
+        self.upload(async move { paths });
+        Ok(summary)
+    }
+
+    fn upload(&self, files: impl std::future::Future<Output = Vec<String>> + Send + 'static) {
+        if let Some(upload) = object_store::upload_recordings(files, &self.span) {
+            self.uploads.lock().unwrap().push(upload);
+        }
+    }
+
+    /// Kayıt ya da döküm sürüyorsa, faks tonu aranıyorsa, telesekreter analizi sürüyorsa, ses tanıma
+    /// servisine akıtılıyorsa ya da oturum dinleniyorsa gelen paketin sesini çözüp onlara verir;
+    /// algılanan faks tonlarını döner.
+    fn inbound_audio(&self, packet: &RtpPacketRef, pcm: &mut Vec<i16>) -> Vec<FaxDetection> {
+        // Gelen sesi beklemeyen yönde (sendonly, inactive) uzak uçtan gelen ses hiçbir yere girmez.
+        if !self.direction().receives() {
+            return Vec::new();
+        }
+        let recording = self.recording.lock().unwrap();
+        let dump = self.audio_dump.lock().unwrap();
+        let mut amd = self.amd.lock().unwrap();
+        let transcribing = self.transcribing();
+        let tapped = tap::is_tapped(self);
+        if recording.is_none() && dump.is_none() && self.fax.is_none() && amd.is_none() && !transcribing && !tapped {
+            return Vec::new();
+        }
+        let Some(audio) = self.audio_payload(packet) else { return Vec::new() };
+        let muted = self.inbound_muted();
+        // G.711 kayıt oturumun codec'indeyse yük çözülmeden yazılır; başka tüketici yoksa hiç
+        // çözülmez. Susturulmuş ses sessizlik olarak kaydedilmek üzere çözülür.
+        let passthrough = recording.as_ref().filter(|recording| !muted && recording.format().g711_codec() == Some(self.codec().name()));
+        if let Some(recording) = passthrough {
+            recording.record_g711(audio);
+            if dump.is_none() && self.fax.is_none() && amd.is_none() && !transcribing && !tapped {
+                return Vec::new();
+            }
+        }
+        pcm.clear();
+        self.codec().decode(audio, pcm);
+        // Faks tonu ve telesekreter algılama susturulmuş sesi de duyar; diğer tüketicilere sessizlik gider.
+        if let Some(result) = amd.as_mut().and_then(|detector| detector.push(pcm)) {
+            *amd = None;
+            amd_finished(self, result);
+        }
+        let fax_tones = self.fax.as_ref().map_or_else(Vec::new, |fax| fax.lock().unwrap().push(pcm));
+        if muted {
+            pcm.fill(0);
+        }
+        if let Some(recording) = recording.as_ref().filter(|_| passthrough.is_none()) {
+            recording.record(pcm);
+        }
+        if let Some(dump) = dump.as_ref() {
+            dump.inbound(pcm);
+        }
+        #[cfg(feature = "asr")]
+        if let Some(asr) = &self.asr {
+            asr.push(pcm);
+        }
+        if tapped {
+            tap::feed(self, Direction::Inbound, pcm);
+        }
+        fax_tones
+    }
+
+    /// Paketin oturum codec'indeki ses yükü; RED paketinde birincil blok.
+    pub(crate) fn audio_payload<'a>(&self, packet: &RtpPacketRef<'a>) -> Option<&'a [u8]> {
+        let payload_type = packet.payload_type();
+        if payload_type == self.codec().payload_type() {
+            return Some(packet.payload());
+        }
+        self.red.filter(|red| red.payload_type == payload_type)?;
+        rfc2198::primary(packet.payload(), packet.timestamp())
+            .filter(|block| block.payload_type == self.codec().payload_type())
+            .map(|block| block.data)
+    }
+
+    pub(crate) fn capture_sent(&self, target: SocketAddr, packet: &[u8]) {
+        if let Some(capture) = self.capture.get() {
+            capture.record(self.local_addr, target, packet);
+        }
+    }
+
+    /// Gelen paketi yakalamaya yazar. Gelen yön susturulmuşsa ses yükü sessizlikle değiştirilir;
+    /// yükü yerinde değiştirilemeyen RED paketleri yazılmaz.
+    pub(crate) fn capture_received(&self, source: SocketAddr, packet: &[u8]) {
+        let Some(capture) = self.capture.get() else { return };
+        if !self.inbound_muted() {
+            return capture.record(source, self.local_addr, packet);
+        }
+        let parsed = RtpPacketRef::parse(packet).ok().filter(|_| !rtcp::is_rtcp(packet));
+        let Some(audio) = parsed.as_ref().and_then(|parsed| self.audio_payload(parsed)) else {
+            return capture.record(source, self.local_addr, packet);
+        };
+        if parsed.is_some_and(|parsed| parsed.payload_type() != self.codec().payload_type()) {
+            return;
+        }
+        let mut pcm = Vec::new();
+        self.codec().decode(audio, &mut pcm);
+        let mut silence = Vec::with_capacity(audio.len());
+        self.codec().encode(&vec![0; pcm.len()], &mut silence);
+        if silence.len() != audio.len() {
+            return;
+        }
+        // Yük paketin sonundaki dolgudan önce biter.
+        let start = audio.as_ptr() as usize - packet.as_ptr() as usize;
+        let mut muted = packet.to_vec();
+        muted[start..start + silence.len()].copy_from_slice(&silence);
+        capture.record(source, self.local_addr, &muted);
+    }
+
+    /// DumpState için oturumun anlık durumu; her alan kendi kısa kilidiyle okunur.
+    pub(crate) fn state(&self, now: Instant) -> SessionState {
+        let stats = &self.stats;
+        let (remote_ssrc, packets_lost, jitter_ms) = {
+            let inbound = stats.inbound.lock().unwrap();
+            (inbound.remote_ssrc, inbound.sequence.lost(), inbound.jitter.jitter_ms(self.codec().clock_rate()))
+        };
+        let receive_bitrate = stats.receive_bitrate.lock().unwrap();
+        let millis = |duration: std::time::Duration| duration.as_millis() as u64;
+        SessionState {
+            port: self.port,
+            session_id: self.session_id.clone(),
+            call_id: self.call_id.clone(),
+            request_id: self.request_id.clone(),
+            tenant: self.tenant.as_ref().map(|t| t.name),
+            codec: self.codec().name(),
+            transport: self.transport.kind().as_str_name().to_lowercase(),
+            local_addr: self.local_addr.to_string(),
+            remote_addr: self.remote_addr.lock().unwrap().map(|addr| addr.to_string()),
+            ssrc: self.stream.ssrc,
+            remote_ssrc,
+            language: self.language.clone(),
+            labels: self.labels.clone(),
+            overflow: self.overflow,
+            send_only: self.send_only,
+            age_ms: millis(now - self.allocated_at),
+            idle_ms: receive_bitrate.updated_at().map(|at| millis(now.saturating_duration_since(at))),
+            expires_in_ms: self.max_duration.map(|max| millis((self.allocated_at + max).saturating_duration_since(now))),
+            stopping: self.stop_reason.lock().unwrap().map(|reason| reason.as_str()),
+            packets_sent: stats.packets_sent.load(Ordering::Relaxed),
+            bytes_sent: stats.bytes_sent.load(Ordering::Relaxed),
+            packets_received: stats.packets_received.load(Ordering::Relaxed),
+            bytes_received: stats.bytes_received.load(Ordering::Relaxed),
+            packets_malformed: stats.packets_malformed.load(Ordering::Relaxed),
+            packets_lost,
+            jitter_ms,
+            send_bitrate_bps: stats.send_bitrate.lock().unwrap().current_bps(now),
+            receive_bitrate_bps: receive_bitrate.current_bps(now),
+            announcements_started: stats.announcements_started.load(Ordering::Relaxed),
+            announcements_failed: stats.announcements_failed.load(Ordering::Relaxed),
+            playing: stats.playing.lock().unwrap().clone(),
+            recording: self.recording.lock().unwrap().as_ref().map_or_else(Vec::new, |recording| recording.paths()),
+            recorded: self.recorded.lock().unwrap().clone(),
+            bridged_to: self.bridge.lock().unwrap().as_ref().and_then(Relay::peer).map(|peer| peer.port),
+            taps: self.taps.lock().unwrap().iter().filter_map(Tap::leg).map(|leg| leg.port).collect(),
+            tapping: self.tap_source().map(|observed| observed.port),
+            capture: self.capture.get().is_some(),
+            audio_dump: self.audio_dump.lock().unwrap().is_some(),
+            inbound_muted: self.inbound_muted(),
+            outbound_muted: self.outbound_muted(),
+            direction: self.direction().as_str(),
+        }
+    }
+
+    /// Tek yönlü ses bekçisinin denetimi için iki yönün son etkinliği.
+    fn one_way_activity(&self, last_received: Option<Instant>) -> one_way::Activity {
+        let last_sent = (self.stats.packets_sent.load(Ordering::Relaxed) > 0).then(|| *self.last_sent.lock().unwrap());
+        let send_failures = self.stats.send_failures.lock().unwrap().filter(|(first, _)| last_sent.is_none_or(|sent| *first > sent));
+        let expected = self.send_only || self.direction() != sdp::Direction::SendRecv || self.tap_source().is_some();
+        one_way::Activity { last_received, last_sent, send_failures, expected }
+    }
+
+    /// Gönderilen paketi sayar ve yansıma denetimi için hatırlar.
+    pub(crate) fn mark_sent(&self, target: SocketAddr, wire: &[u8]) {
+        if let Ok(packet) = RtpPacketRef::parse(wire) {
+            self.stream.remember_sent(&packet);
+        }
+        let bytes = wire.len();
+        let now = Instant::now();
+        *self.last_sent.lock().unwrap() = now;
+        let wire_bytes = bytes + self.transport.wire_overhead(target);
+        self.stats.packet_sent(bytes, wire_bytes, now);
+        metrics::get().packet_sent(bytes, wire_bytes, now);
+    }
+}
+
+/// Aktif oturumlar, RTP portuna göre.
+// Geçici okuma hatalarında okumaya ara verme süresi; art arda hatalarda ikiye katlanır.
+const RECV_BACKOFF_MIN: Duration = Duration::from_millis(10);
+const RECV_BACKOFF_MAX: Duration = Duration::from_secs(1);
+// Veda anonsu bundan uzun sürerse (ör. döngülü) oturum yine de kapanır.
+const MAX_FAREWELL: Duration = Duration::from_secs(30);
+// Kapanış süresi dolduktan sonra kayıtların kapanması için tanınan ek süre.
+const FORCE_FINALIZE: Duration = Duration::from_secs(2);
+
+pub type ActiveSessions = Arc<Mutex<SessionRegistry>>;
+
+/// Aktif oturumlar, porta göre. AllocatePort'un döndüğü `session_id`'den porta bir dizin de tutar;
+/// kimlikle adresleme tabloyu taramaz. Okumalar port tablosuna `Deref` ile yapılır, değişiklikler
+/// dizini de güncelleyen yöntemlerden geçer.
+#[derive(Default)]
+pub struct SessionRegistry {
+    sessions: HashMap<u16, Arc<RtpSession>>,
+    ports: HashMap<String, u16>,
+}
+
+impl SessionRegistry {
+    /// Oturumu portuna yazar; portta başka bir oturum varsa onun yerini alır.
+    pub fn insert(&mut self, session: Arc<RtpSession>) -> Option<Arc<RtpSession>> {
+        let replaced = self.remove(session.port);
+        self.ports.insert(session.session_id.clone(), session.port);
+        self.sessions.insert(session.port, session);
+        replaced
+    }
+
+    pub fn remove(&mut self, port: u16) -> Option<Arc<RtpSession>> {
+        let session = self.sessions.remove(&port)?;
+        if self.ports.get(&session.session_id) == Some(&port) {
+            self.ports.remove(&session.session_id);
+        }
+        Some(session)
+    }
+
+    /// Bütün oturumları tablodan çıkarır.
+    pub fn drain(&mut self) -> impl Iterator<Item = Arc<RtpSession>> + '_ {
+        self.ports.clear();
+        self.sessions.drain().map(|(_, session)| session)
+    }
+
+    pub fn by_id(&self, session_id: &str) -> Option<&Arc<RtpSession>> {
+        self.ports.get(session_id).and_then(|port| self.sessions.get(port))
+    }
+}
+
+impl std::ops::Deref for SessionRegistry {
+    type Target = HashMap<u16, Arc<RtpSession>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sessions
+    }
+}
+
+impl FromIterator<Arc<RtpSession>> for SessionRegistry {
+    fn from_iter<I: IntoIterator<Item = Arc<RtpSession>>>(sessions: I) -> Self {
+        let mut registry = SessionRegistry::default();
+        for session in sessions {
+            registry.insert(session);
+        }
+        registry
+    }
+}
+
+/// Kalan oturumları `shutdown` sebebiyle kapatır ve özetlerinin yazılması için kısa bir süre bekler.
+pub async fn stop_all_sessions(active_sessions: &ActiveSessions) {
+    let sessions: Vec<Arc<RtpSession>> = active_sessions.lock().unwrap().values().cloned().collect();
+    for session in &sessions {
+        session.stop(TeardownReason::Shutdown);
+    }
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while !active_sessions.lock().unwrap().is_empty() && Instant::now() < deadline {
+        sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Oturumu `released` sebebiyle kapatır ve dinleyici görevi kapanışı bitirip portu tablodan
+/// düşürene kadar en fazla bir saniye bekler. Süre içinde düştüyse true; düşmediyse kapanış arka
+/// planda sürer.
+pub async fn release_session(session: &Arc<RtpSession>, active_sessions: &ActiveSessions) -> bool {
+    session.stop(TeardownReason::Released);
+    let listed = || active_sessions.lock().unwrap().get(&session.port).is_some_and(|listed| Arc::ptr_eq(listed, session));
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while listed() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    true
+}
+
+/// Kapanış süresi dolduğunda hâlâ kapanmamış oturumları kayıttan düşürür; kayıtları yazıcı
+/// kuyruğu boşaltılarak kapatılır, özet ve kanca yazılmaz. Kesilen oturum sayısını döner.
+pub async fn force_stop_sessions(active_sessions: &ActiveSessions) -> usize {
+    let sessions: Vec<Arc<RtpSession>> = active_sessions.lock().unwrap().drain().collect();
+    let mut recordings = Vec::new();
+    for session in &sessions {
+        session.stop(TeardownReason::Shutdown);
+        if let Some(recording) = session.recording.lock().unwrap().take() {
+            recordings.push(tokio::spawn(recording.finish()));
+        }
+    }
+    // Yazıcı takılmışsa beklemeden çıkılır; WAV başlığı o dosya için tamamlanmamış kalır.
+    let _ = timeout(FORCE_FINALIZE, async {
+        for recording in recordings {
+            let _ = recording.await;
+        }
+    }).await;
+    sessions.len()
+}
+
+/// Aktif oturumların kendiliğinden bitmesini en fazla `grace` kadar bekler.
+pub async fn wait_for_sessions(active_sessions: &ActiveSessions, grace: Duration) {
+    let deadline = Instant::now() + grace;
+    loop {
+        let remaining = active_sessions.lock().unwrap().len();
+        if remaining == 0 {
+            return;
+        }
+        if Instant::now() >= deadline {
+            warn!(sessions = remaining, "Bekleme süresi doldu, oturumlar sonlandırılmadan çıkılıyor");
+            return;
+        }
+        info!(sessions = remaining, "Aktif oturumların bitmesi bekleniyor...");
+        sleep(Duration::from_millis(500).min(deadline - Instant::now())).await;
+    }
+}
+
+pub async fn rtp_session_handler(
+    session: Arc<RtpSession>, prompts: Arc<PromptLibrary>, timers: TimersConfig, quality: QualityConfig, active_sessions: ActiveSessions,
+    scheduler: Arc<SendScheduler>,
+) {
+    let mut batch = session.transport.batch(session.recv_batch);
+    let mut last_received: Option<Instant> = None;
+    let mut latch = Latch::default();
+    let mut player = Player::new(session.codec(), timers.ptime()).with_queue(prompts.max_queued());
+    let mut pcm = Vec::new();
+    if let Some(red) = session.red {
+        player = player.with_red(red);
+    }
+    *session.player.lock().await = player;
+    // Giden ses dilim görevinden gönderilir; oynatma başlatan her adımdan sonra dilim uyandırılır.
+    let sending = scheduler.join(session.clone());
+    let mut playback_requests = session.playback_requests.lock().unwrap().take()
+        .expect("rtp_session_handler runs once per session");
+    let mut keepalive = timers.keepalive_interval().map(|period| {
+        let mut ticker = interval(period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    });
+
+    // Geçici okuma hatalarından sonra soket bir süre okunmaz; aksi halde döngü boşa döner.
+    let mut recv_errors = 0u32;
+    let mut recv_resume: Option<Instant> = None;
+    let mut inbound_guard = session.inbound_limit.map(InboundGuard::new);
+    // En uzun süre: önce uyarı olayı, süre dolunca varsa veda anonsu, anons bitince ya da en geç
+    // MAX_FAREWELL sonra kapanış.
+    let expires_at = session.max_duration.map(|d| session.allocated_at + d);
+    let mut expiry_warning = expires_at.zip(timers.max_duration_warning())
+        .map(|(at, warning)| at.checked_sub(warning).unwrap_or(session.allocated_at).max(session.allocated_at));
+    let mut expiry = expires_at;
+    let mut farewell_until: Option<Instant> = None;
+    let mut one_way = one_way::Watchdog::new(&quality);
+    let mut one_way_check = one_way.is_some().then(|| {
+        let mut ticker = interval(one_way::CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    });
+    let mut rtcp_buf = [0u8; MAX_PACKET_LEN];
+    let mut rtcp_reports = session.report_interval.map(|period| {
+        let mut ticker = interval_at(Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    });
+    if session.send_only && session.auto_welcome && !session.welcomed.swap(true, Ordering::Relaxed) {
+        play_welcome(&session, &prompts, &mut *session.player.lock().await);
+        sending.wake();
+    }
+
+    let reason = 'session: loop {
+        let (playing, played_until) = {
+            let player = session.player.lock().await;
+            (player.is_playing(), player.idle_since())
+        };
+        // İlk paketten önce first_packet_timeout, sonra media_timeout geçerlidir. Yalnızca gönderen
+        // oturum ilk paketi beklemez; çalarken süre işlemez, sonra media_timeout son gelen paketten
+        // ya da son oynatmanın bitişinden sayılır.
+        let receiving_since = *session.receiving_since.lock().unwrap();
+        let deadline = match last_received {
+            // Dinleme bacağı dinlediği oturumla birlikte kapanır.
+            _ if session.tap_source().is_some() => None,
+            // Bekletilen oturumda gelen ses beklenmez; yön dönünce süre yeniden başlar.
+            _ if !session.direction().receives() => None,
+            _ if session.send_only && playing => None,
+            _ if session.send_only => timers.media_timeout().map(|t| last_received.map_or(played_until, |at| at.max(played_until)).max(receiving_since) + t),
+            None => timers.first_packet_timeout().map(|t| receiving_since + t),
+            Some(at) => timers.media_timeout().map(|t| at.max(receiving_since) + t),
+        };
+
+        tokio::select! {
+            result = session.transport.recv_batch(&mut batch), if recv_resume.is_none() => {
+                match result {
+                    Ok(_) => {
+                        recv_errors = 0;
+                        // Tek çağrıda okunan paketler geldikleri sırayla, teker teker işlenir.
+                        for (data, addr) in batch.iter() {
+                            let len = data.len();
+                            let now = Instant::now();
+                            match inbound_guard.as_mut().map_or(Admission::Pass, |guard| guard.admit(&session, addr, now)) {
+                                Admission::Pass => {}
+                                Admission::Drop => continue,
+                                Admission::Teardown => break 'session TeardownReason::InboundFlood,
+                            }
+                            let wire_len = len + session.transport.wire_overhead(addr);
+                            metrics::get().packet_received(len, wire_len, now);
+                            session.stats.packet_received(len, wire_len, now);
+                            session.capture_received(addr, data);
+                            // RTCP (rtcp-mux ya da RFC 4571 bağlantısında araya giren) medya sayılmaz.
+                            if rtcp::is_rtcp(data) {
+                                rtcp_received(&session, addr, data, now);
+                                continue;
+                            }
+                            let packet = match RtpPacketRef::parse(data) {
+                                Ok(packet) => Some(packet),
+                                Err(e) => {
+                                    debug!(remote = %addr, len, error = %e, "Gelen RTP paketi ayrıştırılamadı");
+                                    metrics::get().packet_malformed(&e);
+                                    session.stats.packets_malformed.fetch_add(1, Ordering::Relaxed);
+                                    None
+                                }
+                            };
+                            // Geri yansıyan kendi paketlerimiz akışı kilitlemez, kayda ve köprüye girmez.
+                            if packet.as_ref().is_some_and(|packet| session.stream.is_reflection(packet)) {
+                                reflected(&session, addr);
+                                continue;
+                            }
+                            let source = Source { addr, ssrc: packet.as_ref().map(|p| p.ssrc()) };
+                            let latched = latch.observe(source, packet.as_ref().map(|p| p.sequence()), now, timers.new_stream_gap());
+                            // Kilitli akışa ait olmayan paketler ne zaman aşımını uzatır ne istatistiklere girer.
+                            if latched != Latched::Ignored {
+                                let mut fax_tones = Vec::new();
+                                last_received = Some(now);
+                                // Teklifteki başka bir codec'le gelen ses uzak ucun seçimidir; henüz gönderilmediyse ona geçilir.
+                                if let Some(codec) = packet.as_ref().and_then(|packet| session.offered_codec(packet.payload_type())) {
+                                    session.renegotiate_codec(codec).await;
+                                }
+                                if latched != Latched::Same {
+                                    stream_latched(&session, &prompts, &mut *session.player.lock().await, source, latched, now);
+                                    sending.wake();
+                                }
+                                if let Some(packet) = packet {
+                                    let mut inbound = session.stats.inbound.lock().unwrap();
+                                    let (arrival, clock_rate) = (now - session.allocated_at, session.codec().clock_rate());
+                                    inbound.remote_ssrc = Some(packet.ssrc());
+                                    let mut missing = inbound.sequence.observe(packet.sequence());
+                                    let mut recovered = Vec::new();
+                                    if session.red.is_some_and(|red| red.payload_type == packet.payload_type()) {
+                                        recovered = recover_red(&session, &mut inbound, &packet, missing);
+                                        missing -= recovered.len() as u64;
+                                    }
+                                    inbound.bursts.observe(missing);
+                                    if session.dtmf_payload_type() == Some(packet.payload_type()) {
+                                        if inbound.dtmf_event(packet.timestamp()) {
+                                            session.stats.dtmf_digits.fetch_add(1, Ordering::Relaxed);
+                                        }
+                                        let event = TelephoneEvent::parse(packet.payload()).filter(|event| event.end);
+                                        if let Some(event) = event.filter(|_| inbound.dtmf_end(packet.timestamp())) {
+                                            dtmf_digit(&session, event);
+                                        }
+                                    }
+                                    if let Some(id) = session.audio_level_id {
+                                        if let Some(level) = AudioLevel::parse(packet.extension(), id) {
+                                            inbound.audio_level = Some(level);
+                                        }
+                                    }
+                                    inbound.jitter.observe(arrival, packet.timestamp(), clock_rate);
+                                    if inbound.skew.observe(arrival, packet.timestamp(), clock_rate) {
+                                        if let Some(skew_ppm) = quality.skew_warning().and_then(|threshold| inbound.skew.exceeded(threshold)) {
+                                            warn!(target: audit::TARGET, event = audit::CLOCK_SKEW, skew_ppm, threshold_ppm = quality.skew_warning_ppm);
+                                        }
+                                    }
+                                    drop(inbound);
+                                    // Kurtarılan çerçeveler birincil yükten önce, sırayla ayrı paketler gibi işlenir.
+                                    let mut wire = [0u8; MAX_PACKET_LEN];
+                                    for (sequence, block) in recovered {
+                                        let frame = RtpPacket::new(block.payload_type, sequence, block.timestamp, packet.ssrc(), block.data);
+                                        let Ok(len) = frame.write(&mut wire) else { continue };
+                                        let Ok(frame) = RtpPacketRef::parse(&wire[..len]) else { continue };
+                                        bridge::forward(&session, &frame, now);
+                                        fax_tones.extend(session.inbound_audio(&frame, &mut pcm));
+                                    }
+                                    bridge::forward(&session, &packet, now);
+                                    fax_tones.extend(session.inbound_audio(&packet, &mut pcm));
+                                }
+                                for detection in fax_tones.drain(..) {
+                                    fax_tone_detected(&session, detection, &mut *session.player.lock().await);
+                                }
+                            }
+                        }
+                    }
+                    Err(e) if transport::is_disconnect(&e) => {
+                        info!(error = %e, "RTP/TCP bağlantısı kapandı, oturum kapatılıyor");
+                        break TeardownReason::PeerDisconnected;
+                    }
+                    Err(e) if recv_error_is_transient(&e) => {
+                        recv_errors += 1;
+                        let delay = (RECV_BACKOFF_MIN * 2u32.pow(recv_errors.min(8) - 1)).min(RECV_BACKOFF_MAX);
+                        debug!(error = %e, errors = recv_errors, delay_ms = delay.as_millis() as u64, "RTP soketi okunamadı, yeniden denenecek");
+                        recv_resume = Some(Instant::now() + delay);
+                    }
+                    Err(e) => {
+                        error!(error = %e, "RTP soketi okunamıyor, oturum kapatılıyor");
+                        break TeardownReason::SocketError;
+                    }
+                }
+            }
+            _ = sleep_until_opt(recv_resume) => {
+                recv_resume = None;
+            }
+            _ = sleep_until_opt(expiry_warning) => {
+                expiry_warning = None;
+                let remaining = expires_at.map_or(Duration::ZERO, |at| at.saturating_duration_since(Instant::now()));
+                info!(
+                    target: audit::TARGET, event = audit::SESSION_EXPIRING,
+                    remaining_s = remaining.as_secs_f64().round() as u64, max_duration_s = session.max_duration.map(|d| d.as_secs()),
+                );
+            }
+            _ = sleep_until_opt(expiry) => {
+                expiry = None;
+                if !play_farewell(&session, &prompts, &mut *session.player.lock().await) {
+                    break TeardownReason::MaxDuration;
+                }
+                sending.wake();
+                farewell_until = Some(Instant::now() + MAX_FAREWELL);
+            }
+            _ = sleep_until_opt(farewell_until) => {
+                break TeardownReason::MaxDuration;
+            }
+            _ = sleep_until_opt(deadline) => {
+                break if last_received.is_none() { TeardownReason::FirstPacketTimeout } else { TeardownReason::MediaTimeout };
+            }
+            _ = session.stop.notified() => {
+                break session.stop_reason.lock().unwrap().unwrap_or(TeardownReason::Shutdown);
+            }
+            Some(request) = playback_requests.recv() => match request {
+                PlaybackRequest::Play { playback, mode, reply } => {
+                    let result = session.player.lock().await.submit(&session, playback, mode);
+                    sending.wake();
+                    if let Some(reply) = reply {
+                        let _ = reply.send(result);
+                    }
+                }
+                PlaybackRequest::Stop { flush, reply } => {
+                    let _ = reply.send(session.player.lock().await.stop_current(&session, flush));
+                    sending.wake();
+                }
+            },
+            // Dilim görevi oynatmanın bittiğini ya da oturumun yönü değişti; veda anonsu bittiyse oturum
+            // kapanır, değilse süreler döngü başında yeniden hesaplanır ve duran oynatma sürer.
+            _ = session.playback_idle.notified() => {
+                if farewell_until.is_some() && !session.player.lock().await.is_playing() {
+                    break TeardownReason::MaxDuration;
+                }
+                sending.wake();
+            }
+            _ = tick_opt(&mut keepalive) => {
+                send_keepalive(&session, timers).await;
+            }
+            _ = tick_opt(&mut rtcp_reports) => {
+                send_report(&session).await;
+            }
+            result = recv_opt(session.rtcp.as_ref(), &mut rtcp_buf) => {
+                match result {
+                    Ok((len, addr)) if rtcp::is_rtcp(&rtcp_buf[..len]) => {
+                        let data = &rtcp_buf[..len];
+                        if let Some(capture) = session.capture.get() {
+                            capture.record(addr, session.rtcp_local_addr(), data);
+                        }
+                        // Karşı uç RTCP'yi RTP portunun bir üstü dışında bir yerden gönderiyorsa
+                        // raporlar oraya döner; yabancı kaynaklar hedefi değiştiremez.
+                        if rtcp_received(&session, addr, data, Instant::now()) {
+                            *session.rtcp_remote.lock().unwrap() = Some(addr);
+                        }
+                    }
+                    Ok((len, addr)) => debug!(remote = %addr, len, "RTCP portuna RTCP olmayan paket geldi"),
+                    Err(e) => debug!(error = %e, "RTCP portu okunamadı"),
+                }
+            }
+            _ = tick_opt(&mut one_way_check) => {
+                let transition = one_way.as_mut().and_then(|watchdog| watchdog.check(&session.one_way_activity(last_received), Instant::now()));
+                if let Some(transition) = transition {
+                    one_way_audio(&session, transition);
+                }
+            }
+        }
+    };
+    drop(sending);
+    session.player.lock().await.stop(&session, PlaybackStopReason::SessionEnded);
+    send_bye(&session, reason).await;
+
+    let one_way = one_way.map_or((None, Duration::ZERO), |watchdog| {
+        if watchdog.is_suspected() {
+            metrics::get().one_way_audio_sessions.dec();
+        }
+        watchdog.summary(Instant::now())
+    });
+    finish_session(&session, reason, timers.ptime(), one_way, &active_sessions);
+}
+
+enum Admission {
+    Pass,
+    Drop,
+    Teardown,
+}
+
+/// Gelen paket seline karşı koruma: sınırı aşan paketler ayrıştırılmadan atılır, sel
+/// `flood_trip_s` boyunca sürerse `flood_action` uygulanır. Engellenen adresler oturum boyunca
+/// kovaya hiç uğramadan atılır.
+struct InboundGuard {
+    config: RateLimitConfig,
+    flood: FloodGuard,
+    blocked: Vec<SocketAddr>,
+}
+
+impl InboundGuard {
+    fn new(config: RateLimitConfig) -> Self {
+        InboundGuard { flood: FloodGuard::new(config.inbound_packets_per_s, config.inbound_burst, config.flood_trip()), config, blocked: Vec::new() }
+    }
+
+    fn admit(&mut self, session: &RtpSession, addr: SocketAddr, now: Instant) -> Admission {
+        let verdict = if self.blocked.contains(&addr) { Inbound::Drop } else { self.flood.admit(now) };
+        match verdict {
+            Inbound::Pass => return Admission::Pass,
+            Inbound::Drop => {}
+            Inbound::Engaged => {
+                warn!(
+                    target: audit::TARGET, event = audit::INBOUND_FLOOD,
+                    remote = %addr, limit_pps = self.config.inbound_packets_per_s, burst = self.config.inbound_burst,
+                );
+            }
+            Inbound::Tripped { flooding } => {
+                let action = match self.config.flood_action {
+                    FloodAction::None => "none",
+                    FloodAction::Teardown => "teardown",
+                    FloodAction::Block => "block",
+                };
+                warn!(target: audit::TARGET, event = audit::INBOUND_FLOOD_TRIPPED, remote = %addr, action, flood_ms = flooding.as_millis() as u64);
+                if self.config.flood_action == FloodAction::Teardown {
+                    return Admission::Teardown;
+                }
+                self.blocked.push(addr);
+                self.flood.reset();
+            }
+        }
+        metrics::get().rtp_packets_flood_dropped.inc();
+        session.stats.packets_flood_dropped.fetch_add(1, Ordering::Relaxed);
+        Admission::Drop
+    }
+}
+
+/// Yansıyan paketi sayar; oturumda ilk kez görülünce yansıtan adresi uyarı olarak yazar.
+fn reflected(session: &RtpSession, addr: SocketAddr) {
+    metrics::get().rtp_packets_reflected.inc();
+    session.stats.packets_reflected.fetch_add(1, Ordering::Relaxed);
+    if !session.reflection_warned.swap(true, Ordering::Relaxed) {
+        warn!(target: audit::TARGET, event = audit::RTP_REFLECTED, remote = %addr, ssrc = session.stream.ssrc);
+    }
+}
+
+/// RED paketindeki yedekleri, daha önce gelmemiş paketlerin yerine sayar. Her yedeğin bir önceki
+/// pakete ait olduğu varsayılır (paket başına bir çerçeve): sondan n. yedek `sequence - n`'dir.
+/// Kurtarılanlardan bu paketle kapanan boşluğa (`missing`) düşenleri sıra numaralarıyla, eskiden
+/// yeniye döner; boşluktan önceki yedekler sayılır ama sesleri geç kaldığı için dönmez.
+fn recover_red<'a>(session: &RtpSession, inbound: &mut InboundStats, packet: &RtpPacketRef<'a>, missing: u64) -> Vec<(u16, RedBlock<'a>)> {
+    let mut blocks = Vec::with_capacity(rfc2198::MAX_GENERATIONS + 1);
+    if let Err(e) = rfc2198::parse(packet.payload(), packet.timestamp(), &mut blocks) {
+        debug!(error = %e, "RED yükü ayrıştırılamadı");
+        return Vec::new();
+    }
+    let redundant = blocks.len() - 1;
+    let mut in_gap = Vec::new();
+    for generation in (1..=redundant).rev() {
+        let sequence = packet.sequence().wrapping_sub(generation as u16);
+        if inbound.sequence.recover(sequence) {
+            metrics::get().red_recovered.inc();
+            session.stats.red_recovered.fetch_add(1, Ordering::Relaxed);
+            if generation as u64 <= missing {
+                in_gap.push((sequence, blocks[redundant - generation]));
+            }
+        }
+    }
+    in_gap
+}
+
+/// Gelen akışın kaynağı; RTP olmayan paketlerin SSRC'si yoktur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Source {
+    addr: SocketAddr,
+    ssrc: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Latched {
+    First,
+    Same,
+    Changed { previous: Source, trigger: StreamChangeTrigger, gap: Duration },
+    /// Kilitli akışa ait değil ve henüz yeni akış olarak kabul edilmedi.
+    Ignored,
+}
+
+/// Gelen akışın kilitlendiği kaynağı izler ve yeni akış kararını verir.
+///
+/// Aynı kaynaktan gelen paketler sıra numarası ne olursa olsun (yeniden sıralama, kayıp, tekrar)
+/// aynı akıştır. Başka bir kaynak ise RFC 3550 A.1'deki gibi ancak ardışık sıra numaralı iki
+/// paketten sonra kabul edilir; araya düşen tek tük paketler veya eski bacağın gecikmiş
+/// paketleri akışı değiştirmez. Aynı SSRC'nin yeni bir adresten gelmesi yalnızca hedefi günceller.
+#[derive(Debug, Default)]
+struct Latch {
+    current: Option<Source>,
+    // Başka kaynaktan gelen son paket ve sıra numarası.
+    candidate: Option<(Source, u16)>,
+    last_at: Option<Instant>,
+}
+
+impl Latch {
+    /// `sequence` yalnızca RTP paketlerinde vardır.
+    fn observe(&mut self, incoming: Source, sequence: Option<u16>, now: Instant, new_stream_gap: Option<Duration>) -> Latched {
+        let addr = incoming.addr;
+        let Some(current) = self.current else {
+            self.accept(incoming, now);
+            return Latched::First;
+        };
+        let gap = self.last_at.map_or(Duration::ZERO, |at| now - at);
+        let silence = new_stream_gap.is_some_and(|threshold| gap >= threshold);
+        let same_source = match (current.ssrc, incoming.ssrc) {
+            (Some(current), Some(incoming)) => current == incoming,
+            _ => current.addr == addr,
+        };
+        let trigger = if same_source {
+            if silence {
+                StreamChangeTrigger::SilenceGap
+            } else if addr != current.addr {
+                StreamChangeTrigger::AddressChanged
+            } else {
+                self.accept(Source { addr, ssrc: incoming.ssrc.or(current.ssrc) }, now);
+                return Latched::Same;
+            }
+        } else {
+            let Some(sequence) = sequence else { return Latched::Ignored };
+            let confirmed = matches!(self.candidate, Some((candidate, last)) if candidate == incoming && sequence == last.wrapping_add(1));
+            if !confirmed {
+                self.candidate = Some((incoming, sequence));
+                return Latched::Ignored;
+            }
+            if silence { StreamChangeTrigger::SilenceGap } else { StreamChangeTrigger::NewSource }
+        };
+        self.accept(incoming, now);
+        Latched::Changed { previous: current, trigger, gap }
+    }
+
+    fn accept(&mut self, source: Source, now: Instant) {
+        if self.current != Some(source) {
+            self.candidate = None;
+        }
+        self.current = Some(source);
+        self.last_at = Some(now);
+    }
+}
+
+/// Okuma hatası geçici mi: ICMP kaynaklı bağlantı hataları ve kesintiler soketi bozmaz. Diğerleri
+/// (EBADF, ENOTSOCK gibi) her denemede tekrarlanır ve oturumu kapatır.
+fn recv_error_is_transient(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionRefused | io::ErrorKind::ConnectionReset | io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::OutOfMemory
+    )
+}
+
+/// Kilitlenen veya değişen gelen akış için hedef adresi, gelen istatistikleri ve karşılama
+/// anonsunu günceller.
+fn stream_latched(session: &RtpSession, prompts: &PromptLibrary, player: &mut Player, source: Source, latched: Latched, now: Instant) {
+    let Source { addr, ssrc } = source;
+    match latched {
+        Latched::Same | Latched::Ignored => {}
+        Latched::First => {
+            if session.follows_inbound() {
+                *session.remote_addr.lock().unwrap() = Some(addr);
+                session.span.record("remote", tracing::field::display(addr));
+            }
+            session.stats.inbound.lock().unwrap().first_packet_at = Some(now);
+            port_journal::latched(session, addr);
+            info!(
+                target: audit::TARGET, event = audit::FIRST_PACKET,
+                remote = %addr, wait_ms = (now - session.allocated_at).as_millis() as u64,
+            );
+            // İlk paket oturumda bir kez karşılama çalar; tekrar yalnızca replay_welcome ile.
+            if session.auto_welcome && !session.welcomed.swap(true, Ordering::Relaxed) {
+                play_welcome(session, prompts, player);
+            }
+        }
+        Latched::Changed { previous, trigger, gap } => {
+            if session.follows_inbound() {
+                *session.remote_addr.lock().unwrap() = Some(addr);
+                session.span.record("remote", tracing::field::display(addr));
+            }
+            if addr != previous.addr {
+                port_journal::latched(session, addr);
+            }
+            let new_stream = trigger != StreamChangeTrigger::AddressChanged;
+            if new_stream {
+                session.stats.inbound.lock().unwrap().reset_stream();
+            }
+            let replay = new_stream && session.follows_inbound() && session.auto_welcome && prompts.replay_welcome() && prompts.welcome().is_some();
+            info!(
+                target: audit::TARGET, event = audit::STREAM_CHANGED,
+                previous_remote = %previous.addr, remote = %addr, previous_ssrc = previous.ssrc, ssrc,
+                trigger = trigger.as_str(), gap_ms = gap.as_millis() as u64, welcome_replayed = replay,
+            );
+            if replay {
+                play_welcome(session, prompts, player);
+            }
+        }
+    }
+}
+
+fn play_welcome(session: &RtpSession, prompts: &PromptLibrary, player: &mut Player) {
+    if let Some(welcome) = prompts.welcome() {
+        if let Some(cause) = admission::skip_welcome() {
+            metrics::get().welcomes_skipped.inc();
+            info!(target: audit::TARGET, event = audit::WELCOME_SKIPPED, prompt = %welcome.name, cause = cause.as_str());
+            return;
+        }
+        let opened = Playback::prompt(&welcome.in_language(session.language.as_deref())).and_then(|playback| match prompts.welcome_bed() {
+            Some((bed, gain_db)) => playback.with_bed(&bed, gain_db),
+            None => Ok(playback),
+        });
+        match opened {
+            Ok(welcome) => {
+                player.install(session, welcome);
+            }
+            Err(e) => playback::load_failed(session, &welcome.name, &e),
+        }
+    }
+}
+
+/// En uzun süre dolduğunda uzak adres biliniyorsa `announcement.max_duration` anonsunu kurar;
+/// kurulamadıysa oturum hemen kapanır. Kuyrukta bekleyenler vedadan sonra çalamayacağı için atılır.
+fn play_farewell(session: &RtpSession, prompts: &PromptLibrary, player: &mut Player) -> bool {
+    let Some(prompt) = prompts.max_duration() else { return false };
+    if session.remote_addr.lock().unwrap().is_none() {
+        return false;
+    }
+    match Playback::prompt(&prompt.in_language(session.language.as_deref())) {
+        Ok(playback) => {
+            player.flush(PlaybackStopReason::SessionEnded);
+            player.install(session, playback);
+            true
+        }
+        Err(e) => {
+            playback::load_failed(session, &prompt.name, &e);
+            false
+        }
+    }
+}
+
+/// Oturumun tek kapanış noktası: kayıttan çıkarır, metrikleri günceller ve özeti yazar.
+fn finish_session(session: &RtpSession, reason: TeardownReason, ptime: Duration, one_way: (Option<OneWayDirection>, Duration), active_sessions: &ActiveSessions) {
+    active_sessions.lock().unwrap().remove(session.port);
+    bridge::unbridge(session, UnbridgeReason::SessionEnded);
+    tap::detach(session, TapEndReason::TapEnded);
+    tap::close(session);
+    session.inbound_muted.store(false, Ordering::Relaxed);
+    session.outbound_muted.store(false, Ordering::Relaxed);
+    if let Some(recording) = session.recording.lock().unwrap().take() {
+        if object_store::uploads_enabled() {
+            // Dosyalar kapanınca yüklenir; oturum kapanışı beklemez.
+            session.recorded.lock().unwrap().extend(recording.paths());
+            session.upload(async move { recording.finish().await.paths });
+        } else {
+            session.recorded.lock().unwrap().extend(recording.close());
+        }
+    }
+    // Karar verilmeden kapanan analiz de sonucunu yazar; bekleyen çağıran hep bir sonuç görür.
+    if let Some(detector) = session.amd.lock().unwrap().take() {
+        amd_finished(session, detector.finish());
+    }
+    // Tampondaki ses arka planda gönderilir ve son sonuçlar beklenir.
+    #[cfg(feature = "asr")]
+    if let Some(asr) = &session.asr {
+        asr.close();
+    }
+    // Dosyalar arka planda kapanır; döküm yeri hemen boşalır.
+    let audio_dumps = session.audio_dump.lock().unwrap().take().map_or_else(Vec::new, |dump| dump.paths().to_vec());
+    metrics::get().releases.inc();
+    metrics::get().active_sessions.dec();
+    if session.overflow {
+        metrics::get().overflow_sessions.dec();
+    }
+    if let Some(tenant) = &session.tenant {
+        tenant.releases.inc();
+        tenant.active_sessions.dec();
+    }
+
+    let stats = &session.stats;
+    let inbound = stats.inbound.lock().unwrap();
+    for series in &session.label_metrics {
+        series.active_sessions.dec();
+        series.packets_received.add(stats.packets_received.load(Ordering::Relaxed));
+        series.packets_lost.add(inbound.sequence.lost());
+    }
+    let clock_rate = session.codec().clock_rate();
+    let quality = inbound.quality(clock_rate, ptime);
+    let playback_failure = *stats.playback_failure.lock().unwrap();
+    let send_error = *stats.send_error.lock().unwrap();
+    let duration = session.allocated_at.elapsed();
+    let (send_bitrate, receive_bitrate) = (stats.send_bitrate.lock().unwrap(), stats.receive_bitrate.lock().unwrap());
+    info!(
+        target: audit::TARGET, event = audit::SESSION_SUMMARY,
+        request_id = %session.request_id,
+        duration_ms = duration.as_millis() as u64,
+        first_packet_ms = inbound.first_packet_at.map(|at| (at - session.allocated_at).as_millis() as u64),
+        packets_sent = stats.packets_sent.load(Ordering::Relaxed),
+        bytes_sent = stats.bytes_sent.load(Ordering::Relaxed),
+        packets_received = stats.packets_received.load(Ordering::Relaxed),
+        bytes_received = stats.bytes_received.load(Ordering::Relaxed),
+        packets_malformed = stats.packets_malformed.load(Ordering::Relaxed),
+        packets_flood_dropped = stats.packets_flood_dropped.load(Ordering::Relaxed),
+        packets_reflected = stats.packets_reflected.load(Ordering::Relaxed),
+        packets_lost = inbound.sequence.lost(),
+        packets_duplicated = inbound.sequence.duplicates(),
+        sequence_gaps = inbound.sequence.gaps(),
+        jitter_ms = inbound.jitter.jitter_ms(clock_rate),
+        clock_skew_ppm = inbound.skew.skew_ppm(),
+        r_factor = quality.map(|q| q.r_factor),
+        mos = quality.map(|q| q.mos),
+        red_recovered = stats.red_recovered.load(Ordering::Relaxed),
+        announcements_played = stats.announcements_started.load(Ordering::Relaxed),
+        announcements_failed = stats.announcements_failed.load(Ordering::Relaxed),
+        playback_failure = playback_failure.map(PlaybackFailure::as_str),
+        dtmf_digits = stats.dtmf_digits.load(Ordering::Relaxed),
+        send_bitrate_avg_bps = send_bitrate.average_bps(duration).round() as u64,
+        send_bitrate_peak_bps = send_bitrate.peak_bps().round() as u64,
+        receive_bitrate_avg_bps = receive_bitrate.average_bps(duration).round() as u64,
+        receive_bitrate_peak_bps = receive_bitrate.peak_bps().round() as u64,
+        recordings = %session.recorded.lock().unwrap().join(","),
+        audio_dumps = %audio_dumps.join(","),
+        codecs = session.codec().name(),
+        teardown_reason = reason.as_str(),
+        tenant = session.tenant.as_ref().map(|t| t.name),
+        recording_key_id = session.recording_key_id.lock().unwrap().as_deref(),
+        labels = %labels::format(&session.labels),
+        fax_tone = session.fax_tone.lock().unwrap().map(FaxTone::as_str),
+        amd_result = session.amd_result.lock().unwrap().map(|result| result.verdict.as_str()),
+        send_error = send_error.map(SendFailure::as_str),
+        one_way_audio = one_way.0.map(OneWayDirection::as_str),
+        one_way_audio_ms = one_way.1.as_millis() as u64,
+    );
+    let mut report = SessionReport {
+        session_id: session.session_id.clone(),
+        call_id: session.call_id.clone(),
+        request_id: session.request_id.clone(),
+        port: session.port,
+        duration_ms: session.allocated_at.elapsed().as_millis() as u64,
+        packets_sent: stats.packets_sent.load(Ordering::Relaxed),
+        bytes_sent: stats.bytes_sent.load(Ordering::Relaxed),
+        packets_received: stats.packets_received.load(Ordering::Relaxed),
+        bytes_received: stats.bytes_received.load(Ordering::Relaxed),
+        packets_lost: inbound.sequence.lost(),
+        packets_duplicated: inbound.sequence.duplicates(),
+        jitter_ms: inbound.jitter.jitter_ms(clock_rate),
+        mos: quality.map(|q| q.mos),
+        announcements_failed: stats.announcements_failed.load(Ordering::Relaxed),
+        playback_failure: playback_failure.map(PlaybackFailure::as_str),
+        send_error: send_error.map(SendFailure::as_str),
+        recordings: session.recorded.lock().unwrap().clone(),
+        teardown_reason: reason.as_str(),
+        tenant: session.tenant.as_ref().map(|t| t.name),
+        uploads: Vec::new(),
+        audio_dumps,
+        recording_key_id: session.recording_key_id.lock().unwrap().clone(),
+        labels: session.labels.clone(),
+    };
+    let uploads = std::mem::take(&mut *session.uploads.lock().unwrap());
+    if uploads.is_empty() {
+        hook::session_ended(report);
+    } else {
+        // Kanca bildirimi kayıtların yükleme sonuçlarıyla gider.
+        tokio::spawn(async move {
+            for upload in uploads {
+                report.uploads.extend(upload.await.unwrap_or_default());
+            }
+            hook::session_ended(report);
+        }.instrument(session.span.clone()));
+    }
+    port_journal::released(session);
+    let wall = |at: Instant| cdr::unix_millis(session.allocated_wall + at.saturating_duration_since(session.allocated_at));
+    let expected = inbound.sequence.expected();
+    cdr::session_ended(CdrRecord {
+        session_id: session.session_id.clone(),
+        call_id: session.call_id.clone(),
+        allocated_at_ms: cdr::unix_millis(session.allocated_wall),
+        first_packet_at_ms: inbound.first_packet_at.map(wall),
+        ended_at_ms: wall(Instant::now()),
+        teardown_reason: reason.as_str(),
+        codecs: session.codec().name(),
+        packets_sent: stats.packets_sent.load(Ordering::Relaxed),
+        bytes_sent: stats.bytes_sent.load(Ordering::Relaxed),
+        packets_received: stats.packets_received.load(Ordering::Relaxed),
+        bytes_received: stats.bytes_received.load(Ordering::Relaxed),
+        loss_percent: if expected > 0 { (inbound.sequence.lost() as f64 * 10_000.0 / expected as f64).round() / 100.0 } else { 0.0 },
+        recordings: session.recorded.lock().unwrap().clone(),
+        dtmf_digits: stats.dtmf_digits.load(Ordering::Relaxed),
+        tenant: session.tenant.as_ref().map_or("", |t| t.name),
+        labels: session.labels.clone(),
+    });
+}
+
+/// Faks tonunu yazar; `fax_detection.stop_playback` açıksa çalanı durdurur ve kuyruğu boşaltır.
+fn fax_tone_detected(session: &RtpSession, detection: FaxDetection, player: &mut Player) {
+    session.fax_tone.lock().unwrap().get_or_insert(detection.tone);
+    metrics::get().fax_tone_detected(detection.tone);
+    let stop = session.fax_stops_playback && player.is_playing();
+    info!(
+        target: audit::TARGET, event = audit::FAX_TONE_DETECTED,
+        tone = detection.tone.as_str(), offset_ms = session.allocated_at.elapsed().as_millis() as u64,
+        duration_ms = detection.duration.as_millis() as u64, playback_stopped = stop,
+    );
+    if stop {
+        player.stop(session, PlaybackStopReason::FaxTone);
+    }
+}
+
+/// Tek yönlü ses bekçisinin bildirdiği değişikliği yazar ve sayar.
+fn one_way_audio(session: &RtpSession, transition: one_way::Transition) {
+    match transition {
+        one_way::Transition::Suspected { direction, silent } => {
+            metrics::get().one_way_audio_suspected(direction);
+            warn!(
+                target: audit::TARGET, event = audit::ONE_WAY_AUDIO_SUSPECTED,
+                direction = direction.as_str(), silent_ms = silent.as_millis() as u64,
+                packets_sent = session.stats.packets_sent.load(Ordering::Relaxed), packets_received = session.stats.packets_received.load(Ordering::Relaxed),
+                send_error = session.stats.send_error.lock().unwrap().map(SendFailure::as_str),
+            );
+        }
+        one_way::Transition::Cleared { direction, duration } => {
+            metrics::get().one_way_audio_sessions.dec();
+            info!(target: audit::TARGET, event = audit::ONE_WAY_AUDIO_CLEARED, direction = direction.as_str(), duration_ms = duration.as_millis() as u64);
+        }
+    }
+}
+
+/// Telesekreter analizinin sonucunu yazar.
+/// Biten tuşu kancaya bildirir; kuyruğa koymaktan başka iş yapmaz.
+fn dtmf_digit(session: &RtpSession, event: TelephoneEvent) {
+    let Some(digit) = event.digit() else { return };
+    let report = DigitReport {
+        session_id: session.session_id.clone(),
+        call_id: session.call_id.clone(),
+        digit,
+        duration_ms: event.duration as u64 * 1000 / session.codec().clock_rate() as u64,
+        method: dtmf_hook::METHOD_RFC4733,
+        timestamp_ms: cdr::unix_millis(SystemTime::now()),
+    };
+    debug!(digit = %digit, duration_ms = report.duration_ms, "DTMF tuşu alındı");
+    dtmf_hook::digit(session.dtmf_hook_url.as_deref(), report);
+}
+
+fn amd_finished(session: &RtpSession, result: AmdResult) {
+    *session.amd_result.lock().unwrap() = Some(result);
+    metrics::get().amd_result(result.verdict);
+    info!(
+        target: audit::TARGET, event = audit::AMD_RESULT,
+        result = result.verdict.as_str(), reason = result.reason.as_str(), initial_silence_ms = result.initial_silence_ms,
+        greeting_ms = result.greeting_ms, words = result.words, silence_after_greeting_ms = result.silence_after_greeting_ms,
+        analysis_ms = result.analysis_ms,
+    );
+}
+
+/// Son keepalive aralığı içinde hiç paket gönderilmediyse NAT bağlantısını canlı tutmak için
+/// oturumun akışında bir konfor gürültüsü paketi gönderir (RFC 6263 4.6, RFC 3389). Paket
+/// akışın sıra numarasını ve zaman damgasını ilerletir; karşı uç kesinti değil sessizlik görür.
+async fn send_keepalive(session: &RtpSession, timers: TimersConfig) {
+    let Some(period) = timers.keepalive_interval() else { return };
+    let Some(target_addr) = *session.remote_addr.lock().unwrap() else { return };
+    if session.last_sent.lock().unwrap().elapsed() < period {
+        return;
+    }
+    // Gürültü seviyesi -127 dBov: duyulabilir bir ses üretmez.
+    const NOISE_LEVEL: [u8; 1] = [127];
+    let (sequence, timestamp) = session.stream.next(Instant::now(), 0);
+    let mut wire = [0u8; 16];
+    let Ok(len) = RtpPacket::new(COMFORT_NOISE_PT, sequence, timestamp, session.stream.ssrc, &NOISE_LEVEL).write(&mut wire) else { return };
+    match session.send_to(&wire[..len], target_addr).await {
+        Ok(_) => {
+            session.mark_sent(target_addr, &wire[..len]);
+            session.capture_sent(target_addr, &wire[..len]);
+        }
+        Err(e) => warn!(error = %e, "Keepalive gönderilemedi"),
+    }
+}
+
+/// Gelen birleşik RTCP paketindeki SR ve RR'leri okur: kilitli akışın SR'ı alım raporumuzun
+/// LSR'ı olur, giden akışımız için gelen blok uzak ucun gördüğü kaybı, jitter'ı ve RTT'yi verir.
+/// Yalnızca kilitli uzak adresin IP'sinden ya da kilitli gelen akışın SSRC'siyle gelen raporlar
+/// okunur; en az biri okunduysa `true` döner.
+fn rtcp_received(session: &RtpSession, addr: SocketAddr, data: &[u8], now: Instant) -> bool {
+    let reports = match rtcp::parse_reports(data) {
+        Ok(reports) => reports,
+        Err(e) => {
+            debug!(remote = %addr, len = data.len(), error = %e, "Gelen RTCP paketi ayrıştırılamadı");
+            return false;
+        }
+    };
+    let from_remote = session.remote_addr.lock().unwrap().is_some_and(|remote| remote.ip() == addr.ip());
+    let remote_ssrc = session.stats.inbound.lock().unwrap().remote_ssrc;
+    let arrival = rtcp::ntp_middle(rtcp::ntp_now());
+    let mut accepted = 0;
+    for report in &reports {
+        if !from_remote && remote_ssrc != Some(report.ssrc) {
+            continue;
+        }
+        accepted += 1;
+        if let Some(sender) = report.sender {
+            let mut inbound = session.stats.inbound.lock().unwrap();
+            if inbound.remote_ssrc.is_none_or(|ssrc| ssrc == report.ssrc) {
+                inbound.last_sr = Some((rtcp::ntp_middle(sender.ntp_timestamp), now));
+            }
+        }
+        if let Some(block) = report.blocks.iter().find(|block| block.ssrc == session.stream.ssrc) {
+            *session.stats.remote_report.lock().unwrap() = Some(RemoteReport {
+                received_at: now,
+                fraction_lost: block.fraction_lost,
+                cumulative_lost: block.cumulative_lost,
+                jitter: block.jitter,
+                round_trip: rtcp::round_trip(block, arrival),
+            });
+        }
+    }
+    if accepted == 0 {
+        debug!(remote = %addr, len = data.len(), "Uzak uca ait olmayan RTCP paketi yok sayıldı");
+        return false;
+    }
+    debug!(remote = %addr, len = data.len(), reports = reports.len(), "RTCP paketi alındı");
+    true
+}
+
+/// Oturum boyunca düzenli rapor: SR (hiç paket gönderilmediyse RR) ve SDES CNAME. Uzak adres
+/// henüz bilinmiyorsa gönderilmez.
+async fn send_report(session: &RtpSession) {
+    let Some(target_addr) = *session.remote_addr.lock().unwrap() else { return };
+    let mut wire = [0u8; 256];
+    let mut writer = rtcp::CompoundWriter::new(&mut wire);
+    if let Err(e) = write_report(session, &mut writer) {
+        warn!(error = %e, "RTCP raporu oluşturulamadı");
+        return;
+    }
+    let len = writer.finish();
+    if let Err(e) = send_rtcp(session, &wire[..len], target_addr).await {
+        debug!(error = %e, "RTCP raporu gönderilemedi");
+    }
+}
+
+/// Birleşik paketin başını yazar: bu akıştan RTP gönderildiyse SR, yoksa RR; kilitli bir gelen
+/// akış varsa onun alım raporu bloğu (RFC 3550 6.4.1) ve SDES CNAME.
+fn write_report<'w, 'a>(session: &RtpSession, writer: &'w mut rtcp::CompoundWriter<'a>) -> Result<&'w mut rtcp::CompoundWriter<'a>, BuildError> {
+    let ssrc = session.stream.ssrc;
+    let block = {
+        let mut inbound = session.stats.inbound.lock().unwrap();
+        let fraction_lost = inbound.fraction_lost_since_report();
+        let (last_sr, delay_since_last_sr) = inbound.last_sr.map_or((0, 0), |(last_sr, at)| {
+            // DLSR 1/65536 saniye birimindedir.
+            (last_sr, (at.elapsed().as_micros() as u64 * 65_536 / 1_000_000).min(u32::MAX as u64) as u32)
+        });
+        let sequence = &inbound.sequence;
+        inbound.remote_ssrc.zip(sequence.highest()).map(|(remote_ssrc, highest)| rtcp::ReportBlock {
+            ssrc: remote_ssrc,
+            fraction_lost,
+            cumulative_lost: sequence.lost().min(i32::MAX as u64) as i32,
+            highest_sequence: highest,
+            jitter: inbound.jitter.jitter_units(),
+            last_sr,
+            delay_since_last_sr,
+        })
+    };
+    let blocks: Vec<rtcp::ReportBlock> = block.into_iter().collect();
+    let packets_sent = session.stats.packets_sent.load(Ordering::Relaxed);
+    let report = if packets_sent > 0 {
+        let sender = rtcp::SenderInfo {
+            ntp_timestamp: rtcp::ntp_now(),
+            rtp_timestamp: session.stream.timestamp_at(Instant::now()),
+            packets: packets_sent as u32,
+            // Sayaç başlık dahil tutulur; yük, her pakette 12 baytlık sabit başlık düşülerek bulunur.
+            octets: session.stats.bytes_sent.load(Ordering::Relaxed).saturating_sub(12 * packets_sent) as u32,
+        };
+        writer.sender_report(ssrc, &sender, &blocks)?
+    } else {
+        writer.receiver_report(ssrc, &blocks)?
+    };
+    report.sdes_cname(ssrc, rtcp::cname())
+}
+
+/// Kapanışta uzak uca son raporla birlikte BYE gönderir (RFC 3550 6.6): SR (hiç paket
+/// gönderilmediyse RR), kilitli akış için alım raporu, SDES CNAME ve kapanış sebebi. Soket
+/// hatasında, kopan TCP bağlantısında ya da ulaşılamayan uçta gönderilemeyeceği için denenmez.
+async fn send_bye(session: &RtpSession, reason: TeardownReason) {
+    if matches!(reason, TeardownReason::SocketError | TeardownReason::PeerDisconnected | TeardownReason::RemoteUnreachable) {
+        return;
+    }
+    let Some(target_addr) = *session.remote_addr.lock().unwrap() else { return };
+    let mut wire = [0u8; 256];
+    let mut writer = rtcp::CompoundWriter::new(&mut wire);
+    let built = write_report(session, &mut writer)
+        .and_then(|writer| writer.bye(session.stream.ssrc, Some(reason.as_str())))
+        .map(|_| ());
+    if let Err(e) = built {
+        warn!(error = %e, "RTCP BYE oluşturulamadı");
+        return;
+    }
+    let len = writer.finish();
+    if let Err(e) = send_rtcp(session, &wire[..len], target_addr).await {
+        debug!(error = %e, "RTCP BYE gönderilemedi");
+    }
+}
+
+/// RTCP paketini ayrı RTCP portundan uzak ucun RTCP'sinin geldiği adrese, henüz gelmediyse
+/// `remote` RTP adresinin bir üst portuna (RFC 3550 11) gönderir. Ayrı port yoksa paket RTP
+/// portundan `remote`'a gider (RFC 5761). RTCP, RTP sayaçlarına ve bit hızına girmez.
+async fn send_rtcp(session: &RtpSession, packet: &[u8], remote: SocketAddr) -> io::Result<()> {
+    let Some(socket) = &session.rtcp else {
+        session.send_to(packet, remote).await?;
+        session.capture_sent(remote, packet);
+        return Ok(());
+    };
+    let learned = *session.rtcp_remote.lock().unwrap();
+    let target = learned.unwrap_or_else(|| SocketAddr::new(remote.ip(), remote.port().wrapping_add(1)));
+    socket.send_to(packet, target).await?;
+    if let Some(capture) = session.capture.get() {
+        capture.record(session.rtcp_local_addr(), target, packet);
+    }
+    Ok(())
+}
+
+async fn sleep_until_opt(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => sleep_until(deadline).await,
+        None => pending().await,
+    }
+}
+
+async fn recv_opt(socket: Option<&UdpSocket>, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+    match socket {
+        Some(socket) => socket.recv_from(buf).await,
+        None => pending().await,
+    }
+}
+
+async fn tick_opt(ticker: &mut Option<Interval>) {
+    match ticker {
+        Some(ticker) => { ticker.tick().await; }
+        None => pending().await,
+    }
+}
+
+#[cfg(test)]
+impl RtpSession {
+    /// Loopback'te bir oturum ve ona paket gönderecek karşı uç.
+    pub(crate) async fn for_test() -> (Arc<RtpSession>, UdpSocket) {
+        let sock = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = sock.local_addr().unwrap().port();
+        let peer = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        (Arc::new(RtpSession::new(port, &crate::codec::Pcmu, sock, "test-call")), peer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AnnouncementConfig;
+
+    fn scheduler() -> Arc<SendScheduler> {
+        Arc::new(SendScheduler::start(1, TimersConfig::default().ptime()))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn session_ends_after_media_timeout() {
+        let (session, peer) = RtpSession::for_test().await;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let timers = TimersConfig { media_timeout_s: 5, ..TimersConfig::default() };
+
+        peer.send_to(&[0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1], session.local_addr).await.unwrap();
+        let started = Instant::now();
+        rtp_session_handler(session, prompts, timers, QualityConfig::default(), sessions.clone(), scheduler()).await;
+
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+        assert!(sessions.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn max_duration_plays_the_farewell_when_the_remote_is_known() {
+        let mut config = AnnouncementConfig { max_duration: Some("farewell".to_string()), ..AnnouncementConfig::default() };
+        config.prompts.insert("farewell".to_string(), crate::config::PromptConfig {
+            path: "audio/processed/standard/welcome.wav".to_string(), gain_db: 0.0, looped: false, language: None, preload: true, languages: Default::default(),
+        });
+        let prompts = Arc::new(PromptLibrary::load(&config).unwrap());
+        let farewell = Duration::from_secs(prompts.max_duration().unwrap().source().unwrap().total_samples().unwrap() / 8000);
+        let timers = TimersConfig { max_duration_warning_s: 3, ..TimersConfig::default() };
+
+        for remote_known in [false, true] {
+            let sock = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let port = sock.local_addr().unwrap().port();
+            let session = Arc::new(RtpSession::new(port, &crate::codec::Pcmu, sock, "test-call").with_max_duration(Duration::from_secs(10)));
+            let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+            let peer = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            if remote_known {
+                peer.send_to(&[0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1], session.local_addr).await.unwrap();
+            }
+            let started = Instant::now();
+            rtp_session_handler(session.clone(), prompts.clone(), timers, QualityConfig::default(), sessions.clone(), scheduler()).await;
+
+            // Uzak uç yoksa tam süresinde kapanır; varsa veda anonsu bitince.
+            let elapsed = started.elapsed();
+            if remote_known {
+                assert!(elapsed >= Duration::from_secs(10) + farewell && elapsed < Duration::from_secs(10) + MAX_FAREWELL, "{:?}", elapsed);
+                assert_eq!(session.stats.announcements_started.load(Ordering::Relaxed), 1);
+            } else {
+                assert_eq!(elapsed, Duration::from_secs(10));
+            }
+            assert!(sessions.lock().unwrap().is_empty());
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn media_timeout_counts_from_last_packet() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = session.local_addr;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let timers = TimersConfig { media_timeout_s: 5, first_packet_timeout_s: 2, ..TimersConfig::default() };
+
+        let started = Instant::now();
+        let handler = tokio::spawn(rtp_session_handler(session.clone(), prompts, timers, QualityConfig::default(), sessions.clone(), scheduler()));
+        peer.send_to(&[0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1], target).await.unwrap();
+        sleep(Duration::from_secs(3)).await;
+        peer.send_to(&[0x80, 0, 0, 2, 0, 0, 0, 160, 0, 0, 0, 1], target).await.unwrap();
+        handler.await.unwrap();
+
+        assert_eq!(started.elapsed(), Duration::from_secs(3 + 5));
+        assert_eq!(session.stats.packets_received.load(Ordering::Relaxed), 2);
+        assert!(sessions.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hold_suspends_media_timeout_until_inbound_audio_is_expected_again() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = session.local_addr;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let timers = TimersConfig { media_timeout_s: 5, first_packet_timeout_s: 2, ..TimersConfig::default() };
+
+        let started = Instant::now();
+        let handler = tokio::spawn(rtp_session_handler(session.clone(), prompts, timers, QualityConfig::default(), sessions.clone(), scheduler()));
+        peer.send_to(&[0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1], target).await.unwrap();
+        sleep(Duration::from_secs(1)).await;
+        assert_eq!(session.set_direction(sdp::Direction::SendOnly), sdp::Direction::SendRecv);
+        sleep(Duration::from_secs(20)).await;
+        assert!(!handler.is_finished());
+        // recvonly gelen sesi yeniden bekler; süre yön değişiminden sayılır, son paketten değil.
+        session.set_direction(sdp::Direction::RecvOnly);
+        handler.await.unwrap();
+
+        assert_eq!(started.elapsed(), Duration::from_secs(1 + 20 + 5));
+        assert!(sessions.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn first_packet_timeout_fires_at_configured_instant() {
+        let (session, _peer) = RtpSession::for_test().await;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let timers = TimersConfig { media_timeout_s: 5, first_packet_timeout_s: 2, ..TimersConfig::default() };
+
+        rtp_session_handler(session.clone(), prompts, timers, QualityConfig::default(), sessions.clone(), scheduler()).await;
+        assert_eq!(session.allocated_at.elapsed(), Duration::from_secs(2));
+        assert_eq!(session.stats.inbound.lock().unwrap().first_packet_at, None);
+        assert!(sessions.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keepalive_is_comfort_noise_on_the_session_stream() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = session.local_addr;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let timers = TimersConfig { keepalive_interval_s: 1, ..TimersConfig::default() };
+        tokio::spawn(rtp_session_handler(session.clone(), prompts, timers, QualityConfig::default(), sessions, scheduler()));
+
+        peer.send_to(&[0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1], target).await.unwrap();
+        let mut buf = [0u8; 64];
+        let mut keepalives = Vec::new();
+        for _ in 0..2 {
+            let (len, _) = peer.recv_from(&mut buf).await.unwrap();
+            let packet = RtpPacketRef::parse(&buf[..len]).unwrap();
+            assert_eq!((packet.payload_type(), packet.payload(), packet.ssrc()), (COMFORT_NOISE_PT, &[127u8][..], session.stream.ssrc));
+            keepalives.push((packet.sequence(), packet.timestamp()));
+        }
+        assert_eq!(keepalives[1].0, keepalives[0].0.wrapping_add(1));
+        assert_eq!(keepalives[1].1.wrapping_sub(keepalives[0].1), 8000);
+        session.stop(TeardownReason::Shutdown);
+    }
+
+    #[tokio::test]
+    async fn force_stop_finalizes_recordings_of_stuck_sessions() {
+        let dir = std::env::temp_dir().join(format!("media-force-stop-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (session, _peer) = RtpSession::for_test().await;
+        let config = RecordingConfig { directory: dir.display().to_string(), ..RecordingConfig::default() };
+        let path = session.start_recording(&config, None, None, None).unwrap();
+        session.recording.lock().unwrap().as_ref().unwrap().record(&[0; 160]);
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+
+        // Dinleyici çalışmıyor; oturum kendiliğinden hiç kapanmaz.
+        assert_eq!(force_stop_sessions(&sessions).await, 1);
+        assert!(sessions.lock().unwrap().is_empty());
+        assert_eq!(hound::WavReader::open(&path).unwrap().duration(), 160);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn rtp(addr: &str, ssrc: u32) -> Source {
+        Source { addr: addr.parse().unwrap(), ssrc: Some(ssrc) }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reordering_and_stray_packets_do_not_relatch() {
+        let mut latch = Latch::default();
+        let now = Instant::now();
+        let a = rtp("10.0.0.1:4000", 1);
+        assert_eq!(latch.observe(a, Some(10), now, None), Latched::First);
+        for sequence in [12, 11, 11, 9, 40] {
+            assert_eq!(latch.observe(a, Some(sequence), now, None), Latched::Same);
+        }
+        // Başka kaynaktan tek paket veya ardışık olmayan paketler kabul edilmez.
+        let b = rtp("10.0.0.2:5000", 2);
+        assert_eq!(latch.observe(b, Some(100), now, None), Latched::Ignored);
+        assert_eq!(latch.observe(b, Some(102), now, None), Latched::Ignored);
+        assert_eq!(latch.observe(Source { ssrc: None, ..b }, None, now, None), Latched::Ignored);
+        assert_eq!(latch.observe(a, Some(41), now, None), Latched::Same);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn new_source_needs_two_sequential_packets() {
+        let mut latch = Latch::default();
+        let now = Instant::now();
+        let (a, b) = (rtp("10.0.0.1:4000", 1), rtp("10.0.0.2:5000", 2));
+        latch.observe(a, Some(10), now, None);
+        assert_eq!(latch.observe(b, Some(65535), now, None), Latched::Ignored);
+        // Eski bacağın araya düşen paketi adayı silmez.
+        assert_eq!(latch.observe(a, Some(11), now, None), Latched::Same);
+        let changed = latch.observe(b, Some(0), now, None);
+        assert_eq!(changed, Latched::Changed { previous: a, trigger: StreamChangeTrigger::NewSource, gap: Duration::ZERO });
+        // Eski bacağın gecikmiş paketi artık yabancıdır.
+        assert_eq!(latch.observe(a, Some(12), now, None), Latched::Ignored);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn address_change_and_silence_gap() {
+        let mut latch = Latch::default();
+        let gap = Some(Duration::from_secs(2));
+        let (a, moved) = (rtp("10.0.0.1:4000", 1), rtp("10.0.0.9:4002", 1));
+        latch.observe(a, Some(10), Instant::now(), gap);
+        assert_eq!(
+            latch.observe(moved, Some(11), Instant::now(), gap),
+            Latched::Changed { previous: a, trigger: StreamChangeTrigger::AddressChanged, gap: Duration::ZERO },
+        );
+        sleep(Duration::from_millis(1999)).await;
+        assert_eq!(latch.observe(moved, Some(12), Instant::now(), gap), Latched::Same);
+        sleep(Duration::from_secs(2)).await;
+        assert_eq!(
+            latch.observe(moved, Some(13), Instant::now(), gap),
+            Latched::Changed { previous: moved, trigger: StreamChangeTrigger::SilenceGap, gap: Duration::from_secs(2) },
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn new_stream_resets_inbound_stats_and_replays_welcome() {
+        let (session, first_leg) = RtpSession::for_test().await;
+        let second_leg = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = session.local_addr;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let mut config = AnnouncementConfig { welcome: Some("welcome".to_string()), replay_welcome: true, ..AnnouncementConfig::default() };
+        config.prompts.insert("welcome".to_string(), crate::config::PromptConfig {
+            path: "audio/processed/standard/welcome.wav".to_string(), gain_db: 0.0, looped: false, language: None, preload: true, languages: Default::default(),
+        });
+        let prompts = Arc::new(PromptLibrary::load(&config).unwrap());
+        tokio::spawn(rtp_session_handler(session.clone(), prompts, TimersConfig::default(), QualityConfig::default(), sessions, scheduler()));
+
+        let packet = |sequence: u16, ssrc: u32| {
+            let mut wire = [0u8; 12];
+            RtpPacket::new(0, sequence, sequence as u32 * 160, ssrc, &[]).write(&mut wire).unwrap();
+            wire
+        };
+        let mut buf = [0u8; 256];
+        first_leg.send_to(&packet(1, 1), target).await.unwrap();
+        first_leg.send_to(&packet(5, 1), target).await.unwrap();
+        first_leg.recv_from(&mut buf).await.unwrap();
+        assert_eq!(session.stats.inbound.lock().unwrap().sequence.lost(), 3);
+
+        for sequence in [700, 701, 702] {
+            second_leg.send_to(&packet(sequence, 2), target).await.unwrap();
+        }
+        let (len, _) = second_leg.recv_from(&mut buf).await.unwrap();
+        let received = RtpPacketRef::parse(&buf[..len]).unwrap();
+        assert_eq!((received.payload_type(), received.ssrc()), (0, session.stream.ssrc));
+        assert_eq!(*session.remote_addr.lock().unwrap(), Some(second_leg.local_addr().unwrap()));
+        {
+            let inbound = session.stats.inbound.lock().unwrap();
+            assert_eq!((inbound.sequence.expected(), inbound.sequence.lost()), (2, 0));
+        }
+        assert_eq!(session.stats.announcements_started.load(Ordering::Relaxed), 2);
+        session.stop(TeardownReason::Shutdown);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn malformed_packets_are_counted_and_kept_out_of_stream_stats() {
+        let (session, peer) = RtpSession::for_test().await;
+        let target = session.local_addr;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let timers = TimersConfig { media_timeout_s: 1, ..TimersConfig::default() };
+        let handler = tokio::spawn(rtp_session_handler(session.clone(), prompts, timers, QualityConfig::default(), sessions, scheduler()));
+
+        peer.send_to(&[0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1], target).await.unwrap();
+        // Uzantı uzunluğu paketi aşıyor, dolgu yükten büyük.
+        peer.send_to(&[0x90, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 1, 0xBE, 0xDE, 0, 4], target).await.unwrap();
+        peer.send_to(&[0xA0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 1, 0x20], target).await.unwrap();
+        peer.send_to(&[0x80, 0, 0, 2, 0, 0, 0, 160, 0, 0, 0, 1], target).await.unwrap();
+        handler.await.unwrap();
+
+        assert_eq!(session.stats.packets_received.load(Ordering::Relaxed), 4);
+        assert_eq!(session.stats.packets_malformed.load(Ordering::Relaxed), 2);
+        let inbound = session.stats.inbound.lock().unwrap();
+        assert_eq!((inbound.sequence.expected(), inbound.sequence.lost(), inbound.sequence.gaps()), (2, 0, 0));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unreadable_socket_ends_the_session_instead_of_spinning() {
+        use std::os::fd::AsRawFd;
+
+        let (session, peer) = RtpSession::for_test().await;
+        let target = session.local_addr;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let handler = tokio::spawn(rtp_session_handler(session.clone(), prompts, TimersConfig::default(), QualityConfig::default(), sessions.clone(), scheduler()));
+
+        // Tanımlayıcının yerine soket olmayan bir dosya konur; bağlı soket kopyada açık kalır ve
+        // gelen paket dinleyiciyi uyandırır, okuma ENOTSOCK ile döner.
+        let Transport::Udp(sock) = &*session.transport else { unreachable!() };
+        let fd = sock.as_raw_fd();
+        let not_a_socket = std::fs::File::open("/dev/null").unwrap();
+        let kept = unsafe { libc::dup(fd) };
+        assert!(kept >= 0 && unsafe { libc::dup2(not_a_socket.as_raw_fd(), fd) } == fd);
+        peer.send_to(&[0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1], target).await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), handler).await.expect("handler exits").unwrap();
+        assert!(sessions.lock().unwrap().is_empty());
+        unsafe { libc::close(kept) };
+
+        assert!(recv_error_is_transient(&io::Error::from(io::ErrorKind::ConnectionRefused)));
+        assert!(!recv_error_is_transient(&io::Error::from_raw_os_error(libc::EBADF)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sustained_inbound_flood_tears_down_or_blocks_the_source() {
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let timers = TimersConfig::default();
+        let limit = RateLimitConfig { inbound_packets_per_s: 50, inbound_burst: 10, flood_trip_s: 1, ..RateLimitConfig::default() };
+        let packet = [0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1];
+
+        for action in [FloodAction::Teardown, FloodAction::Block] {
+            let sock = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let port = sock.local_addr().unwrap().port();
+            let session = Arc::new(RtpSession::new(port, &crate::codec::Pcmu, sock, "test-call").with_inbound_limit(&RateLimitConfig { flood_action: action, ..limit }));
+            let target = session.local_addr;
+            let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+            let handler = tokio::spawn(rtp_session_handler(session.clone(), prompts.clone(), timers, QualityConfig::default(), sessions.clone(), scheduler()));
+            let (flooder, caller) = (UdpSocket::bind("127.0.0.1:0").await.unwrap(), UdpSocket::bind("127.0.0.1:0").await.unwrap());
+
+            // Saniyede 500 paketlik sel, yanında 20 ms'de bir meşru paket; sel 2 saniye sürer.
+            let started = Instant::now();
+            for tick in 0..200 {
+                for _ in 0..5 {
+                    flooder.send_to(&packet, target).await.unwrap();
+                }
+                if tick % 2 == 0 {
+                    caller.send_to(&packet, target).await.unwrap();
+                }
+                sleep(Duration::from_millis(10)).await;
+                if handler.is_finished() {
+                    break;
+                }
+            }
+            assert!(session.stats.packets_flood_dropped.load(Ordering::Relaxed) > 0);
+            if action == FloodAction::Teardown {
+                handler.await.unwrap();
+                let elapsed = started.elapsed();
+                assert!(elapsed >= Duration::from_secs(1) && elapsed < Duration::from_millis(1500), "{:?}", elapsed);
+                continue;
+            }
+
+            // Engellenen kaynağın paketleri sayılmaz; diğer kaynaklar sınırın altında geçer.
+            let received = session.stats.packets_received.load(Ordering::Relaxed);
+            for _ in 0..3 {
+                flooder.send_to(&packet, target).await.unwrap();
+                caller.send_to(&packet, target).await.unwrap();
+                sleep(Duration::from_millis(20)).await;
+            }
+            assert_eq!(session.stats.packets_received.load(Ordering::Relaxed), received + 3);
+            assert!(!handler.is_finished());
+            session.stop.notify_one();
+            handler.await.unwrap();
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn first_packet_starts_the_welcome_once() {
+        let (session, peer) = RtpSession::for_test().await;
+        let mut config = AnnouncementConfig { welcome: Some("welcome".to_string()), ..AnnouncementConfig::default() };
+        config.prompts.insert("welcome".to_string(), crate::config::PromptConfig {
+            path: "audio/processed/standard/welcome.wav".to_string(), gain_db: 0.0, looped: false, language: None, preload: true, languages: Default::default(),
+        });
+        let prompts = PromptLibrary::load(&config).unwrap();
+        let mut player = Player::new(session.codec(), Duration::from_millis(20));
+        let source = Source { addr: peer.local_addr().unwrap(), ssrc: Some(1) };
+        for _ in 0..2 {
+            stream_latched(&session, &prompts, &mut player, source, Latched::First, Instant::now());
+        }
+        assert_eq!(session.stats.announcements_started.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn red_redundancy_recovers_lost_packet() {
+        let sock = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = sock.local_addr().unwrap().port();
+        let red = RedConfig { payload_type: 99, generations: 1 };
+        let session = Arc::new(RtpSession::new(port, &crate::codec::Pcmu, sock, "test-call").with_red(red));
+        // Köprüdeki karşı bacağın uzak ucu kurtarılan çerçeveyi de duymalı.
+        let (callee, far) = RtpSession::for_test().await;
+        *callee.remote_addr.lock().unwrap() = Some(far.local_addr().unwrap());
+        bridge::bridge(&session, &callee, crate::config::DtmfMode::Relay).unwrap();
+        // Reaktör soketlerin yazılabilir olduğunu görsün; aktarım bloklamadan gönderir.
+        sleep(Duration::from_millis(1)).await;
+        let peer = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = session.local_addr;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let timers = TimersConfig { media_timeout_s: 1, ..TimersConfig::default() };
+        let handler = tokio::spawn(rtp_session_handler(session.clone(), prompts, timers, QualityConfig::default(), sessions, scheduler()));
+
+        let mut encoder = rfc2198::RedEncoder::new(1);
+        let mut payload = Vec::new();
+        let mut wire = [0u8; 64];
+        for sequence in 1..=4u16 {
+            encoder.encode(0, sequence as u32 * 160, &[sequence as u8; 4], &mut payload);
+            // 2 kaybolur; 3'teki yedeği onu kurtarır, 4'teki yedek zaten gelmiş 3'ü taşır.
+            if sequence == 2 {
+                continue;
+            }
+            let len = RtpPacket::new(99, sequence, sequence as u32 * 160, 1, &payload).write(&mut wire).unwrap();
+            peer.send_to(&wire[..len], target).await.unwrap();
+        }
+        handler.await.unwrap();
+
+        assert_eq!(session.stats.red_recovered.load(Ordering::Relaxed), 1);
+        let inbound = session.stats.inbound.lock().unwrap();
+        assert_eq!((inbound.sequence.expected(), inbound.sequence.lost()), (4, 0));
+        drop(inbound);
+        let mut relayed = Vec::new();
+        while let Ok(Ok((len, _))) = tokio::time::timeout(Duration::from_millis(100), far.recv_from(&mut wire)).await {
+            relayed.push(RtpPacketRef::parse(&wire[..len]).unwrap().payload()[0]);
+        }
+        assert_eq!(relayed, [1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn inbound_ced_is_recorded_on_the_session() {
+        let sock = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = sock.local_addr().unwrap().port();
+        let config = FaxDetectionConfig { stop_playback: true, ..FaxDetectionConfig::default() };
+        let session = Arc::new(RtpSession::new(port, &crate::codec::Pcmu, sock, "test-call").with_fax_detection(&config));
+        let peer = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = session.local_addr;
+        let sessions: ActiveSessions = Arc::new(Mutex::new(SessionRegistry::from_iter([session.clone()])));
+        let prompts = Arc::new(PromptLibrary::load(&AnnouncementConfig::default()).unwrap());
+        let timers = TimersConfig { media_timeout_s: 1, ..TimersConfig::default() };
+        let handler = tokio::spawn(rtp_session_handler(session.clone(), prompts, timers, QualityConfig::default(), sessions, scheduler()));
+
+        // 800 ms'lik 2100 Hz; CED 500 ms'de bildirilir.
+        let (mut payload, mut wire) = (Vec::new(), [0u8; 256]);
+        for packet in 0..40u16 {
+            let pcm: Vec<i16> = (0..160)
+                .map(|i| ((packet as usize * 160 + i) as f64 * 2100.0 * std::f64::consts::TAU / 8000.0).sin() * 8000.0)
+                .map(|sample| sample as i16)
+                .collect();
+            payload.clear();
+            crate::codec::Pcmu.encode(&pcm, &mut payload);
+            let len = RtpPacket::new(0, packet + 1, packet as u32 * 160, 1, &payload).write(&mut wire).unwrap();
+            peer.send_to(&wire[..len], target).await.unwrap();
+            sleep(Duration::from_millis(20)).await;
+        }
+        handler.await.unwrap();
+
+        assert_eq!(*session.fax_tone.lock().unwrap(), Some(FaxTone::Ced));
+    }
+
+    #[tokio::test]
+    async fn registry_finds_sessions_by_id_until_they_leave_their_port() {
+        let (a, _peer) = RtpSession::for_test().await;
+        let (b, _peer) = RtpSession::for_test().await;
+        let mut registry = SessionRegistry::from_iter([a.clone(), b.clone()]);
+        assert!(registry.by_id(&a.session_id).is_some_and(|found| Arc::ptr_eq(found, &a)));
+
+        registry.remove(a.port);
+        assert!(registry.by_id(&a.session_id).is_none());
+        // Porta yazılan yeni oturum eskisinin kimliğini dizinden düşürür.
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let c = Arc::new(RtpSession::new(b.port, &crate::codec::Pcmu, socket, "test-call"));
+        assert!(registry.insert(c.clone()).is_some_and(|replaced| Arc::ptr_eq(&replaced, &b)));
+        assert!(registry.by_id(&b.session_id).is_none());
+        assert!(registry.by_id(&c.session_id).is_some());
+        assert_eq!(registry.drain().count(), 1);
+        assert!(registry.by_id(&c.session_id).is_none());
+    }
+}
@@ -0,0 +1,562 @@
+// Ses kaynakları: oturumun tempolu göndericisine ptime'lık PCM çerçeveleri veren her şey
+// (WAV dosyası, döngülü dosya, ton, sessizlik, dışarıdan beslenen kanal) ve onları saran hız,
+// fon müziği ve art arda çalma katmanları.
+use std::collections::VecDeque;
+use std::f64::consts::TAU;
+use std::fs::File;
+use std::future::Future;
+use std::io::BufReader;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::config::PromptConfig;
+use crate::error::PlaybackError;
+
+/// `AudioSource::next_frame`'in döndüğü future; trait nesnesi olarak kurulabilmesi için kutulu.
+pub type FrameFuture<'a> = Pin<Box<dyn Future<Output = Result<bool, PlaybackError>> + Send + 'a>>;
+
+/// 8 kHz mono PCM üreten kaynak. Gönderici her ptime'da bir çerçeve ister.
+pub trait AudioSource: Send {
+    /// `frame`'i temizleyip en fazla `samples` örnekle doldurur. Kaynak bittiyse `false` döner;
+    /// son çerçeve kısa olabilir. Henüz hazır olmayan kaynak `frame`'i boş bırakıp `true` döner;
+    /// gönderici o tiki atlar.
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a>;
+
+    /// Biliniyorsa kaynağın toplam örnek sayısı (döngülü kaynaklarda bir tur).
+    fn total_samples(&self) -> Option<u64> {
+        None
+    }
+
+    /// Son çerçeveye sesi karışan kaynakların SSRC'leri; gönderici bunları CSRC listesine
+    /// yazar. Karışım olmayan kaynaklarda boştur ve paketler CC=0 gider.
+    fn contributors(&self) -> &[u32] {
+        &[]
+    }
+}
+
+/// Belleğe alınmış örnekler; önceden yüklenen anonslar için.
+pub struct SampleSource {
+    samples: Arc<Vec<i16>>,
+    position: usize,
+    looped: bool,
+}
+
+impl SampleSource {
+    pub fn new(samples: Arc<Vec<i16>>, looped: bool) -> Self {
+        SampleSource { samples, position: 0, looped }
+    }
+}
+
+impl AudioSource for SampleSource {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            frame.clear();
+            if self.position >= self.samples.len() {
+                if !self.looped || self.samples.is_empty() {
+                    return Ok(false);
+                }
+                self.position = 0;
+            }
+            let end = (self.position + samples).min(self.samples.len());
+            frame.extend_from_slice(&self.samples[self.position..end]);
+            self.position = end;
+            Ok(true)
+        })
+    }
+
+    fn total_samples(&self) -> Option<u64> {
+        Some(self.samples.len() as u64)
+    }
+}
+
+/// Diskten çerçeve çerçeve okunan WAV dosyası; dosyanın tamamı belleğe alınmaz.
+pub struct WavSource {
+    reader: hound::WavReader<BufReader<File>>,
+    path: String,
+    factor: Option<f32>,
+    looped: bool,
+    // Bozuk ya da kesik dosyada hatadan önce okunan örnekler bir çerçeve olarak verilir; hata
+    // sonraki çağrıda döner.
+    failed: Option<PlaybackError>,
+}
+
+impl WavSource {
+    /// Dosyayı açar ve formatını doğrular (16-bit, 8000 Hz, mono).
+    pub fn open(path: &str, gain_db: f32) -> Result<Self, PlaybackError> {
+        let reader = hound::WavReader::open(path)
+            .map_err(|source| PlaybackError::Open { path: path.to_string(), source })?;
+        let spec = reader.spec();
+        if spec.channels != 1 || spec.sample_rate != 8000 || spec.bits_per_sample != 16 {
+            return Err(PlaybackError::UnsupportedFormat { path: path.to_string(), spec: format!("{:?}", spec) });
+        }
+        let factor = (gain_db != 0.0).then(|| 10f32.powf(gain_db / 20.0));
+        Ok(WavSource { reader, path: path.to_string(), factor, looped: false, failed: None })
+    }
+
+    /// Sona gelince başa saran dosya; müzik bekletme (MOH) gibi kullanımlar için.
+    pub fn looping(path: &str, gain_db: f32) -> Result<Self, PlaybackError> {
+        Ok(WavSource { looped: true, ..Self::open(path, gain_db)? })
+    }
+
+    /// Anons config'indeki yol, kazanç ve döngü ayarıyla açar.
+    pub fn for_prompt(config: &PromptConfig) -> Result<Self, PlaybackError> {
+        if config.looped { Self::looping(&config.path, config.gain_db) } else { Self::open(&config.path, config.gain_db) }
+    }
+
+    /// Kalan bütün örnekleri okur; önceden yükleme ve başlangıç doğrulaması için.
+    pub fn read_to_end(mut self) -> Result<Vec<i16>, PlaybackError> {
+        let mut samples = Vec::with_capacity(self.reader.len() as usize);
+        self.read_into(usize::MAX, &mut samples)?;
+        Ok(samples)
+    }
+
+    fn read_into(&mut self, samples: usize, frame: &mut Vec<i16>) -> Result<(), PlaybackError> {
+        for sample in self.reader.samples::<i16>().take(samples) {
+            let sample = sample.map_err(|source| PlaybackError::Read { path: self.path.clone(), source })?;
+            frame.push(match self.factor {
+                Some(factor) => (sample as f32 * factor).clamp(i16::MIN as f32, i16::MAX as f32) as i16,
+                None => sample,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl AudioSource for WavSource {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            frame.clear();
+            if let Some(e) = self.failed.take() {
+                return Err(e);
+            }
+            let mut read = self.read_into(samples, frame);
+            if read.is_ok() && frame.is_empty() && self.looped && self.reader.duration() > 0 {
+                self.reader.seek(0)
+                    .map_err(|e| PlaybackError::Read { path: self.path.clone(), source: hound::Error::IoError(e) })?;
+                read = self.read_into(samples, frame);
+            }
+            match read {
+                Err(e) if frame.is_empty() => Err(e),
+                Err(e) => {
+                    self.failed = Some(e);
+                    Ok(true)
+                }
+                Ok(()) => Ok(!frame.is_empty()),
+            }
+        })
+    }
+
+    fn total_samples(&self) -> Option<u64> {
+        Some(self.reader.duration() as u64)
+    }
+}
+
+/// Bir veya birkaç frekansın toplamı (ör. 350 + 440 Hz çevir sesi). Süre verilmezse sonsuzdur.
+pub struct ToneSource {
+    frequencies: Vec<f64>,
+    amplitude: f64,
+    position: u64,
+    total: Option<u64>,
+}
+
+impl ToneSource {
+    /// `amplitude` tam ölçeğe oranla 0..=1; frekanslar eşit paylaşır.
+    pub fn new(frequencies: &[f64], amplitude: f64, duration_samples: Option<u64>) -> Self {
+        ToneSource { frequencies: frequencies.to_vec(), amplitude: amplitude.clamp(0.0, 1.0), position: 0, total: duration_samples }
+    }
+}
+
+impl AudioSource for ToneSource {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            frame.clear();
+            let remaining = self.total.map_or(samples as u64, |total| total.saturating_sub(self.position));
+            let count = remaining.min(samples as u64);
+            let scale = self.amplitude * i16::MAX as f64 / self.frequencies.len().max(1) as f64;
+            for n in self.position..self.position + count {
+                let t = n as f64 / 8000.0;
+                let value: f64 = self.frequencies.iter().map(|f| (TAU * f * t).sin()).sum();
+                frame.push((value * scale) as i16);
+            }
+            self.position += count;
+            Ok(count > 0)
+        })
+    }
+
+    fn total_samples(&self) -> Option<u64> {
+        self.total
+    }
+}
+
+/// Sıfır örnekler. Süre verilmezse sonsuzdur.
+pub struct SilenceSource {
+    remaining: Option<u64>,
+}
+
+impl SilenceSource {
+    pub fn new(duration_samples: Option<u64>) -> Self {
+        SilenceSource { remaining: duration_samples }
+    }
+}
+
+impl AudioSource for SilenceSource {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            frame.clear();
+            let count = self.remaining.map_or(samples as u64, |remaining| remaining.min(samples as u64));
+            if let Some(remaining) = &mut self.remaining {
+                *remaining -= count;
+            }
+            frame.resize(count as usize, 0);
+            Ok(count > 0)
+        })
+    }
+
+    fn total_samples(&self) -> Option<u64> {
+        self.remaining
+    }
+}
+
+/// Dışarıdan (ör. gRPC ile gelen ses, TTS) parça parça beslenen kaynak. Veri yetişmezse çerçeve
+/// sessizlikle tamamlanır, tempo bozulmaz; gönderen taraf kapanıp tampon boşalınca biter.
+pub struct ChannelSource {
+    receiver: mpsc::Receiver<Vec<i16>>,
+    pending: Vec<i16>,
+    closed: bool,
+}
+
+impl ChannelSource {
+    /// Kaynağı ve ona örnek gönderecek ucu döner; `capacity` parça cinsindendir.
+    pub fn new(capacity: usize) -> (mpsc::Sender<Vec<i16>>, Self) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (sender, ChannelSource { receiver, pending: Vec::new(), closed: false })
+    }
+}
+
+impl AudioSource for ChannelSource {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            frame.clear();
+            while self.pending.len() < samples && !self.closed {
+                match self.receiver.try_recv() {
+                    Ok(chunk) => self.pending.extend_from_slice(&chunk),
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => self.closed = true,
+                }
+            }
+            if self.closed && self.pending.is_empty() {
+                return Ok(false);
+            }
+            let take = self.pending.len().min(samples);
+            frame.extend(self.pending.drain(..take));
+            if !self.closed {
+                frame.resize(samples, 0);
+            }
+            Ok(true)
+        })
+    }
+}
+
+/// Başka bir kaynağın sesini `rate` kat hızla veren sarmalayıcı; örnekler doğrusal
+/// aradeğerlemeyle yeniden örneklenir, perde de hızla birlikte değişir. Çerçeve boyu ve tempo
+/// aynı kalır, yalnızca içeriğin süresi `1 / rate` ile ölçeklenir.
+pub struct RateSource {
+    inner: Box<dyn AudioSource>,
+    rate: f64,
+    // Kaynaktan okunmuş, henüz geçilmemiş örnekler; `position` bu tampona göre kesirli konumdur.
+    buffer: Vec<i16>,
+    position: f64,
+    input: Vec<i16>,
+    finished: bool,
+    failed: Option<PlaybackError>,
+}
+
+impl RateSource {
+    pub fn new(inner: Box<dyn AudioSource>, rate: f64) -> Self {
+        RateSource { inner, rate, buffer: Vec::new(), position: 0.0, input: Vec::new(), finished: false, failed: None }
+    }
+}
+
+impl AudioSource for RateSource {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            frame.clear();
+            if let Some(e) = self.failed.take() {
+                return Err(e);
+            }
+            while frame.len() < samples {
+                let index = self.position as usize;
+                // Aradeğerleme için konumun iki yanındaki örnek gerekir.
+                if index + 1 >= self.buffer.len() && !self.finished {
+                    match self.inner.next_frame(samples, &mut self.input).await {
+                        // Kaynak henüz hazır değil; bu tik elde olanla geçer.
+                        Ok(true) if self.input.is_empty() => break,
+                        Ok(true) => self.buffer.extend_from_slice(&self.input),
+                        Ok(false) => self.finished = true,
+                        Err(e) if frame.is_empty() => return Err(e),
+                        Err(e) => {
+                            self.failed = Some(e);
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                let Some(&a) = self.buffer.get(index) else { break };
+                let b = self.buffer.get(index + 1).copied().unwrap_or(a);
+                let fraction = self.position - index as f64;
+                frame.push((a as f64 + (b as f64 - a as f64) * fraction).round() as i16);
+                self.position += self.rate;
+            }
+            let consumed = (self.position as usize).min(self.buffer.len());
+            self.buffer.drain(..consumed);
+            self.position -= consumed as f64;
+            Ok(!(frame.is_empty() && self.finished))
+        })
+    }
+
+    fn total_samples(&self) -> Option<u64> {
+        self.inner.total_samples().map(|total| (total as f64 / self.rate).ceil() as u64)
+    }
+
+    fn contributors(&self) -> &[u32] {
+        self.inner.contributors()
+    }
+}
+
+/// Ön plandaki kaynağın altına döngülü bir fon müziği karıştırır. Örnekler `gain_db` kısılmış
+/// fonla toplanıp doyurularak kırpılır; ön plan henüz hazır değilken fon tek başına çalar, ön
+/// plan bitince fon da durur. Fon okunamazsa ön plan müziksiz sürer.
+pub struct BedSource {
+    foreground: Box<dyn AudioSource>,
+    bed: Option<Box<dyn AudioSource>>,
+    factor: f32,
+    // Fonun okunmuş, henüz karışmamış örnekleri; döngü başındaki kısa çerçeveler boşluk bırakmaz.
+    buffer: Vec<i16>,
+    input: Vec<i16>,
+}
+
+impl BedSource {
+    pub fn new(foreground: Box<dyn AudioSource>, bed: Box<dyn AudioSource>, gain_db: f32) -> Self {
+        BedSource { foreground, bed: Some(bed), factor: 10f32.powf(gain_db / 20.0), buffer: Vec::new(), input: Vec::new() }
+    }
+
+    /// Fon tamponunu en az `samples` örneğe tamamlamaya çalışır; fon hazır değilse eksik kalır.
+    async fn fill_bed(&mut self, samples: usize) {
+        while self.buffer.len() < samples {
+            let Some(bed) = self.bed.as_mut() else { return };
+            match bed.next_frame(samples - self.buffer.len(), &mut self.input).await {
+                Ok(true) if self.input.is_empty() => return,
+                Ok(true) => self.buffer.extend_from_slice(&self.input),
+                Ok(false) => self.bed = None,
+                Err(e) => {
+                    warn!(error = %e, "Fon müziği okunamadı, anons müziksiz sürüyor");
+                    self.bed = None;
+                }
+            }
+        }
+    }
+}
+
+impl AudioSource for BedSource {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            if !self.foreground.next_frame(samples, frame).await? {
+                return Ok(false);
+            }
+            // Ön plan hazır değilse bu tikte fon tek başına gider.
+            let length = if frame.is_empty() { samples } else { frame.len() };
+            self.fill_bed(length).await;
+            let bed = &self.buffer[..length.min(self.buffer.len())];
+            if frame.is_empty() {
+                frame.extend(bed.iter().map(|&sample| (sample as f32 * self.factor) as i16));
+            } else {
+                for (sample, &music) in frame.iter_mut().zip(bed) {
+                    *sample = (*sample as f32 + music as f32 * self.factor).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+                }
+            }
+            let used = bed.len();
+            self.buffer.drain(..used);
+            Ok(true)
+        })
+    }
+
+    fn total_samples(&self) -> Option<u64> {
+        self.foreground.total_samples()
+    }
+
+    fn contributors(&self) -> &[u32] {
+        self.foreground.contributors()
+    }
+}
+
+/// Kaynakları sırayla, tek bir kaynak gibi çalar. Bir kaynağın son kısa çerçevesi sonrakinin
+/// başıyla tamamlanır; parçalar arasında boşluk ya da kısa paket olmaz, yalnızca en son çerçeve
+/// kısa olabilir. Bir parça okunamazsa oynatma o hatayla biter.
+pub struct SequenceSource {
+    sources: VecDeque<Box<dyn AudioSource>>,
+    input: Vec<i16>,
+    failed: Option<PlaybackError>,
+}
+
+impl SequenceSource {
+    pub fn new(sources: Vec<Box<dyn AudioSource>>) -> Self {
+        SequenceSource { sources: sources.into(), input: Vec::new(), failed: None }
+    }
+}
+
+impl AudioSource for SequenceSource {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            frame.clear();
+            if let Some(e) = self.failed.take() {
+                return Err(e);
+            }
+            while frame.len() < samples {
+                let Some(source) = self.sources.front_mut() else { break };
+                match source.next_frame(samples - frame.len(), &mut self.input).await {
+                    // Parça henüz hazır değil (ör. indiriliyor); bu tik elde olanla geçer.
+                    Ok(true) if self.input.is_empty() => break,
+                    Ok(true) => frame.extend_from_slice(&self.input),
+                    Ok(false) => { self.sources.pop_front(); }
+                    Err(e) if frame.is_empty() => return Err(e),
+                    Err(e) => {
+                        self.failed = Some(e);
+                        break;
+                    }
+                }
+            }
+            Ok(!(frame.is_empty() && self.sources.is_empty()))
+        })
+    }
+
+    fn total_samples(&self) -> Option<u64> {
+        self.sources.iter().map(|source| source.total_samples()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn drain(source: &mut dyn AudioSource, samples: usize) -> Vec<usize> {
+        let mut frame = Vec::new();
+        let mut lengths = Vec::new();
+        while source.next_frame(samples, &mut frame).await.unwrap() {
+            lengths.push(frame.len());
+        }
+        lengths
+    }
+
+    #[tokio::test]
+    async fn finite_sources_end_with_a_short_frame() {
+        assert_eq!(drain(&mut SampleSource::new(Arc::new(vec![1; 400]), false), 160).await, [160, 160, 80]);
+        assert_eq!(drain(&mut SilenceSource::new(Some(320)), 160).await, [160, 160]);
+        assert_eq!(drain(&mut ToneSource::new(&[350.0, 440.0], 0.5, Some(200)), 160).await, [160, 40]);
+    }
+
+    #[tokio::test]
+    async fn streamed_wav_matches_preloaded_samples() {
+        let config = PromptConfig {
+            path: "audio/processed/standard/welcome.wav".to_string(),
+            gain_db: -6.0,
+            looped: false,
+            language: None,
+            preload: false,
+            languages: Default::default(),
+        };
+        let mut streamed = WavSource::for_prompt(&config).unwrap();
+        let total = streamed.total_samples().unwrap() as usize;
+        let mut frame = Vec::new();
+        let mut samples = Vec::new();
+        while streamed.next_frame(160, &mut frame).await.unwrap() {
+            samples.extend_from_slice(&frame);
+        }
+        assert_eq!(samples.len(), total);
+        assert_eq!(samples, WavSource::for_prompt(&config).unwrap().read_to_end().unwrap());
+    }
+
+    #[tokio::test]
+    async fn looping_sources_wrap_around() {
+        let mut source = SampleSource::new(Arc::new(vec![7; 200]), true);
+        let mut frame = Vec::new();
+        for expected in [160, 40, 160, 40] {
+            assert!(source.next_frame(160, &mut frame).await.unwrap());
+            assert_eq!(frame.len(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn channel_source_pads_underruns_and_ends_when_closed() {
+        let (sender, mut source) = ChannelSource::new(4);
+        let mut frame = Vec::new();
+        sender.send(vec![5; 100]).await.unwrap();
+        assert!(source.next_frame(160, &mut frame).await.unwrap());
+        assert_eq!((frame.len(), frame[99], frame[100]), (160, 5, 0));
+
+        sender.send(vec![9; 50]).await.unwrap();
+        drop(sender);
+        assert!(source.next_frame(160, &mut frame).await.unwrap());
+        assert_eq!(frame, vec![9; 50]);
+        assert!(!source.next_frame(160, &mut frame).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rate_scales_content_duration_not_frame_size() {
+        let ramp: Vec<i16> = (0..1600).map(|i| i as i16).collect();
+        let mut slow = RateSource::new(Box::new(SampleSource::new(Arc::new(ramp.clone()), false)), 0.5);
+        assert_eq!(slow.total_samples(), Some(3200));
+        assert_eq!(drain(&mut slow, 160).await, vec![160; 20]);
+
+        let mut fast = RateSource::new(Box::new(SampleSource::new(Arc::new(ramp), false)), 2.0);
+        assert_eq!(fast.total_samples(), Some(800));
+        let mut frame = Vec::new();
+        assert!(fast.next_frame(160, &mut frame).await.unwrap());
+        // İki kat hızda rampanın her ikinci örneği gelir.
+        assert_eq!(&frame[..4], &[0, 2, 4, 6]);
+        assert_eq!(drain(&mut fast, 160).await, vec![160; 4]);
+    }
+
+    #[tokio::test]
+    async fn sequence_joins_segments_without_short_frames() {
+        let segment = |value: i16, len: usize| Box::new(SampleSource::new(Arc::new(vec![value; len]), false)) as Box<dyn AudioSource>;
+        let mut sequence = SequenceSource::new(vec![segment(1, 200), segment(2, 100), segment(3, 90)]);
+        assert_eq!(sequence.total_samples(), Some(390));
+        let mut frame = Vec::new();
+        assert!(sequence.next_frame(160, &mut frame).await.unwrap());
+        assert_eq!(frame, vec![1; 160]);
+        // İkinci çerçeve ilk parçanın kalanı, ikinci parçanın tamamı ve üçüncünün başıdır.
+        assert!(sequence.next_frame(160, &mut frame).await.unwrap());
+        assert_eq!(frame, [vec![1; 40], vec![2; 100], vec![3; 20]].concat());
+        assert_eq!(drain(&mut sequence, 160).await, [70]);
+        assert_eq!(SequenceSource::new(vec![segment(1, 1), Box::new(ToneSource::new(&[440.0], 0.5, None))]).total_samples(), None);
+    }
+
+    #[tokio::test]
+    async fn bed_mixes_under_a_streamed_prompt_and_stops_with_it() {
+        let path = std::env::temp_dir().join(format!("media-bed-{}.wav", std::process::id()));
+        let spec = hound::WavSpec { channels: 1, sample_rate: 8000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..1600 {
+            writer.write_sample(if i < 1440 { 8000i16 } else { 32000 }).unwrap();
+        }
+        writer.finalize().unwrap();
+        // Fonun ilk yarı turu sessiz: o çerçevelerde yalnızca ön plan duyulur.
+        let music = Arc::new([vec![0; 160], vec![4000; 160]].concat());
+        let foreground = WavSource::open(&path.display().to_string(), 0.0).unwrap();
+        let mut mixed = BedSource::new(Box::new(foreground), Box::new(SampleSource::new(music, true)), -20.0 * 2f32.log10());
+
+        let mut frame = Vec::new();
+        let mut levels = Vec::new();
+        while mixed.next_frame(160, &mut frame).await.unwrap() {
+            assert!(frame.iter().all(|&sample| sample == frame[0]));
+            levels.push(frame[0]);
+        }
+        // -6 dB'lik fon yarı genlikle eklenir; taşan toplam kırpılır; ön plan bitince fon da biter.
+        assert_eq!(levels, [8000, 10000, 8000, 10000, 8000, 10000, 8000, 10000, 8000, i16::MAX]);
+        assert_eq!(mixed.total_samples(), Some(1600));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
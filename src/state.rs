@@ -0,0 +1,206 @@
+// Düğümün iç durumunun anlık görüntüsü: DumpState RPC'si döner, SIGUSR1 `state_dump.directory`'ye
+// yazar. Medya duraklatılmaz: oturum tablosu yalnızca oturumlar kopyalanırken kilitlenir, her oturum
+// sonra kendi kısa kilitleriyle tek tek okunur. Görüntü bu yüzden oturumlar arasında tam eşzamanlı
+// değildir; tek bir oturumun alanları da ayrı kilitlerden okunur.
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tokio::time::Instant;
+
+use crate::announcement::PromptLibrary;
+use crate::build_info;
+use crate::cdr;
+use crate::config::Settings;
+use crate::health::Health;
+use crate::labels::Labels;
+use crate::metrics;
+use crate::prompt_cache::CacheStats;
+use crate::session::{ActiveSessions, RtpSession};
+
+#[derive(Debug, Serialize)]
+pub struct StateDump<'a> {
+    pub generated_at_ms: u64,
+    pub version: &'static str,
+    pub git_commit: &'static str,
+    /// Gömülü kullanımda (sağlık durumu tutulmuyorsa) yok.
+    pub health: Option<HealthState>,
+    pub port_pool: PortPoolState,
+    /// Porta göre sıralı.
+    pub sessions: Vec<SessionState>,
+    pub prompts: Vec<PromptState>,
+    pub announcement_cache: CacheStats,
+    /// URL'li anons önbelleği kapalıysa yok.
+    pub url_cache: Option<UrlCacheState>,
+    /// Geçerli konfigürasyon; gizli değerler `<redacted>` olarak yazılır.
+    pub config: &'a Settings,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthState {
+    pub state: &'static str,
+    pub grpc_serving: bool,
+    pub draining: bool,
+    pub readiness_failures: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortPoolState {
+    pub min_port: u16,
+    pub max_port: u16,
+    pub size: i64,
+    pub active_sessions: usize,
+    pub overflow_sessions: i64,
+    pub tenants: Vec<TenantPoolState>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TenantPoolState {
+    pub name: String,
+    pub min_port: u16,
+    pub max_port: u16,
+    pub active_sessions: i64,
+    pub max_sessions: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionState {
+    pub port: u16,
+    pub session_id: String,
+    pub call_id: String,
+    pub request_id: String,
+    pub tenant: Option<&'static str>,
+    pub codec: &'static str,
+    pub transport: String,
+    pub local_addr: String,
+    pub remote_addr: Option<String>,
+    pub ssrc: u32,
+    pub remote_ssrc: Option<u32>,
+    pub language: Option<String>,
+    pub labels: Labels,
+    pub overflow: bool,
+    /// Hedefi tahsiste verilen, yalnızca gönderen oturum.
+    pub send_only: bool,
+    pub age_ms: u64,
+    /// Son gelen paketten beri; hiç paket gelmediyse yok.
+    pub idle_ms: Option<u64>,
+    /// En uzun oturum süresinin dolmasına kalan; sınırsızsa yok.
+    pub expires_in_ms: Option<u64>,
+    /// Kapanış istendiyse sebebi; oturum dinleyicisi henüz kapatmamış.
+    pub stopping: Option<&'static str>,
+    pub packets_sent: u64,
+    pub bytes_sent: u64,
+    pub packets_received: u64,
+    pub bytes_received: u64,
+    pub packets_malformed: u64,
+    pub packets_lost: u64,
+    pub jitter_ms: f64,
+    pub send_bitrate_bps: f64,
+    pub receive_bitrate_bps: f64,
+    pub announcements_started: u64,
+    pub announcements_failed: u64,
+    /// O an çalan anons.
+    pub playing: Option<String>,
+    /// Süren kaydın dosyaları; kayıt yoksa boş.
+    pub recording: Vec<String>,
+    pub recorded: Vec<String>,
+    /// Köprülüyse karşı bacağın portu.
+    pub bridged_to: Option<u16>,
+    /// Oturumu dinleyen dinleme bacaklarının portları.
+    pub taps: Vec<u16>,
+    /// Dinleme bacağıysa dinlediği oturumun portu.
+    pub tapping: Option<u16>,
+    pub capture: bool,
+    pub audio_dump: bool,
+    pub inbound_muted: bool,
+    pub outbound_muted: bool,
+    /// SetDirection ile ayarlanan medya yönü.
+    pub direction: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PromptState {
+    pub name: String,
+    pub path: String,
+    pub languages: Vec<String>,
+    pub cached: bool,
+    pub bytes: Option<u64>,
+    /// Dosya okunamadıysa sebebi.
+    pub error: Option<String>,
+    pub plays: u64,
+    pub active_playbacks: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UrlCacheState {
+    pub directory: String,
+    pub max_bytes: u64,
+    pub bytes: u64,
+    pub entries: Vec<CachedUrl>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CachedUrl {
+    pub url: String,
+    /// Diskteki kopya; henüz indirilmediyse yok.
+    pub file: Option<String>,
+    pub bytes: Option<u64>,
+    pub etag: Option<String>,
+    /// Sunucuya son sorulmasından beri; bu çalışmada sorulmadıysa yok.
+    pub checked_ms_ago: Option<u64>,
+    /// İndirme ya da doğrulama sürüyor; diğer alanlar okunmadı.
+    pub fetching: bool,
+}
+
+/// Görüntüyü alır. Oturum tablosunun kilidi yalnızca oturumlar kopyalanırken tutulur.
+pub fn snapshot<'a>(active_sessions: &ActiveSessions, prompts: &PromptLibrary, settings: &'a Settings, health: Option<&Health>) -> StateDump<'a> {
+    let mut sessions: Vec<Arc<RtpSession>> = active_sessions.lock().unwrap().values().cloned().collect();
+    sessions.sort_by_key(|session| session.port);
+    let now = Instant::now();
+    let metrics = metrics::get();
+    StateDump {
+        generated_at_ms: cdr::unix_millis(SystemTime::now()),
+        version: build_info::VERSION,
+        git_commit: build_info::GIT_COMMIT,
+        health: health.map(|health| HealthState {
+            state: health.state(),
+            grpc_serving: health.is_grpc_serving(),
+            draining: health.is_draining(),
+            readiness_failures: health.readiness_failures(),
+        }),
+        port_pool: PortPoolState {
+            min_port: settings.rtp.min_port,
+            max_port: settings.rtp.max_port,
+            size: metrics.port_pool_size.get(),
+            active_sessions: sessions.len(),
+            overflow_sessions: metrics.overflow_sessions.get(),
+            tenants: settings.tenants.iter()
+                .map(|tenant| TenantPoolState {
+                    name: tenant.name.clone(), min_port: tenant.min_port, max_port: tenant.max_port,
+                    active_sessions: metrics.tenant(&tenant.name).active_sessions.get(), max_sessions: tenant.max_sessions,
+                })
+                .collect(),
+        },
+        sessions: sessions.iter().map(|session| session.state(now)).collect(),
+        prompts: prompts.list().into_iter()
+            .map(|info| PromptState {
+                name: info.name, path: info.path, languages: info.languages, cached: info.cached,
+                bytes: info.file.as_ref().ok().map(|file| file.size_bytes), error: info.file.err(),
+                plays: info.plays, active_playbacks: info.active_playbacks,
+            })
+            .collect(),
+        announcement_cache: prompts.cache_stats(),
+        url_cache: prompts.url_cache().map(|cache| cache.state()),
+        config: settings,
+    }
+}
+
+/// Görüntüyü `dir` altına `state-<unix ms>.json` olarak yazar ve dosyanın yolunu döner.
+pub fn write(dir: &Path, dump: &StateDump) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("state-{}.json", dump.generated_at_ms));
+    std::fs::write(&path, serde_json::to_vec_pretty(dump)?)?;
+    Ok(path)
+}
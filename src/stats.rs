@@ -0,0 +1,495 @@
+// Oturum başına trafik sayaçları, hat hızı ve gelen RTP akışının kalite ölçümleri.
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::audio_level::AudioLevel;
+use crate::audit::{PlaybackFailure, SendFailure};
+use crate::quality::{self, BurstTracker};
+
+/// Oturum boyunca yaşayan sayaçlar; birden fazla görev (dinleyici, anons, keepalive) günceller.
+#[derive(Debug, Default)]
+pub struct SessionStats {
+    pub packets_sent: AtomicU64,
+    pub bytes_sent: AtomicU64,
+    pub packets_received: AtomicU64,
+    pub bytes_received: AtomicU64,
+    // RTP başlığı ayrıştırılamayan (taşan CSRC/uzantı, geçersiz dolgu...) gelen paketler.
+    pub packets_malformed: AtomicU64,
+    // Gelen paket sınırını aştığı için ayrıştırılmadan atılanlar; diğer sayaçlara girmez.
+    pub packets_flood_dropped: AtomicU64,
+    // Kendi giden paketlerimizin geri yansıyanları; yalnızca bu sayaca girer, akışı kilitlemez.
+    pub packets_reflected: AtomicU64,
+    pub announcements_started: AtomicU64,
+    pub announcements_failed: AtomicU64,
+    // Son başarısız anonsun sebebi; çağıran istediği anonsun çalmadığını buradan öğrenir.
+    pub playback_failure: Mutex<Option<PlaybackFailure>>,
+    // Son soket gönderim hatasının sınıfı; oturum özetine yazılır.
+    pub send_error: Mutex<Option<SendFailure>>,
+    // Son başarılı gönderimden sonraki ilk ve son gönderim hatası; tek yönlü ses bekçisi okur.
+    pub send_failures: Mutex<Option<(Instant, Instant)>>,
+    // O an çalan anonsun adı; yalnızca DumpState okur.
+    pub playing: Mutex<Option<String>>,
+    // Kaybolup sonraki paketlerin RED yedeğinden kurtarılan çerçeveler.
+    pub red_recovered: AtomicU64,
+    // Gelen RFC 4733 olaylarından sayılan tuşlar; yeni akışta sıfırlanmaz.
+    pub dtmf_digits: AtomicU64,
+    // Yalnızca dinleyici görevi yazar; kilit pratikte hiç çekişmez.
+    pub inbound: Mutex<InboundStats>,
+    // IP/UDP (ya da TCP) başlıkları dahil hat hızı.
+    pub send_bitrate: Mutex<Bitrate>,
+    pub receive_bitrate: Mutex<Bitrate>,
+    // Uzak ucun giden akışımız için gönderdiği son alım raporu.
+    pub remote_report: Mutex<Option<RemoteReport>>,
+}
+
+impl SessionStats {
+    /// `bytes` RTP paketinin boyu, `wire_bytes` hatta kapladığı yer (alt katman başlıkları dahil).
+    pub fn packet_sent(&self, bytes: usize, wire_bytes: usize, now: Instant) {
+        self.packets_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.send_bitrate.lock().unwrap().record(wire_bytes, now);
+    }
+
+    pub fn playback_failed(&self, failure: PlaybackFailure) {
+        self.announcements_failed.fetch_add(1, Ordering::Relaxed);
+        *self.playback_failure.lock().unwrap() = Some(failure);
+    }
+
+    pub fn packet_received(&self, bytes: usize, wire_bytes: usize, now: Instant) {
+        self.packets_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.receive_bitrate.lock().unwrap().record(wire_bytes, now);
+    }
+}
+
+// Hat hızı ortalamasının zaman sabiti; daha eski trafik e^-1 ağırlıkla kalır.
+const BITRATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Üstel kayan ortalamayla hat hızı (bit/s). Her paket ortalamaya `bit / BITRATE_WINDOW` ekler ve
+/// ortalama paketler arasında geçen süreyle söner; sabit hızlı bir akışta gerçek hıza yakınsar,
+/// akış kesilince sıfıra iner. Ortalama doğrusal olduğundan oturumların toplamı düğüm hızıdır.
+#[derive(Debug, Default)]
+pub struct Bitrate {
+    rate_bps: f64,
+    updated_at: Option<Instant>,
+    peak_bps: f64,
+    wire_bytes: u64,
+}
+
+impl Bitrate {
+    pub const fn new() -> Self {
+        Bitrate { rate_bps: 0.0, updated_at: None, peak_bps: 0.0, wire_bytes: 0 }
+    }
+
+    pub fn record(&mut self, wire_bytes: usize, now: Instant) {
+        self.rate_bps = self.current_bps(now) + (wire_bytes * 8) as f64 / BITRATE_WINDOW.as_secs_f64();
+        self.updated_at = Some(now);
+        self.peak_bps = self.peak_bps.max(self.rate_bps);
+        self.wire_bytes += wire_bytes as u64;
+    }
+
+    /// `now` anındaki ortalama; son paketten beri geçen süre kadar sönmüş.
+    pub fn current_bps(&self, now: Instant) -> f64 {
+        match self.updated_at {
+            Some(at) => self.rate_bps * (-now.saturating_duration_since(at).as_secs_f64() / BITRATE_WINDOW.as_secs_f64()).exp(),
+            None => 0.0,
+        }
+    }
+
+    /// Son paketin anı; hiç paket yoksa `None`.
+    pub fn updated_at(&self) -> Option<Instant> {
+        self.updated_at
+    }
+
+    /// Şimdiye kadarki en yüksek ortalama.
+    pub fn peak_bps(&self) -> f64 {
+        self.peak_bps
+    }
+
+    /// `elapsed` boyunca taşınan bütün baytların ortalaması.
+    pub fn average_bps(&self, elapsed: Duration) -> f64 {
+        match elapsed.as_secs_f64() {
+            secs if secs > 0.0 => (self.wire_bytes * 8) as f64 / secs,
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InboundStats {
+    pub first_packet_at: Option<Instant>,
+    // Kilitlenen akışın SSRC'si.
+    pub remote_ssrc: Option<u32>,
+    // Uzantı anlaşıldıysa kilitli akışın son bildirdiği RFC 6464 ses seviyesi.
+    pub audio_level: Option<AudioLevel>,
+    pub sequence: SequenceTracker,
+    pub jitter: JitterEstimator,
+    pub skew: SkewEstimator,
+    pub bursts: BurstTracker,
+    // Son RFC 4733 olayının zaman damgası; bir tuşun bütün paketleri aynı damgayı taşır.
+    last_dtmf_timestamp: Option<u32>,
+    // Bitişi görülen son olayın zaman damgası; yinelenen bitiş paketleri tekrar bildirilmez.
+    last_dtmf_end: Option<u32>,
+    // Kilitli akışın son SR'ının NTP zamanı (orta 32 bit) ve geliş anı; alım raporumuzun LSR ve
+    // DLSR alanları.
+    pub last_sr: Option<(u32, Instant)>,
+    // Son gönderilen alım raporundaki beklenen ve kaybolan paket sayıları.
+    reported: (u64, u64),
+}
+
+impl InboundStats {
+    /// Yeni gelen akış için sıra, jitter ve saat sapması ölçümlerini sıfırlar.
+    pub fn reset_stream(&mut self) {
+        *self = InboundStats { first_packet_at: self.first_packet_at, ..InboundStats::default() };
+    }
+
+    /// Gelen telephone-event paketi yeni bir tuşa aitse `true`; aynı tuşun devam ve yinelenen
+    /// bitiş paketleri tek sayılır.
+    pub fn dtmf_event(&mut self, timestamp: u32) -> bool {
+        self.last_dtmf_timestamp.replace(timestamp) != Some(timestamp)
+    }
+
+    /// Olayın ilk bitiş paketinde `true`; RFC 4733'ün yinelediği bitişler tek sayılır.
+    pub fn dtmf_end(&mut self, timestamp: u32) -> bool {
+        self.last_dtmf_end.replace(timestamp) != Some(timestamp)
+    }
+
+    /// Bir önceki rapordan beri kaybolanların 256 üzerinden oranı (RFC 3550 A.3); sonraki rapor
+    /// bu andan sayılır. Tekrar eden paketler kaybı aştıysa 0.
+    pub fn fraction_lost_since_report(&mut self) -> u8 {
+        let (expected, lost) = (self.sequence.expected(), self.sequence.lost());
+        let (reported_expected, reported_lost) = std::mem::replace(&mut self.reported, (expected, lost));
+        let interval = expected.saturating_sub(reported_expected);
+        match lost.saturating_sub(reported_lost) {
+            lost if interval == 0 || lost == 0 => 0,
+            lost => (lost * 256 / interval).min(255) as u8,
+        }
+    }
+
+    /// Sıra ve jitter ölçümlerinden E-modeli tahmini. Jitter tamponu olmadığından atılan paket
+    /// yoktur; tampon gecikmesi jitter'ın iki katı, ağ gecikmesi RTT ölçülene kadar bilinmez ve
+    /// yalnızca paketleme süresi eklenir. Henüz paket yoksa `None`.
+    pub fn quality(&self, clock_rate: u32, ptime: Duration) -> Option<CallQuality> {
+        let expected = self.sequence.expected();
+        if expected == 0 {
+            return None;
+        }
+        let loss = self.sequence.lost() as f64 / expected as f64;
+        let delay_ms = 2.0 * self.jitter.jitter_ms(clock_rate) + ptime.as_secs_f64() * 1000.0;
+        let r_factor = quality::r_factor(loss, self.bursts.burst_ratio(), delay_ms);
+        Some(CallQuality { r_factor, mos: quality::mos(r_factor) })
+    }
+}
+
+/// Uzak ucun giden akışımız için gönderdiği alım raporu bloğu (SR ya da RR içinde).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RemoteReport {
+    pub received_at: Instant,
+    /// Uzak ucun bir önceki raporundan beri kaybolanların oranı, 256 üzerinden.
+    pub fraction_lost: u8,
+    pub cumulative_lost: i32,
+    /// Codec saat birimlerinde.
+    pub jitter: u32,
+    /// Blok SR'ımıza dayanıyorsa gidiş dönüş süresi.
+    pub round_trip: Option<Duration>,
+}
+
+/// Gelen akış için tahmini konuşma kalitesi.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallQuality {
+    pub r_factor: f64,
+    pub mos: f64,
+}
+
+/// RFC 3550 A.1'deki gibi sarmayı hesaba katarak beklenen, kaybolan, tekrar eden paketleri ve
+/// sıra boşluklarını sayar. Son 64 sıra numarası bir bit penceresinde tutulur.
+#[derive(Debug, Default)]
+pub struct SequenceTracker {
+    base: Option<i64>,
+    // En yüksek genişletilmiş (sarma dahil) sıra numarası.
+    highest: i64,
+    window: u64,
+    received: u64,
+    duplicates: u64,
+    gaps: u64,
+}
+
+impl SequenceTracker {
+    /// Paketi kaydeder ve en yüksek sıra numarasıyla bu paket arasında atlanan paket sayısını
+    /// döner (sırayla gelen veya geç kalan paketlerde 0).
+    pub fn observe(&mut self, seq: u16) -> u64 {
+        self.received += 1;
+        if self.base.is_none() {
+            self.base = Some(seq as i64);
+            self.highest = seq as i64;
+            self.window = 1;
+            return 0;
+        }
+        // En yüksek değere en yakın genişletilmiş değer.
+        let ext = self.highest + seq.wrapping_sub(self.highest as u16) as i16 as i64;
+        if ext > self.highest {
+            let advance = ext - self.highest;
+            if advance > 1 {
+                self.gaps += 1;
+            }
+            self.window = if advance >= 64 { 0 } else { self.window << advance };
+            self.window |= 1;
+            self.highest = ext;
+            return (advance - 1) as u64;
+        } else {
+            let offset = self.highest - ext;
+            if offset < 64 {
+                let bit = 1u64 << offset;
+                if self.window & bit != 0 {
+                    self.duplicates += 1;
+                } else {
+                    self.window |= bit;
+                }
+            }
+        }
+        0
+    }
+
+    /// Kayıp sayılan bir paket başka yoldan (ör. RED yedeği) elde edildi. Pencere içinde ve daha
+    /// önce görülmemişse alınmış sayılır ve `true` döner.
+    pub fn recover(&mut self, seq: u16) -> bool {
+        if self.base.is_none() {
+            return false;
+        }
+        let ext = self.highest + seq.wrapping_sub(self.highest as u16) as i16 as i64;
+        let offset = self.highest - ext;
+        if !(0..64).contains(&offset) || self.window & (1 << offset) != 0 {
+            return false;
+        }
+        self.window |= 1 << offset;
+        self.received += 1;
+        true
+    }
+
+    pub fn expected(&self) -> u64 {
+        match self.base {
+            Some(base) => (self.highest - base + 1) as u64,
+            None => 0,
+        }
+    }
+
+    /// Tekrar eden paketler düşüldükten sonra kaybolan paket sayısı.
+    pub fn lost(&self) -> u64 {
+        self.expected().saturating_sub(self.received - self.duplicates)
+    }
+
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+
+    /// RTCP alım raporundaki genişletilmiş en yüksek sıra numarası (üst 16 bit sarma sayısı);
+    /// paket yoksa `None`.
+    pub fn highest(&self) -> Option<u32> {
+        self.base.map(|_| self.highest as u32)
+    }
+
+    pub fn gaps(&self) -> u64 {
+        self.gaps
+    }
+}
+
+/// RFC 3550 6.4.1 geliş aralığı jitter'ı, codec saat birimlerinde.
+#[derive(Debug, Default)]
+pub struct JitterEstimator {
+    last_transit: Option<u32>,
+    jitter: f64,
+}
+
+impl JitterEstimator {
+    /// `arrival`, oturum başlangıcından beri geçen süredir.
+    pub fn observe(&mut self, arrival: std::time::Duration, rtp_timestamp: u32, clock_rate: u32) {
+        let arrival_units = (arrival.as_secs_f64() * clock_rate as f64) as u64 as u32;
+        let transit = arrival_units.wrapping_sub(rtp_timestamp);
+        if let Some(last) = self.last_transit {
+            let d = (transit.wrapping_sub(last) as i32).unsigned_abs() as f64;
+            self.jitter += (d - self.jitter) / 16.0;
+        }
+        self.last_transit = Some(transit);
+    }
+
+    pub fn jitter_ms(&self, clock_rate: u32) -> f64 {
+        self.jitter * 1000.0 / clock_rate as f64
+    }
+
+    /// RTCP alım raporunda taşınan değer: codec saat birimlerinde.
+    pub fn jitter_units(&self) -> u32 {
+        self.jitter as u32
+    }
+}
+
+// Sapma tahmininin baktığı süre ve örnekleme aralığı; pencerede en fazla 600 nokta olur.
+const SKEW_WINDOW: Duration = Duration::from_secs(60);
+const SKEW_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+// Bundan kısa gözlemlerde jitter eğimi baskılar; tahmin verilmez.
+const SKEW_MIN_SPAN: Duration = Duration::from_secs(10);
+
+/// Uzak ucun örnekleme saatinin bizimkine göre sapması (ppm). Son `SKEW_WINDOW` içindeki geliş
+/// zamanlarına karşı RTP zaman damgalarının en küçük kareler eğiminden hesaplanır; jitter tek
+/// tek noktaları oynatır ama eğimi uzun pencerede değiştirmez. Pozitif değer uzak saatin hızlı
+/// olduğunu gösterir.
+#[derive(Debug, Default)]
+pub struct SkewEstimator {
+    // (geliş, medya zamanı) saniye cinsinden.
+    points: VecDeque<(f64, f64)>,
+    last_timestamp: u32,
+    // Sarma dahil zaman damgası.
+    extended: Option<i64>,
+    warned: bool,
+}
+
+impl SkewEstimator {
+    /// `arrival`, oturum başlangıcından beri geçen süredir. Pencereye yeni nokta eklendiyse `true`.
+    pub fn observe(&mut self, arrival: Duration, rtp_timestamp: u32, clock_rate: u32) -> bool {
+        let extended = match self.extended {
+            Some(extended) => extended + rtp_timestamp.wrapping_sub(self.last_timestamp) as i32 as i64,
+            None => rtp_timestamp as i64,
+        };
+        self.extended = Some(extended);
+        self.last_timestamp = rtp_timestamp;
+
+        // Seyreltme gelişe göre değil medya zamanına göre yapılır; gelişe göre seçmek geç gelen
+        // paketleri kayırır ve eğimi bozar.
+        let (arrival, media) = (arrival.as_secs_f64(), extended as f64 / clock_rate as f64);
+        if self.points.back().is_some_and(|&(_, last)| media - last < SKEW_SAMPLE_INTERVAL.as_secs_f64()) {
+            return false;
+        }
+        self.points.push_back((arrival, media));
+        while self.points.front().is_some_and(|&(first, _)| arrival - first > SKEW_WINDOW.as_secs_f64()) {
+            self.points.pop_front();
+        }
+        true
+    }
+
+    /// Yeterli gözlem yoksa `None`.
+    pub fn skew_ppm(&self) -> Option<f64> {
+        let (&(first, _), &(last, _)) = (self.points.front()?, self.points.back()?);
+        if last - first < SKEW_MIN_SPAN.as_secs_f64() {
+            return None;
+        }
+        let n = self.points.len() as f64;
+        let (mean_x, mean_y) = self.points.iter().fold((0.0, 0.0), |(x, y), &(px, py)| (x + px / n, y + py / n));
+        let (mut sxy, mut sxx) = (0.0, 0.0);
+        for &(x, y) in &self.points {
+            sxy += (x - mean_x) * (y - mean_y);
+            sxx += (x - mean_x) * (x - mean_x);
+        }
+        Some((sxy / sxx - 1.0) * 1e6)
+    }
+
+    /// Sapma eşiği yeni aşıldıysa tahmini döner; tahmin eşiğin altına inene kadar bir daha dönmez.
+    pub fn exceeded(&mut self, threshold_ppm: f64) -> Option<f64> {
+        let skew = self.skew_ppm()?;
+        let over = skew.abs() > threshold_ppm;
+        let first = over && !self.warned;
+        self.warned = over;
+        first.then_some(skew)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitrate_converges_to_the_stream_rate_and_decays_when_idle() {
+        // G.711 20 ms: 160 bayt yük + 12 RTP + 28 IPv4/UDP = 200 bayt, saniyede 50 paket = 80 kbit/s.
+        let start = Instant::now();
+        let mut bitrate = Bitrate::new();
+        for n in 0..1500 {
+            bitrate.record(200, start + Duration::from_millis(20 * n));
+        }
+        let end = start + Duration::from_millis(20 * 1499);
+        let current = bitrate.current_bps(end);
+        assert!((current - 80_000.0).abs() < 1_000.0, "got {current}");
+        assert!(bitrate.peak_bps() >= current && bitrate.peak_bps() < 81_000.0);
+        assert_eq!(bitrate.average_bps(Duration::from_secs(30)), 80_000.0);
+
+        // Bir zaman sabiti sessizlikten sonra e^-1'e iner; en yüksek değer korunur.
+        let idle = bitrate.current_bps(end + BITRATE_WINDOW);
+        assert!((idle - current / std::f64::consts::E).abs() < 1.0, "got {idle}");
+        assert!(bitrate.peak_bps() > idle);
+        assert_eq!(Bitrate::new().current_bps(end), 0.0);
+    }
+
+    #[test]
+    fn sequence_tracker_counts_loss_duplicates_and_wrap() {
+        let mut tracker = SequenceTracker::default();
+        for seq in [65533u16, 65534, 65535, 0, 0, 3, 2] {
+            tracker.observe(seq);
+        }
+        // 65533..=3 arası 7 paket bekleniyor; 1 kayıp, 1 tekrar, 1 boşluk (0 -> 3).
+        assert_eq!(tracker.expected(), 7);
+        assert_eq!(tracker.lost(), 1);
+        assert_eq!(tracker.duplicates(), 1);
+        assert_eq!(tracker.gaps(), 1);
+
+        // Kayıp 1 sonradan kurtarılır; görülmüş ve pencere dışı paketler kurtarılmaz.
+        assert!(tracker.recover(1));
+        assert!(!tracker.recover(1));
+        assert!(!tracker.recover(2));
+        assert!(!tracker.recover(60_000));
+        assert_eq!(tracker.lost(), 0);
+    }
+
+    #[test]
+    fn fraction_lost_counts_only_since_the_previous_report() {
+        let mut inbound = InboundStats::default();
+        assert_eq!(inbound.fraction_lost_since_report(), 0);
+        // 1..=8 arası 8 paket bekleniyor, 2'si kayıp: 64/256.
+        for seq in [1u16, 2, 4, 5, 7, 8] {
+            inbound.sequence.observe(seq);
+        }
+        assert_eq!(inbound.fraction_lost_since_report(), 64);
+        // Sonraki raporda yalnızca aradaki 4 paket sayılır; kayıp yok.
+        for seq in 9u16..=12 {
+            inbound.sequence.observe(seq);
+        }
+        assert_eq!(inbound.fraction_lost_since_report(), 0);
+        assert_eq!(inbound.sequence.lost(), 2);
+    }
+
+    /// `ppm` kadar hızlı çalışan bir uzak saat; ±5 ms pseudo-jitter eklenir.
+    fn skewed_stream(estimator: &mut SkewEstimator, ppm: f64, seconds: u32) {
+        let start_timestamp = u32::MAX - 80_000;
+        for n in 0..seconds * 50 {
+            let jitter = [0.0, 0.005, -0.003, 0.002, -0.005][n as usize % 5];
+            let arrival = Duration::from_secs_f64(1.0 + n as f64 * 0.02 + jitter);
+            let timestamp = start_timestamp.wrapping_add((n as f64 * 160.0 * (1.0 + ppm / 1e6)) as u32);
+            estimator.observe(arrival, timestamp, 8000);
+        }
+    }
+
+    #[test]
+    fn skew_estimate_tracks_remote_clock_rate() {
+        let mut estimator = SkewEstimator::default();
+        skewed_stream(&mut estimator, 0.0, 5);
+        assert_eq!(estimator.skew_ppm(), None, "too short to estimate");
+
+        for ppm in [0.0, 800.0, -300.0] {
+            let mut estimator = SkewEstimator::default();
+            // Zaman damgası sarar; pencere 60 saniyeyi tutar.
+            skewed_stream(&mut estimator, ppm, 90);
+            let estimate = estimator.skew_ppm().unwrap();
+            assert!((estimate - ppm).abs() < 20.0, "expected {ppm}, got {estimate}");
+        }
+    }
+
+    #[test]
+    fn skew_warning_fires_once_per_crossing() {
+        let mut estimator = SkewEstimator::default();
+        skewed_stream(&mut estimator, 800.0, 30);
+        assert!(estimator.exceeded(500.0).is_some());
+        assert_eq!(estimator.exceeded(500.0), None);
+        assert_eq!(estimator.exceeded(1000.0), None);
+        assert!(estimator.exceeded(500.0).is_some());
+    }
+}
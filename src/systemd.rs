@@ -0,0 +1,140 @@
+// systemd `Type=notify` desteği (sd_notify(3)): durum satırları NOTIFY_SOCKET'teki datagram
+// soketine yazılır. Değişken yoksa `Notifier::from_env` `None` döner ve hiçbir şey gönderilmez.
+// Watchdog ping'i yalnızca oturum tablosunun kilidi alınabiliyor ve gRPC sunucusu çalışıyorsa
+// gönderilir; takılan bir süreç ping'i keser ve systemd onu yeniden başlatır.
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+use std::sync::{Arc, TryLockError};
+use std::time::Duration;
+
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, warn};
+
+use crate::health::Health;
+use crate::session::ActiveSessions;
+
+enum Address {
+    Path(PathBuf),
+    // `@` ile başlayan adres: Linux soyut ad alanı.
+    #[cfg(target_os = "linux")]
+    Abstract(Vec<u8>),
+}
+
+pub struct Notifier {
+    socket: UnixDatagram,
+    address: Address,
+}
+
+impl Notifier {
+    /// NOTIFY_SOCKET tanımlı değilse (systemd altında değil ya da `Type=notify` değil) `None`.
+    pub fn from_env() -> Option<Notifier> {
+        let value = std::env::var_os("NOTIFY_SOCKET")?;
+        let address = match value.to_str().and_then(|v| v.strip_prefix('@')) {
+            #[cfg(target_os = "linux")]
+            Some(name) => Address::Abstract(name.as_bytes().to_vec()),
+            #[cfg(not(target_os = "linux"))]
+            Some(_) => { warn!("NOTIFY_SOCKET soyut bir adres, bu platformda desteklenmiyor"); return None; }
+            None => Address::Path(PathBuf::from(value)),
+        };
+        match UnixDatagram::unbound() {
+            Ok(socket) => Some(Notifier { socket, address }),
+            Err(e) => { warn!(error = %e, "systemd bildirim soketi açılamadı"); None }
+        }
+    }
+
+    fn send(&self, state: &str) {
+        let result: io::Result<usize> = match &self.address {
+            Address::Path(path) => self.socket.send_to(state.as_bytes(), path),
+            #[cfg(target_os = "linux")]
+            Address::Abstract(name) => {
+                use std::os::linux::net::SocketAddrExt;
+                std::os::unix::net::SocketAddr::from_abstract_name(name)
+                    .and_then(|addr| self.socket.send_to_addr(state.as_bytes(), &addr))
+            }
+        };
+        match result {
+            Ok(_) => debug!(state, "systemd bildirimi gönderildi"),
+            Err(e) => warn!(state, error = %e, "systemd bildirimi gönderilemedi"),
+        }
+    }
+
+    /// gRPC portu bağlandıktan ve konfigürasyon ile anonslar doğrulandıktan sonra.
+    pub fn ready(&self) { self.send("READY=1"); }
+
+    /// Kapanış başladığında; systemd bundan sonra yeniden başlatmayı beklemeye geçer.
+    pub fn stopping(&self) { self.send("STOPPING=1"); }
+
+    fn watchdog(&self) { self.send("WATCHDOG=1"); }
+}
+
+/// `WatchdogSec=` ayarlıysa ping aralığı: systemd'nin verdiği sürenin yarısı. WATCHDOG_PID başka
+/// bir süreci gösteriyorsa watchdog bu süreç için değildir.
+pub fn watchdog_interval() -> Option<Duration> {
+    if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+        if pid.parse::<u32>().ok() != Some(std::process::id()) {
+            return None;
+        }
+    }
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec / 2))
+}
+
+/// Her `period`'da node sağlıklıysa watchdog ping'i gönderir; süreç bitene kadar çalışır.
+pub async fn run_watchdog(notifier: Arc<Notifier>, period: Duration, health: Arc<Health>, active_sessions: ActiveSessions) {
+    let mut ticker = interval(period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match alive(&health, &active_sessions) {
+            Ok(()) => notifier.watchdog(),
+            Err(reason) => warn!(reason, "Watchdog ping'i gönderilmedi"),
+        }
+    }
+}
+
+fn alive(health: &Health, active_sessions: &ActiveSessions) -> Result<(), &'static str> {
+    if !health.is_grpc_serving() {
+        return Err("grpc_not_serving");
+    }
+    match active_sessions.try_lock() {
+        Ok(_) => Ok(()),
+        // Anlık çekişme; kilit takılmışsa sonraki ping'ler de atlanır ve systemd devreye girer.
+        Err(TryLockError::WouldBlock) => Err("session_registry_busy"),
+        Err(TryLockError::Poisoned(_)) => Err("session_registry_poisoned"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notifier_writes_states_to_the_notify_socket() {
+        let path = std::env::temp_dir().join(format!("media-notify-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixDatagram::bind(&path).unwrap();
+        let notifier = Notifier { socket: UnixDatagram::unbound().unwrap(), address: Address::Path(path.clone()) };
+
+        notifier.ready();
+        notifier.stopping();
+        let mut buf = [0u8; 64];
+        let len = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1");
+        let len = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"STOPPING=1");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn watchdog_requires_grpc_and_a_free_session_registry() {
+        let health = Health::default();
+        let sessions = ActiveSessions::default();
+        assert_eq!(alive(&health, &sessions), Err("grpc_not_serving"));
+
+        health.set_grpc_serving(true);
+        assert_eq!(alive(&health, &sessions), Ok(()));
+        let _held = sessions.lock().unwrap();
+        assert_eq!(alive(&health, &sessions), Err("session_registry_busy"));
+    }
+}
@@ -0,0 +1,244 @@
+// Dinleme: bir oturumun iki yönü, uzak uçtan gelen ses ve ona giden ses (anons ya da köprülüyse
+// karşı bacağın sesi), karıştırılıp gözlemci bir oturumun ("dinleme bacağı") akışıyla ve codec'iyle
+// onun uzak adresine gönderilir. Köprülü bir bacağı dinlemek böylece görüşmenin iki tarafını da
+// verir. Dinleme bacağından hiçbir şey dinlenen oturuma ya da köprüye geri gitmez; dinleme
+// bacağına anons çalınamaz ve köprülenemez. Yönler ayrı kuyruklarda biriktirilip çerçeve çerçeve
+// toplanır; bir yön sustuysa (sessizlik bastırma, tek yönlü ses) öteki `MAX_LAG` çerçeve bekledikten
+// sonra tek başına gider. Karıştırma sesi üreten görevde yapılır; kayıt, döküm ve köprü aynı sesi
+// değişmeden almaya devam eder.
+use std::collections::VecDeque;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::audit::{self, TapEndReason, TeardownReason};
+use crate::error::SessionError;
+use crate::metrics;
+use crate::rtp::{RtpPacket, MAX_PACKET_LEN};
+use crate::session::RtpSession;
+
+/// Bir yön bu kadar çerçeve önde olunca öteki beklenmeden gönderilir.
+pub const MAX_LAG: usize = 3;
+
+/// Dinlenen oturumdaki sesin yönü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Uzak uçtan gelen ses.
+    Inbound,
+    /// Uzak uca giden ses.
+    Outbound,
+}
+
+/// Dinlenen oturumda tutulan bir dinleme bacağı.
+#[derive(Debug)]
+pub struct Tap {
+    leg: Weak<RtpSession>,
+    // Yön başına henüz gönderilmemiş örnekler.
+    queues: [VecDeque<i16>; 2],
+    // Dinleme bacağının çerçevesi, örnek cinsinden.
+    frame: usize,
+    pcm: Vec<i16>,
+    payload: Vec<u8>,
+    wire: [u8; MAX_PACKET_LEN],
+}
+
+impl Tap {
+    fn new(leg: &Arc<RtpSession>, ptime: Duration) -> Self {
+        Tap {
+            leg: Arc::downgrade(leg), queues: [VecDeque::new(), VecDeque::new()], frame: leg.codec().samples_per_frame(ptime).max(1),
+            pcm: Vec::new(), payload: Vec::new(), wire: [0; MAX_PACKET_LEN],
+        }
+    }
+
+    pub fn leg(&self) -> Option<Arc<RtpSession>> {
+        self.leg.upgrade()
+    }
+
+    fn push(&mut self, leg: &RtpSession, direction: Direction, pcm: &[i16], now: Instant) {
+        self.queues[direction as usize].extend(pcm);
+        while self.mix() {
+            self.send(leg, now);
+        }
+    }
+
+    // İki yönde de bir çerçeve varsa ya da bir yön `MAX_LAG` çerçeve öndeyse sıradaki çerçeveyi
+    // toplar; eksik yön sessizlik sayılır.
+    fn mix(&mut self) -> bool {
+        let [inbound, outbound] = &mut self.queues;
+        let ready = (inbound.len() >= self.frame && outbound.len() >= self.frame)
+            || inbound.len().max(outbound.len()) >= self.frame * MAX_LAG;
+        if !ready {
+            return false;
+        }
+        self.pcm.clear();
+        for _ in 0..self.frame {
+            let sum = inbound.pop_front().unwrap_or(0) as i32 + outbound.pop_front().unwrap_or(0) as i32;
+            self.pcm.push(sum.clamp(i16::MIN as i32, i16::MAX as i32) as i16);
+        }
+        true
+    }
+
+    fn send(&mut self, leg: &RtpSession, now: Instant) {
+        let Some(target) = *leg.remote_addr.lock().unwrap() else { return };
+        self.payload.clear();
+        leg.codec().encode(&self.pcm, &mut self.payload);
+        let increment = leg.codec().timestamp_increment(leg.codec().frame_duration(self.pcm.len()));
+        let (sequence, timestamp) = leg.stream.next(now, increment);
+        let packet = RtpPacket::new(leg.codec().payload_type(), sequence, timestamp, leg.stream.ssrc, &self.payload);
+        let Ok(len) = packet.write(&mut self.wire) else { return };
+        // Sesi üreten görev dinleme bacağının soketini beklemez; tampon doluysa paket düşer.
+        match leg.try_send_to(&self.wire[..len], target) {
+            Ok(_) => {
+                leg.mark_sent(target, &self.wire[..len]);
+                leg.capture_sent(target, &self.wire[..len]);
+                metrics::get().tap_packets_sent.inc();
+            }
+            Err(e) => debug!(parent: &leg.span, target = %target, error = %e, "Dinleme paketi gönderilemedi"),
+        }
+    }
+}
+
+/// `leg`'i `observed`'ın dinleme bacağı yapar. Dinleme bacağının uzak adresi bilinmeli, kendisi
+/// köprülü ya da başka bir oturumu dinliyor olmamalı; dinlenen oturum dinleme bacağı olamaz.
+/// Çağıran `ActiveSessions` kilidini tutar.
+pub fn attach(observed: &Arc<RtpSession>, leg: &Arc<RtpSession>, max_taps: usize, ptime: Duration) -> Result<(), SessionError> {
+    if observed.port == leg.port {
+        return Err(SessionError::SelfTap { port: leg.port });
+    }
+    if observed.tap_source().is_some() {
+        return Err(SessionError::TapLeg { port: observed.port });
+    }
+    if let Some(source) = leg.tap_source() {
+        return Err(SessionError::AlreadyTapping { port: leg.port, observed: source.port });
+    }
+    if let Some(peer) = leg.bridged_peer() {
+        return Err(SessionError::AlreadyBridged { port: leg.port, peer: peer.port });
+    }
+    if !leg.taps.lock().unwrap().is_empty() {
+        return Err(SessionError::Tapped { port: leg.port });
+    }
+    if leg.remote_addr.lock().unwrap().is_none() {
+        return Err(SessionError::RemoteUnknown { port: leg.port });
+    }
+    let mut taps = observed.taps.lock().unwrap();
+    taps.retain(|tap| tap.leg.strong_count() > 0);
+    if taps.len() >= max_taps {
+        return Err(SessionError::TapLimit { port: observed.port, max_taps });
+    }
+    taps.push(Tap::new(leg, ptime));
+    *leg.tapping.lock().unwrap() = Some(Arc::downgrade(observed));
+    // Dinleme bacağının süreleri dinleme boyunca işlemez; dinleyici yeniden hesaplasın.
+    leg.playback_idle.notify_one();
+    metrics::get().taps_active.inc();
+    info!(target: audit::TARGET, parent: &observed.span, event = audit::SESSION_TAPPED, rtp_port = observed.port, tap_port = leg.port, taps = taps.len());
+    Ok(())
+}
+
+/// Dinleme bacağını dinlediği oturumdan ayırır; dinlediği oturumun portunu döner, bacak bir oturumu
+/// dinlemiyorsa `None`.
+pub fn detach(leg: &RtpSession, reason: TapEndReason) -> Option<u16> {
+    let observed = leg.tapping.lock().unwrap().take()?;
+    leg.playback_idle.notify_one();
+    metrics::get().taps_active.dec();
+    let observed = observed.upgrade()?;
+    observed.taps.lock().unwrap().retain(|tap| tap.leg.upgrade().is_some_and(|tapping| tapping.port != leg.port));
+    info!(target: audit::TARGET, parent: &observed.span, event = audit::SESSION_UNTAPPED, rtp_port = observed.port, tap_port = leg.port, reason = reason.as_str());
+    Some(observed.port)
+}
+
+/// Dinlenen oturum kapanırken dinleme bacaklarını ayırır ve kapatır.
+pub fn close(observed: &RtpSession) {
+    let taps = std::mem::take(&mut *observed.taps.lock().unwrap());
+    for leg in taps.iter().filter_map(Tap::leg) {
+        if leg.tapping.lock().unwrap().take().is_none() {
+            continue;
+        }
+        metrics::get().taps_active.dec();
+        info!(
+            target: audit::TARGET, parent: &observed.span, event = audit::SESSION_UNTAPPED, rtp_port = observed.port, tap_port = leg.port,
+            reason = TapEndReason::SessionEnded.as_str(),
+        );
+        leg.stop(TeardownReason::TappedSessionEnded);
+    }
+}
+
+/// Dinlenen oturumun bir yönündeki çözülmüş sesi dinleme bacaklarına verir; dinleyen yoksa bir şey yapmaz.
+pub fn feed(observed: &RtpSession, direction: Direction, pcm: &[i16]) {
+    let mut taps = observed.taps.lock().unwrap();
+    if taps.is_empty() {
+        return;
+    }
+    let now = Instant::now();
+    taps.retain_mut(|tap| match tap.leg() {
+        Some(leg) => {
+            tap.push(&leg, direction, pcm, now);
+            true
+        }
+        None => false,
+    });
+}
+
+/// Dinlenen oturumun dinleyicisi var mı; gelen ses yalnızca o zaman onlar için çözülür.
+pub fn is_tapped(observed: &RtpSession) -> bool {
+    !observed.taps.lock().unwrap().is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::*;
+    use tokio::net::UdpSocket;
+
+    use crate::codec::{g711_ulaw_to_pcm16, Codec, Pcma, Pcmu};
+    use crate::rtp::RtpPacketRef;
+
+    const PTIME: Duration = Duration::from_millis(20);
+
+    async fn session(codec: &'static dyn Codec, remote: Option<SocketAddr>) -> Arc<RtpSession> {
+        let sock = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = sock.local_addr().unwrap().port();
+        let session = RtpSession::new(port, codec, sock, "test-call");
+        *session.remote_addr.lock().unwrap() = remote;
+        Arc::new(session)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn both_directions_are_mixed_into_the_tap_legs_stream() {
+        let supervisor = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let remote = Some(supervisor.local_addr().unwrap());
+        let observed = session(&Pcma, None).await;
+        let leg = session(&Pcmu, remote).await;
+        assert!(matches!(attach(&observed, &session(&Pcmu, None).await, 1, PTIME), Err(SessionError::RemoteUnknown { .. })));
+        attach(&observed, &leg, 1, PTIME).unwrap();
+        assert!(matches!(attach(&leg, &observed, 1, PTIME), Err(SessionError::TapLeg { .. })));
+        assert!(matches!(attach(&observed, &session(&Pcmu, remote).await, 1, PTIME), Err(SessionError::TapLimit { max_taps: 1, .. })));
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        // İki yönden birer çerçeve tek pakete toplanır; yalnız kalan yön MAX_LAG çerçeve bekler.
+        feed(&observed, Direction::Inbound, &[1000; 160]);
+        feed(&observed, Direction::Outbound, &[-400; 80]);
+        feed(&observed, Direction::Outbound, &[-400; 80]);
+        for _ in 0..MAX_LAG {
+            feed(&observed, Direction::Inbound, &[500; 160]);
+        }
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        let mut buf = [0u8; MAX_PACKET_LEN];
+        let mut packets = Vec::new();
+        while let Ok((len, _)) = supervisor.try_recv_from(&mut buf) {
+            let packet = RtpPacketRef::parse(&buf[..len]).unwrap();
+            let levels: Vec<i16> = packet.payload().iter().map(|&byte| g711_ulaw_to_pcm16(byte)).collect();
+            packets.push((packet.payload_type(), packet.ssrc(), levels));
+        }
+        assert_eq!(packets.len(), 2);
+        assert!(packets.iter().all(|(payload_type, ssrc, levels)| (*payload_type, *ssrc, levels.len()) == (0, leg.stream.ssrc, 160)));
+        assert!(packets[0].2.iter().all(|&level| (560..=640).contains(&level)), "{:?}", &packets[0].2[..4]);
+        assert!(packets[1].2.iter().all(|&level| (470..=530).contains(&level)), "{:?}", &packets[1].2[..4]);
+
+        assert_eq!(detach(&leg, TapEndReason::Request), Some(observed.port));
+        assert!(!is_tapped(&observed) && leg.tap_source().is_none());
+    }
+}
@@ -0,0 +1,158 @@
+// OpenTelemetry ihracı (`otel` feature'ı). Mevcut tracing span'leri (allocate_port, session, ...)
+// OTLP span'ine dönüşür; gelen isteklerdeki W3C traceparent başlığı handler span'inin ebeveyni olur.
+// Feature olmadan derlendiğinde fonksiyonlar hiçbir şey yapmaz.
+use tonic::metadata::MetadataMap;
+use tracing::Span;
+
+#[cfg(not(feature = "otel"))]
+use crate::config::TelemetryConfig;
+
+#[cfg(feature = "otel")]
+mod otlp {
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    use opentelemetry::metrics::MetricsError;
+    use opentelemetry::propagation::{Extractor, TextMapPropagator};
+    use opentelemetry::trace::TraceError;
+    use opentelemetry::{global, KeyValue};
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::{runtime, trace, Resource};
+    use tonic::metadata::MetadataMap;
+    use tracing::{Span, Subscriber};
+    use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+    use tracing_subscriber::registry::LookupSpan;
+
+    use crate::metrics::{self, Kind};
+    use crate::config::TelemetryConfig;
+
+    const METRICS_EXPORT_INTERVAL: Duration = Duration::from_secs(15);
+
+    static METER_PROVIDER: OnceLock<SdkMeterProvider> = OnceLock::new();
+
+    fn resource(config: &TelemetryConfig) -> Resource {
+        Resource::new([KeyValue::new("service.name", config.service_name.clone())])
+    }
+
+    pub fn tracing_layer<S>(config: &TelemetryConfig) -> Result<Option<OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>>, TraceError>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        if !config.enabled {
+            return Ok(None);
+        }
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(&config.endpoint))
+            .with_trace_config(trace::config().with_resource(resource(config)))
+            .install_batch(runtime::Tokio)?;
+        Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+    }
+
+    pub fn start_metrics(config: &TelemetryConfig) -> Result<(), MetricsError> {
+        let provider = opentelemetry_otlp::new_pipeline()
+            .metrics(runtime::Tokio)
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(&config.endpoint))
+            .with_resource(resource(config))
+            .with_period(METRICS_EXPORT_INTERVAL)
+            .build()?;
+
+        // Her metrik, Prometheus'taki isim ve açıklamasıyla gözlemlenen bir enstrümandır;
+        // değerler her ihraçta aynı atomiklerden okunur.
+        let meter = opentelemetry::metrics::MeterProvider::meter(&provider, "media");
+        let mut registered: Vec<&'static str> = Vec::new();
+        for sample in metrics::get().samples() {
+            if registered.contains(&sample.name) {
+                continue;
+            }
+            registered.push(sample.name);
+            let name = sample.name;
+            let observe = move |observer: &dyn opentelemetry::metrics::AsyncInstrument<f64>| {
+                for sample in metrics::get().samples().into_iter().filter(|s| s.name == name) {
+                    let attributes: Vec<KeyValue> = sample.label.map(|(k, v)| KeyValue::new(k, v)).into_iter().collect();
+                    observer.observe(sample.value, &attributes);
+                }
+            };
+            match sample.kind {
+                Kind::Counter => { meter.f64_observable_counter(name).with_description(sample.help).with_callback(observe).try_init()?; }
+                Kind::Gauge => { meter.f64_observable_gauge(name).with_description(sample.help).with_callback(observe).try_init()?; }
+            }
+        }
+        let _ = METER_PROVIDER.set(provider);
+        Ok(())
+    }
+
+    pub fn set_remote_parent(span: &Span, metadata: &MetadataMap) {
+        let cx = TraceContextPropagator::new().extract(&MetadataExtractor(metadata));
+        span.set_parent(cx);
+    }
+
+    pub fn shutdown() {
+        if let Some(provider) = METER_PROVIDER.get() {
+            let _ = provider.shutdown();
+        }
+        global::shutdown_tracer_provider();
+    }
+
+    struct MetadataExtractor<'a>(&'a MetadataMap);
+
+    impl Extractor for MetadataExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().filter_map(|key| match key {
+                tonic::metadata::KeyRef::Ascii(key) => Some(key.as_str()),
+                tonic::metadata::KeyRef::Binary(_) => None,
+            }).collect()
+        }
+    }
+}
+
+#[cfg(feature = "otel")]
+pub use otlp::{shutdown, start_metrics, tracing_layer};
+
+/// Feature olmadan katman eklenmez.
+#[cfg(not(feature = "otel"))]
+pub fn tracing_layer(_config: &TelemetryConfig) -> Result<Option<tracing_subscriber::layer::Identity>, std::convert::Infallible> {
+    Ok(None)
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn shutdown() {}
+
+/// İstek metadata'sındaki `traceparent`'ı span'in ebeveyni yapar. Başlık yoksa span kök kalır.
+pub fn set_remote_parent(span: &Span, metadata: &MetadataMap) {
+    #[cfg(feature = "otel")]
+    otlp::set_remote_parent(span, metadata);
+    #[cfg(not(feature = "otel"))]
+    let _ = (span, metadata);
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn incoming_traceparent_becomes_span_parent() {
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder().build();
+        let tracer = provider.tracer("test");
+        let subscriber = tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        let mut metadata = MetadataMap::new();
+        metadata.insert("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".parse().unwrap());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("allocate_port");
+            set_remote_parent(&span, &metadata);
+            let trace_id = span.context().span().span_context().trace_id();
+            assert_eq!(trace_id.to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        });
+    }
+}
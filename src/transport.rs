@@ -0,0 +1,416 @@
+// Oturumun medya taşıması. Varsayılan UDP soketidir; UDP'nin kapalı olduğu ağlar için tahsis
+// RTP aralığında bir TCP portu dinleyip tek bir bağlantı kabul edebilir. TCP'de RTP ve RTCP
+// paketleri aynı bağlantıda RFC 4571'deki 2 baytlık uzunluk önekiyle taşınır; okuma kısmi
+// çerçeveleri tamponda biriktirir, yazma ayrı bir görevden sırayla yapılır. Kabul edilen bağlantı
+// oturumun uzak ucudur; bağlantı kapanınca oturum da kapanır. Linux'ta `recvmmsg` feature'ıyla
+// UDP soketi her uyanışta kuyruktaki paketleri tek çağrıda okur.
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+use crate::audit::SendFailure;
+use crate::media::TransportKind;
+use crate::metrics;
+
+// RFC 4571 uzunluk öneki.
+const LENGTH_PREFIX: usize = 2;
+// Yazıcı görevin önünde bekleyebilecek çerçeve sayısı; dolunca UDP'deki gibi paket düşer.
+const FRAME_QUEUE: usize = 64;
+/// Gelen paket tamponu; RTP paketleri MTU'yu aşmaz.
+pub const RECV_BUFFER: usize = 2048;
+/// Tek okuma çağrısında alınabilecek en fazla paket.
+pub const MAX_RECV_BATCH: usize = 64;
+/// Dolu gönderim tamponunda ilk denemeden sonraki deneme sayısı ve ilk bekleme; bekleme her
+/// denemede ikiye katlanır, toplamı bir paket süresinin altında kalır.
+pub const SEND_RETRIES: u32 = 3;
+const SEND_BACKOFF: Duration = Duration::from_millis(1);
+
+/// Tek okuma çağrısında alınan paketler. Tamponlar oturum boyunca yeniden kullanılır; her biri
+/// `RECV_BUFFER` bayttır.
+#[derive(Debug)]
+pub struct RecvBatch {
+    pub(crate) buffers: Vec<[u8; RECV_BUFFER]>,
+    // Dolu tamponun sırası, paketin uzunluğu ve göndereni; alım sırasıyla.
+    pub(crate) received: Vec<(usize, usize, SocketAddr)>,
+}
+
+impl RecvBatch {
+    /// Son okumanın paketleri, geldikleri sırayla.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], SocketAddr)> {
+        self.received.iter().map(|&(slot, len, addr)| (&self.buffers[slot][..len], addr))
+    }
+}
+
+#[derive(Debug)]
+pub enum Transport {
+    Udp(UdpSocket),
+    Tcp(TcpTransport),
+}
+
+impl From<UdpSocket> for Transport {
+    fn from(sock: UdpSocket) -> Self {
+        Transport::Udp(sock)
+    }
+}
+
+impl Transport {
+    pub fn kind(&self) -> TransportKind {
+        match self {
+            Transport::Udp(_) => TransportKind::Udp,
+            Transport::Tcp(_) => TransportKind::Tcp,
+        }
+    }
+
+    /// `peer` ile alışverişte her paketin RTP'nin altında taşıdığı başlıklar: IP (20 ya da 40
+    /// bayt), UDP (8) ya da TCP (seçeneksiz 20) ve RFC 4571 uzunluk öneki. Ethernet sayılmaz.
+    pub fn wire_overhead(&self, peer: SocketAddr) -> usize {
+        let ip = match peer {
+            SocketAddr::V6(addr) if addr.ip().to_ipv4_mapped().is_none() => 40,
+            _ => 20,
+        };
+        match self {
+            Transport::Udp(_) => ip + 8,
+            Transport::Tcp(_) => ip + 20 + LENGTH_PREFIX,
+        }
+    }
+
+    /// Okuma tamponları. Toplu okuma yalnızca Linux'ta `recvmmsg` feature'ıyla UDP soketinde
+    /// yapılır; diğer durumlarda `capacity` yok sayılır ve paketler tek tek okunur.
+    pub fn batch(&self, capacity: usize) -> RecvBatch {
+        let capacity = match self {
+            Transport::Udp(_) if cfg!(all(target_os = "linux", feature = "recvmmsg")) => capacity.clamp(1, MAX_RECV_BATCH),
+            _ => 1,
+        };
+        RecvBatch { buffers: vec![[0; RECV_BUFFER]; capacity], received: Vec::with_capacity(capacity) }
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            Transport::Udp(sock) => sock.local_addr(),
+            Transport::Tcp(tcp) => Ok(tcp.local_addr),
+        }
+    }
+
+    /// Bir paket (UDP datagramı ya da TCP çerçevesi) ve göndereni. TCP'de ilk çağrı bağlantıyı
+    /// kabul eder. İptal güvenlidir: yarım okunan çerçeve sonraki çağrıya kalır.
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        match self {
+            Transport::Udp(sock) => sock.recv_from(buf).await,
+            Transport::Tcp(tcp) => tcp.recv(buf).await,
+        }
+    }
+
+    /// Soket okunabilir olunca bekleyen paketleri `batch`'e okur ve sayısını döner; toplu okuma
+    /// yoksa tek paket. `recv_from` gibi iptal güvenlidir.
+    pub async fn recv_batch(&self, batch: &mut RecvBatch) -> io::Result<usize> {
+        #[cfg(all(target_os = "linux", feature = "recvmmsg"))]
+        if let Transport::Udp(sock) = self {
+            if batch.buffers.len() > 1 {
+                if let Some(result) = crate::recvmmsg::recv(sock, batch).await {
+                    metrics::get().rtp_recv_calls.inc();
+                    return result;
+                }
+            }
+        }
+        let (len, addr) = self.recv_from(&mut batch.buffers[0]).await?;
+        metrics::get().rtp_recv_calls.inc();
+        batch.received.clear();
+        batch.received.push((0, len, addr));
+        Ok(1)
+    }
+
+    /// TCP'de `target` yok sayılır; paket bağlı uca gider.
+    pub async fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        match self {
+            Transport::Udp(sock) => sock.send_to(buf, target).await,
+            Transport::Tcp(tcp) => tcp.send(buf),
+        }
+    }
+
+    /// Bloklamadan gönderir; tampon ya da kuyruk doluysa `WouldBlock`.
+    pub fn try_send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        match self {
+            Transport::Udp(sock) => sock.try_send_to(buf, target),
+            Transport::Tcp(tcp) => tcp.send(buf),
+        }
+    }
+}
+
+/// Uzak uç TCP bağlantısını kapattı ya da bağlantı koptu; oturum medya alamaz.
+pub fn is_disconnect(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::UnexpectedEof
+}
+
+/// Gönderim hatasının sınıfı (bkz. `SendFailure`). ICMP hataları UDP'de sonraki gönderimde
+/// ECONNREFUSED/EHOSTUNREACH olarak, kopan TCP bağlantısı yazıcı kuyruğunun kapanmasıyla görülür.
+pub fn send_failure(error: &io::Error) -> SendFailure {
+    match error.kind() {
+        io::ErrorKind::ConnectionRefused | io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::HostUnreachable | io::ErrorKind::NetworkUnreachable | io::ErrorKind::BrokenPipe => SendFailure::Unreachable,
+        io::ErrorKind::PermissionDenied => SendFailure::Denied,
+        io::ErrorKind::WouldBlock => SendFailure::NoBuffers,
+        _ if error.raw_os_error().is_some_and(is_enobufs) => SendFailure::NoBuffers,
+        _ => SendFailure::Other,
+    }
+}
+
+// ENOBUFS'un ErrorKind karşılığı yok; libc'ye bağlanmamak için platform değerleri.
+fn is_enobufs(code: i32) -> bool {
+    if cfg!(any(target_os = "linux", target_os = "android")) {
+        code == 105
+    } else if cfg!(any(target_vendor = "apple", target_os = "freebsd")) {
+        code == 55
+    } else if cfg!(windows) {
+        code == 10055
+    } else {
+        false
+    }
+}
+
+/// `send`'i çağırır; gönderim tamponu doluysa kısa ve artan aralıklarla `SEND_RETRIES` kez daha
+/// dener. Diğer hatalar ve son denemenin hatası olduğu gibi döner.
+pub async fn send_retrying<F, Fut>(mut send: F) -> io::Result<usize>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = io::Result<usize>>,
+{
+    let mut delay = SEND_BACKOFF;
+    for _ in 0..SEND_RETRIES {
+        match send().await {
+            Err(e) if send_failure(&e) == SendFailure::NoBuffers => {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+    send().await
+}
+
+#[derive(Debug)]
+pub struct TcpTransport {
+    local_addr: SocketAddr,
+    // Yalnızca dinleyici görevi okur; kilit çekişmez, `select!` iptalinde durum korunur.
+    reader: tokio::sync::Mutex<TcpReader>,
+    // Kabul edilen bağlantının ucu ve yazıcı görevin kuyruğu.
+    connection: Mutex<Option<(SocketAddr, mpsc::Sender<Vec<u8>>)>>,
+}
+
+#[derive(Debug)]
+struct TcpReader {
+    // Bağlantı kabul edilince bırakılır; ikinci bir bağlantı kabul edilmez.
+    listener: Option<TcpListener>,
+    stream: Option<OwnedReadHalf>,
+    // Çerçeve sınırlarını aşan okumalar burada birikir.
+    pending: Vec<u8>,
+}
+
+impl TcpTransport {
+    pub fn new(listener: TcpListener) -> io::Result<Self> {
+        Ok(TcpTransport {
+            local_addr: listener.local_addr()?,
+            reader: tokio::sync::Mutex::new(TcpReader { listener: Some(listener), stream: None, pending: Vec::new() }),
+            connection: Mutex::new(None),
+        })
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        let mut reader = self.reader.lock().await;
+        let reader = &mut *reader;
+        if reader.stream.is_none() {
+            let listener = reader.listener.as_ref().ok_or_else(|| disconnected("connection already closed"))?;
+            let (stream, peer) = listener.accept().await?;
+            let _ = stream.set_nodelay(true);
+            let (read, write) = stream.into_split();
+            let (frames, queue) = mpsc::channel(FRAME_QUEUE);
+            tokio::spawn(write_frames(write, queue));
+            *self.connection.lock().unwrap() = Some((peer, frames));
+            reader.listener = None;
+            reader.stream = Some(read);
+            info!(remote = %peer, "RTP/TCP bağlantısı kabul edildi");
+        }
+        let peer = self.connection.lock().unwrap().as_ref().map(|(peer, _)| *peer).expect("bağlantı kabul edildi");
+        loop {
+            if let Some(len) = frame_len(&reader.pending) {
+                let frame = &reader.pending[LENGTH_PREFIX..LENGTH_PREFIX + len];
+                let copied = frame.len().min(buf.len());
+                buf[..copied].copy_from_slice(&frame[..copied]);
+                reader.pending.drain(..LENGTH_PREFIX + len);
+                // Boş çerçeveler (RFC 4571 2) atlanır.
+                if len == 0 {
+                    continue;
+                }
+                return Ok((copied, peer));
+            }
+            let stream = reader.stream.as_mut().expect("bağlantı kabul edildi");
+            match stream.read_buf(&mut reader.pending).await {
+                Ok(0) => return Err(disconnected("peer closed the connection")),
+                Ok(_) => {}
+                Err(e) => return Err(disconnected(&e.to_string())),
+            }
+        }
+    }
+
+    fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        let connection = self.connection.lock().unwrap();
+        let Some((_, frames)) = connection.as_ref() else {
+            return Err(io::Error::from(io::ErrorKind::NotConnected));
+        };
+        match frames.try_send(buf.to_vec()) {
+            Ok(()) => Ok(buf.len()),
+            Err(mpsc::error::TrySendError::Full(_)) => Err(io::Error::from(io::ErrorKind::WouldBlock)),
+            Err(mpsc::error::TrySendError::Closed(_)) => Err(io::Error::from(io::ErrorKind::BrokenPipe)),
+        }
+    }
+}
+
+/// Tampondaki ilk çerçevenin uzunluğu; çerçeve henüz tamamlanmadıysa `None`.
+fn frame_len(pending: &[u8]) -> Option<usize> {
+    let prefix: [u8; LENGTH_PREFIX] = pending.get(..LENGTH_PREFIX)?.try_into().ok()?;
+    let len = u16::from_be_bytes(prefix) as usize;
+    (pending.len() >= LENGTH_PREFIX + len).then_some(len)
+}
+
+fn disconnected(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, reason.to_string())
+}
+
+/// Kuyruktaki paketleri uzunluk önekiyle sırayla yazar; kuyruk kapanınca ya da yazma başarısız
+/// olunca biter. Kopan bağlantıyı okuma tarafı bildirir.
+async fn write_frames(mut stream: OwnedWriteHalf, mut frames: mpsc::Receiver<Vec<u8>>) {
+    let mut framed = Vec::new();
+    while let Some(frame) = frames.recv().await {
+        framed.clear();
+        framed.extend_from_slice(&(frame.len() as u16).to_be_bytes());
+        framed.extend_from_slice(&frame);
+        if let Err(e) = stream.write_all(&framed).await {
+            debug!(error = %e, "RTP/TCP çerçevesi yazılamadı");
+            return;
+        }
+    }
+    let _ = stream.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpStream;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn frames_split_across_reads_are_reassembled_and_disconnect_is_reported() {
+        let transport = Transport::Tcp(TcpTransport::new(TcpListener::bind("127.0.0.1:0").await.unwrap()).unwrap());
+        let addr = transport.local_addr().unwrap();
+        let mut peer = TcpStream::connect(addr).await.unwrap();
+        assert_eq!(transport.try_send_to(b"x", addr).unwrap_err().kind(), io::ErrorKind::NotConnected);
+
+        // İki çerçeve ve boş bir çerçeve, sınırların ortasından bölünmüş yazımlarla.
+        let wire = [&[0, 3, 1, 2][..], &[3, 0, 0, 0], &[2, 9, 8]];
+        let writer = tokio::spawn(async move {
+            for chunk in wire {
+                peer.write_all(chunk).await.unwrap();
+                peer.flush().await.unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+            peer
+        });
+        let mut buf = [0u8; 16];
+        let (len, from) = transport.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[..len], [1, 2, 3]);
+        let (len, _) = transport.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[..len], [9, 8]);
+        let mut peer = writer.await.unwrap();
+        assert_eq!(from, peer.local_addr().unwrap());
+
+        // Giden paket uzunluk önekiyle yazılır.
+        transport.send_to(&[7, 7, 7, 7], from).await.unwrap();
+        let mut framed = [0u8; 6];
+        peer.read_exact(&mut framed).await.unwrap();
+        assert_eq!(framed, [0, 4, 7, 7, 7, 7]);
+
+        drop(peer);
+        assert!(is_disconnect(&transport.recv_from(&mut buf).await.unwrap_err()));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn send_errors_are_classified_and_full_buffers_retried() {
+        // Kapatılmış porta bağlı soket: ICMP port unreachable sonraki gönderimde döner.
+        let closed = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = closed.local_addr().unwrap();
+        drop(closed);
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket.connect(addr).unwrap();
+        let refused = (0..100)
+            .find_map(|_| socket.send(&[0]).err().or_else(|| { std::thread::sleep(Duration::from_millis(1)); None }))
+            .expect("ICMP error reported");
+        assert_eq!(send_failure(&refused), SendFailure::Unreachable);
+        assert_eq!(send_failure(&io::Error::from(io::ErrorKind::PermissionDenied)), SendFailure::Denied);
+
+        // SO_SNDBUF'ı küçültülmüş, karşısı okumayan datagram soketi hemen dolar.
+        let (sender, receiver) = std::os::unix::net::UnixDatagram::pair().unwrap();
+        sender.set_nonblocking(true).unwrap();
+        let size: libc::c_int = 1;
+        let set = unsafe {
+            use std::os::fd::AsRawFd;
+            libc::setsockopt(sender.as_raw_fd(), libc::SOL_SOCKET, libc::SO_SNDBUF, (&size as *const libc::c_int).cast(), std::mem::size_of::<libc::c_int>() as libc::socklen_t)
+        };
+        assert_eq!(set, 0);
+        let full = loop {
+            if let Err(e) = sender.send(&[0; 512]) {
+                break e;
+            }
+        };
+        assert_eq!(send_failure(&full), SendFailure::NoBuffers);
+
+        // Tampon boşalmazsa denemeler biter; üçüncü denemeden önce bir datagram okununca gönderim geçer.
+        let mut attempts = 0;
+        let failed = send_retrying(|| { attempts += 1; std::future::ready(sender.send(&[0; 512])) }).await;
+        assert_eq!((send_failure(&failed.unwrap_err()), attempts), (SendFailure::NoBuffers, SEND_RETRIES + 1));
+        let (mut attempts, mut buf) = (0, [0u8; 512]);
+        let sent = send_retrying(|| {
+            attempts += 1;
+            if attempts == 3 {
+                receiver.recv(&mut buf).unwrap();
+            }
+            std::future::ready(sender.send(&[0; 512]))
+        }).await;
+        assert_eq!((sent.unwrap(), attempts), (512, 3));
+    }
+
+    #[tokio::test]
+    async fn batched_reads_keep_arrival_order() {
+        let transport = Transport::from(UdpSocket::bind("127.0.0.1:0").await.unwrap());
+        let addr = transport.local_addr().unwrap();
+        let peer = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        for i in 0..40u8 {
+            peer.send_to(&[i; 3], addr).unwrap();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        // Toplu okuma yoksa tampon tektir ve her çağrı bir paket okur.
+        let mut batch = transport.batch(16);
+        let capacity = batch.buffers.len();
+        assert_eq!(capacity, if cfg!(all(target_os = "linux", feature = "recvmmsg")) { 16 } else { 1 });
+        let (mut received, mut calls) = (Vec::new(), 0);
+        while received.len() < 40 {
+            let count = transport.recv_batch(&mut batch).await.unwrap();
+            assert!((1..=capacity).contains(&count));
+            calls += 1;
+            for (data, from) in batch.iter() {
+                assert_eq!(from, peer.local_addr().unwrap());
+                received.push(data[0]);
+            }
+        }
+        assert_eq!(received, (0..40).collect::<Vec<_>>());
+        assert_eq!(calls, 40usize.div_ceil(capacity));
+    }
+}
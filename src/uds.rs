@@ -0,0 +1,66 @@
+// gRPC'nin Unix domain socket dinleyicisi. Aynı makinedeki sinyalleşme süreci TCP portu açmadan
+// bağlanır; erişim dosya izinleriyle sınırlanır. Önceki bir süreçten kalan soket dosyası
+// başlangıçta silinir, dinleyici bırakılınca dosya da kaldırılır.
+use std::fs;
+use std::io;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use tokio::net::UnixListener;
+use tokio_stream::wrappers::UnixListenerStream;
+
+/// Bağlı soket dosyası; düşürülünce dosya silinir.
+#[derive(Debug)]
+pub struct SocketFile {
+    path: PathBuf,
+}
+
+impl SocketFile {
+    /// `path`'e bağlanır ve dosya izinlerini `mode` yapar. Yoldaki eski bir soket silinir; soket
+    /// olmayan bir dosya ise dokunulmadan hata döner.
+    pub fn bind(path: &Path, mode: u32) -> io::Result<(SocketFile, UnixListenerStream)> {
+        match fs::symlink_metadata(path) {
+            Ok(meta) if meta.file_type().is_socket() => fs::remove_file(path)?,
+            Ok(_) => return Err(io::Error::new(io::ErrorKind::AlreadyExists, "path exists and is not a socket")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        let listener = UnixListener::bind(path)?;
+        let file = SocketFile { path: path.to_path_buf() };
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+        Ok((file, UnixListenerStream::new(listener)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stale_socket_is_replaced_and_removed_on_drop() {
+        let path = std::env::temp_dir().join(format!("media-uds-{}.sock", std::process::id()));
+        let _ = fs::remove_file(&path);
+        // Çökmüş bir sürecin bıraktığı soket dosyası.
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let (file, _incoming) = SocketFile::bind(&path, 0o600).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        drop(file);
+        assert!(!path.exists());
+
+        fs::write(&path, b"not a socket").unwrap();
+        assert_eq!(SocketFile::bind(&path, 0o600).unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        fs::remove_file(&path).unwrap();
+    }
+}
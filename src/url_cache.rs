@@ -0,0 +1,450 @@
+// URL'li anonslar: yolu `http://` ya da `s3://bucket/anahtar` olan anons ilk çalındığında `announcement.url_cache_dir`'e
+// indirilir, anons hattındaki doğrulamadan geçerse oradan çalınır. Kopyanın yaşı
+// `url_revalidate_s`'i geçince sonraki çalma sunucuya If-None-Match ile sorar; 304 gelirse
+// indirilmez. İndirme başarısız olursa eldeki eski kopya çalınır ve bir sonraki doğrulama aralığı
+// beklenir. Kopya URL'nin özetiyle adlandırılır, ETag'i yanında saklanır; yeniden başlatmadan sonra
+// da kullanılır. Dizin `url_cache_max_bytes`'ı aşınca en eski kopyalar silinir.
+//
+// İndirme yalnızca onu bekleyen oynatmayı bekletir: kaynak hazır olana kadar boş çerçeve verir ve
+// gönderici o tikleri atlar; tahsis ve dinleyici döngüsü hiç beklemez. TLS istemcisi olmadığından
+// `https://` adresleri reddedilir. `s3://` nesneleri `object_store` üzerinden imzalı istekle okunur.
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use hyper::body::HttpBody;
+use hyper::client::HttpConnector;
+use hyper::header::{ETAG, IF_NONE_MATCH};
+use hyper::{Body, Client, Request, StatusCode, Uri};
+use sha2::{Digest, Sha256};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::announcement::write_synced;
+use crate::config::PromptConfig;
+use crate::error::PlaybackError;
+use crate::object_store;
+use crate::source::{AudioSource, FrameFuture, WavSource};
+use crate::state::{CachedUrl, UrlCacheState};
+
+// Bir indirmenin (bağlantı, başlıklar ve gövde) üst sınırı.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Adres bu düğümde çalınabilir mi; değilse sebebi.
+pub fn check_url(url: &str) -> Result<(), String> {
+    let uri: Uri = url.parse().map_err(|e: hyper::http::uri::InvalidUri| e.to_string())?;
+    match uri.scheme_str() {
+        Some("http") if uri.host().is_some_and(|host| !host.is_empty()) => Ok(()),
+        Some("http") => Err("missing host".to_string()),
+        Some("https") => Err("https is not supported; this build has no TLS client".to_string()),
+        Some("s3") if s3_object(url).is_some() => Ok(()),
+        Some("s3") => Err("expected s3://bucket/key".to_string()),
+        _ => Err("only http:// and s3:// URLs are supported".to_string()),
+    }
+}
+
+/// `s3://bucket/anahtar` adresinin kovası ve anahtarı.
+fn s3_object(url: &str) -> Option<(&str, &str)> {
+    let (bucket, key) = url.strip_prefix("s3://")?.split_once('/')?;
+    (!bucket.is_empty() && !key.is_empty()).then_some((bucket, key))
+}
+
+#[derive(Debug)]
+struct Entry {
+    path: PathBuf,
+    etag: Option<String>,
+    // Sunucuya son sorulan an; diskte bulunup henüz sorulmadıysa yok.
+    checked: Option<Instant>,
+}
+
+enum Fetched {
+    NotModified,
+    Body { data: Vec<u8>, etag: Option<String> },
+}
+
+#[derive(Debug)]
+pub struct UrlCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    revalidate: Duration,
+    client: Client<HttpConnector>,
+    // URL başına tek indirme: aynı adresi isteyen oynatmalar ilkinin sonucunu bekler.
+    entries: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Option<Entry>>>>>,
+}
+
+impl UrlCache {
+    /// Önbellek dizinini oluşturur; içindeki kopyalar ilk kullanımda doğrulanır.
+    pub fn new(dir: &Path, max_bytes: u64, revalidate: Duration) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(UrlCache { dir: dir.to_path_buf(), max_bytes, revalidate, client: Client::new(), entries: Mutex::default() })
+    }
+
+    /// `url`'nin yerel kopyası: tazeyse hemen, değilse sunucuya sorulduktan ya da indirildikten
+    /// sonra. Sunucuya ulaşılamaz ya da dosya geçersizse eski kopya döner; kopya yoksa hata.
+    pub async fn fetch(&self, url: &str) -> Result<PathBuf, PlaybackError> {
+        check_url(url).map_err(|reason| PlaybackError::InvalidUrl { url: url.to_string(), reason })?;
+        let slot = self.entries.lock().unwrap().entry(url.to_string()).or_default().clone();
+        let mut entry = slot.lock().await;
+        // Boyut sınırı ya da silme kopyayı kaldırmış olabilir.
+        if entry.as_ref().is_some_and(|e| !e.path.exists()) {
+            *entry = None;
+        }
+        if entry.is_none() {
+            *entry = self.on_disk(url);
+        }
+        if let Some(fresh) = entry.as_ref().filter(|e| e.checked.is_some_and(|at| at.elapsed() < self.revalidate)) {
+            return Ok(fresh.path.clone());
+        }
+
+        let etag = entry.as_ref().and_then(|e| e.etag.clone());
+        let stored = match self.download(url, etag.as_deref()).await {
+            Ok(Fetched::NotModified) => Ok(None),
+            Ok(Fetched::Body { data, etag }) => self.store(url, data, etag).await.map(Some),
+            Err(e) => Err(e),
+        };
+        match (stored, entry.as_mut()) {
+            (Ok(Some(stored)), _) => {
+                info!(url = %url, file = %stored.path.display(), etag = stored.etag.as_deref().unwrap_or("-"), "Anons indirildi");
+                let path = stored.path.clone();
+                *entry = Some(stored);
+                Ok(path)
+            }
+            (Ok(None), Some(current)) => {
+                current.checked = Some(Instant::now());
+                Ok(current.path.clone())
+            }
+            (Ok(None), None) => Err(fetch_error(url, "server answered 304 without a cached copy")),
+            (Err(e), Some(stale)) => {
+                warn!(url = %url, error = %e, "Anons indirilemedi, önbellekteki eski kopya çalınıyor");
+                stale.checked = Some(Instant::now());
+                Ok(stale.path.clone())
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    /// Diskteki kopyanın yolu; henüz indirilmediyse `None`. Sunucuya sormaz.
+    pub fn cached(&self, url: &str) -> Option<PathBuf> {
+        let path = self.path(url, "wav");
+        path.exists().then_some(path)
+    }
+
+    /// DumpState için bilinen URL'ler ve dizinin toplam boyu. İndirmesi süren girdiler beklenmez.
+    pub fn state(&self) -> UrlCacheState {
+        let mut slots: Vec<(String, Arc<tokio::sync::Mutex<Option<Entry>>>)> =
+            self.entries.lock().unwrap().iter().map(|(url, slot)| (url.clone(), slot.clone())).collect();
+        slots.sort_by(|a, b| a.0.cmp(&b.0));
+        let entries = slots.into_iter()
+            .map(|(url, slot)| match slot.try_lock() {
+                Ok(entry) => {
+                    let entry = entry.as_ref();
+                    let file = entry.map(|e| e.path.clone()).or_else(|| self.cached(&url));
+                    CachedUrl {
+                        bytes: file.as_ref().and_then(|path| fs::metadata(path).ok()).map(|meta| meta.len()),
+                        file: file.map(|path| path.display().to_string()),
+                        etag: entry.and_then(|e| e.etag.clone()),
+                        checked_ms_ago: entry.and_then(|e| e.checked).map(|at| at.elapsed().as_millis() as u64),
+                        fetching: false,
+                        url,
+                    }
+                }
+                Err(_) => CachedUrl { url, file: None, bytes: None, etag: None, checked_ms_ago: None, fetching: true },
+            })
+            .collect();
+        let bytes = fs::read_dir(&self.dir).map_or(0, |dir| {
+            dir.filter_map(|entry| entry.ok()?.metadata().ok()).filter(|meta| meta.is_file()).map(|meta| meta.len()).sum()
+        });
+        UrlCacheState { directory: self.dir.display().to_string(), max_bytes: self.max_bytes, bytes, entries }
+    }
+
+    /// Kopyayı ve ETag'ini siler.
+    pub fn remove(&self, url: &str) -> io::Result<()> {
+        for path in [self.path(url, "wav"), self.path(url, "etag")] {
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn path(&self, url: &str, extension: &str) -> PathBuf {
+        let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+        self.dir.join(format!("{}.{}", &key[..32], extension))
+    }
+
+    /// Önceki çalışmadan kalan kopya ve ETag'i; ilk çalmada sunucuya sorulur.
+    fn on_disk(&self, url: &str) -> Option<Entry> {
+        let path = self.cached(url)?;
+        let etag = fs::read_to_string(self.path(url, "etag")).ok().filter(|etag| !etag.is_empty());
+        Some(Entry { path, etag, checked: None })
+    }
+
+    async fn download(&self, url: &str, etag: Option<&str>) -> Result<Fetched, PlaybackError> {
+        let max_bytes = self.max_bytes;
+        let fetched = async {
+            let response = match s3_object(url) {
+                Some((bucket, key)) => object_store::get_object(bucket, key, etag).await.map_err(|e| fetch_error(url, e))?,
+                None => {
+                    let mut request = Request::get(url);
+                    if let Some(etag) = etag {
+                        request = request.header(IF_NONE_MATCH, etag);
+                    }
+                    let request = request.body(Body::empty()).map_err(|e| fetch_error(url, e))?;
+                    self.client.request(request).await.map_err(|e| fetch_error(url, e))?
+                }
+            };
+            match response.status() {
+                StatusCode::NOT_MODIFIED if etag.is_some() => return Ok(Fetched::NotModified),
+                StatusCode::OK => {}
+                status => return Err(fetch_error(url, format!("server answered {}", status))),
+            }
+            let etag = response.headers().get(ETAG).and_then(|v| v.to_str().ok()).map(str::to_string);
+            let mut body = response.into_body();
+            let mut data = Vec::new();
+            while let Some(chunk) = body.data().await {
+                data.extend_from_slice(&chunk.map_err(|e| fetch_error(url, e))?);
+                if data.len() as u64 > max_bytes {
+                    return Err(fetch_error(url, format!("file exceeds the {}-byte cache limit", max_bytes)));
+                }
+            }
+            Ok(Fetched::Body { data, etag })
+        };
+        tokio::time::timeout(FETCH_TIMEOUT, fetched).await
+            .unwrap_or_else(|_| Err(fetch_error(url, format!("timed out after {:?}", FETCH_TIMEOUT))))
+    }
+
+    /// İndirileni geçici dosyaya yazar, doğrulanırsa kopyanın yerine taşır ve sınırı aşan eski
+    /// kopyaları siler. Dosya işlemleri bloklar.
+    async fn store(&self, url: &str, data: Vec<u8>, etag: Option<String>) -> Result<Entry, PlaybackError> {
+        static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+        let temp = self.dir.join(format!(".{}.tmp", NEXT_TEMP.fetch_add(1, Ordering::Relaxed)));
+        let (path, etag_path) = (self.path(url, "wav"), self.path(url, "etag"));
+        let (owned, dir, max_bytes) = (url.to_string(), self.dir.clone(), self.max_bytes);
+        tokio::task::spawn_blocking(move || {
+            let url = owned.as_str();
+            let stored = write_synced(&temp, &data)
+                .map_err(|e| fetch_error(url, e))
+                .and_then(|()| WavSource::open(&temp.display().to_string(), 0.0)?.read_to_end())
+                .map_err(|e| match e {
+                    PlaybackError::Fetch { .. } => e,
+                    e => fetch_error(url, format!("not a playable prompt: {}", e)),
+                })
+                .and_then(|_| fs::rename(&temp, &path).map_err(|e| fetch_error(url, e)));
+            if let Err(e) = stored {
+                let _ = fs::remove_file(&temp);
+                return Err(e);
+            }
+            let _ = match &etag {
+                Some(etag) => write_synced(&etag_path, etag.as_bytes()),
+                None => fs::remove_file(&etag_path),
+            };
+            evict(&dir, max_bytes, &path);
+            Ok(Entry { path, etag, checked: Some(Instant::now()) })
+        })
+        .await
+        .map_err(|e| fetch_error(url, e))?
+    }
+}
+
+fn fetch_error(url: &str, reason: impl ToString) -> PlaybackError {
+    PlaybackError::Fetch { url: url.to_string(), reason: reason.to_string() }
+}
+
+/// Dizindeki kopyaların toplamı `max_bytes`'ı aşıyorsa en eski indirilenden başlayarak siler;
+/// `keep` silinmez.
+fn evict(dir: &Path, max_bytes: u64, keep: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut copies: Vec<(std::time::SystemTime, u64, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wav"))
+        .filter_map(|path| {
+            let meta = fs::metadata(&path).ok()?;
+            Some((meta.modified().ok()?, meta.len(), path))
+        })
+        .collect();
+    let mut total: u64 = copies.iter().map(|(_, size, _)| size).sum();
+    copies.sort();
+    for (_, size, path) in copies {
+        if total <= max_bytes {
+            break;
+        }
+        if path == keep {
+            continue;
+        }
+        if fs::remove_file(&path).is_ok() {
+            let _ = fs::remove_file(path.with_extension("etag"));
+            info!(file = %path.display(), "Önbellek sınırı aşıldı, eski anons kopyası silindi");
+            total -= size;
+        }
+    }
+}
+
+/// İndirme bitene kadar boş çerçeve veren, sonra yerel kopyayı akıtan kaynak. İndirme oynatma
+/// kurulurken başlar; oynatma erken biterse indirme yine tamamlanıp önbelleğe yazılır.
+pub struct UrlSource {
+    fetching: Option<JoinHandle<Result<PathBuf, PlaybackError>>>,
+    config: PromptConfig,
+    playing: Option<WavSource>,
+}
+
+impl UrlSource {
+    /// Tokio çalışma zamanı içinde çağrılmalı.
+    pub fn new(cache: Arc<UrlCache>, config: &PromptConfig) -> Self {
+        let url = config.path.clone();
+        let fetching = tokio::spawn(async move { cache.fetch(&url).await });
+        UrlSource { fetching: Some(fetching), config: config.clone(), playing: None }
+    }
+}
+
+impl AudioSource for UrlSource {
+    fn next_frame<'a>(&'a mut self, samples: usize, frame: &'a mut Vec<i16>) -> FrameFuture<'a> {
+        Box::pin(async move {
+            if let Some(fetching) = &mut self.fetching {
+                frame.clear();
+                if !fetching.is_finished() {
+                    return Ok(true);
+                }
+                let path = fetching.await.map_err(|e| fetch_error(&self.config.path, e))??;
+                self.fetching = None;
+                let config = PromptConfig { path: path.display().to_string(), ..self.config.clone() };
+                self.playing = Some(WavSource::for_prompt(&config)?);
+            }
+            match &mut self.playing {
+                Some(source) => source.next_frame(samples, frame).await,
+                None => Ok(false),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use std::sync::atomic::AtomicUsize;
+
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Response, Server};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Origin {
+        requests: AtomicUsize,
+        // 0: WAV, 1: 500, 2: WAV olmayan gövde.
+        mode: AtomicUsize,
+    }
+
+    fn wav(samples: usize) -> Vec<u8> {
+        let spec = hound::WavSpec { channels: 1, sample_rate: 8000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut cursor = io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for _ in 0..samples {
+            writer.write_sample(1000i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    /// `/<n>` yolunda n örneklik bir WAV sunar; ETag yola göredir.
+    async fn origin() -> (SocketAddr, Arc<Origin>) {
+        let origin = Arc::new(Origin::default());
+        let state = origin.clone();
+        let make = make_service_fn(move |_| {
+            let state = state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let state = state.clone();
+                    async move {
+                        state.requests.fetch_add(1, Ordering::Relaxed);
+                        let etag = format!("\"{}\"", request.uri().path());
+                        let response = match state.mode.load(Ordering::Relaxed) {
+                            1 => Response::builder().status(500).body(Body::empty()),
+                            2 => Response::builder().header(ETAG, "\"junk\"").body(Body::from("not a wav")),
+                            _ if request.headers().get(IF_NONE_MATCH).is_some_and(|v| v == etag.as_str()) => {
+                                Response::builder().status(304).body(Body::empty())
+                            }
+                            _ => {
+                                let samples = request.uri().path()[1..].parse().unwrap();
+                                Response::builder().header(ETAG, etag).body(Body::from(wav(samples)))
+                            }
+                        };
+                        Ok::<_, Infallible>(response.unwrap())
+                    }
+                }))
+            }
+        });
+        let server = Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(make);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        (addr, origin)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("media-url-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn copies_are_revalidated_with_etag_and_served_stale_when_the_origin_fails() {
+        let (addr, origin) = origin().await;
+        let dir = temp_dir("revalidate");
+        let cache = UrlCache::new(&dir, 1 << 20, Duration::ZERO).unwrap();
+        let url = format!("http://{}/800", addr);
+
+        let path = cache.fetch(&url).await.unwrap();
+        assert_eq!(WavSource::open(path.to_str().unwrap(), 0.0).unwrap().read_to_end().unwrap().len(), 800);
+        // Aralık sıfır: her çalma sorar, sunucu 304 döner ve kopya korunur.
+        assert_eq!(cache.fetch(&url).await.unwrap(), path);
+        assert_eq!(origin.requests.load(Ordering::Relaxed), 2);
+
+        // Sunucu hata verirse ya da bozuk dosya dönerse eski kopya çalınır.
+        origin.mode.store(1, Ordering::Relaxed);
+        assert_eq!(cache.fetch(&url).await.unwrap(), path);
+        origin.mode.store(2, Ordering::Relaxed);
+        assert_eq!(cache.fetch(&url).await.unwrap(), path);
+        assert_eq!(fs::read(&path).unwrap(), wav(800));
+        // Kopyası olmayan adres hata verir; geçici dosya kalmaz.
+        let error = cache.fetch(&format!("http://{}/160", addr)).await.unwrap_err();
+        assert!(error.to_string().contains("not a playable prompt"), "{}", error);
+        assert!(fs::read_dir(&dir).unwrap().all(|e| !e.unwrap().file_name().to_string_lossy().ends_with(".tmp")));
+
+        // Yeniden başlatmadan sonra diskteki kopya ve ETag'i kullanılır.
+        origin.mode.store(0, Ordering::Relaxed);
+        let restarted = UrlCache::new(&dir, 1 << 20, Duration::from_secs(60)).unwrap();
+        let before = origin.requests.load(Ordering::Relaxed);
+        assert_eq!(restarted.fetch(&url).await.unwrap(), path);
+        assert_eq!(restarted.fetch(&url).await.unwrap(), path);
+        assert_eq!(origin.requests.load(Ordering::Relaxed), before + 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn size_cap_evicts_the_oldest_copy_and_rejects_oversized_files() {
+        let (addr, _origin) = origin().await;
+        let dir = temp_dir("evict");
+        // Bir WAV 44 + 2 * 4000 bayt; sınır ikisini birden tutmaz.
+        let cache = UrlCache::new(&dir, 10_000, Duration::from_secs(60)).unwrap();
+        let (first, second) = (format!("http://{}/4000", addr), format!("http://{}/4001", addr));
+        let kept = cache.fetch(&first).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        cache.fetch(&second).await.unwrap();
+        assert!(!kept.exists());
+        assert_eq!((cache.cached(&first), cache.cached(&second).is_some()), (None, true));
+
+        let error = cache.fetch(&format!("http://{}/8000", addr)).await.unwrap_err();
+        assert!(error.to_string().contains("10000-byte cache limit"), "{}", error);
+        assert!(check_url("https://prompts.example/a.wav").unwrap_err().contains("TLS"));
+        assert!(check_url("ftp://prompts.example/a.wav").is_err());
+        assert_eq!((check_url("s3://prompts/tr/welcome.wav"), check_url("s3://prompts").is_err()), (Ok(()), true));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+// Gönderim tarafında sessizlik bastırma: giden çerçevenin seviyesi eşiğin altında kaldıkça ve
+// bekleme süresi (hangover) dolunca ses gönderilmez; yerine ölçülen gürültü seviyesini taşıyan tek
+// bir RFC 3389 konfor gürültüsü paketi gider. Konuşma dönünce ilk paket işaret bitiyle, yeni bir
+// konuşma dilimi olarak gönderilir (RFC 3551 4.1). Akışın zaman damgası saatle ilerlediği için
+// bastırılan aralık karşı uca boşluk olarak görünür.
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::config::SilenceSuppressionConfig;
+
+/// RFC 3389 seviye alanının alt sınırı.
+pub const MIN_LEVEL_DBOV: i32 = -127;
+
+/// Çerçevenin RMS seviyesi (dBov); tam ölçek 0 dBov, sessiz çerçeve `MIN_LEVEL_DBOV`.
+pub fn level_dbov(pcm: &[i16]) -> f64 {
+    let energy = pcm.iter().map(|&s| (s as f64) * (s as f64)).sum::<f64>() / pcm.len().max(1) as f64;
+    let level = 10.0 * (energy / (i16::MAX as f64 * i16::MAX as f64)).log10();
+    level.clamp(MIN_LEVEL_DBOV as f64, 0.0)
+}
+
+/// Bir giden çerçeve için karar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frame {
+    /// Ses gönderilir; sessizlikten sonraki ilk çerçevede `marker` kurulur.
+    Send { marker: bool },
+    /// Sessizlik başladı: ses yerine bu seviyede (-dBov) tek bir CN paketi gönderilir.
+    ComfortNoise { level: u8 },
+    /// Sessizlik sürüyor; hiçbir şey gönderilmez.
+    Suppress,
+}
+
+/// Oturumun giden akışının bastırma durumu; akışa ses yazan bütün göndericiler paylaşır.
+#[derive(Debug)]
+pub struct Suppressor {
+    threshold_dbov: f64,
+    hangover: Duration,
+    announcements: bool,
+    // Eşiği aşan son çerçeve; yoksa akış sessizlikle başlar ve hemen bastırılır.
+    last_voice: Option<Instant>,
+    silent: bool,
+}
+
+impl Suppressor {
+    pub fn new(config: &SilenceSuppressionConfig) -> Self {
+        Suppressor {
+            threshold_dbov: config.threshold_dbov as f64, hangover: config.hangover(), announcements: config.announcements,
+            last_voice: None, silent: false,
+        }
+    }
+
+    /// `now`'da gönderilecek `pcm` çerçevesinin kaderi. `announcement` çerçeveleri
+    /// `silence_suppression.announcements` kapalıyken konuşma sayılır; anonslar kırpılmaz.
+    pub fn decide(&mut self, pcm: &[i16], announcement: bool, now: Instant) -> Frame {
+        let level = level_dbov(pcm);
+        if level >= self.threshold_dbov || (announcement && !self.announcements) {
+            self.last_voice = Some(now);
+            return Frame::Send { marker: std::mem::replace(&mut self.silent, false) };
+        }
+        if self.silent {
+            return Frame::Suppress;
+        }
+        if self.last_voice.is_some_and(|at| now.saturating_duration_since(at) < self.hangover) {
+            return Frame::Send { marker: false };
+        }
+        self.silent = true;
+        Frame::ComfortNoise { level: (-level).round() as u8 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silence_after_hangover_sends_one_noise_packet_and_speech_resumes_with_marker() {
+        let config = SilenceSuppressionConfig { enabled: true, threshold_dbov: -50, hangover_ms: 40, announcements: false };
+        let mut suppressor = Suppressor::new(&config);
+        let (speech, noise) = (vec![3000i16; 160], vec![30i16; 160]);
+        let start = Instant::now();
+        let at = |frame: u64| start + Duration::from_millis(20 * frame);
+
+        let decisions: Vec<Frame> = [&speech, &noise, &noise, &noise, &noise, &speech].iter().enumerate()
+            .map(|(i, pcm)| suppressor.decide(pcm, false, at(i as u64)))
+            .collect();
+        // 30/32767 yaklaşık -61 dBov.
+        assert_eq!(decisions, [
+            Frame::Send { marker: false },
+            Frame::Send { marker: false },
+            Frame::ComfortNoise { level: 61 },
+            Frame::Suppress,
+            Frame::Suppress,
+            Frame::Send { marker: true },
+        ]);
+        // Anons sessizliği kırpılmaz; sessizlikten sonra yeni bir konuşma dilimi başlatır.
+        assert_eq!(suppressor.decide(&noise, false, at(6 + 2)), Frame::ComfortNoise { level: 61 });
+        assert_eq!(suppressor.decide(&noise, true, at(9)), Frame::Send { marker: true });
+        assert_eq!(level_dbov(&[0; 160]), MIN_LEVEL_DBOV as f64);
+        assert_eq!(level_dbov(&[i16::MAX; 160]), 0.0);
+    }
+}
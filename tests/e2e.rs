@@ -0,0 +1,1425 @@
+// Uçtan uca: gerçek gRPC sunucusu üzerinden port tahsisi, loopback'te ilk RTP paketi ve
+// karşılama anonsunun RTP paketleri olarak geri gelmesi, oturum istatistiklerinin sorgulanması
+// ve tahsiste anlaşılan RED, köprülenen bacaklar arasında tuş aktarımı, gelen sesin kaydı ve
+// görüşme ortasında açılan, eşzamanlılığı sınırlı çözülmüş ses dökümü, aynı servisin Unix
+// soketinden sunulması, geri yansıyan kendi paketlerimizin atılması, karşılamasız tahsis, anons
+// hatalarının istatistiklere yansıması, tahsiste verilen en uzun oturum süresi, RFC 4571 TCP
+// taşıması, RFC 6464 ses seviyesi uzantısı, sabitlenmiş akış değerleriyle tests/golden altındaki
+// pcap kaydına uyan paketler (`MEDIA_WRITE_GOLDEN=1` kaydı yeniden yazar), anons deposu
+// (yükleme, listeleme, silme), tahsisi bekletmeden indirilip önbellekten çalınan URL'li
+// anonslar, kiracılara ayrılmış port aralıkları, çalışan derlemenin bilgisi, gizli değerleri
+// maskelenmiş iç durum dökümü ve kapanışta giden SR, SDES CNAME ve BYE, parça anonslarından
+// okunan rakam dizileri ve reddedilen, sıraya alınan ya da çalanı kesen oynatmalar ile kuyruğu
+// boşaltan durdurma ve gelen paket beklemeden çalan, hedefini yalnızca simetrik RTP ile
+// değiştiren yalnızca gönderen oturumlar ve diskte eksik ya da bozuk anons dosyalarını
+// raporlayan yeniden doğrulama, etiketli oturumların listelenip etiketle süzülmesi ve geçersiz
+// etiketlerin reddi, uzak teklifin adresini hedef yapan SDP cevabı ve kendi adresine bağlanıp
+// onu duyuran çok bacaklı sunucu arayüzleri, node'da izin verilmedikçe açılmayan, giden
+// paketleri düşüren ağ bozulması, çalma ortasında susturulup köprü kaldırılınca açılan giden ses ve
+// dinlenen oturumun karışımını kendi codec'iyle alıp onunla kapanan dinleme bacakları.
+mod support;
+
+use std::time::Duration;
+
+use media::media::{AllocatePortRequest, BridgeSessionsRequest, DumpStateRequest, GetSessionStatsRequest, UnbridgeSessionsRequest};
+use media::media::{GenerateSdpRequest, Impairment, SdpRole, SetImpairmentRequest, SetMuteRequest, TapSessionRequest, UntapSessionRequest};
+use media::media::{ExportSessionsRequest, ImportSessionsRequest, ListSessionsRequest, MediaDirection, QueryPortHistoryRequest, ReleasePortRequest, SetDirectionRequest};
+use media::media::{GetServerStatusRequest, GetVersionRequest, PlayAnnouncementRequest, StartAudioDumpRequest, StartRecordingRequest, StopRecordingRequest};
+use media::rtcp;
+use media::rtp::{RtpPacket, MAX_PACKET_LEN};
+use support::golden::Capture;
+use support::{assert_contiguous, assert_paced, test_settings, RtpPeer, TestServer, RTP_PORTS};
+
+#[tokio::test]
+async fn allocated_port_plays_welcome_announcement_over_loopback() {
+    let mut server = TestServer::start().await;
+    let reply = server.allocate("pcmu", "e2e").await;
+    assert_eq!(reply.codec, "pcmu");
+    assert_eq!(reply.payload_type, 0);
+    assert!(RTP_PORTS.contains(&(reply.port as u16)));
+    assert_eq!(server.session_count(), 1);
+
+    // İlk paket uzak adresi kilitler ve karşılama anonsunu başlatır.
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+
+    let packets = peer.recv_many(10).await;
+    for packet in &packets {
+        assert_eq!(packet.from.port() as u32, reply.port);
+        assert_eq!(packet.version, 2);
+        assert_eq!(packet.payload_type, 0);
+        assert_eq!(packet.payload.len(), 160);
+    }
+    assert_contiguous(&packets, 160);
+    assert_paced(&packets, Duration::from_millis(20));
+    // Cevaptaki SSRC ve başlangıç değerleri akışın ilk paketiyle aynı.
+    let first = &packets[0];
+    assert_eq!((first.ssrc, first.sequence as u32, first.timestamp), (reply.ssrc, reply.initial_sequence, reply.initial_timestamp));
+}
+
+#[tokio::test]
+async fn session_stats_report_inbound_traffic() {
+    let mut server = TestServer::start().await;
+    let reply = server.allocate("pcmu", "e2e-stats").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    for _ in 0..3 {
+        peer.send_packet().await;
+    }
+    peer.recv_many(2).await;
+
+    let stats = server.client
+        .get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() })
+        .await
+        .expect("GetSessionStats")
+        .into_inner();
+    assert_eq!((stats.packets_received, stats.packets_lost, stats.packets_malformed), (3, 0, 0));
+    assert!(stats.packets_sent >= 2);
+    // Birkaç paketlik akıştan sapma tahmini yapılmaz.
+    assert_eq!(stats.clock_skew_ppm, None);
+    // Kayıpsız, jitter'sız G.711.
+    assert!(stats.mos.unwrap() > 4.3, "{:?}", stats.mos);
+    assert_eq!((stats.ssrc, stats.remote_ssrc), (reply.ssrc, Some(0x1234_5678)));
+
+    let missing = server.client.get_session_stats(GetSessionStatsRequest { port: 1, ..Default::default() }).await.unwrap_err();
+    assert_eq!(missing.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn reflected_own_packets_are_dropped_without_relatching() {
+    let mut settings = support::test_settings();
+    settings.announcement.replay_welcome = true;
+    let mut server = TestServer::with_settings(settings).await;
+    let reply = server.allocate("pcmu", "e2e-reflect").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+
+    // Karşılama paketleri ikinci bir adresten aynen geri yollanır (yansıtan SBC).
+    let mut buf = [0u8; 2048];
+    let mut sent = Vec::new();
+    while sent.len() < 5 {
+        let (len, _) = tokio::time::timeout(Duration::from_secs(2), peer.sock.recv_from(&mut buf)).await.expect("welcome packet").unwrap();
+        sent.push(buf[..len].to_vec());
+    }
+    let mirror = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    for packet in &sent {
+        mirror.send_to(packet, peer.remote).await.unwrap();
+    }
+
+    // Akış yansıtana kaymaz, karşılama yeniden başlamaz: ses ilk uca kesintisiz gitmeye devam eder.
+    let after = peer.recv_many(5).await;
+    assert_contiguous(&after, 160);
+    assert!(tokio::time::timeout(Duration::from_millis(200), mirror.recv_from(&mut buf)).await.is_err(), "media followed the reflection");
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.expect("GetSessionStats").into_inner();
+    assert_eq!((stats.packets_reflected, stats.remote_ssrc), (5, Some(0x1234_5678)));
+    assert_eq!(stats.packets_received, 6);
+}
+
+#[tokio::test]
+async fn red_payload_type_from_allocation_wraps_outbound_audio() {
+    let mut server = TestServer::start().await;
+    let request = |red_payload_type| AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-red".to_string(), red_payload_type, ..Default::default()
+    };
+    let rejected = server.client.allocate_port(request(8)).await.unwrap_err();
+    assert_eq!(rejected.code(), tonic::Code::InvalidArgument);
+    assert_eq!(server.session_count(), 0);
+
+    let reply = server.client.allocate_port(request(99)).await.expect("AllocatePort").into_inner();
+    // Yanıttaki yük tipi codec'indir; RED yük tipini sinyalleşme zaten biliyor.
+    assert_eq!(reply.payload_type, 0);
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+
+    let packets = peer.recv_many(3).await;
+    assert!(packets.iter().all(|p| p.payload_type == 99));
+    // İlk paket yalnızca birincil yükü taşır: 1 baytlık son blok başlığı + 160 bayt.
+    assert_eq!(packets[0].payload.len(), 1 + 160);
+    // Sonrakiler bir önceki çerçeveyi de taşır: 4 baytlık blok başlığı + yedek.
+    assert_eq!(packets[1].payload.len(), 4 + 1 + 160 * 2);
+}
+
+#[tokio::test]
+async fn bridged_legs_carry_digits_in_each_legs_payload_type() {
+    let mut server = TestServer::start().await;
+    let allocate = |dtmf_payload_type| {
+        let request = AllocatePortRequest {
+            codec: "pcmu".to_string(), call_id: "e2e-bridge".to_string(), dtmf_payload_type, ..Default::default()
+        };
+        let mut client = server.client.clone();
+        async move { client.allocate_port(request).await.expect("AllocatePort").into_inner() }
+    };
+    let (a, b) = (allocate(101).await, allocate(96).await);
+    let (mut peer_a, mut peer_b) = (RtpPeer::connect(a.port).await, RtpPeer::connect(b.port).await);
+    peer_a.send_packet().await;
+    peer_b.send_packet().await;
+    server.client.bridge_sessions(BridgeSessionsRequest { port_a: a.port, port_b: b.port, ..Default::default() }).await.expect("BridgeSessions");
+
+    // '5' tuşunun başlangıç paketi, A'nın anlaştığı 101 ile.
+    let event = RtpPacket { marker: true, ..RtpPacket::new(101, 2, 320, 0x1234_5678, &[5, 10, 0, 160]) };
+    let mut wire = [0u8; MAX_PACKET_LEN];
+    let len = event.write(&mut wire).unwrap();
+    peer_a.sock.send_to(&wire[..len], peer_a.remote).await.unwrap();
+
+    // B'ye karşılama anonsunun arasında, B'nin anlaştığı 96 ile ulaşır.
+    let digit = loop {
+        let packet = peer_b.recv_rtp().await;
+        if packet.payload_type != 0 {
+            break packet;
+        }
+    };
+    assert_eq!((digit.payload_type, digit.marker, digit.payload), (96, true, vec![5, 10, 0, 160]));
+
+    let reply = server.client.unbridge_sessions(UnbridgeSessionsRequest { port: b.port, ..Default::default() }).await.expect("UnbridgeSessions");
+    assert_eq!(reply.into_inner().peer_port, a.port);
+    let again = server.client.unbridge_sessions(UnbridgeSessionsRequest { port: a.port, ..Default::default() }).await.unwrap_err();
+    assert_eq!(again.code(), tonic::Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn outbound_mute_silences_playback_without_breaking_the_stream_and_clears_on_unbridge() {
+    let mut server = TestServer::start().await;
+    let reply = server.allocate("pcmu", "e2e-mute").await;
+    let other = server.allocate("pcmu", "e2e-mute-other").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    let before = peer.recv_rtp().await;
+
+    server.client.set_mute(SetMuteRequest { port: reply.port, outbound: true, ..Default::default() }).await.expect("SetMute");
+    // Susturmadan önce kodlanmış paketler yolda olabilir; ilk sessiz pakete kadar okunur.
+    let mut packets = vec![before];
+    while packets.last().unwrap().payload.iter().any(|&byte| byte != 0xFF) {
+        packets.push(peer.recv_rtp().await);
+    }
+    let silent = packets.len() - 1;
+    packets.extend(peer.recv_many(5).await);
+    // Anons sürüyor: paketler µ-law sessizliği taşır, sıra numarası ve zaman damgası kesintisiz.
+    assert!(packets[silent..].iter().all(|packet| packet.payload.len() == 160 && packet.payload.iter().all(|&byte| byte == 0xFF)));
+    assert_contiguous(&packets, 160);
+
+    let listed = server.client.list_sessions(ListSessionsRequest::default()).await.expect("ListSessions").into_inner();
+    let summary = listed.sessions.iter().find(|session| session.port == reply.port).unwrap();
+    assert_eq!((summary.inbound_muted, summary.outbound_muted), (false, true));
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.expect("GetSessionStats").into_inner();
+    assert_eq!((stats.inbound_muted, stats.outbound_muted, stats.packets_received), (false, true, 1));
+
+    server.client.bridge_sessions(BridgeSessionsRequest { port_a: reply.port, port_b: other.port, ..Default::default() }).await.expect("BridgeSessions");
+    server.client.unbridge_sessions(UnbridgeSessionsRequest { port: other.port, ..Default::default() }).await.expect("UnbridgeSessions");
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.expect("GetSessionStats").into_inner();
+    assert_eq!((stats.inbound_muted, stats.outbound_muted), (false, false));
+    let missing = server.client.set_mute(SetMuteRequest { port: 1, inbound: true, outbound: true, ..Default::default() }).await.unwrap_err();
+    assert_eq!(missing.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn tap_leg_hears_the_call_in_its_own_codec_and_ends_with_it() {
+    let mut server = TestServer::start().await;
+    let observed = server.client.allocate_port(AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-tap".to_string(), max_duration_s: 1, ..Default::default()
+    }).await.expect("AllocatePort").into_inner();
+    let supervisor = RtpPeer::connect(0).await;
+    let tap = server.client.allocate_port(AllocatePortRequest {
+        codec: "pcma".to_string(), call_id: "e2e-tap-supervisor".to_string(), skip_welcome: true, remote_address: supervisor.sock.local_addr().unwrap().to_string(), ..Default::default()
+    }).await.expect("AllocatePort").into_inner();
+    let itself = server.client.tap_session(TapSessionRequest { port: observed.port, tap_port: observed.port, ..Default::default() }).await.unwrap_err();
+    assert_eq!(itself.code(), tonic::Code::InvalidArgument);
+    server.client.tap_session(TapSessionRequest { port: observed.port, tap_port: tap.port, ..Default::default() }).await.expect("TapSession");
+
+    // Arayanın sesi ve ona çalınan karşılama, dinleme bacağının akışında PCMA olarak gelir.
+    let mut peer = RtpPeer::connect(observed.port).await;
+    peer.send_packet().await;
+    peer.recv_rtp().await;
+    let heard = supervisor.recv_many(5).await;
+    assert!(heard.iter().all(|packet| (packet.from.port() as u32, packet.payload_type, packet.ssrc, packet.payload.len()) == (tap.port, 8, tap.ssrc, 160)));
+    // Karışım bir yönü beklerken zaman damgası saati izleyerek atlayabilir; sıra numarası kesintisiz.
+    for pair in heard.windows(2) {
+        assert_eq!(pair[1].sequence, pair[0].sequence.wrapping_add(1));
+        assert!(pair[1].timestamp.wrapping_sub(pair[0].timestamp) >= 160);
+    }
+
+    let listed = server.client.list_sessions(ListSessionsRequest::default()).await.expect("ListSessions").into_inner();
+    let flags: Vec<(u32, Vec<u32>, u32)> = listed.sessions.iter().map(|session| (session.port, session.taps.clone(), session.tapping)).collect();
+    assert!(flags.contains(&(observed.port, vec![tap.port], 0)) && flags.contains(&(tap.port, vec![], observed.port)), "{flags:?}");
+    let played = server.client.play_announcement(PlayAnnouncementRequest {
+        port: tap.port, name: "welcome".to_string(), ..Default::default()
+    }).await.unwrap_err();
+    assert_eq!(played.code(), tonic::Code::FailedPrecondition);
+    let bridged = server.client.bridge_sessions(BridgeSessionsRequest { port_a: tap.port, port_b: observed.port, ..Default::default() }).await.unwrap_err();
+    assert_eq!(bridged.code(), tonic::Code::FailedPrecondition);
+
+    // Dinlenen oturum en uzun süresinde kapanınca dinleme bacağı da kapanır.
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.session_count() > 0 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }).await.expect("tap leg torn down with the observed session");
+    let untapped = server.client.untap_session(UntapSessionRequest { tap_port: tap.port, ..Default::default() }).await.unwrap_err();
+    assert_eq!(untapped.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn recording_writes_inbound_audio_and_reports_files_on_stop() {
+    let mut settings = support::test_settings();
+    let directory = std::env::temp_dir().join(format!("media-e2e-recording-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    settings.recording.directory = directory.display().to_string();
+    let mut server = TestServer::with_settings(settings).await;
+    let reply = server.allocate("pcmu", "e2e-rec").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    peer.recv_rtp().await;
+
+    let started = server.client
+        .start_recording(StartRecordingRequest { port: reply.port, name: "{call_id}/{port}".to_string(), ..Default::default() })
+        .await
+        .expect("StartRecording")
+        .into_inner();
+    let expected = directory.join("e2e-rec").join(format!("{}.wav", reply.port));
+    assert_eq!(started.path, expected.display().to_string());
+    let busy = server.client
+        .start_recording(StartRecordingRequest { port: reply.port, ..Default::default() })
+        .await
+        .unwrap_err();
+    assert_eq!(busy.code(), tonic::Code::AlreadyExists);
+
+    for _ in 0..5 {
+        peer.send_packet().await;
+    }
+    // Paketler dinleyici görevine ulaşsın diye birkaç giden paket beklenir.
+    peer.recv_many(3).await;
+    let stopped = server.client
+        .stop_recording(StopRecordingRequest { port: reply.port, ..Default::default() })
+        .await
+        .expect("StopRecording")
+        .into_inner();
+    assert_eq!(stopped.paths, std::slice::from_ref(&started.path));
+    assert_eq!(stopped.duration_ms, 100);
+    assert_eq!(hound::WavReader::open(&expected).unwrap().len(), 5 * 160);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn audio_dump_starts_mid_call_is_capped_and_closes_with_the_session() {
+    let mut settings = support::test_settings();
+    let directory = std::env::temp_dir().join(format!("media-e2e-audio-dump-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    settings.audio_dump.directory = directory.display().to_string();
+    settings.audio_dump.max_sessions = 1;
+    settings.timers.media_timeout_s = 1;
+    let mut server = TestServer::with_settings(settings).await;
+    let reply = server.allocate("pcmu", "e2e-dump").await;
+    let other = server.allocate("pcmu", "e2e-dump-other").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    peer.recv_rtp().await;
+
+    // Görüşme ortasında açılır; tekrar istemek aynı dosyaları döner.
+    let dump = |port| StartAudioDumpRequest { port, ..Default::default() };
+    let started = server.client.start_audio_dump(dump(reply.port)).await.expect("StartAudioDump").into_inner();
+    let again = server.client.start_audio_dump(dump(reply.port)).await.expect("StartAudioDump").into_inner();
+    assert_eq!((&again.inbound_path, &again.outbound_path), (&started.inbound_path, &started.outbound_path));
+    let full = server.client.start_audio_dump(dump(other.port)).await.unwrap_err();
+    assert_eq!(full.code(), tonic::Code::ResourceExhausted);
+
+    for _ in 0..5 {
+        peer.send_packet().await;
+    }
+    peer.recv_many(3).await;
+    // Medya zaman aşımıyla oturum kapanır, dosyalar kapanır ve döküm yeri boşalır.
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while server.session_count() > 1 {
+        assert!(tokio::time::Instant::now() < deadline, "session did not time out");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    server.client.start_audio_dump(dump(other.port)).await.expect("StartAudioDump after the slot is freed");
+    let decoded = loop {
+        match hound::WavReader::open(&started.inbound_path) {
+            Ok(reader) if reader.len() == 5 * 160 => break reader.into_samples::<i16>().map(Result::unwrap).collect::<Vec<_>>(),
+            _ if tokio::time::Instant::now() < deadline => tokio::time::sleep(Duration::from_millis(50)).await,
+            other => panic!("inbound dump not finalized: {:?}", other.map(|r| r.len())),
+        }
+    };
+    // Uzak ucun 0xFF PCMU yükü sessizliktir.
+    assert!(decoded.iter().all(|&sample| sample == 0));
+    let outbound = hound::WavReader::open(&started.outbound_path).unwrap().len();
+    assert!(outbound >= 3 * 160 && outbound % 160 == 0, "{outbound} outbound samples");
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn unix_socket_serves_the_same_sessions_as_tcp() {
+    let server = TestServer::start().await;
+    let path = std::env::temp_dir().join(format!("media-e2e-{}.sock", std::process::id()));
+    let (socket, mut unix_client) = server.serve_unix(&path).await;
+
+    let reply = unix_client
+        .allocate_port(AllocatePortRequest { codec: "pcmu".to_string(), call_id: "e2e-uds".to_string(), ..Default::default() })
+        .await
+        .expect("AllocatePort over UDS")
+        .into_inner();
+    assert_eq!(server.session_count(), 1);
+    // Aynı oturum TCP istemcisinden de görülür.
+    let mut tcp_client = server.client.clone();
+    let stats = tcp_client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.expect("GetSessionStats over TCP");
+    assert_eq!(stats.into_inner().packets_received, 0);
+
+    drop(socket);
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn server_status_reports_bound_addresses_and_load() {
+    let mut server = TestServer::start().await;
+    server.allocate("pcmu", "e2e-status").await;
+
+    let status = server.client.get_server_status(GetServerStatusRequest {}).await.expect("GetServerStatus").into_inner();
+    assert_eq!(status.listen_addresses, [server.grpc_addr.to_string()]);
+    assert_eq!(status.active_sessions, 1);
+    // [admission] kapalı: örnek alınmaz, node hep normal.
+    assert_eq!((status.admission_state.as_str(), status.admission_cause.as_str(), status.cpu_percent), ("normal", "", None));
+    // Karşılama anonsu başlangıçta önbelleğe sabitlendi.
+    let cache = status.announcement_cache.expect("announcement cache status");
+    assert!(cache.pinned_entries >= 1 && cache.bytes > 0, "{:?}", cache);
+    assert_eq!(cache.max_bytes, 64 * 1024 * 1024);
+}
+
+#[tokio::test]
+async fn version_reports_the_build_embedded_at_compile_time() {
+    let mut server = TestServer::start().await;
+    let version = server.client.get_version(GetVersionRequest {}).await.expect("GetVersion").into_inner();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+    assert!(!version.git_commit.is_empty());
+    assert!(version.build_timestamp.ends_with('Z'), "{}", version.build_timestamp);
+    assert_eq!(version.features.contains(&"metrics".to_string()), cfg!(feature = "metrics"));
+    assert!(version.schema_version >= 1);
+}
+
+#[tokio::test]
+async fn state_dump_lists_sessions_and_redacts_secrets() {
+    let mut settings = support::test_settings();
+    settings.object_storage.secret_access_key = "dump-secret".to_string();
+    let mut server = TestServer::with_settings(settings).await;
+    let reply = server.allocate("pcmu", "e2e-dump").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    peer.recv_rtp().await;
+
+    let json = server.client.dump_state(DumpStateRequest {}).await.expect("DumpState").into_inner().json;
+    assert!(!json.contains("dump-secret"));
+    let dump: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(dump["config"]["object_storage"]["secret_access_key"], "<redacted>");
+    assert_eq!(dump["port_pool"]["active_sessions"], 1);
+    let session = &dump["sessions"][0];
+    assert_eq!((session["port"].as_u64(), session["call_id"].as_str()), (Some(reply.port as u64), Some("e2e-dump")));
+    assert_eq!(session["remote_addr"].as_str(), Some(peer.sock.local_addr().unwrap().to_string().as_str()));
+    assert_eq!(session["playing"], "welcome");
+    assert!(dump["prompts"].as_array().unwrap().iter().any(|prompt| prompt["name"] == "welcome"));
+}
+
+#[tokio::test]
+async fn allocation_rate_limit_rejects_before_binding_a_port() {
+    let mut settings = support::test_settings();
+    settings.rate_limit.allocations_per_s = 1;
+    settings.rate_limit.allocation_burst = 2;
+    let mut server = TestServer::with_settings(settings).await;
+    server.allocate("pcmu", "e2e-limit-1").await;
+    server.allocate("pcmu", "e2e-limit-2").await;
+
+    let request = AllocatePortRequest { codec: "pcmu".to_string(), call_id: "e2e-limit-3".to_string(), ..Default::default() };
+    let status = server.client.allocate_port(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+    let pushback: u64 = status.metadata().get("grpc-retry-pushback-ms").unwrap().to_str().unwrap().parse().unwrap();
+    assert!(pushback > 0 && pushback <= 1000, "{}", pushback);
+    assert_eq!(server.session_count(), 2);
+}
+
+#[tokio::test]
+async fn request_id_is_kept_on_the_session_or_generated_and_returned() {
+    let mut server = TestServer::start().await;
+    let allocate = |call_id: &str| AllocatePortRequest { codec: "pcmu".to_string(), call_id: call_id.to_string(), ..Default::default() };
+
+    let mut request = tonic::Request::new(allocate("e2e-rid-1"));
+    request.metadata_mut().insert("x-request-id", "sip-proxy-42".parse().unwrap());
+    let response = server.client.allocate_port(request).await.expect("AllocatePort");
+    assert_eq!(response.metadata().get("x-request-id").unwrap(), "sip-proxy-42");
+    let port = response.into_inner().port as u16;
+    assert_eq!(server.sessions.lock().unwrap()[&port].request_id, "sip-proxy-42");
+
+    // Başlık yoksa yerelde üretilen kimlik çağırana döner.
+    let response = server.client.allocate_port(allocate("e2e-rid-2")).await.expect("AllocatePort");
+    let generated = response.metadata().get("x-request-id").unwrap().to_str().unwrap().to_string();
+    assert_eq!(generated.len(), 32);
+    let port = response.into_inner().port as u16;
+    assert_eq!(server.sessions.lock().unwrap()[&port].request_id, generated);
+}
+
+#[tokio::test]
+async fn skipped_welcome_stays_silent_and_playback_failures_reach_session_stats() {
+    let mut settings = support::test_settings();
+    let file = std::env::temp_dir().join(format!("media-e2e-prompt-{}.wav", std::process::id()));
+    std::fs::copy("audio/processed/standard/welcome.wav", &file).unwrap();
+    settings.announcement.prompts.insert("moved".to_string(), media::config::PromptConfig {
+        path: file.display().to_string(), gain_db: 0.0, looped: false, language: None, preload: false, languages: Default::default(),
+    });
+    let mut server = TestServer::with_settings(settings).await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-no-welcome".to_string(), skip_welcome: true, ..Default::default()
+    };
+    let port = server.client.allocate_port(request).await.expect("AllocatePort").into_inner().port;
+    let mut peer = RtpPeer::connect(port).await;
+    peer.send_packet().await;
+
+    let stats = |mut client: media::media::media_manager_client::MediaManagerClient<tonic::transport::Channel>| async move {
+        client.get_session_stats(GetSessionStatsRequest { port, ..Default::default() }).await.expect("GetSessionStats").into_inner()
+    };
+    while stats(server.client.clone()).await.packets_received == 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(stats(server.client.clone()).await.packets_sent, 0, "no welcome on a skip_welcome leg");
+
+    // Dosya yüklemeden sonra kayboldu: çağıran hatayı hem cevapta hem oturum istatistiğinde görür.
+    std::fs::remove_file(&file).unwrap();
+    let error = server.client.play_announcement(PlayAnnouncementRequest { port, name: "moved".to_string(), ..Default::default() }).await.unwrap_err();
+    assert!(error.message().contains("failed to open WAV file"), "{}", error.message());
+    let stats = stats(server.client.clone()).await;
+    assert_eq!((stats.announcements_failed, stats.playback_failure.as_str()), (1, "file_missing"));
+}
+
+#[tokio::test]
+async fn allocation_can_override_the_maximum_session_duration() {
+    let mut server = TestServer::start().await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-max-duration".to_string(), max_duration_s: 1, ..Default::default()
+    };
+    server.client.allocate_port(request).await.expect("AllocatePort");
+    server.allocate("pcmu", "e2e-unlimited").await;
+    assert_eq!(server.session_count(), 2);
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    // Yalnızca süre sınırı istenen oturum kapandı; node varsayılanı sınırsız.
+    assert_eq!(server.session_count(), 1);
+}
+
+#[tokio::test]
+async fn teardown_sends_sender_report_cname_and_bye_with_the_reason() {
+    let mut server = TestServer::start().await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-bye".to_string(), max_duration_s: 1, ..Default::default()
+    };
+    let reply = server.client.allocate_port(request).await.expect("AllocatePort").into_inner();
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    let ssrc = peer.recv_rtp().await.ssrc;
+
+    // Karşılama anonsu çalarken süre dolar; RTCP portuna birleşik RTCP gelir.
+    let rtcp = peer.recv_rtcp(Duration::from_secs(3)).await;
+    // SR, içinde yolladığımız akış için bir alım raporu bloğu.
+    assert_eq!((rtcp[0], rtcp[1]), (0x81, 200));
+    assert_eq!(u32::from_be_bytes(rtcp[4..8].try_into().unwrap()), ssrc);
+    assert_eq!(u32::from_be_bytes(rtcp[28..32].try_into().unwrap()), 0x1234_5678);
+    let sdes = &rtcp[52..];
+    assert_eq!((sdes[1], sdes[8], &sdes[10..10 + sdes[9] as usize]), (202, 1, rtcp::cname().as_bytes()));
+    let bye = &sdes[(u16::from_be_bytes([sdes[2], sdes[3]]) as usize + 1) * 4..];
+    assert_eq!((bye[0], bye[1], &bye[9..9 + bye[8] as usize]), (0x81, 203, &b"max_duration"[..]));
+}
+
+#[tokio::test]
+async fn tcp_allocation_frames_media_per_rfc4571_and_ends_with_the_connection() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut server = TestServer::start().await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-tcp".to_string(), transport: media::media::TransportKind::Tcp as i32, ..Default::default()
+    };
+    let reply = server.client.allocate_port(request).await.expect("AllocatePort").into_inner();
+    assert_eq!(reply.transport(), media::media::TransportKind::Tcp);
+    let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", reply.port as u16)).await.unwrap();
+
+    // Bir RTP paketi, arkasından aynı bağlantıda bir RTCP çerçevesi (RR, rapor yok).
+    let packet = RtpPacket::new(0, 1, 160, 0x1234_5678, &[0xFF; 160]);
+    let mut wire = [0u8; MAX_PACKET_LEN];
+    let len = packet.write(&mut wire).unwrap();
+    let rtcp = [0x80, 201, 0, 1, 0x12, 0x34, 0x56, 0x78];
+    let mut framed = Vec::new();
+    for frame in [&wire[..len], &rtcp[..]] {
+        framed.extend_from_slice(&(frame.len() as u16).to_be_bytes());
+        framed.extend_from_slice(frame);
+    }
+    stream.write_all(&framed).await.unwrap();
+
+    // Karşılama anonsu aynı bağlantıdan uzunluk önekli çerçevelerle gelir.
+    for expected in 0..3u16 {
+        let mut prefix = [0u8; 2];
+        tokio::time::timeout(Duration::from_secs(2), stream.read_exact(&mut prefix)).await.expect("frame within timeout").unwrap();
+        let mut frame = vec![0u8; u16::from_be_bytes(prefix) as usize];
+        stream.read_exact(&mut frame).await.unwrap();
+        let packet = media::rtp::RtpPacketRef::parse(&frame).expect("well-formed RTP packet");
+        assert_eq!((packet.payload_type(), packet.ssrc()), (0, reply.ssrc));
+        assert_eq!(packet.sequence(), (reply.initial_sequence as u16).wrapping_add(expected));
+    }
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.unwrap().into_inner();
+    assert_eq!((stats.packets_received, stats.remote_ssrc), (2, Some(0x1234_5678)));
+
+    // Bağlantı kapanınca oturum da kapanır.
+    drop(stream);
+    for _ in 0..100 {
+        if server.session_count() == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(server.session_count(), 0);
+}
+
+#[tokio::test]
+async fn negotiated_audio_level_is_read_inbound_and_written_on_outbound_packets() {
+    use media::audio_level::AudioLevel;
+
+    let mut server = TestServer::start().await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-audio-level".to_string(), audio_level_id: 3, ..Default::default()
+    };
+    let port = server.client.allocate_port(request).await.expect("AllocatePort").into_inner().port;
+    let peer = RtpPeer::connect(port).await;
+
+    let mut ext = [0u8; 4];
+    let extension = Some(AudioLevel { level: 30, voice: true }.extension(3, &mut ext));
+    let packet = RtpPacket { extension, ..RtpPacket::new(0, 1, 160, 0x1234_5678, &[0xFF; 160]) };
+    let mut wire = [0u8; MAX_PACKET_LEN];
+    let len = packet.write(&mut wire).unwrap();
+    peer.sock.send_to(&wire[..len], peer.remote).await.unwrap();
+
+    // Karşılama anonsunun paketleri ölçülen seviyeyi taşır; yük uzantıdan sonra başlar.
+    let mut buf = [0u8; 2048];
+    for _ in 0..3 {
+        let (len, _) = tokio::time::timeout(Duration::from_secs(2), peer.sock.recv_from(&mut buf)).await.unwrap().unwrap();
+        let packet = media::rtp::RtpPacketRef::parse(&buf[..len]).unwrap();
+        assert_eq!(packet.payload().len(), 160);
+        assert!(AudioLevel::parse(packet.extension(), 3).is_some_and(|level| level.level <= 127));
+    }
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port, ..Default::default() }).await.unwrap().into_inner();
+    assert_eq!((stats.remote_audio_level, stats.remote_voice), (Some(30), true));
+    // Tek gelen paket 5 saniyelik ortalamaya IPv4/UDP başlıklarıyla (28 bayt) girer.
+    assert!(stats.receive_bitrate_bps > 0.0 && stats.receive_bitrate_bps <= ((len + 28) * 8) as f64 / 5.0, "{}", stats.receive_bitrate_bps);
+    assert!(stats.send_bitrate_bps > 0.0);
+
+    let invalid = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-audio-level-15".to_string(), audio_level_id: 15, ..Default::default()
+    };
+    assert_eq!(server.client.allocate_port(invalid).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn pinned_stream_seed_reproduces_golden_welcome_packets() {
+    let mut server = TestServer::start().await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-pinned".to_string(), ssrc: Some(0x0BAD_CAFE), initial_sequence: Some(1000), initial_timestamp: Some(160_000), ..Default::default()
+    };
+    let reply = server.client.allocate_port(request).await.expect("AllocatePort").into_inner();
+    assert_eq!((reply.ssrc, reply.initial_sequence, reply.initial_timestamp), (0x0BAD_CAFE, 1000, 160_000));
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+
+    // Altın kayıt: karşılama anonsunun ilk üç paketi.
+    let capture = Capture::record(&peer, 3).await;
+    let first = capture.packets[0].rtp();
+    assert_eq!((first.ssrc(), first.sequence(), first.timestamp()), (0x0BAD_CAFE, 1000, 160_000));
+    capture.assert_golden("pinned_welcome");
+}
+
+#[tokio::test]
+async fn digits_play_as_one_seamless_stream_and_missing_segments_fail_fast() {
+    use media::media::{SayDigitsRequest, SayNumberRequest};
+
+    let dir = std::env::temp_dir().join(format!("media-e2e-say-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut settings = support::test_settings();
+    for (digit, value, samples) in [(1, 8000i16, 240), (2, -8000, 200)] {
+        let path = dir.join(format!("{}.wav", digit));
+        let spec = hound::WavSpec { channels: 1, sample_rate: 8000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        (0..samples).for_each(|_| writer.write_sample(value).unwrap());
+        writer.finalize().unwrap();
+        settings.announcement.prompts.insert(format!("say_{}", digit), media::config::PromptConfig {
+            path: path.display().to_string(), gain_db: 0.0, looped: false, language: None, preload: false, languages: Default::default(),
+        });
+    }
+    let mut server = TestServer::with_settings(settings).await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-say".to_string(), skip_welcome: true, ..Default::default()
+    };
+    let reply = server.client.allocate_port(request).await.expect("AllocatePort").into_inner();
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let say = |digits: &str| SayDigitsRequest { port: reply.port, digits: digits.to_string(), ..Default::default() };
+    let played = server.client.say_digits(say("12")).await.expect("SayDigits").into_inner();
+    assert_eq!(played.segments, ["say_1", "say_2"]);
+    // 240 + 200 örnek tek akış: yalnızca son çerçeve kısa, ikinci çerçeve iki parçayı birleştirir.
+    let packets = peer.recv_many(3).await;
+    assert_eq!(packets.iter().map(|p| p.payload.len()).collect::<Vec<_>>(), [160, 160, 120]);
+    assert_contiguous(&packets, 160);
+    assert_ne!(packets[1].payload[0], packets[1].payload[159]);
+
+    let missing = server.client.say_digits(say("1332")).await.unwrap_err();
+    assert_eq!((missing.code(), missing.message()), (tonic::Code::NotFound, "missing segment prompt(s): say_3"));
+    let number = SayNumberRequest { port: reply.port, number: 12, ordinal: true, language: "tr".to_string(), ..Default::default() };
+    let missing = server.client.say_number(number).await.unwrap_err();
+    assert_eq!(missing.message(), "missing segment prompt(s): say_10, say_ordinal_2");
+    assert_eq!(server.client.say_digits(say("1a")).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn uploaded_announcement_is_stored_listed_and_protected_while_playing() {
+    use media::media::{DeleteAnnouncementRequest, FileChunk, ListAnnouncementsRequest};
+    use sha2::{Digest, Sha256};
+
+    let dir = std::env::temp_dir().join(format!("media-e2e-upload-{}", std::process::id()));
+    let mut settings = support::test_settings();
+    settings.announcement.upload_dir = Some(dir.display().to_string());
+    settings.announcement.max_upload_bytes = Some(100_000);
+    let mut server = TestServer::with_settings(settings).await;
+
+    // Dosya birkaç parçada gelir; ad yalnızca ilk parçada.
+    let data = std::fs::read("audio/processed/standard/welcome.wav").unwrap();
+    let chunks: Vec<FileChunk> = data.chunks(16 * 1024).enumerate()
+        .map(|(i, chunk)| FileChunk { name: if i == 0 { "promo".to_string() } else { String::new() }, data: chunk.to_vec() })
+        .collect();
+    assert!(chunks.len() > 1);
+    let result = server.client.upload_announcement(tokio_stream::iter(chunks)).await.expect("UploadAnnouncement").into_inner();
+    assert_eq!((result.name.as_str(), result.size_bytes), ("promo", data.len() as u64));
+    assert_eq!(result.sha256, format!("{:x}", Sha256::digest(&data)));
+    assert!(result.duration_ms > 0);
+    assert_eq!(std::fs::read(dir.join("promo.wav")).unwrap(), data);
+
+    let reply = server.allocate("pcmu", "e2e-upload").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    peer.recv_rtp().await;
+    server.client.play_announcement(PlayAnnouncementRequest { port: reply.port, name: "promo".to_string(), interrupt: true, ..Default::default() }).await.expect("PlayAnnouncement");
+
+    // Listede yüklenen ve config'deki anons; çalan ve karşılama anonsu silinemez.
+    let list = server.client.list_announcements(ListAnnouncementsRequest {}).await.unwrap().into_inner().announcements;
+    let promo = list.iter().find(|a| a.name == "promo").expect("uploaded prompt listed");
+    assert_eq!((promo.sha256.as_str(), promo.plays, promo.active_playbacks), (result.sha256.as_str(), 1, 1));
+    assert_eq!((promo.sample_rate, promo.bits_per_sample, promo.channels, promo.duration_ms), (8000, 16, 1, result.duration_ms));
+    // Karşılama anonsu preload olmasa da önbelleğe sabitlenir.
+    assert!(list.iter().any(|a| a.name == "welcome" && a.cached && a.plays == 1));
+    for name in ["promo", "welcome"] {
+        let error = server.client.delete_announcement(DeleteAnnouncementRequest { name: name.to_string() }).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::FailedPrecondition, "{name}");
+    }
+
+    let too_large = vec![FileChunk { name: "huge".to_string(), data: vec![0; 100_001] }];
+    let error = server.client.upload_announcement(tokio_stream::iter(too_large)).await.unwrap_err();
+    assert_eq!(error.code(), tonic::Code::ResourceExhausted);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn url_announcements_download_once_without_blocking_allocation() {
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Response};
+
+    // Yanıtı geciken bir anons sunucusu; tahsis ve ilk paket indirmeyi beklememeli.
+    let requests = Arc::new(AtomicUsize::new(0));
+    let served = requests.clone();
+    let make = make_service_fn(move |_| {
+        let served = served.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |_| {
+                served.fetch_add(1, Ordering::Relaxed);
+                async {
+                    tokio::time::sleep(Duration::from_millis(300)).await;
+                    let data = std::fs::read("audio/processed/standard/welcome.wav").unwrap();
+                    Ok::<_, Infallible>(Response::builder().header("etag", "\"v1\"").body(Body::from(data)).unwrap())
+                }
+            }))
+        }
+    });
+    let origin = hyper::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(make);
+    let url = format!("http://{}/welcome.wav", origin.local_addr());
+    tokio::spawn(origin);
+
+    let dir = std::env::temp_dir().join(format!("media-e2e-url-{}", std::process::id()));
+    let mut settings = support::test_settings();
+    settings.announcement.url_cache_dir = Some(dir.display().to_string());
+    settings.announcement.prompts.get_mut("welcome").unwrap().path = url.clone();
+    let mut server = TestServer::with_settings(settings).await;
+
+    let started = tokio::time::Instant::now();
+    let reply = server.allocate("pcmu", "e2e-url").await;
+    assert!(started.elapsed() < Duration::from_millis(300));
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    let packets = peer.recv_many(5).await;
+    assert!(packets[0].arrived - started >= Duration::from_millis(300), "welcome started before the download finished");
+    assert!(packets.iter().all(|p| p.payload.len() == 160));
+    assert_contiguous(&packets, 160);
+
+    // Aynı adres tek seferlik istekle de çalınır; kopya taze olduğundan yeniden indirilmez.
+    server.client.play_announcement(PlayAnnouncementRequest { port: reply.port, name: "again".to_string(), url: url.clone(), interrupt: true, ..Default::default() }).await.expect("PlayAnnouncement");
+    peer.recv_rtp().await;
+    assert_eq!(requests.load(Ordering::Relaxed), 1);
+    let https = PlayAnnouncementRequest { port: reply.port, url: "https://prompts.example/a.wav".to_string(), ..Default::default() };
+    assert_eq!(server.client.play_announcement(https).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn tenants_allocate_only_from_their_own_ranges() {
+    let tenant = |name: &str, min_port, auth_token: &str| media::config::TenantConfig {
+        name: name.to_string(), min_port, max_port: min_port + 3, max_sessions: 1, auth_token: auth_token.to_string(),
+    };
+    let mut settings = support::test_settings();
+    settings.tenants = vec![tenant("e2e-acme", 31900, "acme-token"), tenant("e2e-globex", 31950, "")];
+    let mut server = TestServer::with_settings(settings).await;
+    let allocate = |tenant: &str, token: Option<&str>| {
+        let mut request = tonic::Request::new(AllocatePortRequest {
+            codec: "pcmu".to_string(), call_id: "e2e-tenant".to_string(), skip_welcome: true, tenant: tenant.to_string(), ..Default::default()
+        });
+        if let Some(token) = token {
+            request.metadata_mut().insert("authorization", format!("Bearer {}", token).parse().unwrap());
+        }
+        request
+    };
+
+    // Jetondan bulunan kiracı kendi aralığından alır; sınırı dolunca yalnızca o reddedilir.
+    let acme = server.client.allocate_port(allocate("", Some("acme-token"))).await.expect("AllocatePort").into_inner();
+    assert!((31900..=31903).contains(&acme.port), "{}", acme.port);
+    let full = server.client.allocate_port(allocate("", Some("acme-token"))).await.unwrap_err();
+    assert_eq!(full.code(), tonic::Code::ResourceExhausted);
+    let globex = server.client.allocate_port(allocate("e2e-globex", None)).await.expect("AllocatePort").into_inner();
+    assert!((31950..=31953).contains(&globex.port), "{}", globex.port);
+    let shared = server.client.allocate_port(allocate("", None)).await.expect("AllocatePort").into_inner();
+    assert!(!(31900..=31903).contains(&shared.port) && !(31950..=31953).contains(&shared.port), "{}", shared.port);
+
+    for (request, code) in [
+        (allocate("e2e-acme", None), tonic::Code::Unauthenticated),
+        (allocate("", Some("wrong")), tonic::Code::Unauthenticated),
+        (allocate("e2e-globex", Some("acme-token")), tonic::Code::PermissionDenied),
+        (allocate("e2e-initech", None), tonic::Code::InvalidArgument),
+    ] {
+        assert_eq!(server.client.allocate_port(request).await.unwrap_err().code(), code);
+    }
+
+    let status = server.client.get_server_status(GetServerStatusRequest {}).await.expect("GetServerStatus").into_inner();
+    assert_eq!(status.active_sessions, 3);
+    let tenants: Vec<(&str, u32, u32, u64, u64)> = status.tenants.iter()
+        .map(|t| (t.name.as_str(), t.min_port, t.active_sessions, t.allocations, t.allocation_failures))
+        .collect();
+    assert_eq!(tenants, [("e2e-acme", 31900, 1, 1, 1), ("e2e-globex", 31950, 1, 1, 0)]);
+    let session = server.sessions.lock().unwrap()[&(acme.port as u16)].clone();
+    assert_eq!(session.tenant.as_ref().map(|t| t.name), Some("e2e-acme"));
+}
+
+#[tokio::test]
+async fn interfaces_bind_their_own_address_and_advertise_it() {
+    let interface = |name: &str, address: &str, advertise_address: &str, min_port, max_port| media::config::InterfaceConfig {
+        name: name.to_string(), address: address.to_string(), advertise_address: advertise_address.to_string(), min_port, max_port,
+    };
+    let mut settings = support::test_settings();
+    settings.interfaces = vec![interface("carrier", "127.0.0.2", "198.51.100.7", 31970, 31979), interface("internal", "127.0.0.1", "", 0, 0)];
+    let mut server = TestServer::with_settings(settings).await;
+    let allocate = |interface: &str| AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-interface".to_string(), skip_welcome: true, interface: interface.to_string(), ..Default::default()
+    };
+
+    let carrier = server.client.allocate_port(allocate("carrier")).await.expect("AllocatePort").into_inner();
+    assert!((31970..=31979).contains(&carrier.port), "{}", carrier.port);
+    assert_eq!(carrier.advertise_address, "198.51.100.7");
+    let session = server.sessions.lock().unwrap()[&(carrier.port as u16)].clone();
+    assert_eq!((session.local_addr.ip().to_string(), session.interface.as_deref()), ("127.0.0.2".to_string(), Some("carrier")));
+    let offer = server.client.generate_sdp(GenerateSdpRequest { port: carrier.port, role: SdpRole::Offer as i32, ..Default::default() }).await.expect("GenerateSdp").into_inner();
+    assert!(offer.sdp.contains("c=IN IP4 198.51.100.7\r\n"), "{}", offer.sdp);
+
+    let internal = server.client.allocate_port(allocate("internal")).await.expect("AllocatePort").into_inner();
+    assert_eq!(internal.advertise_address, "127.0.0.1");
+    let default = server.client.allocate_port(allocate("")).await.expect("AllocatePort").into_inner();
+    assert_eq!(default.advertise_address, "127.0.0.1");
+    assert_eq!(server.client.allocate_port(allocate("wifi")).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+    let status = server.client.get_server_status(GetServerStatusRequest {}).await.expect("GetServerStatus").into_inner();
+    let interfaces: Vec<(&str, &str, u32, u32, u32, u32)> = status.interfaces.iter()
+        .map(|i| (i.name.as_str(), i.advertise_address.as_str(), i.min_port, i.max_port, i.active_sessions, i.ports))
+        .collect();
+    assert_eq!(interfaces, [("carrier", "198.51.100.7", 31970, 31979, 1, 10), ("internal", "127.0.0.1", 31000, 31999, 1, 1000)]);
+}
+
+#[tokio::test]
+async fn plays_are_rejected_queued_or_interrupted_and_stop_can_flush_the_queue() {
+    use media::media::StopPlaybackRequest;
+
+    let mut server = TestServer::start().await;
+    let reply = server.allocate("pcmu", "e2e-queue").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    peer.recv_rtp().await;
+
+    // Karşılama çalarken: varsayılan kip reddeder, iki kip birden verilemez, enqueue sıraya alır.
+    let play = |enqueue, interrupt| PlayAnnouncementRequest {
+        port: reply.port, name: "welcome".to_string(), enqueue, interrupt, ..Default::default()
+    };
+    assert_eq!(server.client.play_announcement(play(false, false)).await.unwrap_err().code(), tonic::Code::FailedPrecondition);
+    assert_eq!(server.client.play_announcement(play(true, true)).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+    let mut queued = Vec::new();
+    for _ in 0..2 {
+        queued.push(server.client.play_announcement(play(true, false)).await.expect("PlayAnnouncement").into_inner().playback_id);
+    }
+    assert_eq!(queued, [2, 3]);
+
+    // Karşılama (1) durunca sıradaki başlar; flush çalanı (2) durdurup kalanı (3) atar.
+    let stop = |flush| StopPlaybackRequest { port: reply.port, flush, ..Default::default() };
+    let stopped = server.client.stop_playback(stop(false)).await.expect("StopPlayback").into_inner();
+    assert_eq!((stopped.stopped_playback_id, stopped.flushed), (Some(1), 0));
+    peer.recv_rtp().await;
+    let stopped = server.client.stop_playback(stop(true)).await.expect("StopPlayback").into_inner();
+    assert_eq!((stopped.stopped_playback_id, stopped.flushed), (Some(2), 1));
+    let stopped = server.client.stop_playback(stop(true)).await.expect("StopPlayback").into_inner();
+    assert_eq!((stopped.stopped_playback_id, stopped.flushed), (None, 0));
+
+    // Çalan yokken varsayılan kip hemen başlar; interrupt çalanın yerine geçer.
+    assert_eq!(server.client.play_announcement(play(false, false)).await.expect("PlayAnnouncement").into_inner().playback_id, 4);
+    assert_eq!(server.client.play_announcement(play(false, true)).await.expect("PlayAnnouncement").into_inner().playback_id, 5);
+    peer.recv_rtp().await;
+}
+
+#[tokio::test]
+async fn send_only_session_plays_without_inbound_rtp_and_keeps_its_destination() {
+    let mut settings = support::test_settings();
+    settings.timers.first_packet_timeout_s = 1;
+    let mut server = TestServer::with_settings(settings).await;
+    let peer = RtpPeer::connect(0).await;
+    let mut intruder = RtpPeer::connect(0).await;
+    let request = |skip_welcome, symmetric_rtp| AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-send-only".to_string(), skip_welcome, remote_address: peer.sock.local_addr().unwrap().to_string(), symmetric_rtp, ..Default::default()
+    };
+
+    // Karşılamasız oturum ilk paket zaman aşımından sonra da yaşar ve istenen anonsu hemen çalar.
+    let reply = server.client.allocate_port(request(true, false)).await.expect("AllocatePort").into_inner();
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    assert_eq!(server.session_count(), 1);
+    server.client.play_announcement(PlayAnnouncementRequest {
+        port: reply.port, name: "welcome".to_string(), ..Default::default()
+    }).await.expect("PlayAnnouncement");
+    assert_eq!(peer.recv_rtp().await.from.port() as u32, reply.port);
+
+    // Başka bir adresten gelen akış sayılır ama hedef değişmez.
+    intruder.remote = std::net::SocketAddr::from(([127, 0, 0, 1], reply.port as u16));
+    for _ in 0..3 {
+        intruder.send_packet().await;
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.unwrap().into_inner();
+    assert_eq!(stats.packets_received, 3);
+    peer.recv_many(5).await;
+    let mut buf = [0u8; 2048];
+    assert!(intruder.sock.try_recv_from(&mut buf).is_err(), "send-only destination followed inbound RTP");
+
+    // İlk oturum susturulur; yoldaki paketleri bitince eşe yalnızca ikinci oturum gönderir.
+    server.client.stop_playback(media::media::StopPlaybackRequest { port: reply.port, flush: true, ..Default::default() })
+        .await.expect("StopPlayback");
+    while tokio::time::timeout(Duration::from_millis(100), peer.sock.recv_from(&mut buf)).await.is_ok() {}
+
+    // symmetric_rtp ile karşılama tahsiste başlar, gelen akış hedefi kendine çeker.
+    let reply = server.client.allocate_port(request(false, true)).await.expect("AllocatePort").into_inner();
+    assert_eq!(peer.recv_rtp().await.from.port() as u32, reply.port);
+    intruder.remote = std::net::SocketAddr::from(([127, 0, 0, 1], reply.port as u16));
+    intruder.send_packet().await;
+    assert_eq!(intruder.recv_rtp().await.from.port() as u32, reply.port);
+
+    let invalid = AllocatePortRequest { remote_address: "sbc.example:4000".to_string(), ..request(false, false) };
+    assert_eq!(server.client.allocate_port(invalid).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn reload_announcements_reports_missing_and_invalid_files() {
+    use media::media::{ReloadAnnouncementsRequest, ReloadOutcome};
+
+    let dir = std::env::temp_dir().join(format!("media-e2e-reload-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut settings = support::test_settings();
+    for name in ["promo", "broken"] {
+        std::fs::copy("audio/processed/standard/welcome.wav", dir.join(format!("{name}.wav"))).unwrap();
+        settings.announcement.prompts.insert(name.to_string(), media::config::PromptConfig {
+            path: dir.join(format!("{name}.wav")).display().to_string(), gain_db: 0.0, looped: false, language: None, preload: false, languages: Default::default(),
+        });
+    }
+    let mut server = TestServer::with_settings(settings).await;
+
+    std::fs::remove_file(dir.join("promo.wav")).unwrap();
+    std::fs::write(dir.join("broken.wav"), b"RIFF not a wav").unwrap();
+    let names = ["welcome", "promo", "broken"].map(str::to_string).to_vec();
+    let reloaded = server.client.reload_announcements(ReloadAnnouncementsRequest { names }).await.expect("ReloadAnnouncements").into_inner().announcements;
+    let outcomes: Vec<(&str, ReloadOutcome)> = reloaded.iter().map(|a| (a.name.as_str(), a.outcome())).collect();
+    assert_eq!(outcomes, [("broken", ReloadOutcome::Invalid), ("promo", ReloadOutcome::Missing), ("welcome", ReloadOutcome::Unchanged)]);
+    assert!(!reloaded[0].reason.is_empty());
+    assert_eq!(reloaded[1].reason, dir.join("promo.wav").display().to_string());
+
+    let error = server.client.reload_announcements(ReloadAnnouncementsRequest { names: vec!["missing".to_string()] }).await.unwrap_err();
+    assert_eq!(error.code(), tonic::Code::NotFound);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn labelled_sessions_are_listed_and_filtered_and_bad_labels_are_rejected() {
+    use std::collections::HashMap;
+
+    use media::media::ListSessionsRequest;
+
+    let mut server = TestServer::start().await;
+    let labels = |pairs: &[(&str, &str)]| pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<_, _>>();
+    let request = |call_id: &str, pairs: &[(&str, &str)]| AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: call_id.to_string(), skip_welcome: true, labels: labels(pairs), ..Default::default()
+    };
+    let sales = server.client.allocate_port(request("e2e-sales", &[("queue", "sales"), ("campaign", "q3")])).await.expect("AllocatePort").into_inner();
+    let support = server.client.allocate_port(request("e2e-support", &[("queue", "support")])).await.expect("AllocatePort").into_inner();
+    server.allocate("pcmu", "e2e-unlabelled").await;
+
+    let list = |pairs: &[(&str, &str)]| ListSessionsRequest { labels: labels(pairs) };
+    let all = server.client.list_sessions(list(&[])).await.expect("ListSessions").into_inner().sessions;
+    assert_eq!(all.len(), 3);
+    assert!(all.windows(2).all(|pair| pair[0].port < pair[1].port));
+    let queued = server.client.list_sessions(list(&[("queue", "")])).await.unwrap().into_inner().sessions;
+    let mut expected = vec![sales.port, support.port];
+    expected.sort();
+    assert_eq!(queued.iter().map(|s| s.port).collect::<Vec<_>>(), expected);
+    let campaign = server.client.list_sessions(list(&[("queue", "sales"), ("campaign", "q3")])).await.unwrap().into_inner().sessions;
+    assert_eq!(campaign.len(), 1);
+    assert_eq!((campaign[0].call_id.as_str(), campaign[0].session_id.as_str()), ("e2e-sales", sales.session_id.as_str()));
+    assert_eq!(campaign[0].labels, labels(&[("queue", "sales"), ("campaign", "q3")]));
+    assert!(server.client.list_sessions(list(&[("queue", "billing")])).await.unwrap().into_inner().sessions.is_empty());
+
+    // Geçersiz etiket port almadan reddedilir.
+    for pairs in [&[("Queue", "sales")][..], &[("queue", "a\nb")][..]] {
+        let error = server.client.allocate_port(request("e2e-bad", pairs)).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument, "{:?}", pairs);
+    }
+    assert_eq!(server.session_count(), 3);
+}
+
+#[tokio::test]
+async fn sdp_answer_sets_the_remote_endpoint_and_reports_malformed_lines() {
+    let mut server = TestServer::start().await;
+    let port = server.allocate("pcmu", "e2e-sdp").await.port;
+    let generate = |role: SdpRole, remote_sdp: String| GenerateSdpRequest { port, role: role as i32, remote_sdp, ..Default::default() };
+
+    let offer = server.client.generate_sdp(generate(SdpRole::Offer, String::new())).await.expect("GenerateSdp").into_inner();
+    // Henüz medya gönderilmediği için node'da açık PCMA da oturumunkinden sonra teklif edilir.
+    for line in ["c=IN IP4 127.0.0.1", &format!("m=audio {} RTP/AVP 0 8", port), "a=rtpmap:0 PCMU/8000", "a=rtpmap:8 PCMA/8000", "a=ptime:20", &format!("a=rtcp:{}", port + 1), "a=sendrecv"] {
+        assert!(offer.sdp.contains(&format!("{}\r\n", line)), "{} missing from\n{}", line, offer.sdp);
+    }
+    assert_eq!((offer.remote_address.as_str(), offer.dtmf_payload_type), ("", 0));
+
+    // Cevap teklifin adresini hedef yapar: anons ilk gelen paketi beklemeden çalar.
+    let peer = RtpPeer::connect(port).await;
+    let far = peer.sock.local_addr().unwrap();
+    let remote_offer = format!(
+        "v=0\r\no=sbc 1 1 IN IP4 127.0.0.1\r\ns=-\r\nc=IN IP4 127.0.0.1\r\nt=0 0\r\nm=audio {} RTP/AVP 0 8 96\r\na=rtpmap:96 telephone-event/8000\r\na=sendrecv\r\n",
+        far.port(),
+    );
+    let answer = server.client.generate_sdp(generate(SdpRole::Answer, remote_offer.clone())).await.expect("GenerateSdp").into_inner();
+    assert_eq!((answer.remote_address, answer.dtmf_payload_type), (far.to_string(), 96));
+    assert!(answer.sdp.contains(&format!("m=audio {} RTP/AVP 0 96\r\n", port)), "{}", answer.sdp);
+    assert!(answer.sdp.contains("o=- ") && answer.sdp.contains(" 2 IN IP4 127.0.0.1\r\n"), "version follows the offer:\n{}", answer.sdp);
+    server.client.play_announcement(PlayAnnouncementRequest { port, name: "welcome".to_string(), ..Default::default() })
+        .await.expect("PlayAnnouncement");
+    assert_eq!(peer.recv_rtp().await.payload_type, 0);
+
+    let error = server.client.generate_sdp(generate(SdpRole::Answer, remote_offer.replace("c=IN IP4 127.0.0.1", "c=IN IP4 localhost"))).await.unwrap_err();
+    assert_eq!(error.code(), tonic::Code::InvalidArgument);
+    assert!(error.message().contains("line 4 'c=IN IP4 localhost'"), "{}", error.message());
+    // Anons gönderildikten sonra oturum artık PCMA'ya geçemez.
+    let error = server.client.generate_sdp(generate(SdpRole::Answer, remote_offer.replace("RTP/AVP 0 8 96", "RTP/AVP 8"))).await.unwrap_err();
+    assert_eq!(error.code(), tonic::Code::FailedPrecondition);
+    assert_eq!(server.client.generate_sdp(generate(SdpRole::Answer, String::new())).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn sessions_switch_to_the_codec_the_remote_picks_before_sending_media() {
+    let mut server = TestServer::start().await;
+    let allocate = |call_id: &str| AllocatePortRequest { codec: "pcmu".to_string(), call_id: call_id.to_string(), skip_welcome: true, ..Default::default() };
+    let codec = |mut client: media::media::media_manager_client::MediaManagerClient<tonic::transport::Channel>, port| async move {
+        let sessions = client.list_sessions(ListSessionsRequest::default()).await.expect("ListSessions").into_inner().sessions;
+        sessions.into_iter().find(|session| session.port == port).unwrap().codec
+    };
+
+    // Cevap: teklifte PCMU yok, node'da açık PCMA seçilir ve anons onunla gider.
+    let port = server.client.allocate_port(allocate("e2e-answer-pcma")).await.expect("AllocatePort").into_inner().port;
+    let peer = RtpPeer::connect(port).await;
+    let remote_offer = format!(
+        "v=0\r\no=sbc 1 1 IN IP4 127.0.0.1\r\ns=-\r\nc=IN IP4 127.0.0.1\r\nt=0 0\r\nm=audio {} RTP/AVP 8 96\r\na=rtpmap:96 telephone-event/8000\r\na=sendrecv\r\n",
+        peer.sock.local_addr().unwrap().port(),
+    );
+    let answer = server.client.generate_sdp(GenerateSdpRequest { port, role: SdpRole::Answer as i32, remote_sdp: remote_offer, ..Default::default() })
+        .await.expect("GenerateSdp").into_inner();
+    assert!(answer.sdp.contains(&format!("m=audio {} RTP/AVP 8 96\r\n", port)) && answer.sdp.contains("a=rtpmap:8 PCMA/8000\r\n"), "{}", answer.sdp);
+    assert_eq!(codec(server.client.clone(), port).await, "pcma");
+    server.client.play_announcement(PlayAnnouncementRequest { port, name: "welcome".to_string(), ..Default::default() }).await.expect("PlayAnnouncement");
+    assert_eq!(peer.recv_rtp().await.payload_type, 8);
+
+    // Teklif: PCMA da sunulur; uzak uç PCMA ile ses gönderince oturum ona geçer.
+    let port = server.client.allocate_port(allocate("e2e-offer-pcma")).await.expect("AllocatePort").into_inner().port;
+    let offer = server.client.generate_sdp(GenerateSdpRequest { port, role: SdpRole::Offer as i32, ..Default::default() }).await.expect("GenerateSdp").into_inner();
+    assert!(offer.sdp.contains(&format!("m=audio {} RTP/AVP 0 8\r\n", port)), "{}", offer.sdp);
+    let peer = RtpPeer::connect(port).await;
+    let packet = RtpPacket::new(8, 1, 160, 0x1234_5678, &[0xD5; 160]);
+    let mut wire = [0u8; MAX_PACKET_LEN];
+    let len = packet.write(&mut wire).unwrap();
+    peer.sock.send_to(&wire[..len], peer.remote).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while codec(server.client.clone(), port).await != "pcma" {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }).await.expect("session switches to PCMA");
+    server.client.play_announcement(PlayAnnouncementRequest { port, name: "welcome".to_string(), ..Default::default() }).await.expect("PlayAnnouncement");
+    assert_eq!(peer.recv_rtp().await.payload_type, 8);
+}
+
+#[tokio::test]
+async fn impairments_need_the_node_flag_and_drop_outbound_media() {
+    let impair = |port, loss_pct| SetImpairmentRequest { port, impairment: Some(Impairment { loss_pct, jitter_ms: 0, reorder_pct: 0.0, duplicate_pct: 0.0 }), ..Default::default() };
+    let mut server = TestServer::start().await;
+    let port = server.allocate("pcmu", "e2e-impairment-off").await.port;
+    assert_eq!(server.client.set_impairment(impair(port, 100.0)).await.unwrap_err().code(), tonic::Code::FailedPrecondition);
+    // Kaldırmak her zaman serbesttir.
+    server.client.set_impairment(impair(port, 0.0)).await.expect("SetImpairment");
+
+    let mut settings = test_settings();
+    settings.rtp.enable_impairments = true;
+    let mut server = TestServer::with_settings(settings).await;
+    let port = server.allocate("pcmu", "e2e-impairment").await.port;
+    assert_eq!(server.client.set_impairment(impair(port, 150.0)).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+    let applied = server.client.set_impairment(impair(port, 100.0)).await;
+    if !cfg!(feature = "impairment") {
+        assert_eq!(applied.unwrap_err().code(), tonic::Code::FailedPrecondition);
+        return;
+    }
+    applied.expect("SetImpairment");
+
+    // Karşılama gönderilir ama hiçbir paketi sokete ulaşmaz; bozulma kalkınca akış sürer.
+    let mut peer = RtpPeer::connect(port).await;
+    peer.send_packet().await;
+    let mut buf = [0u8; MAX_PACKET_LEN];
+    assert!(tokio::time::timeout(Duration::from_millis(300), peer.sock.recv_from(&mut buf)).await.is_err());
+    server.client.set_impairment(impair(port, 0.0)).await.expect("SetImpairment");
+    assert_eq!(peer.recv_rtp().await.payload_type, 0);
+}
+
+#[tokio::test]
+async fn transcription_is_refused_when_the_node_has_no_recognizer() {
+    let mut server = TestServer::start().await;
+    let request = AllocatePortRequest { codec: "pcmu".to_string(), call_id: "e2e-asr".to_string(), asr: true, ..Default::default() };
+    let status = server.client.allocate_port(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    assert_eq!(server.session_count(), 0);
+}
+
+#[tokio::test]
+async fn finished_digits_are_posted_to_the_sessions_dtmf_hook() {
+    use std::convert::Infallible;
+
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response};
+
+    let (bodies, mut received) = tokio::sync::mpsc::unbounded_channel();
+    let make = make_service_fn(move |_| {
+        let bodies = bodies.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let bodies = bodies.clone();
+                async move {
+                    let _ = bodies.send(hyper::body::to_bytes(request.into_body()).await.unwrap());
+                    Ok::<_, Infallible>(Response::new(Body::empty()))
+                }
+            }))
+        }
+    });
+    let receiver = hyper::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(make);
+    let url = format!("http://{}/digits", receiver.local_addr());
+    tokio::spawn(receiver);
+
+    media::dtmf_hook::install(&test_settings().dtmf_hook);
+    let mut server = TestServer::start().await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-dtmf-hook".to_string(), dtmf_payload_type: 101, dtmf_hook_url: url, ..Default::default()
+    };
+    let reply = server.client.allocate_port(request).await.expect("AllocatePort").into_inner();
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+
+    // '#' tuşu: başlangıç, devam ve üç kez yinelenen bitiş (960 örnek = 120 ms).
+    let mut wire = [0u8; MAX_PACKET_LEN];
+    for (sequence, payload) in [(2, [11, 10, 0, 160]), (3, [11, 10, 1, 64]), (4, [11, 0x8A, 3, 192]), (5, [11, 0x8A, 3, 192]), (6, [11, 0x8A, 3, 192])] {
+        let event = RtpPacket { marker: sequence == 2, ..RtpPacket::new(101, sequence, 320, 0x1234_5678, &payload) };
+        let len = event.write(&mut wire).unwrap();
+        peer.sock.send_to(&wire[..len], peer.remote).await.unwrap();
+    }
+
+    let body = tokio::time::timeout(Duration::from_secs(2), received.recv()).await.expect("digit within timeout").unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["session_id"], reply.session_id.as_str());
+    assert_eq!(body["call_id"], "e2e-dtmf-hook");
+    assert_eq!((body["digit"].as_str(), body["duration_ms"].as_u64(), body["method"].as_str()), (Some("#"), Some(120), Some("rfc4733")));
+    assert!(tokio::time::timeout(Duration::from_millis(300), received.recv()).await.is_err(), "repeated end packets were reported again");
+}
+
+#[tokio::test]
+async fn dtmf_hook_urls_must_be_plain_http() {
+    let mut server = TestServer::start().await;
+    let request = AllocatePortRequest { codec: "pcmu".to_string(), call_id: "e2e-dtmf-hook-https".to_string(), dtmf_payload_type: 101, dtmf_hook_url: "https://hooks.example/digits".to_string(), ..Default::default() };
+    assert_eq!(server.client.allocate_port(request).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn exported_sessions_are_re_anchored_on_another_node_with_their_stream() {
+    let mut draining = TestServer::start().await;
+    let mut standby = TestServer::start().await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-export".to_string(), dtmf_payload_type: 101, labels: [("queue".to_string(), "moh".to_string())].into(), ..Default::default()
+    };
+    let reply = draining.client.allocate_port(request).await.expect("AllocatePort").into_inner();
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    let before = peer.recv_many(5).await;
+
+    // Bilinmeyen bir port bütün isteği reddeder; karşılama çalmaya devam eder.
+    let unknown = ExportSessionsRequest { ports: vec![reply.port, 1], ..Default::default() };
+    assert_eq!(draining.client.export_sessions(unknown).await.unwrap_err().code(), tonic::Code::NotFound);
+    let selected = ExportSessionsRequest { labels: [("queue".to_string(), "moh".to_string())].into(), ..Default::default() };
+    let exported = draining.client.export_sessions(selected).await.expect("ExportSessions").into_inner();
+    assert_eq!(exported.sessions.len(), 1);
+    assert_eq!((exported.sessions[0].port, exported.sessions[0].session_id.as_str()), (reply.port, reply.session_id.as_str()));
+    assert!(exported.sessions[0].stopped_playback_id.is_some(), "welcome was not reported as terminated");
+    // Oturum yeni node'a taşınana kadar eski node'da sürer.
+    assert_eq!(draining.session_count(), 1);
+
+    let imported = standby.client.import_sessions(ImportSessionsRequest { state: exported.state }).await.expect("ImportSessions").into_inner();
+    let session = &imported.sessions[0];
+    assert_eq!((session.previous_port, session.previous_session_id.as_str(), session.call_id.as_str(), session.error.as_str()), (reply.port, reply.session_id.as_str(), "e2e-export", ""));
+    assert_ne!(session.session_id, reply.session_id);
+    let listed = standby.client.list_sessions(ListSessionsRequest { labels: [("queue".to_string(), "moh".to_string())].into() }).await.unwrap().into_inner();
+    assert_eq!(listed.sessions.iter().map(|listed| listed.port).collect::<Vec<_>>(), [session.port]);
+
+    // Yeni oturum uzak uca gelen paket beklemeden aynı akışı sürdürür.
+    standby.client.play_announcement(PlayAnnouncementRequest {
+        port: session.port, name: "welcome".to_string(), ..Default::default()
+    }).await.expect("PlayAnnouncement");
+    let mut last = before.last().unwrap().clone();
+    let resumed = loop {
+        let packet = peer.recv_rtp().await;
+        if packet.from.port() as u32 == session.port {
+            break packet;
+        }
+        last = packet;
+    };
+    assert_eq!(resumed.ssrc, last.ssrc);
+    assert!((1..=5).contains(&resumed.sequence.wrapping_sub(last.sequence)), "sequence jumped from {} to {}", last.sequence, resumed.sequence);
+    assert!((resumed.timestamp.wrapping_sub(last.timestamp) as i32) > 0);
+    let offer = standby.client.generate_sdp(GenerateSdpRequest { port: session.port, role: SdpRole::Offer as i32, ..Default::default() }).await.unwrap().into_inner();
+    assert_eq!(offer.dtmf_payload_type, 101);
+
+    let garbage = ImportSessionsRequest { state: b"{\"version\": 99}".to_vec() };
+    assert_eq!(standby.client.import_sessions(garbage).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn held_session_pauses_the_announcement_and_resumes_it_in_place() {
+    let mut server = TestServer::start().await;
+    let reply = server.allocate("pcmu", "e2e-hold").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    let before = peer.recv_many(3).await;
+
+    let direction = |direction: MediaDirection| SetDirectionRequest { port: reply.port, direction: direction as i32, ..Default::default() };
+    let held = server.client.set_direction(direction(MediaDirection::RecvOnly)).await.expect("SetDirection").into_inner();
+    assert_eq!(held.previous(), MediaDirection::SendRecv);
+    // Yön değişmeden önce gönderilmiş paketler yolda olabilir; akış kesilene kadar okunur.
+    let mut buf = [0u8; MAX_PACKET_LEN];
+    let mut last_sequence = before.last().unwrap().sequence;
+    while let Ok(Ok((len, _))) = tokio::time::timeout(Duration::from_millis(200), peer.sock.recv_from(&mut buf)).await {
+        last_sequence = media::rtp::RtpPacketRef::parse(&buf[..len]).unwrap().sequence();
+    }
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.expect("GetSessionStats").into_inner();
+    assert_eq!(stats.direction(), MediaDirection::RecvOnly);
+    let listed = server.client.list_sessions(ListSessionsRequest::default()).await.expect("ListSessions").into_inner();
+    assert_eq!(listed.sessions[0].direction(), MediaDirection::RecvOnly);
+
+    // Anons durdurulmadı, bekletildi: akış kaldığı yerden, bir sonraki sıra numarasıyla sürer.
+    let resumed = server.client.set_direction(direction(MediaDirection::SendRecv)).await.expect("SetDirection").into_inner();
+    assert_eq!(resumed.previous(), MediaDirection::RecvOnly);
+    let after = peer.recv_many(3).await;
+    assert_eq!(after[0].sequence, last_sequence.wrapping_add(1));
+    assert_eq!(after[0].ssrc, before[0].ssrc);
+
+    let missing = server.client.set_direction(SetDirectionRequest { port: 1, direction: MediaDirection::Inactive as i32, ..Default::default() }).await.unwrap_err();
+    assert_eq!(missing.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn port_history_needs_the_journal_and_a_valid_port() {
+    let mut server = TestServer::start().await;
+    let disabled = server.client.query_port_history(QueryPortHistoryRequest { port: *RTP_PORTS.start() as u32, ..Default::default() }).await.unwrap_err();
+    assert_eq!(disabled.code(), tonic::Code::FailedPrecondition);
+    let invalid = server.client.query_port_history(QueryPortHistoryRequest { port: 70_000, ..Default::default() }).await.unwrap_err();
+    assert_eq!(invalid.code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn sessions_are_addressed_by_id_and_released_ports_go_back_to_the_pool() {
+    let mut server = TestServer::start().await;
+    let reply = server.allocate("pcmu", "e2e-release").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    peer.recv_rtp().await;
+
+    // Oturum AllocatePort'un döndüğü kimlikle de bulunur; kimlik başka bir portla verilirse istek
+    // geçersizdir, bilinmeyen kimlik bulunmaz.
+    let by_id = |port, session_id: &str| GetSessionStatsRequest { port, session_id: session_id.to_string() };
+    let stats = server.client.get_session_stats(by_id(0, &reply.session_id)).await.expect("GetSessionStats").into_inner();
+    assert_eq!(stats.packets_received, 1);
+    let mismatched = server.client.get_session_stats(by_id(reply.port + 2, &reply.session_id)).await.unwrap_err();
+    assert_eq!(mismatched.code(), tonic::Code::InvalidArgument);
+    let unknown = server.client.get_session_stats(by_id(reply.port, "0123456789abcdef")).await.unwrap_err();
+    assert_eq!(unknown.code(), tonic::Code::NotFound);
+
+    // Portu alan diğer RPC'ler de kimliği kabul eder.
+    let mute = SetMuteRequest { port: 0, outbound: true, session_id: reply.session_id.clone(), ..Default::default() };
+    server.client.set_mute(mute).await.expect("SetMute");
+    let direction = SetDirectionRequest { port: 0, direction: MediaDirection::RecvOnly as i32, session_id: reply.session_id.clone() };
+    server.client.set_direction(direction).await.expect("SetDirection");
+    let stats = server.client.get_session_stats(by_id(0, &reply.session_id)).await.expect("GetSessionStats").into_inner();
+    assert!(stats.outbound_muted);
+    assert_eq!(stats.direction(), MediaDirection::RecvOnly);
+
+    let released = server.client.release_port(ReleasePortRequest { port: 0, session_id: reply.session_id.clone() }).await.expect("ReleasePort").into_inner();
+    assert_eq!(released.session_id, reply.session_id);
+    assert_eq!(server.session_count(), 0);
+    let rtcp = peer.recv_rtcp(Duration::from_secs(1)).await;
+    assert!(rtcp.windows(b"released".len()).any(|reason| reason == b"released"));
+
+    // Soket kapanınca port yeniden bağlanabilir.
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(1);
+    while std::net::UdpSocket::bind(("127.0.0.1", reply.port as u16)).is_err() {
+        assert!(tokio::time::Instant::now() < deadline, "port {} was not closed", reply.port);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let missing = server.client.release_port(ReleasePortRequest { port: reply.port, ..Default::default() }).await.unwrap_err();
+    assert_eq!(missing.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn periodic_sender_reports_flow_and_receiver_reports_fill_remote_quality() {
+    let mut settings = test_settings();
+    settings.rtcp.report_interval_s = 1;
+    let mut server = TestServer::with_settings(settings).await;
+    let reply = server.allocate("pcmu", "e2e-rtcp").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    let ssrc = peer.recv_rtp().await.ssrc;
+
+    // Karşılama çalarken RTCP portundan düzenli SR gelir ve yolladığımız akış için alım raporu taşır.
+    assert_eq!(reply.rtcp_port, reply.port + 1);
+    let report = rtcp::parse_reports(&peer.recv_rtcp(Duration::from_secs(2)).await).unwrap().remove(0);
+    let sender = report.sender.expect("SR while sending");
+    assert_eq!(report.ssrc, ssrc);
+    assert_eq!(report.blocks.iter().map(|block| block.ssrc).collect::<Vec<_>>(), [0x1234_5678]);
+
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.unwrap().into_inner();
+    assert_eq!(stats.remote_report, None);
+    let block = rtcp::ReportBlock { ssrc, fraction_lost: 64, cumulative_lost: 3, jitter: 80, last_sr: rtcp::ntp_middle(sender.ntp_timestamp), ..rtcp::ReportBlock::default() };
+    let mut wire = [0u8; 128];
+    let mut writer = rtcp::CompoundWriter::new(&mut wire);
+    writer.receiver_report(0x1234_5678, &[block]).unwrap().sdes_cname(0x1234_5678, "peer@e2e").unwrap();
+    let len = writer.finish();
+    peer.rtcp.send_to(&wire[..len], ("127.0.0.1", reply.rtcp_port as u16)).await.unwrap();
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(1);
+    let remote = loop {
+        let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.unwrap().into_inner();
+        if let Some(remote) = stats.remote_report {
+            break remote;
+        }
+        assert!(tokio::time::Instant::now() < deadline, "receiver report was not read");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    };
+    assert_eq!((remote.fraction_lost, remote.cumulative_lost, remote.jitter_ms), (0.25, 3, 10.0));
+    assert!(remote.round_trip_ms.is_some_and(|rtt| (0.0..1000.0).contains(&rtt)), "{:?}", remote.round_trip_ms);
+}
+
+#[tokio::test]
+async fn receiver_reports_from_strangers_are_ignored() {
+    let mut settings = test_settings();
+    settings.rtcp.report_interval_s = 1;
+    let mut server = TestServer::with_settings(settings).await;
+    let reply = server.allocate("pcmu", "e2e-rtcp-stranger").await;
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    let ssrc = peer.recv_rtp().await.ssrc;
+
+    // Başka bir IP'den, kilitli akışın SSRC'si olmadan gelen RR; hem RTCP portuna hem RTP portuna.
+    let block = rtcp::ReportBlock { ssrc, fraction_lost: 255, cumulative_lost: 99, jitter: 800, ..rtcp::ReportBlock::default() };
+    let mut wire = [0u8; 128];
+    let mut writer = rtcp::CompoundWriter::new(&mut wire);
+    writer.receiver_report(0x0BAD_F00D, &[block]).unwrap();
+    let len = writer.finish();
+    let stranger = tokio::net::UdpSocket::bind("127.0.0.2:0").await.unwrap();
+    for port in [reply.rtcp_port, reply.port] {
+        stranger.send_to(&wire[..len], ("127.0.0.1", port as u16)).await.unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.unwrap().into_inner();
+    assert_eq!(stats.remote_report, None);
+
+    // Yabancı RTCP hedefi de değiştirmez: düzenli SR kilitli ucun RTCP portuna gelmeye devam eder.
+    peer.recv_rtcp(Duration::from_secs(2)).await;
+    let mut buf = [0u8; 256];
+    assert!(tokio::time::timeout(Duration::from_millis(200), stranger.recv_from(&mut buf)).await.is_err(), "RTCP followed the stranger");
+}
+
+#[tokio::test]
+async fn rtcp_mux_sessions_send_and_read_reports_on_the_rtp_port() {
+    let mut settings = test_settings();
+    settings.rtcp.report_interval_s = 1;
+    let mut server = TestServer::with_settings(settings).await;
+    let request = AllocatePortRequest {
+        codec: "pcmu".to_string(), call_id: "e2e-rtcp-mux".to_string(), rtcp_mux: true, ..Default::default()
+    };
+    let reply = server.client.allocate_port(request).await.expect("AllocatePort").into_inner();
+    assert_eq!(reply.rtcp_port, 0);
+    let offer = server.client.generate_sdp(GenerateSdpRequest { port: reply.port, role: SdpRole::Offer as i32, ..Default::default() }).await.expect("GenerateSdp").into_inner();
+    for line in [format!("a=rtcp:{}", reply.port), "a=rtcp-mux".to_string()] {
+        assert!(offer.sdp.contains(&format!("{}\r\n", line)), "{} missing from\n{}", line, offer.sdp);
+    }
+
+    let mut peer = RtpPeer::connect(reply.port).await;
+    peer.send_packet().await;
+    let ssrc = peer.recv_rtp().await.ssrc;
+    // Alım raporu RTP portuna gelir ve yük tipinden RTCP olarak ayrılır.
+    let block = rtcp::ReportBlock { ssrc, fraction_lost: 128, cumulative_lost: 7, jitter: 160, ..rtcp::ReportBlock::default() };
+    let mut wire = [0u8; 128];
+    let mut writer = rtcp::CompoundWriter::new(&mut wire);
+    writer.receiver_report(0x1234_5678, &[block]).unwrap().sdes_cname(0x1234_5678, "peer@e2e").unwrap();
+    let len = writer.finish();
+    peer.sock.send_to(&wire[..len], peer.remote).await.unwrap();
+
+    // Düzenli SR de RTP portundan, medya paketlerinin arasında gelir.
+    let mut buf = [0u8; 2048];
+    let report = loop {
+        let (len, _) = tokio::time::timeout(Duration::from_secs(2), peer.sock.recv_from(&mut buf)).await.expect("RTCP SR within timeout").unwrap();
+        if rtcp::is_rtcp(&buf[..len]) {
+            break rtcp::parse_reports(&buf[..len]).unwrap().remove(0);
+        }
+    };
+    assert_eq!((report.ssrc, report.sender.is_some()), (ssrc, true));
+
+    let stats = server.client.get_session_stats(GetSessionStatsRequest { port: reply.port, ..Default::default() }).await.unwrap().into_inner();
+    let remote = stats.remote_report.expect("receiver report read from the RTP port");
+    assert_eq!((remote.fraction_lost, remote.cumulative_lost, remote.jitter_ms, remote.round_trip_ms), (0.5, 7, 20.0, None));
+}
@@ -0,0 +1,145 @@
+// Altın kayıtlar: oturumun gönderdiği paketler bellekte zaman damgalarıyla toplanır, Wireshark'ta
+// açılabilsin diye pcap olarak saklanır ve tests/golden altındaki kayıtla yapısal olarak
+// karşılaştırılır. `MEDIA_WRITE_GOLDEN=1` karşılaştırmak yerine kaydı yeniden yazar.
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+use tokio::time::{timeout, Instant};
+
+use media::capture;
+use media::rtp::RtpPacketRef;
+
+use super::{RtpPeer, RECV_TIMEOUT};
+
+/// Alınan bir UDP yükü; `at` yakalamanın ilk paketine göredir.
+#[derive(Debug, Clone)]
+pub struct CapturedPacket {
+    pub at: Duration,
+    pub from: SocketAddr,
+    pub to: SocketAddr,
+    pub data: Vec<u8>,
+}
+
+impl CapturedPacket {
+    pub fn rtp(&self) -> RtpPacketRef<'_> {
+        RtpPacketRef::parse(&self.data).expect("well-formed RTP packet")
+    }
+}
+
+/// Sırasıyla alınmış paketler.
+#[derive(Debug, Clone, Default)]
+pub struct Capture {
+    pub packets: Vec<CapturedPacket>,
+}
+
+impl Capture {
+    /// `peer`'e gelen ilk `count` paketi olduğu gibi, konfor gürültüsü dahil toplar.
+    pub async fn record(peer: &RtpPeer, count: usize) -> Self {
+        let to = peer.sock.local_addr().unwrap();
+        let mut buf = [0u8; 2048];
+        let mut packets = Vec::with_capacity(count);
+        let mut started = None;
+        for _ in 0..count {
+            let (len, from) = timeout(RECV_TIMEOUT, peer.sock.recv_from(&mut buf))
+                .await
+                .expect("packet within timeout")
+                .unwrap();
+            let started = *started.get_or_insert_with(Instant::now);
+            packets.push(CapturedPacket { at: started.elapsed(), from, to, data: buf[..len].to_vec() });
+        }
+        Capture { packets }
+    }
+
+    /// Yakalama dosyasıyla aynı biçimde pcap; zaman damgaları 1970'ten itibaren göreli yazılır.
+    pub fn to_pcap(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        capture::write_packets(&mut out, self.packets.iter().map(|p| (UNIX_EPOCH + p.at, p.from, p.to, p.data.as_slice()))).unwrap();
+        out
+    }
+
+    /// `to_pcap`'in (ya da sunucunun yakalama dosyasının) tersi; yalnızca UDP çerçeveleri okunur.
+    pub fn from_pcap(bytes: &[u8]) -> Self {
+        assert!(bytes.len() >= 24 && bytes[..4] == 0xa1b2c3d4u32.to_le_bytes(), "not a little-endian pcap file");
+        assert_eq!(u32::from_le_bytes(bytes[20..24].try_into().unwrap()), 1, "expected Ethernet link type");
+        let mut packets = Vec::new();
+        let mut rest = &bytes[24..];
+        while !rest.is_empty() {
+            let field = |at: usize| u32::from_le_bytes(rest[at..at + 4].try_into().unwrap());
+            let at = Duration::new(field(0) as u64, field(4) * 1000);
+            let len = field(8) as usize;
+            let frame = &rest[16..16 + len];
+            let (from, to, data) = parse_frame(frame);
+            packets.push(CapturedPacket { at, from, to, data: data.to_vec() });
+            rest = &rest[16 + len..];
+        }
+        Capture { packets }
+    }
+
+    /// Kaydı `tests/golden/<name>.pcap` ile karşılaştırır. Adresler ve varış anları gerçek
+    /// saate ve rastgele portlara bağlı olduğundan karşılaştırılmaz; sıra numarası ve zaman
+    /// damgası ilk pakete göre, geri kalan her bayt birebir karşılaştırılır.
+    pub fn assert_golden(&self, name: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{name}.pcap"));
+        if std::env::var_os("MEDIA_WRITE_GOLDEN").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, self.to_pcap()).unwrap();
+            return;
+        }
+        let bytes = std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {e}; regenerate with MEDIA_WRITE_GOLDEN=1", path.display()));
+        let golden = Capture::from_pcap(&bytes);
+        assert_eq!(self.packets.len(), golden.packets.len(), "packet count differs from {}", path.display());
+        let (actual, expected) = (self.shapes(), golden.shapes());
+        for (i, (actual, expected)) in actual.iter().zip(&expected).enumerate() {
+            assert_eq!(actual, expected, "packet {i} differs from {}", path.display());
+        }
+    }
+
+    /// Paketlerin karşılaştırılan yapısı: ilk iki bayt (sürüm, işaret, yük tipi), ilk pakete göre
+    /// sıra numarası ve zaman damgası farkı, SSRC'nin değişip değişmediği ve sabit başlıktan sonrası.
+    fn shapes(&self) -> Vec<PacketShape> {
+        let Some(first) = self.packets.first().map(|p| p.rtp()) else { return Vec::new() };
+        self.packets.iter().map(|packet| {
+            let rtp = packet.rtp();
+            PacketShape {
+                head: [packet.data[0], packet.data[1]],
+                sequence_delta: rtp.sequence().wrapping_sub(first.sequence()),
+                timestamp_delta: rtp.timestamp().wrapping_sub(first.timestamp()),
+                same_ssrc: rtp.ssrc() == first.ssrc(),
+                rest: packet.data[12..].to_vec(),
+            }
+        }).collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct PacketShape {
+    head: [u8; 2],
+    sequence_delta: u16,
+    timestamp_delta: u32,
+    same_ssrc: bool,
+    rest: Vec<u8>,
+}
+
+/// Ethernet + IPv4/IPv6 + UDP çerçevesinden adresleri ve yükü çıkarır.
+fn parse_frame(frame: &[u8]) -> (SocketAddr, SocketAddr, &[u8]) {
+    let (src, dst, udp) = match u16::from_be_bytes([frame[12], frame[13]]) {
+        0x0800 => {
+            let ip = &frame[14..];
+            let header_len = (ip[0] & 0x0F) as usize * 4;
+            let src = IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(&ip[12..16]).unwrap()));
+            let dst = IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(&ip[16..20]).unwrap()));
+            (src, dst, &ip[header_len..])
+        }
+        0x86DD => {
+            let ip = &frame[14..];
+            let src = IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(&ip[8..24]).unwrap()));
+            let dst = IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(&ip[24..40]).unwrap()));
+            (src, dst, &ip[40..])
+        }
+        other => panic!("unexpected ethertype {other:#06x}"),
+    };
+    let port = |at: usize| u16::from_be_bytes([udp[at], udp[at + 1]]);
+    let len = port(4) as usize;
+    (SocketAddr::new(src, port(0)), SocketAddr::new(dst, port(2)), &udp[8..len])
+}
@@ -0,0 +1,231 @@
+// Entegrasyon testleri için ortak düzenek: servisi süreç içinde geçici portlarda başlatır,
+// tonic istemcisi bağlar ve RTP tarafında uzak uç gibi davranan bir UDP soketi sağlar.
+// Her test ikili dosyası `mod support;` ile dahil eder; her test her yardımcıyı kullanmaz.
+#![allow(dead_code)]
+
+pub mod golden;
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::net::{TcpListener, UdpSocket, UnixStream};
+use tokio::time::{timeout, Instant};
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::{Channel, Endpoint, Server, Uri};
+use tower::service_fn;
+
+use media::announcement::PromptLibrary;
+use media::config::{PromptConfig, Settings};
+use media::grpc::MyMediaManager;
+use media::media::media_manager_client::MediaManagerClient;
+use media::media::media_manager_server::MediaManagerServer;
+use media::media::{AllocatePortResponse, AllocatePortRequest};
+use media::request_id::RequestIdInterceptor;
+use media::rtcp;
+use media::rtp::{RtpPacket, RtpPacketRef, COMFORT_NOISE_PT, MAX_PACKET_LEN};
+use media::session::ActiveSessions;
+use media::uds::SocketFile;
+
+/// Testlerin kullandığı RTP port aralığı; oturumlar bu aralıktan rastgele port alır.
+pub const RTP_PORTS: std::ops::RangeInclusive<u16> = 31000..=31999;
+/// Tek bir paketi beklerken verilen süre; CI makinelerinde bile aşılmaması gerekir.
+pub const RECV_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Gömülü varsayılanlar, loopback RTP ve repodaki karşılama anonsu.
+pub fn test_settings() -> Settings {
+    let mut settings = Settings::builtin();
+    settings.rtp.host = "127.0.0.1".to_string();
+    settings.rtp.min_port = *RTP_PORTS.start();
+    settings.rtp.max_port = *RTP_PORTS.end();
+    settings.announcement.welcome = Some("welcome".to_string());
+    settings.announcement.prompts.insert("welcome".to_string(), PromptConfig {
+        path: "audio/processed/standard/welcome.wav".to_string(),
+        gain_db: 0.0,
+        looped: false,
+        language: None,
+        preload: false,
+        languages: Default::default(),
+    });
+    settings
+}
+
+/// Süreç içinde çalışan servis ve ona bağlı istemci.
+pub struct TestServer {
+    pub grpc_addr: SocketAddr,
+    pub sessions: ActiveSessions,
+    pub client: MediaManagerClient<Channel>,
+    service: InterceptedService<MediaManagerServer<MyMediaManager>, RequestIdInterceptor>,
+}
+
+impl TestServer {
+    pub async fn start() -> Self {
+        Self::with_settings(test_settings()).await
+    }
+
+    /// gRPC'yi 127.0.0.1:0'a bağlar; sunucu görevi test runtime'ı kapanınca biter.
+    pub async fn with_settings(settings: Settings) -> Self {
+        let prompts = PromptLibrary::load(&settings.announcement).expect("test prompts load");
+        let sessions = ActiveSessions::default();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let grpc_addr = listener.local_addr().unwrap();
+        let interceptor = RequestIdInterceptor::new(&settings.grpc.request_id_header);
+        let manager = MyMediaManager::new(sessions.clone(), Arc::new(settings), Arc::new(prompts), None)
+            .with_listen_addresses(vec![grpc_addr.to_string()]);
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        let service = InterceptedService::new(MediaManagerServer::from_arc(Arc::new(manager)), interceptor);
+        tokio::spawn(Server::builder().add_service(service.clone()).serve_with_incoming(incoming));
+
+        let client = MediaManagerClient::connect(format!("http://{}", grpc_addr)).await.unwrap();
+        TestServer { grpc_addr, sessions, client, service }
+    }
+
+    /// Aynı servisi ayrıca `path`'teki Unix soketinde sunar ve oraya bağlı bir istemci döner.
+    /// tonic UDS adresi bilmez; URI yalnızca yer tutucudur, bağlantıyı connector açar.
+    pub async fn serve_unix(&self, path: &Path) -> (SocketFile, MediaManagerClient<Channel>) {
+        let (file, incoming) = SocketFile::bind(path, 0o600).unwrap();
+        tokio::spawn(Server::builder().add_service(self.service.clone()).serve_with_incoming(incoming));
+
+        let path = path.to_path_buf();
+        let channel = Endpoint::from_static("http://[::]:50051")
+            .connect_with_connector(service_fn(move |_: Uri| UnixStream::connect(path.clone())))
+            .await
+            .unwrap();
+        (file, MediaManagerClient::new(channel))
+    }
+
+    pub async fn allocate(&mut self, codec: &str, call_id: &str) -> AllocatePortResponse {
+        self.client
+            .allocate_port(AllocatePortRequest { codec: codec.to_string(), call_id: call_id.to_string(), ..Default::default() })
+            .await
+            .expect("AllocatePort")
+            .into_inner()
+    }
+
+    pub fn session_count(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+}
+
+/// Alınan bir RTP paketinin başlığı ve varış anı.
+#[derive(Debug, Clone)]
+pub struct ReceivedRtp {
+    pub from: SocketAddr,
+    pub arrived: Instant,
+    pub version: u8,
+    pub marker: bool,
+    pub payload_type: u8,
+    pub sequence: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+    pub payload: Vec<u8>,
+}
+
+/// Uzak uç (ör. SBC) rolündeki UDP soketleri: RTP çift portta, RTCP bir üstündeki tek portta.
+pub struct RtpPeer {
+    pub sock: UdpSocket,
+    pub rtcp: UdpSocket,
+    pub remote: SocketAddr,
+    sequence: u16,
+}
+
+impl RtpPeer {
+    /// Tahsis edilen porta paket gönderecek loopback RTP/RTCP soket çiftini açar.
+    pub async fn connect(port: u32) -> Self {
+        let (sock, rtcp) = loop {
+            let sock = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let local = sock.local_addr().unwrap().port();
+            if local % 2 == 1 {
+                continue;
+            }
+            if let Ok(rtcp) = UdpSocket::bind(("127.0.0.1", local + 1)).await {
+                break (sock, rtcp);
+            }
+        };
+        let remote = SocketAddr::from(([127, 0, 0, 1], port as u16));
+        RtpPeer { sock, rtcp, remote, sequence: 1 }
+    }
+
+    /// RTCP soketine gelen bir sonraki birleşik RTCP paketini bekler.
+    pub async fn recv_rtcp(&self, wait: Duration) -> Vec<u8> {
+        let mut buf = [0u8; 2048];
+        let (len, _) = tokio::time::timeout(wait, self.rtcp.recv_from(&mut buf))
+            .await
+            .expect("RTCP packet within timeout")
+            .unwrap();
+        assert!(rtcp::is_rtcp(&buf[..len]), "non-RTCP packet on the RTCP port");
+        buf[..len].to_vec()
+    }
+
+    /// 160 baytlık sessiz PCMU yüküyle bir RTP paketi gönderir; ilk paket uzak adresi kilitler.
+    pub async fn send_packet(&mut self) {
+        let payload = [0xFF; 160];
+        let packet = RtpPacket::new(0, self.sequence, self.sequence as u32 * 160, 0x1234_5678, &payload);
+        let mut wire = [0u8; MAX_PACKET_LEN];
+        let len = packet.write(&mut wire).unwrap();
+        self.sock.send_to(&wire[..len], self.remote).await.unwrap();
+        self.sequence = self.sequence.wrapping_add(1);
+    }
+
+    /// Bir sonraki medya paketini bekler; konfor gürültüsü keepalive'larını ve RTP portundan gelen
+    /// (rtcp-mux) RTCP raporlarını atlar.
+    pub async fn recv_rtp(&self) -> ReceivedRtp {
+        let mut buf = [0u8; 2048];
+        loop {
+            let (len, from) = timeout(RECV_TIMEOUT, self.sock.recv_from(&mut buf))
+                .await
+                .expect("RTP packet within timeout")
+                .unwrap();
+            if rtcp::is_rtcp(&buf[..len]) {
+                continue;
+            }
+            let packet = RtpPacketRef::parse(&buf[..len]).expect("well-formed RTP packet");
+            if packet.payload_type() == COMFORT_NOISE_PT {
+                continue;
+            }
+            return ReceivedRtp {
+                from,
+                arrived: Instant::now(),
+                version: packet.version(),
+                marker: packet.marker(),
+                payload_type: packet.payload_type(),
+                sequence: packet.sequence(),
+                timestamp: packet.timestamp(),
+                ssrc: packet.ssrc(),
+                payload: packet.payload().to_vec(),
+            };
+        }
+    }
+
+    pub async fn recv_many(&self, count: usize) -> Vec<ReceivedRtp> {
+        let mut packets = Vec::with_capacity(count);
+        for _ in 0..count {
+            packets.push(self.recv_rtp().await);
+        }
+        packets
+    }
+}
+
+/// Paketlerin tek bir akış olduğunu doğrular: aynı SSRC, ardışık sıra numaraları ve paket
+/// başına `samples_per_packet` kadar ilerleyen zaman damgası.
+pub fn assert_contiguous(packets: &[ReceivedRtp], samples_per_packet: u32) {
+    for pair in packets.windows(2) {
+        assert_eq!(pair[1].ssrc, pair[0].ssrc, "SSRC changed mid-stream");
+        assert_eq!(pair[1].sequence, pair[0].sequence.wrapping_add(1), "sequence not consecutive");
+        assert_eq!(pair[1].timestamp, pair[0].timestamp.wrapping_add(samples_per_packet), "timestamp step");
+    }
+}
+
+/// Ortalama paket aralığının `ptime` civarında olduğunu doğrular. Gerçek saatle ölçüldüğü için
+/// tolerans geniştir; amaç paketlerin yığın halinde ya da iki kat yavaş gitmesini yakalamak.
+pub fn assert_paced(packets: &[ReceivedRtp], ptime: Duration) {
+    let (first, last) = (packets.first().unwrap(), packets.last().unwrap());
+    let intervals = (packets.len() - 1) as u32;
+    let average = (last.arrived - first.arrived) / intervals;
+    assert!(
+        average >= ptime / 2 && average <= ptime * 2,
+        "average packet interval {average:?} is not close to {ptime:?}"
+    );
+}